@@ -262,6 +262,9 @@ pub const ENABLE_GIC: bool = {};
 /// 是否启用VirtIO网络设备探测
 pub const ENABLE_VIRTIO_NET_PROBE: bool = {};
 
+/// virtio-mmio 槽位数量
+pub const VIRTIO_MMIO_SLOTS: usize = {};
+
 // ============================================================
 // SMP 配置
 // ============================================================
@@ -285,6 +288,16 @@ pub const DEFAULT_TIME_SLICE_MS: u32 = {};
 /// 时间片滴答数
 pub const TIME_SLICE_TICKS: u32 = {};
 
+// ============================================================
+// 定时器配置
+// ============================================================
+
+/// 时钟中断频率 (HZ)
+pub const TIMER_HZ: u64 = {};
+
+/// 是否启用空闲无滴答 (tickless idle)
+pub const ENABLE_TICKLESS_IDLE: bool = {};
+
 // ============================================================
 // 内存管理配置
 // ============================================================
@@ -400,6 +413,10 @@ pub const AUTO_MOUNT_EXT4: bool = {};
             .and_then(|d| d.get("enable_virtio_net_probe"))
             .and_then(|v| v.as_bool())
             .unwrap_or(false),
+        config.get("drivers")
+            .and_then(|d| d.get("virtio_mmio_slots"))
+            .and_then(|v| v.as_integer())
+            .unwrap_or(8) as usize,
         // SMP 配置
         config.get("smp")
             .and_then(|s| s.get("enable_smp"))
@@ -422,6 +439,15 @@ pub const AUTO_MOUNT_EXT4: bool = {};
             .and_then(|s| s.get("time_slice_ticks"))
             .and_then(|v| v.as_integer())
             .unwrap_or(10) as u32,
+        // 定时器配置
+        config.get("timer")
+            .and_then(|t| t.get("hz"))
+            .and_then(|v| v.as_integer())
+            .unwrap_or(100) as u64,
+        config.get("timer")
+            .and_then(|t| t.get("tickless_idle"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
         // 内存管理配置
         config.get("memory")
             .and_then(|m| m.get("user_stack_size"))
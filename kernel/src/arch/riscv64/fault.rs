@@ -0,0 +1,147 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! 用户态缺页处理 (arch/riscv/mm/fault.c)
+//!
+//! 区分合法缺页和非法访问：
+//! - 按需分页、写时复制、陈旧的 TLB 项：修复后重新执行指令，计入任务的 min_flt / maj_flt
+//! - 地址不在任何 VMA 中 (SEGV_MAPERR) 或访问权限不符 (SEGV_ACCERR)：
+//!   向任务发送 SIGSEGV，信号信息中带有出错地址；没有处理函数时以 SIGSEGV 终止任务
+
+use crate::arch::riscv64::mm::{
+    handle_cow_fault, handle_mm_fault, user_pte, AddressSpace, FaultFlags, MmFaultResult, VirtAddr,
+};
+use crate::mm::page::{VirtAddr as PageVirtAddr, PAGE_SIZE};
+use crate::mm::vma::VmaType;
+use crate::process::task::Task;
+use crate::signal::{si_code, Signal};
+
+/// 一次用户缺页的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultOutcome {
+    /// 已修复，不需要 I/O（匿名页清零、写时复制、陈旧的 TLB 项）
+    Minor,
+    /// 已修复，页面内容从文件读取
+    Major,
+    /// 非法访问，应发送 SIGSEGV；参数为 si_code (SEGV_MAPERR / SEGV_ACCERR)
+    Segv(i32),
+    /// 内存不足，无法修复
+    OutOfMemory,
+}
+
+/// 页表项是否已经允许这次访问
+fn pte_allows(root_ppn: u64, addr: u64, flags: u32) -> bool {
+    let Some(pte) = (unsafe { user_pte(root_ppn, addr) }) else {
+        return false;
+    };
+    if !pte.is_valid() || !pte.is_user() {
+        return false;
+    }
+    if flags & FaultFlags::WRITE != 0 {
+        pte.is_writable()
+    } else if flags & FaultFlags::EXEC != 0 {
+        pte.is_executable()
+    } else {
+        pte.is_readable()
+    }
+}
+
+/// 处理 `addr_space` 中 `addr` 处的用户缺页
+///
+/// `flags` 为 `FaultFlags` 中的访问类型（READ / WRITE / EXEC）
+pub fn handle_user_fault(addr_space: &AddressSpace, addr: u64, flags: u32) -> FaultOutcome {
+    let fault_addr = VirtAddr::new(addr);
+    let root_ppn = addr_space.root_ppn();
+
+    match handle_mm_fault(addr_space, fault_addr, flags | FaultFlags::USER) {
+        MmFaultResult::Handled => {
+            let file_backed = addr_space.vma_read().find(PageVirtAddr::new(addr as usize))
+                .is_some_and(|vma| vma.vma_type() == VmaType::FileBacked);
+            if file_backed {
+                FaultOutcome::Major
+            } else {
+                FaultOutcome::Minor
+            }
+        }
+        MmFaultResult::CowPending => match unsafe { handle_cow_fault(root_ppn, fault_addr) } {
+            Some(()) => FaultOutcome::Minor,
+            None => FaultOutcome::OutOfMemory,
+        },
+        MmFaultResult::AlreadyMapped => {
+            // 页表已经允许这次访问：本 CPU 的 TLB 中是修改前的旧表项
+            if pte_allows(root_ppn, addr, flags) {
+                let page = addr as usize & !(PAGE_SIZE - 1);
+                crate::arch::riscv64::tlb::flush_tlb_range_local(page, page + PAGE_SIZE);
+                FaultOutcome::Minor
+            } else {
+                FaultOutcome::Segv(si_code::SEGV_ACCERR)
+            }
+        }
+        MmFaultResult::Segfault => FaultOutcome::Segv(si_code::SEGV_MAPERR),
+        MmFaultResult::PermissionDenied => FaultOutcome::Segv(si_code::SEGV_ACCERR),
+        MmFaultResult::OutOfMemory => FaultOutcome::OutOfMemory,
+    }
+}
+
+/// 处理任务 `task` 在用户态 `addr` 处的缺页
+///
+/// 修复成功时计入缺页统计；非法访问向任务发送带出错地址的 SIGSEGV。
+///
+/// # 返回
+/// 任务应被终止时返回终止信号（SIGSEGV 没有处理函数，或内存不足时的 SIGKILL）；
+/// 返回 None 时重新执行出错的指令
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务
+pub unsafe fn do_user_page_fault(task: *mut Task, addr: u64, flags: u32) -> Option<i32> {
+    let outcome = match (*task).address_space() {
+        Some(addr_space) => handle_user_fault(addr_space, addr, flags),
+        None => FaultOutcome::Segv(si_code::SEGV_MAPERR),
+    };
+
+    match outcome {
+        FaultOutcome::Minor => {
+            (*task).account_fault(false);
+            None
+        }
+        FaultOutcome::Major => {
+            (*task).account_fault(true);
+            None
+        }
+        FaultOutcome::Segv(code) => {
+            let sig = Signal::SIGSEGV as i32;
+            crate::signal::force_sig_fault(task, sig, code, addr).then_some(sig)
+        }
+        FaultOutcome::OutOfMemory => Some(Signal::SIGKILL as i32),
+    }
+}
+
+/// 处理内核访问用户地址时的缺页（如系统调用写入 fork 后的写时复制页）
+///
+/// 只修复按需分页和写时复制，非法访问不发送信号，由调用者按内核缺页处理。
+///
+/// # 返回
+/// 修复成功、应重新执行出错的指令时返回 true
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务
+pub unsafe fn do_kernel_user_fault(task: *mut Task, addr: u64, flags: u32) -> bool {
+    let Some(addr_space) = (*task).address_space() else {
+        return false;
+    };
+    match handle_user_fault(addr_space, addr, flags) {
+        FaultOutcome::Minor => {
+            (*task).account_fault(false);
+            true
+        }
+        FaultOutcome::Major => {
+            (*task).account_fault(true);
+            true
+        }
+        FaultOutcome::Segv(_) | FaultOutcome::OutOfMemory => false,
+    }
+}
@@ -10,11 +10,17 @@
 //! IPI 类型：
 //! - RESCHEDULE: 通知目标 CPU 重新调度（当有新任务或负载均衡时）
 //! - STOP: 停止目标 CPU
+//! - TLB_FLUSH: 刷新目标 CPU 的 TLB（见 `tlb` 模块）
 //!
 //! 使用 RISC-V 软件中断（SSIP）和 SBI IPI Extension (EID #0x735049)
+//!
+//! 每个 CPU 有一个 IPI 消息队列：发送方先把消息放入目标 CPU 的队列再触发软件中断，
+//! 目标 CPU 在中断中取出全部消息处理，多个 CPU 同时发送也不会丢失请求
 
+use crate::config::MAX_CPUS;
 use crate::sbi;
-use crate::println;
+use crate::sync::mpsc::Full;
+use crate::sync::MpscQueue;
 
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -23,6 +29,44 @@ pub enum IpiType {
     Reschedule = 0,
     /// 停止 CPU
     Stop = 1,
+    /// TLB shootdown，刷新范围在 `tlb` 模块的全局请求中
+    TlbFlush = 2,
+}
+
+/// 每个 CPU 的 IPI 消息队列容量
+pub const IPI_QUEUE_SIZE: usize = 16;
+
+/// 发往各个 CPU 的 IPI 消息，由目标 CPU 在软件中断中取出
+static IPI_QUEUES: [MpscQueue<IpiType, IPI_QUEUE_SIZE>; MAX_CPUS] = [const { MpscQueue::new() }; MAX_CPUS];
+
+/// 目标 CPU 尚未处理的 IPI 消息数
+pub fn pending_ipis(cpu: usize) -> usize {
+    IPI_QUEUES.get(cpu).map_or(0, |queue| queue.len())
+}
+
+/// 向指定 CPU 发送 IPI 消息
+///
+/// 发给自己或无效 CPU 时什么也不做
+///
+/// # 返回
+/// 目标 CPU 的消息队列已满时返回 `Err(Full(ipi))`，
+/// 此时仍会触发软件中断，让目标 CPU 尽快处理积压的消息
+pub fn smp_cross_call(target_cpu: usize, ipi: IpiType) -> Result<(), Full<IpiType>> {
+    if target_cpu >= MAX_CPUS {
+        return Ok(());
+    }
+
+    // 不要发送给自己
+    let current_cpu = crate::arch::cpu_id() as usize;
+    if target_cpu == current_cpu {
+        return Ok(());
+    }
+
+    let result = IPI_QUEUES[target_cpu].try_push(ipi);
+
+    // 通过 SBI 发送 IPI
+    let _ = sbi::send_ipi(target_cpu);
+    result
 }
 
 /// 发送 Reschedule IPI 到指定 CPU
@@ -34,33 +78,62 @@ pub enum IpiType {
 /// # 参数
 /// * `target_cpu` - 目标 CPU ID
 pub fn send_reschedule_ipi(target_cpu: usize) {
-    if target_cpu >= 4 {
-        return;
-    }
+    // 队列满时目标 CPU 已有积压的消息，本次软件中断足以让它重新调度
+    let _ = smp_cross_call(target_cpu, IpiType::Reschedule);
+}
 
-    // 不要发送给自己
+/// 等待其他 CPU 取走 Stop 消息的最大轮询次数
+const STOP_WAIT_SPINS: usize = 1_000_000;
+
+/// 停止除当前 CPU 外的所有 CPU（对应 Linux `smp_send_stop()`）
+///
+/// 用于 panic：先让其他 CPU 停在 wfi，避免它们的输出与 panic 信息交错。
+/// 只发给已启动的 CPU，并有限次等待它们取走消息，已经停止的 CPU 不会阻塞调用者
+pub fn smp_send_stop() {
     let current_cpu = crate::arch::cpu_id() as usize;
-    if target_cpu == current_cpu {
-        return;
+    let others = || (0..MAX_CPUS).filter(move |&cpu| cpu != current_cpu && super::smp::cpu_started(cpu));
+    for cpu in others() {
+        let _ = smp_cross_call(cpu, IpiType::Stop);
     }
 
-    // 通过 SBI 发送 IPI
-    let _ = sbi::send_ipi(target_cpu);
+    for _ in 0..STOP_WAIT_SPINS {
+        if others().all(|cpu| pending_ipis(cpu) == 0) {
+            break;
+        }
+        core::hint::spin_loop();
+    }
 }
 
 /// 处理软件中断 IPI
 ///
-/// 当接收到软件中断时调用此函数
-/// 通知调度器重新调度
+/// 当接收到软件中断时调用此函数，取出本 CPU 队列中的全部消息；
+/// 没有消息的软件中断按重新调度处理（与引入消息队列之前的行为一致）
 ///
 ///
 /// # 参数
 /// * `hart` - 当前 hart ID
 pub fn handle_software_ipi(hart: usize) {
-    // 处理 IPI - 触发调度器
-    // 当其他 CPU 发送 Reschedule IPI 时，表示需要触发调度
-    // 例如：唤醒了高优先级任务、需要负载均衡等
+    let mut resched = true;
+    if let Some(queue) = IPI_QUEUES.get(hart) {
+        resched = queue.is_empty();
+        while let Some(ipi) = queue.pop() {
+            match ipi {
+                IpiType::Reschedule => resched = true,
+                IpiType::Stop => stop_this_cpu(),
+                IpiType::TlbFlush => super::tlb::handle_flush_ipi(hart),
+            }
+        }
+    }
+
+    if resched {
+        reschedule();
+    }
+}
 
+/// 响应 Reschedule IPI
+///
+/// 当其他 CPU 唤醒了高优先级任务或需要负载均衡时触发调度
+fn reschedule() {
     #[cfg(feature = "riscv64")]
     {
         // 设置需要重新调度标志
@@ -69,8 +142,15 @@ pub fn handle_software_ipi(hart: usize) {
         // 立即调度
         crate::sched::schedule();
     }
+}
 
-    // println!("ipi: Hart {} received reschedule IPI", hart);
+/// 响应 Stop IPI：停止当前 CPU
+fn stop_this_cpu() -> ! {
+    loop {
+        unsafe {
+            core::arch::asm!("wfi", options(nomem, nostack));
+        }
+    }
 }
 
 /// 处理 PLIC IPI（旧版，用于兼容）
@@ -87,11 +167,7 @@ pub fn handle_ipi(irq: usize, hart: usize) {
         }
         11 => {
             // Stop IPI
-            loop {
-                unsafe {
-                    core::arch::asm!("wfi", options(nomem, nostack));
-                }
-            }
+            stop_this_cpu();
         }
         _ => {}
     }
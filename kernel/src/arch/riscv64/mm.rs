@@ -443,6 +443,8 @@ impl Satp {
 // ==================== 地址空间 ====================
 
 use crate::mm::vma::{Vma, VmaManager, VmaFlags, VmaType};
+use crate::mm::filemap::FileMapping;
+use alloc::collections::BTreeMap;
 use crate::mm::pagemap::{MapError, Perm, PageTableType};
 use crate::mm::page::{VirtAddr as PageVirtAddr, PhysAddr as PagePhysAddr, PAGE_SIZE as PAGE_SIZE_USIZE};
 
@@ -460,6 +462,8 @@ pub struct AddressSpace {
     mm_users: AtomicI32,
     /// 引用计数：mm_struct 的生命期引用
     mm_count: AtomicI32,
+    /// 文件映射表（VMA 起始地址 -> 背后的文件）
+    file_maps: spin::Mutex<BTreeMap<PageVirtAddr, FileMapping>>,
 }
 
 impl AddressSpace {
@@ -486,6 +490,7 @@ impl AddressSpace {
             brk: core::sync::atomic::AtomicUsize::new(brk),
             mm_users: AtomicI32::new(1),
             mm_count: AtomicI32::new(1),
+            file_maps: spin::Mutex::new(BTreeMap::new()),
         }
     }
 
@@ -499,6 +504,7 @@ impl AddressSpace {
             brk: core::sync::atomic::AtomicUsize::new(brk.as_usize()),
             mm_users: AtomicI32::new(1),
             mm_count: AtomicI32::new(1),
+            file_maps: spin::Mutex::new(BTreeMap::new()),
         }
     }
 
@@ -593,6 +599,50 @@ impl AddressSpace {
         asm!("sfence.vma {}, zero", in(reg) vaddr.as_usize());
     }
 
+    /// 页表项修改后刷新 [start, end) 的 TLB，包括其他正在运行本地址空间的 CPU
+    pub fn flush_tlb_range(&self, start: usize, end: usize) {
+        super::tlb::flush_tlb_range_mm(self.root_ppn, start, end);
+    }
+
+    /// 解除 [start, end) 中的用户页映射并释放物理页
+    ///
+    /// COW 共享页只减少引用计数，由仍在共享的地址空间继续持有
+    pub fn release_user_pages(&self, start: usize, end: usize) {
+        use crate::mm::page_desc::{pfn_to_page_mut, PHYS_MEMORY_BASE};
+
+        let mut released = alloc::vec::Vec::new();
+        let mut addr = start;
+        while addr < end {
+            unsafe {
+                if let Some((table, index)) = PageTableWalker::leaf(self.root_ppn, addr as u64) {
+                    let pte = (*table).get(index);
+                    if pte.is_valid() && pte.bits() & PageTableEntry::U != 0 {
+                        (*table).set(index, PageTableEntry::from_bits(0));
+                        released.push(pte);
+                    }
+                }
+            }
+            addr += PAGE_SIZE_USIZE;
+        }
+        if released.is_empty() {
+            return;
+        }
+
+        // 其他 CPU 不再能通过 TLB 访问这些页之后才能释放
+        self.flush_tlb_range(start, end);
+
+        for pte in released {
+            if pte.bits() & cow_flags::COW != 0 {
+                let page = pfn_to_page_mut(pte.ppn() as usize + PHYS_MEMORY_BASE / 0x1000);
+                if !page.is_null() && unsafe { (*page).refcount() } > 0 {
+                    unsafe { (*page).put_page() };
+                    continue;
+                }
+            }
+            free_user_phys_page(pte.ppn() << PAGE_SHIFT);
+        }
+    }
+
     // ==================== VMA 操作 ====================
 
     /// 映射 VMA（需要写锁）
@@ -649,6 +699,26 @@ impl AddressSpace {
         vma_mgr.find(addr).cloned()
     }
 
+    /// `addr` 位于可向下增长的栈 VMA 下方时扩展栈 (mm/mmap.c: expand_stack)
+    ///
+    /// 扩展后栈的大小（栈 VMA 结束地址到 `addr` 所在页）不能超过
+    /// `stack_limit`（RLIMIT_STACK 软限制）
+    ///
+    /// # 返回
+    /// 栈已扩展到包含 `addr` 时返回 true
+    pub fn expand_stack(&self, addr: PageVirtAddr, stack_limit: u64) -> bool {
+        let mut vma_mgr = self.vma_write();
+        let (start, end) = match vma_mgr.find_vma_after(addr) {
+            Some(stack) if stack.flags().contains(VmaFlags::GROWSDOWN) => (stack.start(), stack.end()),
+            _ => return false,
+        };
+        let new_start = addr.as_usize() & !(PAGE_SIZE_USIZE - 1);
+        if (end.as_usize() - new_start) as u64 > stack_limit {
+            return false;
+        }
+        vma_mgr.expand_down(start, PageVirtAddr::new(new_start)).is_ok()
+    }
+
     /// 调整堆指针（需要写锁）
     pub fn set_brk(&self, new_brk: PageVirtAddr) -> Result<PageVirtAddr, MapError> {
         use crate::mm;
@@ -740,6 +810,160 @@ impl AddressSpace {
             return Err(MapError::Invalid);
         }
 
+        let start = self.mmap_start(addr, aligned_size, flags, map_flags)?;
+        let end = PageVirtAddr::new(start.as_usize() + aligned_size);
+        let mut vma = Vma::new(start, end, flags);
+        vma.set_type(vma_type);
+        self.map_vma(vma, perm)?;
+        Ok(start)
+    }
+
+    /// 文件映射的 mmap 实现
+    ///
+    /// 与匿名映射不同，这里只建立 VMA 并记录背后的文件，
+    /// 不预先分配物理页；页面在缺页时由 `handle_mm_fault` 从页缓存填充。
+    ///
+    /// # 参数
+    /// - `addr`: 建议的起始地址（0 表示由内核选择）
+    /// - `size`: 映射长度
+    /// - `flags`: VMA 标志
+    /// - `map_flags`: mmap 标志（MAP_FIXED 等）
+    /// - `offset`: 文件偏移（必须页对齐）
+    /// - `mapping`: 背后的文件
+    pub fn mmap_file(
+        &self,
+        addr: PageVirtAddr,
+        size: usize,
+        flags: VmaFlags,
+        map_flags: u32,
+        offset: usize,
+        mapping: FileMapping,
+    ) -> Result<PageVirtAddr, MapError> {
+        let aligned_size = (size + PAGE_SIZE_USIZE - 1) & !(PAGE_SIZE_USIZE - 1);
+        if aligned_size == 0 || offset % PAGE_SIZE_USIZE != 0 {
+            return Err(MapError::Invalid);
+        }
+
+        let start = self.mmap_start(addr, aligned_size, flags, map_flags)?;
+        let end = PageVirtAddr::new(start.as_usize() + aligned_size);
+        let mut vma = Vma::new(start, end, flags);
+        vma.set_type(VmaType::FileBacked);
+        vma.set_offset(offset);
+
+        self.vma_write().add(vma).map_err(|_| MapError::Invalid)?;
+        self.file_maps.lock().insert(start, mapping);
+        Ok(start)
+    }
+
+    /// 设备内存的 mmap 实现
+    ///
+    /// 建立 `VmaType::Device` 类型的 VMA，并立即把 [phys, phys+size) 映射进来。
+    /// 这些物理页属于设备，munmap 时不会被释放。
+    ///
+    /// # 参数
+    /// - `addr`: 建议的起始地址（0 表示由内核选择）
+    /// - `size`: 映射长度
+    /// - `phys`: 设备内存的物理地址（必须页对齐）
+    /// - `flags`: VMA 标志
+    /// - `perm`: 页权限
+    /// - `map_flags`: mmap 标志（MAP_FIXED 等）
+    pub fn mmap_device(
+        &self,
+        addr: PageVirtAddr,
+        size: usize,
+        phys: usize,
+        flags: VmaFlags,
+        perm: Perm,
+        map_flags: u32,
+    ) -> Result<PageVirtAddr, MapError> {
+        let aligned_size = (size + PAGE_SIZE_USIZE - 1) & !(PAGE_SIZE_USIZE - 1);
+        if aligned_size == 0 || phys % PAGE_SIZE_USIZE != 0 {
+            return Err(MapError::Invalid);
+        }
+
+        let start = self.mmap_start(addr, aligned_size, flags, map_flags)?;
+        let end = PageVirtAddr::new(start.as_usize() + aligned_size);
+        let mut vma = Vma::new(start, end, flags);
+        vma.set_type(VmaType::Device);
+        self.vma_write().add(vma).map_err(|_| MapError::Invalid)?;
+
+        unsafe {
+            map_user_region(
+                self.root_ppn,
+                start.as_usize() as u64,
+                phys as u64,
+                aligned_size as u64,
+                perm_to_flags(perm, self.space_type),
+            );
+        }
+        Ok(start)
+    }
+
+    /// 获取 VMA 背后的文件映射
+    pub fn file_mapping(&self, vma_start: PageVirtAddr) -> Option<FileMapping> {
+        self.file_maps.lock().get(&vma_start).cloned()
+    }
+
+    /// msync 系统调用实现
+    ///
+    /// 将 [addr, addr+size) 范围内共享文件映射的驻留页写回页缓存
+    ///
+    /// # 返回
+    /// 范围内没有任何 VMA 时返回 `MapError::NotMapped`
+    pub fn msync(&self, addr: PageVirtAddr, size: usize) -> Result<(), MapError> {
+        let aligned_size = (size + PAGE_SIZE_USIZE - 1) & !(PAGE_SIZE_USIZE - 1);
+        let end = addr.as_usize() + aligned_size;
+
+        // 收集与范围重叠的文件映射 VMA
+        let vmas: alloc::vec::Vec<Vma> = {
+            let vma_mgr = self.vma_read();
+            vma_mgr.iter()
+                .filter(|v| v.start().as_usize() < end && addr.as_usize() < v.end().as_usize())
+                .cloned()
+                .collect()
+        };
+        if vmas.is_empty() {
+            return Err(MapError::NotMapped);
+        }
+
+        for vma in vmas.iter().filter(|v| v.vma_type() == VmaType::FileBacked) {
+            let sync_start = vma.start().as_usize().max(addr.as_usize());
+            let sync_end = vma.end().as_usize().min(end);
+            self.write_back_file_pages(vma, sync_start, sync_end);
+        }
+
+        Ok(())
+    }
+
+    /// 将文件映射 VMA 中 [start, end) 范围内的驻留页写回页缓存
+    fn write_back_file_pages(&self, vma: &Vma, start: usize, end: usize) {
+        let mapping = match self.file_mapping(vma.start()) {
+            Some(m) if m.is_shared() => m,
+            _ => return,
+        };
+
+        let mut addr = start;
+        while addr < end {
+            if let Some(ppn) = unsafe { PageTableWalker::walk(self.root_ppn, addr as u64) } {
+                // 内核使用恒等映射，物理地址即可直接访问
+                let page = unsafe {
+                    core::slice::from_raw_parts((ppn << PAGE_SHIFT) as *const u8, PAGE_SIZE_USIZE)
+                };
+                let file_offset = vma.offset() + (addr - vma.start().as_usize());
+                mapping.write_back_page(file_offset, page);
+            }
+            addr += PAGE_SIZE_USIZE;
+        }
+    }
+
+    /// 确定 mmap 的起始地址
+    fn mmap_start(
+        &self,
+        addr: PageVirtAddr,
+        aligned_size: usize,
+        flags: VmaFlags,
+        map_flags: u32,
+    ) -> Result<PageVirtAddr, MapError> {
         // 检查 MAP_FIXED
         let is_fixed = map_flags & map::MAP_FIXED != 0;
 
@@ -776,10 +1000,6 @@ impl AddressSpace {
             }
         };
 
-        let end = PageVirtAddr::new(start.as_usize() + aligned_size);
-        let mut vma = Vma::new(start, end, flags);
-        vma.set_type(vma_type);
-        self.map_vma(vma, perm)?;
         Ok(start)
     }
 
@@ -856,17 +1076,22 @@ impl AddressSpace {
             let vma_mgr = self.vma_read();
 
             // 查找包含起始地址的 VMA，获取必要信息
-            let vma_info = vma_mgr.find(addr).map(|vma| {
-                (vma.start(), vma.end())
-            });
+            let vma_info = vma_mgr.find(addr).cloned();
             drop(vma_mgr);  // 释放读锁
 
-            if let Some((vma_start, vma_end)) = vma_info {
+            if let Some(vma) = vma_info {
+                let vma_start = vma.start();
                 let vma_start_usize = vma_start.as_usize();
-                let vma_end_usize = vma_end.as_usize();
+                let vma_end_usize = vma.end().as_usize();
 
                 // 检查是否完全覆盖 VMA
                 if addr.as_usize() <= vma_start_usize && end_addr >= vma_end_usize {
+                    // 共享文件映射：取消映射前写回驻留页
+                    if vma.vma_type() == VmaType::FileBacked {
+                        self.write_back_file_pages(&vma, vma_start_usize, vma_end_usize);
+                        self.file_maps.lock().remove(&vma_start);
+                    }
+
                     // 完全取消映射
                     let mut vma_mgr = self.vma_write();
                     vma_mgr.remove(vma_start)?;
@@ -905,15 +1130,20 @@ impl AddressSpace {
                 unsafe {
                     self.clear_pte(addr as u64);
                 }
+            } else if unsafe { user_pte(self.root_ppn, addr as u64) }
+                .is_some_and(|pte| pte.bits() & sw_flags::PROT_NONE != 0)
+            {
+                // PROT_NONE 页的有效位已清除，同样需要清除，否则之后的 mprotect 会恢复旧页
+                unsafe {
+                    self.clear_pte(addr as u64);
+                }
             }
 
             addr += PAGE_SIZE_USIZE;
         }
 
-        // 刷新 TLB
-        unsafe {
-            core::arch::asm!("sfence.vma zero, zero");
-        }
+        // 刷新 TLB（TLB shootdown）
+        self.flush_tlb_range(start.as_usize(), end);
 
         Ok(())
     }
@@ -943,6 +1173,104 @@ impl AddressSpace {
         (*table0).set(vpn0, PageTableEntry::from_bits(0));
     }
 
+    /// mprotect 系统调用实现 (mm/mprotect.c)
+    ///
+    /// 在范围边界分裂 VMA，更新范围内 VMA 的读/写/执行标志，
+    /// 重写已映射页的页表项权限位，最后对该范围做 TLB shootdown
+    ///
+    /// # 返回
+    /// 范围没有被 VMA 完整覆盖时返回 `MapError::NotMapped`
+    pub fn mprotect(&self, addr: PageVirtAddr, size: usize, prot_flags: u32) -> Result<(), MapError> {
+        if addr.as_usize() % PAGE_SIZE_USIZE != 0 || prot_flags & !prot::PROT_MASK != 0 {
+            return Err(MapError::Invalid);
+        }
+        let aligned_size = (size + PAGE_SIZE_USIZE - 1) & !(PAGE_SIZE_USIZE - 1);
+        if aligned_size == 0 {
+            return Ok(());
+        }
+        let start = addr.as_usize();
+        let end = start.checked_add(aligned_size).ok_or(MapError::NotMapped)?;
+
+        let mut vma_mgr = self.vma_write();
+        if !vma_mgr.covers(addr, PageVirtAddr::new(end)) {
+            return Err(MapError::NotMapped);
+        }
+
+        // 只修改范围内的部分：在两端分裂 VMA
+        for boundary in [start, end] {
+            if let Some((first, second)) = vma_mgr.split_at(PageVirtAddr::new(boundary)) {
+                // 文件映射表按 VMA 起始地址索引，后半部分同样需要能找到文件
+                if first.vma_type() == VmaType::FileBacked {
+                    let mut file_maps = self.file_maps.lock();
+                    if let Some(mapping) = file_maps.get(&first.start()).cloned() {
+                        file_maps.insert(second.start(), mapping);
+                    }
+                }
+            }
+        }
+
+        for vma in vma_mgr.range_mut(addr, PageVirtAddr::new(end)) {
+            let mut flags = vma.flags();
+            flags.remove(VmaFlags::READ | VmaFlags::WRITE | VmaFlags::EXEC);
+            if prot_flags & prot::PROT_READ != 0 {
+                flags.insert(VmaFlags::READ);
+            }
+            if prot_flags & prot::PROT_WRITE != 0 {
+                flags.insert(VmaFlags::WRITE);
+            }
+            if prot_flags & prot::PROT_EXEC != 0 {
+                flags.insert(VmaFlags::EXEC);
+            }
+            vma.set_flags(flags);
+        }
+        drop(vma_mgr);
+
+        self.change_pte_range(start, end, prot_flags);
+        self.flush_tlb_range(start, end);
+        Ok(())
+    }
+
+    /// 按新的保护标志重写 [start, end) 内已映射页的页表项 (mm/mprotect.c: change_pte_range)
+    ///
+    /// - 写时复制页保持只读，由 COW 缺页在复制后再赋予写权限
+    /// - PROT_NONE 页清除有效位并设置 `sw_flags::PROT_NONE`，保留物理页以便之后恢复
+    fn change_pte_range(&self, start: usize, end: usize, prot_flags: u32) {
+        let mut perm = 0;
+        // RISC-V 不允许只写的页表项：可写页同时可读
+        if prot_flags & (prot::PROT_READ | prot::PROT_WRITE) != 0 {
+            perm |= PageTableEntry::R;
+        }
+        if prot_flags & prot::PROT_WRITE != 0 {
+            perm |= PageTableEntry::W;
+        }
+        if prot_flags & prot::PROT_EXEC != 0 {
+            perm |= PageTableEntry::X;
+        }
+
+        let rwx = PageTableEntry::V | PageTableEntry::R | PageTableEntry::W | PageTableEntry::X;
+        let mut addr = start;
+        while addr < end {
+            unsafe {
+                if let Some((table, index)) = PageTableWalker::leaf(self.root_ppn, addr as u64) {
+                    let old = (*table).get(index).bits();
+                    if old & (PageTableEntry::V | sw_flags::PROT_NONE) != 0 {
+                        let mut bits = old & !(rwx | sw_flags::PROT_NONE);
+                        if perm == 0 {
+                            bits |= sw_flags::PROT_NONE;
+                        } else {
+                            bits |= PageTableEntry::V | perm;
+                            if old & cow_flags::COW != 0 {
+                                bits &= !PageTableEntry::W;
+                            }
+                        }
+                        (*table).set(index, PageTableEntry::from_bits(bits));
+                    }
+                }
+            }
+            addr += PAGE_SIZE_USIZE;
+        }
+    }
+
     /// brk 系统调用实现（兼容旧接口）
     pub fn do_brk(&self, new_brk: PageVirtAddr) -> Result<PageVirtAddr, MapError> {
         self.set_brk(new_brk)
@@ -971,6 +1299,8 @@ impl AddressSpace {
         let new_root_ppn = unsafe {
             copy_page_table_cow(self.root_ppn).ok_or(MapError::OutOfMemory)?
         };
+        // 父进程的用户页已改为只读 + COW（用户区域是 VPN2 < 2 的低 2GB）
+        self.flush_tlb_range(0, 0x8000_0000);
 
         let new_space = unsafe { AddressSpace::new_shared(
             new_root_ppn,
@@ -985,11 +1315,12 @@ impl AddressSpace {
             if vma_mgr.iter().count() > 0 {
                 let mut new_vma_mgr = new_space.vma_write();
                 for vma in vma_mgr.iter() {
-                    let new_vma = Vma::new(vma.start(), vma.end(), vma.flags());
-                    let _ = new_vma_mgr.add(new_vma);
+                    // 保留 VMA 类型和文件偏移，文件映射在子进程中继续有效
+                    let _ = new_vma_mgr.add(*vma);
                 }
             }
         }
+        *new_space.file_maps.lock() = self.file_maps.lock().clone();
 
         Ok(new_space)
     }
@@ -1355,6 +1686,27 @@ fn user_phys_allocator_is_initialized() -> bool {
 struct PageTableWalker;
 
 impl PageTableWalker {
+    /// 查找虚拟地址所在的末级页表及其中的下标
+    ///
+    /// 只要求中间级页表存在，不检查末级页表项本身是否有效
+    unsafe fn leaf(user_root_ppn: u64, virt: u64) -> Option<(*mut PageTable, usize)> {
+        let virt_addr = VirtAddr::new(virt);
+
+        let root_table = (user_root_ppn << PAGE_SHIFT) as *const PageTable;
+        let pte2 = (*root_table).get(virt_addr.vpn(2) as usize);
+        if !pte2.is_valid() {
+            return None;
+        }
+
+        let table1 = (pte2.ppn() << PAGE_SHIFT) as *const PageTable;
+        let pte1 = (*table1).get(virt_addr.vpn(1) as usize);
+        if !pte1.is_valid() {
+            return None;
+        }
+
+        Some(((pte1.ppn() << PAGE_SHIFT) as *mut PageTable, virt_addr.vpn(0) as usize))
+    }
+
     /// 遍历页表查找虚拟地址对应的物理页号
     /// 返回 Some(ppn) 如果找到，None 如果未映射
     unsafe fn walk(user_root_ppn: u64, virt: u64) -> Option<u64> {
@@ -1397,6 +1749,10 @@ struct PhysAllocator {
     current: u64,
     /// 分配限制（最低地址）
     limit: u64,
+    /// 分配起始地址（最高地址）
+    end: u64,
+    /// 已释放页的链表头（物理地址，0 表示空），每页的首个 u64 保存下一页的地址
+    free_list: u64,
 }
 
 impl PhysAllocator {
@@ -1404,6 +1760,8 @@ impl PhysAllocator {
         Self {
             current: 0,
             limit: 0,
+            end: 0,
+            free_list: 0,
         }
     }
 
@@ -1415,12 +1773,21 @@ impl PhysAllocator {
     unsafe fn init(&mut self, start: u64, limit: u64) {
         self.current = start;
         self.limit = limit;
+        self.end = start;
     }
 
     /// 分配一页物理内存
     ///
     /// 返回物理页的物理地址，如果分配失败则返回 None
     unsafe fn alloc_page(&mut self) -> Option<u64> {
+        // 优先复用已释放的页
+        if self.free_list != 0 {
+            let page = self.free_list;
+            self.free_list = *(page as *const u64);
+            *(page as *mut u64) = 0;
+            return Some(page);
+        }
+
         if self.current < self.limit + PAGE_SIZE {
             return None;
         }
@@ -1431,6 +1798,10 @@ impl PhysAllocator {
 
     /// 分配多页物理内存
     unsafe fn alloc_pages(&mut self, count: usize) -> Option<u64> {
+        if count == 1 {
+            return self.alloc_page();
+        }
+
         let total_size = count as u64 * PAGE_SIZE;
 
         if self.current < self.limit + total_size {
@@ -1440,6 +1811,18 @@ impl PhysAllocator {
         self.current -= total_size;
         Some(self.current)
     }
+
+    /// 释放一页物理内存，放入空闲链表
+    ///
+    /// 返回 false 表示该页不属于本分配器
+    unsafe fn free_page(&mut self, phys: u64) -> bool {
+        if !(self.current..self.end).contains(&phys) || phys & (PAGE_SIZE - 1) != 0 {
+            return false;
+        }
+        *(phys as *mut u64) = self.free_list;
+        self.free_list = phys;
+        true
+    }
 }
 
 pub fn init_user_phys_allocator(start: u64, size: u64) {
@@ -1468,6 +1851,20 @@ pub fn alloc_user_phys_page() -> Option<u64> {
     unsafe { USER_PHYS_ALLOCATOR.alloc_page() }
 }
 
+/// 释放一页用户物理内存
+///
+/// 来自用户物理内存分配器的页放回其空闲链表，
+/// 其他页（例如 COW 复制时从内核帧分配器分配的页）归还给帧分配器
+pub fn free_user_phys_page(phys: u64) {
+    unsafe {
+        if !USER_PHYS_ALLOCATOR.free_page(phys) {
+            crate::mm::page::dealloc_frame(crate::mm::page::PhysFrame::containing_address(
+                PagePhysAddr::new(phys as usize),
+            ));
+        }
+    }
+}
+
 pub fn create_user_address_space() -> Option<u64> {
     unsafe {
         // 分配根页表（一页）
@@ -1616,6 +2013,18 @@ pub unsafe fn alloc_and_map_user_memory(
     Some(phys_addr)
 }
 
+/// 读取用户虚拟地址的末级页表项（包括无效的页表项），页表不存在时返回 None
+pub unsafe fn user_pte(user_root_ppn: u64, virt: u64) -> Option<PageTableEntry> {
+    let (table, index) = PageTableWalker::leaf(user_root_ppn, virt)?;
+    Some((*table).get(index))
+}
+
+/// 查询用户虚拟地址映射到的物理地址，未映射时返回 None
+pub unsafe fn user_virt_to_phys(user_root_ppn: u64, virt: u64) -> Option<u64> {
+    let ppn = PageTableWalker::walk(user_root_ppn, virt)?;
+    Some((ppn << PAGE_SHIFT) | (virt & PAGE_OFFSET_MASK))
+}
+
 pub fn get_kernel_page_table_ppn() -> u64 {
     unsafe {
         let root_addr = &raw mut ROOT_PAGE_TABLE as *mut PageTable as u64;
@@ -1646,6 +2055,30 @@ pub unsafe fn alloc_and_map_to_kernel_table(
     Some(phys_addr)
 }
 
+/// 信号返回 trampoline 的用户虚拟地址：紧挨用户栈顶之上的一页
+///
+/// RISC-V 没有 SA_RESTORER，Linux 让信号处理函数的 ra 指向 vDSO 中的
+/// `__vdso_rt_sigreturn`；这里每个程序映射一页只包含这段代码的只读可执行页
+pub const SIGRETURN_TRAMPOLINE: u64 = crate::config::USER_STACK_TOP;
+
+/// `__vdso_rt_sigreturn`：`li a7, 139`（__NR_rt_sigreturn）；`ecall`
+const SIGRETURN_CODE: [u32; 2] = [0x08b0_0893, 0x0000_0073];
+
+/// 在 `root_ppn` 页表中分配并映射信号返回 trampoline 页
+///
+/// execve 和加载 init 程序时调用；fork 通过页表复制共享这一页
+pub unsafe fn map_sigreturn_trampoline(root_ppn: u64) -> Option<u64> {
+    let phys = USER_PHYS_ALLOCATOR.alloc_pages(1)?;
+    core::ptr::write_bytes(phys as *mut u8, 0, PAGE_SIZE as usize);
+    core::ptr::copy_nonoverlapping(SIGRETURN_CODE.as_ptr(), phys as *mut u32, SIGRETURN_CODE.len());
+    asm!("fence.i", options(nostack));
+
+    let flags = PageTableEntry::V | PageTableEntry::R | PageTableEntry::X
+        | PageTableEntry::U | PageTableEntry::A;
+    map_user_region(root_ppn, SIGRETURN_TRAMPOLINE, phys, PAGE_SIZE, flags);
+    Some(phys)
+}
+
 pub unsafe fn switch_to_user(entry: u64, user_stack: u64) -> ! {
     // 直接调用汇编函数切换到用户模式
     switch_to_user_asm(entry, user_stack);
@@ -1663,9 +2096,17 @@ pub mod cow_flags {
     pub const COW: u64 = 1 << 8;  // 使用位 8（在 A 和 D 之后）
 }
 
+/// 其他软件使用的页表项位
+pub mod sw_flags {
+    /// PROT_NONE 页：有效位已清除，但 PPN 仍指向原来的物理页（位 9）
+    pub const PROT_NONE: u64 = 1 << 9;
+}
+
 /// 复制页表（用于 fork）
 ///
-/// 创建新页表，复制父进程的页表项，但将可写页标记为只读 + COW
+/// 创建新页表，复制父进程的页表项。所有用户页在父子进程中都标记为只读 + COW 并
+/// 增加物理页引用计数，包括只读页和 PROT_NONE 页：之后 mprotect 恢复写权限时
+/// COW 标志使写权限保持关闭，第一次写入仍会先复制。调用者需要刷新父进程的 TLB
 ///
 /// # 参数
 /// - parent_root_ppn: 父进程根页表的物理页号
@@ -1730,22 +2171,19 @@ pub unsafe fn copy_page_table_cow(parent_root_ppn: u64) -> Option<u64> {
             let child_ppn0 = (child_table0 as *const PageTable as u64) >> PAGE_SHIFT;
             (*child_table1_ref).set(vpn1, PageTableEntry::new_table(child_ppn0));
 
-            let parent_table0 = (ppn0 << PAGE_SHIFT) as *const PageTable;
+            let parent_table0 = (ppn0 << PAGE_SHIFT) as *mut PageTable;
             let child_table0_ref = &mut *child_table0;
 
             // 复制 L0 页表项（512 项）
             for vpn0 in 0..512 {
                 let pte0 = (*parent_table0).get(vpn0);
 
-                if !pte0.is_valid() {
+                // PROT_NONE 页的有效位已清除，但仍然持有物理页
+                if !pte0.is_valid() && pte0.bits() & sw_flags::PROT_NONE == 0 {
                     continue;  // 跳过无效项
                 }
 
-                // 只对用户可写页进行 COW 标记
-                let is_user = pte0.bits() & PageTableEntry::U != 0;
-                let is_writable = pte0.is_writable();
-
-                let new_pte = if is_user && is_writable {
+                let new_pte = if pte0.bits() & PageTableEntry::U != 0 {
                     // 获取物理页的 Page 描述符并增加引用计数
                     let phys_ppn = pte0.ppn();
                     let pfn = (phys_ppn as usize) + (PHYS_MEMORY_BASE / 0x1000);
@@ -1756,12 +2194,14 @@ pub unsafe fn copy_page_table_cow(parent_root_ppn: u64) -> Option<u64> {
                         (*page).set_flag(crate::mm::page_desc::PageFlag::Cow);
                     }
 
-                    // 移除 W 标志，添加 COW 标志
-                    PageTableEntry::from_bits(
+                    // 移除 W 标志，添加 COW 标志；父进程的页表项同样修改
+                    let cow = PageTableEntry::from_bits(
                         pte0.bits() & !PageTableEntry::W | cow_flags::COW
-                    )
+                    );
+                    (*parent_table0).set(vpn0, cow);
+                    cow
                 } else {
-                    // 非用户页或只读页，直接复制 PTE
+                    // 非用户页，直接复制 PTE
                     pte0
                 };
 
@@ -1837,7 +2277,7 @@ pub unsafe fn handle_cow_fault(root_ppn: u64, fault_addr: VirtAddr) -> Option<()
     let refcount = if !old_page.is_null() {
         (*old_page).refcount()
     } else {
-        1  // 如果没有 page descriptor，假设只有一个引用
+        2  // 没有 page descriptor 时无法确认独占，按共享处理总是复制
     };
 
     // 如果只有一个引用，直接恢复写权限（不需要复制）
@@ -2001,6 +2441,12 @@ pub fn handle_mm_fault(
     // 如果页面已映射，先检查是否是 COW
     if already_mapped {
         let is_write = flags & FaultFlags::WRITE != 0;
+        // mprotect 去掉写权限后，COW 页也不能再被复制成可写页
+        let write_denied = addr_space.vma_read().find(page_virt_addr)
+            .is_some_and(|vma| !vma.flags().is_writable());
+        if is_write && write_denied {
+            return MmFaultResult::PermissionDenied;
+        }
         if is_write && unsafe { is_cow_page(root_ppn, fault_addr) } {
             return MmFaultResult::CowPending;
         }
@@ -2009,6 +2455,15 @@ pub fn handle_mm_fault(
         return MmFaultResult::AlreadyMapped;
     }
 
+    // 栈下方的地址：在 RLIMIT_STACK 范围内向下扩展栈 VMA，之后按普通缺页处理
+    if addr_space.vma_read().find(page_virt_addr).is_none() {
+        use crate::process::rlimit::{current_rlimit, RLIMIT_STACK};
+        let stack_limit = current_rlimit(RLIMIT_STACK).rlim_cur;
+        if !addr_space.expand_stack(page_virt_addr, stack_limit) {
+            return MmFaultResult::Segfault;
+        }
+    }
+
     // 1. 查找 VMA
     let vma_mgr = addr_space.vma_read();
     let vma = match vma_mgr.find(page_virt_addr) {
@@ -2022,6 +2477,8 @@ pub fn handle_mm_fault(
     // 获取 VMA 属性
     let vma_flags = vma.flags();
     let vma_type = vma.vma_type();
+    let vma_start = vma.start();
+    let file_offset = vma.offset() + (fault_addr.floor().as_usize() - vma_start.as_usize());
 
     // 2. 验证权限
     let is_write = flags & FaultFlags::WRITE != 0;
@@ -2058,9 +2515,14 @@ pub fn handle_mm_fault(
                 core::ptr::write_bytes(page_ptr, 0, PAGE_SIZE_USIZE);
             }
             VmaType::FileBacked => {
-                // 文件映射：TODO - 从文件读取
-                // 暂时清零
-                core::ptr::write_bytes(page_ptr, 0, PAGE_SIZE_USIZE);
+                // 文件映射：从页缓存读取（MAP_PRIVATE 得到私有副本，写入不会回写）
+                let page = core::slice::from_raw_parts_mut(page_ptr, PAGE_SIZE_USIZE);
+                match addr_space.file_mapping(vma_start) {
+                    Some(mapping) => {
+                        mapping.fill_page(file_offset, page);
+                    }
+                    None => page.fill(0),
+                }
             }
             VmaType::Device => {
                 // 设备映射：不清零，由驱动处理
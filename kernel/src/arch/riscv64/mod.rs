@@ -15,6 +15,8 @@ pub mod syscall;
 pub mod mm;
 pub mod smp;
 pub mod ipi;
+pub mod tlb;
+pub mod fault;
 
 use crate::println;
 use core::arch::asm;
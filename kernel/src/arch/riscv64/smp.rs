@@ -150,6 +150,11 @@ pub fn init() -> bool {
     }
 }
 
+/// CPU 是否已经启动
+pub fn cpu_started(cpu: usize) -> bool {
+    cpu < MAX_CPUS && CPU_STARTED[cpu].load(Ordering::Acquire) == 1
+}
+
 pub fn num_started_cpus() -> usize {
     let mut count = 0;
     for i in 0..MAX_CPUS {
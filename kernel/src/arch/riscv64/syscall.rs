@@ -228,7 +228,9 @@ pub extern "C" fn syscall_handler(frame: &mut SyscallFrame) {
     frame.a0 = match syscall_no as u32 {
         63 => sys_read(args),
         64 => sys_write(args),
+        65 => sys_readv(args),        // RISC-V readv
         66 => sys_writev(args),       // RISC-V writev
+        71 => sys_sendfile(args),     // RISC-V sendfile
         2 => sys_open(args),          // RISC-V open
         56 => sys_openat(args),
         57 => sys_close(args),
@@ -240,7 +242,10 @@ pub extern "C" fn syscall_handler(frame: &mut SyscallFrame) {
         96 => sys_set_tid_address(args),   // musl libc: set_tid_address
         99 => sys_set_robust_list(args),   // musl libc: set_robust_list
         134 => { debug_println!("sys_rt_sigaction: not implemented"); -38_i64 as u64 },  // ENOSYS
+        132 => sys_sigaltstack(args),     // RISC-V sigaltstack
+        133 => sys_rt_sigsuspend(args),   // RISC-V rt_sigsuspend
         135 => sys_rt_sigprocmask(args),  // RISC-V rt_sigprocmask
+        137 => sys_rt_sigtimedwait(args), // RISC-V rt_sigtimedwait
         280 => sys_select(args),          // RISC-V select
         281 => sys_pselect6(args),        // RISC-V pselect6
         7 => sys_poll(args),              // RISC-V poll
@@ -252,7 +257,7 @@ pub extern "C" fn syscall_handler(frame: &mut SyscallFrame) {
         290 => sys_eventfd(args),         // RISC-V eventfd (可能需要确认)
         291 => sys_eventfd2(args),        // RISC-V eventfd2
         59 => sys_pipe2(args),            // RISC-V pipe2 (supports flags)
-        220 => sys_fork(args),
+        220 => sys_clone(args),
         221 => sys_execve(args),
         260 => sys_wait4(args),
         160 => sys_uname(args),
@@ -266,6 +271,11 @@ pub extern "C" fn syscall_handler(frame: &mut SyscallFrame) {
         23 => sys_dup(args),
         24 => sys_dup2(args),
         25 => sys_fcntl(args),
+        46 => sys_ftruncate(args),      // RISC-V ftruncate
+        81 => sys_sync(args),           // RISC-V sync
+        82 => sys_fsync(args),          // RISC-V fsync
+        83 => sys_fsync(args),          // RISC-V fdatasync - 与 fsync 相同
+        52 => sys_fchmod(args),         // RISC-V fchmod
         29 => sys_ioctl(args),          // RISC-V ioctl
         73 => sys_flock(args),          // RISC-V flock
         80 => sys_fstat(args),
@@ -275,6 +285,9 @@ pub extern "C" fn syscall_handler(frame: &mut SyscallFrame) {
         74 => sys_unlink(args),
         78 => sys_link(args),
         214 => sys_brk(args),
+        163 => sys_getrlimit(args),     // RISC-V getrlimit
+        164 => sys_setrlimit(args),     // RISC-V setrlimit
+        261 => sys_prlimit64(args),     // RISC-V prlimit64
         222 => {
             sys_mmap(args)
         }
@@ -300,6 +313,26 @@ pub extern "C" fn syscall_handler(frame: &mut SyscallFrame) {
             -38_i64 as u64  // ENOSYS - 函数未实现
         }
     };
+
+    // 被信号中断时，根据 SA_RESTART 决定重启还是返回 -EINTR
+    let restart = crate::signal::current_syscall_restart(syscall_no, frame.a0 as i64);
+    apply_syscall_restart(frame, args[0], restart);
+}
+
+/// 按重启决策调整系统调用帧
+///
+/// 重启时恢复被返回值覆盖的第一个参数，并把 PC 回退一条 ecall 指令；
+/// trap 返回时统一给 PC 加 4，所以回到用户态后会再次执行 ecall。
+/// 其余参数寄存器没有被修改，不需要恢复
+pub fn apply_syscall_restart(
+    frame: &mut SyscallFrame,
+    orig_a0: u64,
+    restart: crate::signal::SyscallRestart,
+) {
+    if restart == crate::signal::SyscallRestart::Restart {
+        frame.a0 = orig_a0;
+        frame.pc = frame.pc.wrapping_sub(4);
+    }
 }
 
 // ============================================================================
@@ -360,7 +393,8 @@ fn sys_write(args: [u64; 6]) -> u64 {
 
     unsafe {
         // Special handling for stdout (1) and stderr (2) - write directly to UART
-        if fd == 1 || fd == 2 {
+        // 仅当它们仍指向控制台时（未被 dup2 重定向到管道或文件）
+        if (fd == 1 || fd == 2) && fd_is_console(fd) {
             use crate::console::putchar;
             let slice = core::slice::from_raw_parts(buf, count);
             for &b in slice {
@@ -389,7 +423,35 @@ fn sys_write(args: [u64; 6]) -> u64 {
     }
 }
 
-/// sys_writev - 向文件描述符写入多个缓冲区
+/// 单次 readv/writev 允许的最大 iovec 数量（Linux UIO_MAXIOV）
+const UIO_MAXIOV: usize = 1024;
+
+/// 从用户空间复制并校验 iovec 数组
+///
+/// 每个非空段的基地址都必须位于用户空间，否则返回 EFAULT
+fn import_iovec(iov_ptr: u64, iovcnt: usize) -> Result<alloc::vec::Vec<crate::fs::Iovec>, u64> {
+    use crate::fs::Iovec;
+
+    if iovcnt > UIO_MAXIOV {
+        return Err(-22_i64 as u64);  // EINVAL
+    }
+
+    let mut iovs = alloc::vec![Iovec { iov_base: core::ptr::null_mut(), iov_len: 0 }; iovcnt];
+    let size = iovcnt * core::mem::size_of::<Iovec>();
+    if unsafe { copy_from_user(iovs.as_mut_ptr() as *mut u8, iov_ptr, size) } != 0 {
+        return Err(-14_i64 as u64);  // EFAULT
+    }
+
+    for iov in iovs.iter() {
+        if iov.iov_len > 0 && !unsafe { verify_user_range(iov.iov_base as u64, iov.iov_len) } {
+            return Err(-14_i64 as u64);  // EFAULT
+        }
+    }
+
+    Ok(iovs)
+}
+
+/// sys_readv - 从文件描述符读入多个缓冲区
 ///
 /// # 参数
 /// - args[0]: fd - 文件描述符
@@ -397,62 +459,137 @@ fn sys_write(args: [u64; 6]) -> u64 {
 /// - args[2]: iovcnt - iovec 数组的长度
 ///
 /// # 返回
-/// 成功返回写入的总字节数，失败返回负错误码
+/// 成功返回读取的总字节数，失败返回负错误码
+///
+/// - RISC-V: 65
+fn sys_readv(args: [u64; 6]) -> u64 {
+    let fd = args[0] as usize;
+    let iovcnt = args[2] as usize;
 
-/// iovec 结构体 (用于 writev/readv)
-#[repr(C)]
-struct Iovec {
-    iov_base: *const u8,
-    iov_len: usize,
+    let iovs = match import_iovec(args[1], iovcnt) {
+        Ok(iovs) => iovs,
+        Err(e) => return e,
+    };
+
+    match unsafe { crate::fs::get_file_fd(fd) } {
+        Some(file) => match crate::fs::vfs_readv(&file, &iovs) {
+            Ok(n) => n as u64,
+            Err(e) => e as i64 as u64,
+        },
+        None => -9_i64 as u64,  // EBADF
+    }
+}
+
+/// 检查文件描述符是否指向 UART 控制台（或尚未安装）
+fn fd_is_console(fd: usize) -> bool {
+    match unsafe { crate::fs::get_file_fd(fd) } {
+        Some(file) => match unsafe { *file.ops.get() } {
+            Some(ops) => core::ptr::eq(ops, &crate::fs::char_dev::UART_OPS),
+            None => false,
+        },
+        None => true,
+    }
 }
 
+/// sys_writev - 向文件描述符写入多个缓冲区
+///
+/// # 参数
+/// - args[0]: fd - 文件描述符
+/// - args[1]: iov - 指向 iovec 结构数组的指针
+/// - args[2]: iovcnt - iovec 数组的长度
+///
+/// # 返回
+/// 成功返回写入的总字节数，失败返回负错误码
+///
+/// - RISC-V: 66
 fn sys_writev(args: [u64; 6]) -> u64 {
     let fd = args[0] as usize;
-    let iov_ptr = args[1] as *const Iovec;
     let iovcnt = args[2] as usize;
 
-    // 用户空间地址范围：0x10000 到 0x7fff_f000
-    const USER_START: usize = 0x10000;
-    const USER_END: usize = 0x7fff_f000;
-
-    // 检查 iov_ptr 是否在用户空间范围内
-    let iov_addr = iov_ptr as usize;
-    if iov_addr < USER_START || iov_addr >= USER_END {
-        return -14_i64 as u64; // EFAULT
-    }
-
-    let mut total_written: isize = 0;
-    let mut has_valid_iov = false;
+    let iovs = match import_iovec(args[1], iovcnt) {
+        Ok(iovs) => iovs,
+        Err(e) => return e,
+    };
 
-    unsafe {
-        for i in 0..iovcnt {
-            let iov = &*iov_ptr.add(i);
-
-            let base = iov.iov_base as usize;
-            if iov.iov_len > 0 && base >= USER_START && base < USER_END {
-                has_valid_iov = true;
-                let write_args = [fd as u64, iov.iov_base as u64, iov.iov_len as u64, 0, 0, 0];
-                let result = sys_write(write_args);
-                let result_i64 = result as i64;
-                if result_i64 < 0 {
-                    if total_written == 0 {
-                        return result;
-                    }
-                    break;
-                }
-                total_written += result as isize;
-            } else if iov.iov_len > 0 {
-                // 无效的 iov_base 地址
-                return -14_i64 as u64; // EFAULT
+    // stdout/stderr 指向控制台时直接写 UART（与 sys_write 一致）
+    if (fd == 1 || fd == 2) && fd_is_console(fd) {
+        let mut total_written = 0u64;
+        for iov in iovs.iter().filter(|iov| iov.iov_len > 0) {
+            let write_args = [fd as u64, iov.iov_base as u64, iov.iov_len as u64, 0, 0, 0];
+            let result = sys_write(write_args);
+            if (result as i64) < 0 {
+                return if total_written == 0 { result } else { total_written };
             }
+            total_written += result;
         }
+        return total_written;
     }
 
-    if !has_valid_iov && iovcnt > 0 {
-        return -14_i64 as u64; // EFAULT
+    match unsafe { crate::fs::get_file_fd(fd) } {
+        Some(file) => match crate::fs::vfs_writev(&file, &iovs) {
+            Ok(n) => n as u64,
+            Err(e) => e as i64 as u64,
+        },
+        None => -9_i64 as u64,  // EBADF
     }
+}
+
+/// sys_sendfile - 在文件描述符之间复制数据
+///
+/// # 参数
+/// - args[0]: out_fd - 输出文件描述符（管道、socket 或文件）
+/// - args[1]: in_fd - 输入文件描述符（常规文件）
+/// - args[2]: offset - 输入偏移指针（NULL 表示使用并更新 in_fd 的文件位置）
+/// - args[3]: count - 要复制的字节数
+///
+/// # 返回
+/// 成功返回复制的字节数，失败返回负错误码
+///
+/// - RISC-V: 71
+fn sys_sendfile(args: [u64; 6]) -> u64 {
+    use crate::fs::get_file_fd;
+
+    let out_fd = args[0] as usize;
+    let in_fd = args[1] as usize;
+    let offset_ptr = args[2];
+    let count = args[3] as usize;
+
+    // 与 Linux 一样先读取用户的偏移，再查找文件
+    let offset = if offset_ptr == 0 {
+        None
+    } else {
+        let mut off: i64 = 0;
+        if unsafe { copy_from_user(&mut off as *mut i64 as *mut u8, offset_ptr, core::mem::size_of::<i64>()) } != 0 {
+            return -14_i64 as u64;  // EFAULT
+        }
+        if off < 0 {
+            return -22_i64 as u64;  // EINVAL
+        }
+        Some(off as u64)
+    };
 
-    total_written as u64
+    let in_file = match unsafe { get_file_fd(in_fd) } {
+        Some(f) => f,
+        None => return -9_i64 as u64,  // EBADF
+    };
+    let out_file = match unsafe { get_file_fd(out_fd) } {
+        Some(f) => f,
+        None => return -9_i64 as u64,  // EBADF
+    };
+
+    match crate::fs::do_sendfile(&out_file, &in_file, offset, count) {
+        Ok((copied, new_offset)) => {
+            // 显式偏移：写回更新后的偏移，文件位置保持不变
+            let new_offset = new_offset as i64;
+            if offset_ptr != 0
+                && unsafe { copy_to_user(offset_ptr, &new_offset as *const i64 as *const u8, core::mem::size_of::<i64>()) } != 0
+            {
+                return -14_i64 as u64;  // EFAULT
+            }
+            copied as u64
+        }
+        Err(e) => e as i64 as u64,
+    }
 }
 
 /// sys_open - 打开文件
@@ -671,16 +808,14 @@ fn sys_pipe2_impl(args: [u64; 6], flags: u64) -> u64 {
 /// 成功返回就绪的文件描述符数量，超时返回 0，失败返回负错误码
 ///
 /// # 说明
-/// 这是一个简化实现，主要支持:
-/// - 检查文件描述符是否就绪
-/// - 超时机制
-/// - 返回修改后的 fd_sets
+/// 通过各文件的 `FileOps::poll` 查询就绪状态，未就绪时在 poll 等待队列上
+/// 睡眠直到状态变化或超时；sigmask 暂未使用
 fn sys_pselect6(args: [u64; 6]) -> u64 {
     let nfds = args[0] as i32;
     let readfds_ptr = args[1] as *mut FdSet;
     let writefds_ptr = args[2] as *mut FdSet;
     let exceptfds_ptr = args[3] as *mut FdSet;
-    let timeout_ptr = args[4] as *const TimeVal;
+    let timeout_ptr = args[4];
     let _sigmask_ptr = args[5] as *const u64;  // sigmask 暂未使用
 
     // 验证 nfds 范围
@@ -721,52 +856,58 @@ fn sys_pselect6(args: [u64; 6]) -> u64 {
         None => return -9_i64 as u64,  // EBADF
     };
 
-    let mut ready_count = 0;
-
-    // 检查所有文件描述符
-    for fd in 0..nfds {
-        let mut is_readable = false;
-        let mut is_writable = false;
-        let mut has_exception = false;
-
-        // 检查文件描述符是否存在
-        let file_exists = fdtable.get_file(fd as usize).is_some();
-
-        if !file_exists {
-            // 文件描述符不存在，跳过
-            continue;
+    // 超时：NULL 表示一直等待
+    let timeout_ms = if timeout_ptr == 0 {
+        -1
+    } else {
+        let mut tv = TimeVal { tv_sec: 0, tv_usec: 0 };
+        if unsafe { copy_from_user(&mut tv as *mut TimeVal as *mut u8, timeout_ptr, core::mem::size_of::<TimeVal>()) } != 0 {
+            return -14_i64 as u64;  // EFAULT
         }
-
-        // 简化实现：
-        // 1. 对于 readfds: 所有有效的 fd 都认为是可读的
-        if original_readfds.is_set(fd) {
-            is_readable = true;
+        if tv.tv_sec < 0 || tv.tv_usec < 0 {
+            return -22_i64 as u64;  // EINVAL
         }
+        tv.tv_sec * 1000 + tv.tv_usec / 1000
+    };
 
-        // 2. 对于 writefds: 所有有效的 fd 都认为是可写的
-        if original_writefds.is_set(fd) {
-            is_writable = true;
-        }
+    // 检查所有文件描述符
+    let scan = || {
+        use crate::fs::poll::{self, events::*};
+
+        let mut ready_count = 0;
+        result_readfds = FdSet::new();
+        result_writefds = FdSet::new();
+        result_exceptfds = FdSet::new();
+
+        for fd in 0..nfds {
+            let wanted = original_readfds.is_set(fd) || original_writefds.is_set(fd)
+                || original_exceptfds.is_set(fd);
+            if !wanted {
+                continue;
+            }
+            let revents = poll_fd(fdtable, fd, poll::READABLE | poll::WRITABLE | POLLPRI);
 
-        // 3. 对于 exceptfds: 暂不实现异常检查
-        if original_exceptfds.is_set(fd) {
-            has_exception = false;  // 暂不支持异常
+            // 出错或挂断的 fd 同时报告为可读/可写，读写时再返回具体错误
+            if original_readfds.is_set(fd) && revents & (poll::READABLE | POLLHUP | POLLERR) != 0 {
+                result_readfds.set(fd);
+                ready_count += 1;
+            }
+            if original_writefds.is_set(fd) && revents & (poll::WRITABLE | POLLERR) != 0 {
+                result_writefds.set(fd);
+                ready_count += 1;
+            }
+            if original_exceptfds.is_set(fd) && revents & POLLPRI != 0 {
+                result_exceptfds.set(fd);
+                ready_count += 1;
+            }
         }
+        ready_count
+    };
 
-        // 设置返回的 fd_sets
-        if is_readable {
-            result_readfds.set(fd);
-            ready_count += 1;
-        }
-        if is_writable {
-            result_writefds.set(fd);
-            ready_count += 1;
-        }
-        if has_exception {
-            result_exceptfds.set(fd);
-            ready_count += 1;
-        }
-    }
+    let ready_count = match wait_ready(timeout_ms, scan) {
+        Ok(n) => n,
+        Err(e) => return e as i64 as u64,
+    };
 
     // 将结果写回用户空间
     unsafe {
@@ -900,6 +1041,167 @@ fn sys_rt_sigprocmask(args: [u64; 6]) -> u64 {
     0  // 成功
 }
 
+/// sigaltstack - 设置/查询备用信号栈
+///
+/// # 参数
+/// * `args[0]` - 新的信号栈 (SignalStack) 指针，为空时只查询
+/// * `args[1]` - 原信号栈输出指针（可为空）
+fn sys_sigaltstack(args: [u64; 6]) -> u64 {
+    use crate::signal::SignalStack;
+
+    let ss_ptr = args[0];
+    let old_ss_ptr = args[1];
+    let size = core::mem::size_of::<SignalStack>();
+
+    let new = if ss_ptr != 0 {
+        let mut ss = SignalStack::new();
+        unsafe {
+            if copy_from_user(&mut ss as *mut SignalStack as *mut u8, ss_ptr, size) != 0 {
+                return -14_i64 as u64;  // EFAULT
+            }
+        }
+        Some(ss)
+    } else {
+        None
+    };
+    // 先检查输出地址，避免设置了新栈却无法返回旧栈
+    if old_ss_ptr != 0 && !unsafe { verify_user_range(old_ss_ptr, size) } {
+        return -14_i64 as u64;  // EFAULT
+    }
+
+    let current = match crate::sched::current() {
+        Some(c) => c,
+        None => return -1_i64 as u64,  // EPERM
+    };
+
+    unsafe {
+        match crate::signal::do_sigaltstack(current, new) {
+            Ok(old) => {
+                if old_ss_ptr != 0
+                    && copy_to_user(old_ss_ptr, &old as *const SignalStack as *const u8, size) != 0
+                {
+                    return -14_i64 as u64;  // EFAULT
+                }
+                0
+            }
+            Err(e) => e as i64 as u64,
+        }
+    }
+}
+
+/// rt_sigsuspend - 临时替换信号掩码并等待信号
+///
+/// # 参数
+/// * `args[0]` - 新信号掩码指针 (u64)
+/// * `args[1]` - sigsetsize，必须为 8
+///
+/// 收到未屏蔽的信号后恢复原掩码，返回 -EINTR
+fn sys_rt_sigsuspend(args: [u64; 6]) -> u64 {
+    let mask_ptr = args[0];
+    let sigsetsize = args[1] as usize;
+
+    if sigsetsize != 8 {
+        return -22_i64 as u64;  // EINVAL
+    }
+
+    let mut mask = 0u64;
+    unsafe {
+        if !verify_user_range(mask_ptr, 8)
+            || copy_from_user(&mut mask as *mut u64 as *mut u8, mask_ptr, 8) != 0
+        {
+            return -14_i64 as u64;  // EFAULT
+        }
+    }
+
+    let current = match crate::sched::current() {
+        Some(c) => c,
+        None => return -1_i64 as u64,  // EPERM
+    };
+
+    unsafe {
+        crate::signal::sigsuspend_task(current, mask, || {
+            crate::process::Task::sleep(crate::process::task::TaskState::Interruptible);
+        }) as i64 as u64
+    }
+}
+
+/// rt_sigtimedwait - 同步等待信号集合中的信号
+///
+/// # 参数
+/// * `args[0]` - 等待的信号集合指针 (u64)
+/// * `args[1]` - siginfo 输出指针（可为空）
+/// * `args[2]` - 超时 timespec 指针（为空表示一直等待）
+/// * `args[3]` - sigsetsize，必须为 8
+///
+/// 返回取走的信号编号；超时返回 -EAGAIN
+fn sys_rt_sigtimedwait(args: [u64; 6]) -> u64 {
+    use crate::drivers::timer;
+    use crate::signal::SigInfo;
+
+    let set_ptr = args[0];
+    let info_ptr = args[1];
+    let timeout_ptr = args[2];
+    let sigsetsize = args[3] as usize;
+    let info_size = core::mem::size_of::<SigInfo>();
+    let timespec_size = core::mem::size_of::<Timespec>();
+
+    if sigsetsize != 8 {
+        return -22_i64 as u64;  // EINVAL
+    }
+
+    let mut set = 0u64;
+    let mut timeout = Timespec { tv_sec: 0, tv_nsec: 0 };
+    unsafe {
+        if !verify_user_range(set_ptr, 8)
+            || copy_from_user(&mut set as *mut u64 as *mut u8, set_ptr, 8) != 0
+        {
+            return -14_i64 as u64;  // EFAULT
+        }
+        if timeout_ptr != 0
+            && copy_from_user(&mut timeout as *mut Timespec as *mut u8, timeout_ptr, timespec_size) != 0
+        {
+            return -14_i64 as u64;  // EFAULT
+        }
+        if info_ptr != 0 && !verify_user_range(info_ptr, info_size) {
+            return -14_i64 as u64;  // EFAULT
+        }
+    }
+
+    let current = match crate::sched::current() {
+        Some(c) => c,
+        None => return -1_i64 as u64,  // EPERM
+    };
+
+    unsafe {
+        let deadline = if timeout_ptr == 0 {
+            None
+        } else {
+            if timeout.tv_sec < 0 || !(0..1_000_000_000).contains(&timeout.tv_nsec) {
+                return -22_i64 as u64;  // EINVAL
+            }
+            let msecs = timeout.tv_sec as u64 * 1000 + timeout.tv_nsec as u64 / 1_000_000;
+            Some(timer::get_jiffies() + timer::msecs_to_jiffies(msecs))
+        };
+
+        let result = crate::signal::sigtimedwait_task(current, set, deadline, timer::get_jiffies, || {
+            crate::process::Task::sleep(crate::process::task::TaskState::Interruptible);
+        });
+
+        match result {
+            Ok(info) => {
+                // 信号已经被取走，复制失败也只能报告 EFAULT
+                if info_ptr != 0
+                    && copy_to_user(info_ptr, &info as *const SigInfo as *const u8, info_size) != 0
+                {
+                    return -14_i64 as u64;  // EFAULT
+                }
+                info.si_signo as u64
+            }
+            Err(e) => e as i64 as u64,
+        }
+    }
+}
+
 /// pollfd 结构体 (struct pollfd)
 ///
 #[repr(C)]
@@ -911,17 +1213,41 @@ pub struct PollFd {
 }
 
 /// poll 事件类型
-pub mod poll_events {
-    pub const POLLIN: u16 = 0x0001;      // 可读
-    pub const POLLPRI: u16 = 0x0002;     // 紧急可读
-    pub const POLLOUT: u16 = 0x0004;     // 可写
-    pub const POLLERR: u16 = 0x0008;     // 错误
-    pub const POLLHUP: u16 = 0x0010;     // 挂断
-    pub const POLLNVAL: u16 = 0x0020;    // 无效请求
-    pub const POLLRDNORM: u16 = 0x0040;  // 等同于 POLLIN
-    pub const POLLRDBAND: u16 = 0x0080;  // 优先带数据可读
-    pub const POLLWRNORM: u16 = 0x0100;  // 等同于 POLLOUT
-    pub const POLLWRBAND: u16 = 0x0200;  // 优先带数据可写
+pub use crate::fs::poll::events as poll_events;
+
+/// 查询 fd 的就绪事件（见 `File::poll`），fd 无效时返回 POLLNVAL
+fn poll_fd(fdtable: &crate::fs::FdTable, fd: i32, events: u16) -> u16 {
+    match fdtable.get_file(fd as usize) {
+        Some(file) => file.poll(events),
+        None => poll_events::POLLNVAL,
+    }
+}
+
+/// poll/select/epoll 共用的等待循环
+///
+/// 反复调用 `scan` 统计就绪数量，直到有 fd 就绪、超时或被信号打断。
+/// `timeout_ms` 为负表示一直等待，为 0 表示只检查一次。
+/// 没有就绪的 fd 时在 `fs::poll` 的等待队列上睡眠，就绪状态变化或到达截止时间时被唤醒
+fn wait_ready(timeout_ms: i64, mut scan: impl FnMut() -> usize) -> Result<usize, i32> {
+    use crate::drivers::timer;
+    use crate::errno::Errno;
+
+    let deadline = (timeout_ms > 0)
+        .then(|| timer::get_jiffies() + timer::msecs_to_jiffies(timeout_ms as u64));
+
+    loop {
+        let ready = scan();
+        if ready > 0 || timeout_ms == 0 {
+            return Ok(ready);
+        }
+        if deadline.is_some_and(|d| timer::get_jiffies() >= d) {
+            return Ok(0);
+        }
+        if crate::signal::signal_pending() {
+            return Err(Errno::InterruptedSystemCall.as_neg_i32());
+        }
+        crate::fs::poll::poll_wait(deadline, || scan() > 0);
+    }
 }
 
 /// sys_poll - I/O 多路复用 (poll 方式)
@@ -935,10 +1261,9 @@ pub mod poll_events {
 /// 成功返回就绪的文件描述符数量，超时返回 0，失败返回负错误码
 ///
 /// # 说明
-/// poll 比 select 更灵活，没有文件描述符数量限制
+/// poll 比 select 更灵活，没有文件描述符数量限制；
+/// 就绪状态来自各文件的 `FileOps::poll`
 fn sys_poll(args: [u64; 6]) -> u64 {
-    use poll_events::*;
-
     let fds_ptr = args[0] as *mut PollFd;
     let nfds = args[1] as usize;
     let timeout_ms = args[2] as i32;
@@ -966,45 +1291,26 @@ fn sys_poll(args: [u64; 6]) -> u64 {
         }
     };
 
-    let mut ready_count = 0;
-
-    // 检查所有文件描述符
-    for i in 0..nfds {
-        unsafe {
-            let pollfd = &mut *fds_ptr.add(i);
-            pollfd.revents = 0;  // 清空返回事件
-
-            // 检查文件描述符是否存在
-            let file_exists = fdtable.get_file(pollfd.fd as usize).is_some();
-
-            if !file_exists {
-                // 文件描述符不存在
-                pollfd.revents |= POLLNVAL;
-                ready_count += 1;
-                continue;
-            }
-
-            // 简化实现：
-            // 1. 对于 POLLIN: 所有有效的 fd 都认为是可读的
-            if pollfd.events & POLLIN != 0 {
-                pollfd.revents |= POLLIN | POLLRDNORM;
-                ready_count += 1;
-            }
-
-            // 2. 对于 POLLOUT: 所有有效的 fd 都认为是可写的
-            if pollfd.events & POLLOUT != 0 {
-                pollfd.revents |= POLLOUT | POLLWRNORM;
-                ready_count += 1;
+    // 检查所有文件描述符，返回 revents 非零的 fd 数量
+    let scan = || {
+        let mut ready_count = 0;
+        for i in 0..nfds {
+            unsafe {
+                let pollfd = &mut *fds_ptr.add(i);
+                // 负 fd 被忽略
+                pollfd.revents = if pollfd.fd < 0 { 0 } else { poll_fd(fdtable, pollfd.fd, pollfd.events) };
+                if pollfd.revents != 0 {
+                    ready_count += 1;
+                }
             }
-
-            // 3. 对于 POLLPRI: 暂不支持
-            // 4. 暂不设置 POLLERR/POLLHUP
         }
-    }
+        ready_count
+    };
 
-    // TODO: 实现超时机制
-    // 当前简化实现：立即返回
-    let _ = timeout_ms;
+    let ready_count = match wait_ready(timeout_ms as i64, scan) {
+        Ok(n) => n,
+        Err(e) => return e as i64 as u64,
+    };
 
     println!("sys_poll: {} file descriptors ready", ready_count);
 
@@ -1044,6 +1350,10 @@ use core::sync::atomic::{AtomicU32, Ordering};
 
 static EPOLL_INSTANCE_COUNTER: AtomicU32 = AtomicU32::new(1);
 
+/// epoll 兴趣列表：(pid, epfd) -> [(fd, 关注的事件)]
+static EPOLL_INTEREST: spin::Mutex<alloc::collections::BTreeMap<(u32, i32), alloc::vec::Vec<(i32, EPollEvent)>>> =
+    spin::Mutex::new(alloc::collections::BTreeMap::new());
+
 /// sys_epoll_create - 创建 epoll 实例
 ///
 /// # 参数
@@ -1079,8 +1389,10 @@ fn sys_epoll_create(args: [u64; 6]) -> u64 {
 
     // 简化实现：
     // 在真实实现中，应该创建一个 EpollFile 并安装到 fdtable
-    // 这里我们只是分配一个 fd，实际功能由 epoll_ctl/epoll_wait 实现
+    // 这里只分配 fd 并登记一个空的兴趣列表，由 epoll_ctl/epoll_wait 使用
     // TODO: 创建 EpollFile 结构
+    let pid = crate::process::current_pid();
+    EPOLL_INTEREST.lock().insert((pid, epoll_fd as i32), alloc::vec::Vec::new());
 
     println!("sys_epoll_create: created epoll fd {}", epoll_fd);
 
@@ -1164,12 +1476,6 @@ fn sys_epoll_ctl(args: [u64; 6]) -> u64 {
         EPollEvent { events: 0, data: 0 }
     };
 
-    // 简化实现：
-    // 在真实实现中，应该：
-    // 1. 查找 epfd 对应的 EpollFile
-    // 2. 根据 op 添加/删除/修改 fd 到 epoll 集合
-    // TODO: 实现 EpollFile 和红黑树
-
     println!("sys_epoll_ctl: op={}, fd={}, events={:#x}, data={:#x}",
              match op {
                  EPOLL_CTL_ADD => "ADD",
@@ -1179,6 +1485,23 @@ fn sys_epoll_ctl(args: [u64; 6]) -> u64 {
              },
              fd, event.events, event.data);
 
+    // 按 op 修改 epfd 的兴趣列表（简化实现：线性列表代替红黑树）
+    let pid = crate::process::current_pid();
+    let mut interest = EPOLL_INTEREST.lock();
+    let list = match interest.get_mut(&(pid, epfd)) {
+        Some(list) => list,
+        None => return -9_i64 as u64,  // EBADF - 不是 epoll fd
+    };
+    let pos = list.iter().position(|(f, _)| *f == fd);
+
+    match (op, pos) {
+        (EPOLL_CTL_ADD, Some(_)) => return -17_i64 as u64,  // EEXIST
+        (EPOLL_CTL_ADD, None) => list.push((fd, event)),
+        (EPOLL_CTL_MOD, Some(i)) => list[i].1 = event,
+        (EPOLL_CTL_DEL, Some(i)) => { list.remove(i); }
+        _ => return -2_i64 as u64,  // ENOENT
+    }
+
     0  // 成功
 }
 
@@ -1223,19 +1546,53 @@ fn sys_epoll_wait(args: [u64; 6]) -> u64 {
         return -22_i64 as u64;  // EINVAL
     }
 
-    // 简化实现：
-    // 在真实实现中，应该：
-    // 1. 查找 epfd 对应的 EpollFile
-    // 3. 等待事件或超时
-    // 4. 将就绪事件复制到用户空间
-    // TODO: 实现真实的等待逻辑
+    let fdtable = match crate::sched::get_current_fdtable() {
+        Some(ft) => ft,
+        None => return -9_i64 as u64,  // EBADF
+    };
 
-    // 当前简化：立即返回 0（超时）
-    let _ = (epfd, events_ptr, maxevents, timeout_ms);
+    let key = (crate::process::current_pid(), epfd);
+    if !EPOLL_INTEREST.lock().contains_key(&key) {
+        return -9_i64 as u64;  // EBADF - 不是 epoll fd
+    }
+
+    // 水平触发：每次都查询兴趣列表中所有 fd 的当前状态（EPOLLET 按水平触发处理）
+    let scan = || {
+        let mut interest = EPOLL_INTEREST.lock();
+        let Some(list) = interest.get_mut(&key) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for (fd, event) in list.iter_mut() {
+            if count == maxevents as usize {
+                break;
+            }
+            // EPOLLIN/EPOLLOUT 等低 16 位与 POLL* 取值相同
+            let revents = poll_fd(fdtable, *fd, event.events as u16);
+            if revents == 0 {
+                continue;
+            }
+            unsafe {
+                *events_ptr.add(count) = EPollEvent { events: revents as u32, data: event.data };
+            }
+            count += 1;
+            // EPOLLONESHOT：报告一次后停止监听，直到 EPOLL_CTL_MOD 重新启用
+            if event.events & epoll_events::EPOLLONESHOT != 0 {
+                event.events = 0;
+            }
+        }
+        count
+    };
+
+    let ready = match wait_ready(timeout_ms as i64, scan) {
+        Ok(n) => n,
+        Err(e) => return e as i64 as u64,
+    };
 
-    println!("sys_epoll_wait: timeout (no events)");
+    println!("sys_epoll_wait: {} events ready", ready);
 
-    0  // 超时
+    ready as u64
 }
 
 /// sys_epoll_pwait - 等待 epoll 事件（带信号掩码）
@@ -1380,8 +1737,12 @@ fn sys_kill(args: [u64; 6]) -> u64 {
 
 // 辅助函数用于测试
 #[inline(never)]
-fn sys_fork(_args: [u64; 6]) -> u64 {
-    match crate::process::do_fork() {
+fn sys_clone(args: [u64; 6]) -> u64 {
+    // args[0] 为 clone 标志（低 8 位是子进程退出时发给父进程的信号），args[1] 为新栈地址
+    if let Err(e) = crate::process::fork::check_clone_args(args[0], args[1]) {
+        return e as i64 as u64;
+    }
+    match crate::process::do_clone(args[0]) {
         Some(pid) => pid as u64,
         None => -12_i64 as u64,  // ENOMEM
     }
@@ -1592,6 +1953,12 @@ pub fn sys_execve(args: [u64; 6]) -> u64 {
 
     println!("sys_execve: user stack: virt={:#x}, phys={:#x}", USER_STACK_TOP, user_stack_phys);
 
+    // 信号处理函数返回用的 rt_sigreturn trampoline
+    if unsafe { crate::arch::riscv64::mm::map_sigreturn_trampoline(user_root_ppn) }.is_none() {
+        println!("sys_execve: failed to map sigreturn trampoline");
+        return -12_i64 as u64;  // ENOMEM
+    }
+
     // ===== 10.5 创建 AddressSpace 并注册 VMA =====
     use crate::arch::riscv64::mm::AddressSpace;
     use crate::mm::pagemap::PageTableType;
@@ -1644,6 +2011,16 @@ pub fn sys_execve(args: [u64; 6]) -> u64 {
     addr_space.vma_write().add(stack_vma).ok();
     println!("sys_execve: registered stack VMA {:#x}-{:#x}", user_stack_bottom, USER_STACK_TOP);
 
+    // trampoline 页（对应 Linux 的 [vdso]）
+    let mut trampoline_flags = VmaFlags::new();
+    trampoline_flags.insert(VmaFlags::READ | VmaFlags::EXEC);
+    let trampoline_vma = Vma::new(
+        crate::mm::page::VirtAddr::new(crate::arch::riscv64::mm::SIGRETURN_TRAMPOLINE as usize),
+        crate::mm::page::VirtAddr::new((crate::arch::riscv64::mm::SIGRETURN_TRAMPOLINE + PAGE_SIZE) as usize),
+        trampoline_flags,
+    );
+    addr_space.vma_write().add(trampoline_vma).ok();
+
     // 更新当前任务的 address_space
     if let Some(current_task) = crate::sched::current() {
         unsafe {
@@ -1652,6 +2029,11 @@ pub fn sys_execve(args: [u64; 6]) -> u64 {
         println!("sys_execve: updated task address_space");
     }
 
+    // 关闭 close-on-exec 文件描述符，其余 fd（包括 0/1/2 和重定向）保留给新程序
+    if let Some(fdtable) = crate::sched::get_current_fdtable() {
+        fdtable.close_on_exec();
+    }
+
     // ===== 11. 设置 argv/envp 到用户栈 =====
     // | envp[n]     |
     // | ...         |
@@ -2057,14 +2439,9 @@ fn sys_gettimeofday(args: [u64; 6]) -> u64 {
         return 0;  // NULL 指针是合法的
     }
 
-    // 从 RISC-V 定时器获取时间
-    // rdtime 返回的是 cycles，需要转换为微秒
-    // QEMU virt 机器的时钟频率是 10 MHz (10000000 Hz)
-    let cycles = crate::drivers::intc::clint::read_time();
-    let freq_hz: u64 = 10_000_000;  // 10 MHz
-
-    let sec = cycles / freq_hz;
-    let usec = (cycles % freq_hz) * 1_000_000 / freq_hz;
+    // 从 timekeeper 获取墙上时间（seqlock 保护，无锁读取）
+    let (sec, nsec) = crate::drivers::timer::ktime_get_real_ts();
+    let usec = nsec / 1_000;
 
     unsafe {
         (*tv_ptr).tv_sec = sec as i64;
@@ -2097,12 +2474,8 @@ fn sys_clock_gettime(args: [u64; 6]) -> u64 {
     // 目前只支持 REALTIME 和 MONOTONIC
     match clk_id {
         CLOCK_REALTIME | CLOCK_MONOTONIC => {
-            // 从 RISC-V 定时器获取时间
-            let cycles = crate::drivers::intc::clint::read_time();
-            let freq_hz: u64 = 10_000_000;  // 10 MHz
-
-            let sec = cycles / freq_hz;
-            let nsec = (cycles % freq_hz) * 1_000_000_000 / freq_hz;
+            // 从 timekeeper 获取时间（seqlock 保护，无锁读取）
+            let (sec, nsec) = crate::drivers::timer::ktime_get_real_ts();
 
             unsafe {
                 (*tp_ptr).tv_sec = sec as i64;
@@ -2357,6 +2730,83 @@ fn sys_fcntl(args: [u64; 6]) -> u64 {
     }
 }
 
+/// sys_ftruncate - 把打开的文件截断或扩展到指定长度
+///
+/// # 参数
+/// - args[0] (fd): 文件描述符
+/// - args[1] (length): 新的文件长度
+///
+/// # 返回
+/// 成功返回 0，失败返回负错误码；长度为负时返回 -EINVAL
+///
+/// - RISC-V: 46
+fn sys_ftruncate(args: [u64; 6]) -> u64 {
+    use crate::fs::file_truncate;
+
+    let fd = args[0] as usize;
+    let length = args[1] as i64;
+
+    if length < 0 {
+        return -22_i64 as u64;  // EINVAL
+    }
+
+    match file_truncate(fd, length as u64) {
+        Ok(()) => 0,
+        Err(errno) => errno as i64 as u64,
+    }
+}
+
+/// sys_fchmod - 修改打开文件的权限
+///
+/// # 参数
+/// - args[0] (fd): 文件描述符
+/// - args[1] (mode): 新的权限位
+///
+/// # 返回
+/// 成功返回 0，失败返回负错误码
+///
+/// - RISC-V: 52
+fn sys_fchmod(args: [u64; 6]) -> u64 {
+    use crate::fs::file_chmod;
+
+    let fd = args[0] as usize;
+    let mode = args[1] as u32;
+
+    match file_chmod(fd, mode) {
+        Ok(()) => 0,
+        Err(errno) => errno as i64 as u64,
+    }
+}
+
+/// sys_sync - 把所有脏缓冲区写回磁盘
+///
+/// # 返回
+/// 总是返回 0（与 Linux 一样不报告写回错误）
+///
+/// - RISC-V: 81
+fn sys_sync(_args: [u64; 6]) -> u64 {
+    let _ = crate::fs::bio::sync_buffers();
+    0
+}
+
+/// sys_fsync - 把打开文件的数据写回磁盘
+///
+/// # 参数
+/// - args[0] (fd): 文件描述符
+///
+/// # 返回
+/// 成功返回 0，失败返回负错误码
+///
+/// - RISC-V: 82 (fsync), 83 (fdatasync)
+fn sys_fsync(args: [u64; 6]) -> u64 {
+    use crate::fs::file_fsync;
+
+    match file_fsync(args[0] as usize) {
+        Ok(()) => 0,
+        Err(errno) => errno as i64 as u64,
+    }
+}
+
 /// sys_ioctl - 设备控制
 ///
 ///
@@ -2366,115 +2816,27 @@ fn sys_fcntl(args: [u64; 6]) -> u64 {
 /// - args[2] (arg): 命令参数
 ///
 /// # 返回
-/// 成功返回 0，失败返回负错误码
+/// 成功返回 0，失败返回负错误码；文件不支持该命令时返回 -ENOTTY
+///
+/// 命令由 fd 对应文件的 `FileOps::ioctl` 处理
 ///
 /// - RISC-V: 29
 fn sys_ioctl(args: [u64; 6]) -> u64 {
+    use crate::fs::get_file_fd;
+
     let fd = args[0] as i32;
     let cmd = args[1] as u32;
     let arg = args[2] as usize;
 
-    // 特殊处理 framebuffer 设备 (fd >= 1000 为设备文件)
-    if fd >= 1000 {
-        return crate::drivers::gpu::fbdev_ioctl(cmd, arg) as u64;
+    if fd < 0 {
+        return -9_i64 as u64; // EBADF
     }
 
-    // TTY ioctl 命令
-    match cmd {
-        // TCGETS - 获取终端属性 (0x5401)
-        0x5401 => {
-            if arg == 0 {
-                return -14_i64 as u64; // EFAULT
-            }
-            // 填充默认的 termios 结构
-            // struct termios {
-            //     tcflag_t c_iflag;   // 0x00: input flags
-            //     tcflag_t c_oflag;   // 0x04: output flags
-            //     tcflag_t c_cflag;   // 0x08: control flags
-            //     tcflag_t c_lflag;   // 0x0C: local flags (ICANON=0x100, ECHO=0x8)
-            //     cc_t c_line;        // 0x10: line discipline
-            //     cc_t c_cc[19];      // 0x11-0x23: control chars
-            // }
-            unsafe {
-                let ptr = arg as *mut u32;
-                // c_iflag: ICRNL | IXON
-                *ptr.offset(0) = 0x0100 | 0x0400;
-                // c_oflag: OPOST | ONLCR
-                *ptr.offset(1) = 0x0001 | 0x0004;
-                // c_cflag: B38400 | CS8 | CREAD | HUPCL
-                *ptr.offset(2) = 0x000F | 0x0030 | 0x0080 | 0x0400;
-                // c_lflag: ICANON | ECHO | ECHOE | ECHOK | ISIG
-                *ptr.offset(3) = 0x0100 | 0x0008 | 0x0010 | 0x0020 | 0x0001;
-                // c_line
-                *ptr.offset(4) = 0;
-                // c_cc[19] - control characters
-                let cc_ptr = ptr.offset(5) as *mut u8;
-                // VINTR=0, VQUIT=1, VERASE=2, VKILL=3, VEOF=4, VTIME=5, VMIN=6
-                *cc_ptr.offset(0) = 3;   // VINTR = ^C
-                *cc_ptr.offset(1) = 28;  // VQUIT = ^\
-                *cc_ptr.offset(2) = 127; // VERASE = DEL
-                *cc_ptr.offset(3) = 21;  // VKILL = ^U
-                *cc_ptr.offset(4) = 4;   // VEOF = ^D
-                *cc_ptr.offset(5) = 0;   // VTIME
-                *cc_ptr.offset(6) = 1;   // VMIN
-                // 其余保持 0
-            }
-            0
-        }
-        // TCSETS, TCSETSW, TCSETSF - 设置终端属性 (0x5402, 0x5403, 0x5404)
-        0x5402 | 0x5403 | 0x5404 => {
-            // 简化实现：忽略设置，返回成功
-            0
-        }
-        // TIOCGWINSZ - 获取窗口大小 (0x5413)
-        0x5413 => {
-            if arg == 0 {
-                return -14_i64 as u64; // EFAULT
-            }
-            // struct winsize {
-            //     unsigned short ws_row;
-            //     unsigned short ws_col;
-            //     unsigned short ws_xpixel;
-            //     unsigned short ws_ypixel;
-            // }
-            unsafe {
-                let ptr = arg as *mut u16;
-                *ptr.offset(0) = 25;  // ws_row
-                *ptr.offset(1) = 80;  // ws_col
-                *ptr.offset(2) = 0;   // ws_xpixel
-                *ptr.offset(3) = 0;   // ws_ypixel
-            }
-            0
-        }
-        // TIOCSWINSZ - 设置窗口大小 (0x5414)
-        0x5414 => {
-            0 // 忽略设置
-        }
-        // FIONREAD - 获取可读字节数 (0x541B)
-        0x541B => {
-            if arg == 0 {
-                return -14_i64 as u64; // EFAULT
-            }
-            unsafe {
-                let ptr = arg as *mut i32;
-                // 简化：返回 0（没有数据可读）
-                *ptr = 0;
-            }
-            0
-        }
-        // 其他 TTY 命令
-        _ if (cmd & 0xFF00) == 0x5400 => {
-            0 // 简化：返回成功
-        }
-        // 其他命令
-        _ => {
-            // 对于 stdin/stdout/stderr，返回成功
-            if fd >= 0 && fd <= 2 {
-                0
-            } else {
-                -25_i64 as u64  // ENOTTY
-            }
-        }
+    match unsafe { get_file_fd(fd as usize) } {
+        Some(file) => file.ioctl(cmd, arg) as u64,
+        // 兼容旧约定：未打开的 fd >= 1000 表示 framebuffer 设备
+        None if fd >= 1000 => crate::drivers::gpu::fbdev_ioctl(cmd, arg) as u64,
+        None => -9_i64 as u64, // EBADF
     }
 }
 
@@ -3071,83 +3433,172 @@ fn sys_recvfrom(args: [u64; 6]) -> u64 {
 ///
 /// - RISC-V: 214
 fn sys_brk(args: [u64; 6]) -> u64 {
-    use crate::sched;
+    match crate::sched::current() {
+        Some(current_task) => do_brk(current_task, args[0]),
+        None => -12_i64 as u64  // ENOMEM
+    }
+}
+
+/// 调整任务的堆顶（brk 的核心逻辑）
+///
+/// # 对齐保证
+/// - 初始 brk 是地址空间的堆起始地址，按页对齐
+/// - 返回值就是请求的地址本身（字节粒度，不做对齐），用户态按需自行对齐
+/// - 页表按页映射：新堆顶所在的整页都可读写，新映射的页清零
+/// - 缩小时解除新堆顶所在页之上的映射并释放物理页，再次增长得到的是清零的新页
+///
+/// # 返回
+/// 新的堆顶；`new_brk` 为 0、低于堆起始地址或映射失败时返回当前堆顶
+pub fn do_brk(task: &crate::process::Task, new_brk: u64) -> u64 {
     use crate::mm::page::PAGE_SIZE;
-    use crate::arch::riscv64::mm::{alloc_and_map_user_memory, PageTableEntry};
+    use crate::arch::riscv64::mm::{alloc_and_map_user_memory, user_virt_to_phys, PageTableEntry};
 
-    let new_brk = args[0] as u64;
+    // 堆起始地址来自地址空间，没有地址空间时使用默认值
+    let heap_start = task.address_space()
+        .map_or(0x2000_0000u64, |addr_space| addr_space.brk().as_usize() as u64);  // 512MB，避开设备映射区域
 
-    // 获取当前进程
-    match sched::current() {
-        Some(current_task) => {
-            // 获取当前 brk 值
-            let current_brk = current_task.get_brk();
-
-            // 如果 brk 未初始化，从地址空间获取或设置默认值
-            if current_brk == 0 {
-                // 尝试从地址空间的 brk 获取
-                let default_brk = if let Some(addr_space) = current_task.address_space() {
-                    addr_space.brk().as_usize() as u64
-                } else {
-                    0x2000_0000u64  // 512MB，避开设备映射区域
-                };
-                current_task.set_brk(default_brk);
+    // 如果 brk 未初始化，从堆起始地址开始
+    if task.get_brk() == 0 {
+        task.set_brk(heap_start);
+    }
+    let current_brk = task.get_brk();
 
-                if new_brk == 0 {
-                    return default_brk;
-                }
-            }
+    // 如果 new_brk 为 0 或低于堆起始地址，返回当前 brk
+    if new_brk == 0 || new_brk < heap_start {
+        return current_brk;
+    }
 
-            // 重新获取当前 brk（可能已更新）
-            let current_brk = current_task.get_brk();
+    let page_mask = !(PAGE_SIZE as u64 - 1);
 
-            // 如果 new_brk 为 0，返回当前 brk
-            if new_brk == 0 {
-                return current_brk;
-            }
+    // 缩小堆：解除新堆顶之上的页映射并释放，之后再增长时重新分配清零的页
+    if new_brk <= current_brk {
+        if let Some(addr_space) = task.address_space() {
+            let free_start = (new_brk + PAGE_SIZE as u64 - 1) & page_mask;
+            let free_end = (current_brk + PAGE_SIZE as u64 - 1) & page_mask;
+            addr_space.release_user_pages(free_start as usize, free_end as usize);
+        }
+        task.set_brk(new_brk);
+        return new_brk;
+    }
+
+    // 扩展堆：映射 [当前堆顶所在页, 新堆顶所在页] 中尚未映射的页
+    let root_ppn = match task.address_space() {
+        Some(addr_space) => addr_space.root_ppn(),
+        None => return current_brk,
+    };
+
+    // 权限: User + Read + Write + Valid + Accessed + Dirty
+    let pte_flags = PageTableEntry::V | PageTableEntry::R | PageTableEntry::W
+        | PageTableEntry::U | PageTableEntry::A | PageTableEntry::D;
 
-            // 确保新 brk 不低于当前值（不允许缩小堆）
-            if new_brk < current_brk {
+    let mut page = current_brk & page_mask;
+    let new_page_end = (new_brk + PAGE_SIZE as u64 - 1) & page_mask;
+    while page < new_page_end {
+        unsafe {
+            if user_virt_to_phys(root_ppn, page).is_none()
+                && alloc_and_map_user_memory(root_ppn, page, PAGE_SIZE as u64, pte_flags).is_none()
+            {
                 return current_brk;
             }
+        }
+        page += PAGE_SIZE as u64;
+    }
 
-            // 扩展堆：需要映射新的内存页
-            if new_brk > current_brk {
-                // 计算需要映射的页面范围
-                let current_page_end = (current_brk + PAGE_SIZE as u64 - 1) & !(PAGE_SIZE as u64 - 1);
-                let new_page_end = (new_brk + PAGE_SIZE as u64 - 1) & !(PAGE_SIZE as u64 - 1);
-
-                // 如果需要映射新页面
-                if new_page_end > current_page_end {
-                    // 获取地址空间的根页表
-                    let root_ppn = if let Some(addr_space) = current_task.address_space() {
-                        addr_space.root_ppn()
-                    } else {
-                        return current_brk;
-                    };
+    task.set_brk(new_brk);
+    new_brk
+}
 
-                    // 映射新的堆页面
-                    let size = new_page_end - current_page_end;
+/// sys_getrlimit - 获取当前进程的资源限制
+///
+/// # 参数
+/// - args[0] (resource): 资源编号 (RLIMIT_*)
+/// - args[1] (rlim): 输出的 struct rlimit 指针
+///
+/// # 返回
+/// 成功返回 0，失败返回负错误码
+///
+/// - RISC-V: 163
+fn sys_getrlimit(args: [u64; 6]) -> u64 {
+    sys_prlimit64([0, args[0], 0, args[1], 0, 0])
+}
 
-                    // 权限: User + Read + Write + Valid + Accessed + Dirty
-                    let pte_flags = PageTableEntry::V | PageTableEntry::R | PageTableEntry::W
-                        | PageTableEntry::U | PageTableEntry::A | PageTableEntry::D;
+/// sys_setrlimit - 设置当前进程的资源限制
+///
+/// # 参数
+/// - args[0] (resource): 资源编号 (RLIMIT_*)
+/// - args[1] (rlim): 新的 struct rlimit 指针
+///
+/// # 返回
+/// 成功返回 0，失败返回负错误码
+///
+/// - RISC-V: 164
+fn sys_setrlimit(args: [u64; 6]) -> u64 {
+    sys_prlimit64([0, args[0], args[1], 0, 0, 0])
+}
 
-                    unsafe {
-                        let result = alloc_and_map_user_memory(root_ppn, current_page_end, size, pte_flags);
-                        if result.is_none() {
-                            return current_brk;
-                        }
-                    }
-                }
+/// sys_prlimit64 - 获取/设置任意进程的资源限制
+///
+/// # 参数
+/// - args[0] (pid): 目标进程，0 表示当前进程
+/// - args[1] (resource): 资源编号 (RLIMIT_*)
+/// - args[2] (new_limit): 新的 struct rlimit64 指针，为 0 时不修改
+/// - args[3] (old_limit): 输出原限制的指针，为 0 时不输出
+///
+/// # 返回
+/// 成功返回 0；进程不存在返回 -ESRCH，限制无效返回 -EINVAL/-EPERM，指针无效返回 -EFAULT
+///
+/// - RISC-V: 261
+fn sys_prlimit64(args: [u64; 6]) -> u64 {
+    use crate::process::rlimit::{do_prlimit, RLimit};
+
+    let pid = args[0] as i32;
+    let resource = args[1] as usize;
+    let new_ptr = args[2];
+    let old_ptr = args[3];
+    let size = core::mem::size_of::<RLimit>();
+
+    let new = if new_ptr != 0 {
+        let mut limit = RLimit::INFINITY;
+        unsafe {
+            if !verify_user_range(new_ptr, size)
+                || copy_from_user(&mut limit as *mut RLimit as *mut u8, new_ptr, size) != 0
+            {
+                return -14_i64 as u64; // EFAULT
+            }
+        }
+        Some(limit)
+    } else {
+        None
+    };
+    if old_ptr != 0 && !unsafe { verify_user_range(old_ptr, size) } {
+        return -14_i64 as u64; // EFAULT
+    }
 
-                current_task.set_brk(new_brk);
-                new_brk
-            } else {
-                current_brk
+    let task: &crate::process::Task = match pid {
+        0 => match crate::sched::current() {
+            Some(task) => task,
+            None => return -3_i64 as u64, // ESRCH
+        },
+        pid if pid > 0 => {
+            let task_ptr = unsafe { crate::sched::find_task_by_pid(pid as u32) };
+            if task_ptr.is_null() {
+                return -3_i64 as u64; // ESRCH
             }
+            unsafe { &*task_ptr }
         }
-        None => -12_i64 as u64  // ENOMEM
+        _ => return -3_i64 as u64, // ESRCH
+    };
+
+    match do_prlimit(task, resource, new) {
+        Ok(old) => {
+            if old_ptr != 0
+                && unsafe { copy_to_user(old_ptr, &old as *const RLimit as *const u8, size) } != 0
+            {
+                return -14_i64 as u64; // EFAULT
+            }
+            0
+        }
+        Err(e) => e as i64 as u64,
     }
 }
 
@@ -3177,7 +3628,7 @@ fn sys_mmap(args: [u64; 6]) -> u64 {
     let prot_flags = args[2] as u32;
     let map_flags = args[3] as u32;
     let fd = args[4] as i32;
-    let _offset = args[5] as u64;
+    let offset = args[5] as usize;
 
     // 特殊处理：如果 length=0，分配一个页面
     // 这是为了兼容某些程序（如 musl）可能在某些边缘情况下请求 0 长度
@@ -3208,6 +3659,35 @@ fn sys_mmap(args: [u64; 6]) -> u64 {
         return mmap_error::EBADF as u64;
     }
 
+    // 文件映射：解析文件描述符对应的 inode
+    let file_mapping = if map_flags & map::MAP_ANONYMOUS == 0 {
+        if offset % 4096 != 0 {
+            return mmap_error::EINVAL as u64;
+        }
+        let file = match unsafe { crate::fs::get_file_fd(fd as usize) } {
+            Some(f) => f,
+            None => return mmap_error::EBADF as u64,
+        };
+        // 只写打开的文件不能映射；共享可写映射要求读写打开
+        if file.flags.is_writeonly() {
+            return mmap_error::EACCES as u64;
+        }
+        if map_type == map::MAP_SHARED && prot_flags & prot::PROT_WRITE != 0 && !file.flags.is_rdwr() {
+            return mmap_error::EACCES as u64;
+        }
+        // 设备文件（如 /dev/fb0）由驱动建立映射
+        if let Some(ret) = file.mmap(addr, actual_length, prot_flags, map_flags, offset) {
+            return ret as i64 as u64;
+        }
+        let inode = match unsafe { (*file.inode.get()).clone() } {
+            Some(inode) if inode.mode.is_regular_file() => inode,
+            _ => return mmap_error::ENODEV as u64,
+        };
+        Some(crate::mm::filemap::FileMapping::new(inode, map_type == map::MAP_SHARED))
+    } else {
+        None
+    };
+
     // 获取当前进程
     match crate::sched::current() {
         Some(current_task) => {
@@ -3260,15 +3740,25 @@ fn sys_mmap(args: [u64; 6]) -> u64 {
                         VmaType::FileBacked
                     };
 
-                    // 调用 AddressSpace::mmap
-                    let result = address_space.mmap(
-                        VirtAddr::new(addr),
-                        actual_length,
-                        vma_flags,
-                        vma_type,
-                        perm,
-                        map_flags,
-                    );
+                    // 调用 AddressSpace::mmap / mmap_file
+                    let result = match file_mapping {
+                        Some(mapping) => address_space.mmap_file(
+                            VirtAddr::new(addr),
+                            actual_length,
+                            vma_flags,
+                            map_flags,
+                            offset,
+                            mapping,
+                        ),
+                        None => address_space.mmap(
+                            VirtAddr::new(addr),
+                            actual_length,
+                            vma_flags,
+                            vma_type,
+                            perm,
+                            map_flags,
+                        ),
+                    };
                     match result {
                         Ok(mapped_addr) => mapped_addr.as_usize() as u64,
                         Err(e) => {
@@ -3463,28 +3953,19 @@ fn sys_munmap(args: [u64; 6]) -> u64 {
 /// - RISC-V: 226
 ///
 /// # 说明
-/// mprotect 用于更改已存在内存映射的保护属性
+/// mprotect 用于更改已存在内存映射的保护属性；
+/// 范围内存在未映射的地址时返回 ENOMEM
 fn sys_mprotect(args: [u64; 6]) -> u64 {
     use crate::mm::page::VirtAddr;
-    use crate::mm::vma::{VmaFlags, VmaType};
-    use crate::mm::pagemap::Perm;
+    use crate::arch::riscv64::mm::mmap_error;
 
     let addr = args[0] as usize;
     let length = args[1] as usize;
     let prot = args[2] as u32;
 
-    println!("sys_mprotect: addr={:#x}, length={}, prot={:#x}", addr, length, prot);
-
-    // 验证参数
-    if length == 0 {
-        println!("sys_mprotect: length is 0");
-        return -22_i64 as u64;  // EINVAL
-    }
-
     // 地址必须页对齐
     if addr % crate::mm::page::PAGE_SIZE != 0 {
-        println!("sys_mprotect: addr not page aligned");
-        return -22_i64 as u64;  // EINVAL
+        return mmap_error::EINVAL as u64;
     }
 
     // 获取当前进程
@@ -3492,44 +3973,16 @@ fn sys_mprotect(args: [u64; 6]) -> u64 {
         Some(current_task) => {
             match current_task.address_space_mut() {
                 Some(address_space) => {
-                    // 解析保护标志
-                    let mut perm = Perm::None;
-                    if prot & 0x1 != 0 {  // PROT_READ
-                        perm = Perm::Read;
-                    }
-                    if prot & 0x2 != 0 {  // PROT_WRITE
-                        perm = Perm::ReadWrite;
-                    }
-                    if prot & 0x4 != 0 {  // PROT_EXEC
-                        match perm {
-                            Perm::None => perm = Perm::ReadWriteExec,
-                            Perm::Read => perm = Perm::ReadWriteExec,
-                            Perm::ReadWrite => perm = Perm::ReadWriteExec,
-                            _ => {}
-                        }
+                    match address_space.mprotect(VirtAddr::new(addr), length, prot) {
+                        Ok(()) => 0,
+                        Err(crate::mm::pagemap::MapError::Invalid) => mmap_error::EINVAL as u64,
+                        Err(_) => mmap_error::ENOMEM as u64,
                     }
-
-                    // 简化实现：查找并更新 VMA 的保护
-                    // 在真实实现中，应该：
-                    // 1. 查找覆盖 [addr, addr+length) 的所有 VMA
-                    // 2. 分割 VMA 如果需要
-                    // 3. 更新页表权限
-                    // TODO: 实现完整的 mprotect 逻辑
-
-                    // 当前简化：直接返回成功
-                    println!("sys_mprotect: protection changed to {:?} (simplified)", perm);
-                    0
-                }
-                None => {
-                    println!("sys_mprotect: no address space");
-                    -12_i64 as u64  // ENOMEM
                 }
+                None => mmap_error::ENOMEM as u64,
             }
         }
-        None => {
-            println!("sys_mprotect: no current task");
-            -12_i64 as u64  // ENOMEM
-        }
+        None => mmap_error::ENOMEM as u64,
     }
 }
 
@@ -3586,16 +4039,24 @@ fn sys_msync(args: [u64; 6]) -> u64 {
         return -22_i64 as u64;  // EINVAL
     }
 
-    // 简化实现：
-    // 在真实实现中，应该：
-    // 1. 查找覆盖 [addr, addr+length) 的所有 VMA
-    // 2. 对文件映射的 VMA，将脏页写回
-    // 3. 如果设置了 MS_INVALIDATE，使缓存失效
-    // TODO: 实现完整的 msync 逻辑
-
-    println!("sys_msync: sync completed (simplified implementation)");
-
-    0  // 成功
+    // 将共享文件映射的驻留页写回页缓存
+    // MS_ASYNC 与 MS_SYNC 行为相同（页缓存写回是同步的）
+    // MS_INVALIDATE：映射页是页缓存的副本，无需额外处理
+    match crate::sched::current() {
+        Some(current_task) => {
+            match current_task.address_space_mut() {
+                Some(address_space) => {
+                    match address_space.msync(VirtAddr::new(addr), length) {
+                        Ok(()) => 0,
+                        // 范围内没有映射
+                        Err(_) => -12_i64 as u64,  // ENOMEM
+                    }
+                }
+                None => -12_i64 as u64,  // ENOMEM
+            }
+        }
+        None => -12_i64 as u64,  // ENOMEM
+    }
 }
 
 /// sys_mremap - 重新映射内存
@@ -3969,6 +4430,48 @@ pub unsafe fn verify_user_ptr_array(ptr: u64, size: usize) -> bool {
     }
 }
 
+/// 检查 [ptr, ptr+size) 是否完全位于用户程序可用的地址范围内
+///
+/// 与 sys_read/sys_write 的检查一致：低于 0x10000 的地址和内核地址（0x80000000 以上）无效
+#[inline]
+pub unsafe fn verify_user_range(ptr: u64, size: usize) -> bool {
+    const USER_START: u64 = 0x10000;
+    const USER_END: u64 = 0x8000_0000;
+
+    match ptr.checked_add(size as u64) {
+        Some(end) => ptr >= USER_START && end <= USER_END,
+        None => false,
+    }
+}
+
+/// 从用户空间复制数据到内核
+///
+/// 对应 Linux `copy_from_user()`：返回未能复制的字节数，0 表示成功
+pub unsafe fn copy_from_user(dst: *mut u8, src: u64, size: usize) -> usize {
+    if size == 0 {
+        return 0;
+    }
+    if !verify_user_range(src, size) {
+        return size;
+    }
+    core::ptr::copy_nonoverlapping(src as *const u8, dst, size);
+    0
+}
+
+/// 从内核复制数据到用户空间
+///
+/// 对应 Linux `copy_to_user()`：返回未能复制的字节数，0 表示成功
+pub unsafe fn copy_to_user(dst: u64, src: *const u8, size: usize) -> usize {
+    if size == 0 {
+        return 0;
+    }
+    if !verify_user_range(dst, size) {
+        return size;
+    }
+    core::ptr::copy_nonoverlapping(src, dst as *mut u8, size);
+    0
+}
+
 pub fn sys_write_impl(fd: i32, buf: *const u8, count: usize) -> u64 {
     use crate::console::putchar;
 
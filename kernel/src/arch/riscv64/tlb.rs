@@ -0,0 +1,166 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! 跨 CPU 的 TLB shootdown (arch/x86/mm/tlb.c, kernel/smp.c)
+//!
+//! 修改了其他 CPU 可能正在使用的页表项（munmap/mprotect）之后，
+//! 只刷新本 CPU 的 TLB 不够：其他正在运行同一地址空间的 CPU 仍可能使用旧的映射。
+//!
+//! 流程：
+//! 1. 发起者刷新本 CPU 的 TLB
+//! 2. 把刷新范围和目标 CPU 位掩码写入全局请求，向每个目标 CPU 发送 `IpiType::TlbFlush`
+//! 3. 目标 CPU 在 IPI 中刷新该范围并清除自己的位（确认）
+//! 4. 发起者等待位掩码清零后返回
+//!
+//! 同一时间只有一个 shootdown 在进行；等待其他发起者时会处理发给自己的请求，避免互相等待
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use spin::Mutex;
+
+use crate::arch::ipi::{smp_cross_call, IpiType};
+use crate::mm::page::PAGE_SIZE;
+
+/// 超过这个页数时刷新整个 TLB，而不是逐页刷新
+pub const FLUSH_ALL_THRESHOLD: usize = 32;
+
+/// 一次 shootdown 请求
+pub struct FlushRequest {
+    /// 刷新范围 [start, end)
+    start: AtomicUsize,
+    end: AtomicUsize,
+    /// 尚未确认的 CPU（bit N 对应 CPU N）
+    pending: AtomicUsize,
+}
+
+impl FlushRequest {
+    pub const fn new() -> Self {
+        Self {
+            start: AtomicUsize::new(0),
+            end: AtomicUsize::new(0),
+            pending: AtomicUsize::new(0),
+        }
+    }
+
+    /// 尚未确认的 CPU 位掩码
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Acquire)
+    }
+
+    /// `cpu` 是否还需要处理这个请求
+    pub fn is_pending(&self, cpu: usize) -> bool {
+        self.pending() & (1 << cpu) != 0
+    }
+
+    /// 目标 CPU 处理请求：刷新范围后确认
+    ///
+    /// # 返回
+    /// 请求不包含该 CPU 时返回 false（重复的或过期的 IPI）
+    pub fn ack(&self, cpu: usize) -> bool {
+        if !self.is_pending(cpu) {
+            return false;
+        }
+        let start = self.start.load(Ordering::Acquire);
+        let end = self.end.load(Ordering::Acquire);
+        flush_tlb_range_local(start, end);
+        self.pending.fetch_and(!(1 << cpu), Ordering::AcqRel);
+        true
+    }
+
+    /// 向 `targets` 中的每个 CPU 发出请求，并等待它们全部确认
+    ///
+    /// `send` 负责通知目标 CPU（实际为发送 IPI），`relax` 在每次检查确认之间调用。
+    /// 调用者必须保证同一时间只有一个请求在进行
+    pub fn run(
+        &self,
+        targets: usize,
+        start: usize,
+        end: usize,
+        mut send: impl FnMut(usize),
+        mut relax: impl FnMut(),
+    ) {
+        if targets == 0 {
+            return;
+        }
+        self.start.store(start, Ordering::Release);
+        self.end.store(end, Ordering::Release);
+        self.pending.store(targets, Ordering::Release);
+
+        for cpu in 0..usize::BITS as usize {
+            if targets & (1 << cpu) != 0 {
+                send(cpu);
+            }
+        }
+
+        while self.pending() != 0 {
+            relax();
+        }
+    }
+}
+
+impl Default for FlushRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 全局 shootdown 请求
+static FLUSH_REQUEST: FlushRequest = FlushRequest::new();
+
+/// 串行化 shootdown 的发起者
+static SHOOTDOWN_LOCK: Mutex<()> = Mutex::new(());
+
+/// 刷新本 CPU 上 [start, end) 范围的 TLB
+pub fn flush_tlb_range_local(start: usize, end: usize) {
+    let pages = end.saturating_sub(start).div_ceil(PAGE_SIZE);
+    unsafe {
+        if pages > FLUSH_ALL_THRESHOLD {
+            core::arch::asm!("sfence.vma zero, zero");
+        } else {
+            let mut addr = start & !(PAGE_SIZE - 1);
+            while addr < end {
+                core::arch::asm!("sfence.vma {}, zero", in(reg) addr);
+                addr += PAGE_SIZE;
+            }
+        }
+    }
+}
+
+/// 处理 `IpiType::TlbFlush`（在目标 CPU 的 IPI 中调用）
+pub fn handle_flush_ipi(cpu: usize) {
+    FLUSH_REQUEST.ack(cpu);
+}
+
+/// 刷新所有正在使用页表 `root_ppn` 的 CPU 上 [start, end) 范围的 TLB
+///
+/// 本 CPU 立即刷新；其他运行同一地址空间的 CPU 通过 IPI 刷新，
+/// 返回时它们都已确认
+pub fn flush_tlb_range_mm(root_ppn: u64, start: usize, end: usize) {
+    flush_tlb_range_local(start, end);
+
+    let this_cpu = crate::arch::cpu_id() as usize;
+    let targets = crate::sched::cpus_running_mm(root_ppn) & !(1 << this_cpu);
+    if targets == 0 {
+        return;
+    }
+
+    // 等待其他发起者时处理发给自己的请求，否则双方会互相等待确认
+    let _guard = loop {
+        if let Some(guard) = SHOOTDOWN_LOCK.try_lock() {
+            break guard;
+        }
+        handle_flush_ipi(this_cpu);
+        core::hint::spin_loop();
+    };
+
+    FLUSH_REQUEST.run(
+        targets,
+        start,
+        end,
+        |cpu| {
+            let _ = smp_cross_call(cpu, IpiType::TlbFlush);
+        },
+        core::hint::spin_loop,
+    );
+}
@@ -220,6 +220,19 @@ pub fn enable_external_interrupt() {
     }
 }
 
+/// 当前 CPU 是否会响应外部中断（sstatus.SIE 和 sie.SEIE 都已置位）
+///
+/// 陷入时硬件会清除 SIE，因此在陷入处理（包括系统调用）中返回 false
+pub fn external_interrupt_enabled() -> bool {
+    let sstatus: usize;
+    let sie: usize;
+    unsafe {
+        asm!("csrr {}, sstatus", out(reg) sstatus, options(nomem, nostack));
+        asm!("csrr {}, sie", out(reg) sie, options(nomem, nostack));
+    }
+    sstatus & (1 << 1) != 0 && sie & (1 << 9) != 0
+}
+
 /// 当前 CPU 的 TrapFrame 指针（用于 fork）
 /// 在 trap 入口时设置，在 trap 出口时清除
 static CURRENT_TRAP_FRAME: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0);
@@ -230,6 +243,138 @@ pub fn current_trap_frame() -> *const TrapFrame {
     CURRENT_TRAP_FRAME.load(core::sync::atomic::Ordering::Relaxed) as *const TrapFrame
 }
 
+/// 内核态访问用户地址时的缺页
+///
+/// 系统调用直接写入用户缓冲区时可能碰到写时复制页或尚未分配的页，
+/// 修复后重新执行出错的指令；无法修复时跳过该指令
+unsafe fn kernel_user_page_fault(frame: *mut TrapFrame, stval: u64, access: u32) {
+    const USER_SPACE_END: u64 = 0x8000_0000;
+
+    let fixed = stval < USER_SPACE_END
+        && crate::sched::current().is_some_and(|current| {
+            crate::arch::riscv64::fault::do_kernel_user_fault(current, stval, access)
+        });
+    if !fixed {
+        (*frame).sepc += 4;
+    }
+}
+
+/// 用户态缺页
+///
+/// 修复成功时直接返回，重新执行出错的指令；信号有处理函数时由 trap 返回路径
+/// （`deliver_signals`）进入处理函数；
+/// 信号致命时以该信号终止任务（wait 状态的低 7 位为终止信号）
+unsafe fn user_page_fault(frame: *mut TrapFrame, stval: u64, access: u32) {
+    let Some(current) = crate::sched::current() else {
+        return;
+    };
+    let current: *mut crate::process::task::Task = current;
+
+    if let Some(sig) = crate::arch::riscv64::fault::do_user_page_fault(current, stval, access) {
+        crate::println!("trap: PID {} killed by signal {} at {:#x}, sepc={:#x}",
+            (*current).pid(), sig, stval, (*frame).sepc);
+        CURRENT_TRAP_FRAME.store(0, core::sync::atomic::Ordering::Relaxed);
+        crate::sched::do_exit(sig);
+    }
+}
+
+/// TrapFrame 之前 8 字节处保存的用户 sp，trap 返回时从这里恢复
+unsafe fn user_sp_slot(frame: *mut TrapFrame) -> *mut u64 {
+    (frame as *mut u8).offset(-8) as *mut u64
+}
+
+/// 信号帧 uc_mcontext 中保存 ra、t0-t6 的起始槽位
+///
+/// `setup_frame` 只保存 a0-a7（x0-x7 槽位），槽位 8-18 在 RISC-V 上未使用
+const SIGFRAME_CALLER_SAVED: usize = 8;
+
+/// 返回用户态前投递一个待处理信号
+///
+/// 用户寄存器先同步到任务的 CpuContext，由 `signal::do_signal_task` 选择信号并调用
+/// `setup_frame` 保存上下文，再把处理函数入口、参数和新的用户栈写回 TrapFrame。
+/// 被打断代码的 ra 和 t0-t6 也存入信号帧，rt_sigreturn 时一起恢复；
+/// s 寄存器由处理函数按调用约定保存。处理函数的 ra 指向信号返回
+/// trampoline，返回时执行 rt_sigreturn（对应 Linux riscv `setup_rt_frame()`）
+///
+/// 系统调用的重启决策必须在此之前完成，信号帧保存的是回退后的 PC
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务，`frame` 必须是从用户态进入的 TrapFrame
+pub unsafe fn deliver_signals(task: *mut crate::process::task::Task, frame: *mut TrapFrame) {
+    let user_sp = user_sp_slot(frame);
+    let ctx = (*task).context_mut();
+    ctx.x0 = (*frame).a0;
+    ctx.x1 = (*frame).a1;
+    ctx.x2 = (*frame).a2;
+    ctx.x3 = (*frame).a3;
+    ctx.x4 = (*frame).a4;
+    ctx.x5 = (*frame).a5;
+    ctx.x6 = (*frame).a6;
+    ctx.x7 = (*frame).a7;
+    ctx.pc = (*frame).sepc;
+    ctx.user_sp = *user_sp;
+
+    if !crate::signal::do_signal_task(task) {
+        return;
+    }
+
+    if let Some(sigframe) = (*task).sigframe.as_mut() {
+        sigframe.uc.uc_mcontext[SIGFRAME_CALLER_SAVED..SIGFRAME_CALLER_SAVED + 8].copy_from_slice(&[
+            (*frame).ra, (*frame).t0, (*frame).t1, (*frame).t2,
+            (*frame).t3, (*frame).t4, (*frame).t5, (*frame).t6,
+        ]);
+    }
+
+    let ctx = (*task).context();
+    (*frame).a0 = ctx.x0;
+    (*frame).a1 = ctx.x1;
+    (*frame).a2 = ctx.x2;
+    (*frame).ra = crate::arch::riscv64::mm::SIGRETURN_TRAMPOLINE;
+    (*frame).sepc = ctx.pc;
+    *user_sp = ctx.user_sp;
+}
+
+/// rt_sigreturn：从信号帧恢复被信号打断时的用户寄存器
+///
+/// 恢复的 PC 直接写入 sepc（不再跳过 ecall），a0 为被打断时的值。
+/// 没有信号帧时返回 false
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务，`frame` 必须是从用户态进入的 TrapFrame
+pub unsafe fn restore_signal_frame(task: *mut crate::process::task::Task, frame: *mut TrapFrame) -> bool {
+    let Some(saved) = (*task).sigframe else {
+        return false;
+    };
+    if !crate::signal::restore_sigcontext(task, (*task).sigframe_addr) {
+        return false;
+    }
+
+    let ctx = (*task).context();
+    (*frame).a0 = ctx.x0;
+    (*frame).a1 = ctx.x1;
+    (*frame).a2 = ctx.x2;
+    (*frame).a3 = ctx.x3;
+    (*frame).a4 = ctx.x4;
+    (*frame).a5 = ctx.x5;
+    (*frame).a6 = ctx.x6;
+    (*frame).a7 = ctx.x7;
+    (*frame).sepc = ctx.pc;
+    *user_sp_slot(frame) = ctx.user_sp;
+
+    let regs = &saved.uc.uc_mcontext[SIGFRAME_CALLER_SAVED..SIGFRAME_CALLER_SAVED + 8];
+    (*frame).ra = regs[0];
+    (*frame).t0 = regs[1];
+    (*frame).t1 = regs[2];
+    (*frame).t2 = regs[3];
+    (*frame).t3 = regs[4];
+    (*frame).t4 = regs[5];
+    (*frame).t5 = regs[6];
+    (*frame).t6 = regs[7];
+    true
+}
+
 #[no_mangle]
 pub extern "C" fn trap_handler(frame: *mut TrapFrame) {
     unsafe {
@@ -246,6 +391,9 @@ pub extern "C" fn trap_handler(frame: *mut TrapFrame) {
 
         let exception = ExceptionCause::from_scause(scause);
 
+        // SPP bit (8): 0 = from U-mode, 1 = from S-mode
+        let from_user = (*frame).sstatus & 0x100 == 0;
+
         // 调试输出（可选）
         // if !matches!(exception, ExceptionCause::SupervisorTimerInterrupt) {
         //     crate::println!("TRAP: {:?} sepc={:#x} stval={:#x}", exception, (*frame).sepc, stval);
@@ -309,8 +457,7 @@ pub extern "C" fn trap_handler(frame: *mut TrapFrame) {
                         }
                         10 => {
                             // UART 中断（ns16550a）- QEMU RISC-V virt 使用 IRQ 10
-                            // TODO: 实现 UART 输入处理
-                            // crate::println!("IRQ: UART interrupt (IRQ 10)");
+                            crate::fs::tty::console_interrupt();
                         }
                         11..=13 => {
                             // IPI 中断（核间中断）
@@ -331,6 +478,17 @@ pub extern "C" fn trap_handler(frame: *mut TrapFrame) {
             ExceptionCause::EnvironmentCallFromSMode => {
                 // Supervisor-mode ecall - 不应该发生
             }
+            ExceptionCause::EnvironmentCallFromUMode
+                if (*frame).a7 == crate::arch::riscv64::syscall::SyscallNo::RtSigreturn as u64 =>
+            {
+                // rt_sigreturn 需要恢复全部用户寄存器，不经过 SyscallFrame
+                let restored = crate::sched::current()
+                    .is_some_and(|current| restore_signal_frame(current, frame));
+                if !restored {
+                    (*frame).a0 = -14_i64 as u64;  // EFAULT
+                    (*frame).sepc += 4;
+                }
+            }
             ExceptionCause::EnvironmentCallFromUMode => {
                 // 来自用户模式的系统调用
 
@@ -388,8 +546,8 @@ pub extern "C" fn trap_handler(frame: *mut TrapFrame) {
                 (*frame).a4 = syscall_frame.a4;
                 (*frame).a5 = syscall_frame.a5;
 
-                // 跳过 ecall 指令
-                (*frame).sepc += 4;
+                // 跳过 ecall 指令（需要重启的系统调用已把 PC 回退了 4）
+                (*frame).sepc = syscall_frame.pc.wrapping_add(4);
             }
             ExceptionCause::IllegalInstruction => {
                 // 静默处理非法指令
@@ -425,8 +583,10 @@ pub extern "C" fn trap_handler(frame: *mut TrapFrame) {
             ExceptionCause::StoreAMOAccessFault => {
                 // SPP bit (8): 0 = from U-mode, 1 = from S-mode
                 let is_user = (*frame).sstatus & 0x100 == 0;
-                crate::println!("trap: Store/AMO access fault at sepc={:#x}, addr={:#x} ({}mode)",
-                    (*frame).sepc, stval, if is_user { "user " } else { "kernel " });
+                // 用物理地址登记表标出出错地址所在的区域（设备寄存器、帧缓冲区等）
+                let region = crate::mm::lookup_region(stval as usize).map_or("unregistered", |r| r.name);
+                crate::println!("trap: Store/AMO access fault at sepc={:#x}, addr={:#x} [{}] ({}mode)",
+                    (*frame).sepc, stval, region, if is_user { "user " } else { "kernel " });
                 (*frame).sepc += 4; // 跳过错误指令
             }
             ExceptionCause::InstructionPageFault => {
@@ -434,147 +594,31 @@ pub extern "C" fn trap_handler(frame: *mut TrapFrame) {
                 let is_user = (*frame).sstatus & 0x100 == 0;
 
                 if is_user {
-                    if let Some(current) = crate::sched::current() {
-                        if let Some(addr_space) = current.address_space() {
-                            use crate::arch::riscv64::mm::{
-                                VirtAddr, handle_mm_fault, handle_cow_fault,
-                                FaultFlags, MmFaultResult
-                            };
-
-                            let fault_addr = VirtAddr::new(stval);
-                            let flags = FaultFlags::EXEC | FaultFlags::USER;
-
-                            match handle_mm_fault(&addr_space, fault_addr, flags) {
-                                MmFaultResult::Handled => {
-                                    // 页面已映射，重新执行指令
-                                    return;
-                                }
-                                MmFaultResult::CowPending => {
-                                    // COW 不适用于执行错误
-                                }
-                                MmFaultResult::AlreadyMapped => {
-                                    // 已映射但可能是权限问题
-                                }
-                                MmFaultResult::Segfault => {
-                                    crate::println!("trap: Segfault at {:#x} (exec)", stval);
-                                }
-                                MmFaultResult::PermissionDenied => {
-                                    crate::println!("trap: Permission denied at {:#x} (exec)", stval);
-                                }
-                                MmFaultResult::OutOfMemory => {
-                                    crate::println!("trap: Out of memory at {:#x} (exec)", stval);
-                                }
-                            }
-                        }
-                    }
+                    user_page_fault(frame, stval, crate::arch::riscv64::mm::FaultFlags::EXEC);
+                } else {
+                    // 无法处理，跳过指令
+                    (*frame).sepc += 4;
                 }
-
-                // 无法处理，跳过指令
-                (*frame).sepc += 4;
             }
             ExceptionCause::LoadPageFault => {
                 // SPP bit (8): 0 = from U-mode, 1 = from S-mode
                 let is_user = (*frame).sstatus & 0x100 == 0;
 
                 if is_user {
-                    if let Some(current) = crate::sched::current() {
-                        if let Some(addr_space) = current.address_space() {
-                            use crate::arch::riscv64::mm::{
-                                VirtAddr, handle_mm_fault,
-                                FaultFlags, MmFaultResult
-                            };
-
-                            let fault_addr = VirtAddr::new(stval);
-                            let flags = FaultFlags::READ | FaultFlags::USER;
-
-                            match handle_mm_fault(&addr_space, fault_addr, flags) {
-                                MmFaultResult::Handled => {
-                                    // 页面已映射，重新执行指令
-                                    return;
-                                }
-                                MmFaultResult::AlreadyMapped => {
-                                    // 已映射，可能需要其他处理
-                                }
-                                MmFaultResult::Segfault => {
-                                    crate::println!("trap: Segfault at {:#x} (read), sepc={:#x}", stval, (*frame).sepc);
-                                }
-                                MmFaultResult::PermissionDenied => {
-                                    crate::println!("trap: Permission denied at {:#x} (read)", stval);
-                                }
-                                MmFaultResult::OutOfMemory => {
-                                    crate::println!("trap: Out of memory at {:#x} (read)", stval);
-                                }
-                                MmFaultResult::CowPending => {
-                                    // 读操作不需要 COW
-                                }
-                            }
-                        }
-                    }
+                    user_page_fault(frame, stval, crate::arch::riscv64::mm::FaultFlags::READ);
+                } else {
+                    kernel_user_page_fault(frame, stval, crate::arch::riscv64::mm::FaultFlags::READ);
                 }
-
-                // 无法处理，跳过指令
-                (*frame).sepc += 4;
             }
             ExceptionCause::StorePageFault => {
                 // SPP bit (8): 0 = from U-mode, 1 = from S-mode
                 let is_user = (*frame).sstatus & 0x100 == 0;
 
                 if is_user {
-                    if let Some(current) = crate::sched::current() {
-                        if let Some(addr_space) = current.address_space() {
-                            use crate::arch::riscv64::mm::{
-                                VirtAddr, handle_mm_fault, handle_cow_fault,
-                                FaultFlags, MmFaultResult
-                            };
-
-                            let fault_addr = VirtAddr::new(stval);
-                            let flags = FaultFlags::WRITE | FaultFlags::USER;
-
-                            // 首先尝试 handle_mm_fault
-                            match handle_mm_fault(&addr_space, fault_addr, flags) {
-                                MmFaultResult::Handled => {
-                                    // 页面已映射，重新执行指令
-                                    return;
-                                }
-                                MmFaultResult::CowPending => {
-                                    // COW 页面，尝试写时复制
-                                    match handle_cow_fault(addr_space.root_ppn(), fault_addr) {
-                                        Some(()) => {
-                                            // COW 成功，重新执行指令
-                                            return;
-                                        }
-                                        None => {
-                                            crate::println!("trap: COW failed at {:#x}", stval);
-                                        }
-                                    }
-                                }
-                                MmFaultResult::AlreadyMapped => {
-                                    // 已映射但可能不是 COW 页
-                                    crate::println!("trap: Store fault on non-COW page at {:#x}", stval);
-                                }
-                                MmFaultResult::Segfault => {
-                                    crate::println!("trap: Segfault at {:#x} (write)", stval);
-                                }
-                                MmFaultResult::PermissionDenied => {
-                                    crate::println!("trap: Permission denied at {:#x} (write)", stval);
-                                }
-                                MmFaultResult::OutOfMemory => {
-                                    crate::println!("trap: Out of memory at {:#x} (write)", stval);
-                                }
-                            }
-                        }
-                    }
-
-                    // 终止进程而不是跳过指令
-                    crate::println!("trap: Terminating process due to unhandled page fault");
-                    if let Some(current) = crate::sched::current() {
-                        current.set_state(crate::process::task::TaskState::Zombie);
-                        crate::sched::schedule();
-                    }
+                    user_page_fault(frame, stval, crate::arch::riscv64::mm::FaultFlags::WRITE);
+                } else {
+                    kernel_user_page_fault(frame, stval, crate::arch::riscv64::mm::FaultFlags::WRITE);
                 }
-
-                // 无法处理，跳过指令
-                (*frame).sepc += 4;
             }
             _ => {
                 crate::println!("trap: Unknown exception: scause={:#x}, sepc={:#x}, stval={:#x}",
@@ -582,6 +626,13 @@ pub extern "C" fn trap_handler(frame: *mut TrapFrame) {
             }
         }
 
+        // 返回用户态前投递待处理信号
+        if from_user {
+            if let Some(current) = crate::sched::current() {
+                deliver_signals(current, frame);
+            }
+        }
+
         // 清除当前 TrapFrame 指针
         CURRENT_TRAP_FRAME.store(0, core::sync::atomic::Ordering::Relaxed);
     }
@@ -47,6 +47,9 @@ pub const ENABLE_GIC: bool = false;
 /// 是否启用VirtIO网络设备探测
 pub const ENABLE_VIRTIO_NET_PROBE: bool = true;
 
+/// virtio-mmio 槽位数量
+pub const VIRTIO_MMIO_SLOTS: usize = 8;
+
 // ============================================================
 // SMP 配置
 // ============================================================
@@ -70,6 +73,16 @@ pub const DEFAULT_TIME_SLICE_MS: u32 = 100;
 /// 时间片滴答数
 pub const TIME_SLICE_TICKS: u32 = 10;
 
+// ============================================================
+// 定时器配置
+// ============================================================
+
+/// 时钟中断频率 (HZ)
+pub const TIMER_HZ: u64 = 100;
+
+/// 是否启用空闲无滴答 (tickless idle)
+pub const ENABLE_TICKLESS_IDLE: bool = false;
+
 // ============================================================
 // 内存管理配置
 // ============================================================
@@ -4,6 +4,7 @@
 //!
 use core::fmt;
 use core::arch::asm;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use spin::Mutex;
 
 // UART 基础地址 - 根据架构选择
@@ -53,9 +54,31 @@ impl Uart {
 /// 全局 UART 控制台（使用自旋锁保护，SMP 安全）
 static UART: Mutex<Uart> = Mutex::new(Uart::new(UART0_BASE));
 
-/// 初始化控制台（QEMU virt 不需要初始化）
+/// 当前 UART 基地址（供不获取锁的输出路径使用）
+static UART_BASE_ADDR: AtomicUsize = AtomicUsize::new(UART0_BASE);
+
+/// 切换 UART 基地址（解析设备树之后调用）
+pub fn set_uart_base(base: usize) {
+    UART_BASE_ADDR.store(base, Ordering::Release);
+    UART.lock().base = base;
+    enable_rx_interrupt();
+}
+
+/// 初始化控制台
+///
+/// QEMU virt 的 UART 已经预初始化，只需打开接收中断
 pub fn init() {
-    // QEMU virt 的 UART 已经预初始化，无需操作
+    enable_rx_interrupt();
+}
+
+/// 打开 UART 接收中断（IER.ERBFI），输入到达时由 `fs::tty::console_interrupt` 处理
+fn enable_rx_interrupt() {
+    #[cfg(feature = "riscv64")]
+    unsafe {
+        const UART_IER: usize = 1;  // Interrupt Enable Register
+        const IER_ERBFI: u8 = 0x01; // 接收数据可用中断
+        core::ptr::write_volatile((UART_BASE_ADDR.load(Ordering::Acquire) + UART_IER) as *mut u8, IER_ERBFI);
+    }
 }
 
 /// 写入单个字符（SMP 安全）
@@ -85,7 +108,7 @@ pub fn lock() -> spin::MutexGuard<'static, Uart> {
 /// 仅在中断处理程序中使用
 /// 注意：如果多个CPU同时调用此函数，输出可能交错
 pub fn putchar_no_lock(c: u8) {
-    let uart = Uart::new(UART0_BASE);
+    let uart = Uart::new(UART_BASE_ADDR.load(Ordering::Acquire));
     uart.putc(c);
 }
 
@@ -93,25 +116,50 @@ pub fn putchar_no_lock(c: u8) {
 ///
 /// 仅在中断处理程序中使用
 pub fn puts_no_lock(s: &str) {
-    let uart = Uart::new(UART0_BASE);
+    let uart = Uart::new(UART_BASE_ADDR.load(Ordering::Acquire));
     for b in s.bytes() {
         uart.putc(b);
     }
 }
 
+/// 接收缓冲区是否有数据（不读取）
+pub fn has_input() -> bool {
+    #[cfg(feature = "riscv64")]
+    {
+        let uart_base: usize = UART_BASE_ADDR.load(Ordering::Acquire);
+        const UART_LSR: usize = 5;  // Line Status Register
+
+        unsafe {
+            let lsr: u8;
+            asm!(
+                "lb t0, 0(a0)",
+                in("a0") uart_base + UART_LSR,
+                out("t0") lsr,
+                options(nostack)
+            );
+            lsr & 1 == 1
+        }
+    }
+
+    #[cfg(not(feature = "riscv64"))]
+    {
+        false
+    }
+}
+
 /// 读取单个字符（非阻塞）
 /// 如果有数据可用则返回 Some(c)，否则返回 None
 ///
-/// 在 canonical 模式下，需要回显字符
+/// 返回原始字节，回显和换行转换由行规程 (`fs::tty`) 处理
 pub fn getchar() -> Option<u8> {
     #[cfg(feature = "riscv64")]
     {
-        const UART_BASE: usize = 0x1000_0000;
+        let uart_base: usize = UART_BASE_ADDR.load(Ordering::Acquire);
         const UART_LSR: usize = 5;  // Line Status Register
 
         unsafe {
             // 检查 LSR 的 bit 0 (DR - Data Ready)
-            let lsr_addr = UART_BASE + UART_LSR;
+            let lsr_addr = uart_base + UART_LSR;
             let lsr: u8;
             asm!(
                 "lb t0, 0(a0)",
@@ -125,27 +173,11 @@ pub fn getchar() -> Option<u8> {
                 let c: u8;
                 asm!(
                     "lb t0, 0(a0)",
-                    in("a0") UART_BASE,
+                    in("a0") uart_base,
                     out("t0") c,
                     options(nostack)
                 );
 
-                // 回显字符（终端需要）
-                if c == b'\n' || c == b'\r' {
-                    // 回车键：回显 \r\n，但返回 \n 给程序
-                    putchar(b'\r');
-                    putchar(b'\n');
-                    return Some(b'\n');
-                } else if c == 127 || c == 8 {
-                    // 退格/删除键
-                    putchar(8);      // backspace
-                    putchar(b' ');   // 空格覆盖
-                    putchar(8);      // 再退格
-                    return Some(c);  // 返回原字符让程序处理
-                } else {
-                    putchar(c);
-                }
-
                 Some(c)
             } else {
                 None
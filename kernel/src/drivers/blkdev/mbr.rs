@@ -0,0 +1,157 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! MBR 分区表 (block/partitions/msdos.c)
+//!
+//! 扇区 0 的布局：
+//! - 0x1BE 开始的 4 个 16 字节主分区表项
+//! - 0x1FE 处的签名 0x55 0xAA
+//!
+//! 每个非空主分区注册为一个子 `GenDisk`，读写时扇区号加上分区起始扇区后交给整个磁盘处理。
+//! 扩展分区和 GPT 保护分区不展开。
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+
+use super::{blkdev_read, register_disk, GenDisk};
+use crate::fs::devfs;
+
+/// 扇区大小
+pub const SECTOR_SIZE: usize = 512;
+
+/// 分区表在扇区 0 中的偏移
+const PARTITION_TABLE_OFFSET: usize = 0x1BE;
+
+/// 分区表项大小
+const PARTITION_ENTRY_SIZE: usize = 16;
+
+/// 主分区数量
+pub const MAX_PRIMARY: usize = 4;
+
+/// 签名偏移
+const SIGNATURE_OFFSET: usize = 0x1FE;
+
+/// 分区类型
+pub mod part_type {
+    /// 空表项
+    pub const EMPTY: u8 = 0x00;
+    /// DOS 扩展分区
+    pub const DOS_EXTENDED: u8 = 0x05;
+    /// Windows 扩展分区 (LBA)
+    pub const WIN98_EXTENDED: u8 = 0x0F;
+    /// Linux
+    pub const LINUX: u8 = 0x83;
+    /// Linux 扩展分区
+    pub const LINUX_EXTENDED: u8 = 0x85;
+    /// GPT 保护分区
+    pub const GPT_PROTECTIVE: u8 = 0xEE;
+}
+
+/// 一个主分区表项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MbrPartition {
+    /// 分区号（1-4）
+    pub partno: u32,
+    /// 分区类型
+    pub part_type: u8,
+    /// 活动分区标志
+    pub bootable: bool,
+    /// 起始扇区 (LBA)
+    pub start_lba: u32,
+    /// 扇区数
+    pub num_sectors: u32,
+}
+
+fn is_extended(part_type: u8) -> bool {
+    matches!(
+        part_type,
+        part_type::DOS_EXTENDED | part_type::WIN98_EXTENDED | part_type::LINUX_EXTENDED
+    )
+}
+
+/// 解析扇区 0 中的主分区表
+///
+/// 跳过空表项、扩展分区和 GPT 保护分区
+///
+/// # 返回
+/// 缓冲区不足一个扇区或签名不是 0x55AA 时返回错误
+pub fn parse_mbr(sector0: &[u8]) -> Result<Vec<MbrPartition>, &'static str> {
+    if sector0.len() < SECTOR_SIZE {
+        return Err("MBR sector too short");
+    }
+    if sector0[SIGNATURE_OFFSET] != 0x55 || sector0[SIGNATURE_OFFSET + 1] != 0xAA {
+        return Err("Invalid MBR signature");
+    }
+
+    let mut partitions = Vec::new();
+    for i in 0..MAX_PRIMARY {
+        let entry = &sector0[PARTITION_TABLE_OFFSET + i * PARTITION_ENTRY_SIZE..][..PARTITION_ENTRY_SIZE];
+        let part_type = entry[4];
+        let start_lba = u32::from_le_bytes([entry[8], entry[9], entry[10], entry[11]]);
+        let num_sectors = u32::from_le_bytes([entry[12], entry[13], entry[14], entry[15]]);
+
+        if part_type == part_type::EMPTY || num_sectors == 0 {
+            continue;
+        }
+        if is_extended(part_type) || part_type == part_type::GPT_PROTECTIVE {
+            continue;
+        }
+
+        partitions.push(MbrPartition {
+            partno: i as u32 + 1,
+            part_type,
+            bootable: entry[0] == 0x80,
+            start_lba,
+            num_sectors,
+        });
+    }
+
+    Ok(partitions)
+}
+
+/// 把分区限制在磁盘容量内
+///
+/// # 返回
+/// 分区起始扇区超出磁盘时返回 None；否则返回 (起始扇区, 扇区数)，长度被截断到磁盘末尾
+pub fn clamp_to_disk(part: &MbrPartition, capacity: u32) -> Option<(u64, u32)> {
+    if part.start_lba == 0 || part.start_lba >= capacity {
+        return None;
+    }
+    let max_len = capacity - part.start_lba;
+    Some((part.start_lba as u64, part.num_sectors.min(max_len)))
+}
+
+/// 读取 `disk` 的分区表，并把每个主分区注册为子设备 `/dev/<name><分区号>`
+///
+/// # 返回
+/// 注册的分区数；读取扇区 0 失败时返回错误码，没有 MBR 时返回 0
+pub fn add_partitions(disk: *const GenDisk, name: &str) -> Result<usize, i32> {
+    let mut sector0 = [0u8; SECTOR_SIZE];
+    blkdev_read(disk, 0, &mut sector0)?;
+
+    let partitions = match parse_mbr(&sector0) {
+        Ok(partitions) => partitions,
+        Err(_) => return Ok(0),
+    };
+
+    let capacity = unsafe { (*disk).get_capacity() };
+    let mut count = 0;
+    for part in partitions.iter() {
+        let Some((start, len)) = clamp_to_disk(part, capacity) else {
+            crate::println!("blkdev: partition {} starts beyond the end of the disk, skipped", part.partno);
+            continue;
+        };
+
+        let child = Box::new(unsafe { GenDisk::new_partition(disk, part.partno, start, len) });
+        let child_ptr = &*child as *const GenDisk;
+        if register_disk(child).is_ok() {
+            let _ = devfs::register_block_device(format!("{}{}", name, part.partno), child_ptr);
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
@@ -13,11 +13,15 @@
 //! - `struct bio`: I/O 描述符
 
 use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use spin::Mutex;
 use core::sync::atomic::{AtomicU32, Ordering};
 
+pub mod mbr;
+
 #[repr(C)]
 pub struct BlockDeviceOps {
     /// 打开块设备
@@ -60,6 +64,12 @@ pub struct GenDisk {
     pub private_data: Option<*mut u8>,
     /// 请求处理函数
     pub request_fn: Option<unsafe extern "C" fn(&mut Request)>,
+    /// 分区号（0 表示整个磁盘）
+    pub partno: u32,
+    /// 分区在整个磁盘上的起始扇区
+    pub start_sect: u64,
+    /// 分区所在的整个磁盘（整个磁盘为 None）
+    pub parent: Option<*const GenDisk>,
 }
 
 unsafe impl Send for GenDisk {}
@@ -84,9 +94,40 @@ impl GenDisk {
             ops,
             private_data: None,
             request_fn: None,
+            partno: 0,
+            start_sect: 0,
+            parent: None,
         }
     }
 
+    /// 创建 `disk` 上的分区
+    ///
+    /// # Safety
+    ///
+    /// `disk` 必须指向已注册的整个磁盘，且比分区活得更久
+    pub unsafe fn new_partition(disk: *const GenDisk, partno: u32, start_sect: u64, nr_sects: u32) -> Self {
+        let whole = &*disk;
+        Self {
+            name: whole.name,
+            major: whole.major,
+            first_minor: whole.first_minor + partno,
+            minors: 1,
+            capacity: AtomicU32::new(nr_sects),
+            block_size: whole.block_size,
+            ops: whole.ops,
+            private_data: None,
+            request_fn: None,
+            partno,
+            start_sect,
+            parent: Some(disk),
+        }
+    }
+
+    /// 是否是分区
+    pub fn is_partition(&self) -> bool {
+        self.parent.is_some()
+    }
+
     /// 设置容量
     pub fn set_capacity(&self, sectors: u32) {
         self.capacity.store(sectors, Ordering::Release);
@@ -155,11 +196,11 @@ impl BlockDeviceManager {
     pub fn register_disk(&self, disk: Box<GenDisk>) -> Result<(), &'static str> {
         let mut disks = self.disks.lock();
 
-        // 检查设备号是否已使用
+        // 检查设备号是否已使用（分区与整个磁盘共用主设备号）
         for d in disks.iter() {
             if let Some(ref gd) = d {
-                if gd.major == disk.major {
-                    return Err("Major number already in use");
+                if gd.major == disk.major && gd.first_minor == disk.first_minor {
+                    return Err("Device number already in use");
                 }
             }
         }
@@ -174,7 +215,22 @@ impl BlockDeviceManager {
 
         for d in disks.iter() {
             if let Some(ref gd) = d {
-                if gd.major == major {
+                if gd.major == major && !gd.is_partition() {
+                    return Some(gd.as_ref() as *const GenDisk);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// 查找分区
+    pub fn get_partition(&self, major: u32, partno: u32) -> Option<*const GenDisk> {
+        let disks = self.disks.lock();
+
+        for d in disks.iter() {
+            if let Some(ref gd) = d {
+                if gd.major == major && gd.partno == partno {
                     return Some(gd.as_ref() as *const GenDisk);
                 }
             }
@@ -184,9 +240,21 @@ impl BlockDeviceManager {
     }
 
     /// 处理 I/O 请求
+    ///
+    /// 分区上的请求先检查范围，再把扇区号加上分区起始扇区交给整个磁盘
     pub fn submit_request(&self, disk: *const GenDisk, req: &mut Request) -> i32 {
         unsafe {
-            let gd = &*disk;
+            let mut gd = &*disk;
+
+            if let Some(whole) = gd.parent {
+                let sectors = req.buffer.len().div_ceil(mbr::SECTOR_SIZE) as u64;
+                if req.sector + sectors > gd.get_capacity() as u64 {
+                    return -5;  // EIO
+                }
+                req.sector += gd.start_sect;
+                req.device = whole;
+                gd = &*whole;
+            }
 
             if let Some(request_fn) = gd.request_fn {
                 request_fn(req);
@@ -200,6 +268,19 @@ impl BlockDeviceManager {
 
 static BLOCK_MANAGER: BlockDeviceManager = BlockDeviceManager::new();
 
+/// 顺序读取时预读的块数（0 表示关闭预读）
+static READAHEAD_BLOCKS: AtomicU32 = AtomicU32::new(8);
+
+/// 设置顺序读取时预读的块数
+pub fn set_readahead(blocks: usize) {
+    READAHEAD_BLOCKS.store(blocks as u32, Ordering::Relaxed);
+}
+
+/// 顺序读取时预读的块数
+pub fn readahead() -> usize {
+    READAHEAD_BLOCKS.load(Ordering::Relaxed) as usize
+}
+
 pub fn register_disk(disk: Box<GenDisk>) -> Result<(), &'static str> {
     BLOCK_MANAGER.register_disk(disk)
 }
@@ -208,6 +289,32 @@ pub fn get_disk(major: u32) -> Option<*const GenDisk> {
     BLOCK_MANAGER.get_disk(major)
 }
 
+/// 已分配设备名的磁盘数（vda、vdb ...）
+static DISK_NAMES: AtomicU32 = AtomicU32::new(0);
+
+/// 给整个磁盘分配设备名并登记 `/dev/<name>`，再把 MBR 主分区登记为 `/dev/<name><分区号>`
+///
+/// 磁盘按调用顺序命名为 vda、vdb ...；`disk` 必须在系统运行期间一直有效
+///
+/// # 返回
+/// 分配的磁盘名
+pub fn add_disk(disk: *const GenDisk) -> String {
+    let index = DISK_NAMES.fetch_add(1, Ordering::Relaxed);
+    let name = format!("vd{}", (b'a' + (index % 26) as u8) as char);
+    let _ = crate::fs::devfs::register_block_device(name.clone(), disk);
+
+    match mbr::add_partitions(disk, &name) {
+        Ok(0) => {}
+        Ok(n) => crate::println!("blkdev: {}: {} partition(s) found", name, n),
+        Err(e) => crate::println!("blkdev: {}: failed to read partition table: {}", name, e),
+    }
+    name
+}
+
+pub fn get_partition(major: u32, partno: u32) -> Option<*const GenDisk> {
+    BLOCK_MANAGER.get_partition(major, partno)
+}
+
 pub fn submit_request(disk: *const GenDisk, req: &mut Request) -> i32 {
     BLOCK_MANAGER.submit_request(disk, req)
 }
@@ -255,3 +362,21 @@ pub fn blkdev_write(disk: *const GenDisk, sector: u64, buf: &[u8]) -> Result<usi
         Ok(buf.len())
     }
 }
+
+/// 刷新 `disk` 的写缓存（分区的请求交给整个磁盘）
+pub fn blkdev_flush(disk: *const GenDisk) -> Result<(), i32> {
+    let mut req = Request {
+        cmd_type: ReqCmd::Flush,
+        sector: 0,
+        buffer: Vec::new(),
+        device: disk,
+        end_io: None,
+    };
+
+    let ret = submit_request(disk, &mut req);
+    if ret < 0 {
+        return Err(ret);
+    }
+
+    Ok(())
+}
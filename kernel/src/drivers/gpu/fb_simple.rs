@@ -8,13 +8,15 @@
 //!
 //! 直接使用 QEMU 的 framebuffer MMIO 区域
 //!
-//! QEMU RISC-V virt 平台默认 framebuffer 配置：
-//! - 地址：0x10000000 (但通常通过设备树配置）
-//! - 尺寸：1024x768 (默认)
-//! - 格式：xRGB 32bpp
+//! 优先使用设备树中 `simple-framebuffer` 节点的 `reg`、`width`、`height`、
+//! `stride` 和 `format`；没有该节点时使用默认配置：
+//! - 地址：0x10000000
+//! - 尺寸：1024x768
+//! - 格式：xRGB 32bpp（也支持 RGB565 16bpp）
 
 use crate::println;
-use super::framebuffer::{FrameBuffer, FrameBufferInfo};
+use crate::fdt::DeviceNode;
+use super::framebuffer::{FrameBuffer, FrameBufferInfo, PixelFormat};
 
 /// QEMU RISC-V virt 平台的默认 framebuffer 地址
 const FB_DEFAULT_ADDR: u64 = 0x10000000;
@@ -22,6 +24,8 @@ const FB_DEFAULT_ADDR: u64 = 0x10000000;
 /// 默认 framebuffer 尺寸
 const FB_DEFAULT_WIDTH: u32 = 1024;
 const FB_DEFAULT_HEIGHT: u32 = 768;
+/// 默认每像素位数
+const FB_DEFAULT_BPP: u32 = 32;
 
 /// 简化的 Framebuffer 信息
 pub struct SimpleFrameBufferInfo {
@@ -35,24 +39,70 @@ pub struct SimpleFrameBufferInfo {
     pub height: u32,
     /// 每行字节数
     pub stride: u32,
+    /// 像素格式（由 bits_per_pixel 识别）
+    pub format: PixelFormat,
 }
 
 /// 探测并初始化简化的 framebuffer
 pub fn probe_simple_framebuffer() -> Option<SimpleFrameBufferInfo> {
-    // 暂时使用默认配置
-    // TODO: 从设备树读取实际配置
-    let fb_addr = FB_DEFAULT_ADDR;
-    let fb_width = FB_DEFAULT_WIDTH;
-    let fb_height = FB_DEFAULT_HEIGHT;
-    let fb_stride = fb_width * 4; // 32bpp
-    let fb_size = fb_stride * fb_height;
+    match crate::fdt::simple_framebuffer() {
+        Some(node) => simple_framebuffer_from_node(&node),
+        None => simple_framebuffer_info(FB_DEFAULT_ADDR, FB_DEFAULT_WIDTH, FB_DEFAULT_HEIGHT, FB_DEFAULT_BPP),
+    }
+}
+
+/// simple-framebuffer 绑定中 `format` 字符串对应的每像素位数
+///
+/// 只列出 `PixelFormat` 支持的格式
+fn format_bpp(format: &str) -> Option<u32> {
+    match format {
+        "a8r8g8b8" | "x8r8g8b8" => Some(32),
+        "r5g6b5" => Some(16),
+        _ => None,
+    }
+}
+
+/// 按设备树 `simple-framebuffer` 节点计算 framebuffer 布局
+///
+/// 缺少必需属性、格式不支持或 `stride` 小于一行像素时返回 None
+pub fn simple_framebuffer_from_node(node: &DeviceNode) -> Option<SimpleFrameBufferInfo> {
+    let addr = node.base()?;
+    let (width, height) = (node.prop_u32("width")?, node.prop_u32("height")?);
+    let format = node.prop_str("format")?;
+    let Some(bits_per_pixel) = format_bpp(format) else {
+        println!("fb_simple: unsupported format {}", format);
+        return None;
+    };
+
+    let mut info = simple_framebuffer_info(addr, width, height, bits_per_pixel)?;
+    if let Some(stride) = node.prop_u32("stride") {
+        if stride < info.stride {
+            println!("fb_simple: stride {} shorter than a {}-pixel line", stride, width);
+            return None;
+        }
+        info.stride = stride;
+        info.size = stride * height;
+    }
+    Some(info)
+}
+
+/// 按分辨率和每像素位数计算 framebuffer 布局
+///
+/// 不支持的 `bits_per_pixel` 返回 None
+pub fn simple_framebuffer_info(addr: u64, width: u32, height: u32, bits_per_pixel: u32) -> Option<SimpleFrameBufferInfo> {
+    let Some(format) = PixelFormat::from_bpp(bits_per_pixel) else {
+        println!("fb_simple: unsupported bits_per_pixel {}", bits_per_pixel);
+        return None;
+    };
+    let stride = width * format.bytes_per_pixel();
 
     Some(SimpleFrameBufferInfo {
-        addr: fb_addr,
-        size: fb_size,
-        width: fb_width,
-        height: fb_height,
-        stride: fb_stride,
+        addr,
+        size: stride * height,
+        width,
+        height,
+        stride,
+        format,
     })
 }
 
@@ -66,7 +116,7 @@ pub fn create_framebuffer(info: &SimpleFrameBufferInfo) -> Option<FrameBuffer> {
             width: info.width,
             height: info.height,
             stride: info.stride,
-            format: 1, // xRGB
+            format: info.format,
         });
         Some(fb)
     }
@@ -8,7 +8,7 @@
 //!
 //! 实现 兼容的 framebuffer 设备接口
 
-use super::FrameBufferInfo;
+use super::{FrameBufferInfo, PixelFormat};
 
 /// ioctl 命令码
 /// 获取可变屏幕信息
@@ -198,13 +198,24 @@ pub fn create_var_screeninfo(info: &FrameBufferInfo) -> FbVarScreeninfo {
     var.yres = info.height;
     var.xres_virtual = info.width;
     var.yres_virtual = info.height;
-    var.bits_per_pixel = 32;
+    var.bits_per_pixel = info.format.bits_per_pixel();
 
-    // xRGB 格式 (little-endian)
-    var.red = FbBitfield { offset: 16, length: 8, msb_right: 0 };
-    var.green = FbBitfield { offset: 8, length: 8, msb_right: 0 };
-    var.blue = FbBitfield { offset: 0, length: 8, msb_right: 0 };
-    var.transp = FbBitfield { offset: 24, length: 8, msb_right: 0 };
+    match info.format {
+        // xRGB 格式 (little-endian)
+        PixelFormat::Xrgb8888 => {
+            var.red = FbBitfield { offset: 16, length: 8, msb_right: 0 };
+            var.green = FbBitfield { offset: 8, length: 8, msb_right: 0 };
+            var.blue = FbBitfield { offset: 0, length: 8, msb_right: 0 };
+            var.transp = FbBitfield { offset: 24, length: 8, msb_right: 0 };
+        }
+        // RGB565，无 alpha
+        PixelFormat::Rgb565 => {
+            var.red = FbBitfield { offset: 11, length: 5, msb_right: 0 };
+            var.green = FbBitfield { offset: 5, length: 6, msb_right: 0 };
+            var.blue = FbBitfield { offset: 0, length: 5, msb_right: 0 };
+            var.transp = FbBitfield::default();
+        }
+    }
 
     var
 }
@@ -238,3 +249,154 @@ pub fn fbdev_ioctl(cmd: u32, arg: usize) -> i64 {
         _ => -25, // ENOTTY: 不支持的 ioctl 命令
     }
 }
+
+/// framebuffer 设备文件的 ioctl，见 `fbdev_ioctl`
+pub fn fbdev_file_ioctl(_file: &crate::fs::File, cmd: u32, arg: usize) -> isize {
+    fbdev_ioctl(cmd, arg) as isize
+}
+
+/// 读取 framebuffer 内容，文件位置是字节偏移，到达末尾时返回 0
+pub fn fbdev_file_read(file: &crate::fs::File, buf: &mut [u8]) -> isize {
+    let info = match super::get_framebuffer_info() {
+        Some(info) => info,
+        None => return -6, // ENXIO
+    };
+
+    let size = info.size as u64;
+    let pos = file.get_pos();
+    if pos >= size {
+        return 0;
+    }
+    let count = buf.len().min((size - pos) as usize);
+    unsafe {
+        // framebuffer 位于内核恒等映射的内存中
+        core::ptr::copy_nonoverlapping((info.addr + pos) as *const u8, buf.as_mut_ptr(), count);
+    }
+    file.set_pos(pos + count as u64);
+    count as isize
+}
+
+/// 写入 framebuffer 内容，超出末尾的部分被截断
+///
+/// 与 Linux fb_write 相同：起始位置超出末尾返回 -EFBIG，没有剩余空间返回 -ENOSPC
+pub fn fbdev_file_write(file: &crate::fs::File, buf: &[u8]) -> isize {
+    let info = match super::get_framebuffer_info() {
+        Some(info) => info,
+        None => return -6, // ENXIO
+    };
+
+    let size = info.size as u64;
+    let pos = file.get_pos();
+    if pos > size {
+        return -27; // EFBIG
+    }
+    let count = buf.len().min((size - pos) as usize);
+    if count == 0 && !buf.is_empty() {
+        return -28; // ENOSPC
+    }
+    unsafe {
+        core::ptr::copy_nonoverlapping(buf.as_ptr(), (info.addr + pos) as *mut u8, count);
+    }
+    file.set_pos(pos + count as u64);
+    count as isize
+}
+
+/// 设置文件位置，新位置必须位于 [0, framebuffer 大小] 范围内
+pub fn fbdev_file_lseek(file: &crate::fs::File, offset: isize, whence: i32) -> isize {
+    let size = match super::get_framebuffer_info() {
+        Some(info) => info.size as isize,
+        None => return -6, // ENXIO
+    };
+
+    let new_pos = match whence {
+        0 => offset,                           // SEEK_SET
+        1 => file.get_pos() as isize + offset, // SEEK_CUR
+        2 => size + offset,                    // SEEK_END
+        _ => return -22,                       // EINVAL
+    };
+    if new_pos < 0 || new_pos > size {
+        return -22; // EINVAL
+    }
+
+    file.set_pos(new_pos as u64);
+    new_pos
+}
+
+/// 把 framebuffer 从 `offset` 开始的 `length` 字节映射到 `addr_space`
+///
+/// 映射的是 framebuffer 的物理内存本身，写入立即反映在屏幕内容中。
+/// 范围超出 framebuffer（按页向上取整）时返回 -EINVAL。
+///
+/// # 返回
+/// 成功返回映射的起始地址
+pub fn fbdev_mmap(
+    addr_space: &crate::arch::riscv64::mm::AddressSpace,
+    addr: usize,
+    length: usize,
+    prot: u32,
+    map_flags: u32,
+    offset: usize,
+) -> Result<usize, i32> {
+    use crate::arch::riscv64::mm::{map, prot as mprot};
+    use crate::mm::page::{VirtAddr, PAGE_SIZE};
+    use crate::mm::pagemap::Perm;
+    use crate::mm::vma::VmaFlags;
+
+    let info = super::get_framebuffer_info().ok_or(-6)?; // ENXIO
+
+    let fb_len = (info.size as usize + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+    let map_len = length.checked_add(PAGE_SIZE - 1).ok_or(-22)? & !(PAGE_SIZE - 1);
+    if length == 0 || offset % PAGE_SIZE != 0 || offset.checked_add(map_len).map_or(true, |end| end > fb_len) {
+        return Err(-22); // EINVAL
+    }
+
+    let mut flags = VmaFlags::new();
+    flags.insert(VmaFlags::READ | VmaFlags::IO);
+    flags.insert(if map_flags & map::MAP_SHARED != 0 { VmaFlags::SHARED } else { VmaFlags::PRIVATE });
+    let perm = if prot & mprot::PROT_WRITE != 0 {
+        flags.insert(VmaFlags::WRITE);
+        Perm::ReadWrite
+    } else if prot & mprot::PROT_READ != 0 {
+        Perm::Read
+    } else {
+        Perm::None
+    };
+
+    addr_space
+        .mmap_device(VirtAddr::new(addr), length, info.addr as usize + offset, flags, perm, map_flags)
+        .map(|start| start.as_usize())
+        .map_err(|_| -12) // ENOMEM
+}
+
+/// framebuffer 设备文件的 mmap，映射到当前进程，见 `fbdev_mmap`
+pub fn fbdev_file_mmap(
+    _file: &crate::fs::File,
+    addr: usize,
+    length: usize,
+    prot: u32,
+    flags: u32,
+    offset: usize,
+) -> isize {
+    let task = match crate::sched::current() {
+        Some(task) => task,
+        None => return -12, // ENOMEM
+    };
+    match task.address_space() {
+        Some(addr_space) => match fbdev_mmap(addr_space, addr, length, prot, flags, offset) {
+            Ok(start) => start as isize,
+            Err(e) => e as isize,
+        },
+        None => -12, // ENOMEM
+    }
+}
+
+/// framebuffer 设备文件操作 (/dev/fb0)
+pub static FBDEV_FILE_OPS: crate::fs::FileOps = crate::fs::FileOps {
+    read: Some(fbdev_file_read),
+    write: Some(fbdev_file_write),
+    lseek: Some(fbdev_file_lseek),
+    close: None,
+    poll: None,
+    ioctl: Some(fbdev_file_ioctl),
+    mmap: Some(fbdev_file_mmap),
+};
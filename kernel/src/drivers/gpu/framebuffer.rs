@@ -23,8 +23,72 @@ pub struct FrameBufferInfo {
     pub height: u32,
     /// 每行字节数
     pub stride: u32,
-    /// 格式（xRGB = 1）
-    pub format: u32,
+    /// 像素格式
+    pub format: PixelFormat,
+}
+
+/// 像素格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 32 位 xRGB（高 8 位忽略）
+    Xrgb8888,
+    /// 16 位 RGB565
+    Rgb565,
+}
+
+impl PixelFormat {
+    /// 由每像素位数识别格式，不支持时返回 None
+    pub const fn from_bpp(bits_per_pixel: u32) -> Option<Self> {
+        match bits_per_pixel {
+            32 => Some(Self::Xrgb8888),
+            16 => Some(Self::Rgb565),
+            _ => None,
+        }
+    }
+
+    /// 每像素位数
+    #[inline]
+    pub const fn bits_per_pixel(self) -> u32 {
+        match self {
+            Self::Xrgb8888 => 32,
+            Self::Rgb565 => 16,
+        }
+    }
+
+    /// 每像素字节数
+    #[inline]
+    pub const fn bytes_per_pixel(self) -> u32 {
+        self.bits_per_pixel() / 8
+    }
+
+    /// 把 32 位 ARGB 颜色转换为本格式的像素值（截断低位）
+    #[inline]
+    pub const fn encode(self, argb: u32) -> u32 {
+        match self {
+            Self::Xrgb8888 => argb,
+            Self::Rgb565 => {
+                let r = (argb >> 19) & 0x1F;
+                let g = (argb >> 10) & 0x3F;
+                let b = (argb >> 3) & 0x1F;
+                (r << 11) | (g << 5) | b
+            }
+        }
+    }
+
+    /// 把本格式的像素值还原为不透明的 32 位 ARGB（低位按高位复制扩展）
+    #[inline]
+    pub const fn decode(self, raw: u32) -> u32 {
+        match self {
+            Self::Xrgb8888 => raw,
+            Self::Rgb565 => {
+                let r = (raw >> 11) & 0x1F;
+                let g = (raw >> 5) & 0x3F;
+                let b = raw & 0x1F;
+                let (r, g, b) = ((r << 3) | (r >> 2), (g << 2) | (g >> 4), (b << 3) | (b >> 2));
+                0xFF00_0000 | (r << 16) | (g << 8) | b
+            }
+        }
+    }
 }
 
 /// 颜色常量 (xRGB 格式)
@@ -84,42 +148,61 @@ impl FrameBuffer {
         self.info.stride
     }
 
-    /// 绘制单个像素
+    /// 获取像素格式
+    #[inline]
+    pub fn format(&self) -> PixelFormat {
+        self.info.format
+    }
+
+    /// 写入已按格式编码的像素值
+    #[inline]
+    fn write_raw(&self, x: u32, y: u32, raw: u32) {
+        let bpp = self.info.format.bytes_per_pixel();
+        unsafe {
+            let pixel_ptr = self.ptr.add((y * self.stride() + x * bpp) as usize);
+            match self.info.format {
+                PixelFormat::Xrgb8888 => write_volatile(pixel_ptr as *mut u32, raw),
+                PixelFormat::Rgb565 => write_volatile(pixel_ptr as *mut u16, raw as u16),
+            }
+        }
+    }
+
+    /// 绘制单个像素（32 位 ARGB 颜色按 framebuffer 格式转换）
     #[inline]
     pub fn put_pixel(&self, x: u32, y: u32, color: u32) {
         if x >= self.width() || y >= self.height() {
             return;
         }
-
-        unsafe {
-            let offset = (y * self.stride() + x * 4) as usize;
-            let pixel_ptr = self.ptr.add(offset) as *mut u32;
-            write_volatile(pixel_ptr, color);
-        }
+        self.write_raw(x, y, self.info.format.encode(color));
     }
 
-    /// 获取像素颜色
+    /// 获取像素颜色（转换回 32 位 ARGB）
     #[inline]
     pub fn get_pixel(&self, x: u32, y: u32) -> u32 {
         if x >= self.width() || y >= self.height() {
             return 0;
         }
 
+        let bpp = self.info.format.bytes_per_pixel();
         unsafe {
-            let offset = (y * self.stride() + x * 4) as usize;
-            let pixel_ptr = self.ptr.add(offset) as *const u32;
-            core::ptr::read_volatile(pixel_ptr)
+            let pixel_ptr = self.ptr.add((y * self.stride() + x * bpp) as usize);
+            let raw = match self.info.format {
+                PixelFormat::Xrgb8888 => core::ptr::read_volatile(pixel_ptr as *const u32),
+                PixelFormat::Rgb565 => core::ptr::read_volatile(pixel_ptr as *const u16) as u32,
+            };
+            self.info.format.decode(raw)
         }
     }
 
-    /// 填充矩形
+    /// 填充矩形（颜色只转换一次）
     pub fn fill_rect(&self, x: u32, y: u32, width: u32, height: u32, color: u32) {
         let x_end = (x + width).min(self.width());
         let y_end = (y + height).min(self.height());
+        let raw = self.info.format.encode(color);
 
         for py in y..y_end {
             for px in x..x_end {
-                self.put_pixel(px, py, color);
+                self.write_raw(px, py, raw);
             }
         }
     }
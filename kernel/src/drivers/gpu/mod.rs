@@ -18,11 +18,11 @@ pub mod fbdev;
 pub mod virtio_cmd;
 pub mod virtio_gpu;
 
-pub use framebuffer::{FrameBuffer, FrameBufferInfo};
-pub use fb_simple::{probe_simple_framebuffer, create_framebuffer, SimpleFrameBufferInfo};
+pub use framebuffer::{FrameBuffer, FrameBufferInfo, PixelFormat};
+pub use fb_simple::{probe_simple_framebuffer, simple_framebuffer_info, simple_framebuffer_from_node, create_framebuffer, SimpleFrameBufferInfo};
 pub use virtio_gpu::{VirtioGpuDevice, probe_virtio_gpu};
 pub use fbdev::{
-    fbdev_ioctl, create_fix_screeninfo, create_var_screeninfo,
+    fbdev_ioctl, fbdev_file_ioctl, fbdev_mmap, FBDEV_FILE_OPS, create_fix_screeninfo, create_var_screeninfo,
     FbFixScreeninfo, FbVarScreeninfo, FbBitfield,
     FBIOGET_FSCREENINFO, FBIOGET_VSCREENINFO,
 };
@@ -12,7 +12,7 @@ use crate::drivers::pci::{self, virtio_device};
 use crate::drivers::virtio::virtio_pci::{VirtIOPCI, status};
 use crate::drivers::virtio::queue::VirtQueue;
 use crate::drivers::virtio::offset;
-use super::framebuffer::{FrameBuffer, FrameBufferInfo};
+use super::framebuffer::{FrameBuffer, FrameBufferInfo, PixelFormat};
 use super::virtio_cmd::cmd;
 use alloc::alloc::{alloc_zeroed, dealloc, Layout};
 use core::ptr::{read_volatile, write_volatile};
@@ -356,7 +356,7 @@ impl VirtioGpuDevice {
             width,
             height,
             stride,
-            format: 1,
+            format: PixelFormat::Xrgb8888,
         });
 
         self.fb_info.as_ref()
@@ -0,0 +1,103 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! 输入事件字符设备 /dev/input0 (drivers/input/evdev.c)
+//!
+//! `read` 以完整的 `RawInputEvent` 结构为单位返回输入事件队列中的事件：
+//! - 缓冲区放不下一个事件时返回 -EINVAL
+//! - 只返回能完整放下的事件，剩余事件留给下一次读取
+//! - 没有事件时阻塞，O_NONBLOCK 时返回 -EAGAIN
+//!
+//! `poll` 在有事件可读时报告 POLLIN，可用于 poll/select/epoll
+//!
+//! `ioctl` 支持 EVIOCGREP / EVIOCSREP，读取和设置按键自动重复的延迟与间隔
+
+use crate::fs::File;
+use crate::input::{get_raw_input_event, key_repeat, raw_input_pending, set_key_repeat, RawInputEvent};
+
+/// 设备名（/dev/input0）
+pub const DEVICE_NAME: &str = "input0";
+
+/// 每个事件的字节数
+pub const EVENT_SIZE: usize = core::mem::size_of::<RawInputEvent>();
+
+/// 读取自动重复设置：`unsigned int[2]` = [延迟, 间隔]（毫秒），_IOR('E', 0x03, unsigned int[2])
+pub const EVIOCGREP: u32 = 0x8008_4503;
+/// 设置自动重复，参数格式同 `EVIOCGREP`，_IOW('E', 0x03, unsigned int[2])
+pub const EVIOCSREP: u32 = 0x4008_4503;
+
+/// 读取尽可能多的完整事件
+pub fn evdev_file_read(file: &File, buf: &mut [u8]) -> isize {
+    if buf.len() < EVENT_SIZE {
+        return -22; // EINVAL
+    }
+
+    loop {
+        let mut count = 0;
+        while count + EVENT_SIZE <= buf.len() {
+            let Some(event) = get_raw_input_event() else {
+                break;
+            };
+            unsafe {
+                core::ptr::write_unaligned(buf[count..].as_mut_ptr() as *mut RawInputEvent, event);
+            }
+            count += EVENT_SIZE;
+        }
+        if count > 0 {
+            return count as isize;
+        }
+
+        if file.flags.is_nonblock() || crate::sched::current().is_none() {
+            return -11; // EAGAIN
+        }
+
+        // 输入设备由轮询驱动，没有中断唤醒等待者，让出 CPU 后重新检查
+        #[cfg(feature = "riscv64")]
+        crate::sched::schedule();
+    }
+}
+
+/// 有事件时可读
+pub fn evdev_file_poll(_file: &File) -> u16 {
+    if raw_input_pending() {
+        crate::fs::poll::READABLE
+    } else {
+        0
+    }
+}
+
+/// 自动重复设置的 ioctl，其他命令返回 -ENOTTY
+pub fn evdev_file_ioctl(_file: &File, cmd: u32, arg: usize) -> isize {
+    match cmd {
+        EVIOCGREP => {
+            if arg == 0 {
+                return -14; // EFAULT
+            }
+            let (delay, interval) = key_repeat();
+            unsafe { core::ptr::write_unaligned(arg as *mut [u32; 2], [delay as u32, interval as u32]) };
+            0
+        }
+        EVIOCSREP => {
+            if arg == 0 {
+                return -14; // EFAULT
+            }
+            let [delay, interval] = unsafe { core::ptr::read_unaligned(arg as *const [u32; 2]) };
+            set_key_repeat(delay as u64, interval as u64);
+            0
+        }
+        _ => -25, // ENOTTY
+    }
+}
+
+/// 输入事件设备文件操作 (/dev/input0)
+pub static EVDEV_FILE_OPS: crate::fs::FileOps = crate::fs::FileOps {
+    read: Some(evdev_file_read),
+    write: None,
+    lseek: None,
+    close: None,
+    poll: Some(evdev_file_poll),
+    ioctl: Some(evdev_file_ioctl),
+    mmap: None,
+};
@@ -0,0 +1,13 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+
+//! 输入设备驱动模块
+//!
+//! PS/2 键盘和鼠标位于 `keyboard` / `mouse` 模块，这里放置其他输入设备
+//! 以及把输入事件导出给用户态的 `/dev/input0` (`evdev`)
+
+pub mod evdev;
+pub mod virtio_input;
@@ -0,0 +1,373 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+
+//! VirtIO 输入设备驱动
+//!
+//! QEMU 的 virtio-keyboard / virtio-mouse / virtio-tablet 都是 virtio-input 设备。
+//! 设备把 evdev 格式的事件写入事件队列（队列 0）中驱动预先提供的缓冲区，
+//! 驱动取出后翻译成 `InputEvent` 并放入输入事件队列。
+//!
+//! 一组事件以 `EV_SYN/SYN_REPORT` 结束：相对移动和绝对坐标在同步时合并上报，
+//! 按键事件立即上报。绝对坐标（virtio-tablet）上报为 `MouseAbsolute`，
+//! 用户态光标据此切换到绝对定位模式。
+//!
+//! 参考：
+//! - VirtIO 1.1 规范 5.8 Input Device
+//! - Linux: drivers/virtio/virtio_input.c
+
+use crate::println;
+use crate::drivers::keyboard::ps2::KeyEvent;
+use crate::drivers::virtio::queue::{UsedElem, VirtQueue};
+use crate::input::{
+    InputEvent, ABS_X, ABS_Y, BTN_LEFT, BTN_MIDDLE, BTN_RIGHT, EV_ABS, EV_KEY, EV_REL, EV_SYN,
+    REL_X, REL_Y, SYN_REPORT,
+};
+use crate::mm::dma;
+use spin::Mutex;
+
+/// virtio-input 设备 ID
+pub const VIRTIO_ID_INPUT: u32 = 18;
+
+/// 事件队列大小
+const EVENT_QUEUE_SIZE: u16 = 64;
+
+/// 设备可写描述符标志
+const VIRTQ_DESC_F_WRITE: u16 = 2;
+
+/// 普通按键码上限，从 `BTN_MISC` 开始是鼠标/手柄按键
+const BTN_MISC: u16 = 0x100;
+
+/// virtio-mmio (version 2) 寄存器偏移
+mod mmio_reg {
+    pub const MAGIC: u64 = 0x000;
+    pub const VERSION: u64 = 0x004;
+    pub const DEVICE_ID: u64 = 0x008;
+    pub const DRIVER_FEATURES: u64 = 0x020;
+    pub const DRIVER_FEATURES_SEL: u64 = 0x024;
+    pub const QUEUE_SEL: u64 = 0x030;
+    pub const QUEUE_NUM_MAX: u64 = 0x034;
+    pub const QUEUE_NUM: u64 = 0x038;
+    pub const QUEUE_READY: u64 = 0x044;
+    pub const QUEUE_NOTIFY: u64 = 0x050;
+    pub const INTERRUPT_STATUS: u64 = 0x060;
+    pub const INTERRUPT_ACK: u64 = 0x064;
+    pub const STATUS: u64 = 0x070;
+    pub const QUEUE_DESC_LO: u64 = 0x080;
+    pub const QUEUE_DESC_HI: u64 = 0x084;
+    pub const QUEUE_DRIVER_LO: u64 = 0x090;
+    pub const QUEUE_DRIVER_HI: u64 = 0x094;
+    pub const QUEUE_DEVICE_LO: u64 = 0x0A0;
+    pub const QUEUE_DEVICE_HI: u64 = 0x0A4;
+}
+
+/// 设备写入的事件（`struct virtio_input_event`，小端）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VirtioInputEvent {
+    /// 事件类型（EV_*）
+    pub type_: u16,
+    /// 事件代码
+    pub code: u16,
+    /// 事件值
+    pub value: u32,
+}
+
+impl VirtioInputEvent {
+    /// 创建事件
+    pub const fn new(type_: u16, code: u16, value: u32) -> Self {
+        Self { type_, code, value }
+    }
+}
+
+/// 已完成事件的来源
+///
+/// 真实设备从 used ring 取出事件，测试可以提供模拟的事件环
+pub trait EventRing {
+    /// 取出下一个设备已写入的事件
+    fn pop_event(&mut self) -> Option<VirtioInputEvent>;
+}
+
+/// virtio-input 事件翻译器
+///
+/// 累积一组事件中的相对移动和绝对坐标，在 `SYN_REPORT` 时上报
+#[derive(Debug, Default)]
+pub struct VirtioInputDecoder {
+    /// 累积的相对移动
+    rel: (i32, i32),
+    /// 最近的绝对坐标
+    abs: (u16, u16),
+    /// 本组事件是否更新了绝对坐标
+    abs_dirty: bool,
+    /// 当前鼠标按键状态（左、右、中）
+    buttons: (bool, bool, bool),
+}
+
+impl VirtioInputDecoder {
+    /// 创建翻译器
+    pub const fn new() -> Self {
+        Self {
+            rel: (0, 0),
+            abs: (0, 0),
+            abs_dirty: false,
+            buttons: (false, false, false),
+        }
+    }
+
+    /// 翻译一个设备事件，产生的输入事件交给 `sink`
+    pub fn feed<F: FnMut(InputEvent)>(&mut self, event: VirtioInputEvent, sink: &mut F) {
+        let value = event.value as i32;
+        match event.type_ {
+            EV_KEY => self.feed_key(event.code, value, sink),
+            EV_REL => match event.code {
+                REL_X => self.rel.0 += value,
+                REL_Y => self.rel.1 += value,
+                _ => {}
+            },
+            EV_ABS => match event.code {
+                ABS_X => {
+                    self.abs.0 = event.value as u16;
+                    self.abs_dirty = true;
+                }
+                ABS_Y => {
+                    self.abs.1 = event.value as u16;
+                    self.abs_dirty = true;
+                }
+                _ => {}
+            },
+            EV_SYN if event.code == SYN_REPORT => self.sync(sink),
+            _ => {}
+        }
+    }
+
+    /// 按键：普通按键立即上报，鼠标按键更新按键状态
+    fn feed_key<F: FnMut(InputEvent)>(&mut self, code: u16, value: i32, sink: &mut F) {
+        if code < BTN_MISC {
+            // value: 0 = 释放，1 = 按下，2 = 自动重复
+            sink(InputEvent::Keyboard(if value == 0 {
+                KeyEvent::Release(code)
+            } else {
+                KeyEvent::Press(code)
+            }));
+            return;
+        }
+
+        let pressed = value != 0;
+        let (left, right, middle) = &mut self.buttons;
+        let button = match code {
+            BTN_LEFT => left,
+            BTN_RIGHT => right,
+            BTN_MIDDLE => middle,
+            _ => return,
+        };
+        if *button != pressed {
+            *button = pressed;
+            let (left, right, middle) = self.buttons;
+            sink(InputEvent::MouseButton { left, right, middle });
+        }
+    }
+
+    /// 同步：上报本组累积的移动
+    fn sync<F: FnMut(InputEvent)>(&mut self, sink: &mut F) {
+        let (dx, dy) = core::mem::take(&mut self.rel);
+        if dx != 0 || dy != 0 {
+            sink(InputEvent::MouseMove {
+                dx: dx.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+                dy: dy.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+            });
+        }
+        if core::mem::take(&mut self.abs_dirty) {
+            sink(InputEvent::MouseAbsolute { x: self.abs.0, y: self.abs.1 });
+        }
+    }
+}
+
+/// 取出事件环中的所有事件并翻译
+///
+/// # 返回
+/// 处理的设备事件数量
+pub fn drain_ring<R, F>(ring: &mut R, decoder: &mut VirtioInputDecoder, mut sink: F) -> usize
+where
+    R: EventRing,
+    F: FnMut(InputEvent),
+{
+    let mut count = 0;
+    while let Some(event) = ring.pop_event() {
+        decoder.feed(event, &mut sink);
+        count += 1;
+    }
+    count
+}
+
+/// VirtIO 输入设备
+pub struct VirtIOInputDevice {
+    /// MMIO 基地址
+    base_addr: u64,
+    /// 事件队列（队列 0）
+    queue: VirtQueue,
+    /// 事件缓冲区（每个描述符一个 `VirtioInputEvent`）
+    events: *mut VirtioInputEvent,
+    /// 下一个待处理的 used ring 索引
+    last_used: u16,
+}
+
+unsafe impl Send for VirtIOInputDevice {}
+
+impl VirtIOInputDevice {
+    /// 初始化设备并填充事件队列
+    pub fn new(base_addr: u64) -> Result<Self, &'static str> {
+        let read = |off: u64| unsafe { core::ptr::read_volatile((base_addr + off) as *const u32) };
+        let write = |off: u64, val: u32| unsafe {
+            core::ptr::write_volatile((base_addr + off) as *mut u32, val)
+        };
+
+        if read(mmio_reg::MAGIC) != 0x74726976 {
+            return Err("Invalid VirtIO magic value");
+        }
+        if read(mmio_reg::VERSION) != 2 {
+            return Err("Unsupported VirtIO version");
+        }
+        if read(mmio_reg::DEVICE_ID) != VIRTIO_ID_INPUT {
+            return Err("Not a VirtIO input device");
+        }
+
+        // 状态机：重置 -> ACKNOWLEDGE -> DRIVER
+        write(mmio_reg::STATUS, 0);
+        write(mmio_reg::STATUS, 0x01);
+        write(mmio_reg::STATUS, 0x01 | 0x02);
+
+        // 只协商 VIRTIO_F_VERSION_1（bit 32），不需要其他可选特性
+        write(mmio_reg::DRIVER_FEATURES_SEL, 1);
+        write(mmio_reg::DRIVER_FEATURES, 1);
+        write(mmio_reg::DRIVER_FEATURES_SEL, 0);
+        write(mmio_reg::DRIVER_FEATURES, 0);
+        write(mmio_reg::STATUS, 0x01 | 0x02 | 0x08);
+        if read(mmio_reg::STATUS) & 0x08 == 0 {
+            write(mmio_reg::STATUS, 0x80);
+            return Err("VirtIO input feature negotiation failed");
+        }
+
+        // 事件队列（队列 0）；状态队列（队列 1）不使用
+        write(mmio_reg::QUEUE_SEL, 0);
+        let max = read(mmio_reg::QUEUE_NUM_MAX);
+        if max == 0 {
+            return Err("VirtIO device has zero queue size");
+        }
+        let queue_size = EVENT_QUEUE_SIZE.min(max as u16);
+        write(mmio_reg::QUEUE_NUM, queue_size as u32);
+
+        let mut queue = VirtQueue::new(
+            queue_size,
+            0,
+            base_addr + mmio_reg::QUEUE_NOTIFY,
+            base_addr + mmio_reg::INTERRUPT_STATUS,
+            base_addr + mmio_reg::INTERRUPT_ACK,
+        )
+        .ok_or("Failed to allocate VirtQueue")?;
+
+        let buf_size = queue_size as usize * core::mem::size_of::<VirtioInputEvent>();
+        let (events, events_phys) =
+            dma::alloc_coherent(buf_size, 4096).ok_or("Failed to allocate event buffers")?;
+
+        for (lo, hi, phys) in [
+            (mmio_reg::QUEUE_DESC_LO, mmio_reg::QUEUE_DESC_HI, queue.get_desc_phys()),
+            (mmio_reg::QUEUE_DRIVER_LO, mmio_reg::QUEUE_DRIVER_HI, queue.get_avail_phys()),
+            (mmio_reg::QUEUE_DEVICE_LO, mmio_reg::QUEUE_DEVICE_HI, queue.get_used_phys()),
+        ] {
+            write(lo, phys as u32);
+            write(hi, (phys >> 32) as u32);
+        }
+        write(mmio_reg::QUEUE_READY, 1);
+
+        // 每个描述符指向一个事件缓冲区，全部交给设备
+        let event_size = core::mem::size_of::<VirtioInputEvent>();
+        for i in 0..queue_size {
+            let phys = events_phys + (i as usize * event_size) as u64;
+            queue.set_desc(i, phys, event_size as u32, VIRTQ_DESC_F_WRITE, 0);
+            queue.submit(i);
+        }
+
+        write(mmio_reg::STATUS, 0x01 | 0x02 | 0x08 | 0x04);
+
+        Ok(Self {
+            base_addr,
+            queue,
+            events: events as *mut VirtioInputEvent,
+            last_used: 0,
+        })
+    }
+
+    /// MMIO 基地址
+    pub fn base_addr(&self) -> u64 {
+        self.base_addr
+    }
+}
+
+impl EventRing for VirtIOInputDevice {
+    fn pop_event(&mut self) -> Option<VirtioInputEvent> {
+        // 设备通过 DMA 更新 used ring，读取前无效化缓存
+        dma::invalidate_after_device((self.queue.used as usize + 2) as *const u8, 2);
+        let used_idx = self.queue.get_used();
+        if used_idx == self.last_used {
+            return None;
+        }
+
+        let size = self.queue.queue_size as usize;
+        let elem = unsafe {
+            let elems = (self.queue.used as usize + 4) as *const UsedElem;
+            let ptr = elems.add(self.last_used as usize % size);
+            dma::invalidate_after_device(ptr as *const u8, core::mem::size_of::<UsedElem>());
+            core::ptr::read_volatile(ptr)
+        };
+        self.last_used = self.last_used.wrapping_add(1);
+
+        let id = elem.id as u16;
+        if id as usize >= size {
+            return None;
+        }
+        let event = unsafe {
+            let ptr = self.events.add(id as usize);
+            dma::invalidate_after_device(ptr as *const u8, core::mem::size_of::<VirtioInputEvent>());
+            core::ptr::read_volatile(ptr)
+        };
+
+        // 缓冲区归还给设备
+        self.queue.submit(id);
+        Some(event)
+    }
+}
+
+impl Drop for VirtIOInputDevice {
+    fn drop(&mut self) {
+        let buf_size = self.queue.queue_size as usize * core::mem::size_of::<VirtioInputEvent>();
+        unsafe { dma::free_coherent(self.events as *mut u8, buf_size, 4096) };
+    }
+}
+
+/// 全局 VirtIO 输入设备及其翻译器
+static VIRTIO_INPUT: Mutex<Option<(VirtIOInputDevice, VirtioInputDecoder)>> = Mutex::new(None);
+
+/// 初始化 VirtIO 输入设备
+///
+/// # 参数
+/// - `base_addr`: MMIO 基地址
+pub fn init(base_addr: u64) -> Result<(), &'static str> {
+    let device = VirtIOInputDevice::new(base_addr)?;
+    println!("virtio-input: device at {:#x} ready", base_addr);
+    *VIRTIO_INPUT.lock() = Some((device, VirtioInputDecoder::new()));
+    Ok(())
+}
+
+/// 取出设备的所有事件并放入输入事件队列
+///
+/// # 返回
+/// 处理的设备事件数量
+pub fn poll() -> usize {
+    let mut guard = VIRTIO_INPUT.lock();
+    match guard.as_mut() {
+        Some((device, decoder)) => drain_ring(device, decoder, crate::input::push_event),
+        None => 0,
+    }
+}
@@ -10,6 +10,7 @@
 
 use core::arch::asm;
 use crate::println;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 // PLIC base address - QEMU virt platform uses 0x0c000000
 // NOTE: Must use plain hex digits (0x0c000000) not (0x0c00_0000) to avoid
@@ -245,10 +246,18 @@ impl Plic {
     }
 }
 
-static PLIC: Plic = Plic::new(PLIC_BASE, 4);
+/// PLIC 基地址（启动时从设备树获取，默认 QEMU virt 地址）
+static PLIC_BASE_ADDR: AtomicUsize = AtomicUsize::new(PLIC_BASE);
+
+/// 当前 PLIC 实例
+#[inline]
+fn plic() -> Plic {
+    Plic::new(PLIC_BASE_ADDR.load(Ordering::Acquire), 4)
+}
 
 pub fn init() {
-    PLIC.init();
+    PLIC_BASE_ADDR.store(crate::fdt::plic_base(), Ordering::Release);
+    plic().init();
 
     // 使能关键中断
     // RISC-V virt 平台中断映射（QEMU）:
@@ -257,42 +266,41 @@ pub fn init() {
     // - IRQ 11-13: IPI (软件中断，用于核间通信)
     let boot_hart = crate::arch::riscv64::smp::cpu_id();
 
-    // 为启动核使能 VirtIO 设备中断
-    // IRQ 1 是第一个 VirtIO 设备（通常是 VirtIO-Blk）
-    PLIC.enable_interrupt(boot_hart, 1);
-    // 也使能其他 VirtIO 槽位的 IRQ（以防有多个 VirtIO 设备）
-    // IRQ 2-8 对应 VirtIO 槽位 1-7
-    for virtio_irq in 2..9 {  // 2 到 8（包含 8）
-        PLIC.enable_interrupt(boot_hart, virtio_irq);
+    // 为启动核使能所有 VirtIO 槽位的中断
+    // 中断号来自设备树的 virtio-mmio 节点（默认 IRQ 1-8 对应槽位 0-7）
+    for (_, _, virtio_irq) in crate::fdt::virtio_mmio_devices() {
+        if virtio_irq != 0 {
+            plic().enable_interrupt(boot_hart, virtio_irq as usize);
+        }
     }
 
-    // 为启动核使能 UART 中断（QEMU RISC-V virt: IRQ 10）
-    PLIC.enable_interrupt(boot_hart, 10);
+    // 为启动核使能 UART 中断（默认 QEMU RISC-V virt: IRQ 10）
+    plic().enable_interrupt(boot_hart, crate::fdt::uart_irq() as usize);
 
     // 使能 IPI 中断（用于核间通信）
     for hart in 0..4 {
         for ipi_irq in 11..14 {  // 11-13: IPI
-            PLIC.enable_interrupt(hart, ipi_irq);
+            plic().enable_interrupt(hart, ipi_irq);
         }
     }
 }
 
 pub fn claim(hart: usize) -> Option<usize> {
-    PLIC.claim(hart)
+    plic().claim(hart)
 }
 
 pub fn complete(hart: usize, irq: usize) {
-    PLIC.complete(hart, irq)
+    plic().complete(hart, irq)
 }
 
 pub fn enable_interrupt(hart: usize, irq: usize) {
-    PLIC.enable_interrupt(hart, irq);
+    plic().enable_interrupt(hart, irq);
 }
 
 pub fn read_pending() -> u32 {
-    PLIC.read_pending()
+    plic().read_pending()
 }
 
 pub fn trigger_ipi(irq: usize) {
-    PLIC.trigger_ipi(irq)
+    plic().trigger_ipi(irq)
 }
@@ -80,6 +80,11 @@ pub mod scancode {
     pub const KEY_LALT: u16 = 0x38;
     pub const KEY_RALT: u16 = 0x138;
 
+    /// 锁定键
+    pub const KEY_CAPSLOCK: u16 = 0x3A;
+    pub const KEY_NUMLOCK: u16 = 0x45;
+    pub const KEY_SCROLLLOCK: u16 = 0x46;
+
     /// 功能键 F1-F12
     pub const KEY_F1: u16 = 0x3B;
     pub const KEY_F2: u16 = 0x3C;
@@ -101,6 +106,19 @@ pub mod scancode {
     pub const KEY_RIGHT: u16 = 0x14D;
 }
 
+/// 键盘命令
+pub mod command {
+    /// 设置 LED，后跟一个 LED 位掩码字节
+    pub const SET_LEDS: u8 = 0xED;
+}
+
+/// `command::SET_LEDS` 的 LED 位
+pub mod led {
+    pub const SCROLL_LOCK: u8 = 0x01;
+    pub const NUM_LOCK: u8 = 0x02;
+    pub const CAPS_LOCK: u8 = 0x04;
+}
+
 /// 键盘事件
 #[derive(Debug, Clone, Copy)]
 pub enum KeyEvent {
@@ -111,120 +129,43 @@ pub enum KeyEvent {
 }
 
 /// PS/2 键盘驱动状态
+///
+/// 修饰键状态和字符翻译由输入层的 `input::keymap::Keymap` 负责
 pub struct PS2Keyboard {
-    /// Shift 键状态
-    shift_pressed: bool,
-    /// Ctrl 键状态
-    ctrl_pressed: bool,
-    /// Alt 键状态
-    alt_pressed: bool,
+    /// 最近一次设置的 LED 状态（`led::*` 位掩码）
+    leds: u8,
 }
 
 impl PS2Keyboard {
     /// 创建新的 PS/2 键盘驱动
     pub const fn new() -> Self {
         Self {
-            shift_pressed: false,
-            ctrl_pressed: false,
-            alt_pressed: false,
+            leds: 0,
         }
     }
 
-    /// 读取扫描码并转换为键盘事件
-    pub fn read_scancode(&mut self) -> Option<KeyEvent> {
-        // TODO: Implement RISC-V PS/2 keyboard input
-        // The x86 inb instruction doesn't work on RISC-V
-        None
+    /// 设置键盘 LED（`led::*` 位掩码）
+    pub fn set_leds(&mut self, leds: u8) {
+        self.leds = leds;
+        self.send_command(&[command::SET_LEDS, leds]);
     }
 
-    /// 处理修饰键按下
-    fn handle_modifier_press(&mut self, scancode: u16) {
-        match scancode {
-            scancode::KEY_LSHIFT | scancode::KEY_RSHIFT => {
-                self.shift_pressed = true;
-            }
-            scancode::KEY_LCTRL | scancode::KEY_RCTRL => {
-                self.ctrl_pressed = true;
-            }
-            scancode::KEY_LALT | scancode::KEY_RALT => {
-                self.alt_pressed = true;
-            }
-            _ => {}
-        }
+    /// 最近一次设置的 LED 状态
+    pub fn leds(&self) -> u8 {
+        self.leds
     }
 
-    /// 处理修饰键释放
-    fn handle_modifier_release(&mut self, scancode: u16) {
-        match scancode {
-            scancode::KEY_LSHIFT | scancode::KEY_RSHIFT => {
-                self.shift_pressed = false;
-            }
-            scancode::KEY_LCTRL | scancode::KEY_RCTRL => {
-                self.ctrl_pressed = false;
-            }
-            scancode::KEY_LALT | scancode::KEY_RALT => {
-                self.alt_pressed = false;
-            }
-            _ => {}
-        }
+    /// 向键盘发送命令字节
+    fn send_command(&mut self, _bytes: &[u8]) {
+        // TODO: Implement RISC-V PS/2 keyboard output
+        // The x86 outb instruction doesn't work on RISC-V
     }
 
-    /// 将扫描码转换为 ASCII
-    pub fn scancode_to_ascii(&self, scancode: u16) -> Option<u8> {
-        let shifted = self.shift_pressed;
-
-        let ascii = match scancode {
-            // 字母键
-            scancode::KEY_A => if shifted { b'A' } else { b'a' },
-            scancode::KEY_B => if shifted { b'B' } else { b'b' },
-            scancode::KEY_C => if shifted { b'C' } else { b'c' },
-            scancode::KEY_D => if shifted { b'D' } else { b'd' },
-            scancode::KEY_E => if shifted { b'E' } else { b'e' },
-            scancode::KEY_F => if shifted { b'F' } else { b'f' },
-            scancode::KEY_G => if shifted { b'G' } else { b'g' },
-            scancode::KEY_H => if shifted { b'H' } else { b'h' },
-            scancode::KEY_I => if shifted { b'I' } else { b'i' },
-            scancode::KEY_J => if shifted { b'J' } else { b'j' },
-            scancode::KEY_K => if shifted { b'K' } else { b'k' },
-            scancode::KEY_L => if shifted { b'L' } else { b'l' },
-            scancode::KEY_M => if shifted { b'M' } else { b'm' },
-            scancode::KEY_N => if shifted { b'N' } else { b'n' },
-            scancode::KEY_O => if shifted { b'O' } else { b'o' },
-            scancode::KEY_P => if shifted { b'P' } else { b'p' },
-            scancode::KEY_Q => if shifted { b'Q' } else { b'q' },
-            scancode::KEY_R => if shifted { b'R' } else { b'r' },
-            scancode::KEY_S => if shifted { b'S' } else { b's' },
-            scancode::KEY_T => if shifted { b'T' } else { b't' },
-            scancode::KEY_U => if shifted { b'U' } else { b'u' },
-            scancode::KEY_V => if shifted { b'V' } else { b'v' },
-            scancode::KEY_W => if shifted { b'W' } else { b'w' },
-            scancode::KEY_X => if shifted { b'X' } else { b'x' },
-            scancode::KEY_Y => if shifted { b'Y' } else { b'y' },
-            scancode::KEY_Z => if shifted { b'Z' } else { b'z' },
-
-            // 数字键
-            scancode::KEY_1 => if shifted { b'!' } else { b'1' },
-            scancode::KEY_2 => if shifted { b'@' } else { b'2' },
-            scancode::KEY_3 => if shifted { b'#' } else { b'3' },
-            scancode::KEY_4 => if shifted { b'$' } else { b'4' },
-            scancode::KEY_5 => if shifted { b'%' } else { b'5' },
-            scancode::KEY_6 => if shifted { b'^' } else { b'6' },
-            scancode::KEY_7 => if shifted { b'&' } else { b'7' },
-            scancode::KEY_8 => if shifted { b'*' } else { b'8' },
-            scancode::KEY_9 => if shifted { b'(' } else { b'9' },
-            scancode::KEY_0 => if shifted { b')' } else { b'0' },
-
-            // 特殊键
-            scancode::KEY_SPACE => b' ',
-            scancode::KEY_ENTER => b'\n',
-            scancode::KEY_BACKSPACE => 0x08,
-            scancode::KEY_TAB => b'\t',
-            scancode::KEY_ESCAPE => 0x1B,
-
-            _ => return None,
-        };
-
-        Some(ascii)
+    /// 读取扫描码并转换为键盘事件
+    pub fn read_scancode(&mut self) -> Option<KeyEvent> {
+        // TODO: Implement RISC-V PS/2 keyboard input
+        // The x86 inb instruction doesn't work on RISC-V
+        None
     }
 
     /// 检查是否有可读数据
@@ -253,18 +194,9 @@ pub fn read_event() -> Option<KeyEvent> {
     }
 }
 
-/// 读取 ASCII 字符（非阻塞）
-pub fn read_char() -> Option<u8> {
+/// 设置键盘 LED（`led::*` 位掩码）
+pub fn set_leds(leds: u8) {
     unsafe {
-        if let Some(event) = read_event() {
-            match event {
-                KeyEvent::Press(scancode) => {
-                    KEYBOARD.scancode_to_ascii(scancode)
-                }
-                KeyEvent::Release(_) => None,
-            }
-        } else {
-            None
-        }
+        KEYBOARD.set_leds(leds);
     }
 }
@@ -16,6 +16,7 @@ pub mod gpu;
 
 pub mod keyboard;
 pub mod mouse;
+pub mod input;
 
 // Re-export VirtIO probe module for backward compatibility
 pub use virtio::probe;
@@ -0,0 +1,142 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+//! 延迟工作队列 (delayed work) - 平台无关部分
+//!
+//! 完全...
+//! - `kernel/workqueue.c` - queue_delayed_work()
+//! - `kernel/time/timer.c` - run_timers() / get_next_timer_interrupt()
+//!
+//! 核心概念：
+//! - 每个延迟工作记录以 jiffies 为单位的到期时间
+//! - 时钟中断中执行所有已到期的工作（回调在锁外执行）
+//! - tickless idle 通过 `next_deadline()` 获取最近的截止时间，
+//!   空闲时直接定时到该时间点，而不是每个 tick 唤醒一次
+
+use alloc::vec::Vec;
+use spin::Mutex;
+
+/// 延迟工作回调函数
+pub type WorkFn = fn();
+
+/// 延迟工作项
+#[derive(Clone, Copy)]
+struct DelayedWork {
+    /// 到期时间 (jiffies)
+    expires: u64,
+    /// 回调函数
+    func: WorkFn,
+}
+
+/// 延迟工作队列
+pub struct DelayedWorkQueue {
+    works: Mutex<Vec<DelayedWork>>,
+}
+
+impl DelayedWorkQueue {
+    /// 创建空的延迟工作队列
+    pub const fn new() -> Self {
+        Self {
+            works: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 添加一个在 `expires` (jiffies) 到期的工作
+    pub fn queue(&self, expires: u64, func: WorkFn) {
+        self.works.lock().push(DelayedWork { expires, func });
+    }
+
+    /// 取消所有使用 `func` 回调的待执行工作
+    ///
+    /// # 返回
+    /// 是否取消了至少一个工作
+    pub fn cancel(&self, func: WorkFn) -> bool {
+        let mut works = self.works.lock();
+        let before = works.len();
+        works.retain(|w| w.func as usize != func as usize);
+        works.len() != before
+    }
+
+    /// 取消一个在 `expires` 到期、使用 `func` 回调的工作
+    ///
+    /// 多个调用者共用同一个回调时只取消自己添加的那一个
+    pub fn cancel_at(&self, expires: u64, func: WorkFn) -> bool {
+        let mut works = self.works.lock();
+        match works.iter().position(|w| w.expires == expires && w.func as usize == func as usize) {
+            Some(index) => {
+                works.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 最近的到期时间 (jiffies)，队列为空时返回 None
+    pub fn next_deadline(&self) -> Option<u64> {
+        self.works.lock().iter().map(|w| w.expires).min()
+    }
+
+    /// 待执行的工作数量
+    pub fn len(&self) -> usize {
+        self.works.lock().len()
+    }
+
+    /// 队列是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 执行所有在 `now` (jiffies) 之前到期的工作
+    ///
+    /// 回调在释放队列锁之后执行，回调中可以再次添加工作
+    ///
+    /// # 返回
+    /// 执行的工作数量
+    pub fn run_expired(&self, now: u64) -> usize {
+        let expired: Vec<DelayedWork> = {
+            let mut works = self.works.lock();
+            let (expired, pending) = works.iter().partition(|w| w.expires <= now);
+            *works = pending;
+            expired
+        };
+
+        for work in expired.iter() {
+            (work.func)();
+        }
+        expired.len()
+    }
+}
+
+/// 全局延迟工作队列
+static DELAYED_WORK: DelayedWorkQueue = DelayedWorkQueue::new();
+
+/// 在 `delay` 个 jiffies 之后执行 `func`（对应 `schedule_delayed_work()`）
+///
+/// # 返回
+/// 到期时间 (jiffies)
+pub fn schedule_delayed_work(func: WorkFn, delay: u64) -> u64 {
+    let expires = super::get_jiffies() + delay;
+    DELAYED_WORK.queue(expires, func);
+    expires
+}
+
+/// 取消延迟工作（对应 `cancel_delayed_work()`）
+pub fn cancel_delayed_work(func: WorkFn) -> bool {
+    DELAYED_WORK.cancel(func)
+}
+
+/// 取消 `schedule_delayed_work` 返回的 `expires` 对应的那一个工作
+pub fn cancel_delayed_work_at(func: WorkFn, expires: u64) -> bool {
+    DELAYED_WORK.cancel_at(expires, func)
+}
+
+/// 最近的延迟工作截止时间 (jiffies)
+pub fn next_work_deadline() -> Option<u64> {
+    DELAYED_WORK.next_deadline()
+}
+
+/// 执行到期的延迟工作（在时钟中断中调用）
+pub fn run_delayed_work(now: u64) -> usize {
+    DELAYED_WORK.run_expired(now)
+}
@@ -13,3 +13,6 @@ pub use armv8::*;
 pub mod riscv64;
 #[cfg(feature = "riscv64")]
 pub use riscv64::*;
+
+#[cfg(feature = "riscv64")]
+pub mod delayed_work;
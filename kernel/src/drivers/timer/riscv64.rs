@@ -13,22 +13,42 @@
 
 use riscv::register::time;
 use crate::sbi;
-use core::sync::atomic::{AtomicU64, Ordering};
+use crate::sync::SeqLock;
 
 /// 定时器频率 (QEMU virt 平台)
 pub const CLOCK_FREQ: u64 = 10_000_000;  // 10 MHz
 
 /// 系统时钟频率 (HZ)
 ///
-/// 每秒触发 100 次时钟中断（每 10ms 一次）
-pub const HZ: u64 = 100;
+/// 由 Kernel.toml 的 `[timer] hz` 配置，默认每秒 100 次时钟中断（每 10ms 一次）
+pub const HZ: u64 = crate::config::TIMER_HZ;
 
-/// 时间片长度 (10 毫秒)
+/// 每个 tick 的 time CSR 周期数（对应一个时间片）
 ///
-/// 每个时间片 10ms，用于抢占式调度
-const TIME_SLICE_TICKS: u64 = CLOCK_FREQ / HZ;  // 10ms
+/// HZ=100 时为 10ms，用于抢占式调度
+pub const TIME_SLICE_TICKS: u64 = cycles_per_tick(HZ);
 
-/// jiffies - 全局时钟计数器
+/// tickless idle 一次最多跳过的 tick 数（1 秒）
+///
+/// 限制单次空闲时长，避免 time CSR 回绕或长时间不更新 jiffies
+pub const MAX_IDLE_TICKS: u64 = HZ;
+
+/// 时间保持数据 (timekeeper)
+///
+///
+/// 时钟中断（写者）在 seqlock 保护下更新，读者无锁读取一致的快照：
+/// - `jiffies`: 自系统启动以来的时钟中断次数
+/// - `cycle_last`: 最近一次 tick 时的 time CSR 值
+/// - `xtime_sec`/`xtime_nsec`: 最近一次 tick 时的墙上时间
+#[derive(Debug, Clone, Copy)]
+struct Timekeeper {
+    jiffies: u64,
+    cycle_last: u64,
+    xtime_sec: u64,
+    xtime_nsec: u64,
+}
+
+/// 全局时间保持数据
 ///
 ///
 /// 用于：
@@ -37,8 +57,13 @@ const TIME_SLICE_TICKS: u64 = CLOCK_FREQ / HZ;  // 10ms
 /// - 调度统计
 /// - 性能分析
 ///
-/// 类型：AtomicU64（支持多核并发访问）
-static JIFFIES: AtomicU64 = AtomicU64::new(0);
+/// 类型：SeqLock（读者无锁，写者在时钟中断中更新）
+static TIMEKEEPER: SeqLock<Timekeeper> = SeqLock::new(Timekeeper {
+    jiffies: 0,
+    cycle_last: 0,
+    xtime_sec: 0,
+    xtime_nsec: 0,
+});
 
 /// jiffies 相关函数
 
@@ -49,15 +74,51 @@ static JIFFIES: AtomicU64 = AtomicU64::new(0);
 /// - 当前 jiffies 值（自系统启动以来的时钟中断次数）
 #[inline]
 pub fn get_jiffies() -> u64 {
-    JIFFIES.load(Ordering::Acquire)
+    TIMEKEEPER.read().jiffies
 }
 
-/// 增加 jiffies 计数器
+/// 增加 jiffies 计数器并推进墙上时间
 ///
-/// 在每次时钟中断时调用
+/// 在每次时钟中断时调用。tickless idle 期间会跳过若干 tick，
+/// 因此按自上次 tick 以来经过的周期数补齐 jiffies（至少加 1），
+/// 对应 Linux `tick_do_update_jiffies64()`
 #[inline]
 fn increment_jiffies() {
-    JIFFIES.fetch_add(1, Ordering::Release);
+    let now = read_time();
+    TIMEKEEPER.write(|tk| {
+        let elapsed = now.wrapping_sub(tk.cycle_last) / TIME_SLICE_TICKS;
+        tk.jiffies += elapsed.max(1);
+        advance_xtime(tk, now);
+    });
+}
+
+/// 将墙上时间推进到 `now`（time CSR 值）
+#[inline]
+fn advance_xtime(tk: &mut Timekeeper, now: u64) {
+    let delta = now.wrapping_sub(tk.cycle_last);
+    let nsec = tk.xtime_nsec + cycles_to_nsecs(delta);
+    tk.xtime_sec += nsec / NSEC_PER_SEC;
+    tk.xtime_nsec = nsec % NSEC_PER_SEC;
+    tk.cycle_last = now;
+}
+
+/// 每秒纳秒数
+const NSEC_PER_SEC: u64 = 1_000_000_000;
+
+/// 将 time CSR 周期数转换为纳秒
+#[inline]
+const fn cycles_to_nsecs(cycles: u64) -> u64 {
+    (cycles / CLOCK_FREQ) * NSEC_PER_SEC + (cycles % CLOCK_FREQ) * NSEC_PER_SEC / CLOCK_FREQ
+}
+
+/// 获取墙上时间 (秒, 纳秒)
+///
+/// 从 seqlock 保护的 timekeeper 读取上次 tick 的时间，
+/// 再加上自上次 tick 以来经过的周期数，读者无需加锁
+pub fn ktime_get_real_ts() -> (u64, u64) {
+    let mut tk = TIMEKEEPER.read();
+    advance_xtime(&mut tk, read_time());
+    (tk.xtime_sec, tk.xtime_nsec)
 }
 
 /// 将 jiffies 转换为毫秒
@@ -85,14 +146,71 @@ pub fn set_timer(deadline: u64) {
     sbi::set_timer(deadline);
 }
 
+/// 给定频率下每个 tick 的 time CSR 周期数
+#[inline]
+pub const fn cycles_per_tick(hz: u64) -> u64 {
+    CLOCK_FREQ / hz
+}
+
+/// 周期性 tick 的比较值：`now` 之后一个 tick
+#[inline]
+pub const fn tick_deadline(now: u64) -> u64 {
+    now + TIME_SLICE_TICKS
+}
+
+/// tickless idle 的比较值（对应 Linux `tick_nohz_next_event()`）
+///
+/// # 参数
+/// * `now` - 当前 time CSR 值
+/// * `jiffies` - 当前 jiffies
+/// * `next_work` - 最近的延迟工作截止时间 (jiffies)
+///
+/// 下一个截止时间在一个 tick 以内时保持周期性 tick；
+/// 否则定时到截止时间，最多跳过 `MAX_IDLE_TICKS` 个 tick
+pub fn tickless_deadline(now: u64, jiffies: u64, next_work: Option<u64>) -> u64 {
+    let ticks = match next_work {
+        Some(expires) => expires.saturating_sub(jiffies),
+        None => MAX_IDLE_TICKS,
+    };
+
+    if ticks <= 1 {
+        return tick_deadline(now);
+    }
+    now + ticks.min(MAX_IDLE_TICKS) * TIME_SLICE_TICKS
+}
+
 /// 设置下一次定时器中断（时间片长度）
 ///
 pub fn set_next_trigger() {
-    let current = read_time();
-    let deadline = current + TIME_SLICE_TICKS;  // 10ms 后触发
+    set_timer(tick_deadline(read_time()));
+}
+
+/// idle 任务进入 WFI 前调用（对应 `tick_nohz_idle_enter()`）
+///
+/// 启用 tickless idle 时，定时器直接设置到下一个延迟工作的截止时间，
+/// 减少空闲 CPU 的唤醒次数；未启用时保持周期性 tick
+pub fn tick_nohz_idle_enter() {
+    if !crate::config::ENABLE_TICKLESS_IDLE {
+        return;
+    }
+
+    let deadline = tickless_deadline(
+        read_time(),
+        get_jiffies(),
+        super::delayed_work::next_work_deadline(),
+    );
     set_timer(deadline);
 }
 
+/// idle 任务从 WFI 返回后调用（对应 `tick_nohz_idle_exit()`）
+///
+/// 被非时钟中断唤醒时恢复周期性 tick，保证后续调度的时间片计数
+pub fn tick_nohz_idle_exit() {
+    if crate::config::ENABLE_TICKLESS_IDLE {
+        set_next_trigger();
+    }
+}
+
 /// 时钟中断处理函数
 ///
 ///
@@ -116,9 +234,8 @@ pub fn timer_interrupt_handler() {
     //    - 当前进程的 utime/stime
     //    - CPU 统计信息
 
-    // 4. TODO: 处理软件定时器
-    //    - 检查到期的定时器
-    //    - 调用定时器回调函数
+    // 4. 处理到期的延迟工作
+    super::delayed_work::run_delayed_work(get_jiffies());
 
     // 5. TODO: 触发调度器 tick
     //    - 更新当前进程运行时间
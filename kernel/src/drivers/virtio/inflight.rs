@@ -0,0 +1,166 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+//! 进行中的 VirtIO 请求表
+//!
+//! 参考: drivers/virtio/virtio_ring.c (desc_state), drivers/block/virtio_blk.c (virtblk_done)
+//!
+//! 核心概念：
+//! - 提交请求前按描述符链头登记，设备完成后 used ring 元素的 id 就是这个链头
+//! - 中断处理遍历 used ring，按链头找到请求，记录写入长度并标记完成，然后唤醒等待者
+//! - 提交请求的任务在等待队列上睡眠，醒来后检查自己的请求是否完成
+//! - 不能睡眠时（没有当前任务或外部中断未开启）退回轮询，轮询时自己遍历 used ring
+
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+use crate::process::wait::{WaitQueueEntry, WaitQueueHead};
+
+/// 表项状态
+mod state {
+    /// 空闲
+    pub const FREE: u8 = 0;
+    /// 已提交，等待设备完成
+    pub const PENDING: u8 = 1;
+    /// 设备已完成，等待提交者取走结果
+    pub const DONE: u8 = 2;
+}
+
+/// 轮询等待的最大次数（与 `VirtQueue::wait_for_completion` 一致）
+const POLL_TIMEOUT: usize = 10_000_000;
+
+/// 按描述符链头索引的请求表，`N` 为队列大小上限
+pub struct InflightTable<const N: usize> {
+    /// 每个描述符一项
+    state: [AtomicU8; N],
+    /// 设备写入的字节数（used ring 元素的 len）
+    len: [AtomicU32; N],
+    /// 等待请求完成的任务
+    waiters: WaitQueueHead,
+}
+
+impl<const N: usize> InflightTable<N> {
+    pub const fn new() -> Self {
+        Self {
+            state: [const { AtomicU8::new(state::FREE) }; N],
+            len: [const { AtomicU32::new(0) }; N],
+            waiters: WaitQueueHead::new(),
+        }
+    }
+
+    /// 登记以 `head` 为链头的请求（必须在提交到可用环之前调用）
+    ///
+    /// # 返回
+    /// 索引越界或该链头已有请求在进行时返回 false
+    pub fn register(&self, head: u16) -> bool {
+        match self.state.get(head as usize) {
+            Some(slot) => slot
+                .compare_exchange(state::FREE, state::PENDING, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok(),
+            None => false,
+        }
+    }
+
+    /// 记录设备完成了 `id` 对应的请求（由 used ring 元素调用）
+    ///
+    /// # 返回
+    /// `id` 不是进行中的请求时返回 false（越界或重复的 used 元素）
+    pub fn complete(&self, id: u32, len: u32) -> bool {
+        let Some(slot) = self.state.get(id as usize) else {
+            return false;
+        };
+        if slot.load(Ordering::Acquire) != state::PENDING {
+            return false;
+        }
+        self.len[id as usize].store(len, Ordering::Relaxed);
+        slot.compare_exchange(state::PENDING, state::DONE, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    /// 请求是否已完成
+    pub fn is_complete(&self, head: u16) -> bool {
+        self.state.get(head as usize)
+            .is_some_and(|slot| slot.load(Ordering::Acquire) == state::DONE)
+    }
+
+    /// 取走已完成请求的结果并释放表项
+    ///
+    /// # 返回
+    /// 设备写入的字节数；请求尚未完成时返回 None
+    pub fn finish(&self, head: u16) -> Option<u32> {
+        let slot = self.state.get(head as usize)?;
+        slot.compare_exchange(state::DONE, state::FREE, Ordering::AcqRel, Ordering::Acquire)
+            .ok()?;
+        Some(self.len[head as usize].load(Ordering::Relaxed))
+    }
+
+    /// 放弃请求（超时），之后到达的完成会被忽略
+    pub fn cancel(&self, head: u16) {
+        if let Some(slot) = self.state.get(head as usize) {
+            slot.store(state::FREE, Ordering::Release);
+        }
+    }
+
+    /// 尚未取走结果的请求数
+    pub fn in_flight(&self) -> usize {
+        self.state.iter()
+            .filter(|slot| slot.load(Ordering::Acquire) != state::FREE)
+            .count()
+    }
+
+    /// 唤醒所有等待者（在中断处理中发现完成后调用）
+    pub fn wake_waiters(&self) {
+        self.waiters.wake_up_all();
+    }
+
+    /// 等待 `head` 对应的请求完成
+    ///
+    /// `reap` 遍历 used ring 并调用 `complete`；每次检查前调用一次，
+    /// 以免中断处理因队列被占用而错过完成。
+    /// 先加入等待队列并设置睡眠状态，再检查条件，避免丢失唤醒
+    ///
+    /// # 返回
+    /// 轮询超时返回 false（只在 `can_sleep` 为 false 时发生）
+    pub fn wait(&self, head: u16, can_sleep: bool, mut reap: impl FnMut()) -> bool {
+        use crate::process::task::TaskState;
+
+        let current = match crate::sched::current() {
+            Some(task) if can_sleep => task,
+            _ => {
+                for _ in 0..POLL_TIMEOUT {
+                    reap();
+                    if self.is_complete(head) {
+                        return true;
+                    }
+                    core::hint::spin_loop();
+                }
+                return false;
+            }
+        };
+
+        loop {
+            self.waiters.add(WaitQueueEntry::new(current, false));
+            unsafe { (*current).set_state(TaskState::Uninterruptible); }
+
+            reap();
+            let done = self.is_complete(head);
+            if !done {
+                #[cfg(feature = "riscv64")]
+                crate::sched::schedule();
+            }
+
+            unsafe { (*current).set_state(TaskState::Running); }
+            self.waiters.remove(current);
+
+            if done || self.is_complete(head) {
+                return true;
+            }
+        }
+    }
+}
+
+impl<const N: usize> Default for InflightTable<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
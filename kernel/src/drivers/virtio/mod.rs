@@ -11,6 +11,7 @@ use spin::Mutex;
 use crate::drivers::blkdev::{GenDisk, Request, BlockDeviceOps};
 
 pub mod queue;
+pub mod inflight;
 pub mod probe;
 pub mod offset;
 pub mod virtio_pci;
@@ -67,6 +68,20 @@ pub struct VirtIOBlkRegs {
     _reserved9: [u32; 4],
 }
 
+/// 块设备队列大小上限（也是进行中请求表的大小）
+pub const MAX_QUEUE_SIZE: usize = 8;
+
+/// 设备支持 VIRTIO_BLK_T_FLUSH（设备有易失性写缓存）
+pub const VIRTIO_BLK_F_FLUSH: u32 = 1 << 9;
+
+/// 驱动支持的特性（特性位 0-31）
+pub const DRIVER_SUPPORTED_FEATURES: u32 = VIRTIO_BLK_F_FLUSH;
+
+/// 特性协商：只接受设备提供且驱动支持的特性
+pub fn negotiate_features(device_features: u32) -> u32 {
+    device_features & DRIVER_SUPPORTED_FEATURES
+}
+
 /// VirtIO 块设备
 pub struct VirtIOBlkDevice {
     /// MMIO 基地址
@@ -85,6 +100,10 @@ pub struct VirtIOBlkDevice {
     queue_size: u16,
     /// IRQ 号
     irq: u32,
+    /// 已提交、等待设备完成的请求
+    inflight: inflight::InflightTable<MAX_QUEUE_SIZE>,
+    /// 协商后的特性
+    features: u32,
 }
 
 unsafe impl Send for VirtIOBlkDevice {}
@@ -102,6 +121,8 @@ impl VirtIOBlkDevice {
             virtqueue: Mutex::new(None),
             queue_size: 0,
             irq: 1,  // 默认 IRQ 1（第一个 VirtIO 设备）
+            inflight: inflight::InflightTable::new(),
+            features: 0,
         }
     }
 
@@ -114,7 +135,9 @@ impl VirtIOBlkDevice {
         const STATUS_OFFSET: u64 = 0x070;
         const GUEST_PAGE_SIZE_OFFSET: u64 = 0x028;
         const DEVICE_FEATURES_OFFSET: u64 = 0x010;
+        const DEVICE_FEATURES_SEL_OFFSET: u64 = 0x014;
         const DRIVER_FEATURES_OFFSET: u64 = 0x020;
+        const DRIVER_FEATURES_SEL_OFFSET: u64 = 0x024;
         const QUEUE_SEL_OFFSET: u64 = 0x030;
         const QUEUE_NUM_MAX_OFFSET: u64 = 0x034;
         const QUEUE_NUM_OFFSET: u64 = 0x038;
@@ -174,17 +197,26 @@ impl VirtIOBlkDevice {
                 write_reg!(STATUS_OFFSET, "STATUS", 0x01 | 0x02);
             }
 
-            // 7. 读取设备特性
-            let _device_features = read_reg!(DEVICE_FEATURES_OFFSET, "DEVICE_FEATURES");
+            // 7. 读取设备特性（第 0 组，特性位 0-31）
+            write_reg!(DEVICE_FEATURES_SEL_OFFSET, "DEVICE_FEATURES_SEL", 0);
+            let device_features = read_reg!(DEVICE_FEATURES_OFFSET, "DEVICE_FEATURES");
 
             // 9. 特性协商（Modern VirtIO）
             // 写入 DRIVER_FEATURES 寄存器
             // 设置 FEATURES_OK 位（表示特性协商完成）
-            write_reg!(DRIVER_FEATURES_OFFSET, "DRIVER_FEATURES", 0);
+            self.features = negotiate_features(device_features);
+            write_reg!(DRIVER_FEATURES_SEL_OFFSET, "DRIVER_FEATURES_SEL", 0);
+            write_reg!(DRIVER_FEATURES_OFFSET, "DRIVER_FEATURES", self.features);
 
             // 9.5. 设置 FEATURES_OK 位
             write_reg!(STATUS_OFFSET, "STATUS", 0x01 | 0x02 | 0x08);
 
+            // 设备不接受协商结果时会清除 FEATURES_OK
+            if read_reg!(STATUS_OFFSET, "STATUS") & 0x08 == 0 {
+                write_reg!(STATUS_OFFSET, "STATUS", 0x80);  // FAILED
+                return Err("VirtIO device rejected negotiated features");
+            }
+
             // ========== VirtQueue 设置 ==========
 
             // 10. 选择队列 0
@@ -197,7 +229,7 @@ impl VirtIOBlkDevice {
                 return Err("VirtIO device has zero queue size");
             }
 
-            self.queue_size = if max_queue_size < 8 { 4 } else { 8 };
+            self.queue_size = if max_queue_size < MAX_QUEUE_SIZE as u32 { 4 } else { MAX_QUEUE_SIZE as u16 };
 
             // 12. 设置队列数量
             write_reg!(QUEUE_NUM_OFFSET, "QUEUE_NUM", self.queue_size as u32);
@@ -214,9 +246,6 @@ impl VirtIOBlkDevice {
                 None => return Err("Failed to allocate VirtQueue"),
             };
 
-            let desc_addr = virtqueue.get_desc_addr();
-            let avail_addr = virtqueue.get_avail_addr();
-            let used_addr = virtqueue.get_used_addr();
             // 14. Modern VirtIO: 设置队列地址（64位，分高低位）
             // Modern VirtIO 使用三个独立的地址寄存器对来设置队列
             use crate::drivers::virtio::offset;
@@ -228,25 +257,10 @@ impl VirtIOBlkDevice {
             const QUEUE_DEVICE_HI_OFFSET: u64 = offset::COMMON_CFG_QUEUE_DEVICE_HI as u64;
             const QUEUE_READY_OFFSET: u64 = offset::COMMON_CFG_QUEUE_ENABLE as u64;
 
-            // 转换虚拟地址为物理地址
-            #[cfg(feature = "riscv64")]
-            let desc_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-                crate::arch::riscv64::mm::VirtAddr::new(desc_addr)
-            ).0;
-            #[cfg(not(feature = "riscv64"))]
-            let desc_phys_addr = desc_addr;
-            #[cfg(feature = "riscv64")]
-            let avail_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-                crate::arch::riscv64::mm::VirtAddr::new(avail_addr)
-            ).0;
-            #[cfg(not(feature = "riscv64"))]
-            let avail_phys_addr = avail_addr;
-            #[cfg(feature = "riscv64")]
-            let used_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-                crate::arch::riscv64::mm::VirtAddr::new(used_addr)
-            ).0;
-            #[cfg(not(feature = "riscv64"))]
-            let used_phys_addr = used_addr;
+            // vring 位于 DMA 一致性内存中，物理地址在分配时已知
+            let desc_phys_addr = virtqueue.get_desc_phys();
+            let avail_phys_addr = virtqueue.get_avail_phys();
+            let used_phys_addr = virtqueue.get_used_phys();
 
             // 写入描述符表地址（低32位）
             write_reg!(QUEUE_DESC_LO_OFFSET, "QUEUE_DESC_LO", (desc_phys_addr & 0xFFFFFFFF) as u32);
@@ -294,6 +308,11 @@ impl VirtIOBlkDevice {
         self.capacity
     }
 
+    /// 是否协商了 VIRTIO_BLK_F_FLUSH
+    pub fn has_flush(&self) -> bool {
+        self.features & VIRTIO_BLK_F_FLUSH != 0
+    }
+
     /// 处理 I/O 请求
     unsafe extern "C" fn handle_request(req: &mut Request) {
         // 从 private_data 获取 VirtIOBlkDevice 指针
@@ -321,8 +340,8 @@ impl VirtIOBlkDevice {
                 device.write_block(req.sector, &req.buffer)
             }
             crate::drivers::blkdev::ReqCmd::Flush => {
-                // 刷新操作（暂时返回成功）
-                Ok(())
+                // 刷新设备写缓存
+                device.flush()
             }
         };
 
@@ -355,71 +374,26 @@ impl VirtIOBlkDevice {
             None => return Err(-5),
         };
 
-        use queue::{VirtIOBlkReqHeader, VirtIOBlkResp};
+        use queue::{BlkReqBuffer, VirtIOBlkReqHeader, VirtIOBlkResp};
 
-        // 构造 VirtIO 块请求头
-        let req_header = VirtIOBlkReqHeader {
-            type_: queue::req_type::VIRTIO_BLK_T_IN,
-            reserved: 0,
-            sector,
+        // 请求头和响应状态放在 DMA 一致性缓冲区中（需要持久化直到请求完成）
+        let req = match BlkReqBuffer::new(queue::req_type::VIRTIO_BLK_T_IN, sector) {
+            Some(req) => req,
+            None => return Err(-12),  // ENOMEM
         };
 
-        // 分配请求头缓冲区（需要持久化直到请求完成）
-        let header_layout = alloc::alloc::Layout::new::<VirtIOBlkReqHeader>();
-        let header_ptr: *mut VirtIOBlkReqHeader;
-        unsafe {
-            header_ptr = alloc::alloc::alloc(header_layout) as *mut VirtIOBlkReqHeader;
-        }
-        if header_ptr.is_null() {
-            return Err(-12);  // ENOMEM
-        }
-        unsafe {
-            *header_ptr = req_header;
-        }
-
-        // 分配响应缓冲区
-        let resp_layout = alloc::alloc::Layout::new::<VirtIOBlkResp>();
-        let resp_ptr: *mut VirtIOBlkResp;
-        unsafe {
-            resp_ptr = alloc::alloc::alloc(resp_layout) as *mut VirtIOBlkResp;
-        }
-        if resp_ptr.is_null() {
-            unsafe {
-                alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-            }
-            return Err(-12);  // ENOMEM
-        }
-        unsafe {
-            (*resp_ptr).status = 0xFF;  // 初始化为无效状态
-        }
-
         // VirtIO 描述符标志
-        const VIRTQ_DESC_F_NEXT: u16 = 1;
-        const VIRTQ_DESC_F_WRITE: u16 = 2;
+        use queue::desc_flags::{VIRTQ_DESC_F_NEXT, VIRTQ_DESC_F_WRITE};
 
-        // 将虚拟地址转换为物理地址（VirtIO 设备需要物理地址进行 DMA）
-        #[cfg(feature = "riscv64")]
-        let header_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-            crate::arch::riscv64::mm::VirtAddr::new(header_ptr as u64)
-        ).0;
-        #[cfg(feature = "riscv64")]
-        let data_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-            crate::arch::riscv64::mm::VirtAddr::new(buf.as_ptr() as u64)
-        ).0;
-        #[cfg(feature = "riscv64")]
-        let resp_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-            crate::arch::riscv64::mm::VirtAddr::new(resp_ptr as u64)
-        ).0;
-
-        // 如果不是 RISC-V，使用原始地址（仅用于其他架构）
-        #[cfg(not(feature = "riscv64"))]
-        let header_phys_addr = header_ptr as u64;
-        #[cfg(not(feature = "riscv64"))]
-        let data_phys_addr = buf.as_ptr() as u64;
-        #[cfg(not(feature = "riscv64"))]
-        let resp_phys_addr = resp_ptr as u64;
+        // VirtIO 设备需要物理地址进行 DMA
+        let header_phys_addr = req.header_phys();
+        let data_phys_addr = crate::mm::dma::virt_to_phys(buf.as_ptr() as usize);
+        let resp_phys_addr = req.resp_phys();
 
-        // 分配三个描述符
+        // 分配三个描述符（先检查数量，避免分配到一半失败时泄漏已分配的描述符）
+        if queue.num_free() < 3 {
+            return Err(-5);
+        }
         let header_desc_idx = match queue.alloc_desc() {
             Some(idx) => idx,
             None => return Err(-5),
@@ -461,40 +435,105 @@ impl VirtIOBlkDevice {
             0,
         );
 
-        // 提交到可用环
+        // 写回数据缓冲区中的脏缓存行，避免之后覆盖设备写入的数据
+        crate::mm::dma::flush_for_device(buf.as_ptr(), buf.len());
+
+        // 登记请求后提交到可用环（中断可能在提交后立即到达）
+        if !self.inflight.register(header_desc_idx) {
+            return Err(-5);
+        }
         queue.submit(header_desc_idx);
 
         // 通知设备
         queue.notify();
 
-        // 等待设备完成请求
-        let prev_used = queue.get_used();
-        let used = queue.wait_for_completion(prev_used);
+        // 释放队列后等待设备完成请求，其他任务可以在此期间提交 I/O
+        drop(queue_guard);
+        if let Err(err) = self.wait_for_request(header_desc_idx) {
+            // 超时后设备仍可能写入响应状态，不能归还请求缓冲区
+            core::mem::forget(req);
+            return Err(err);
+        }
+
+        // 设备已写入数据缓冲区，读取前无效化缓存
+        crate::mm::dma::invalidate_after_device(buf.as_ptr(), buf.len());
 
-        // 检查中断状态并清除
+        // 检查响应状态（缓冲区在 req 离开作用域时释放）
+        if req.status() == queue::status::VIRTIO_BLK_S_OK {
+            Ok(())
+        } else {
+            Err(-5)  // EIO
+        }
+    }
+
+    /// 读取并应答设备中断状态
+    fn ack_interrupt(&self) -> u32 {
         const INTERRUPT_STATUS_OFFSET: u64 = 0x60;
+        const INTERRUPT_ACK_OFFSET: u64 = 0x64;
         unsafe {
-            let irq_ptr = (self.base_addr + INTERRUPT_STATUS_OFFSET) as *const u32;
-            let irq_status = core::ptr::read_volatile(irq_ptr);
+            let irq_status = core::ptr::read_volatile((self.base_addr + INTERRUPT_STATUS_OFFSET) as *const u32);
             if irq_status != 0 {
-                const INTERRUPT_ACK_OFFSET: u64 = 0x64;
-                let ack_ptr = (self.base_addr + INTERRUPT_ACK_OFFSET) as *mut u32;
-                core::ptr::write_volatile(ack_ptr, irq_status);
+                core::ptr::write_volatile((self.base_addr + INTERRUPT_ACK_OFFSET) as *mut u32, irq_status);
             }
+            irq_status
         }
+    }
 
-        // 检查响应状态
-        unsafe {
-            let status = (*resp_ptr).status;
-            alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-            alloc::alloc::dealloc(resp_ptr as *mut u8, resp_layout);
-
-            if status == queue::status::VIRTIO_BLK_S_OK {
-                Ok(())
-            } else {
-                Err(-5)  // EIO
+    /// 遍历 used ring，把完成的请求标记到进行中请求表并唤醒等待者
+    ///
+    /// 队列正被其他路径占用时直接返回（持有者释放队列后会再次调用），
+    /// 因此可以在中断上下文中调用
+    ///
+    /// # 返回
+    /// 本次完成的请求数
+    fn reap_completions(&self) -> usize {
+        let mut completed = 0;
+        if let Some(mut queue_guard) = self.virtqueue.try_lock() {
+            if let Some(queue) = queue_guard.as_mut() {
+                while let Some(elem) = queue.pop_used() {
+                    if self.inflight.complete(elem.id, elem.len) {
+                        completed += 1;
+                    } else {
+                        crate::println!("virtio-blk: spurious completion for descriptor {}", elem.id);
+                    }
+                }
             }
         }
+        if completed > 0 {
+            self.inflight.wake_waiters();
+        }
+        completed
+    }
+
+    /// 等待以 `head` 为链头的请求完成，完成后释放它的描述符链
+    ///
+    /// 有当前任务且外部中断已开启时睡眠，由 `interrupt_handler` 唤醒；
+    /// 否则（启动早期、中断关闭的陷入上下文）轮询 used ring。
+    /// 超时时设备可能仍在使用描述符，因此不释放
+    fn wait_for_request(&self, head: u16) -> Result<(), i32> {
+        #[cfg(feature = "riscv64")]
+        let can_sleep = crate::arch::riscv64::trap::external_interrupt_enabled();
+        #[cfg(not(feature = "riscv64"))]
+        let can_sleep = false;
+
+        if !self.inflight.wait(head, can_sleep, || { self.reap_completions(); }) {
+            crate::println!("virtio-blk: I/O timeout (descriptor {})", head);
+            self.inflight.cancel(head);
+            return Err(-5);  // EIO
+        }
+        if !can_sleep {
+            // 轮询完成时中断未被处理，手动应答
+            self.ack_interrupt();
+        }
+        self.inflight.finish(head);
+
+        // 设备已用完描述符链，放回空闲链表
+        if let Some(queue) = self.virtqueue.lock().as_mut() {
+            queue.free_desc_chain(head);
+        }
+        // 持有队列期间中断处理可能错过了其他请求的完成
+        self.reap_completions();
+        Ok(())
     }
 
     /// 写入块
@@ -507,49 +546,18 @@ impl VirtIOBlkDevice {
         let mut queue_guard = self.virtqueue.lock();
         let queue = queue_guard.as_mut().ok_or(-5)?;
 
-        use queue::{VirtIOBlkReqHeader, VirtIOBlkResp};
+        use queue::{BlkReqBuffer, VirtIOBlkReqHeader, VirtIOBlkResp};
 
-        // 构造 VirtIO 块请求头
-        let req_header = VirtIOBlkReqHeader {
-            type_: queue::req_type::VIRTIO_BLK_T_OUT,
-            reserved: 0,
-            sector,
-        };
-
-        // 分配请求头缓冲区（需要持久化直到请求完成）
-        let header_layout = alloc::alloc::Layout::new::<VirtIOBlkReqHeader>();
-        let header_ptr: *mut VirtIOBlkReqHeader;
-        unsafe {
-            header_ptr = alloc::alloc::alloc(header_layout) as *mut VirtIOBlkReqHeader;
-        }
-        if header_ptr.is_null() {
-            return Err(-12);  // ENOMEM
-        }
-        unsafe {
-            *header_ptr = req_header;
-        }
-
-        // 分配响应缓冲区
-        let resp_layout = alloc::alloc::Layout::new::<VirtIOBlkResp>();
-        let resp_ptr: *mut VirtIOBlkResp;
-        unsafe {
-            resp_ptr = alloc::alloc::alloc(resp_layout) as *mut VirtIOBlkResp;
-        }
-        if resp_ptr.is_null() {
-            unsafe {
-                alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-            }
-            return Err(-12);  // ENOMEM
-        }
-        unsafe {
-            (*resp_ptr).status = 0xFF;  // 初始化为无效状态
-        }
+        // 请求头和响应状态放在 DMA 一致性缓冲区中（需要持久化直到请求完成）
+        let req = BlkReqBuffer::new(queue::req_type::VIRTIO_BLK_T_OUT, sector).ok_or(-12)?;  // ENOMEM
 
         // VirtIO 描述符标志
-        const VIRTQ_DESC_F_NEXT: u16 = 1;
-        const VIRTQ_DESC_F_WRITE: u16 = 2;
+        use queue::desc_flags::{VIRTQ_DESC_F_NEXT, VIRTQ_DESC_F_WRITE};
 
-        // 分配三个描述符
+        // 分配三个描述符（先检查数量，避免分配到一半失败时泄漏已分配的描述符）
+        if queue.num_free() < 3 {
+            return Err(-5);
+        }
         let header_desc_idx = queue.alloc_desc().ok_or(-5)?;
         let data_desc_idx = queue.alloc_desc().ok_or(-5)?;
         let resp_desc_idx = queue.alloc_desc().ok_or(-5)?;
@@ -557,7 +565,7 @@ impl VirtIOBlkDevice {
         // 设置请求头描述符（只读，设备读取）
         queue.set_desc(
             header_desc_idx,
-            header_ptr as u64,
+            req.header_phys(),
             core::mem::size_of::<VirtIOBlkReqHeader>() as u32,
             VIRTQ_DESC_F_NEXT,
             data_desc_idx,
@@ -566,7 +574,7 @@ impl VirtIOBlkDevice {
         // 设置数据缓冲区描述符（只读，设备读取）
         queue.set_desc(
             data_desc_idx,
-            buf.as_ptr() as u64,
+            crate::mm::dma::virt_to_phys(buf.as_ptr() as usize),
             buf.len() as u32,
             VIRTQ_DESC_F_NEXT,
             resp_desc_idx,
@@ -575,35 +583,113 @@ impl VirtIOBlkDevice {
         // 设置响应描述符（只写，设备写入）
         queue.set_desc(
             resp_desc_idx,
-            resp_ptr as u64,
+            req.resp_phys(),
             core::mem::size_of::<VirtIOBlkResp>() as u32,
             VIRTQ_DESC_F_WRITE,
             0,
         );
 
-        // 提交到可用环
+        // 设备读取数据缓冲区前写回缓存
+        crate::mm::dma::flush_for_device(buf.as_ptr(), buf.len());
+
+        // 登记请求后提交到可用环（中断可能在提交后立即到达）
+        if !self.inflight.register(header_desc_idx) {
+            return Err(-5);
+        }
         queue.submit(header_desc_idx);
 
         // 通知设备
         queue.notify();
 
-        // 等待完成
-        let prev_used = queue.get_used();
-        let _used = queue.wait_for_completion(prev_used);
+        // 释放队列后等待完成
+        drop(queue_guard);
+        if let Err(err) = self.wait_for_request(header_desc_idx) {
+            // 超时后设备仍可能写入响应状态，不能归还请求缓冲区
+            core::mem::forget(req);
+            return Err(err);
+        }
 
-        // 检查响应状态
-        unsafe {
-            let status = (*resp_ptr).status;
-            alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-            alloc::alloc::dealloc(resp_ptr as *mut u8, resp_layout);
-
-            if status == queue::status::VIRTIO_BLK_S_OK {
-                Ok(())
-            } else if status == queue::status::VIRTIO_BLK_S_IOERR {
-                Err(-5)  // EIO
-            } else {
-                Err(-5)  // EIO
-            }
+        // 检查响应状态（缓冲区在 req 离开作用域时释放）
+        let status = req.status();
+        if status == queue::status::VIRTIO_BLK_S_OK {
+            Ok(())
+        } else if status == queue::status::VIRTIO_BLK_S_IOERR {
+            Err(-5)  // EIO
+        } else {
+            Err(-5)  // EIO
+        }
+    }
+
+    /// 刷新设备写缓存 (VIRTIO_BLK_T_FLUSH)
+    ///
+    /// 没有协商 VIRTIO_BLK_F_FLUSH 时设备没有易失性写缓存，
+    /// 写请求完成即已持久化，直接返回成功
+    pub fn flush(&self) -> Result<(), i32> {
+        if !*self.initialized.lock() {
+            return Err(-5);  // EIO
+        }
+        if !self.has_flush() {
+            return Ok(());
+        }
+
+        // 获取 VirtQueue
+        let mut queue_guard = self.virtqueue.lock();
+        let queue = queue_guard.as_mut().ok_or(-5)?;
+
+        use queue::{BlkReqBuffer, VirtIOBlkReqHeader, VirtIOBlkResp};
+
+        // 刷新请求没有数据缓冲区，扇区号必须为 0
+        let req = BlkReqBuffer::new(queue::req_type::VIRTIO_BLK_T_FLUSH, 0).ok_or(-12)?;  // ENOMEM
+
+        // VirtIO 描述符标志
+        use queue::desc_flags::{VIRTQ_DESC_F_NEXT, VIRTQ_DESC_F_WRITE};
+
+        // 分配两个描述符：请求头 + 响应
+        if queue.num_free() < 2 {
+            return Err(-5);
+        }
+        let header_desc_idx = queue.alloc_desc().ok_or(-5)?;
+        let resp_desc_idx = queue.alloc_desc().ok_or(-5)?;
+
+        // 设置请求头描述符（只读，设备读取）
+        queue.set_desc(
+            header_desc_idx,
+            req.header_phys(),
+            core::mem::size_of::<VirtIOBlkReqHeader>() as u32,
+            VIRTQ_DESC_F_NEXT,
+            resp_desc_idx,
+        );
+
+        // 设置响应描述符（只写，设备写入）
+        queue.set_desc(
+            resp_desc_idx,
+            req.resp_phys(),
+            core::mem::size_of::<VirtIOBlkResp>() as u32,
+            VIRTQ_DESC_F_WRITE,
+            0,
+        );
+
+        // 登记请求后提交到可用环
+        if !self.inflight.register(header_desc_idx) {
+            return Err(-5);
+        }
+        queue.submit(header_desc_idx);
+
+        // 通知设备
+        queue.notify();
+
+        // 释放队列后等待完成
+        drop(queue_guard);
+        if let Err(err) = self.wait_for_request(header_desc_idx) {
+            // 超时后设备仍可能写入响应状态，不能归还请求缓冲区
+            core::mem::forget(req);
+            return Err(err);
+        }
+
+        match req.status() {
+            queue::status::VIRTIO_BLK_S_OK => Ok(()),
+            queue::status::VIRTIO_BLK_S_UNSUPP => Err(-95),  // EOPNOTSUPP
+            _ => Err(-5),  // EIO
         }
     }
 }
@@ -648,6 +734,9 @@ pub fn init(base_addr: u64) -> Result<(), &'static str> {
         if let Some(ref mut dev) = VIRTIO_BLK {
             let device_ptr = dev as *const VirtIOBlkDevice as *mut u8;
             dev.disk.private_data = Some(device_ptr);
+
+            // 登记 /dev 节点和 MBR 中的主分区
+            crate::drivers::blkdev::add_disk(&dev.disk);
         }
 
         Ok(())
@@ -770,8 +859,12 @@ pub fn register_pci_gen_disk() {
         // 设置请求处理函数
         disk.set_request_fn(pci_virtio_handle_request);
 
-        // 注册到块设备管理器
-        let _ = crate::drivers::blkdev::register_disk(disk);
+        // 注册到块设备管理器，并登记 /dev 节点和分区
+        if crate::drivers::blkdev::register_disk(disk).is_ok() {
+            if let Some(disk) = crate::drivers::blkdev::get_disk(8) {
+                crate::drivers::blkdev::add_disk(disk);
+            }
+        }
     }
 }
 
@@ -895,32 +988,14 @@ pub fn interrupt_handler_pci(irq: usize) {
 
 /// VirtIO-Blk 中断处理器（Legacy MMIO VirtIO）
 ///
-/// 处理 Legacy MMIO VirtIO-Blk 设备的中断
+/// 应答中断后遍历 used ring，标记完成的请求并唤醒等待它们的任务
 pub fn interrupt_handler() {
-    crate::println!("virtio-blk: interrupt_handler called (MMIO)!");
     unsafe {
         // MMIO VirtIO 设备（Legacy VirtIO）
         if let Some(device) = VIRTIO_BLK.as_ref() {
-            // 读取中断状态 (INTERRUPT_STATUS at 0x60)
-            let irq_status_ptr = (device.base_addr + 0x60) as *const u32;
-            let irq_status = core::ptr::read_volatile(irq_status_ptr);
-
-            crate::println!("virtio-blk: MMIO IRQ status = 0x{:x}", irq_status);
-
-            if irq_status != 0 {
-                crate::println!("virtio-blk: MMIO Interrupt! status=0x{:x}", irq_status);
-
-                // 清除中断（INTERRUPT_ACK at 0x64）
-                let irq_ack_ptr = (device.base_addr + 0x64) as *mut u32;
-                core::ptr::write_volatile(irq_ack_ptr, irq_status);
-
-                // 获取队列并打印状态
-                if let Some(queue_guard) = device.virtqueue.try_lock() {
-                    if let Some(queue) = queue_guard.as_ref() {
-                        let used_idx = queue.get_used();
-                        crate::println!("virtio-blk: used_idx now = {}", used_idx);
-                    }
-                }
+            // 读取并清除中断状态 (INTERRUPT_STATUS at 0x60, INTERRUPT_ACK at 0x64)
+            if device.ack_interrupt() != 0 {
+                device.reap_completions();
             }
         } else {
             crate::println!("virtio-blk: ERROR: No VirtIO block device found!");
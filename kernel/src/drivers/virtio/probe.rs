@@ -5,9 +5,16 @@
 //! VirtIO 设备探测
 //!
 //! 用于探测和初始化 VirtIO 设备
+//!
+//! 完全...
+//! - `drivers/virtio/virtio_mmio.c` - virtio_mmio_probe()
+//!
+//! virtio-mmio 槽位来自设备树，没有设备树时扫描默认窗口
+//! （基地址 0x10001000，间隔 0x1000，数量 `config::VIRTIO_MMIO_SLOTS`）
 
 use crate::println;
 use crate::config::ENABLE_VIRTIO_NET_PROBE;
+use alloc::vec::Vec;
 
 /// VirtIO 设备 ID
 ///
@@ -29,76 +36,151 @@ pub enum VirtIODeviceId {
     VirtioScsi = 8,
     /// GPU
     VirtioGpu = 16,
+    /// 输入设备（键盘、鼠标、触摸板）
+    VirtioInput = 18,
+}
+
+impl VirtIODeviceId {
+    /// 从设备 ID 寄存器的值转换
+    pub fn from_u32(id: u32) -> Option<Self> {
+        match id {
+            1 => Some(Self::VirtioNet),
+            2 => Some(Self::VirtioBlk),
+            3 => Some(Self::VirtioConsole),
+            4 => Some(Self::VirtioRng),
+            5 => Some(Self::VirtioBalloon),
+            8 => Some(Self::VirtioScsi),
+            16 => Some(Self::VirtioGpu),
+            18 => Some(Self::VirtioInput),
+            _ => None,
+        }
+    }
+
+    /// 是否有对应的驱动（扫描时只报告这些设备）
+    pub fn has_driver(self) -> bool {
+        matches!(self, Self::VirtioNet | Self::VirtioBlk | Self::VirtioGpu | Self::VirtioInput)
+    }
+}
+
+/// virtio-mmio 魔数（"virt"）
+pub const VIRTIO_MMIO_MAGIC: u32 = 0x74726976;
+
+/// virtio-mmio 寄存器偏移
+mod mmio_reg {
+    pub const MAGIC: u64 = 0x000;
+    pub const VERSION: u64 = 0x004;
+    pub const DEVICE_ID: u64 = 0x008;
 }
 
-/// VirtIO 设备 MMIO 基地址
+/// MMIO 寄存器读取接口
 ///
-/// QEMU virt 平台的 VirtIO 设备地址范围
-/// 参考: QEMU virt 平台文档
-/// 使用恒等映射：VIRTIO_MMIO_BASE 在 0x10000000 附近
-const VIRTIO_MMIO_BASE: u64 = 0x10001000;
-const VIRTIO_MMIO_SIZE: u64 = 0x1000;
+/// 真实硬件使用 `PhysMmio`，测试可以提供模拟的 MMIO 窗口
+pub trait MmioBus {
+    /// 读取 `addr` 处的 32 位寄存器
+    fn read_u32(&self, addr: u64) -> u32;
+}
 
-/// VirtIO 设备数量
-const VIRTIO_MAX_DEVICES: usize = 8;
+/// 直接访问物理 MMIO 地址（恒等映射）
+pub struct PhysMmio;
 
-/// 探测所有 VirtIO 设备
+impl MmioBus for PhysMmio {
+    #[inline]
+    fn read_u32(&self, addr: u64) -> u32 {
+        unsafe { core::ptr::read_volatile(addr as *const u32) }
+    }
+}
+
+/// 扫描发现的 virtio-mmio 设备
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VirtioMmioDevice {
+    /// 槽位序号
+    pub slot: usize,
+    /// MMIO 基地址
+    pub base: u64,
+    /// 中断号（未知时为 0）
+    pub irq: u32,
+    /// 传输层版本（1 = legacy，2 = modern）
+    pub version: u32,
+    /// 设备类型
+    pub device_id: VirtIODeviceId,
+}
+
+/// 当前平台的 virtio-mmio 槽位
 ///
-/// # 返回
-/// 返回找到的设备数量
+/// 来自设备树的 virtio-mmio 节点；没有设备树时为 QEMU virt 默认窗口
+/// （`config::VIRTIO_MMIO_SLOTS` 个槽位，间隔 0x1000）
+pub fn virtio_mmio_slots() -> Vec<(u64, u32)> {
+    crate::fdt::virtio_mmio_devices()
+        .into_iter()
+        .map(|(base, _, irq)| (base, irq))
+        .collect()
+}
+
+/// 扫描 virtio-mmio 槽位
 ///
-/// # 说明
-/// 扫描所有 8 个 VirtIO 设备槽位
-pub fn virtio_probe_devices() -> usize {
-    let mut device_count = 0;
+/// 读取每个槽位的 magic / version / device-id：
+/// - 魔数不符或版本不是 1/2 的槽位被跳过
+/// - 设备 ID 为 0 的空槽位被跳过
+/// - 只报告有驱动的设备类型（blk、net、gpu）
+pub fn scan_virtio_mmio<B: MmioBus>(bus: &B, slots: &[(u64, u32)]) -> Vec<VirtioMmioDevice> {
+    let mut devices = Vec::new();
+
+    for (slot, &(base, irq)) in slots.iter().enumerate() {
+        if bus.read_u32(base + mmio_reg::MAGIC) != VIRTIO_MMIO_MAGIC {
+            continue;
+        }
 
-    // 扫描所有 VirtIO 设备槽位
-    for device_index in 0..VIRTIO_MAX_DEVICES {
-        let base_addr = VIRTIO_MMIO_BASE + (device_index as u64 * VIRTIO_MMIO_SIZE);
+        let version = bus.read_u32(base + mmio_reg::VERSION);
+        if version != 1 && version != 2 {
+            continue;
+        }
 
-        // 快速读取魔数
-        let magic = unsafe {
-            let magic_ptr = base_addr as *const u32;
-            core::ptr::read_volatile(magic_ptr)
+        let device_id = match VirtIODeviceId::from_u32(bus.read_u32(base + mmio_reg::DEVICE_ID)) {
+            Some(id) if id.has_driver() => id,
+            _ => continue,
         };
 
-        // 检查魔数（"virt" = 0x74726976）
-        if magic == 0x74726976 {
-            // 找到了 VirtIO 设备，读取更多信息
-            let (version, device_id, _vendor, _device_features) = unsafe {
-                let version_ptr = (base_addr + 4) as *const u32;
-                let device_id_ptr = (base_addr + 8) as *const u32;
-                let vendor_ptr = (base_addr + 12) as *const u32;
-                let features_ptr = (base_addr + 16) as *const u32;
-                (
-                    core::ptr::read_volatile(version_ptr),
-                    core::ptr::read_volatile(device_id_ptr),
-                    core::ptr::read_volatile(vendor_ptr),
-                    core::ptr::read_volatile(features_ptr),
-                )
-            };
-
-            // 检查版本
-            if version == 1 || version == 2 {
-                // 识别设备类型并初始化
-                match device_id {
-                    1 => {
-                        if init_virtio_net(base_addr).is_ok() {
-                            device_count += 1;
-                        }
-                    }
-                    2 => {
-                        if init_virtio_blk(base_addr).is_ok() {
-                            device_count += 1;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        }
+        devices.push(VirtioMmioDevice { slot, base, irq, version, device_id });
     }
 
-    device_count
+    devices
+}
+
+/// 扫描 virtio-mmio 槽位并用 `init` 初始化发现的设备
+///
+/// # 返回
+/// 初始化成功的设备列表
+pub fn probe_virtio_mmio<B, F>(bus: &B, slots: &[(u64, u32)], mut init: F) -> Vec<VirtioMmioDevice>
+where
+    B: MmioBus,
+    F: FnMut(&VirtioMmioDevice) -> Result<(), &'static str>,
+{
+    scan_virtio_mmio(bus, slots)
+        .into_iter()
+        .filter(|dev| init(dev).is_ok())
+        .collect()
+}
+
+/// 按设备类型初始化对应的驱动
+fn init_virtio_device(dev: &VirtioMmioDevice) -> Result<(), &'static str> {
+    match dev.device_id {
+        VirtIODeviceId::VirtioNet => init_virtio_net(dev.base),
+        VirtIODeviceId::VirtioBlk => init_virtio_blk(dev.base),
+        VirtIODeviceId::VirtioGpu => init_virtio_gpu(dev.base),
+        VirtIODeviceId::VirtioInput => init_virtio_input(dev.base),
+        _ => Err("No driver for VirtIO device"),
+    }
+}
+
+/// 探测所有 VirtIO 设备
+///
+/// # 返回
+/// 返回初始化成功的设备列表
+///
+/// # 说明
+/// 扫描设备树中的所有 virtio-mmio 节点（没有设备树时扫描默认的 virtio-mmio 窗口）
+pub fn virtio_probe_devices() -> Vec<VirtioMmioDevice> {
+    probe_virtio_mmio(&PhysMmio, &virtio_mmio_slots(), init_virtio_device)
 }
 
 /// 初始化 VirtIO-Net 设备
@@ -144,6 +226,27 @@ fn init_virtio_blk(base_addr: u64) -> Result<(), &'static str> {
     }
 }
 
+/// 初始化 VirtIO-GPU 设备
+///
+/// # 说明
+/// VirtIO-GPU 驱动目前只支持 PCI 传输层（见 `gpu::probe_virtio_gpu()`），
+/// MMIO 传输层的 GPU 只会被识别，不会初始化
+fn init_virtio_gpu(base_addr: u64) -> Result<(), &'static str> {
+    let _ = base_addr;
+    Err("VirtIO-GPU over MMIO not supported")
+}
+
+/// 初始化 VirtIO-Input 设备
+///
+/// # 参数
+/// - `base_addr`: 设备 MMIO 基地址
+///
+/// # 返回
+/// 成功返回 Ok(())，失败返回 Err(&str)
+fn init_virtio_input(base_addr: u64) -> Result<(), &'static str> {
+    crate::drivers::input::virtio_input::init(base_addr)
+}
+
 /// 初始化回环网络设备
 ///
 /// # 返回
@@ -174,8 +277,7 @@ pub fn init_network_devices() -> usize {
 
     // 2. VirtIO 设备探测（通过 menuconfig 配置控制）
     if ENABLE_VIRTIO_NET_PROBE {
-        let virtio_count = virtio_probe_devices();
-        device_count += virtio_count;
+        device_count += virtio_probe_devices().len();
     }
 
     device_count
@@ -189,36 +291,14 @@ pub fn init_network_devices() -> usize {
 /// # 返回
 /// 返回初始化的设备数量
 pub fn init_block_devices() -> usize {
-    let mut device_count = 0;
-
-    // 扫描所有 VirtIO 设备槽位
-    for device_index in 0..VIRTIO_MAX_DEVICES {
-        let base_addr = VIRTIO_MMIO_BASE + (device_index as u64 * VIRTIO_MMIO_SIZE);
-
-        // 快速读取魔数
-        let magic = unsafe {
-            let magic_ptr = base_addr as *const u32;
-            core::ptr::read_volatile(magic_ptr)
-        };
-
-        // 检查魔数（"virt" = 0x74726976）
-        if magic == 0x74726976 {
-            // 读取设备 ID
-            let device_id = unsafe {
-                let device_id_ptr = (base_addr + 8) as *const u32;
-                core::ptr::read_volatile(device_id_ptr)
-            };
-
-            // 检查是否为块设备
-            if device_id == 2 {
-                if init_virtio_blk(base_addr).is_ok() {
-                    device_count += 1;
-                }
-            }
+    probe_virtio_mmio(&PhysMmio, &virtio_mmio_slots(), |dev| {
+        if dev.device_id == VirtIODeviceId::VirtioBlk {
+            init_virtio_blk(dev.base)
+        } else {
+            Err("Not a block device")
         }
-    }
-
-    device_count
+    })
+    .len()
 }
 
 /// 初始化 PCI 块设备
@@ -6,7 +6,8 @@
 //!
 //! 完全遵循 VirtIO 规范的队列实现
 
-use core::sync::atomic::{AtomicU16, Ordering};
+use crate::mm::dma;
+use core::sync::atomic::Ordering;
 
 /// VirtIO 描述符 (16 字节对齐)
 #[repr(C)]
@@ -76,8 +77,16 @@ pub struct VirtQueue {
     pub(crate) used: *mut UsedRing,
     /// vring 地址
     vring_addr: u64,
-    /// 下一个要分配的描述符索引
-    next_desc: AtomicU16,
+    /// vring 物理地址（设备 DMA 使用）
+    vring_phys: u64,
+    /// vring 大小（字节）
+    vring_size: usize,
+    /// 空闲描述符链表头（空闲描述符通过 `next` 字段串联）
+    free_head: u16,
+    /// 空闲描述符数量
+    num_free: u16,
+    /// 驱动已处理到的 used ring 位置
+    last_used: u16,
 }
 
 unsafe impl Send for VirtQueue {}
@@ -106,18 +115,8 @@ impl VirtQueue {
 
         let total_size = desc_size_aligned + avail_size_aligned + used_size_aligned;
 
-        let layout = alloc::alloc::Layout::from_size_align(total_size, PAGE_SIZE).ok()?;
-        let mem_ptr = unsafe { alloc::alloc::alloc(layout) as *mut u8 };
-        if mem_ptr.is_null() {
-            return None;
-        }
-
-        let addr = mem_ptr as usize;
-        if addr & (PAGE_SIZE - 1) != 0 {
-            crate::println!("virtio: ERROR: vring not page-aligned!");
-            unsafe { alloc::alloc::dealloc(mem_ptr, layout) };
-            return None;
-        }
+        // vring 使用 DMA 一致性内存：页对齐、物理连续、物理地址已知
+        let (mem_ptr, vring_phys) = dma::alloc_coherent(total_size, PAGE_SIZE)?;
 
         let desc = mem_ptr as *mut Desc;
         let avail = unsafe { (mem_ptr as usize + desc_size_aligned) as *mut AvailRing };
@@ -130,9 +129,10 @@ impl VirtQueue {
             (*used).idx = 0;
         }
 
+        // 所有描述符串成空闲链表
         for i in 0..queue_size {
             unsafe {
-                *desc.add(i as usize) = Desc { addr: 0, len: 0, flags: 0, next: 0 };
+                *desc.add(i as usize) = Desc { addr: 0, len: 0, flags: 0, next: i + 1 };
             }
         }
 
@@ -146,7 +146,11 @@ impl VirtQueue {
             avail,
             used,
             vring_addr: mem_ptr as u64,
-            next_desc: AtomicU16::new(0),
+            vring_phys,
+            vring_size: total_size,
+            free_head: 0,
+            num_free: queue_size,
+            last_used: 0,
         })
     }
 
@@ -178,13 +182,11 @@ impl VirtQueue {
         }
 
         loop {
-            // 使用内存屏障确保读取顺序
-            core::sync::atomic::fence(core::sync::atomic::Ordering::Acquire);
+            // 设备通过 DMA 更新 used ring，读取前无效化缓存
+            let used_idx_ptr = (self.used as usize + 2) as *const u16;
+            dma::invalidate_after_device(used_idx_ptr as *const u8, 2);
 
-            let used_idx = unsafe {
-                let used_idx_ptr = (self.used as usize + 2) as *const u16;
-                core::ptr::read_volatile(used_idx_ptr)
-            };
+            let used_idx = unsafe { core::ptr::read_volatile(used_idx_ptr) };
 
             if used_idx != prev_used {
                 return used_idx;
@@ -200,6 +202,33 @@ impl VirtQueue {
         }
     }
 
+    /// 取出下一个设备已完成的 used ring 元素
+    ///
+    /// 元素的 `id` 是提交时的描述符链头；没有新完成的请求时返回 None
+    pub fn pop_used(&mut self) -> Option<UsedElem> {
+        if self.used.is_null() {
+            return None;
+        }
+
+        let used_idx_ptr = (self.used as usize + 2) as *const u16;
+        dma::invalidate_after_device(used_idx_ptr as *const u8, 2);
+        let used_idx = unsafe { core::ptr::read_volatile(used_idx_ptr) };
+        if used_idx == self.last_used {
+            return None;
+        }
+
+        // 读取元素之前必须先看到 used.idx 的更新
+        core::sync::atomic::fence(Ordering::Acquire);
+        unsafe {
+            let ring_ptr = (self.used as usize + 4) as *const UsedElem;
+            let elem_ptr = ring_ptr.add(self.last_used as usize % self.queue_size as usize);
+            dma::invalidate_after_device(elem_ptr as *const u8, core::mem::size_of::<UsedElem>());
+            let elem = core::ptr::read_volatile(elem_ptr);
+            self.last_used = self.last_used.wrapping_add(1);
+            Some(elem)
+        }
+    }
+
     /// 添加描述符链到队列并通知设备
     pub fn submit(&mut self, head_idx: u16) {
         unsafe {
@@ -212,9 +241,10 @@ impl VirtQueue {
             core::ptr::write_volatile(ring_ptr.add(idx % self.queue_size as usize), head_idx);
 
             let new_idx = (idx as u16) + 1;
-            core::sync::atomic::fence(Ordering::Release);
+            dma::flush_for_device(ring_ptr.add(idx % self.queue_size as usize) as *const u8, 2);
             core::ptr::write_volatile(&mut (*avail).idx as *mut u16, new_idx);
-            core::sync::atomic::fence(Ordering::SeqCst);
+            // avail.idx 必须在设备收到通知前可见
+            dma::flush_for_device(core::ptr::addr_of!((*avail).idx) as *const u8, 2);
 
             Self::notify(self);
 
@@ -235,22 +265,66 @@ impl VirtQueue {
         }
     }
 
-    /// 分配新的描述符
+    /// 从空闲链表分配描述符
     pub fn alloc_desc(&mut self) -> Option<u16> {
-        let idx = self.next_desc.fetch_add(1, Ordering::AcqRel);
-        if idx < self.queue_size {
-            Some(idx)
-        } else {
-            None
+        if self.num_free == 0 {
+            return None;
+        }
+        let idx = self.free_head;
+        self.free_head = unsafe { (*self.desc.add(idx as usize)).next };
+        self.num_free -= 1;
+        Some(idx)
+    }
+
+    /// 空闲描述符数量
+    pub fn num_free(&self) -> u16 {
+        self.num_free
+    }
+
+    /// 把单个描述符放回空闲链表
+    fn free_desc(&mut self, idx: u16) {
+        unsafe {
+            let desc = &mut *self.desc.add(idx as usize);
+            desc.flags = 0;
+            desc.next = self.free_head;
+        }
+        self.free_head = idx;
+        self.num_free += 1;
+    }
+
+    /// 释放以 `head` 为链头的描述符链 (drivers/virtio/virtio_ring.c: detach_buf_split)
+    ///
+    /// 沿 NEXT 标志遍历整条链，把每个描述符放回空闲链表。
+    /// 必须在设备完成请求（链头出现在 used ring 中）之后调用
+    ///
+    /// # 返回
+    /// 释放的描述符数
+    pub fn free_desc_chain(&mut self, head: u16) -> usize {
+        let mut idx = head;
+        let mut freed = 0;
+        // 链长不会超过队列大小，防止损坏的 next 字段导致死循环
+        while idx < self.queue_size && freed < self.queue_size as usize {
+            let desc = unsafe { *self.desc.add(idx as usize) };
+            self.free_desc(idx);
+            freed += 1;
+            if desc.flags & desc_flags::VIRTQ_DESC_F_NEXT == 0 {
+                break;
+            }
+            idx = desc.next;
         }
+        freed
     }
 
     /// 重置描述符分配器
     ///
-    /// 在开始新的 I/O 操作前调用，以便重用描述符
-    /// 注意：这假设没有并发 I/O 操作
+    /// 把所有描述符放回空闲链表
+    /// 注意：这假设没有进行中的 I/O 操作
     pub fn reset_desc_allocator(&mut self) {
-        self.next_desc.store(0, Ordering::Release);
+        for i in 0..self.queue_size {
+            unsafe { (*self.desc.add(i as usize)).next = i + 1; }
+        }
+        self.free_head = 0;
+        self.num_free = self.queue_size;
     }
 
     /// 设置描述符内容
@@ -258,8 +332,9 @@ impl VirtQueue {
         if idx < self.queue_size {
             unsafe {
                 *self.desc.add(idx as usize) = Desc { addr, len, flags, next };
+                // 设备通过 DMA 读取描述符
+                dma::flush_for_device(self.desc.add(idx as usize) as *const u8, core::mem::size_of::<Desc>());
             }
-            core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
         }
     }
 
@@ -287,6 +362,89 @@ impl VirtQueue {
     pub fn get_notify_addr(&self) -> u64 {
         self.queue_notify
     }
+
+    /// 将 vring 内的虚拟地址转换为物理地址
+    #[inline]
+    fn vring_phys_of(&self, virt: u64) -> u64 {
+        self.vring_phys + (virt - self.vring_addr)
+    }
+
+    /// 获取描述符表物理地址
+    pub fn get_desc_phys(&self) -> u64 {
+        self.vring_phys_of(self.desc as u64)
+    }
+
+    /// 获取 Available Ring 物理地址
+    pub fn get_avail_phys(&self) -> u64 {
+        self.vring_phys_of(self.avail as u64)
+    }
+
+    /// 获取 Used Ring 物理地址
+    pub fn get_used_phys(&self) -> u64 {
+        self.vring_phys_of(self.used as u64)
+    }
+}
+
+impl Drop for VirtQueue {
+    fn drop(&mut self) {
+        unsafe { dma::free_coherent(self.vring_addr as *mut u8, self.vring_size, 4096) };
+    }
+}
+
+/// 块请求的 DMA 一致性缓冲区（请求头 + 响应状态）
+///
+/// 请求头位于缓冲区开头，响应状态紧随其后，
+/// 两者共享一次一致性分配，释放时自动归还
+pub struct BlkReqBuffer {
+    virt: *mut u8,
+    phys: u64,
+}
+
+impl BlkReqBuffer {
+    /// 响应状态在缓冲区中的偏移
+    const RESP_OFFSET: usize = core::mem::size_of::<VirtIOBlkReqHeader>();
+    /// 缓冲区大小
+    const SIZE: usize = Self::RESP_OFFSET + core::mem::size_of::<VirtIOBlkResp>();
+
+    /// 分配并填写请求头，响应状态初始化为无效值 (0xFF)
+    pub fn new(type_: u32, sector: u64) -> Option<Self> {
+        let (virt, phys) = dma::alloc_coherent(Self::SIZE, 16)?;
+        unsafe {
+            core::ptr::write_volatile(
+                virt as *mut VirtIOBlkReqHeader,
+                VirtIOBlkReqHeader { type_, reserved: 0, sector },
+            );
+            core::ptr::write_volatile(virt.add(Self::RESP_OFFSET), 0xFF);
+        }
+        dma::flush_for_device(virt, Self::SIZE);
+        Some(Self { virt, phys })
+    }
+
+    /// 请求头物理地址
+    #[inline]
+    pub fn header_phys(&self) -> u64 {
+        self.phys
+    }
+
+    /// 响应状态物理地址
+    #[inline]
+    pub fn resp_phys(&self) -> u64 {
+        self.phys + Self::RESP_OFFSET as u64
+    }
+
+    /// 读取设备写回的响应状态
+    #[inline]
+    pub fn status(&self) -> u8 {
+        let resp = unsafe { self.virt.add(Self::RESP_OFFSET) };
+        dma::invalidate_after_device(resp, core::mem::size_of::<VirtIOBlkResp>());
+        unsafe { core::ptr::read_volatile(resp) }
+    }
+}
+
+impl Drop for BlkReqBuffer {
+    fn drop(&mut self) {
+        unsafe { dma::free_coherent(self.virt, Self::SIZE, 16) };
+    }
 }
 
 #[repr(C)]
@@ -318,6 +476,13 @@ impl core::fmt::Display for VirtIOBlkResp {
     }
 }
 
+pub mod desc_flags {
+    /// 链中还有下一个描述符
+    pub const VIRTQ_DESC_F_NEXT: u16 = 1;
+    /// 设备可写（否则设备只读）
+    pub const VIRTQ_DESC_F_WRITE: u16 = 2;
+}
+
 pub mod req_type {
     pub const VIRTIO_BLK_T_IN: u32 = 0;
     pub const VIRTIO_BLK_T_OUT: u32 = 1;
@@ -263,14 +263,8 @@ impl VirtIOPCI {
 
         // ========== PCI BAR 地址分配 ==========
         // VirtIO PCI 设备需要内核分配 BAR 地址
-        // 使用固定的 MMIO 区域：0x40000000 - 0x50000000 (256MB)
-        const PCI_MMIO_BASE: u64 = 0x40000000;
-
-        // 使用全局静态变量跟踪 MMIO 偏移量，避免多个设备地址冲突
-        use core::sync::atomic::{AtomicU64, Ordering};
-        static MMIO_OFFSET: AtomicU64 = AtomicU64::new(0);
-
-        let mut mmio_offset = MMIO_OFFSET.load(Ordering::SeqCst);
+        // 从登记表的 PCIe MMIO 窗口中分配，避免与其他设备和帧缓冲区重叠
+        use crate::mm::{alloc_region, release_region, RegionKind};
 
         // 收集需要分配的 BAR 索引（去重）
         let mut bars_to_assign = alloc::vec::Vec::new();
@@ -290,34 +284,27 @@ impl VirtIOPCI {
 
         // 为每个 BAR 分配地址
         for &bar_idx in &bars_to_assign {
-            // 探测 BAR 大小
-            let bar_size = pci_config.probe_bar_size(bar_idx);
-
-            // 计算对齐后的地址
-            let aligned_addr = if mmio_offset % bar_size != 0 {
-                ((mmio_offset / bar_size) + 1) * bar_size
-            } else {
-                mmio_offset
-            };
-
-            let bar_addr = PCI_MMIO_BASE + aligned_addr;
+            // 探测 BAR 大小，BAR 按自身大小对齐
+            let bar_size = pci_config.probe_bar_size(bar_idx) as usize;
+            if bar_size == 0 {
+                return Err("Invalid BAR size");
+            }
+            let bar_addr = alloc_region(bar_size, bar_size, RegionKind::Mmio, "virtio-pci BAR")
+                .ok_or("PCI MMIO window exhausted")?;
 
             // 写入 BAR 地址并存储返回的 PCIBAR 对象
-            match pci_config.assign_bar(bar_idx, bar_addr) {
+            match pci_config.assign_bar(bar_idx, bar_addr as u64) {
                 Ok(bar_obj) => {
-                    mmio_offset = aligned_addr + bar_size;
                     assigned_bars.insert(bar_idx, bar_obj);
                 }
                 Err(e) => {
+                    release_region(bar_addr, RegionKind::Mmio);
                     crate::println!("virtio-pci: ERROR - Failed to assign BAR{}: {}", bar_idx, e);
                     return Err("Failed to assign PCI BAR");
                 }
             }
         }
 
-        // 更新全局 MMIO 偏移量，避免下一个设备地址冲突
-        MMIO_OFFSET.store(mmio_offset, Ordering::SeqCst);
-
         // ========== 使用分配的 BAR 信息 ==========
         let common_bar_obj = assigned_bars.get(&common_bar)
             .ok_or("Common CFG BAR not assigned")?;
@@ -456,31 +443,10 @@ impl VirtIOPCI {
             let _queue_size = queue_max_size;
         }
 
-        // 获取描述符表、可用环、已用环的物理地址
-        let desc_addr = virt_queue.get_desc_addr();
-        let avail_addr = virt_queue.get_avail_addr();
-        let used_addr = virt_queue.get_used_addr();
-
-        // 转换为物理地址
-        #[cfg(feature = "riscv64")]
-        let desc_phys = crate::arch::riscv64::mm::virt_to_phys(
-            crate::arch::riscv64::mm::VirtAddr::new(desc_addr)
-        ).0;
-        #[cfg(feature = "riscv64")]
-        let avail_phys = crate::arch::riscv64::mm::virt_to_phys(
-            crate::arch::riscv64::mm::VirtAddr::new(avail_addr)
-        ).0;
-        #[cfg(feature = "riscv64")]
-        let used_phys = crate::arch::riscv64::mm::virt_to_phys(
-            crate::arch::riscv64::mm::VirtAddr::new(used_addr)
-        ).0;
-
-        #[cfg(not(feature = "riscv64"))]
-        let desc_phys = desc_addr;
-        #[cfg(not(feature = "riscv64"))]
-        let avail_phys = avail_addr;
-        #[cfg(not(feature = "riscv64"))]
-        let used_phys = used_addr;
+        // 获取描述符表、可用环、已用环的物理地址（vring 位于 DMA 一致性内存中）
+        let desc_phys = virt_queue.get_desc_phys();
+        let avail_phys = virt_queue.get_avail_phys();
+        let used_phys = virt_queue.get_used_phys();
 
         // 写入描述符表地址 (64-bit)
         unsafe {
@@ -582,8 +548,7 @@ impl VirtIOPCI {
     /// # 返回
     /// 成功返回读取的字节数，失败返回错误码
     pub fn read_block(&self, sector: u64, buf: &mut [u8]) -> Result<usize, &'static str> {
-        use crate::drivers::virtio::queue::{VirtIOBlkReqHeader, VirtIOBlkResp, req_type};
-        use crate::arch::riscv64::mm::VirtAddr;
+        use crate::drivers::virtio::queue::{BlkReqBuffer, VirtIOBlkReqHeader, VirtIOBlkResp, req_type};
 
         // 分配三个描述符
         let virt_queue_opt: Option<queue::VirtQueue> = queue::VirtQueue::new(8u16,
@@ -609,55 +574,19 @@ impl VirtIOPCI {
             None => return Err("Failed to alloc response descriptor"),
         };
 
-        // 构造 VirtIO 块请求头
-        let req_header = VirtIOBlkReqHeader {
-            type_: req_type::VIRTIO_BLK_T_IN,
-            reserved: 0,
-            sector,
+        // 请求头和响应状态放在 DMA 一致性缓冲区中
+        let req = match BlkReqBuffer::new(req_type::VIRTIO_BLK_T_IN, sector) {
+            Some(req) => req,
+            None => return Err("Failed to allocate request buffer"),
         };
 
-        // 分配请求头缓冲区
-        let header_layout = alloc::alloc::Layout::new::<VirtIOBlkReqHeader>();
-        let header_ptr: *mut VirtIOBlkReqHeader;
-        unsafe {
-            header_ptr = alloc::alloc::alloc(header_layout) as *mut VirtIOBlkReqHeader;
-        }
-        if header_ptr.is_null() {
-            return Err("Failed to allocate header");
-        }
-        unsafe {
-            *header_ptr = req_header;
-        }
-
-        // 分配响应缓冲区
-        let resp_layout = alloc::alloc::Layout::new::<VirtIOBlkResp>();
-        let resp_ptr: *mut VirtIOBlkResp;
-        unsafe {
-            resp_ptr = alloc::alloc::alloc(resp_layout) as *mut VirtIOBlkResp;
-        }
-        if resp_ptr.is_null() {
-            unsafe {
-                alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-            }
-            return Err("Failed to allocate response");
-        }
-        unsafe {
-            (*resp_ptr).status = 0xFF;  // 初始化为无效状态
-        }
-
         // VirtIO 描述符标志
         const VIRTQ_DESC_F_NEXT: u16 = 1;
         const VIRTQ_DESC_F_WRITE: u16 = 2;
 
-        // 将虚拟地址转换为物理地址
-        #[cfg(feature = "riscv64")]
-        let header_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-            VirtAddr::new(header_ptr as u64)
-        ).0;
-        #[cfg(feature = "riscv64")]
-        let resp_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-            VirtAddr::new(resp_ptr as u64)
-        ).0;
+        // VirtIO 设备需要物理地址进行 DMA
+        let header_phys_addr = req.header_phys();
+        let resp_phys_addr = req.resp_phys();
 
         // 设置请求头描述符
         virt_queue.set_desc(
@@ -669,13 +598,7 @@ impl VirtIOPCI {
         );
 
         // 设置数据缓冲区描述符（设备写入）
-        // 对于 PCI VirtIO，我们需要确保缓冲区在物理内存中可访问
-        #[cfg(feature = "riscv64")]
-        let data_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-            VirtAddr::new(buf.as_ptr() as u64)
-        ).0;
-        #[cfg(not(feature = "riscv64"))]
-        let data_phys_addr = buf.as_ptr() as u64;
+        let data_phys_addr = crate::mm::dma::virt_to_phys(buf.as_ptr() as usize);
 
         virt_queue.set_desc(
             data_desc_idx,
@@ -694,6 +617,9 @@ impl VirtIOPCI {
             0,
         );
 
+        // 写回数据缓冲区中的脏缓存行，避免之后覆盖设备写入的数据
+        crate::mm::dma::flush_for_device(buf.as_ptr(), buf.len());
+
         // 提交到可用环
         virt_queue.submit(header_desc_idx);
 
@@ -704,25 +630,16 @@ impl VirtIOPCI {
         let prev_used = virt_queue.get_used();
         let new_used = virt_queue.wait_for_completion(prev_used);
 
+        // 设备已写入数据缓冲区，读取前无效化缓存
+        crate::mm::dma::invalidate_after_device(buf.as_ptr(), buf.len());
+
         if new_used == prev_used {
             // 请求失败，设备没有更新 used ring
-            unsafe {
-                alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-                alloc::alloc::dealloc(resp_ptr as *mut u8, resp_layout);
-            }
             return Err("VirtIO request timeout");
         }
 
-        // 读取响应状态
-        let status = unsafe { *resp_ptr };
-
-        // 清理缓冲区
-        unsafe {
-            alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-            alloc::alloc::dealloc(resp_ptr as *mut u8, resp_layout);
-        }
-
-        match status.status {
+        // 读取响应状态（缓冲区在 req 离开作用域时释放）
+        match req.status() {
             crate::drivers::virtio::queue::status::VIRTIO_BLK_S_OK => Ok(buf.len()),
             _ => Err("VirtIO block I/O error"),
         }
@@ -770,7 +687,7 @@ fn read_block_once(
     sector: u64,
     buf: &mut [u8]
 ) -> Result<usize, &'static str> {
-    use crate::drivers::virtio::queue::{VirtIOBlkReqHeader, VirtIOBlkResp, req_type};
+    use crate::drivers::virtio::queue::{BlkReqBuffer, VirtIOBlkReqHeader, VirtIOBlkResp, req_type};
 
     // 获取已配置的 VirtQueue（可变引用）
     let virt_queue = match crate::drivers::virtio::get_pci_device_queue_mut() {
@@ -795,63 +712,20 @@ fn read_block_once(
         None => return Err("Failed to alloc response descriptor"),
     };
 
-    // 构造 VirtIO 块请求头
-    let req_header = VirtIOBlkReqHeader {
-        type_: req_type::VIRTIO_BLK_T_IN,
-        reserved: 0,
-        sector,
+    // 请求头和响应状态放在 DMA 一致性缓冲区中
+    let req = match BlkReqBuffer::new(req_type::VIRTIO_BLK_T_IN, sector) {
+        Some(req) => req,
+        None => return Err("Failed to allocate request buffer"),
     };
 
-    // 分配请求头缓冲区
-    let header_layout = alloc::alloc::Layout::new::<VirtIOBlkReqHeader>();
-    let header_ptr: *mut VirtIOBlkReqHeader;
-    unsafe {
-        header_ptr = alloc::alloc::alloc(header_layout) as *mut VirtIOBlkReqHeader;
-    }
-    if header_ptr.is_null() {
-        return Err("Failed to allocate header");
-    }
-    unsafe {
-        *header_ptr = req_header;
-    }
-
-    // 分配响应缓冲区
-    let resp_layout = alloc::alloc::Layout::new::<VirtIOBlkResp>();
-    let resp_ptr: *mut VirtIOBlkResp;
-    unsafe {
-        resp_ptr = alloc::alloc::alloc(resp_layout) as *mut VirtIOBlkResp;
-    }
-    if resp_ptr.is_null() {
-        unsafe {
-            alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-        }
-        return Err("Failed to allocate response");
-    }
-    unsafe {
-        (*resp_ptr).status = 0xFF;  // 初始化为无效状态
-    }
-
     // VirtIO 描述符标志
     const VIRTQ_DESC_F_NEXT: u16 = 1;
     const VIRTQ_DESC_F_WRITE: u16 = 2;
 
-    // 将虚拟地址转换为物理地址
-    #[cfg(feature = "riscv64")]
-    let header_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-        crate::arch::riscv64::mm::VirtAddr::new(header_ptr as u64)
-    ).0;
-    #[cfg(feature = "riscv64")]
-    let resp_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-        crate::arch::riscv64::mm::VirtAddr::new(resp_ptr as u64)
-    ).0;
-
-    // 对于 PCI VirtIO，我们需要确保缓冲区在物理内存中可访问
-    #[cfg(feature = "riscv64")]
-    let data_phys_addr = crate::arch::riscv64::mm::virt_to_phys(
-        crate::arch::riscv64::mm::VirtAddr::new(buf.as_ptr() as u64)
-    ).0;
-    #[cfg(not(feature = "riscv64"))]
-    let data_phys_addr = buf.as_ptr() as u64;
+    // VirtIO 设备需要物理地址进行 DMA
+    let header_phys_addr = req.header_phys();
+    let resp_phys_addr = req.resp_phys();
+    let data_phys_addr = crate::mm::dma::virt_to_phys(buf.as_ptr() as usize);
 
     // 设置请求头描述符
     virt_queue.set_desc(
@@ -883,6 +757,9 @@ fn read_block_once(
     // 获取当前的期望值（提交前的 used.idx 期望值）
     let prev_expected = crate::drivers::virtio::get_expected_used_idx();
 
+    // 写回数据缓冲区中的脏缓存行，避免之后覆盖设备写入的数据
+    crate::mm::dma::flush_for_device(buf.as_ptr(), buf.len());
+
     // 提交到可用环（submit 内部会调用 notify() 并添加延迟）
     virt_queue.submit(header_desc_idx);
 
@@ -892,25 +769,16 @@ fn read_block_once(
     // 等待完成 - 等待 used.idx 达到期望值
     let new_used = virt_queue.wait_for_completion(prev_expected);
 
+    // 设备已写入数据缓冲区，读取前无效化缓存
+    crate::mm::dma::invalidate_after_device(buf.as_ptr(), buf.len());
+
     if new_used == prev_expected {
         // 请求失败，设备没有更新 used ring
-        unsafe {
-            alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-            alloc::alloc::dealloc(resp_ptr as *mut u8, resp_layout);
-        }
         return Err("VirtIO request timeout");
     }
 
-    // 读取响应状态
-    let status = unsafe { *resp_ptr };
-
-    // 清理缓冲区
-    unsafe {
-        alloc::alloc::dealloc(header_ptr as *mut u8, header_layout);
-        alloc::alloc::dealloc(resp_ptr as *mut u8, resp_layout);
-    }
-
-    match status.status {
+    // 读取响应状态（缓冲区在 req 离开作用域时释放）
+    match req.status() {
         crate::drivers::virtio::queue::status::VIRTIO_BLK_S_OK => Ok(buf.len()),
         _ => Err("VirtIO block I/O error"),
     }
@@ -121,6 +121,9 @@ pub enum Errno {
     /// Function not implemented (ENOSYS, 38)
     FunctionNotImplemented = 38,
 
+    /// Too many symbolic links encountered (ELOOP, 40)
+    TooManySymbolicLinks = 40,
+
     /// Value too large (EOVERFLOW, 75)
     ValueTooLarge = 75,
 }
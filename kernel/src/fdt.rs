@@ -0,0 +1,431 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+//! 扁平设备树 (Flattened Device Tree) 解析
+//!
+//! 完全...
+//! - `drivers/of/fdt.c` - unflatten_device_tree() / early_init_dt_scan()
+//! - `drivers/of/address.c` - of_address_to_resource()
+//! - `drivers/of/irq.c` - irq_of_parse_and_map()
+//!
+//! 核心概念：
+//! - QEMU/OpenSBI 在启动时通过寄存器 (riscv64: a1, aarch64: x0) 传递 DTB 地址
+//! - 结构块由 BEGIN_NODE / PROP / END_NODE 令牌组成，属性名存放在字符串块中
+//! - `reg` 按父节点的 `#address-cells` / `#size-cells` 解码（默认 2 / 1）
+//! - 启动时提取 UART、PLIC/GIC、virtio-mmio 节点的 `reg` 和 `interrupts`，
+//!   驱动通过 `uart_base()`、`plic_base()`、`virtio_mmio_devices()` 查询，
+//!   没有 DTB 时回退到 QEMU virt 平台的默认地址
+//! - 其他属性保留原始字节，由驱动按需解码（如 simple-framebuffer 的 `format`、`stride`）
+//!
+//! 简化实现：
+//! - 只解码第一个 `reg` 条目和 `interrupts` 的第一个单元
+//! - 不处理 `ranges` 地址转换和 `interrupt-parent` 级联
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use spin::Mutex;
+
+/// FDT 魔数
+pub const FDT_MAGIC: u32 = 0xd00dfeed;
+
+const FDT_BEGIN_NODE: u32 = 0x1;
+const FDT_END_NODE: u32 = 0x2;
+const FDT_PROP: u32 = 0x3;
+const FDT_NOP: u32 = 0x4;
+const FDT_END: u32 = 0x9;
+
+/// FDT 头大小（字节）
+const FDT_HEADER_SIZE: usize = 0x28;
+
+/// 设备节点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceNode {
+    /// 节点名（含单元地址，如 `virtio_mmio@10001000`）
+    pub name: String,
+    /// `compatible` 字符串列表
+    pub compatible: Vec<String>,
+    /// 第一个 `reg` 条目：(基地址, 大小)
+    pub reg: Option<(u64, u64)>,
+    /// `interrupts` 的第一个单元
+    pub irq: Option<u32>,
+    /// 其他属性：(属性名, 原始值)
+    pub props: Vec<(String, Vec<u8>)>,
+}
+
+impl DeviceNode {
+    /// 是否与 `compat` 兼容
+    pub fn is_compatible(&self, compat: &str) -> bool {
+        self.compatible.iter().any(|c| c == compat)
+    }
+
+    /// `reg` 的基地址
+    #[inline]
+    pub fn base(&self) -> Option<u64> {
+        self.reg.map(|(base, _)| base)
+    }
+
+    /// 属性的原始值
+    pub fn prop(&self, name: &str) -> Option<&[u8]> {
+        self.props.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_slice())
+    }
+
+    /// 单个 32 位单元的属性
+    pub fn prop_u32(&self, name: &str) -> Option<u32> {
+        be32(self.prop(name)?, 0)
+    }
+
+    /// 字符串属性（去掉结尾的 NUL）
+    pub fn prop_str(&self, name: &str) -> Option<&str> {
+        cstr(self.prop(name)?, 0).map(|(s, _)| s)
+    }
+}
+
+/// 扁平设备树
+pub struct Fdt<'a> {
+    data: &'a [u8],
+    off_dt_struct: usize,
+    off_dt_strings: usize,
+    size_dt_struct: usize,
+}
+
+/// 读取大端 u32
+#[inline]
+fn be32(data: &[u8], off: usize) -> Option<u32> {
+    let bytes = data.get(off..off.checked_add(4)?)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// 对齐到 4 字节
+#[inline]
+fn align4(off: usize) -> usize {
+    (off + 3) & !3
+}
+
+/// 读取以 NUL 结尾的字符串，返回 (字符串, 结尾 NUL 之后的偏移)
+fn cstr(data: &[u8], off: usize) -> Option<(&str, usize)> {
+    let rest = data.get(off..)?;
+    let len = rest.iter().position(|&b| b == 0)?;
+    let s = core::str::from_utf8(&rest[..len]).ok()?;
+    Some((s, off + len + 1))
+}
+
+/// 按 `cells` 个 32 位单元解码一个数值
+fn read_cells(data: &[u8], off: usize, cells: u32) -> Option<u64> {
+    let mut value = 0u64;
+    for i in 0..cells as usize {
+        value = (value << 32) | be32(data, off + i * 4)? as u64;
+    }
+    Some(value)
+}
+
+/// 正在解析的节点
+struct OpenNode {
+    node: DeviceNode,
+    /// 父节点的 (#address-cells, #size-cells)，用于解码本节点的 `reg`
+    parent_cells: (u32, u32),
+    /// 本节点的 (#address-cells, #size-cells)，用于解码子节点的 `reg`
+    cells: (u32, u32),
+}
+
+impl<'a> Fdt<'a> {
+    /// 从内存中的 DTB 创建解析器
+    ///
+    /// 魔数不匹配或头部描述的块越界时返回 None
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        if data.len() < FDT_HEADER_SIZE || be32(data, 0x00)? != FDT_MAGIC {
+            return None;
+        }
+
+        let totalsize = be32(data, 0x04)? as usize;
+        let off_dt_struct = be32(data, 0x08)? as usize;
+        let off_dt_strings = be32(data, 0x0C)? as usize;
+        let size_dt_strings = be32(data, 0x20)? as usize;
+        let size_dt_struct = be32(data, 0x24)? as usize;
+
+        if totalsize > data.len()
+            || off_dt_struct.checked_add(size_dt_struct)? > totalsize
+            || off_dt_strings.checked_add(size_dt_strings)? > totalsize
+        {
+            return None;
+        }
+
+        Some(Self {
+            data: &data[..totalsize],
+            off_dt_struct,
+            off_dt_strings,
+            size_dt_struct,
+        })
+    }
+
+    /// 从启动时传入的物理地址创建解析器
+    ///
+    /// # Safety
+    /// `addr` 必须指向可读的 DTB（或至少可读的 FDT 头部大小内存）
+    pub unsafe fn from_ptr(addr: u64) -> Option<Fdt<'static>> {
+        if addr == 0 {
+            return None;
+        }
+        let header = core::slice::from_raw_parts(addr as *const u8, FDT_HEADER_SIZE);
+        if be32(header, 0x00)? != FDT_MAGIC {
+            return None;
+        }
+        let totalsize = be32(header, 0x04)? as usize;
+        Fdt::new(core::slice::from_raw_parts(addr as *const u8, totalsize))
+    }
+
+    /// 属性名
+    fn prop_name(&self, nameoff: usize) -> Option<&'a str> {
+        cstr(self.data, self.off_dt_strings.checked_add(nameoff)?).map(|(s, _)| s)
+    }
+
+    /// 遍历结构块，返回所有节点（按结束顺序，子节点在父节点之前）
+    pub fn nodes(&self) -> Vec<DeviceNode> {
+        let mut result = Vec::new();
+        let mut stack: Vec<OpenNode> = Vec::new();
+        let data = self.data;
+        let end = self.off_dt_struct + self.size_dt_struct;
+        let mut off = self.off_dt_struct;
+
+        while off + 4 <= end {
+            let token = match be32(data, off) {
+                Some(t) => t,
+                None => break,
+            };
+            off += 4;
+
+            match token {
+                FDT_BEGIN_NODE => {
+                    let (name, next) = match cstr(data, off) {
+                        Some(v) => v,
+                        None => break,
+                    };
+                    off = align4(next);
+                    let parent_cells = stack.last().map(|n| n.cells).unwrap_or((2, 1));
+                    stack.push(OpenNode {
+                        node: DeviceNode {
+                            name: String::from(name),
+                            compatible: Vec::new(),
+                            reg: None,
+                            irq: None,
+                            props: Vec::new(),
+                        },
+                        parent_cells,
+                        cells: (2, 1),
+                    });
+                }
+                FDT_END_NODE => match stack.pop() {
+                    Some(open) => result.push(open.node),
+                    None => break,
+                },
+                FDT_PROP => {
+                    let (len, nameoff) = match (be32(data, off), be32(data, off + 4)) {
+                        (Some(len), Some(nameoff)) => (len as usize, nameoff as usize),
+                        _ => break,
+                    };
+                    let value = off + 8;
+                    off = align4(value + len);
+                    if off > end {
+                        break;
+                    }
+
+                    let (name, open) = match (self.prop_name(nameoff), stack.last_mut()) {
+                        (Some(name), Some(open)) => (name, open),
+                        _ => continue,
+                    };
+                    let bytes = &data[value..value + len];
+
+                    match name {
+                        "#address-cells" => {
+                            if let Some(v) = be32(bytes, 0) {
+                                open.cells.0 = v;
+                            }
+                        }
+                        "#size-cells" => {
+                            if let Some(v) = be32(bytes, 0) {
+                                open.cells.1 = v;
+                            }
+                        }
+                        "compatible" => {
+                            open.node.compatible = bytes
+                                .split(|&b| b == 0)
+                                .filter(|s| !s.is_empty())
+                                .filter_map(|s| core::str::from_utf8(s).ok())
+                                .map(String::from)
+                                .collect();
+                        }
+                        "reg" => {
+                            let (addr_cells, size_cells) = open.parent_cells;
+                            let base = read_cells(bytes, 0, addr_cells);
+                            let size = read_cells(bytes, addr_cells as usize * 4, size_cells);
+                            if let (Some(base), Some(size)) = (base, size) {
+                                open.node.reg = Some((base, size));
+                            }
+                        }
+                        "interrupts" => {
+                            open.node.irq = be32(bytes, 0);
+                        }
+                        _ => open.node.props.push((String::from(name), bytes.to_vec())),
+                    }
+                }
+                FDT_NOP => {}
+                FDT_END => break,
+                _ => break, // 未知令牌
+            }
+        }
+
+        result
+    }
+
+    /// 查找所有与 `compat` 兼容的节点
+    pub fn find_compatible(&self, compat: &str) -> Vec<DeviceNode> {
+        self.nodes().into_iter().filter(|n| n.is_compatible(compat)).collect()
+    }
+}
+
+/// UART 节点的 compatible 字符串
+const UART_COMPATIBLE: &[&str] = &["ns16550a", "ns16550", "arm,pl011"];
+/// 中断控制器节点的 compatible 字符串
+const INTC_COMPATIBLE: &[&str] = &["riscv,plic0", "sifive,plic-1.0.0", "arm,gic-v3"];
+/// virtio-mmio 节点的 compatible 字符串
+const VIRTIO_MMIO_COMPATIBLE: &str = "virtio,mmio";
+/// 固件预先配置的帧缓冲区节点的 compatible 字符串
+const SIMPLE_FB_COMPATIBLE: &str = "simple-framebuffer";
+
+/// 从设备树提取的平台设备信息
+#[derive(Debug, Clone, Default)]
+pub struct PlatformInfo {
+    /// 串口
+    pub uart: Option<DeviceNode>,
+    /// 中断控制器 (PLIC / GIC)
+    pub intc: Option<DeviceNode>,
+    /// virtio-mmio 传输层，按基地址升序排列
+    pub virtio_mmio: Vec<DeviceNode>,
+    /// simple-framebuffer
+    pub framebuffer: Option<DeviceNode>,
+}
+
+impl PlatformInfo {
+    /// 从设备树中提取平台设备
+    pub fn from_fdt(fdt: &Fdt) -> Self {
+        let nodes = fdt.nodes();
+        let first_of = |compats: &[&str]| {
+            nodes
+                .iter()
+                .find(|n| n.reg.is_some() && compats.iter().any(|c| n.is_compatible(c)))
+                .cloned()
+        };
+
+        let mut virtio_mmio: Vec<DeviceNode> = nodes
+            .iter()
+            .filter(|n| n.reg.is_some() && n.is_compatible(VIRTIO_MMIO_COMPATIBLE))
+            .cloned()
+            .collect();
+        virtio_mmio.sort_by_key(|n| n.base());
+
+        Self {
+            uart: first_of(UART_COMPATIBLE),
+            intc: first_of(INTC_COMPATIBLE),
+            virtio_mmio,
+            framebuffer: first_of(&[SIMPLE_FB_COMPATIBLE]),
+        }
+    }
+}
+
+/// 启动时解析得到的平台信息
+static PLATFORM: Mutex<Option<PlatformInfo>> = Mutex::new(None);
+
+/// QEMU virt 平台的默认地址（没有 DTB 时使用）
+mod defaults {
+    #[cfg(feature = "aarch64")]
+    pub const UART_BASE: usize = 0x0900_0000;
+    #[cfg(not(feature = "aarch64"))]
+    pub const UART_BASE: usize = 0x1000_0000;
+    pub const UART_IRQ: u32 = 10;
+
+    #[cfg(feature = "aarch64")]
+    pub const INTC_BASE: usize = 0x0800_0000;
+    #[cfg(not(feature = "aarch64"))]
+    pub const INTC_BASE: usize = 0x0c00_0000;
+
+    pub const VIRTIO_MMIO_BASE: u64 = 0x1000_1000;
+    pub const VIRTIO_MMIO_SIZE: u64 = 0x1000;
+    pub const VIRTIO_MMIO_COUNT: usize = crate::config::VIRTIO_MMIO_SLOTS;
+    pub const VIRTIO_MMIO_IRQ_BASE: u32 = 1;
+}
+
+/// 解析启动时传入的 DTB（需要在堆初始化之后调用）
+///
+/// # 返回
+/// 是否成功解析设备树
+pub fn init(dtb_ptr: u64) -> bool {
+    let info = match unsafe { Fdt::from_ptr(dtb_ptr) } {
+        Some(fdt) => PlatformInfo::from_fdt(&fdt),
+        None => return false,
+    };
+    *PLATFORM.lock() = Some(info);
+    true
+}
+
+/// UART 基地址
+pub fn uart_base() -> usize {
+    PLATFORM
+        .lock()
+        .as_ref()
+        .and_then(|p| p.uart.as_ref()?.base())
+        .map(|b| b as usize)
+        .unwrap_or(defaults::UART_BASE)
+}
+
+/// UART 中断号
+pub fn uart_irq() -> u32 {
+    PLATFORM
+        .lock()
+        .as_ref()
+        .and_then(|p| p.uart.as_ref()?.irq)
+        .unwrap_or(defaults::UART_IRQ)
+}
+
+/// 中断控制器 (PLIC / GIC 分发器) 基地址
+pub fn plic_base() -> usize {
+    PLATFORM
+        .lock()
+        .as_ref()
+        .and_then(|p| p.intc.as_ref()?.base())
+        .map(|b| b as usize)
+        .unwrap_or(defaults::INTC_BASE)
+}
+
+/// simple-framebuffer 节点，设备树中没有时返回 None
+pub fn simple_framebuffer() -> Option<DeviceNode> {
+    PLATFORM.lock().as_ref()?.framebuffer.clone()
+}
+
+/// virtio-mmio 传输层列表：(基地址, 大小, 中断号)
+///
+/// 设备树中没有 virtio-mmio 节点时返回 QEMU virt 的 8 个默认槽位
+pub fn virtio_mmio_devices() -> Vec<(u64, u64, u32)> {
+    let platform = PLATFORM.lock();
+    if let Some(info) = platform.as_ref() {
+        if !info.virtio_mmio.is_empty() {
+            return info
+                .virtio_mmio
+                .iter()
+                .filter_map(|n| {
+                    let (base, size) = n.reg?;
+                    Some((base, size, n.irq.unwrap_or(0)))
+                })
+                .collect();
+        }
+    }
+
+    (0..defaults::VIRTIO_MMIO_COUNT)
+        .map(|i| {
+            (
+                defaults::VIRTIO_MMIO_BASE + i as u64 * defaults::VIRTIO_MMIO_SIZE,
+                defaults::VIRTIO_MMIO_SIZE,
+                defaults::VIRTIO_MMIO_IRQ_BASE + i as u32,
+            )
+        })
+        .collect()
+}
@@ -9,7 +9,7 @@
 //! 核心概念：
 //! - `struct buffer_head`: 缓冲区头，表示一个被缓存的块
 //! - 块缓存：缓存磁盘块以提高性能
-//! - 哈希表：快速查找已缓存的块
+//! - LRU：按 (设备, 块号) 查找，超出容量时淘汰最久未使用的空闲块
 
 use alloc::boxed::Box;
 use alloc::vec;
@@ -115,10 +115,8 @@ impl BufferHead {
 
     /// 设置状态位
     pub fn set_state_bit(&self, bit: u8) {
-        // 暂时禁用锁定来调试
-        // let mut state = self.b_state.lock();
-        // state.set(bit);
-        let _ = bit; // 避免未使用警告
+        let mut state = self.b_state.lock();
+        state.set(bit);
     }
 
     /// 清除状态位
@@ -143,6 +141,11 @@ impl BufferHead {
         self.b_count.fetch_sub(1, Ordering::AcqRel) - 1
     }
 
+    /// 引用计数
+    pub fn count(&self) -> u32 {
+        self.b_count.load(Ordering::Acquire)
+    }
+
     /// 读取数据
     pub fn read(&self, offset: usize, buf: &mut [u8]) -> usize {
         if offset >= self.b_size as usize {
@@ -186,12 +189,21 @@ impl BufferHead {
     }
 }
 
+/// 块缓存容量（缓冲区个数）
+const CACHE_CAPACITY: usize = 64;
+
+/// LRU 块缓存，按 (设备, 块号) 查找
+///
+/// 超出容量时淘汰最久未使用、且没有被引用的缓冲区，脏缓冲区先写回。
+/// 顺序读取（块 N 之后读 N+1）且下一个块不在缓存中时，
+/// 用一个请求预读之后的 `blkdev::readahead()` 个块
 struct BlockCache {
-    /// 缓冲区哈希表
-    /// 索引: (设备主设备号, 块号) % 哈希表大小
-    buffers: Mutex<Vec<Option<*mut BufferHead>>>,
-    /// 哈希表大小（必须是 2 的幂）
-    hash_size: usize,
+    /// 缓冲区，按最近使用排序（末尾是最近使用的）
+    lru: Mutex<Vec<*mut BufferHead>>,
+    /// 上一次读取的 (设备, 块号)，用于检测顺序读取
+    last_access: Mutex<(usize, u64)>,
+    /// 容量
+    capacity: usize,
     /// 缓冲区大小
     block_size: u32,
 }
@@ -201,105 +213,164 @@ unsafe impl Sync for BlockCache {}
 
 impl BlockCache {
     /// 创建新的块缓存
-    fn new(hash_size: usize, block_size: u32) -> Self {
-        // 使用裸指针初始化，避免需要 Clone trait
-        let mut vec = Vec::with_capacity(hash_size);
-        for _ in 0..hash_size {
-            vec.push(None);
-        }
-
+    fn new(capacity: usize, block_size: u32) -> Self {
         Self {
-            buffers: Mutex::new(vec),
-            hash_size,
+            lru: Mutex::new(Vec::with_capacity(capacity + 1)),
+            last_access: Mutex::new((0, u64::MAX)),
+            capacity,
             block_size,
         }
     }
 
-    /// 计算哈希索引
-    fn hash_index(&self, device_major: u32, blocknr: u64) -> usize {
-        // 使用简单的哈希函数
-        let hash = (device_major as u64).wrapping_mul(31).wrapping_add(blocknr);
-        (hash as usize) & (self.hash_size - 1)
+    /// 查找缓冲区在 LRU 中的位置
+    fn position(lru: &[*mut BufferHead], device: *const blkdev::GenDisk, blocknr: u64) -> Option<usize> {
+        lru.iter().position(|&bh| unsafe {
+            (*bh).b_blocknr == blocknr && (*bh).b_device == Some(device)
+        })
     }
 
-    /// 查找缓冲区
-    fn lookup(&self, device_major: u32, blocknr: u64) -> Option<*const BufferHead> {
-        let index = self.hash_index(device_major, blocknr);
-        let buffers = self.buffers.lock();
+    /// 命中：移到最近使用端并增加引用计数
+    fn touch(lru: &mut Vec<*mut BufferHead>, index: usize) -> *mut BufferHead {
+        let bh = lru.remove(index);
+        lru.push(bh);
+        unsafe { (*bh).get(); }
+        bh
+    }
 
-        if let Some(bh_ptr) = buffers[index] {
-            unsafe {
-                let bh = &*bh_ptr;
-                if bh.b_blocknr == blocknr {
-                    if let Some(device) = bh.b_device {
-                        if (*device).major == device_major {
-                            return Some(bh_ptr);
-                        }
-                    }
-                }
-            }
+    /// 获取缓冲区，未命中时从磁盘读取；顺序读取时预读后续的块
+    fn get(&self, device: *const blkdev::GenDisk, blocknr: u64) -> Option<*mut BufferHead> {
+        let bh = self.lookup_or_read(device, blocknr)?;
+
+        let sequential = {
+            let mut last = self.last_access.lock();
+            let sequential = last.0 == device as usize && last.1.wrapping_add(1) == blocknr;
+            *last = (device as usize, blocknr);
+            sequential
+        };
+        if sequential {
+            self.readahead(device, blocknr + 1);
         }
 
-        None
+        Some(bh)
     }
 
-    /// 获取或创建缓冲区
-    fn get(&self, device: *const blkdev::GenDisk, blocknr: u64) -> Option<*mut BufferHead> {
-        unsafe {
-            let device_major = (*device).major;
+    /// 从 `start` 开始预读最多 `blkdev::readahead()` 个不在缓存中的连续块
+    ///
+    /// `start` 已在缓存中时不预读：上一次预读的窗口还没用完
+    fn readahead(&self, device: *const blkdev::GenDisk, start: u64) {
+        let window = blkdev::readahead();
+        let sectors_per_block = self.block_size as u64 / 512;
+        let capacity = unsafe { (*device).get_capacity() } as u64;
+
+        // 窗口内从 start 开始连续未缓存的块（不超过磁盘末尾）
+        let count = {
+            let lru = self.lru.lock();
+            (0..window as u64)
+                .take_while(|i| {
+                    let block = start + i;
+                    (block + 1) * sectors_per_block <= capacity
+                        && Self::position(&lru, device, block).is_none()
+                })
+                .count()
+        };
+        if count == 0 {
+            return;
+        }
 
-            // 首先尝试查找已存在的缓冲区
-            if let Some(bh) = self.lookup(device_major, blocknr) {
-                let bh_ref = &*bh;
-                bh_ref.get();
-                return Some(bh as *mut u8 as *mut BufferHead);
-            }
+        // 一个请求读取整个窗口
+        let block_size = self.block_size as usize;
+        let mut data = vec![0u8; count * block_size];
+        if blkdev::blkdev_read(device, start * sectors_per_block, &mut data).is_err() {
+            return;
+        }
 
-            // 创建新缓冲区
-            let bh = Box::new(BufferHead::new(blocknr, self.block_size));
+        let mut lru = self.lru.lock();
+        for (i, chunk) in data.chunks_exact(block_size).enumerate() {
+            let block = start + i as u64;
+            if Self::position(&lru, device, block).is_some() {
+                continue;
+            }
+            let mut bh = Box::new(BufferHead::new(block, self.block_size));
+            bh.b_data.copy_from_slice(chunk);
+            bh.set_device(device);
+            bh.set_state_bit(BufferState::BH_Uptodate);
+            // 预读的块没有使用者
+            bh.put();
+            lru.push(Box::into_raw(bh));
+        }
+        self.shrink(&mut lru);
+    }
 
-            // 从磁盘读取数据
-            let mut bh_owned = bh;
-            if let Err(_e) = blkdev::blkdev_read(
-                device,
-                blocknr * (self.block_size as u64 / 512),
-                &mut bh_owned.b_data,
-            ) {
-                return None;
+    /// 查找缓冲区，未命中时从磁盘读取
+    fn lookup_or_read(&self, device: *const blkdev::GenDisk, blocknr: u64) -> Option<*mut BufferHead> {
+        {
+            let mut lru = self.lru.lock();
+            if let Some(index) = Self::position(&lru, device, blocknr) {
+                return Some(Self::touch(&mut lru, index));
             }
+        }
 
-            bh_owned.set_device(device);
-            bh_owned.set_state_bit(BufferState::BH_Uptodate);
+        // 未命中：在锁外读取磁盘
+        let mut bh = Box::new(BufferHead::new(blocknr, self.block_size));
+        if let Err(_e) = blkdev::blkdev_read(
+            device,
+            blocknr * (self.block_size as u64 / 512),
+            &mut bh.b_data,
+        ) {
+            return None;
+        }
+        bh.set_device(device);
+        bh.set_state_bit(BufferState::BH_Uptodate);
 
-            // 转换为裸指针并泄漏
-            let bh_ptr = Box::leak(bh_owned);
+        let mut lru = self.lru.lock();
+        // 读取期间其他任务可能已经缓存了同一个块
+        if let Some(index) = Self::position(&lru, device, blocknr) {
+            return Some(Self::touch(&mut lru, index));
+        }
+        let bh_ptr = Box::into_raw(bh);
+        lru.push(bh_ptr);
+        self.shrink(&mut lru);
 
-            // 插入到哈希表
-            let index = self.hash_index(device_major, blocknr);
-            let mut buffers = self.buffers.lock();
-            buffers[index] = Some(bh_ptr);
+        Some(bh_ptr)
+    }
 
-            Some(bh_ptr)
+    /// 淘汰最久未使用的空闲缓冲区，直到不超过容量
+    ///
+    /// 所有缓冲区都在使用时允许暂时超出容量
+    fn shrink(&self, lru: &mut Vec<*mut BufferHead>) {
+        while lru.len() > self.capacity {
+            let Some(index) = lru.iter().position(|&bh| unsafe { (*bh).count() == 0 }) else {
+                return;
+            };
+            unsafe {
+                let bh = lru[index];
+                // 写回失败的脏缓冲区留在缓存中，避免丢失数据
+                if (*bh).is_dirty() && (*bh).sync().is_err() {
+                    return;
+                }
+                lru.remove(index);
+                drop(Box::from_raw(bh));
+            }
         }
     }
 
     /// 释放缓冲区
-    fn put(&self, _bh: *const BufferHead) {
-        // 简化实现：不真正释放
-        // 在完整实现中，应该减少引用计数，并在计数为 0 时回收
+    fn put(&self, bh: *const BufferHead) {
+        unsafe {
+            if (*bh).count() > 0 {
+                (*bh).put();
+            }
+        }
     }
 
-    /// 同步所有脏缓冲区
-    fn sync_all(&self) -> Result<(), i32> {
-        let buffers = self.buffers.lock();
-
-        for bh_opt in buffers.iter() {
-            if let Some(bh_ptr) = *bh_opt {
-                unsafe {
-                    let bh = &*bh_ptr;
-                    if bh.is_dirty() {
-                        bh.sync()?;
-                    }
+    /// 写回 `device` 的脏缓冲区
+    fn sync_device(&self, device: *const blkdev::GenDisk) -> Result<(), i32> {
+        let lru = self.lru.lock();
+
+        for &bh in lru.iter() {
+            unsafe {
+                if (*bh).b_device == Some(device) && (*bh).is_dirty() {
+                    (*bh).sync()?;
                 }
             }
         }
@@ -307,19 +378,33 @@ impl BlockCache {
         Ok(())
     }
 
-    /// 释放所有缓冲区
-    fn invalidate(&self) {
-        let mut buffers = self.buffers.lock();
+    /// 同步所有脏缓冲区
+    fn sync_all(&self) -> Result<(), i32> {
+        let lru = self.lru.lock();
 
-        for i in 0..buffers.len() {
-            if let Some(bh_ptr) = buffers[i] {
-                unsafe {
-                    // 重新获取所有权并释放
-                    let _ = Box::from_raw(bh_ptr);
+        for &bh in lru.iter() {
+            unsafe {
+                if (*bh).is_dirty() {
+                    (*bh).sync()?;
                 }
-                buffers[i] = None;
             }
         }
+
+        Ok(())
+    }
+
+    /// 丢弃 `device` 的所有空闲缓冲区（不写回）
+    fn invalidate_device(&self, device: *const blkdev::GenDisk) {
+        let mut lru = self.lru.lock();
+
+        lru.retain(|&bh| unsafe {
+            if (*bh).b_device == Some(device) && (*bh).count() == 0 {
+                drop(Box::from_raw(bh));
+                false
+            } else {
+                true
+            }
+        });
     }
 }
 
@@ -332,8 +417,8 @@ static mut BLOCK_CACHE: Option<BlockCache> = None;
 fn get_block_cache() -> &'static BlockCache {
     unsafe {
         if !CACHE_INIT.load(AtomicOrdering::Acquire) {
-            // 使用 16 个条目的缓存（64KB）
-            BLOCK_CACHE = Some(BlockCache::new(16, 4096));
+            // 64 个 4KB 缓冲区（256KB）
+            BLOCK_CACHE = Some(BlockCache::new(CACHE_CAPACITY, 4096));
             CACHE_INIT.store(true, AtomicOrdering::Release);
         }
         BLOCK_CACHE.as_ref().unwrap()
@@ -348,6 +433,13 @@ pub fn brelse(bh: *const BufferHead) {
     get_block_cache().put(bh)
 }
 
+/// 标记缓冲区为脏，由 `sync_device` / `sync_buffers` 或淘汰时写回
+pub fn bwrite(bh: *const BufferHead) {
+    unsafe {
+        (*bh).set_state_bit(BufferState::BH_Dirty);
+    }
+}
+
 pub fn sync_dirty_buffer(bh: *const BufferHead) -> Result<(), i32> {
     unsafe {
         let bh_ref = &*bh;
@@ -355,8 +447,24 @@ pub fn sync_dirty_buffer(bh: *const BufferHead) -> Result<(), i32> {
     }
 }
 
+/// 写回所有脏缓冲区，然后刷新每个已登记磁盘的写缓存 (sync)
 pub fn sync_buffers() -> Result<(), i32> {
-    get_block_cache().sync_all()
+    get_block_cache().sync_all()?;
+    for disk in crate::fs::devfs::block_disks() {
+        blkdev::blkdev_flush(disk)?;
+    }
+    Ok(())
+}
+
+/// 写回 `device` 的所有脏缓冲区，然后刷新设备写缓存
+pub fn sync_device(device: *const blkdev::GenDisk) -> Result<(), i32> {
+    get_block_cache().sync_device(device)?;
+    blkdev::blkdev_flush(device)
+}
+
+/// 丢弃 `device` 的缓存块（设备移除或内容在缓存之外被修改时调用）
+pub fn invalidate_device(device: *const blkdev::GenDisk) {
+    get_block_cache().invalidate_device(device)
 }
 
 pub fn init() {
@@ -9,6 +9,7 @@
 //!
 
 use crate::console;
+use crate::fs::tty;
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -50,36 +51,28 @@ impl CharDev {
     }
 }
 
+/// 从控制台读取，经过行规程 (`fs::tty`) 处理
+///
+/// 忙等待直到有可返回的数据：规范模式下是一整行，否则至少一个字节
 pub unsafe fn uart_read(buf: *mut u8, count: usize) -> isize {
-    let mut bytes_read: usize = 0;
+    if count == 0 {
+        return 0;
+    }
     let slice = core::slice::from_raw_parts_mut(buf, count);
 
-    // 忙等待第一个字符
-    while bytes_read == 0 {
-        if let Some(c) = console::getchar() {
-            slice[bytes_read] = c;
-            bytes_read += 1;
+    loop {
+        {
+            let mut ldisc = tty::console_tty();
+            tty::console_receive(&mut ldisc);
+            if let Some(n) = ldisc.read(slice) {
+                return n as isize;
+            }
         }
         // 短暂延迟，避免过度占用 CPU
         for _ in 0..1000 {
             core::arch::asm!("nop", options(nomem, nostack));
         }
     }
-
-    // 继续读取更多字符（非阻塞）
-    while bytes_read < count {
-        if let Some(c) = console::getchar() {
-            slice[bytes_read] = c;
-            bytes_read += 1;
-            if c == b'\n' {
-                break;
-            }
-        } else {
-            break;
-        }
-    }
-
-    bytes_read as isize
 }
 
 pub unsafe fn uart_write(buf: *const u8, count: usize) -> isize {
@@ -96,8 +89,96 @@ pub static UART_OPS: crate::fs::FileOps = crate::fs::FileOps {
     write: Some(uart_file_write),
     lseek: None,
     close: None,
+    poll: Some(uart_file_poll),
+    ioctl: Some(uart_file_ioctl),
+    mmap: None,
 };
 
+/// UART 总是可写，行规程有可返回的数据时可读（规范模式下需要整行）
+pub fn uart_file_poll(_file: &crate::fs::File) -> u16 {
+    use crate::fs::poll;
+
+    let mut ldisc = tty::console_tty();
+    tty::console_receive(&mut ldisc);
+    if ldisc.is_readable() {
+        poll::READABLE | poll::WRITABLE
+    } else {
+        poll::WRITABLE
+    }
+}
+
+/// UART 终端的 ioctl：TCGETS/TCSETS*、TIOCGWINSZ/TIOCSWINSZ、FIONREAD
+///
+/// 其他 TTY 命令（0x54xx）简化为成功，非 TTY 命令返回 -ENOTTY
+pub fn uart_file_ioctl(_file: &crate::fs::File, cmd: u32, arg: usize) -> isize {
+    match cmd {
+        // TCGETS - 获取终端属性
+        tty::TCGETS => {
+            if arg == 0 {
+                return -14; // EFAULT
+            }
+            let termios = tty::console_tty().termios();
+            unsafe { core::ptr::write_unaligned(arg as *mut tty::Termios, termios); }
+            0
+        }
+        // TCSETS, TCSETSW, TCSETSF - 设置终端属性（ECHO/ICANON 等）
+        tty::TCSETS | tty::TCSETSW | tty::TCSETSF => {
+            if arg == 0 {
+                return -14; // EFAULT
+            }
+            let termios = unsafe { core::ptr::read_unaligned(arg as *const tty::Termios) };
+            let mut ldisc = tty::console_tty();
+            if cmd == tty::TCSETSF {
+                ldisc.flush_input();
+            }
+            ldisc.set_termios(termios);
+            0
+        }
+        // TIOCGWINSZ - 获取窗口大小 (0x5413)
+        0x5413 => {
+            if arg == 0 {
+                return -14; // EFAULT
+            }
+            // struct winsize {
+            //     unsigned short ws_row;
+            //     unsigned short ws_col;
+            //     unsigned short ws_xpixel;
+            //     unsigned short ws_ypixel;
+            // }
+            unsafe {
+                let ptr = arg as *mut u16;
+                *ptr.offset(0) = 25;  // ws_row
+                *ptr.offset(1) = 80;  // ws_col
+                *ptr.offset(2) = 0;   // ws_xpixel
+                *ptr.offset(3) = 0;   // ws_ypixel
+            }
+            0
+        }
+        // TIOCSWINSZ - 设置窗口大小 (0x5414)
+        0x5414 => {
+            0 // 忽略设置
+        }
+        // FIONREAD - 获取可读字节数 (0x541B)
+        0x541B => {
+            if arg == 0 {
+                return -14; // EFAULT
+            }
+            let pending = tty::console_tty().pending() as i32;
+            unsafe {
+                let ptr = arg as *mut i32;
+                *ptr = pending;
+            }
+            0
+        }
+        // 其他 TTY 命令
+        _ if (cmd & 0xFF00) == 0x5400 => {
+            0 // 简化：返回成功
+        }
+        // 其他命令
+        _ => -25, // ENOTTY
+    }
+}
+
 fn uart_file_read(file: &crate::fs::File, buf: &mut [u8]) -> isize {
     if let Some(priv_data) = unsafe { *file.private_data.get() } {
         let char_dev = unsafe { &*(priv_data as *const CharDev) };
@@ -0,0 +1,98 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! 字符设备节点 (/dev)
+//!
+//! 简化的 devtmpfs：驱动在初始化时用 `register_char_device` 登记设备名和
+//! 文件操作，`file_open` 遇到 `/dev/<name>` 时直接创建使用这些操作的文件对象。
+//! 块设备（磁盘 vda 和分区 vda1）用 `register_block_device` 登记，
+//! 挂载时按设备名找到对应的 `GenDisk`。
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use spin::Mutex;
+
+use crate::drivers::blkdev::GenDisk;
+use crate::errno;
+use crate::fs::file::{File, FileFlags, FileOps};
+
+/// 设备文件所在目录
+pub const DEV_PREFIX: &str = "/dev/";
+
+/// 已登记的字符设备
+struct DevNode {
+    name: &'static str,
+    ops: &'static FileOps,
+}
+
+static DEVICES: Mutex<Vec<DevNode>> = Mutex::new(Vec::new());
+
+/// 已登记的块设备
+struct BlkNode {
+    name: String,
+    disk: *const GenDisk,
+}
+
+// GenDisk 注册后不再移动，且本身是 Send + Sync
+unsafe impl Send for BlkNode {}
+
+static BLOCK_DEVICES: Mutex<Vec<BlkNode>> = Mutex::new(Vec::new());
+
+/// 登记字符设备 `/dev/<name>`
+///
+/// # 返回
+/// 同名设备已存在时返回 `Err(-EEXIST)`
+pub fn register_char_device(name: &'static str, ops: &'static FileOps) -> Result<(), i32> {
+    let mut devices = DEVICES.lock();
+    if devices.iter().any(|dev| dev.name == name) {
+        return Err(errno::Errno::FileExists.as_neg_i32());
+    }
+    devices.push(DevNode { name, ops });
+    Ok(())
+}
+
+/// 登记块设备 `/dev/<name>`
+///
+/// # 返回
+/// 同名设备已存在时返回 `Err(-EEXIST)`
+pub fn register_block_device(name: String, disk: *const GenDisk) -> Result<(), i32> {
+    let mut devices = BLOCK_DEVICES.lock();
+    if devices.iter().any(|dev| dev.name == name) {
+        return Err(errno::Errno::FileExists.as_neg_i32());
+    }
+    devices.push(BlkNode { name, disk });
+    Ok(())
+}
+
+/// 查找块设备名（如 "vda1"）对应的磁盘或分区
+pub fn lookup_block_device(name: &str) -> Option<*const GenDisk> {
+    BLOCK_DEVICES.lock().iter().find(|dev| dev.name == name).map(|dev| dev.disk)
+}
+
+/// 已登记的整个磁盘（不含分区）
+pub fn block_disks() -> Vec<*const GenDisk> {
+    BLOCK_DEVICES.lock()
+        .iter()
+        .filter(|dev| unsafe { !(*dev.disk).is_partition() })
+        .map(|dev| dev.disk)
+        .collect()
+}
+
+/// 查找设备名对应的文件操作
+pub fn lookup(name: &str) -> Option<&'static FileOps> {
+    DEVICES.lock().iter().find(|dev| dev.name == name).map(|dev| dev.ops)
+}
+
+/// 打开设备 `/dev/<name>`，返回新的文件对象
+///
+/// # 返回
+/// 设备不存在时返回 `Err(-ENOENT)`
+pub fn open(name: &str, flags: u32) -> Result<Arc<File>, i32> {
+    let ops = lookup(name).ok_or(errno::Errno::NoSuchFileOrDirectory.as_neg_i32())?;
+    let mut file = File::new(FileFlags::new(flags));
+    file.set_ops(ops);
+    Ok(Arc::new(file))
+}
@@ -322,8 +322,8 @@ impl<'a> InodeAllocator<'a> {
             // 在位图中查找空闲 inode
             // ext4 中 inode 从 1 开始计数（0 保留）
             if let Some(inode_offset) = self.find_free_bit(&bitmap, 1, inodes_per_group) {
-                // 计算实际 inode 号
-                let inode_number = (group_idx as u64) * inodes_per_group + inode_offset;
+                // 计算实际 inode 号：位图第 n 位对应组内第 n + 1 个 inode
+                let inode_number = (group_idx as u64) * inodes_per_group + inode_offset + 1;
 
                 // 标记 inode 为已使用
                 self.mark_inode_used(group_idx as u64, inode_offset as usize, inode_bitmap_block)?;
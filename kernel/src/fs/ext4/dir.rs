@@ -74,6 +74,59 @@ impl Ext4DirEntry {
     }
 }
 
+/// 目录项头部大小（inode、rec_len、name_len、file_type）
+pub const EXT4_DIR_ENTRY_HEADER: usize = 8;
+
+/// 名字最大长度
+pub const EXT4_NAME_LEN: usize = 255;
+
+/// metadata_csum 目录块末尾校验和项的长度（struct ext4_dir_entry_tail）
+pub const EXT4_DIR_TAIL_LEN: usize = 12;
+
+/// `rec` 开头是否是目录块末尾的校验和项：inode 为 0，rec_len 为 12，name_len 为 0，
+/// file_type 为 EXT4_FT_DIR_CSUM
+pub fn is_dir_csum_tail(rec: &[u8]) -> bool {
+    rec.len() >= EXT4_DIR_TAIL_LEN
+        && rec[0..4] == [0; 4]
+        && u16::from_le_bytes([rec[4], rec[5]]) as usize == EXT4_DIR_TAIL_LEN
+        && rec[6] == 0
+        && rec[7] == file_type::EXT4_FT_DIR_CSUM
+}
+
+/// 名字长度为 `name_len` 的目录项占用的最小记录长度，按 4 字节对齐
+///
+/// 参考 EXT4_DIR_REC_LEN
+pub const fn ext4_dir_rec_len(name_len: usize) -> usize {
+    (EXT4_DIR_ENTRY_HEADER + name_len + 3) & !3
+}
+
+/// 在 `buf` 开头写入一个目录项
+///
+/// `buf` 至少要有 `ext4_dir_rec_len(name.len())` 字节
+pub fn write_dir_entry(buf: &mut [u8], inode: u32, rec_len: u16, name: &[u8], file_type: u8) {
+    buf[0..4].copy_from_slice(&inode.to_le_bytes());
+    buf[4..6].copy_from_slice(&rec_len.to_le_bytes());
+    buf[6] = name.len() as u8;
+    buf[7] = file_type;
+    buf[EXT4_DIR_ENTRY_HEADER..EXT4_DIR_ENTRY_HEADER + name.len()].copy_from_slice(name);
+}
+
+/// 由 i_mode 的文件类型位得到目录项中的 EXT4_FT_*
+pub fn file_type_from_mode(mode: u16) -> u8 {
+    use crate::fs::ext4::inode::file_type::*;
+
+    match mode & S_IFMT {
+        S_IFREG => file_type::EXT4_FT_REG_FILE,
+        S_IFDIR => file_type::EXT4_FT_DIR,
+        S_IFCHR => file_type::EXT4_FT_CHRDEV,
+        S_IFBLK => file_type::EXT4_FT_BLKDEV,
+        S_IFIFO => file_type::EXT4_FT_FIFO,
+        S_IFSOCK => file_type::EXT4_FT_SOCK,
+        S_IFLNK => file_type::EXT4_FT_SYMLINK,
+        _ => file_type::EXT4_FT_UNKNOWN,
+    }
+}
+
 pub mod file_type {
     /// 未知
     pub const EXT4_FT_UNKNOWN: u8 = 0;
@@ -91,6 +144,8 @@ pub mod file_type {
     pub const EXT4_FT_SOCK: u8 = 6;
     /// 符号链接
     pub const EXT4_FT_SYMLINK: u8 = 7;
+    /// 目录块校验和项（不是真正的目录项）
+    pub const EXT4_FT_DIR_CSUM: u8 = 0xDE;
 }
 
 pub struct Ext4DirIterator {
@@ -11,6 +11,9 @@ use crate::fs::bio;
 /// Extent header magic number
 pub const EXT4_EXT_MAGIC: u16 = 0xF30A;
 
+/// 已初始化 extent 的最大长度，ee_len 大于它表示未初始化的 extent（EXT_INIT_MAX_LEN）
+pub const EXT_INIT_MAX_LEN: u16 = 1 << 15;
+
 /// Extent header (in i_block or external block)
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -106,6 +109,60 @@ pub fn ext4_ext_get_block(
     find_block_in_extent_tree(fs, i_block, logical_block, 0)
 }
 
+/// 把物理块 `phys` 映射到逻辑块 `logical_block`，追加到 i_block 中 extent 叶子的末尾
+///
+/// 与最后一个 extent 在逻辑上和物理上都相邻时延长它，否则在叶子中加入一个新的 extent。
+/// 只支持 depth 为 0 的 extent 树，且 `logical_block` 必须在所有已有 extent 之后
+///
+/// # 返回
+/// 叶子已满时返回 ENOSPC（还不支持分裂 extent 树）
+pub fn ext4_ext_append_block(i_block: &mut [u32; 15], logical_block: u32, phys: u64) -> Result<(), i32> {
+    let base = i_block.as_mut_ptr() as *mut u8;
+    let header = unsafe { &mut *(base as *mut Ext4ExtentHeader) };
+    if header.eh_magic != EXT4_EXT_MAGIC || header.eh_depth != 0 {
+        return Err(errno::Errno::IOError.as_neg_i32());
+    }
+
+    // i_block 共 60 字节，除去头部最多放 4 个 extent
+    let header_size = core::mem::size_of::<Ext4ExtentHeader>();
+    let capacity = (core::mem::size_of::<[u32; 15]>() - header_size) / core::mem::size_of::<Ext4Extent>();
+    let max = (header.eh_max as usize).min(capacity);
+    let count = header.eh_entries as usize;
+    if count > max {
+        return Err(errno::Errno::IOError.as_neg_i32());
+    }
+    let extents = unsafe {
+        core::slice::from_raw_parts_mut(base.add(header_size) as *mut Ext4Extent, max)
+    };
+
+    if let Some(last) = extents[..count].last_mut() {
+        let initialized = last.ee_len <= EXT_INIT_MAX_LEN;
+        let len = if initialized { last.ee_len } else { last.ee_len - EXT_INIT_MAX_LEN };
+        let end = last.ee_block + len as u32;
+        if logical_block < end {
+            return Err(errno::Errno::InvalidArgument.as_neg_i32());
+        }
+        if initialized && last.ee_len < EXT_INIT_MAX_LEN
+            && logical_block == end && phys == last.start_block() + len as u64
+        {
+            last.ee_len += 1;
+            return Ok(());
+        }
+    }
+
+    if count == max {
+        return Err(errno::Errno::NoSpaceLeftOnDevice.as_neg_i32());
+    }
+    extents[count] = Ext4Extent {
+        ee_block: logical_block,
+        ee_len: 1,
+        ee_start_hi: (phys >> 32) as u16,
+        ee_start_lo: phys as u32,
+    };
+    header.eh_entries += 1;
+    Ok(())
+}
+
 /// 在 extent 树中查找逻辑块
 fn find_block_in_extent_tree(
     fs: &crate::fs::ext4::Ext4FileSystem,
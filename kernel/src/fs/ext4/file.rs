@@ -71,6 +71,10 @@ pub fn ext4_file_write(
     offset: u64,
     buf: &[u8],
 ) -> Result<usize, i32> {
+    if fs.read_only {
+        return Err(errno::Errno::ReadOnlyFileSystem.as_neg_i32());
+    }
+
     let block_size = fs.block_size as u64;
     let to_write = buf.len() as u64;
 
@@ -109,28 +113,16 @@ pub fn ext4_file_write(
             Err(e) => return Err(e),
         };
 
-        unsafe {
-            let bh = bio::bread(fs.device, block_num)
-                .ok_or(errno::Errno::IOError.as_neg_i32())?;
-
-            let data = &mut (*bh).b_data;
-            let remaining = to_write as usize - total_written;
-            let available_in_block = block_size as usize - block_offset;
-            let write_in_block = core::cmp::min(remaining, available_in_block);
+        let remaining = to_write as usize - total_written;
+        let available_in_block = block_size as usize - block_offset;
+        let write_in_block = core::cmp::min(remaining, available_in_block);
 
-            // 写入数据到块
-            data[block_offset..block_offset + write_in_block]
-                .copy_from_slice(&buf[buf_offset..buf_offset + write_in_block]);
+        // 写入数据到块
+        fs.write_block(block_num, block_offset, &buf[buf_offset..buf_offset + write_in_block])?;
 
-            // 标记为脏
-            (*bh).set_state_bit(crate::fs::bio::BufferState::BH_Dirty);
-            bio::sync_dirty_buffer(bh)?;
-            bio::brelse(bh);
-
-            total_written += write_in_block;
-            buf_offset += write_in_block;
-            current_offset += write_in_block as u64;
-        }
+        total_written += write_in_block;
+        buf_offset += write_in_block;
+        current_offset += write_in_block as u64;
     }
 
     // 更新文件大小
@@ -138,8 +130,12 @@ pub fn ext4_file_write(
         inode.set_size(end_offset);
     }
 
-    // TODO: 更新 inode 时间戳
-    // TODO: 同步 inode 到磁盘
+    let now = crate::drivers::timer::ktime_get_real_ts().0 as u32;
+    inode.mtime = now;
+    inode.ctime = now;
+
+    // 同步 inode 到磁盘
+    fs.write_inode(inode.ino, inode)?;
 
     Ok(total_written)
 }
@@ -181,6 +177,7 @@ fn allocate_blocks_for_file(
                     // 间接块
                     allocate_indirect_block(fs, inode, block_index, data_block, &allocator)?;
                 }
+                inode.blocks += block_size / 512;
             }
             Err(e) => {
                 // 分配失败，回滚已分配的块
@@ -210,6 +207,7 @@ fn allocate_indirect_block(
             // 需要分配单级间接块
             let indirect_block = allocator.alloc_block()?;
             inode.block[12] = indirect_block as u32;
+            inode.blocks += block_size / 512;
 
             // 清零间接块
             unsafe {
@@ -243,6 +241,7 @@ fn allocate_indirect_block(
                 // 需要分配二级间接块
                 let double_block = allocator.alloc_block()?;
                 inode.block[13] = double_block as u32;
+                inode.blocks += block_size / 512;
 
                 // 清零
                 unsafe {
@@ -273,6 +272,7 @@ fn allocate_indirect_block(
             if indirect_block == 0 {
                 // 需要分配单级间接块
                 indirect_block = allocator.alloc_block()?;
+                inode.blocks += block_size / 512;
 
                 // 清零
                 unsafe {
@@ -313,6 +313,88 @@ fn allocate_indirect_block(
     Ok(())
 }
 
+/// 把文件截断或扩展到 `size` 字节，并把 inode 写回磁盘
+///
+/// 扩展时分配清零的数据块；缩短时清零新的最后一块中 `size` 之后的部分，
+/// 释放之后的直接块和一级间接块。
+///
+/// # 返回
+/// 只读挂载返回 EROFS；缩短 extent 文件或用到二级间接块的文件返回 ENOSYS
+pub fn ext4_truncate(
+    fs: &crate::fs::ext4::Ext4FileSystem,
+    inode: &mut crate::fs::ext4::inode::Ext4Inode,
+    size: u64,
+) -> Result<(), i32> {
+    if fs.read_only {
+        return Err(errno::Errno::ReadOnlyFileSystem.as_neg_i32());
+    }
+    if !inode.is_reg() {
+        return Err(errno::Errno::InvalidArgument.as_neg_i32());
+    }
+
+    let block_size = fs.block_size as u64;
+    let old_blocks = inode.get_size().div_ceil(block_size);
+    let new_blocks = size.div_ceil(block_size);
+
+    if new_blocks > old_blocks {
+        allocate_blocks_for_file(fs, inode, new_blocks)?;
+    } else if size < inode.get_size() {
+        let pointers_per_block = block_size / 4;
+        if new_blocks < old_blocks && (inode.has_extent() || old_blocks > 12 + pointers_per_block) {
+            return Err(errno::Errno::FunctionNotImplemented.as_neg_i32());
+        }
+
+        // 清零最后一块的尾部，之后再扩展时读出的是 0
+        let tail = (size % block_size) as usize;
+        if tail != 0 {
+            let block = inode.get_data_block(fs, new_blocks - 1)?;
+            if block != 0 {
+                let zeros = alloc::vec![0u8; block_size as usize - tail];
+                fs.write_block(block, tail, &zeros)?;
+            }
+        }
+
+        let allocator = crate::fs::ext4::allocator::BlockAllocator::new(fs);
+        for i in new_blocks..old_blocks {
+            let block = inode.get_data_block(fs, i)?;
+            if block == 0 {
+                continue;
+            }
+            allocator.free_block(block)?;
+            inode.blocks = inode.blocks.saturating_sub(block_size / 512);
+            if i < 12 {
+                inode.block[i as usize] = 0;
+            } else if new_blocks > 12 {
+                indirect::write_indirect_block(fs, inode.block[12] as u64, (i - 12) as usize, 0)?;
+            }
+        }
+
+        // 一级间接块中不再有数据块时释放它
+        if new_blocks <= 12 && old_blocks > 12 && inode.block[12] != 0 {
+            allocator.free_block(inode.block[12] as u64)?;
+            inode.block[12] = 0;
+            inode.blocks = inode.blocks.saturating_sub(block_size / 512);
+        }
+    }
+
+    inode.set_size(size);
+    let now = crate::drivers::timer::ktime_get_real_ts().0 as u32;
+    inode.mtime = now;
+    inode.ctime = now;
+    fs.write_inode(inode.ino, inode)
+}
+
+/// 修改文件的权限位并写回 inode，文件类型位保持不变
+pub fn ext4_chmod(
+    fs: &crate::fs::ext4::Ext4FileSystem,
+    inode: &mut crate::fs::ext4::inode::Ext4Inode,
+    mode: u16,
+) -> Result<(), i32> {
+    inode.mode = (inode.mode & crate::fs::ext4::inode::file_type::S_IFMT) | (mode & 0o7777);
+    inode.ctime = crate::drivers::timer::ktime_get_real_ts().0 as u32;
+    fs.write_inode(inode.ino, inode)
+}
+
 pub fn ext4_file_lseek(
     inode: &crate::fs::ext4::inode::Ext4Inode,
     offset: isize,
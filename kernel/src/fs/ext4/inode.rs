@@ -12,6 +12,9 @@ use alloc::vec::Vec;
 
 use crate::errno;
 
+/// i_block 中的块指针数
+pub const EXT4_N_BLOCKS: usize = 15;
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct Ext4InodeOnDisk {
@@ -129,6 +132,23 @@ impl Ext4Inode {
         }
     }
 
+    /// 写回磁盘格式
+    ///
+    /// 只覆盖本结构体建模的字段，`disk` 中其余字段（扩展时间戳、校验和等）保持原值
+    pub fn to_disk(&self, disk: &mut Ext4InodeOnDisk) {
+        disk.i_mode = self.mode;
+        disk.i_uid = self.uid;
+        disk.i_gid = self.gid;
+        disk.i_size = self.size as u32;
+        disk.i_blocks = self.blocks as u32;
+        disk.i_links_count = self.links_count;
+        disk.i_flags = self.flags;
+        disk.i_block = self.block;
+        disk.i_atime = self.atime;
+        disk.i_mtime = self.mtime;
+        disk.i_ctime = self.ctime;
+    }
+
     /// 检查是否是目录
     pub fn is_dir(&self) -> bool {
         (self.mode & 0xF000) == 0x4000
@@ -144,6 +164,11 @@ impl Ext4Inode {
         (self.mode & 0xF000) == 0xA000
     }
 
+    /// 检查是否是快速符号链接（目标直接存放在 i_block 中）
+    pub fn is_fast_symlink(&self) -> bool {
+        self.is_symlink() && self.blocks == 0 && (self.size as usize) < EXT4_N_BLOCKS * 4
+    }
+
     /// 检查是否使用 extent
     pub fn has_extent(&self) -> bool {
         (self.flags & 0x80000) != 0
@@ -34,6 +34,14 @@ use crate::fs::superblock::{FileSystemType, FsContext, SuperBlock};
 
 pub const EXT4_SUPER_MAGIC: u16 = 0xEF53;
 
+/// 一次路径查找中最多解析的符号链接数（MAXSYMLINKS）
+pub const EXT4_MAX_SYMLINKS: usize = 40;
+
+/// 块组描述符带校验和（s_feature_ro_compat）
+pub const EXT4_FEATURE_RO_COMPAT_GDT_CSUM: u32 = 0x0010;
+/// 所有元数据带 crc32c 校验和（s_feature_ro_compat）
+pub const EXT4_FEATURE_RO_COMPAT_METADATA_CSUM: u32 = 0x0400;
+
 pub struct Ext4FileSystem {
     /// 块设备
     pub device: *const blkdev::GenDisk,
@@ -57,6 +65,10 @@ pub struct Ext4FileSystem {
     pub total_blocks: u64,
     /// 总 inode 数
     pub total_inodes: u32,
+    /// 只读挂载，拒绝 write_inode / write_block
+    pub read_only: bool,
+    /// 只读兼容特性标志（s_feature_ro_compat）
+    pub feature_ro_compat: u32,
 }
 
 unsafe impl Send for Ext4FileSystem {}
@@ -77,9 +89,20 @@ impl Ext4FileSystem {
             group_count: 0,
             total_blocks: 0,
             total_inodes: 0,
+            read_only: true,
+            feature_ro_compat: 0,
         }
     }
 
+    /// 元数据是否带校验和（GDT_CSUM 或 METADATA_CSUM）
+    ///
+    /// 写路径不计算校验和，这样的文件系统只能只读挂载
+    pub fn has_checksums(&self) -> bool {
+        self.feature_ro_compat
+            & (EXT4_FEATURE_RO_COMPAT_GDT_CSUM | EXT4_FEATURE_RO_COMPAT_METADATA_CSUM)
+            != 0
+    }
+
     /// 初始化 ext4 文件系统
     ///
     /// 读取超级块和块组描述符
@@ -162,6 +185,7 @@ impl Ext4FileSystem {
             self.total_blocks = total_blocks as u64;
             self.total_inodes = total_inodes;
             self.group_descs = group_descs;
+            self.feature_ro_compat = ext4_sb.s_feature_ro_compat;
 
             Ok(())
         }
@@ -202,6 +226,78 @@ impl Ext4FileSystem {
         }
     }
 
+    /// 把 inode 写回磁盘
+    ///
+    /// 读出 inode 所在的 inode 表块，覆盖其中这个 inode 的字段后同步写回
+    ///
+    /// # 返回
+    /// 只读挂载时返回 EROFS；文件大小超出 32 位 i_size 时返回 EFBIG
+    pub fn write_inode(&self, ino: u32, inode: &inode::Ext4Inode) -> Result<(), i32> {
+        if self.read_only {
+            return Err(errno::Errno::ReadOnlyFileSystem.as_neg_i32());
+        }
+        if ino == 0 {
+            return Err(errno::Errno::InvalidArgument.as_neg_i32());
+        }
+        // 还没有写 i_size_high / i_blocks_high
+        if inode.size > u32::MAX as u64 || inode.blocks > u32::MAX as u64 {
+            return Err(errno::Errno::FileTooLarge.as_neg_i32());
+        }
+
+        unsafe {
+            let group = (ino - 1) / self.inodes_per_group;
+            let index = (ino - 1) % self.inodes_per_group;
+
+            if group as usize >= self.group_descs.len() {
+                return Err(errno::Errno::NoSuchFileOrDirectory.as_neg_i32());
+            }
+
+            let gd = &self.group_descs[group as usize];
+
+            let inodes_per_block = self.block_size / (self.inode_size as u32);
+            let inode_block = gd.bg_inode_table + (index / inodes_per_block);
+            let inode_offset = ((index % inodes_per_block) * (self.inode_size as u32)) as usize;
+
+            let bh = bio::bread(self.device, inode_block as u64)
+                .ok_or(errno::Errno::IOError.as_neg_i32())?;
+
+            let data = &mut (*bh).b_data;
+            let ext4_inode = &mut *(data.as_mut_ptr().add(inode_offset) as *mut inode::Ext4InodeOnDisk);
+            inode.to_disk(ext4_inode);
+
+            (*bh).set_state_bit(crate::fs::bio::BufferState::BH_Dirty);
+            let result = bio::sync_dirty_buffer(bh);
+            bio::brelse(bh);
+            result
+        }
+    }
+
+    /// 覆盖一个已分配数据块中从 `offset` 开始的内容并同步写回
+    ///
+    /// 不分配新块，`block` 必须是文件已经拥有的块
+    pub fn write_block(&self, block: u64, offset: usize, data: &[u8]) -> Result<(), i32> {
+        if self.read_only {
+            return Err(errno::Errno::ReadOnlyFileSystem.as_neg_i32());
+        }
+        if block == 0 || block >= self.total_blocks || offset + data.len() > self.block_size as usize {
+            return Err(errno::Errno::InvalidArgument.as_neg_i32());
+        }
+
+        unsafe {
+            let bh = bio::bread(self.device, block)
+                .ok_or(errno::Errno::IOError.as_neg_i32())?;
+
+            {
+                let buffer = &mut *bh;
+                buffer.b_data[offset..offset + data.len()].copy_from_slice(data);
+                buffer.set_state_bit(crate::fs::bio::BufferState::BH_Dirty);
+            }
+            let result = bio::sync_dirty_buffer(bh);
+            bio::brelse(bh);
+            result
+        }
+    }
+
     /// 获取根 inode
     pub fn get_root_inode(&self) -> Result<inode::Ext4Inode, i32> {
         // ext4 中根 inode 的编号总是 2
@@ -298,34 +394,342 @@ impl Ext4FileSystem {
         }
     }
 
+    /// 在目录 `dir` 中添加名为 `name` 的目录项，指向 `child_ino`
+    ///
+    /// 依次检查目录已有的数据块：可以复用的空闲目录项，或者 rec_len 比实际所需长、
+    /// 尾部空余足够放下新目录项的目录项（拆分为两项）。块末尾的校验和项不会被复用。
+    /// 都放不下时从分配器申请一个新块追加到目录末尾（直接块指针或 extent 叶子），
+    /// 并把目录 inode 写回磁盘。
+    ///
+    /// # 参数
+    /// - `file_type`: `dir::file_type` 中的 EXT4_FT_*
+    ///
+    /// # 返回
+    /// 名字已存在时返回 EEXIST；文件系统带元数据校验和时返回 EROFS（不计算目录块校验和）；
+    /// 直接块用完或 extent 叶子已满时返回 ENOSPC（还不支持间接块目录和分裂 extent 树）
+    pub fn add_dir_entry(
+        &self,
+        dir: &mut inode::Ext4Inode,
+        name: &str,
+        child_ino: u32,
+        file_type: u8,
+    ) -> Result<(), i32> {
+        if self.read_only || self.has_checksums() {
+            return Err(errno::Errno::ReadOnlyFileSystem.as_neg_i32());
+        }
+        if !dir.is_dir() {
+            return Err(errno::Errno::NotADirectory.as_neg_i32());
+        }
+        let name_bytes = name.as_bytes();
+        if name_bytes.is_empty() || name_bytes.contains(&b'/') || child_ino == 0 {
+            return Err(errno::Errno::InvalidArgument.as_neg_i32());
+        }
+        if name_bytes.len() > dir::EXT4_NAME_LEN {
+            return Err(-errno::constants::ENAMETOOLONG);
+        }
+        if self.lookup(dir, name).is_ok() {
+            return Err(errno::Errno::FileExists.as_neg_i32());
+        }
+
+        let needed = dir::ext4_dir_rec_len(name_bytes.len());
+        let block_size = self.block_size as usize;
+
+        // 在已有的块中查找空间
+        for block in dir.get_data_blocks(self)? {
+            if block == 0 {
+                continue;
+            }
+
+            let data = unsafe {
+                let bh = bio::bread(self.device, block)
+                    .ok_or(errno::Errno::IOError.as_neg_i32())?;
+                let buffer = &*bh;
+                let data = buffer.b_data[..block_size].to_vec();
+                bio::brelse(bh);
+                data
+            };
+
+            let mut offset = 0;
+            while offset + dir::EXT4_DIR_ENTRY_HEADER <= block_size {
+                let entry_ino = u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+                let rec_len = u16::from_le_bytes([data[offset + 4], data[offset + 5]]) as usize;
+                let name_len = data[offset + 6] as usize;
+
+                if rec_len < dir::EXT4_DIR_ENTRY_HEADER || rec_len % 4 != 0 || offset + rec_len > block_size {
+                    return Err(errno::Errno::IOError.as_neg_i32());
+                }
+
+                if dir::is_dir_csum_tail(&data[offset..]) {
+                    // 校验和项总在块末尾
+                    break;
+                }
+
+                if entry_ino == 0 && rec_len >= needed {
+                    // 复用空闲目录项，保留它的 rec_len
+                    let mut record = data[offset..offset + rec_len].to_vec();
+                    dir::write_dir_entry(&mut record, child_ino, rec_len as u16, name_bytes, file_type);
+                    return self.write_block(block, offset, &record);
+                }
+
+                let used = dir::ext4_dir_rec_len(name_len);
+                if entry_ino != 0 && rec_len >= used + needed {
+                    // 拆分：原目录项缩短到实际长度，剩余部分给新目录项
+                    let mut record = data[offset..offset + rec_len].to_vec();
+                    record[4..6].copy_from_slice(&(used as u16).to_le_bytes());
+                    dir::write_dir_entry(&mut record[used..], child_ino, (rec_len - used) as u16, name_bytes, file_type);
+                    return self.write_block(block, offset, &record);
+                }
+
+                offset += rec_len;
+            }
+        }
+
+        // 没有空间，追加一个新的目录块
+        let block_index = dir.size.div_ceil(block_size as u64);
+        if !dir.has_extent() && block_index >= 12 {
+            return Err(errno::Errno::NoSpaceLeftOnDevice.as_neg_i32());
+        }
+
+        let allocator = allocator::BlockAllocator::new(self);
+        let new_block = allocator.alloc_block()?;
+
+        let mut record = alloc::vec![0u8; block_size];
+        dir::write_dir_entry(&mut record, child_ino, block_size as u16, name_bytes, file_type);
+        self.write_block(new_block, 0, &record)?;
+
+        if dir.has_extent() {
+            if let Err(e) = extent::ext4_ext_append_block(&mut dir.block, block_index as u32, new_block) {
+                let _ = allocator.free_block(new_block);
+                return Err(e);
+            }
+        } else {
+            dir.block[block_index as usize] = new_block as u32;
+        }
+        dir.size = (block_index + 1) * block_size as u64;
+        dir.blocks += (block_size / 512) as u64;
+        self.write_inode(dir.ino, dir)
+    }
+
+    /// 在 `path` 创建一个新 inode 并在父目录中加入目录项
+    ///
+    /// 常规文件创建为空文件；目录分配一个数据块写入 "." 和 ".."，父目录的链接数加一。
+    /// 新 inode 使用直接块指针，不设置 extent 标志
+    ///
+    /// # 参数
+    /// - `mode`: 包含文件类型位的 i_mode（如 0o100644）
+    ///
+    /// # 返回
+    /// 新 inode 的编号；父目录不存在返回 ENOENT，名字已存在返回 EEXIST
+    pub fn create(&self, path: &str, mode: u16) -> Result<u32, i32> {
+        if self.read_only {
+            return Err(errno::Errno::ReadOnlyFileSystem.as_neg_i32());
+        }
+
+        let (parent_path, name) = split_parent(path)?;
+        let (parent_ino, mut parent) = self.lookup_path(parent_path, true)?;
+        if !parent.is_dir() {
+            return Err(errno::Errno::NotADirectory.as_neg_i32());
+        }
+        if self.lookup(&parent, name).is_ok() {
+            return Err(errno::Errno::FileExists.as_neg_i32());
+        }
+
+        let ino = allocator::InodeAllocator::new(self).alloc_inode()?;
+        let now = crate::drivers::timer::ktime_get_real_ts().0 as u32;
+        let mut new = inode::Ext4Inode {
+            ino,
+            mode,
+            uid: 0,
+            gid: 0,
+            size: 0,
+            blocks: 0,
+            links_count: 1,
+            flags: 0,
+            block: [0; inode::EXT4_N_BLOCKS],
+            atime: now,
+            mtime: now,
+            ctime: now,
+        };
+
+        let result = self.init_new_inode(&mut new, parent_ino)
+            .and_then(|()| self.add_dir_entry(&mut parent, name, ino, dir::file_type_from_mode(mode)));
+        if let Err(e) = result {
+            // 尽力回收已分配的 inode 和目录块
+            if new.is_dir() && new.block[0] != 0 {
+                let _ = allocator::BlockAllocator::new(self).free_block(new.block[0] as u64);
+            }
+            let _ = allocator::InodeAllocator::new(self).free_inode(ino);
+            return Err(e);
+        }
+
+        if new.is_dir() {
+            // 子目录的 ".." 指向父目录
+            parent.links_count += 1;
+            self.write_inode(parent_ino, &parent)?;
+        }
+
+        Ok(ino)
+    }
+
+    /// 写入新 inode，目录还要分配并初始化第一个数据块
+    fn init_new_inode(&self, new: &mut inode::Ext4Inode, parent_ino: u32) -> Result<(), i32> {
+        if new.is_dir() {
+            let block_size = self.block_size as usize;
+            let block = allocator::BlockAllocator::new(self).alloc_block()?;
+            new.block[0] = block as u32;
+
+            let dot_len = dir::ext4_dir_rec_len(1);
+            let mut data = alloc::vec![0u8; block_size];
+            dir::write_dir_entry(&mut data, new.ino, dot_len as u16, b".", dir::file_type::EXT4_FT_DIR);
+            dir::write_dir_entry(
+                &mut data[dot_len..],
+                parent_ino,
+                (block_size - dot_len) as u16,
+                b"..",
+                dir::file_type::EXT4_FT_DIR,
+            );
+            self.write_block(block, 0, &data)?;
+
+            new.size = block_size as u64;
+            new.blocks = (block_size / 512) as u64;
+            new.links_count = 2;
+        }
+        self.write_inode(new.ino, new)
+    }
+
+    /// 为已有的非目录 inode 创建硬链接 `newpath`
+    ///
+    /// # 返回
+    /// 源是目录返回 EPERM，`newpath` 已存在返回 EEXIST
+    pub fn link(&self, oldpath: &str, newpath: &str) -> Result<(), i32> {
+        if self.read_only {
+            return Err(errno::Errno::ReadOnlyFileSystem.as_neg_i32());
+        }
+
+        let (ino, mut target) = self.lookup_path(oldpath, false)?;
+        if target.is_dir() {
+            return Err(errno::Errno::OperationNotPermitted.as_neg_i32());
+        }
+        if target.links_count == u16::MAX {
+            return Err(-errno::constants::EMLINK);
+        }
+
+        let (parent_path, name) = split_parent(newpath)?;
+        let (_, mut parent) = self.lookup_path(parent_path, true)?;
+        self.add_dir_entry(&mut parent, name, ino, dir::file_type_from_mode(target.mode))?;
+
+        target.links_count += 1;
+        target.ctime = crate::drivers::timer::ktime_get_real_ts().0 as u32;
+        self.write_inode(ino, &target)
+    }
+
+    /// 读取符号链接的目标
+    ///
+    /// 目标不超过 60 字节且没有数据块的是快速符号链接，目标直接存放在 i_block 中；
+    /// 否则目标存放在数据块中
+    pub fn read_symlink(&self, inode: &inode::Ext4Inode) -> Result<String, i32> {
+        if !inode.is_symlink() {
+            return Err(errno::Errno::InvalidArgument.as_neg_i32());
+        }
+
+        let len = inode.get_size() as usize;
+        let target = if inode.is_fast_symlink() {
+            let mut inline = [0u8; inode::EXT4_N_BLOCKS * 4];
+            for (i, word) in inode.block.iter().enumerate() {
+                inline[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+            }
+            inline[..len].to_vec()
+        } else {
+            if len > self.block_size as usize {
+                return Err(errno::Errno::IOError.as_neg_i32());
+            }
+            let mut buf = alloc::vec![0u8; len];
+            let n = inode.read_data(self, 0, &mut buf)?;
+            buf.truncate(n);
+            buf
+        };
+
+        String::from_utf8(target).map_err(|_| errno::Errno::IOError.as_neg_i32())
+    }
+
     /// 根据路径查找 inode
     ///
+    /// 中间分量的符号链接总是被解析：绝对目标从根目录开始，相对目标从链接所在的目录开始。
+    /// 解析超过 `EXT4_MAX_SYMLINKS` 次返回 ELOOP。
+    ///
     /// # 参数
     /// - `path`: 文件路径（绝对路径，如 "/bin/sh"）
+    /// - `follow_last`: 是否跟随最后一个分量的符号链接；为 false 时返回链接本身
+    ///   （O_NOFOLLOW、link 的源路径）
     ///
     /// # 返回
     /// inode 编号和 inode 结构
-    pub fn lookup_path(&self, path: &str) -> Result<(u32, inode::Ext4Inode), i32> {
-        // 解析路径
-        let path_parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    pub fn lookup_path(&self, path: &str, follow_last: bool) -> Result<(u32, inode::Ext4Inode), i32> {
+        // 待解析的路径分量，栈顶是下一个分量
+        let mut pending: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .rev()
+            .map(|s| s.to_string())
+            .collect();
 
         // 从根 inode 开始
         let mut current_inode = self.get_root_inode()?;
         let mut current_ino = 2u32; // 根 inode 编号
+        let mut links = 0;
 
         // 遍历路径
-        for part in path_parts.iter() {
-            let entry = self.lookup(&current_inode, part)?;
+        while let Some(part) = pending.pop() {
+            if !current_inode.is_dir() {
+                return Err(errno::Errno::NotADirectory.as_neg_i32());
+            }
+
+            let entry = self.lookup(&current_inode, &part)?;
 
             // 读取下一级 inode
+            let next_inode = self.read_inode(entry.inode)?;
+
+            if next_inode.is_symlink() && (follow_last || !pending.is_empty()) {
+                links += 1;
+                if links > EXT4_MAX_SYMLINKS {
+                    return Err(errno::Errno::TooManySymbolicLinks.as_neg_i32());
+                }
+
+                let target = self.read_symlink(&next_inode)?;
+                if target.starts_with('/') {
+                    current_inode = self.get_root_inode()?;
+                    current_ino = 2;
+                }
+                pending.extend(target.split('/').filter(|s| !s.is_empty()).rev().map(|s| s.to_string()));
+                continue;
+            }
+
             current_ino = entry.inode;
-            current_inode = self.read_inode(entry.inode)?;
+            current_inode = next_inode;
         }
 
         Ok((current_ino, current_inode))
     }
 }
 
+/// 把路径拆成父目录路径和最后一个分量
+///
+/// 最后一个分量为空（根目录）时返回 EEXIST，"." 和 ".." 返回 EINVAL
+fn split_parent(path: &str) -> Result<(&str, &str), i32> {
+    let trimmed = path.trim_end_matches('/');
+    let (parent, name) = match trimmed.rfind('/') {
+        Some(pos) => (&trimmed[..pos], &trimmed[pos + 1..]),
+        None => ("/", trimmed),
+    };
+    if name.is_empty() {
+        return Err(errno::Errno::FileExists.as_neg_i32());
+    }
+    if name == "." || name == ".." {
+        return Err(errno::Errno::InvalidArgument.as_neg_i32());
+    }
+    Ok((if parent.is_empty() { "/" } else { parent }, name))
+}
+
 static EXT4_FS_TYPE: FileSystemType = FileSystemType::new(
     "ext4",
     Some(ext4_mount),
@@ -341,25 +745,36 @@ unsafe extern "C" fn ext4_mount(fc: &FsContext) -> Result<*mut SuperBlock, i32>
         putchar(b);
     }
 
-    // 获取源设备
-    let _source = fc.source.ok_or(-2_i32)?;  // ENOENT
-
-    // TODO: 从 source 获取块设备
-    // 简化实现：假设设备已经注册
-    // 这里需要实现设备名到设备的映射
+    // 获取源设备（"/dev/vda1" 或 "vda1"）
+    let source = fc.source.ok_or(-2_i32)?;  // ENOENT
+    let name = source.strip_prefix(crate::fs::devfs::DEV_PREFIX).unwrap_or(source);
+    let device = crate::fs::devfs::lookup_block_device(name)
+        .ok_or(errno::Errno::NoSuchFileOrDirectory.as_neg_i32())?;
 
     // 创建 ext4 文件系统实例
-    let mut fs = Box::new(Ext4FileSystem::new(core::ptr::null()));
+    let mut fs = Box::new(Ext4FileSystem::new(device));
 
     // 初始化文件系统
     fs.init()?;
+    fs.read_only = !mount_rw(fc);
+    if !fs.read_only && fs.has_checksums() {
+        // 与 Linux 遇到不支持的 ro_compat 特性时一样，拒绝读写挂载
+        const RW_MSG: &[u8] = b"ext4: couldn't mount RDWR because of unsupported optional features (checksums)\n";
+        for &b in RW_MSG {
+            putchar(b);
+        }
+        return Err(errno::Errno::InvalidArgument.as_neg_i32());
+    }
 
     // 创建 VFS 超级块
     let mut sb = Box::new(SuperBlock::new(fs.block_size as usize, EXT4_SUPER_MAGIC as u32));
     sb.set_type(&EXT4_FS_TYPE);
-    sb.set_flags(crate::fs::superblock::SuperBlockFlags::new(
-        crate::fs::superblock::SuperBlockFlags::SB_RDONLY,
-    ));
+    let sb_flags = if fs.read_only {
+        crate::fs::superblock::SuperBlockFlags::SB_RDONLY
+    } else {
+        0
+    };
+    sb.set_flags(crate::fs::superblock::SuperBlockFlags::new(sb_flags));
 
     // 设置私有数据
     let fs_ptr = Box::into_raw(fs) as *mut u8;
@@ -368,9 +783,37 @@ unsafe extern "C" fn ext4_mount(fc: &FsContext) -> Result<*mut SuperBlock, i32>
     Ok(Box::into_raw(sb) as *mut SuperBlock)
 }
 
+/// 挂载选项中是否要求读写挂载
+///
+/// 默认只读；数据选项（如 "noatime,rw"）中最后出现的 "rw" / "ro" 决定结果，
+/// ms_flags 带 MS_RDONLY 时总是只读
+pub fn mount_rw(fc: &FsContext) -> bool {
+    const MS_RDONLY: u64 = 1;
+
+    if fc.ms_flags & MS_RDONLY != 0 {
+        return false;
+    }
+
+    let mut rw = false;
+    if let Some(data) = fc.data {
+        for opt in data.split(',').map(|s| s.trim()) {
+            match opt {
+                "rw" => rw = true,
+                "ro" => rw = false,
+                _ => {}
+            }
+        }
+    }
+    rw
+}
+
+/// 卸载 ext4：写回并丢弃设备的缓存块，释放文件系统实例和超级块
 unsafe extern "C" fn ext4_kill_sb(sb: *mut SuperBlock) {
     if let Some(fs_info) = (*sb).s_fs_info {
-        let _fs = Box::from_raw(fs_info as *mut Ext4FileSystem);
+        let fs = Box::from_raw(fs_info as *mut Ext4FileSystem);
+        // 卸载前写回脏缓冲区，再丢弃设备的缓存块
+        let _ = bio::sync_device(fs.device);
+        bio::invalidate_device(fs.device);
         // Box 会自动释放
     }
 
@@ -399,35 +842,14 @@ pub fn read_file(device: *const blkdev::GenDisk, path: &str) -> Option<Vec<u8>>
             return None;
         }
 
-        // 解析路径
-        let path_parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
-
-        // 从根 inode 开始
-        let mut current_inode = match fs.get_root_inode() {
-            Ok(inode) => inode,
+        // 查找目标 inode（解析路径中的符号链接）
+        let (_, current_inode) = match fs.lookup_path(path, true) {
+            Ok(found) => found,
             Err(_) => {
                 return None;
             }
         };
 
-        // 遍历路径
-        for part in path_parts.iter() {
-            let entry = match fs.lookup(&current_inode, part) {
-                Ok(e) => e,
-                Err(_) => {
-                    return None;
-                }
-            };
-
-            // 读取目标 inode
-            current_inode = match fs.read_inode(entry.inode) {
-                Ok(inode) => inode,
-                Err(_) => {
-                    return None;
-                }
-            };
-        }
-
         // 读取文件内容
         let file_size = current_inode.get_size() as usize;
         if file_size == 0 {
@@ -556,7 +978,7 @@ pub fn list_dir(path: &str) -> Option<Vec<dir::Ext4DirEntry>> {
         let fs = &*fs_ptr;
 
         // 查找目录 inode
-        let (_, dir_inode) = fs.lookup_path(&abs_path).ok()?;
+        let (_, dir_inode) = fs.lookup_path(&abs_path, true).ok()?;
 
         // 列出目录内容
         fs.list_dir(&dir_inode).ok()
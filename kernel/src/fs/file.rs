@@ -17,10 +17,15 @@ use crate::fs::dentry::Dentry;
 use alloc::sync::Arc;
 use spin::Mutex;
 use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU32, Ordering};
 
+/// 文件状态标志
+///
+/// 访问模式在打开时确定，O_NONBLOCK/O_APPEND 等状态标志可以通过
+/// fcntl(F_SETFL) 在打开的文件描述上修改，因此使用原子变量保存
 #[repr(C)]
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct FileFlags(u32);
+#[derive(Debug)]
+pub struct FileFlags(AtomicU32);
 
 impl FileFlags {
     pub const O_RDONLY: u32 = 0o00000000;
@@ -42,30 +47,66 @@ impl FileFlags {
     pub const O_CLOEXEC: u32 = 0o02000000;
     pub const O_SYNC: u32 = 0o04000000;
     pub const O_PATH: u32 = 0o10000000;
+    /// 异步 I/O 通知（FASYNC）
+    pub const O_ASYNC: u32 = 0o00020000;
+
+    /// F_SETFL 可以修改的标志（对应 Linux SETFL_MASK）
+    pub const SETFL_MASK: u32 = Self::O_APPEND | Self::O_ASYNC | Self::O_NONBLOCK
+        | Self::O_DIRECT | Self::O_NOATIME;
 
     pub fn new(flags: u32) -> Self {
-        Self(flags)
+        Self(AtomicU32::new(flags))
     }
 
     pub fn is_readonly(&self) -> bool {
-        (self.0 & Self::O_ACCMODE) == Self::O_RDONLY
+        (self.bits() & Self::O_ACCMODE) == Self::O_RDONLY
     }
 
     pub fn is_writeonly(&self) -> bool {
-        (self.0 & Self::O_ACCMODE) == Self::O_WRONLY
+        (self.bits() & Self::O_ACCMODE) == Self::O_WRONLY
     }
 
     pub fn is_rdwr(&self) -> bool {
-        (self.0 & Self::O_ACCMODE) == Self::O_RDWR
+        (self.bits() & Self::O_ACCMODE) == Self::O_RDWR
+    }
+
+    /// 是否为非阻塞模式
+    pub fn is_nonblock(&self) -> bool {
+        self.bits() & Self::O_NONBLOCK != 0
+    }
+
+    /// 是否为追加模式
+    pub fn is_append(&self) -> bool {
+        self.bits() & Self::O_APPEND != 0
     }
 
     pub fn bits(&self) -> u32 {
-        self.0
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// 设置标志位
+    pub fn set_bits(&self, flags: u32) {
+        self.0.store(flags, Ordering::Release);
+    }
+
+    /// 更新可修改的状态标志（用于 F_SETFL）
+    ///
+    /// 只修改 `SETFL_MASK` 中的位，访问模式和其他标志保持不变
+    pub fn set_status_flags(&self, flags: u32) {
+        let old = self.bits();
+        self.set_bits((old & !Self::SETFL_MASK) | (flags & Self::SETFL_MASK));
     }
+}
 
-    /// 设置标志位（用于 F_SETFL）
-    pub fn set_bits(&mut self, flags: u32) {
-        self.0 = flags;
+impl Clone for FileFlags {
+    fn clone(&self) -> Self {
+        Self::new(self.bits())
+    }
+}
+
+impl PartialEq for FileFlags {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits() == other.bits()
     }
 }
 
@@ -79,6 +120,13 @@ pub struct FileOps {
     pub lseek: Option<fn(&File, isize, i32) -> isize>,
     /// 关闭文件
     pub close: Option<fn(&File) -> i32>,
+    /// 查询就绪事件（`fs::poll::events`），为 None 时总是可读可写
+    pub poll: Option<fn(&File) -> u16>,
+    /// 设备控制命令 (cmd, arg)，为 None 时所有命令返回 -ENOTTY
+    pub ioctl: Option<fn(&File, u32, usize) -> isize>,
+    /// 把设备内存映射到当前进程 (addr, length, prot, flags, offset)，
+    /// 返回映射地址或负错误码；为 None 时按普通文件映射处理
+    pub mmap: Option<fn(&File, usize, usize, u32, u32, usize) -> isize>,
 }
 
 #[repr(C)]
@@ -99,6 +147,11 @@ pub struct File {
     pub cloexec: Mutex<bool>,
 }
 
+// SAFETY: inode、dentry、ops 和 private_data 只能通过 `&mut self` 的 set_* 在
+// File 放进 Arc 之前设置，共享之后只读，所以 UnsafeCell 不会被并发写；
+// private_data 指向的对象由各自的文件操作负责同步（如管道的锁），不属于某个 CPU
+// 或任务。fork/dup 和 sendfile 通过 Arc<File> 在任务间共享同一个打开文件
+unsafe impl Send for File {}
 unsafe impl Sync for File {}
 
 impl File {
@@ -115,24 +168,24 @@ impl File {
         }
     }
 
-    /// 设置 inode
-    pub fn set_inode(&self, inode: Arc<Inode>) {
-        unsafe { *self.inode.get() = Some(inode); }
+    /// 设置 inode（只能在 File 被 Arc 共享之前调用，下同）
+    pub fn set_inode(&mut self, inode: Arc<Inode>) {
+        *self.inode.get_mut() = Some(inode);
     }
 
     /// 设置 dentry
-    pub fn set_dentry(&self, dentry: Arc<Dentry>) {
-        unsafe { *self.dentry.get() = Some(dentry); }
+    pub fn set_dentry(&mut self, dentry: Arc<Dentry>) {
+        *self.dentry.get_mut() = Some(dentry);
     }
 
     /// 设置文件操作
-    pub fn set_ops(&self, ops: &'static FileOps) {
-        unsafe { *self.ops.get() = Some(ops); }
+    pub fn set_ops(&mut self, ops: &'static FileOps) {
+        *self.ops.get_mut() = Some(ops);
     }
 
     /// 设置私有数据
-    pub fn set_private_data(&self, data: *mut u8) {
-        unsafe { *self.private_data.get() = Some(data); }
+    pub fn set_private_data(&mut self, data: *mut u8) {
+        *self.private_data.get_mut() = Some(data);
     }
 
     /// 获取 close-on-exec 标志
@@ -145,6 +198,34 @@ impl File {
         *self.cloexec.lock() = cloexec;
     }
 
+    /// 查询就绪状态，返回 `events` 中已就绪的事件
+    ///
+    /// POLLERR / POLLHUP / POLLNVAL 即使没有请求也会报告
+    pub fn poll(&self, events: u16) -> u16 {
+        use crate::fs::poll;
+
+        let ready = match unsafe { *self.ops.get() }.and_then(|ops| ops.poll) {
+            Some(poll_fn) => poll_fn(self),
+            None => poll::default_poll(),
+        };
+        ready & (events | poll::ALWAYS_REPORTED)
+    }
+
+    /// 执行 ioctl 命令，文件不支持该命令时返回 -ENOTTY
+    pub fn ioctl(&self, cmd: u32, arg: usize) -> isize {
+        match unsafe { *self.ops.get() }.and_then(|ops| ops.ioctl) {
+            Some(ioctl_fn) => ioctl_fn(self, cmd, arg),
+            None => crate::errno::Errno::NotATypewriter.as_neg_i32() as isize,
+        }
+    }
+
+    /// 由驱动建立内存映射，文件没有 mmap 操作时返回 None
+    pub fn mmap(&self, addr: usize, length: usize, prot: u32, flags: u32, offset: usize) -> Option<isize> {
+        unsafe { *self.ops.get() }
+            .and_then(|ops| ops.mmap)
+            .map(|mmap_fn| mmap_fn(self, addr, length, prot, flags, offset))
+    }
+
     /// 读取文件
     pub unsafe fn read(&self, buf: *mut u8, count: usize) -> isize {
         if let Some(ops) = *self.ops.get() {
@@ -198,18 +279,26 @@ impl File {
     }
 }
 
+/// 最后一个引用释放时调用 close（对应 Linux `fput()` -> `__fput()`）
+///
+/// fork/dup、sendfile 和 mmap 都可能持有同一个 `Arc<File>`，
+/// 因此释放动作只能跟随引用计数，不能在关闭某个 fd 时判断
+impl Drop for File {
+    fn drop(&mut self) {
+        unsafe { self.close(); }
+    }
+}
+
 pub struct FdTable {
     /// 文件描述符数组 (每个进程最多 1024 个打开文件)
-    /// 使用 Vec 避免在栈上创建大数组
-    fds: UnsafeCell<alloc::vec::Vec<Option<Arc<File>>>>,
+    /// 使用 Vec 避免在栈上创建大数组；clone(CLONE_FILES) 的线程共享同一个表，需要加锁
+    fds: Mutex<alloc::vec::Vec<Option<Arc<File>>>>,
     /// 下一个可用的文件描述符
     next_fd: Mutex<usize>,
     /// 文件描述符数量
     count: Mutex<usize>,
 }
 
-unsafe impl Sync for FdTable {}
-
 impl FdTable {
     /// 创建新的文件描述符表
     pub fn new() -> Self {
@@ -220,22 +309,31 @@ impl FdTable {
         }
 
         Self {
-            fds: UnsafeCell::new(fds),
+            fds: Mutex::new(fds),
             next_fd: Mutex::new(0),
             count: Mutex::new(0),
         }
     }
 
-    /// 分配文件描述符
+    /// 分配文件描述符，编号小于当前进程的 RLIMIT_NOFILE 软限制
     pub fn alloc_fd(&self) -> Option<usize> {
+        self.alloc_fd_below(crate::process::rlimit::nofile_limit())
+    }
+
+    /// 分配小于 `limit` 的文件描述符，没有空闲编号时返回 None（EMFILE）
+    pub fn alloc_fd_below(&self, limit: usize) -> Option<usize> {
+        let limit = limit.min(1024);
+        if limit == 0 {
+            return None;
+        }
         let mut next = self.next_fd.lock();
-        let fds = unsafe { &mut *self.fds.get() };
+        let fds = self.fds.lock();
 
         // 从 next_fd 开始搜索可用的文件描述符
-        for i in 0..1024 {
-            let fd = (*next + i) % 1024;
+        for i in 0..limit {
+            let fd = (*next + i) % limit;
             if fds[fd].is_none() {
-                *next = (fd + 1) % 1024;
+                *next = (fd + 1) % limit;
                 *self.count.lock() += 1;
                 return Some(fd);
             }
@@ -250,7 +348,7 @@ impl FdTable {
             return Err(());
         }
 
-        let fds = unsafe { &mut *self.fds.get() };
+        let mut fds = self.fds.lock();
 
         if fds[fd].is_some() {
             return Err(()); // 文件描述符已被占用
@@ -265,10 +363,7 @@ impl FdTable {
         if fd >= 1024 {
             return None;
         }
-        let fds = unsafe { &*self.fds.get() };
-
-        // 简单的 clone 方式
-        fds[fd].clone()
+        self.fds.lock()[fd].clone()
     }
 
     /// 关闭文件描述符
@@ -277,34 +372,44 @@ impl FdTable {
             return Err(());
         }
 
-        let fds = unsafe { &mut *self.fds.get() };
-
-        if fds[fd].is_none() {
+        // 取出文件后在锁外释放引用，最后一个引用释放时由 File 的析构调用 close
+        let file = self.fds.lock()[fd].take();
+        if file.is_none() {
             return Err(());
         }
 
-        // 取出文件并调用关闭操作
-        // 注意：需要先取出文件，避免在 unsafe 块中借用问题
-        let file_opt = unsafe {
-            // 使用 swap 将 fds[fd] 替换为 None，同时获取原值
-            let temp = &mut fds[fd];
-            core::mem::replace(temp, None)
-        };
+        *self.count.lock() -= 1;
+        drop(file);
+        Ok(())
+    }
 
-        // 如果文件有操作函数指针，调用 close
-        if let Some(file) = file_opt {
-            unsafe {
-                let file_ptr = Arc::as_ptr(&file) as *mut File;
-                // 检查是否有 ops（避免访问 None）
-                let ops_ptr = (*file_ptr).ops.get();
-                if !ops_ptr.is_null() && !(*ops_ptr).is_none() {
-                    (*file_ptr).close();
-                }
-            }
+    /// 复制整个文件描述符表（用于 fork）
+    ///
+    /// 对应 Linux `dup_fd()`：子进程得到新的表，表项与父进程共享同一个打开的文件
+    pub fn dup_table(&self) -> FdTable {
+        let new_fds: alloc::vec::Vec<Option<Arc<File>>> = self.fds.lock().iter().cloned().collect();
+
+        FdTable {
+            fds: Mutex::new(new_fds),
+            next_fd: Mutex::new(*self.next_fd.lock()),
+            count: Mutex::new(*self.count.lock()),
         }
+    }
 
-        *self.count.lock() -= 1;
-        Ok(())
+    /// 关闭所有设置了 close-on-exec 的文件描述符（用于 execve）
+    ///
+    /// 对应 Linux `do_close_on_exec()`
+    pub fn close_on_exec(&self) {
+        let cloexec_fds: alloc::vec::Vec<usize> = self.fds.lock()
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.as_ref().map_or(false, |f| f.get_cloexec()))
+            .map(|(fd, _)| fd)
+            .collect();
+
+        for fd in cloexec_fds {
+            let _ = self.close_fd(fd);
+        }
     }
 
     /// 复制文件描述符
@@ -381,8 +486,12 @@ fn reg_file_read(file: &File, buf: &mut [u8]) -> isize {
 
 fn reg_file_write(file: &File, buf: &[u8]) -> isize {
     if let Some(ref inode) = unsafe { &*file.inode.get() } {
-        // 获取当前文件位置
-        let offset = file.get_pos() as usize;
+        // 获取当前文件位置（O_APPEND：每次写入前定位到文件末尾）
+        let offset = if file.flags.is_append() {
+            inode.get_size() as usize
+        } else {
+            file.get_pos() as usize
+        };
 
         // 写入数据到 inode（buf.length 自动处理）
         let bytes_written = inode.write_data(offset, buf);
@@ -433,6 +542,9 @@ pub static REG_FILE_OPS: FileOps = FileOps {
     write: Some(reg_file_write),
     lseek: Some(reg_file_lseek),
     close: Some(reg_file_close),
+    poll: None,
+    ioctl: None,
+    mmap: None,
 };
 
 pub static REG_RO_FILE_OPS: FileOps = FileOps {
@@ -440,4 +552,7 @@ pub static REG_RO_FILE_OPS: FileOps = FileOps {
     write: None,
     lseek: Some(reg_file_lseek),
     close: Some(reg_file_close),
+    poll: None,
+    ioctl: None,
+    mmap: None,
 };
@@ -10,13 +10,19 @@
 //! - `inode`: 索引节点管理 (fs/inode.c)
 //! - `dentry`: 目录项管理 (fs/dcache.c)
 //! - `pipe`: 管道文件系统 (fs/pipe.c)
+//! - `poll`: 文件就绪状态查询 (fs/select.c)
+//! - `devfs`: 字符设备节点 /dev (drivers/base/devtmpfs.c)
+//! - `tty`: 终端行规程 (drivers/tty/n_tty.c)
 //! - `elf`: ELF 加载器 (fs/binfmt_elf.c)
 
 pub mod file;
 pub mod inode;
 pub mod dentry;
 pub mod pipe;
+pub mod poll;
 pub mod char_dev;
+pub mod devfs;
+pub mod tty;
 pub mod elf;
 pub mod buffer;
 pub mod bio;
@@ -34,7 +40,7 @@ pub use stat::Stat;
 pub use pipe::create_pipe;
 pub use char_dev::CharDev;
 pub use rootfs::get_rootfs;
-pub use vfs::{file_open, file_close, file_stat, file_fcntl, fcntl, file_mkdir, file_rmdir, file_unlink, file_link};
+pub use vfs::{file_open, file_close, do_sendfile, vfs_readv, vfs_writev, Iovec, file_stat, file_fcntl, fcntl, file_mkdir, file_rmdir, file_unlink, file_link, file_truncate, file_chmod, file_fsync};
 
 pub fn read_file_from_rootfs(filename: &str) -> Option<alloc::vec::Vec<u8>> {
     use alloc::vec::Vec;
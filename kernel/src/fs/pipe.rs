@@ -17,108 +17,112 @@ use spin::Mutex;
 use core::sync::atomic::{AtomicUsize, Ordering};
 use alloc::sync::Arc;
 use crate::process::wait::WaitQueueHead;
+use crate::fs::poll;
 
+/// 默认管道容量（对应 Linux 默认 16 页的一半）
 const PIPE_BUF_SIZE: usize = 16384;
 
+/// 管道最大容量（对应 /proc/sys/fs/pipe-max-size 默认值 1MB）
+pub const PIPE_MAX_SIZE: usize = 1024 * 1024;
+
+/// 管道环形缓冲区
+///
+/// 固定容量的环形缓冲区，容量可以通过 `resize` 调整（对应 F_SETPIPE_SZ）。
+/// 缓冲区本身不加锁，由 `Pipe` 的互斥锁保护。
 #[repr(C)]
 pub struct PipeBuffer {
     /// 缓冲区数据
     data: Vec<u8>,
-    /// 读指针
-    read_pos: AtomicUsize,
-    /// 写指针
-    write_pos: AtomicUsize,
-    /// 缓冲区大小
-    size: usize,
+    /// 读指针（最早写入数据的位置）
+    head: usize,
+    /// 已缓存的字节数
+    len: usize,
 }
 
 impl PipeBuffer {
     /// 创建新的管道缓冲区
     pub fn new(size: usize) -> Self {
-        // 手动分配并初始化向量，避免使用 vec! 宏
         let mut data = Vec::with_capacity(size);
-        unsafe {
-            data.set_len(size);
-            core::ptr::write_bytes(data.as_mut_ptr(), 0, size);
-        }
+        data.resize(size, 0);
 
         Self {
             data,
-            read_pos: AtomicUsize::new(0),
-            write_pos: AtomicUsize::new(0),
-            size,
+            head: 0,
+            len: 0,
         }
     }
 
+    /// 缓冲区容量
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
     /// 读取数据
+    ///
+    /// 最多读取 `buf.len()` 字节，跨越环形缓冲区末尾时自动回绕
     pub fn read(&mut self, buf: &mut [u8]) -> usize {
-        let read_pos = self.read_pos.load(Ordering::Acquire);
-        let write_pos = self.write_pos.load(Ordering::Acquire);
-
-        if read_pos == write_pos {
-            return 0; // 缓冲区为空
-        }
-
-        let available = if write_pos > read_pos {
-            write_pos - read_pos
-        } else {
-            self.size - read_pos
-        };
+        let to_read = core::cmp::min(self.len, buf.len());
+        let cap = self.capacity();
 
-        let to_read = core::cmp::min(available, buf.len());
+        // 分两段复制：head 到缓冲区末尾，然后从头开始
+        let first = core::cmp::min(to_read, cap - self.head);
+        buf[..first].copy_from_slice(&self.data[self.head..self.head + first]);
+        buf[first..to_read].copy_from_slice(&self.data[..to_read - first]);
 
-        for i in 0..to_read {
-            buf[i] = self.data[(read_pos + i) % self.size];
-        }
-
-        self.read_pos.store((read_pos + to_read) % self.size, Ordering::Release);
+        self.head = (self.head + to_read) % cap;
+        self.len -= to_read;
         to_read
     }
 
     /// 写入数据
+    ///
+    /// 最多写入剩余空间大小的字节，返回实际写入的字节数
     pub fn write(&mut self, buf: &[u8]) -> usize {
-        let read_pos = self.read_pos.load(Ordering::Acquire);
-        let write_pos = self.write_pos.load(Ordering::Acquire);
-
-        // 计算可用空间
-        let available = if write_pos >= read_pos {
-            self.size - (write_pos - read_pos) - 1
-        } else {
-            read_pos - write_pos - 1
-        };
+        let to_write = core::cmp::min(self.available_write(), buf.len());
+        let cap = self.capacity();
+        let tail = (self.head + self.len) % cap;
 
-        let to_write = core::cmp::min(available, buf.len());
+        let first = core::cmp::min(to_write, cap - tail);
+        self.data[tail..tail + first].copy_from_slice(&buf[..first]);
+        self.data[..to_write - first].copy_from_slice(&buf[first..to_write]);
 
-        for i in 0..to_write {
-            self.data[(write_pos + i) % self.size] = buf[i];
-        }
-
-        self.write_pos.store((write_pos + to_write) % self.size, Ordering::Release);
+        self.len += to_write;
         to_write
     }
 
     /// 获取可用读取字节数
+    #[inline]
     pub fn available_read(&self) -> usize {
-        let read_pos = self.read_pos.load(Ordering::Acquire);
-        let write_pos = self.write_pos.load(Ordering::Acquire);
-
-        if write_pos >= read_pos {
-            write_pos - read_pos
-        } else {
-            self.size - read_pos + write_pos
-        }
+        self.len
     }
 
     /// 获取可用写入空间
+    #[inline]
     pub fn available_write(&self) -> usize {
-        let read_pos = self.read_pos.load(Ordering::Acquire);
-        let write_pos = self.write_pos.load(Ordering::Acquire);
+        self.capacity() - self.len
+    }
 
-        if write_pos >= read_pos {
-            self.size - (write_pos - read_pos) - 1
-        } else {
-            read_pos - write_pos - 1
+    /// 调整缓冲区容量（对应 Linux `pipe_resize_ring()`）
+    ///
+    /// 已缓存的数据保持顺序不变；新容量小于已缓存数据量时返回 EBUSY
+    pub fn resize(&mut self, new_size: usize) -> Result<(), i32> {
+        if new_size == 0 || new_size > PIPE_MAX_SIZE {
+            return Err(crate::errno::Errno::InvalidArgument.as_neg_i32());
         }
+        if new_size < self.len {
+            return Err(crate::errno::Errno::DeviceOrResourceBusy.as_neg_i32());
+        }
+
+        let mut data = Vec::with_capacity(new_size);
+        data.resize(new_size, 0);
+        let len = self.len;
+        self.read(&mut data[..len]);
+
+        self.data = data;
+        self.head = 0;
+        self.len = len;
+        Ok(())
     }
 }
 
@@ -153,6 +157,7 @@ impl Pipe {
         self.read_closed.store(1, Ordering::Release);
         // 唤醒所有写等待者（读端关闭会导致写操作返回 SIGPIPE）
         self.write_queue.wake_up_all();
+        poll::wake_pollers();
     }
 
     /// 关闭写端
@@ -160,6 +165,7 @@ impl Pipe {
         self.write_closed.store(1, Ordering::Release);
         // 唤醒所有读等待者（EOF）
         self.read_queue.wake_up_all();
+        poll::wake_pollers();
     }
 
     /// 检查读端是否关闭
@@ -172,6 +178,20 @@ impl Pipe {
         self.write_closed.load(Ordering::Acquire) == 1
     }
 
+    /// 获取管道容量
+    pub fn capacity(&self) -> usize {
+        self.buffer.lock().capacity()
+    }
+
+    /// 调整管道容量（对应 F_SETPIPE_SZ）
+    pub fn set_capacity(&self, size: usize) -> Result<(), i32> {
+        self.buffer.lock().resize(size)?;
+        // 容量变大时可能有写者在等待空间
+        self.write_queue.wake_up_all();
+        poll::wake_pollers();
+        Ok(())
+    }
+
     /// 获取读等待队列
     pub fn read_queue(&self) -> &WaitQueueHead {
         &self.read_queue
@@ -184,17 +204,24 @@ impl Pipe {
 }
 
 pub fn pipe_read(pipe: &Pipe, buf: &mut [u8]) -> isize {
-    if pipe.is_write_closed() && pipe.buffer.lock().available_read() == 0 {
-        return 0; // EOF
+    let count = pipe.buffer.lock().read(buf);
+    if count > 0 {
+        // 有空间了，唤醒写等待者
+        pipe.write_queue().wake_up_all();
+        poll::wake_pollers();
+        return count as isize;
     }
 
-    let count = pipe.buffer.lock().read(buf);
-    count as isize
+    if pipe.is_write_closed() {
+        0 // EOF
+    } else {
+        -11_i32 as isize // EAGAIN
+    }
 }
 
 pub fn pipe_write(pipe: &Pipe, buf: &[u8]) -> isize {
     if pipe.is_read_closed() {
-        return -9; // EBADF - 读端已关闭，写入会失败
+        return pipe_broken();
     }
 
     let count = pipe.buffer.lock().write(buf);
@@ -202,61 +229,71 @@ pub fn pipe_write(pipe: &Pipe, buf: &[u8]) -> isize {
         // 缓冲区满，非阻塞模式下返回 EAGAIN
         -11_i32 as isize // EAGAIN
     } else {
+        // 有数据了，唤醒读等待者
+        pipe.read_queue().wake_up_all();
+        poll::wake_pollers();
         count as isize
     }
 }
 
+/// 向没有读者的管道写入：向当前进程发送 SIGPIPE 并返回 EPIPE
+fn pipe_broken() -> isize {
+    let _ = crate::sched::send_signal_self(crate::signal::Signal::SIGPIPE as i32);
+    crate::errno::Errno::BrokenPipe.as_neg_i32() as isize
+}
+
+/// 在管道等待队列上睡眠，直到 `ready` 返回 true
+///
+/// 先加入等待队列并设置睡眠状态，再重新检查条件，避免丢失唤醒
+fn pipe_wait(queue: &WaitQueueHead, ready: impl Fn() -> bool) {
+    use crate::process::task::TaskState;
+
+    let current = match crate::sched::current() {
+        Some(task) => task,
+        None => return,
+    };
+
+    queue.add(crate::process::wait::WaitQueueEntry::new(current, false));
+    unsafe { (*current).set_state(TaskState::Interruptible); }
+
+    if !ready() {
+        #[cfg(feature = "riscv64")]
+        crate::sched::schedule();
+    }
+
+    unsafe { (*current).set_state(TaskState::Running); }
+    queue.remove(current);
+}
+
 use crate::fs::file::{File, FileOps, FileFlags};
 
 fn pipe_file_read(file: &File, buf: &mut [u8]) -> isize {
     if let Some(pipe_ptr) = unsafe { *file.private_data.get() } {
         let pipe = unsafe { &*(pipe_ptr as *const Pipe) };
 
-        // 检查是否为非阻塞模式
-        let nonblock = (file.flags.bits() & FileFlags::O_NONBLOCK) != 0;
+        if buf.is_empty() {
+            return 0;
+        }
 
         loop {
-            // 检查 EOF 条件：写端已关闭且缓冲区为空
-            if pipe.is_write_closed() && pipe.buffer.lock().available_read() == 0 {
-                return 0; // EOF
-            }
-
-            // 尝试读取数据
-            let count = pipe.buffer.lock().read(buf);
-            if count > 0 {
-                // 读取成功，唤醒写等待者（有空间了）
-                pipe.write_queue().wake_up_all();
-                return count as isize;
+            // 尝试读取数据（读取后唤醒写等待者）
+            let result = pipe_read(pipe, buf);
+            if result != -11 {
+                return result;
             }
 
-            // 缓冲区为空
-            if nonblock {
-                // 非阻塞模式：返回 EAGAIN
+            // 缓冲区为空且写端未关闭
+            if file.flags.is_nonblock() {
                 return -11_i32 as isize; // EAGAIN
             }
 
-            // 阻塞模式：使用等待队列等待数据
-            // 条件：缓冲区有数据或写端关闭
-            {
-                // 创建等待队列项
-                let current = match crate::sched::current() {
-                    Some(task) => task,
-                    None => return 0, // 无法获取当前任务，返回 EOF
-                };
-
-                let entry = crate::process::wait::WaitQueueEntry::new(current, false);
-                pipe.read_queue().add(entry);
-
-                // 让出 CPU
-                #[cfg(feature = "riscv64")]
-                crate::sched::schedule();
-
-                // 被唤醒后，从等待队列移除
-                pipe.read_queue().remove(current);
-
-                // 重新检查条件
-                continue;
+            // 阻塞模式：等待数据或写端关闭
+            if crate::sched::current().is_none() {
+                return 0; // 无法获取当前任务，返回 EOF
             }
+            pipe_wait(pipe.read_queue(), || {
+                pipe.buffer.lock().available_read() > 0 || pipe.is_write_closed()
+            });
         }
     } else {
         -9  // EBADF
@@ -267,33 +304,24 @@ fn pipe_file_write(file: &File, buf: &[u8]) -> isize {
     if let Some(pipe_ptr) = unsafe { *file.private_data.get() } {
         let pipe = unsafe { &*(pipe_ptr as *const Pipe) };
 
-        // 检查读端是否已关闭
-        if pipe.is_read_closed() {
-            return -9; // EBADF - 读端已关闭，写入会失败（SIGPIPE）
-        }
-
-        // 检查是否为非阻塞模式
-        let nonblock = (file.flags.bits() & FileFlags::O_NONBLOCK) != 0;
-
         let mut total_written = 0;
 
         // 循环写入，直到所有数据写入完毕或遇到错误
         while total_written < buf.len() {
-            let remaining = &buf[total_written..];
-
-            // 尝试写入数据
-            let count = pipe.buffer.lock().write(remaining);
+            // 读端已关闭：SIGPIPE + EPIPE
+            if pipe.is_read_closed() {
+                return if total_written > 0 { total_written as isize } else { pipe_broken() };
+            }
 
-            if count > 0 {
-                // 写入成功
-                total_written += count;
-                // 唤醒读等待者（有数据了）
-                pipe.read_queue().wake_up_all();
+            // 尝试写入数据（写入后唤醒读等待者）
+            let result = pipe_write(pipe, &buf[total_written..]);
+            if result > 0 {
+                total_written += result as usize;
                 continue;
             }
 
             // 缓冲区满
-            if nonblock {
+            if file.flags.is_nonblock() {
                 // 非阻塞模式：返回已写入的字节数或 EAGAIN
                 if total_written > 0 {
                     return total_written as isize;
@@ -302,27 +330,13 @@ fn pipe_file_write(file: &File, buf: &[u8]) -> isize {
                 }
             }
 
-            // 阻塞模式：使用等待队列等待空间
-            {
-                // 创建等待队列项
-                let current = match crate::sched::current() {
-                    Some(task) => task,
-                    None => return total_written as isize, // 无法获取当前任务，返回已写入字节数
-                };
-
-                let entry = crate::process::wait::WaitQueueEntry::new(current, false);
-                pipe.write_queue().add(entry);
-
-                // 让出 CPU
-                #[cfg(feature = "riscv64")]
-                crate::sched::schedule();
-
-                // 被唤醒后，从等待队列移除
-                pipe.write_queue().remove(current);
-
-                // 重新尝试写入
-                continue;
+            // 阻塞模式：等待空间或读端关闭
+            if crate::sched::current().is_none() {
+                return total_written as isize; // 无法获取当前任务，返回已写入字节数
             }
+            pipe_wait(pipe.write_queue(), || {
+                pipe.buffer.lock().available_write() > 0 || pipe.is_read_closed()
+            });
         }
 
         total_written as isize
@@ -331,6 +345,34 @@ fn pipe_file_write(file: &File, buf: &[u8]) -> isize {
     }
 }
 
+/// 读端有数据时可读，写端关闭后挂断；写端有空间时可写，读端关闭后出错
+fn pipe_file_poll(file: &File) -> u16 {
+    use crate::fs::poll::events::*;
+
+    let Some(pipe_ptr) = (unsafe { *file.private_data.get() }) else {
+        return POLLNVAL;
+    };
+    let pipe = unsafe { &*(pipe_ptr as *const Pipe) };
+
+    let mut ready = 0;
+    if file.flags.is_readonly() || file.flags.is_rdwr() {
+        if pipe.buffer.lock().available_read() > 0 {
+            ready |= poll::READABLE;
+        }
+        if pipe.is_write_closed() {
+            ready |= POLLHUP;
+        }
+    }
+    if file.flags.is_writeonly() || file.flags.is_rdwr() {
+        if pipe.is_read_closed() {
+            ready |= POLLERR;
+        } else if pipe.buffer.lock().available_write() > 0 {
+            ready |= poll::WRITABLE;
+        }
+    }
+    ready
+}
+
 fn pipe_file_close(file: &File) -> i32 {
     if let Some(pipe_ptr) = unsafe { *file.private_data.get() } {
         let pipe = unsafe { &*(pipe_ptr as *const Pipe) };
@@ -361,28 +403,67 @@ fn pipe_file_close(file: &File) -> i32 {
     }
 }
 
+/// 管道文件操作
+static PIPE_OPS: FileOps = FileOps {
+    read: Some(pipe_file_read),
+    write: Some(pipe_file_write),
+    lseek: None,  // 管道不支持 lseek
+    close: Some(pipe_file_close),
+    poll: Some(pipe_file_poll),
+    ioctl: None,
+    mmap: None,
+};
+
+/// 获取管道文件对应的管道，不是管道文件时返回 None（对应 Linux `get_pipe_info()`）
+fn get_pipe_info(file: &File) -> Option<&Pipe> {
+    let ops = unsafe { *file.ops.get() }?;
+    if !core::ptr::eq(ops, &PIPE_OPS) {
+        return None;
+    }
+    let pipe_ptr = unsafe { *file.private_data.get() }?;
+    Some(unsafe { &*(pipe_ptr as *const Pipe) })
+}
+
+/// F_SETPIPE_SZ 的容量取整：向上取到 2 的幂，至少一页（对应 Linux `round_pipe_size()`）
+fn round_pipe_size(size: usize) -> usize {
+    if size > PIPE_MAX_SIZE {
+        return size;
+    }
+    size.max(crate::mm::page::PAGE_SIZE).next_power_of_two()
+}
+
+/// 管道的 fcntl 命令 F_SETPIPE_SZ / F_GETPIPE_SZ（对应 Linux `pipe_fcntl()`）
+///
+/// # 返回
+/// 成功返回管道容量；不是管道文件时返回 EBADF
+pub fn pipe_fcntl(file: &File, cmd: usize, arg: usize) -> Result<usize, i32> {
+    let pipe = get_pipe_info(file).ok_or(crate::errno::Errno::BadFileNumber.as_neg_i32())?;
+
+    match cmd {
+        crate::fs::vfs::fcntl::F_SETPIPE_SZ => {
+            let size = round_pipe_size(arg);
+            pipe.set_capacity(size)?;
+            Ok(size)
+        }
+        crate::fs::vfs::fcntl::F_GETPIPE_SZ => Ok(pipe.capacity()),
+        _ => Err(crate::errno::Errno::InvalidArgument.as_neg_i32()),
+    }
+}
+
 pub fn create_pipe() -> (Arc<File>, Arc<File>) {
     // 创建管道并在堆上分配（使用 Box::leak 确保生命周期直到手动释放）
     let pipe = Box::new(Pipe::new());
     let pipe_ptr = Box::leak(pipe) as *mut Pipe as *mut u8;
 
-    // 管道文件操作
-    static PIPE_OPS: FileOps = FileOps {
-        read: Some(pipe_file_read),
-        write: Some(pipe_file_write),
-        lseek: None,  // 管道不支持 lseek
-        close: Some(pipe_file_close),
-    };
-
     // 创建读端文件
-    let read_file = Arc::new(File::new(FileFlags::new(FileFlags::O_RDONLY)));
+    let mut read_file = File::new(FileFlags::new(FileFlags::O_RDONLY));
     read_file.set_ops(&PIPE_OPS);
     read_file.set_private_data(pipe_ptr);
 
     // 创建写端文件
-    let write_file = Arc::new(File::new(FileFlags::new(FileFlags::O_WRONLY)));
+    let mut write_file = File::new(FileFlags::new(FileFlags::O_WRONLY));
     write_file.set_ops(&PIPE_OPS);
     write_file.set_private_data(pipe_ptr);
 
-    (read_file, write_file)
+    (Arc::new(read_file), Arc::new(write_file))
 }
@@ -0,0 +1,90 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! 文件就绪状态查询
+//!
+//! 对应 Linux 的 `file_operations::poll` 和 `poll_wait` (fs/select.c)：
+//! - 每种文件通过 `FileOps::poll` 报告当前就绪的事件，poll/select/epoll 统一调用 `File::poll`
+//! - 就绪状态变化时（管道读写、关闭等）调用 `wake_pollers` 唤醒等待者
+//!
+//! 目前实现 `poll` 的有管道、UART 终端和 evdev；其他文件（普通文件、目录、fbdev）使用
+//! `default_poll`。TCP/UDP 套接字的描述符在 `net` 的套接字表中，不是进程 fd 表里的
+//! `File`，因此 poll/select/epoll 还不支持套接字
+
+use crate::process::wait::{WaitQueueEntry, WaitQueueHead};
+
+/// poll 事件位 (struct pollfd 的 events / revents)
+pub mod events {
+    pub const POLLIN: u16 = 0x0001;      // 可读
+    pub const POLLPRI: u16 = 0x0002;     // 紧急可读
+    pub const POLLOUT: u16 = 0x0004;     // 可写
+    pub const POLLERR: u16 = 0x0008;     // 错误
+    pub const POLLHUP: u16 = 0x0010;     // 挂断
+    pub const POLLNVAL: u16 = 0x0020;    // 无效请求
+    pub const POLLRDNORM: u16 = 0x0040;  // 等同于 POLLIN
+    pub const POLLRDBAND: u16 = 0x0080;  // 优先带数据可读
+    pub const POLLWRNORM: u16 = 0x0100;  // 等同于 POLLOUT
+    pub const POLLWRBAND: u16 = 0x0200;  // 优先带数据可写
+}
+
+use events::*;
+
+/// 可读
+pub const READABLE: u16 = POLLIN | POLLRDNORM;
+/// 可写
+pub const WRITABLE: u16 = POLLOUT | POLLWRNORM;
+/// 无论是否请求都会报告的事件
+pub const ALWAYS_REPORTED: u16 = POLLERR | POLLHUP | POLLNVAL;
+
+/// 等待任意文件就绪状态变化的任务
+static POLL_WAITERS: WaitQueueHead = WaitQueueHead::new();
+
+/// 没有 `poll` 操作的文件（普通文件、目录）总是可读可写
+pub fn default_poll() -> u16 {
+    READABLE | WRITABLE
+}
+
+/// 就绪状态可能发生变化，唤醒所有 poll/select/epoll 等待者
+pub fn wake_pollers() {
+    POLL_WAITERS.wake_up_all();
+}
+
+/// 睡眠直到被 `wake_pollers` 唤醒或到达 `deadline` (jiffies)
+///
+/// 先加入等待队列并设置睡眠状态，再检查 `ready`，避免丢失唤醒。
+/// 有截止时间时设置一个到期唤醒等待者的延迟工作
+/// （对应 Linux `poll_schedule_timeout()`），返回前取消
+pub fn poll_wait(deadline: Option<u64>, mut ready: impl FnMut() -> bool) {
+    use crate::process::task::TaskState;
+
+    let current = match crate::sched::current() {
+        Some(task) => task,
+        None => return,
+    };
+
+    POLL_WAITERS.add(WaitQueueEntry::new(current, false));
+    unsafe { (*current).set_state(TaskState::Interruptible); }
+
+    if !ready() {
+        #[cfg(feature = "riscv64")]
+        {
+            use crate::drivers::timer::{self, delayed_work};
+
+            let timeout = deadline.map(|d| {
+                delayed_work::schedule_delayed_work(wake_pollers, d.saturating_sub(timer::get_jiffies()))
+            });
+            crate::sched::schedule();
+            if let Some(expires) = timeout {
+                delayed_work::cancel_delayed_work_at(wake_pollers, expires);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "riscv64"))]
+    let _ = deadline;
+
+    unsafe { (*current).set_state(TaskState::Running); }
+    POLL_WAITERS.remove(current);
+}
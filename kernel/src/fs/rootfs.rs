@@ -956,6 +956,38 @@ impl RootFSSuperBlock {
         Ok(())
     }
 
+    /// 解析打开路径，处理最后一个分量的符号链接
+    ///
+    /// `lookup` 只跟随中间分量的符号链接；`follow` 为 true 时继续跟随最后的链接
+    /// （相对目标按链接所在目录解析），为 false 时最后一个分量是链接则返回 ELOOP（O_NOFOLLOW）
+    ///
+    /// # 返回
+    /// 成功返回 (最终节点, 最终节点的规范化路径)
+    pub fn resolve(&self, path: &str, follow: bool) -> Result<(Arc<RootFSNode>, String), i32> {
+        let mut path = path_normalize(path);
+        let mut depth = 0;
+
+        loop {
+            let node = self.lookup(&path).ok_or(errno::Errno::NoSuchFileOrDirectory.as_neg_i32())?;
+            if !node.is_symlink() {
+                return Ok((node, path));
+            }
+            if !follow || depth >= MAX_SYMLINKS {
+                return Err(errno::Errno::TooManySymbolicLinks.as_neg_i32());
+            }
+            depth += 1;
+
+            let target_bytes = node.get_link_target().ok_or(errno::Errno::NoSuchFileOrDirectory.as_neg_i32())?;
+            let target = core::str::from_utf8(&target_bytes).map_err(|_| errno::Errno::InvalidArgument.as_neg_i32())?;
+            path = if target.starts_with('/') {
+                path_normalize(target)
+            } else {
+                let parent = path.rsplit_once('/').map_or("", |(parent, _)| parent);
+                path_normalize(&alloc::format!("{}/{}", parent, target))
+            };
+        }
+    }
+
     /// 读取符号链接目标
     ///
     pub fn readlink(&self, path: &str) -> Result<Vec<u8>, i32> {
@@ -0,0 +1,304 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! 终端行规程 (drivers/tty/n_tty.c)
+//!
+//! 控制台 UART 收到的字节先经过行规程，再由 read 返回：
+//! - `ICRNL`: 输入的 '\r' 转换为 '\n'
+//! - `ICANON`: 规范模式，按行缓冲并支持 VERASE/VKILL/VEOF 编辑，read 在整行完成后才返回
+//! - `ECHO`: 回显输入的字节，`ECHOE` 时退格会擦除屏幕上的字符
+//!
+//! termios 通过 TCGETS/TCSETS ioctl 读写（见 `char_dev::uart_file_ioctl`）
+
+use alloc::vec::Vec;
+use spin::{Mutex, MutexGuard};
+
+use crate::ring_buffer::RingBuffer;
+
+/// TCGETS - 读取 termios
+pub const TCGETS: u32 = 0x5401;
+/// TCSETS - 立即设置 termios
+pub const TCSETS: u32 = 0x5402;
+/// TCSETSW - 等待输出完成后设置 termios
+pub const TCSETSW: u32 = 0x5403;
+/// TCSETSF - 丢弃未读输入后设置 termios
+pub const TCSETSF: u32 = 0x5404;
+
+/// c_cc 控制字符数量
+pub const NCCS: usize = 19;
+
+// c_cc 下标
+pub const VINTR: usize = 0;
+pub const VQUIT: usize = 1;
+pub const VERASE: usize = 2;
+pub const VKILL: usize = 3;
+pub const VEOF: usize = 4;
+pub const VTIME: usize = 5;
+pub const VMIN: usize = 6;
+
+/// 输入标志 (c_iflag)
+pub mod iflag {
+    pub const ICRNL: u32 = 0o000400;
+    pub const IXON: u32 = 0o002000;
+}
+
+/// 输出标志 (c_oflag)
+pub mod oflag {
+    pub const OPOST: u32 = 0o000001;
+    pub const ONLCR: u32 = 0o000004;
+}
+
+/// 控制标志 (c_cflag)
+pub mod cflag {
+    pub const B38400: u32 = 0o000017;
+    pub const CS8: u32 = 0o000060;
+    pub const CREAD: u32 = 0o000200;
+    pub const HUPCL: u32 = 0o002000;
+}
+
+/// 本地标志 (c_lflag)
+pub mod lflag {
+    pub const ISIG: u32 = 0o000001;
+    pub const ICANON: u32 = 0o000002;
+    pub const ECHO: u32 = 0o000010;
+    pub const ECHOE: u32 = 0o000020;
+    pub const ECHOK: u32 = 0o000040;
+}
+
+/// 规范模式下一行的最大长度（超出的字节被丢弃，换行符除外）
+pub const MAX_CANON: usize = 4095;
+
+/// 等待 read 的输入缓冲区大小，写满后新输入被丢弃
+pub const N_TTY_BUF_SIZE: usize = 4096;
+
+/// struct termios (include/uapi/asm-generic/termbits.h)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Termios {
+    pub c_iflag: u32,
+    pub c_oflag: u32,
+    pub c_cflag: u32,
+    pub c_lflag: u32,
+    pub c_line: u8,
+    pub c_cc: [u8; NCCS],
+}
+
+impl Termios {
+    /// 控制台的默认设置：规范模式并回显
+    pub const fn new() -> Self {
+        let mut c_cc = [0; NCCS];
+        c_cc[VINTR] = 3;    // ^C
+        c_cc[VQUIT] = 28;   // ^\
+        c_cc[VERASE] = 127; // DEL
+        c_cc[VKILL] = 21;   // ^U
+        c_cc[VEOF] = 4;     // ^D
+        c_cc[VTIME] = 0;
+        c_cc[VMIN] = 1;
+        Self {
+            c_iflag: iflag::ICRNL | iflag::IXON,
+            c_oflag: oflag::OPOST | oflag::ONLCR,
+            c_cflag: cflag::B38400 | cflag::CS8 | cflag::CREAD | cflag::HUPCL,
+            c_lflag: lflag::ICANON | lflag::ECHO | lflag::ECHOE | lflag::ECHOK | lflag::ISIG,
+            c_line: 0,
+            c_cc,
+        }
+    }
+
+    /// 是否为规范模式
+    pub fn is_canonical(&self) -> bool {
+        self.c_lflag & lflag::ICANON != 0
+    }
+
+    /// 是否回显输入
+    pub fn echo(&self) -> bool {
+        self.c_lflag & lflag::ECHO != 0
+    }
+}
+
+impl Default for Termios {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 行规程状态
+pub struct LineDiscipline {
+    termios: Termios,
+    /// 规范模式下正在编辑的行
+    line: Vec<u8>,
+    /// 可以被 read 返回的字节
+    ready: RingBuffer<N_TTY_BUF_SIZE>,
+    /// 规范模式下在空行输入了 VEOF，下一次 read 返回 0
+    eof: bool,
+}
+
+impl LineDiscipline {
+    pub const fn new() -> Self {
+        Self {
+            termios: Termios::new(),
+            line: Vec::new(),
+            ready: RingBuffer::new(),
+            eof: false,
+        }
+    }
+
+    /// 当前 termios
+    pub fn termios(&self) -> Termios {
+        self.termios
+    }
+
+    /// 替换 termios
+    ///
+    /// 离开规范模式时正在编辑的行立即变为可读
+    pub fn set_termios(&mut self, termios: Termios) {
+        if self.termios.is_canonical() && !termios.is_canonical() {
+            self.commit_line();
+        }
+        self.termios = termios;
+    }
+
+    /// 丢弃所有未读输入
+    pub fn flush_input(&mut self) {
+        self.line.clear();
+        self.ready.clear();
+        self.eof = false;
+    }
+
+    /// 可以被 read 返回的字节数
+    pub fn pending(&self) -> usize {
+        self.ready.len()
+    }
+
+    /// read 是否会立即返回
+    pub fn is_readable(&self) -> bool {
+        !self.ready.is_empty() || self.eof
+    }
+
+    /// 处理收到的一个字节，需要回显的字节交给 `echo`
+    pub fn receive(&mut self, c: u8, mut echo: impl FnMut(u8)) {
+        let termios = self.termios;
+        let c = if c == b'\r' && termios.c_iflag & iflag::ICRNL != 0 { b'\n' } else { c };
+
+        if !termios.is_canonical() {
+            self.ready.push(c);
+            if termios.echo() {
+                self.echo_byte(c, &mut echo);
+            }
+            return;
+        }
+
+        let erase_echo = termios.echo() && termios.c_lflag & lflag::ECHOE != 0;
+        if c == termios.c_cc[VERASE] || c == 8 {
+            if self.line.pop().is_some() && erase_echo {
+                Self::echo_erase(&mut echo);
+            }
+        } else if c == termios.c_cc[VKILL] {
+            if erase_echo {
+                for _ in 0..self.line.len() {
+                    Self::echo_erase(&mut echo);
+                }
+            }
+            self.line.clear();
+        } else if c == termios.c_cc[VEOF] {
+            // VEOF 结束当前行但不加入数据，空行时表示文件结束
+            if self.line.is_empty() {
+                self.eof = true;
+            } else {
+                self.commit_line();
+            }
+        } else if c == b'\n' || self.line.len() < MAX_CANON {
+            self.line.push(c);
+            if termios.echo() {
+                self.echo_byte(c, &mut echo);
+            }
+            if c == b'\n' {
+                self.commit_line();
+            }
+        }
+    }
+
+    /// 读取可用的输入，规范模式下最多返回一行
+    ///
+    /// # 返回
+    /// 没有可读数据时返回 None（调用者应等待），文件结束返回 Some(0)
+    pub fn read(&mut self, buf: &mut [u8]) -> Option<usize> {
+        if self.ready.is_empty() {
+            if self.eof {
+                self.eof = false;
+                return Some(0);
+            }
+            return None;
+        }
+
+        let canonical = self.termios.is_canonical();
+        let mut count = 0;
+        while count < buf.len() {
+            let Some(c) = self.ready.pop() else {
+                break;
+            };
+            buf[count] = c;
+            count += 1;
+            if canonical && c == b'\n' {
+                break;
+            }
+        }
+        Some(count)
+    }
+
+    /// 把正在编辑的行移入可读缓冲区，放不下的部分被丢弃
+    fn commit_line(&mut self) {
+        self.ready.push_slice(&self.line);
+        self.line.clear();
+    }
+
+    /// 回显一个字节，OPOST|ONLCR 时 '\n' 输出为 "\r\n"
+    fn echo_byte(&self, c: u8, echo: &mut impl FnMut(u8)) {
+        let onlcr = oflag::OPOST | oflag::ONLCR;
+        if c == b'\n' && self.termios.c_oflag & onlcr == onlcr {
+            echo(b'\r');
+        }
+        echo(c);
+    }
+
+    /// 在屏幕上擦除一个字符
+    fn echo_erase(echo: &mut impl FnMut(u8)) {
+        echo(8);
+        echo(b' ');
+        echo(8);
+    }
+}
+
+impl Default for LineDiscipline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 控制台的行规程
+static CONSOLE_TTY: Mutex<LineDiscipline> = Mutex::new(LineDiscipline::new());
+
+/// 锁定控制台的行规程
+pub fn console_tty() -> MutexGuard<'static, LineDiscipline> {
+    CONSOLE_TTY.lock()
+}
+
+/// 把控制台 UART 已收到的字节全部交给行规程
+pub fn console_receive(tty: &mut LineDiscipline) {
+    while let Some(c) = crate::console::getchar() {
+        tty.receive(c, crate::console::putchar);
+    }
+}
+
+/// UART 接收中断：读空接收 FIFO 并唤醒等待控制台输入的 poll/select/epoll
+///
+/// 行规程被其他 CPU 持有时由持有者读取 FIFO，这里只负责唤醒
+pub fn console_interrupt() {
+    if let Some(mut tty) = CONSOLE_TTY.try_lock() {
+        while let Some(c) = crate::console::getchar() {
+            tty.receive(c, crate::console::putchar_no_lock);
+        }
+    }
+    crate::fs::poll::wake_pollers();
+}
@@ -57,8 +57,9 @@ pub fn init() {
 ///
 /// # 参数
 /// - filename: 文件名（必须是绝对路径）
-/// - flags: O_RDONLY (0), O_WRONLY (1), O_RDWR (2), O_CREAT (0o100), O_EXCL (0o200), O_TRUNC (0o1000)
-/// - mode: 文件权限（创建时使用，当前未实现）
+/// - flags: O_RDONLY (0), O_WRONLY (1), O_RDWR (2), O_CREAT (0o100), O_EXCL (0o200), O_TRUNC (0o1000),
+///   O_DIRECTORY (0o200000), O_NOFOLLOW (0o400000)
+/// - mode: 文件权限（在 ext4 上创建时使用）
 ///
 /// # 返回
 /// 成功返回文件描述符，失败返回错误码
@@ -66,9 +67,17 @@ pub fn init() {
 /// # 支持的标志
 /// - O_RDONLY/O_WRONLY/O_RDWR: 读写模式
 /// - O_CREAT: 文件不存在时创建
-/// - O_EXCL: 与 O_CREAT 一起使用，文件已存在时返回错误
+/// - O_EXCL: 与 O_CREAT 一起使用，文件（包括符号链接本身）已存在时返回 EEXIST
 /// - O_TRUNC: 截断文件为空
-pub fn file_open(filename: &str, flags: u32, _mode: u32) -> Result<usize, i32> {
+/// - O_DIRECTORY: 目标不是目录时返回 ENOTDIR（目录由 `file_opendir` 打开）
+/// - O_NOFOLLOW: 最后一个分量是符号链接时返回 ELOOP，否则跟随符号链接
+pub fn file_open(filename: &str, flags: u32, mode: u32) -> Result<usize, i32> {
+    // 设备文件不经过 RootFS
+    if let Some(name) = filename.strip_prefix(crate::fs::devfs::DEV_PREFIX) {
+        let file = crate::fs::devfs::open(name, flags)?;
+        return unsafe { get_file_fd_install(file) }.ok_or(errno::Errno::TooManyOpenFiles.as_neg_i32());
+    }
+
     unsafe {
         // 1. 获取 RootFS 超级块
         let sb_ptr = get_rootfs();
@@ -82,23 +91,35 @@ pub fn file_open(filename: &str, flags: u32, _mode: u32) -> Result<usize, i32> {
         let o_creat = (flags & FileFlags::O_CREAT) != 0;
         let o_excl = (flags & FileFlags::O_EXCL) != 0;
         let o_trunc = (flags & FileFlags::O_TRUNC) != 0;
+        let o_directory = (flags & FileFlags::O_DIRECTORY) != 0;
+        let o_nofollow = (flags & FileFlags::O_NOFOLLOW) != 0;
+
+        // O_EXCL + O_CREAT：路径已存在（即使是悬空的符号链接）时返回错误
+        if o_excl && o_creat && sb.lookup(filename).is_some() {
+            return Err(errno::Errno::FileExists.as_neg_i32());
+        }
 
-        // 2. 查找文件节点
-        let (node, _was_created) = match sb.lookup(filename) {
-            Some(n) => {
-                // 文件已存在
-                if o_excl && o_creat {
-                    // O_EXCL + O_CREAT：文件已存在，返回错误
-                    return Err(errno::Errno::FileExists.as_neg_i32());
+        // 2. 查找文件节点（按 O_NOFOLLOW 处理最后一个分量的符号链接）
+        let (node, _was_created) = match sb.resolve(filename, !o_nofollow) {
+            Ok((n, _)) => (n, false),
+            Err(e) if e != errno::Errno::NoSuchFileOrDirectory.as_neg_i32() => return Err(e),
+            Err(_) => {
+                // RootFS 中不存在，尝试已挂载的 ext4
+                match ext4_open(filename, flags) {
+                    Err(e) if e == errno::Errno::NoSuchFileOrDirectory.as_neg_i32() => {}
+                    result => return result,
                 }
-                (n, false)
-            }
-            None => {
+
                 // 文件不存在
                 if o_creat {
-                    // 创建新文件
-                    if let Err(e) = sb.create_file(filename, Vec::new()) {
-                        return Err(e);
+                    // 创建新文件；父目录不在 RootFS 中时在 ext4 上创建
+                    match sb.create_file(filename, Vec::new()) {
+                        Ok(()) => {}
+                        Err(e) if e == errno::Errno::NoSuchFileOrDirectory.as_neg_i32() && ext4::is_mounted() => {
+                            ext4_create(filename, EXT4_S_IFREG | (mode & 0o7777) as u16)?;
+                            return ext4_open(filename, flags & !FileFlags::O_TRUNC);
+                        }
+                        Err(e) => return Err(e),
                     }
                     // 重新查找刚创建的文件
                     match sb.lookup(filename) {
@@ -111,6 +132,11 @@ pub fn file_open(filename: &str, flags: u32, _mode: u32) -> Result<usize, i32> {
             }
         };
 
+        // 3. O_DIRECTORY 要求目标是目录
+        if o_directory && !node.is_dir() {
+            return Err(errno::Errno::NotADirectory.as_neg_i32());
+        }
+
         // 4. 检查是否是目录（目录不能打开为文件）
         if node.is_dir() {
             return Err(errno::Errno::IsADirectory.as_neg_i32());
@@ -126,7 +152,7 @@ pub fn file_open(filename: &str, flags: u32, _mode: u32) -> Result<usize, i32> {
 
         // 6. 创建 File 对象
         let file_flags = FileFlags::new(flags);
-        let file = Arc::new(File::new(file_flags));
+        let mut file = File::new(file_flags);
 
         // 7. 设置文件操作
         file.set_ops(&ROOTFS_FILE_OPS);
@@ -137,13 +163,59 @@ pub fn file_open(filename: &str, flags: u32, _mode: u32) -> Result<usize, i32> {
         file.set_private_data(node_ptr);
 
         // 9. 分配文件描述符
-        match get_file_fd_install(file) {
+        match get_file_fd_install(Arc::new(file)) {
             Some(fd) => Ok(fd),
             None => Err(errno::Errno::TooManyOpenFiles.as_neg_i32()),
         }
     }
 }
 
+/// ext4 inode 的文件类型位
+const EXT4_S_IFREG: u16 = ext4::inode::file_type::S_IFREG;
+const EXT4_S_IFDIR: u16 = ext4::inode::file_type::S_IFDIR;
+
+/// 在已挂载的 ext4 上创建 inode，ext4 未挂载时返回 ENOENT
+fn ext4_create(path: &str, mode: u16) -> Result<u32, i32> {
+    match ext4::get_ext4_fs() {
+        Some(fs) => unsafe { (*fs).create(path, mode) },
+        None => Err(errno::Errno::NoSuchFileOrDirectory.as_neg_i32()),
+    }
+}
+
+/// 在已挂载的 ext4 上打开常规文件
+///
+/// 由 `file_open` 在 RootFS 中找不到路径时调用；ext4 未挂载时返回 ENOENT。
+/// O_NOFOLLOW 时最后一个分量是符号链接返回 ELOOP；O_TRUNC 把文件截断为空并写回 inode
+unsafe fn ext4_open(filename: &str, flags: u32) -> Result<usize, i32> {
+    let fs = match ext4::get_ext4_fs() {
+        Some(fs) => &*fs,
+        None => return Err(errno::Errno::NoSuchFileOrDirectory.as_neg_i32()),
+    };
+
+    let (ino, mut inode) = fs.lookup_path(filename, (flags & FileFlags::O_NOFOLLOW) == 0)?;
+
+    if inode.is_symlink() {
+        return Err(errno::Errno::TooManySymbolicLinks.as_neg_i32());
+    }
+
+    if (flags & FileFlags::O_DIRECTORY) != 0 && !inode.is_dir() {
+        return Err(errno::Errno::NotADirectory.as_neg_i32());
+    }
+    if inode.is_dir() {
+        return Err(errno::Errno::IsADirectory.as_neg_i32());
+    }
+    if (flags & FileFlags::O_TRUNC) != 0 && inode.get_size() != 0 {
+        ext4::file::ext4_truncate(fs, &mut inode, 0)?;
+    }
+
+    let mut file = File::new(FileFlags::new(flags));
+    file.set_ops(&EXT4_FILE_OPS);
+    let ctx = Box::new(Ext4FileContext { ino });
+    file.set_private_data(Box::into_raw(ctx) as *mut u8);
+
+    get_file_fd_install(Arc::new(file)).ok_or(errno::Errno::TooManyOpenFiles.as_neg_i32())
+}
+
 ///
 ///
 /// # 参数
@@ -230,6 +302,147 @@ pub fn file_write(fd: usize, buf: &[u8], count: usize) -> Result<usize, i32> {
     }
 }
 
+/// iovec 结构体（用于 readv/writev）
+///
+/// 对应 Linux `struct iovec` (include/uapi/linux/uio.h)
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Iovec {
+    /// 缓冲区起始地址
+    pub iov_base: *mut u8,
+    /// 缓冲区长度
+    pub iov_len: usize,
+}
+
+/// 分散读：依次把数据读入每个 iovec 段
+///
+/// 对应 Linux `vfs_readv()`
+///
+/// # 返回
+/// 成功返回读取的总字节数；某段短读（包括 EOF）时停止。
+/// 第一段即出错时返回错误码，否则返回已读取的字节数
+pub fn vfs_readv(file: &File, iovs: &[Iovec]) -> Result<usize, i32> {
+    let mut total = 0usize;
+
+    for iov in iovs.iter().filter(|iov| iov.iov_len > 0) {
+        let result = unsafe { file.read(iov.iov_base, iov.iov_len) };
+        if result < 0 {
+            if total == 0 {
+                return Err(result as i32);
+            }
+            break;
+        }
+
+        total += result as usize;
+        if (result as usize) < iov.iov_len {
+            break;
+        }
+    }
+
+    Ok(total)
+}
+
+/// 聚集写：依次写出每个 iovec 段
+///
+/// 对应 Linux `vfs_writev()`
+///
+/// # 返回
+/// 成功返回写入的总字节数；某段短写时停止。
+/// 第一段即出错时返回错误码，否则返回已写入的字节数
+pub fn vfs_writev(file: &File, iovs: &[Iovec]) -> Result<usize, i32> {
+    let mut total = 0usize;
+
+    for iov in iovs.iter().filter(|iov| iov.iov_len > 0) {
+        let result = unsafe { file.write(iov.iov_base, iov.iov_len) };
+        if result < 0 {
+            if total == 0 {
+                return Err(result as i32);
+            }
+            break;
+        }
+
+        total += result as usize;
+        if (result as usize) < iov.iov_len {
+            break;
+        }
+    }
+
+    Ok(total)
+}
+
+/// 在内核中把数据从一个文件复制到另一个文件（sendfile 核心逻辑）
+///
+/// 对应 Linux `do_sendfile()` (fs/read_write.c)
+///
+/// # 参数
+/// - out_file: 输出文件（管道、socket 或常规文件）
+/// - in_file: 输入文件（必须是常规文件）
+/// - offset: 输入偏移；`None` 表示使用并更新输入文件的当前位置
+/// - count: 要复制的字节数
+///
+/// # 返回
+/// 成功返回 (复制的字节数, 新的输入偏移)，失败返回错误码
+///
+/// # 说明
+/// - 数据直接从输入 inode 的页缓存读取，不经过用户空间
+/// - 输出发生短写时停止，只计入实际写出的字节
+/// - 已复制部分数据后遇到输出错误，返回已复制的字节数
+pub fn do_sendfile(
+    out_file: &File,
+    in_file: &File,
+    offset: Option<u64>,
+    count: usize,
+) -> Result<(usize, u64), i32> {
+    use crate::fs::buffer::PAGE_SIZE;
+
+    // 输入文件必须可读
+    if in_file.flags.is_writeonly() || out_file.flags.is_readonly() {
+        return Err(errno::Errno::BadFileNumber.as_neg_i32());
+    }
+
+    // 输入文件必须是有 inode 的常规文件
+    let inode = match unsafe { (*in_file.inode.get()).clone() } {
+        Some(inode) if inode.mode.is_regular_file() => inode,
+        _ => return Err(errno::Errno::InvalidArgument.as_neg_i32()),
+    };
+
+    let start = offset.unwrap_or_else(|| in_file.get_pos());
+    let mut pos = start as usize;
+    let mut total = 0usize;
+    let mut chunk = alloc::vec![0u8; PAGE_SIZE];
+
+    while total < count {
+        let want = (count - total).min(PAGE_SIZE);
+        let read = inode.read_data(pos, &mut chunk[..want]);
+        if read == 0 {
+            break;  // EOF
+        }
+
+        let written = unsafe { out_file.write(chunk.as_ptr(), read) };
+        if written < 0 {
+            if total == 0 {
+                return Err(written as i32);
+            }
+            break;
+        }
+
+        let written = written as usize;
+        total += written;
+        pos += written;
+
+        // 输出短写：停止复制
+        if written < read {
+            break;
+        }
+    }
+
+    if offset.is_none() {
+        in_file.set_pos(pos as u64);
+    }
+
+    Ok((total, pos as u64))
+}
+
 ///
 ///
 /// # 参数
@@ -302,6 +515,29 @@ pub fn file_stat(fd: usize, stat: &mut Stat) -> Result<(), i32> {
                             stat.st_ctime = 0;
                             stat.st_ctime_nsec = 0;
                             return Ok(());
+                        } else if core::ptr::eq(*ops_ref, &EXT4_FILE_OPS as *const FileOps) {
+                            // ext4 常规文件：从磁盘上的 inode 填充
+                            let (fs, ino) = ext4_file_info(file_ref)
+                                .ok_or(errno::Errno::BadFileNumber.as_neg_i32())?;
+                            let inode = fs.read_inode(ino)?;
+                            stat.st_dev = 0;
+                            stat.st_ino = ino as u64;
+                            stat.st_nlink = inode.links_count as u32;
+                            stat.st_uid = inode.uid as u32;
+                            stat.st_gid = inode.gid as u32;
+                            stat.st_rdev = 0;
+                            stat.st_size = inode.size as i64;
+                            stat.st_blocks = inode.blocks;
+                            stat.st_blksize = fs.block_size as u64;
+                            stat.set_regular_file();
+                            stat.set_mode(inode.mode as u32 & 0o7777);
+                            stat.st_atime = inode.atime as u64;
+                            stat.st_atime_nsec = 0;
+                            stat.st_mtime = inode.mtime as u64;
+                            stat.st_mtime_nsec = 0;
+                            stat.st_ctime = inode.ctime as u64;
+                            stat.st_ctime_nsec = 0;
+                            return Ok(());
                         } else if core::ptr::eq(*ops_ref, &EXT4_DIR_OPS as *const FileOps) {
                             // ext4 目录
                             stat.st_dev = 0;
@@ -399,6 +635,12 @@ pub mod fcntl {
     /// 设置文件状态标志
     pub const F_SETFL: usize = 4;
 
+    /// 设置管道容量
+    pub const F_SETPIPE_SZ: usize = 1031;
+
+    /// 获取管道容量
+    pub const F_GETPIPE_SZ: usize = 1032;
+
     /// FD_CLOEXEC 标志值
     pub const FD_CLOEXEC: usize = 1;
 }
@@ -419,6 +661,7 @@ pub mod fcntl {
 /// - F_SETFD (2) - 设置 close-on-exec 标志
 /// - F_GETFL (3) - 获取文件状态标志
 /// - F_SETFL (4) - 设置文件状态标志
+/// - F_SETPIPE_SZ (1031) / F_GETPIPE_SZ (1032) - 设置/获取管道容量
 pub fn file_fcntl(fd: usize, cmd: usize, arg: usize) -> Result<usize, i32> {
     use crate::fs::file::{get_file_fd, get_file_fd_install};
 
@@ -479,7 +722,7 @@ pub fn file_fcntl(fd: usize, cmd: usize, arg: usize) -> Result<usize, i32> {
                     None => return Err(errno::Errno::BadFileNumber.as_neg_i32()),
                 };
 
-                // 返回文件状态标志（访问模式）
+                // 返回文件访问模式和状态标志
                 Ok(file.flags.bits() as usize)
             }
 
@@ -490,27 +733,23 @@ pub fn file_fcntl(fd: usize, cmd: usize, arg: usize) -> Result<usize, i32> {
                     None => return Err(errno::Errno::BadFileNumber.as_neg_i32()),
                 };
 
-                // 只允许设置部分标志（O_NONBLOCK, O_APPEND, O_ASYNC 等）
-                // 不允许改变访问模式（O_RDONLY, O_WRONLY, O_RDWR）
-                const SETFL_FLAGS: u32 = crate::fs::file::FileFlags::O_APPEND
-                    | crate::fs::file::FileFlags::O_NONBLOCK
-                    | crate::fs::file::FileFlags::O_SYNC
-                    | crate::fs::file::FileFlags::O_DSYNC;
-
-                // 保留访问模式
-                let accmode = file.flags.bits() & crate::fs::file::FileFlags::O_ACCMODE;
-                // 设置新标志
-                let new_flags = accmode | (arg as u32 & SETFL_FLAGS);
-
-                // 使用 unsafe 设置标志（FileFlags 不是 Mutex，需要直接赋值）
-                unsafe {
-                    let flags_ptr = &file.flags as *const FileFlags as *mut FileFlags;
-                    (*flags_ptr).set_bits(new_flags);
-                }
+                // 只允许修改 O_NONBLOCK, O_APPEND, O_ASYNC 等状态标志
+                // 访问模式（O_RDONLY, O_WRONLY, O_RDWR）和其他标志保持不变
+                file.flags.set_status_flags(arg as u32);
 
                 Ok(0)  // 成功返回 0
             }
 
+            // F_SETPIPE_SZ / F_GETPIPE_SZ: 管道容量
+            fcntl::F_SETPIPE_SZ | fcntl::F_GETPIPE_SZ => {
+                let file = match get_file_fd(fd) {
+                    Some(f) => f,
+                    None => return Err(errno::Errno::BadFileNumber.as_neg_i32()),
+                };
+
+                crate::fs::pipe::pipe_fcntl(&file, cmd, arg)
+            }
+
             // 不支持的命令
             _ => {
                 Err(errno::Errno::FunctionNotImplemented.as_neg_i32())
@@ -549,8 +788,13 @@ pub fn file_mkdir(pathname: &str, mode: u32) -> Result<(), i32> {
 
         let sb = &*sb_ptr;
 
-        // 调用 RootFS 创建目录
-        sb.create_dir(pathname, mode)
+        // 调用 RootFS 创建目录；父目录不在 RootFS 中时在 ext4 上创建
+        match sb.create_dir(pathname, mode) {
+            Err(e) if e == errno::Errno::NoSuchFileOrDirectory.as_neg_i32() && ext4::is_mounted() => {
+                ext4_create(pathname, EXT4_S_IFDIR | (mode & 0o7777) as u16).map(|_| ())
+            }
+            result => result,
+        }
     }
 }
 
@@ -622,8 +866,59 @@ pub fn file_link(oldpath: &str, newpath: &str) -> Result<(), i32> {
 
         let sb = &*sb_ptr;
 
-        // 调用 RootFS 创建硬链接
-        sb.link(oldpath, newpath)
+        // 调用 RootFS 创建硬链接；源文件不在 RootFS 中时在 ext4 上创建
+        match sb.link(oldpath, newpath) {
+            Err(e) if e == errno::Errno::NoSuchFileOrDirectory.as_neg_i32() => match ext4::get_ext4_fs() {
+                Some(fs) => (*fs).link(oldpath, newpath),
+                None => Err(e),
+            },
+            result => result,
+        }
+    }
+}
+
+/// 把打开的文件截断或扩展到 `length` 字节 (ftruncate)
+///
+/// # 返回
+/// 只支持 ext4 常规文件；其他文件返回 EINVAL，未以写方式打开返回 EBADF
+///
+/// - RISC-V: 46
+pub fn file_truncate(fd: usize, length: u64) -> Result<(), i32> {
+    let file = unsafe { get_file_fd(fd) }.ok_or(errno::Errno::BadFileNumber.as_neg_i32())?;
+    let (fs, ino) = ext4_file_info(&file).ok_or(errno::Errno::InvalidArgument.as_neg_i32())?;
+    if file.flags.is_readonly() {
+        return Err(errno::Errno::BadFileNumber.as_neg_i32());
+    }
+
+    let mut inode = fs.read_inode(ino)?;
+    ext4::file::ext4_truncate(fs, &mut inode, length)
+}
+
+/// 修改打开文件的权限位 (fchmod)
+///
+/// # 返回
+/// 只支持 ext4 文件；其他文件返回 EPERM
+///
+/// - RISC-V: 52
+pub fn file_chmod(fd: usize, mode: u32) -> Result<(), i32> {
+    let file = unsafe { get_file_fd(fd) }.ok_or(errno::Errno::BadFileNumber.as_neg_i32())?;
+    let (fs, ino) = ext4_file_info(&file).ok_or(errno::Errno::OperationNotPermitted.as_neg_i32())?;
+
+    let mut inode = fs.read_inode(ino)?;
+    ext4::file::ext4_chmod(fs, &mut inode, mode as u16)
+}
+
+/// 把打开文件所在设备的脏缓冲区写回磁盘 (fsync / fdatasync)
+///
+/// ext4 文件写回整个设备的缓冲区（数据和元数据都在块缓存中）；
+/// RootFS 等内存文件系统没有需要写回的数据，直接返回成功
+///
+/// - RISC-V: 82, 83
+pub fn file_fsync(fd: usize) -> Result<(), i32> {
+    let file = unsafe { get_file_fd(fd) }.ok_or(errno::Errno::BadFileNumber.as_neg_i32())?;
+    match ext4_file_info(&file) {
+        Some((fs, _)) => crate::fs::bio::sync_device(fs.device),
+        None => Ok(()),
     }
 }
 
@@ -729,6 +1024,9 @@ static ROOTFS_FILE_OPS: FileOps = FileOps {
     write: Some(rootfs_file_write),  // 暂时返回 EBADF
     lseek: Some(rootfs_file_lseek),
     close: Some(rootfs_file_close),
+    poll: None,
+    ioctl: None,
+    mmap: None,
 };
 
 // ============================================================================
@@ -794,11 +1092,13 @@ impl DirContext {
 ///
 /// # 参数
 /// - pathname: 目录路径
-/// - flags: 打开标志
+/// - flags: 打开标志（O_NOFOLLOW 时拒绝打开符号链接）
 ///
 /// # 返回
-/// 成功返回文件描述符，失败返回错误码
+/// 成功返回文件描述符，失败返回错误码；目标不是目录时返回 ENOTDIR
 pub fn file_opendir(pathname: &str, flags: u32) -> Result<usize, i32> {
+    let nofollow = (flags & FileFlags::O_NOFOLLOW) != 0;
+
     unsafe {
         // 1. 首先尝试从 RootFS 查找
         let sb_ptr = get_rootfs();
@@ -806,9 +1106,13 @@ pub fn file_opendir(pathname: &str, flags: u32) -> Result<usize, i32> {
         if !sb_ptr.is_null() {
             let sb = &*sb_ptr;
 
-            let lookup_result = sb.lookup(pathname);
+            let lookup_result = match sb.resolve(pathname, !nofollow) {
+                Ok(found) => Some(found),
+                Err(e) if e != errno::Errno::NoSuchFileOrDirectory.as_neg_i32() => return Err(e),
+                Err(_) => None,
+            };
 
-            if let Some(node) = lookup_result {
+            if let Some((node, resolved)) = lookup_result {
                 // 检查是否是目录
                 if !node.is_dir() {
                     return Err(errno::Errno::NotADirectory.as_neg_i32());
@@ -816,18 +1120,18 @@ pub fn file_opendir(pathname: &str, flags: u32) -> Result<usize, i32> {
 
                 // 创建 File 对象
                 let file_flags = FileFlags::new(flags);
-                let file = Arc::new(File::new(file_flags));
+                let mut file = File::new(file_flags);
 
                 // 设置目录操作
                 file.set_ops(&ROOTFS_DIR_OPS);
 
-                // 创建目录上下文
-                let ctx = Box::new(DirContext::new_rootfs(pathname));
+                // 创建目录上下文（使用跟随符号链接后的路径）
+                let ctx = Box::new(DirContext::new_rootfs(&resolved));
                 let ctx_ptr = Box::into_raw(ctx) as *mut u8;
                 file.set_private_data(ctx_ptr);
 
                 // 分配文件描述符
-                return match get_file_fd_install(file) {
+                return match get_file_fd_install(Arc::new(file)) {
                     Some(fd) => Ok(fd),
                     None => Err(errno::Errno::TooManyOpenFiles.as_neg_i32())
                 };
@@ -836,13 +1140,25 @@ pub fn file_opendir(pathname: &str, flags: u32) -> Result<usize, i32> {
 
         // 2. RootFS 中未找到，尝试从 ext4 查找
         if ext4::is_mounted() {
+            // 检查最后一个分量的类型
+            if let Some(fs) = ext4::get_ext4_fs() {
+                if let Ok((_, inode)) = (*fs).lookup_path(pathname, !nofollow) {
+                    if inode.is_symlink() {
+                        return Err(errno::Errno::TooManySymbolicLinks.as_neg_i32());
+                    }
+                    if !inode.is_dir() {
+                        return Err(errno::Errno::NotADirectory.as_neg_i32());
+                    }
+                }
+            }
+
             // 检查目录是否存在
             let entries = ext4::list_dir(pathname);
 
             if let Some(_entries) = entries {
                 // 创建 File 对象
                 let file_flags = FileFlags::new(flags);
-                let file = Arc::new(File::new(file_flags));
+                let mut file = File::new(file_flags);
 
                 // 设置目录操作（使用 ext4 操作）
                 file.set_ops(&EXT4_DIR_OPS);
@@ -853,7 +1169,7 @@ pub fn file_opendir(pathname: &str, flags: u32) -> Result<usize, i32> {
                 file.set_private_data(ctx_ptr);
 
                 // 分配文件描述符
-                return match get_file_fd_install(file) {
+                return match get_file_fd_install(Arc::new(file)) {
                     Some(fd) => Ok(fd),
                     None => Err(errno::Errno::TooManyOpenFiles.as_neg_i32())
                 };
@@ -1148,6 +1464,9 @@ static ROOTFS_DIR_OPS: FileOps = FileOps {
     write: None,
     lseek: Some(rootfs_file_lseek),
     close: Some(rootfs_file_close),
+    poll: None,
+    ioctl: None,
+    mmap: None,
 };
 
 /// ext4 目录读取操作
@@ -1247,4 +1566,127 @@ static EXT4_DIR_OPS: FileOps = FileOps {
     write: None,
     lseek: None,  // ext4 目录不支持 lseek
     close: Some(ext4_dir_close),
+    poll: None,
+    ioctl: None,
+    mmap: None,
+};
+
+// ============================================================================
+// ext4 常规文件操作
+// ============================================================================
+
+/// 打开的 ext4 常规文件（存储在 File 的 private_data 中）
+///
+/// 只保存 inode 编号，每次操作都从磁盘（块缓存）重新读取 inode，
+/// 同一文件的多个打开实例看到的大小总是一致
+pub struct Ext4FileContext {
+    /// inode 编号
+    pub ino: u32,
+}
+
+/// 如果 `file` 是打开的 ext4 常规文件，返回 ext4 实例和 inode 编号
+pub fn ext4_file_info(file: &File) -> Option<(&'static ext4::Ext4FileSystem, u32)> {
+    unsafe {
+        let ops = (*file.ops.get())?;
+        if !core::ptr::eq(ops, &EXT4_FILE_OPS) {
+            return None;
+        }
+        let ctx = &*((*file.private_data.get())? as *const Ext4FileContext);
+        let fs = &*ext4::get_ext4_fs()?;
+        Some((fs, ctx.ino))
+    }
+}
+
+/// ext4 文件读取操作
+fn ext4_file_read(file: &File, buf: &mut [u8]) -> isize {
+    let (fs, ino) = match ext4_file_info(file) {
+        Some(info) => info,
+        None => return -9,  // EBADF
+    };
+    let inode = match fs.read_inode(ino) {
+        Ok(inode) => inode,
+        Err(e) => return e as isize,
+    };
+
+    let pos = file.get_pos();
+    match ext4::file::ext4_file_read(fs, &inode, pos, buf) {
+        Ok(n) => {
+            file.set_pos(pos + n as u64);
+            n as isize
+        }
+        Err(e) => e as isize,
+    }
+}
+
+/// ext4 文件写入操作
+///
+/// 新数据和新的文件大小在返回前都已写回磁盘；O_APPEND 时总是写到文件末尾
+fn ext4_file_write(file: &File, buf: &[u8]) -> isize {
+    let (fs, ino) = match ext4_file_info(file) {
+        Some(info) => info,
+        None => return -9,  // EBADF
+    };
+    if file.flags.is_readonly() {
+        return -9;  // EBADF
+    }
+    let mut inode = match fs.read_inode(ino) {
+        Ok(inode) => inode,
+        Err(e) => return e as isize,
+    };
+
+    let pos = if file.flags.is_append() { inode.get_size() } else { file.get_pos() };
+    match ext4::file::ext4_file_write(fs, &mut inode, pos, buf) {
+        Ok(n) => {
+            file.set_pos(pos + n as u64);
+            n as isize
+        }
+        Err(e) => e as isize,
+    }
+}
+
+/// ext4 文件定位操作
+fn ext4_file_lseek(file: &File, offset: isize, whence: i32) -> isize {
+    let (fs, ino) = match ext4_file_info(file) {
+        Some(info) => info,
+        None => return -9,  // EBADF
+    };
+    let inode = match fs.read_inode(ino) {
+        Ok(inode) => inode,
+        Err(e) => return e as isize,
+    };
+
+    let new_pos = match whence {
+        1 => file.get_pos() as isize + offset,  // SEEK_CUR
+        _ => match ext4::file::ext4_file_lseek(&inode, offset, whence) {
+            Ok(pos) => pos,
+            Err(e) => return e as isize,
+        },
+    };
+    if new_pos < 0 {
+        return -22;  // EINVAL
+    }
+
+    file.set_pos(new_pos as u64);
+    new_pos
+}
+
+/// ext4 文件关闭操作：释放 Ext4FileContext
+fn ext4_file_close(file: &File) -> i32 {
+    unsafe {
+        if let Some(ctx_ptr) = (*file.private_data.get()).take() {
+            drop(Box::from_raw(ctx_ptr as *mut Ext4FileContext));
+        }
+    }
+    0
+}
+
+/// ext4 常规文件操作表
+static EXT4_FILE_OPS: FileOps = FileOps {
+    read: Some(ext4_file_read),
+    write: Some(ext4_file_write),
+    lseek: Some(ext4_file_lseek),
+    close: Some(ext4_file_close),
+    poll: None,
+    ioctl: None,
+    mmap: None,
 };
@@ -23,7 +23,6 @@ use crate::println;
 use crate::cmdline;
 use alloc::vec::Vec;
 use alloc::sync::Arc;
-use alloc::boxed::Box;
 use core::slice;
 
 // 静态存储：init 进程和用户上下文
@@ -116,11 +115,11 @@ fn create_and_start_init_process(program_data: &[u8]) -> Option<*mut Task> {
         (*task_ptr).set_parent(core::ptr::null_mut());
 
         // 创建并初始化文件描述符表
-        let fdtable = Box::new(FdTable::new());
+        let fdtable = Arc::new(FdTable::new());
         (*task_ptr).set_fdtable(Some(fdtable));
 
         // 初始化标准文件描述符
-        if let Some(fdtable) = (*task_ptr).try_fdtable_mut() {
+        if let Some(fdtable) = (*task_ptr).try_fdtable() {
             init_std_fds_for_task(fdtable);
         } else {
             return None;
@@ -211,6 +210,10 @@ fn load_and_setup_elf(task_ptr: *mut Task, program_data: &[u8]) -> Result<(), El
         )
     }.ok_or(ElfError::OutOfMemory)?;
 
+    // 信号处理函数返回用的 rt_sigreturn trampoline
+    unsafe { mm::map_sigreturn_trampoline(get_kernel_page_table_ppn()) }
+        .ok_or(ElfError::OutOfMemory)?;
+
     // 第二遍：加载每个段的数据
     for i in 0..phdr_count {
         let phdr = unsafe { ehdr.get_program_header(program_data, i) }
@@ -489,24 +492,24 @@ pub fn init_std_fds_for_task(fdtable: &crate::fs::FdTable) {
     static UART_DEV: CharDev = CharDev::new(CharDevType::UartConsole, 0);
 
     // 创建 stdin (fd=0)
-    let stdin = Arc::new(File::new(FileFlags::new(FileFlags::O_RDONLY)));
+    let mut stdin = File::new(FileFlags::new(FileFlags::O_RDONLY));
     stdin.set_ops(&UART_OPS);
     stdin.set_private_data(&UART_DEV as *const CharDev as *mut u8);
 
     // 创建 stdout (fd=1)
-    let stdout = Arc::new(File::new(FileFlags::new(FileFlags::O_WRONLY)));
+    let mut stdout = File::new(FileFlags::new(FileFlags::O_WRONLY));
     stdout.set_ops(&UART_OPS);
     stdout.set_private_data(&UART_DEV as *const CharDev as *mut u8);
 
     // 创建 stderr (fd=2)
-    let stderr = Arc::new(File::new(FileFlags::new(FileFlags::O_WRONLY)));
+    let mut stderr = File::new(FileFlags::new(FileFlags::O_WRONLY));
     stderr.set_ops(&UART_OPS);
     stderr.set_private_data(&UART_DEV as *const CharDev as *mut u8);
 
     // 安装标准文件描述符
-    let _ = fdtable.install_fd(0, stdin);
-    let _ = fdtable.install_fd(1, stdout);
-    let _ = fdtable.install_fd(2, stderr);
+    let _ = fdtable.install_fd(0, Arc::new(stdin));
+    let _ = fdtable.install_fd(1, Arc::new(stdout));
+    let _ = fdtable.install_fd(2, Arc::new(stderr));
 }
 
 /// 停止系统
@@ -0,0 +1,71 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! US 键盘布局 (drivers/tty/vt/defkeymap.c_shipped)
+//!
+//! 把 PS/2 set 1 扫描码翻译成 ASCII。主键区的 set 1 扫描码与 evdev 键码相同，
+//! 所以 virtio-input 键盘的事件也使用这张表。
+//! - Shift 选择上档字符，CapsLock 只影响字母（与 Shift 同时生效时相互抵消）
+//! - Ctrl+字母 产生控制字符（Ctrl+C = 0x03）
+//! - Enter 产生 '\r'，与终端和 GUI 控件的约定一致
+
+use super::{ModifierState, Modifiers};
+use crate::drivers::keyboard::ps2::KeyEvent;
+
+/// 无修饰键时的字符，下标为扫描码，0 表示没有对应字符
+const NORMAL: &[u8; 58] = b"\0\x1b1234567890-=\x08\tqwertyuiop[]\r\0asdfghjkl;'`\0\\zxcvbnm,./\0*\0 ";
+
+/// 按住 Shift 时的字符
+const SHIFTED: &[u8; 58] = b"\0\x1b!@#$%^&*()_+\x08\tQWERTYUIOP{}\r\0ASDFGHJKL:\"~\0|ZXCVBNM<>?\0*\0 ";
+
+/// 按当前修饰键把扫描码翻译成 ASCII
+///
+/// 修饰键、功能键和扩展键没有字符，返回 None
+pub fn translate(code: u16, mods: &Modifiers) -> Option<u8> {
+    let index = code as usize;
+    if index >= NORMAL.len() {
+        return None;
+    }
+    let base = NORMAL[index];
+    if base == 0 {
+        return None;
+    }
+
+    if base.is_ascii_lowercase() {
+        if mods.ctrl {
+            return Some(base & 0x1F);
+        }
+        return Some(if mods.shift != mods.caps { base.to_ascii_uppercase() } else { base });
+    }
+    Some(if mods.shift { SHIFTED[index] } else { base })
+}
+
+/// 跟踪修饰键状态并把按键事件翻译成字符
+///
+/// 输入层持有一个全局实例，`poll_event` 返回的每个键盘事件都经过它
+#[derive(Debug, Default)]
+pub struct Keymap {
+    state: ModifierState,
+}
+
+impl Keymap {
+    pub const fn new() -> Self {
+        Self { state: ModifierState::new() }
+    }
+
+    /// 当前修饰键状态
+    pub fn modifiers(&self) -> Modifiers {
+        self.state.modifiers()
+    }
+
+    /// 处理一个按键事件，按下产生字符时返回该字符
+    pub fn feed(&mut self, event: KeyEvent) -> Option<u8> {
+        self.state.apply(event);
+        match event {
+            KeyEvent::Press(code) => translate(code, &self.state.modifiers()),
+            KeyEvent::Release(_) => None,
+        }
+    }
+}
@@ -8,12 +8,19 @@ use crate::drivers::mouse::ps2::{MouseEvent, MOUSE};
 use alloc::collections::vec_deque::VecDeque;
 use core::sync::atomic::{AtomicBool, Ordering};
 
+pub mod keymap;
+
+pub const EV_SYN: u16 = 0x00;  // 同步事件
 pub const EV_KEY: u16 = 0x01;  // 按键事件
 pub const EV_REL: u16 = 0x02;  // 相对坐标事件
 pub const EV_ABS: u16 = 0x03;  // 绝对坐标事件
 
+pub const SYN_REPORT: u16 = 0x00;
+
 pub const REL_X: u16 = 0x00;
 pub const REL_Y: u16 = 0x01;
+pub const ABS_X: u16 = 0x00;
+pub const ABS_Y: u16 = 0x01;
 pub const BTN_LEFT: u16 = 0x110;
 pub const BTN_RIGHT: u16 = 0x111;
 pub const BTN_MIDDLE: u16 = 0x112;
@@ -42,9 +49,264 @@ pub enum InputEvent {
     MouseMove { dx: i16, dy: i16 },
     /// 鼠标按键
     MouseButton { left: bool, right: bool, middle: bool },
+    /// 绝对坐标（virtio-tablet，范围由设备决定，QEMU 为 0..=0x7FFF）
+    MouseAbsolute { x: u16, y: u16 },
+    /// 按键按下后按 US 布局和修饰键解析出的字符，紧跟在对应的 `Keyboard` 事件之后
+    Char(u8),
+}
+
+/// 修饰键与锁定键状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// Caps Lock 是否开启
+    pub caps: bool,
+    /// Num Lock 是否开启
+    pub num: bool,
+    /// Scroll Lock 是否开启
+    pub scroll: bool,
+}
+
+impl Modifiers {
+    /// 锁定键状态对应的 PS/2 LED 位掩码
+    pub fn leds(&self) -> u8 {
+        use crate::drivers::keyboard::ps2::led;
+
+        let mut leds = 0;
+        if self.scroll {
+            leds |= led::SCROLL_LOCK;
+        }
+        if self.num {
+            leds |= led::NUM_LOCK;
+        }
+        if self.caps {
+            leds |= led::CAPS_LOCK;
+        }
+        leds
+    }
+}
+
+/// evdev 的右 Ctrl / 右 Alt（PS/2 使用扩展扫描码 `KEY_RCTRL` / `KEY_RALT`）
+const EV_KEY_RIGHTCTRL: u16 = 97;
+const EV_KEY_RIGHTALT: u16 = 100;
+
+/// 是否为修饰键或锁定键（不自动重复）
+pub fn is_modifier_key(code: u16) -> bool {
+    use crate::drivers::keyboard::ps2::scancode;
+
+    matches!(
+        code,
+        scancode::KEY_LSHIFT
+            | scancode::KEY_RSHIFT
+            | scancode::KEY_LCTRL
+            | scancode::KEY_RCTRL
+            | scancode::KEY_LALT
+            | scancode::KEY_RALT
+            | scancode::KEY_CAPSLOCK
+            | scancode::KEY_NUMLOCK
+            | scancode::KEY_SCROLLLOCK
+            | EV_KEY_RIGHTCTRL
+            | EV_KEY_RIGHTALT
+    )
+}
+
+/// 按键事件驱动的修饰键状态机
+///
+/// PS/2 set 1 扫描码与 evdev 键码在修饰键和锁定键上一致，
+/// 右 Ctrl/Alt 同时接受两种编码
+#[derive(Debug, Default)]
+pub struct ModifierState {
+    mods: Modifiers,
+    /// 当前按住的锁定键（`led::*` 位），按住时的自动重复不再翻转
+    held_locks: u8,
+}
+
+impl ModifierState {
+    pub const fn new() -> Self {
+        Self {
+            mods: Modifiers { shift: false, ctrl: false, alt: false, caps: false, num: false, scroll: false },
+            held_locks: 0,
+        }
+    }
+
+    /// 当前状态
+    pub fn modifiers(&self) -> Modifiers {
+        self.mods
+    }
+
+    /// 处理一个按键事件
+    ///
+    /// # 返回
+    /// 锁定键状态是否改变（需要更新键盘 LED）
+    pub fn apply(&mut self, event: KeyEvent) -> bool {
+        use crate::drivers::keyboard::ps2::{led, scancode};
+
+        let (code, pressed) = match event {
+            KeyEvent::Press(code) => (code, true),
+            KeyEvent::Release(code) => (code, false),
+        };
+        let (lock, flag) = match code {
+            scancode::KEY_LSHIFT | scancode::KEY_RSHIFT => {
+                self.mods.shift = pressed;
+                return false;
+            }
+            scancode::KEY_LCTRL | scancode::KEY_RCTRL | EV_KEY_RIGHTCTRL => {
+                self.mods.ctrl = pressed;
+                return false;
+            }
+            scancode::KEY_LALT | scancode::KEY_RALT | EV_KEY_RIGHTALT => {
+                self.mods.alt = pressed;
+                return false;
+            }
+            scancode::KEY_CAPSLOCK => (led::CAPS_LOCK, &mut self.mods.caps),
+            scancode::KEY_NUMLOCK => (led::NUM_LOCK, &mut self.mods.num),
+            scancode::KEY_SCROLLLOCK => (led::SCROLL_LOCK, &mut self.mods.scroll),
+            _ => return false,
+        };
+
+        if !pressed {
+            self.held_locks &= !lock;
+            return false;
+        }
+        if self.held_locks & lock != 0 {
+            return false;
+        }
+        self.held_locks |= lock;
+        *flag = !*flag;
+        true
+    }
+}
+
+/// 全局键盘布局，跟踪 PS/2 和 virtio-input 键盘共同的修饰键状态
+static KEYMAP: spin::Mutex<keymap::Keymap> = spin::Mutex::new(keymap::Keymap::new());
+
+/// 当前修饰键与锁定键状态（供控件和快捷键查询）
+pub fn modifiers() -> Modifiers {
+    KEYMAP.lock().modifiers()
+}
+
+/// 自动重复默认延迟（毫秒）
+pub const DEFAULT_REPEAT_DELAY_MS: u64 = 500;
+/// 自动重复默认间隔（毫秒）
+pub const DEFAULT_REPEAT_INTERVAL_MS: u64 = 50;
+
+/// 毫秒转换为 jiffies（至少 1 个 tick）
+const fn ms_to_ticks(ms: u64) -> u64 {
+    let ticks = ms * crate::config::TIMER_HZ / 1000;
+    if ticks == 0 { 1 } else { ticks }
+}
+
+/// jiffies 转换为毫秒
+const fn ticks_to_ms(ticks: u64) -> u64 {
+    ticks * 1000 / crate::config::TIMER_HZ
+}
+
+/// 按键自动重复
+///
+/// 非修饰键按住超过 `delay` 个 tick 后，每 `interval` 个 tick 合成一次 `Press`，
+/// 释放或按下其他键时停止。时间由调用者传入，便于测试
+#[derive(Debug)]
+pub struct KeyRepeat {
+    /// 首次重复前的延迟（tick）
+    delay: u64,
+    /// 重复间隔（tick）
+    interval: u64,
+    /// 按住的键及下一次重复的时间
+    held: Option<(u16, u64)>,
+}
+
+impl KeyRepeat {
+    /// 以 tick 为单位创建（间隔至少为 1）
+    pub const fn new(delay: u64, interval: u64) -> Self {
+        Self {
+            delay,
+            interval: if interval == 0 { 1 } else { interval },
+            held: None,
+        }
+    }
+
+    /// 修改延迟和间隔（tick），正在进行的重复在下一次到期后使用新间隔
+    pub fn set_rate(&mut self, delay: u64, interval: u64) {
+        self.delay = delay;
+        self.interval = interval.max(1);
+    }
+
+    /// (延迟, 间隔)，单位 tick
+    pub fn rate(&self) -> (u64, u64) {
+        (self.delay, self.interval)
+    }
+
+    /// 正在重复（或等待首次重复）的键
+    pub fn held_key(&self) -> Option<u16> {
+        self.held.map(|(code, _)| code)
+    }
+
+    /// 下一次重复的时间，没有按住的键时返回 None
+    pub fn next_due(&self) -> Option<u64> {
+        self.held.map(|(_, due)| due)
+    }
+
+    /// 处理真实按键事件
+    pub fn on_key(&mut self, event: KeyEvent, now: u64) {
+        match event {
+            KeyEvent::Press(code) if is_modifier_key(code) => {}
+            // 设备自身的重复不重新计时
+            KeyEvent::Press(code) if self.held_key() == Some(code) => {}
+            KeyEvent::Press(code) => self.held = Some((code, now + self.delay)),
+            KeyEvent::Release(code) if self.held_key() == Some(code) => self.held = None,
+            KeyEvent::Release(_) => {}
+        }
+    }
+
+    /// 到期时返回一个合成的 `Press` 事件
+    ///
+    /// 长时间没有调用时只补发一次，之后从 `now` 开始重新计时
+    pub fn tick(&mut self, now: u64) -> Option<KeyEvent> {
+        let (code, due) = self.held?;
+        if now < due {
+            return None;
+        }
+        self.held = Some((code, now + self.interval));
+        Some(KeyEvent::Press(code))
+    }
+}
+
+/// 全局按键自动重复状态
+static KEY_REPEAT: spin::Mutex<KeyRepeat> = spin::Mutex::new(KeyRepeat::new(
+    ms_to_ticks(DEFAULT_REPEAT_DELAY_MS),
+    ms_to_ticks(DEFAULT_REPEAT_INTERVAL_MS),
+));
+
+/// 设置自动重复的延迟和间隔（毫秒，按 tick 取整）
+pub fn set_key_repeat(delay_ms: u64, interval_ms: u64) {
+    KEY_REPEAT.lock().set_rate(ms_to_ticks(delay_ms), ms_to_ticks(interval_ms));
+}
+
+/// 自动重复的 (延迟, 间隔)，单位毫秒
+pub fn key_repeat() -> (u64, u64) {
+    let (delay, interval) = KEY_REPEAT.lock().rate();
+    (ticks_to_ms(delay), ticks_to_ms(interval))
 }
 
-/// 输入事件队列（最大容量 128）
+/// 当前 jiffies
+fn now_jiffies() -> u64 {
+    #[cfg(feature = "riscv64")]
+    {
+        crate::drivers::timer::get_jiffies()
+    }
+
+    #[cfg(not(feature = "riscv64"))]
+    {
+        0
+    }
+}
+
+/// 输入事件队列的最大容量，队列满时丢弃最旧的事件
+pub const EVENT_QUEUE_CAP: usize = 128;
+
+/// 输入事件队列（最大容量 `EVENT_QUEUE_CAP`）
 static EVENT_QUEUE: spin::Mutex<VecDeque<InputEvent>> = spin::Mutex::new(VecDeque::new());
 
 /// 输入系统初始化标志
@@ -61,26 +323,127 @@ pub fn init() {
     // 初始化鼠标驱动
     mouse::ps2::init();
 
+    // 登记 /dev/input0
+    let _ = crate::fs::devfs::register_char_device(
+        crate::drivers::input::evdev::DEVICE_NAME,
+        &crate::drivers::input::evdev::EVDEV_FILE_OPS,
+    );
+
     INPUT_INIT.store(true, Ordering::Release);
 }
 
+/// 把事件追加到有界队列，队列已满时丢弃并返回最旧的事件
+pub fn push_bounded(queue: &mut VecDeque<InputEvent>, event: InputEvent, cap: usize) -> Option<InputEvent> {
+    let dropped = if queue.len() >= cap { queue.pop_front() } else { None };
+    queue.push_back(event);
+    dropped
+}
+
+/// 把驱动解码的事件放入输入事件队列，并唤醒等待 /dev/input0 的 poll/select
+pub fn push_event(event: InputEvent) {
+    push_bounded(&mut EVENT_QUEUE.lock(), event, EVENT_QUEUE_CAP);
+    crate::fs::poll::wake_pollers();
+}
+
 /// 拉取输入事件（非阻塞）
+///
+/// 返回的键盘事件同时更新修饰键状态；没有新事件时返回到期的自动重复。
+/// 产生字符的按下事件（包括自动重复）之后紧跟一个 `Char` 事件
 pub fn poll_event() -> Option<InputEvent> {
+    let now = now_jiffies();
+    if let Some(event) = next_event() {
+        if let InputEvent::Keyboard(key) = event {
+            let mut repeat = KEY_REPEAT.lock();
+            repeat.on_key(key, now);
+            arm_repeat_timer(&repeat, now);
+            drop(repeat);
+            queue_char(key);
+        }
+        return Some(event);
+    }
     if !INPUT_INIT.load(Ordering::Acquire) {
         return None;
     }
+    let mut repeat = KEY_REPEAT.lock();
+    let key = repeat.tick(now)?;
+    arm_repeat_timer(&repeat, now);
+    drop(repeat);
+    queue_char(key);
+    Some(InputEvent::Keyboard(key))
+}
+
+/// 按下一次重复的时间重新设置延迟工作，没有按住的键时取消
+///
+/// 重复事件由 `poll_event` 产生；延迟工作只负责在到期时唤醒
+/// 睡眠在 /dev/input0 上的 read/poll，使其重新拉取事件
+fn arm_repeat_timer(repeat: &KeyRepeat, now: u64) {
+    #[cfg(feature = "riscv64")]
+    {
+        use crate::drivers::timer::delayed_work::{cancel_delayed_work, schedule_delayed_work};
 
-    // 首先检查键盘事件
-    if let Some(event) = fetch_keyboard_event() {
-        return Some(InputEvent::Keyboard(event));
+        cancel_delayed_work(key_repeat_work);
+        if let Some(due) = repeat.next_due() {
+            schedule_delayed_work(key_repeat_work, due.saturating_sub(now));
+        }
+    }
+
+    #[cfg(not(feature = "riscv64"))]
+    let _ = (repeat, now);
+}
+
+/// 自动重复到期（在时钟中断中执行）
+pub fn key_repeat_work() {
+    crate::fs::poll::wake_pollers();
+}
+
+/// 按键事件交给键盘布局：更新修饰键状态，锁定键翻转时同步键盘 LED；
+/// 按下产生字符时，把 `Char` 事件放到队首，作为下一个事件返回
+fn queue_char(key: KeyEvent) {
+    let mut keymap = KEYMAP.lock();
+    let leds = keymap.modifiers().leds();
+    let c = keymap.feed(key);
+    if keymap.modifiers().leds() != leds {
+        crate::drivers::keyboard::ps2::set_leds(keymap.modifiers().leds());
+    }
+    drop(keymap);
+
+    if let Some(c) = c {
+        EVENT_QUEUE.lock().push_front(InputEvent::Char(c));
+    }
+}
+
+/// 按优先级从各个来源取下一个事件
+fn next_event() -> Option<InputEvent> {
+    if !INPUT_INIT.load(Ordering::Acquire) {
+        return None;
     }
 
-    // 然后检查鼠标事件
-    if let Some(event) = fetch_mouse_event() {
+    // 先取队列中已缓冲的事件
+    if let Some(event) = EVENT_QUEUE.lock().pop_front() {
         return Some(event);
     }
 
-    None
+    // 队列为空时才轮询设备，把收到的所有事件放入队列
+    crate::drivers::input::virtio_input::poll();
+    drain_ps2();
+    EVENT_QUEUE.lock().pop_front()
+}
+
+/// 把 PS/2 键盘和鼠标已收到的事件全部放入事件队列
+///
+/// PS/2 没有接入中断，由读取事件的一方调用，相当于中断处理的下半部。
+/// 多字节的扫描码和鼠标数据包读到中间字节时不产生事件，所以按“是否还有数据”循环
+fn drain_ps2() {
+    while unsafe { KEYBOARD.has_data() } {
+        if let Some(event) = fetch_keyboard_event() {
+            push_event(InputEvent::Keyboard(event));
+        }
+    }
+    while unsafe { MOUSE.has_data() } {
+        if let Some(event) = fetch_mouse_event() {
+            push_event(event);
+        }
+    }
 }
 
 /// 从键盘拉取事件
@@ -120,79 +483,101 @@ fn fetch_mouse_event() -> Option<InputEvent> {
     }
 }
 
-pub fn get_raw_input_event() -> Option<RawInputEvent> {
-    if let Some(event) = poll_event() {
-        let raw_event = match event {
-            InputEvent::Keyboard(key_event) => {
-                // 键盘事件
-                match key_event {
-                    KeyEvent::Press(code) => RawInputEvent {
-                        tv_sec: 0,
-                        tv_usec: 0,
-                        type_: EV_KEY,
-                        code: code as u16,
-                        value: 1,  // 按下
-                    },
-                    KeyEvent::Release(code) => RawInputEvent {
-                        tv_sec: 0,
-                        tv_usec: 0,
-                        type_: EV_KEY,
-                        code: code as u16,
-                        value: 0,  // 释放
-                    },
-                }
-            }
+/// 把 `InputEvent` 翻译成 evdev 风格的 `RawInputEvent`
+///
+/// 记录上一次的鼠标按键状态，按键变化时为每个变化的按键生成一个事件；
+/// 一个输入事件可能产生多个原始事件（如移动的 X/Y 分量），按顺序排队返回
+#[derive(Default)]
+pub struct RawEventTranslator {
+    /// 上一次的按键状态（左、右、中）
+    buttons: (bool, bool, bool),
+    /// 待返回的原始事件
+    pending: VecDeque<RawInputEvent>,
+}
+
+impl RawEventTranslator {
+    pub const fn new() -> Self {
+        Self {
+            buttons: (false, false, false),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// 取出下一个待返回的原始事件
+    pub fn pop(&mut self) -> Option<RawInputEvent> {
+        self.pending.pop_front()
+    }
+
+    /// 翻译一个输入事件，结果追加到待返回队列
+    ///
+    /// 按键状态没有变化的 `MouseButton` 不产生事件
+    pub fn translate(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::Keyboard(KeyEvent::Press(code)) => self.push(EV_KEY, code, 1),
+            InputEvent::Keyboard(KeyEvent::Release(code)) => self.push(EV_KEY, code, 0),
             InputEvent::MouseMove { dx, dy } => {
-                // 鼠标移动事件 - 需要返回两个事件 (X 和 Y)
-                // 简化处理：只返回 X 移动，Y 移动在下一次调用返回
-                RawInputEvent {
-                    tv_sec: 0,
-                    tv_usec: 0,
-                    type_: EV_REL,
-                    code: REL_X,
-                    value: dx as i32,
-                }
+                self.push(EV_REL, REL_X, dx as i32);
+                self.push(EV_REL, REL_Y, dy as i32);
+            }
+            InputEvent::MouseAbsolute { x, y } => {
+                self.push(EV_ABS, ABS_X, x as i32);
+                self.push(EV_ABS, ABS_Y, y as i32);
             }
             InputEvent::MouseButton { left, right, middle } => {
-                // 鼠标按键事件
-                if left {
-                    RawInputEvent {
-                        tv_sec: 0,
-                        tv_usec: 0,
-                        type_: EV_KEY,
-                        code: BTN_LEFT,
-                        value: 1,
-                    }
-                } else if right {
-                    RawInputEvent {
-                        tv_sec: 0,
-                        tv_usec: 0,
-                        type_: EV_KEY,
-                        code: BTN_RIGHT,
-                        value: 1,
-                    }
-                } else if middle {
-                    RawInputEvent {
-                        tv_sec: 0,
-                        tv_usec: 0,
-                        type_: EV_KEY,
-                        code: BTN_MIDDLE,
-                        value: 1,
-                    }
-                } else {
-                    // 按键释放 - 假设是左键
-                    RawInputEvent {
-                        tv_sec: 0,
-                        tv_usec: 0,
-                        type_: EV_KEY,
-                        code: BTN_LEFT,
-                        value: 0,
+                let (old_left, old_right, old_middle) = self.buttons;
+                for (code, old, new) in [
+                    (BTN_LEFT, old_left, left),
+                    (BTN_RIGHT, old_right, right),
+                    (BTN_MIDDLE, old_middle, middle),
+                ] {
+                    if old != new {
+                        self.push(EV_KEY, code, new as i32);
                     }
                 }
+                self.buttons = (left, right, middle);
             }
-        };
-        Some(raw_event)
-    } else {
-        None
+            // 字符没有对应的 evdev 事件，读取 /dev/input0 的程序自己处理按键
+            InputEvent::Char(_) => {}
+        }
+    }
+
+    fn push(&mut self, type_: u16, code: u16, value: i32) {
+        self.pending.push_back(RawInputEvent {
+            tv_sec: 0,
+            tv_usec: 0,
+            type_,
+            code,
+            value,
+        });
+    }
+}
+
+/// 全局原始事件翻译状态
+static RAW_TRANSLATOR: spin::Mutex<RawEventTranslator> = spin::Mutex::new(RawEventTranslator::new());
+
+/// 拉取一个原始输入事件（非阻塞）
+pub fn get_raw_input_event() -> Option<RawInputEvent> {
+    let mut translator = RAW_TRANSLATOR.lock();
+    loop {
+        if let Some(raw) = translator.pop() {
+            return Some(raw);
+        }
+        // 没有产生原始事件的输入事件（按键状态未变）直接跳过
+        translator.translate(poll_event()?);
+    }
+}
+
+/// 是否有可读取的原始输入事件
+///
+/// 不丢弃事件：从各个来源取出的事件翻译后留在待返回队列中，
+/// 下一次 `get_raw_input_event` 按顺序返回
+pub fn raw_input_pending() -> bool {
+    let mut translator = RAW_TRANSLATOR.lock();
+    while translator.pending.is_empty() {
+        match poll_event() {
+            Some(event) => translator.translate(event),
+            None => return false,
+        }
     }
+    true
 }
@@ -102,6 +102,7 @@ mod drivers;
 mod input;
 mod config;
 mod list;
+mod ring_buffer;
 mod process;
 mod sched;
 mod fs;
@@ -110,6 +111,7 @@ mod sync;
 mod errno;
 mod net;
 mod cmdline;
+mod fdt;
 mod init;
 
 #[cfg(feature = "unit-test")]
@@ -210,6 +212,9 @@ pub extern "C" fn rust_main() -> ! {
     let slab_start = 0x80A0_0000 + crate::config::KERNEL_HEAP_SIZE;
     mm::init_slab(slab_start, 4 * 1024 * 1024);  // 4MB for slab
 
+    // 登记物理地址空间区域（RAM、MMIO、内核堆）
+    mm::init_regions();
+
     // ========== 堆已初始化，以下可以使用 format! ==========
 
     // 打印启动提示
@@ -266,6 +271,11 @@ pub extern "C" fn rust_main() -> ! {
         let dtb_ptr = arch::riscv64::boot::get_dtb_pointer();
         cmdline::init(dtb_ptr);
         print_status("boot", "FDT/DTB parsed", true);
+        if fdt::init(dtb_ptr) {
+            console::set_uart_base(fdt::uart_base());
+            let info = format!("{} virtio-mmio nodes", fdt::virtio_mmio_devices().len());
+            print_status("boot", &info, true);
+        }
         if let Some(cmdline) = cmdline::get_cmdline() {
             if !cmdline.is_empty() {
                 // 截断过长的 cmdline
@@ -304,7 +314,7 @@ pub extern "C" fn rust_main() -> ! {
         #[cfg(feature = "riscv64")]
         {
             drivers::intc::init();
-            print_status("intc", "PLIC @ 0x0C000000", true);
+            print_status("intc", &format!("PLIC @ {:#010X}", fdt::plic_base()), true);
             print_status("intc", "external IRQ routing", true);
         }
 
@@ -408,8 +418,16 @@ pub extern "C" fn rust_main() -> ! {
                 // 初始化帧缓冲区
                 if let Some(fb_info) = gpu_device.init_framebuffer() {
                     print_status("gpu", &format!("{}x{} 32bpp framebuffer", fb_info.width, fb_info.height), true);
+                    // 登记帧缓冲区，防止与动态分配的区域重叠
+                    let _ = mm::reserve_region(
+                        fb_info.addr as usize,
+                        fb_info.size as usize,
+                        mm::RegionKind::Framebuffer,
+                        "Framebuffer",
+                    );
                     // 保存 framebuffer 信息供用户态 mmap 使用
                     drivers::gpu::set_framebuffer_info(*fb_info);
+                    let _ = fs::devfs::register_char_device("fb0", &drivers::gpu::FBDEV_FILE_OPS);
                 } else {
                     print_status("gpu", "framebuffer init failed", false);
                 }
@@ -514,6 +532,9 @@ pub extern "C" fn rust_main() -> ! {
 // Panic handler
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
+    #[cfg(feature = "riscv64")]
+    arch::ipi::smp_send_stop();
+
     unsafe {
         use crate::console::putchar;
         const MSG: &[u8] = b"\nPANIC! ";
@@ -0,0 +1,191 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+//! DMA 一致性内存 (DMA coherent memory)
+//!
+//! 完全...
+//! - `kernel/dma/mapping.c` - dma_alloc_coherent() / dma_free_coherent()
+//! - `kernel/dma/direct.c` - dma_direct_alloc()
+//!
+//! 核心概念：
+//! - 一致性缓冲区按页对齐、物理连续，分配时即知道设备可用的物理地址
+//! - 驱动不再为每个请求手动做虚拟→物理地址转换
+//! - 流式 DMA 的缓存维护：`flush_for_device()` / `invalidate_after_device()`
+//!   （对应 `arch_sync_dma_for_device()` / `arch_sync_dma_for_cpu()`）
+//!
+//! 简化实现：
+//! - 内核堆采用恒等映射，页对齐的堆分配即物理连续
+//! - 缓冲区在物理地址登记表中登记为 `RegionKind::Dma`，释放时注销
+//! - RISC-V (QEMU virt) 的 DMA 与 CPU 缓存一致，缓存维护退化为内存屏障
+//! - aarch64 按缓存行执行 `dc cvac`（清理）/ `dc ivac`（无效化），最后 `dsb sy`
+
+use crate::mm::page::PAGE_SIZE;
+use crate::mm::{release_region, reserve_region, RegionKind};
+use alloc::alloc::Layout;
+
+/// DMA 传输方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmaDirection {
+    /// CPU 写入、设备读取（对应 `DMA_TO_DEVICE`）
+    ToDevice,
+    /// 设备写入、CPU 读取（对应 `DMA_FROM_DEVICE`）
+    FromDevice,
+}
+
+/// 架构相关的缓存维护操作
+///
+/// 按缓存行执行维护指令，最后执行一次屏障；
+/// 测试可以提供记录操作的实现来检查发出的指令序列
+pub trait CacheMaintenance {
+    /// 数据缓存行大小（字节）
+    fn line_size(&self) -> usize;
+    /// 清理一个缓存行（写回内存，设备可见）
+    fn clean_line(&self, addr: usize);
+    /// 无效化一个缓存行（丢弃缓存内容，CPU 重新从内存读取）
+    fn invalidate_line(&self, addr: usize);
+    /// 等待之前的维护操作完成
+    fn barrier(&self);
+}
+
+/// 对 [addr, addr + len) 覆盖的每个缓存行执行维护操作
+pub fn sync_range<C: CacheMaintenance>(cache: &C, addr: usize, len: usize, dir: DmaDirection) {
+    if len == 0 {
+        return;
+    }
+
+    let line = cache.line_size();
+    if line != 0 {
+        let mut cur = addr & !(line - 1);
+        let end = addr + len;
+        while cur < end {
+            match dir {
+                DmaDirection::ToDevice => cache.clean_line(cur),
+                DmaDirection::FromDevice => cache.invalidate_line(cur),
+            }
+            cur += line;
+        }
+    }
+    cache.barrier();
+}
+
+/// 当前架构的缓存维护实现
+pub struct ArchCache;
+
+#[cfg(feature = "aarch64")]
+impl CacheMaintenance for ArchCache {
+    fn line_size(&self) -> usize {
+        // CTR_EL0.DminLine: log2(数据缓存最小行的字数)
+        let ctr: u64;
+        unsafe { core::arch::asm!("mrs {}, ctr_el0", out(reg) ctr, options(nomem, nostack)) };
+        4 << ((ctr >> 16) & 0xF)
+    }
+
+    fn clean_line(&self, addr: usize) {
+        unsafe { core::arch::asm!("dc cvac, {}", in(reg) addr, options(nostack)) };
+    }
+
+    fn invalidate_line(&self, addr: usize) {
+        unsafe { core::arch::asm!("dc ivac, {}", in(reg) addr, options(nostack)) };
+    }
+
+    fn barrier(&self) {
+        unsafe { core::arch::asm!("dsb sy", options(nostack)) };
+    }
+}
+
+#[cfg(not(feature = "aarch64"))]
+impl CacheMaintenance for ArchCache {
+    /// RISC-V (QEMU virt) 的 DMA 与缓存一致，不需要逐行维护
+    fn line_size(&self) -> usize {
+        0
+    }
+
+    fn clean_line(&self, _addr: usize) {}
+
+    fn invalidate_line(&self, _addr: usize) {}
+
+    fn barrier(&self) {
+        core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+/// 设备读取缓冲区之前调用：清理缓存，使 CPU 的写入对设备可见
+#[inline]
+pub fn flush_for_device(ptr: *const u8, len: usize) {
+    sync_range(&ArchCache, ptr as usize, len, DmaDirection::ToDevice);
+}
+
+/// 设备写入缓冲区之后调用：无效化缓存，使 CPU 读到设备写入的数据
+#[inline]
+pub fn invalidate_after_device(ptr: *const u8, len: usize) {
+    sync_range(&ArchCache, ptr as usize, len, DmaDirection::FromDevice);
+}
+
+/// 计算一致性缓冲区的分配布局：大小向上取整到页，对齐至少为一页
+fn coherent_layout(size: usize, align: usize) -> Option<Layout> {
+    if size == 0 || !align.is_power_of_two() {
+        return None;
+    }
+    let size = size.checked_add(PAGE_SIZE - 1)? & !(PAGE_SIZE - 1);
+    Layout::from_size_align(size, align.max(PAGE_SIZE)).ok()
+}
+
+/// 将内核虚拟地址转换为设备可见的物理地址
+#[inline]
+pub fn virt_to_phys(virt: usize) -> u64 {
+    #[cfg(feature = "riscv64")]
+    {
+        crate::arch::riscv64::mm::virt_to_phys(crate::arch::riscv64::mm::VirtAddr::new(virt as u64)).0
+    }
+    #[cfg(not(feature = "riscv64"))]
+    {
+        virt as u64
+    }
+}
+
+/// 分配 DMA 一致性缓冲区（对应 `dma_alloc_coherent()`）
+///
+/// # 参数
+/// * `size` - 缓冲区大小（向上取整到页）
+/// * `align` - 对齐要求（2 的幂，至少按页对齐）
+///
+/// # 返回
+/// `(虚拟地址, 物理地址)`，缓冲区已清零；失败返回 None
+pub fn alloc_coherent(size: usize, align: usize) -> Option<(*mut u8, u64)> {
+    let layout = coherent_layout(size, align)?;
+    let virt = unsafe { alloc::alloc::alloc_zeroed(layout) };
+    if virt.is_null() {
+        return None;
+    }
+
+    let phys = virt_to_phys(virt as usize);
+
+    // 设备按物理地址访问整个缓冲区，必须物理连续
+    let contiguous = (0..layout.size())
+        .step_by(PAGE_SIZE)
+        .all(|off| virt_to_phys(virt as usize + off) == phys + off as u64);
+    if !contiguous || reserve_region(phys as usize, layout.size(), RegionKind::Dma, "DMA coherent").is_err() {
+        unsafe { alloc::alloc::dealloc(virt, layout) };
+        return None;
+    }
+
+    // 确保清零写入在设备访问之前完成
+    core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+
+    Some((virt, phys))
+}
+
+/// 释放 DMA 一致性缓冲区（对应 `dma_free_coherent()`）
+///
+/// # Safety
+/// `virt` 必须由 `alloc_coherent(size, align)` 以相同的参数返回，且设备不再访问
+pub unsafe fn free_coherent(virt: *mut u8, size: usize, align: usize) {
+    if virt.is_null() {
+        return;
+    }
+    if let Some(layout) = coherent_layout(size, align) {
+        release_region(virt_to_phys(virt as usize) as usize, RegionKind::Dma);
+        alloc::alloc::dealloc(virt, layout);
+    }
+}
@@ -0,0 +1,86 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+//! 文件映射 (File-backed mmap) - 平台无关部分
+//!
+//! 完全...
+//! - `mm/filemap.c` - filemap_fault() / 页缓存读取
+//! - `mm/msync.c` - msync() 写回
+//!
+//! 核心概念：
+//! - 文件映射的 VMA 记录文件偏移（`Vma::offset`），`FileMapping` 记录背后的 inode
+//! - 缺页时从 inode 的页缓存读取对应页面内容（超出文件末尾的部分清零）
+//! - MAP_SHARED：msync/munmap 时把驻留页写回页缓存，随后 read() 可见
+//! - MAP_PRIVATE：每个缺页得到私有副本，写入只在本地可见，永不写回
+//!
+//! 简化实现：
+//! - 页缓存使用 inode 的数据缓冲区（`Inode::read_data/write_data`）
+//! - 共享映射的页面是页缓存的副本而非同一物理页，因此写入在 msync 之前对其他映射不可见
+//! - 没有脏页跟踪，写回时所有驻留页都视为脏页
+
+use crate::fs::inode::Inode;
+use crate::mm::page::PAGE_SIZE;
+use alloc::sync::Arc;
+
+/// 文件映射描述
+///
+/// 对应 Linux `vm_area_struct` 中的 `vm_file` 部分，
+/// 文件偏移由 VMA 自身记录（`Vma::offset`，对应 `vm_pgoff`）
+#[derive(Clone)]
+pub struct FileMapping {
+    /// 背后的 inode（页缓存所有者）
+    inode: Arc<Inode>,
+    /// 是否为共享映射 (MAP_SHARED)
+    shared: bool,
+}
+
+impl FileMapping {
+    /// 创建新的文件映射描述
+    pub fn new(inode: Arc<Inode>, shared: bool) -> Self {
+        Self { inode, shared }
+    }
+
+    /// 获取背后的 inode
+    #[inline]
+    pub fn inode(&self) -> &Arc<Inode> {
+        &self.inode
+    }
+
+    /// 是否为共享映射
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// 从页缓存填充一个页面（对应 `filemap_fault()`）
+    ///
+    /// 超出文件末尾的部分清零，返回从文件读取的字节数
+    pub fn fill_page(&self, file_offset: usize, page: &mut [u8]) -> usize {
+        let len = page.len().min(PAGE_SIZE);
+        let read = self.inode.read_data(file_offset, &mut page[..len]);
+        page[read..].fill(0);
+        read
+    }
+
+    /// 将一个页面写回页缓存（对应 msync 的写回路径）
+    ///
+    /// 只写回文件大小以内的部分，映射不会扩展文件。
+    /// 私有映射从不写回，返回 0。
+    ///
+    /// # 返回
+    /// 写回的字节数
+    pub fn write_back_page(&self, file_offset: usize, page: &[u8]) -> usize {
+        if !self.shared {
+            return 0;
+        }
+
+        let file_size = self.inode.get_size() as usize;
+        if file_offset >= file_size {
+            return 0;
+        }
+
+        let len = page.len().min(PAGE_SIZE).min(file_size - file_offset);
+        self.inode.write_data(file_offset, &page[..len])
+    }
+}
@@ -10,10 +10,13 @@ pub mod allocator;
 pub mod page;
 pub mod page_desc;
 pub mod vma;
+pub mod filemap;
 pub mod pagemap;
 pub mod slab;
 pub mod pcp;
 pub mod meminfo;
+pub mod region;
+pub mod dma;
 
 pub use page::*;
 pub use page_desc::{Page, PageFlag, PageFlags, PageType};
@@ -39,6 +42,10 @@ pub use meminfo::{
     get_memory_info, print_memory_info, get_memory_summary,
     is_memory_low, should_trigger_oom, MemoryInfo, MemorySummary,
 };
+pub use region::{
+    reserve_region, release_region, alloc_region, lookup_region,
+    init_regions, RegionKind,
+};
 pub use buddy_allocator::buddy_stats;
 pub use page::frame_stats;
 pub use page_desc::page_desc_stats;
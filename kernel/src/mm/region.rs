@@ -0,0 +1,256 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+//! 物理地址空间区域登记表 (memory region reservation map)
+//!
+//! 完全...
+//! - `kernel/resource.c` - iomem_resource / request_resource() / allocate_resource()
+//! - `mm/memblock.c` - memblock_reserve()
+//!
+//! 核心概念：
+//! - 启动时登记 RAM、MMIO、帧缓冲区、内核堆等区域，形成统一的物理地址映射
+//! - 区域可以嵌套在允许它的"容器"区域中（如堆位于 RAM 中，BAR 位于 MMIO 窗口中），
+//!   除此之外任何重叠都会被拒绝 (EBUSY)
+//! - `alloc_region()` 在容器中寻找不与已登记区域冲突的空闲范围，
+//!   用于动态分配 MMIO BAR、DMA 缓冲区等
+
+use crate::errno;
+use alloc::vec::Vec;
+use spin::Mutex;
+
+/// 区域类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionKind {
+    /// 物理内存（容器）
+    Ram,
+    /// 内核镜像
+    Kernel,
+    /// 内核堆 / slab
+    Heap,
+    /// 帧缓冲区
+    Framebuffer,
+    /// DMA 缓冲区
+    Dma,
+    /// 设备寄存器
+    Mmio,
+    /// 可分配给设备的 MMIO 窗口（容器，如 PCIe BAR 空间）
+    MmioWindow,
+    /// 其他保留区域
+    Reserved,
+}
+
+impl RegionKind {
+    /// 是否允许 `child` 类型的区域嵌套在本类型区域中
+    pub fn can_contain(self, child: RegionKind) -> bool {
+        match self {
+            RegionKind::Ram => !matches!(
+                child,
+                RegionKind::Ram | RegionKind::Mmio | RegionKind::MmioWindow
+            ),
+            // 帧缓冲区和 DMA 缓冲区可能由堆分配
+            RegionKind::Heap => matches!(child, RegionKind::Framebuffer | RegionKind::Dma),
+            RegionKind::MmioWindow => matches!(child, RegionKind::Mmio | RegionKind::Framebuffer),
+            _ => false,
+        }
+    }
+
+    /// `alloc_region()` 为本类型区域搜索空闲范围时使用的容器类型
+    pub fn pool(self) -> RegionKind {
+        match self {
+            RegionKind::Mmio => RegionKind::MmioWindow,
+            _ => RegionKind::Ram,
+        }
+    }
+}
+
+/// 已登记的区域
+#[derive(Debug, Clone, Copy)]
+pub struct MemRegion {
+    /// 起始物理地址
+    pub base: usize,
+    /// 大小（字节）
+    pub size: usize,
+    /// 区域类型
+    pub kind: RegionKind,
+    /// 区域名称（用于调试输出）
+    pub name: &'static str,
+}
+
+impl MemRegion {
+    /// 结束地址（不含）
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.base + self.size
+    }
+
+    /// 是否与 [base, base + size) 重叠
+    #[inline]
+    pub fn overlaps(&self, base: usize, size: usize) -> bool {
+        base < self.end() && self.base < base + size
+    }
+
+    /// 是否完全包含 [base, base + size)
+    #[inline]
+    pub fn contains(&self, base: usize, size: usize) -> bool {
+        self.base <= base && base + size <= self.end()
+    }
+}
+
+/// 区域登记表
+pub struct RegionMap {
+    regions: Vec<MemRegion>,
+}
+
+impl RegionMap {
+    /// 创建空的登记表
+    pub const fn new() -> Self {
+        Self { regions: Vec::new() }
+    }
+
+    /// 登记一个区域（对应 `request_resource()`）
+    ///
+    /// # 返回
+    /// - `Ok(())` - 登记成功
+    /// - `Err(EINVAL)` - 大小为 0 或地址溢出
+    /// - `Err(EBUSY)` - 与已登记区域冲突
+    pub fn reserve(&mut self, base: usize, size: usize, kind: RegionKind, name: &'static str) -> Result<(), i32> {
+        if size == 0 || base.checked_add(size).is_none() {
+            return Err(errno::Errno::InvalidArgument.as_neg_i32());
+        }
+
+        if self.conflicts(base, size, kind) {
+            return Err(errno::Errno::DeviceOrResourceBusy.as_neg_i32());
+        }
+
+        self.regions.push(MemRegion { base, size, kind, name });
+        self.regions.sort_by_key(|r| r.base);
+        Ok(())
+    }
+
+    /// 释放 `base` 处登记的区域
+    pub fn release(&mut self, base: usize, kind: RegionKind) -> bool {
+        let before = self.regions.len();
+        self.regions.retain(|r| !(r.base == base && r.kind == kind));
+        self.regions.len() != before
+    }
+
+    /// 寻找可容纳 `size` 字节、按 `align` 对齐的空闲范围（对应 `allocate_resource()`）
+    ///
+    /// 只在 `kind.pool()` 类型的容器中搜索，并跳过所有已登记的区域
+    pub fn find_free(&self, size: usize, align: usize, kind: RegionKind) -> Option<usize> {
+        if size == 0 || !align.is_power_of_two() {
+            return None;
+        }
+
+        let pool = kind.pool();
+        for container in self.regions.iter().filter(|r| r.kind == pool) {
+            let mut addr = align_up(container.base, align)?;
+            while addr.checked_add(size)? <= container.end() {
+                match self.first_conflict(addr, size, kind) {
+                    None => return Some(addr),
+                    Some(blocker) => addr = align_up(blocker.end(), align)?,
+                }
+            }
+        }
+        None
+    }
+
+    /// 按地址顺序遍历已登记的区域
+    pub fn iter(&self) -> impl Iterator<Item = &MemRegion> {
+        self.regions.iter()
+    }
+
+    /// 查找包含 `addr` 的最内层区域
+    pub fn lookup(&self, addr: usize) -> Option<&MemRegion> {
+        self.regions
+            .iter()
+            .filter(|r| r.contains(addr, 1))
+            .min_by_key(|r| r.size)
+    }
+
+    /// 新区域是否与已登记区域冲突
+    fn conflicts(&self, base: usize, size: usize, kind: RegionKind) -> bool {
+        self.regions.iter().any(|r| {
+            if !r.overlaps(base, size) {
+                return false;
+            }
+            // 嵌套在容器中，或新区域是已登记区域的容器
+            let nested = r.contains(base, size) && r.kind.can_contain(kind);
+            let wraps = r.base >= base && r.end() <= base + size && kind.can_contain(r.kind);
+            !(nested || wraps)
+        })
+    }
+
+    /// 与候选范围冲突的第一个区域（不包括容纳它的容器）
+    fn first_conflict(&self, base: usize, size: usize, kind: RegionKind) -> Option<&MemRegion> {
+        self.regions.iter().find(|r| {
+            r.overlaps(base, size) && !(r.kind == kind.pool() && r.contains(base, size))
+        })
+    }
+}
+
+/// 向上对齐，溢出时返回 None
+#[inline]
+fn align_up(addr: usize, align: usize) -> Option<usize> {
+    Some(addr.checked_add(align - 1)? & !(align - 1))
+}
+
+/// 全局区域登记表
+static MEM_REGIONS: Mutex<RegionMap> = Mutex::new(RegionMap::new());
+
+/// 登记一个区域
+pub fn reserve_region(base: usize, size: usize, kind: RegionKind, name: &'static str) -> Result<(), i32> {
+    MEM_REGIONS.lock().reserve(base, size, kind, name)
+}
+
+/// 释放一个已登记的区域
+pub fn release_region(base: usize, kind: RegionKind) -> bool {
+    MEM_REGIONS.lock().release(base, kind)
+}
+
+/// 寻找空闲范围并立即登记（查找和登记在同一把锁内完成）
+pub fn alloc_region(size: usize, align: usize, kind: RegionKind, name: &'static str) -> Option<usize> {
+    let mut map = MEM_REGIONS.lock();
+    let base = map.find_free(size, align, kind)?;
+    map.reserve(base, size, kind, name).ok()?;
+    Some(base)
+}
+
+/// 查找包含 `addr` 的最内层区域
+pub fn lookup_region(addr: usize) -> Option<MemRegion> {
+    MEM_REGIONS.lock().lookup(addr).copied()
+}
+
+/// 启动时登记 QEMU virt 平台的固定区域
+///
+/// 必须在堆初始化之后调用（登记表使用 Vec）
+pub fn init_regions() {
+    use crate::config::{KERNEL_HEAP_SIZE, PHYS_MEMORY_SIZE};
+
+    const RAM_BASE: usize = 0x8000_0000;
+    const HEAP_BASE: usize = 0x80A0_0000;
+    const SLAB_SIZE: usize = 4 * 1024 * 1024;
+
+    let fixed: [(usize, usize, RegionKind, &'static str); 12] = [
+        (RAM_BASE, PHYS_MEMORY_SIZE, RegionKind::Ram, "System RAM"),
+        (RAM_BASE, HEAP_BASE - RAM_BASE, RegionKind::Kernel, "Kernel image"),
+        (HEAP_BASE, KERNEL_HEAP_SIZE, RegionKind::Heap, "Kernel heap"),
+        (HEAP_BASE + KERNEL_HEAP_SIZE, SLAB_SIZE, RegionKind::Heap, "Slab"),
+        (0x8400_0000, 0x0400_0000, RegionKind::Reserved, "User frames"),
+        (0x0010_0000, 0x1000, RegionKind::Mmio, "SiFive test"),
+        (0x0200_0000, 0x1_0000, RegionKind::Mmio, "CLINT"),
+        (0x0c00_0000, 0x0400_0000, RegionKind::Mmio, "PLIC"),
+        (0x1000_0000, 0x100, RegionKind::Mmio, "UART0"),
+        (0x1000_1000, 0x8000, RegionKind::Mmio, "virtio-mmio"),
+        (0x3000_0000, 0x1000_0000, RegionKind::Mmio, "PCIe ECAM"),
+        (0x4000_0000, 0x4000_0000, RegionKind::MmioWindow, "PCIe MMIO"),
+    ];
+
+    let mut map = MEM_REGIONS.lock();
+    for &(base, size, kind, name) in fixed.iter() {
+        if let Err(e) = map.reserve(base, size, kind, name) {
+            crate::println!("mm: failed to reserve {} @ {:#x}: {}", name, base, e);
+        }
+    }
+}
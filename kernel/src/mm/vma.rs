@@ -226,6 +226,11 @@ impl Vma {
         self.vma_type = vma_type;
     }
 
+    /// 设置标志（mprotect）
+    pub fn set_flags(&mut self, flags: VmaFlags) {
+        self.flags = flags;
+    }
+
     /// 检查地址是否在 VMA 范围内
     #[inline]
     pub fn contains(&self, addr: VirtAddr) -> bool {
@@ -437,11 +442,62 @@ impl VmaManager {
         self.vmas.values().next_back()
     }
 
+    /// 将起始地址为 `start` 的 VMA 向下扩展到 `new_start`（栈增长）
+    ///
+    /// # 返回
+    /// - `Err(VmaError::Invalid)`: `new_start` 未按页对齐或不低于 `start`
+    /// - `Err(VmaError::Overlap)`: 扩展部分与下方的 VMA 重叠
+    /// - `Err(VmaError::NotFound)`: 没有起始于 `start` 的 VMA
+    pub fn expand_down(&mut self, start: VirtAddr, new_start: VirtAddr) -> Result<(), VmaError> {
+        if new_start.as_usize() % PAGE_SIZE != 0 || new_start >= start {
+            return Err(VmaError::Invalid);
+        }
+        if let Some((_, prev)) = self.vmas.range(..start).next_back() {
+            if prev.end().as_usize() > new_start.as_usize() {
+                return Err(VmaError::Overlap);
+            }
+        }
+        let mut vma = self.vmas.remove(&start).ok_or(VmaError::NotFound)?;
+        vma.start = new_start;
+        self.vmas.insert(new_start, vma);
+        Ok(())
+    }
+
     /// 查找起始地址 >= addr 的第一个 VMA
     pub fn find_vma_after(&self, addr: VirtAddr) -> Option<&Vma> {
         self.vmas.range(addr..).next().map(|(_, vma)| vma)
     }
 
+    /// [start, end) 是否被 VMA 完整覆盖（中间没有空洞）
+    pub fn covers(&self, start: VirtAddr, end: VirtAddr) -> bool {
+        let mut addr = start.as_usize();
+        while addr < end.as_usize() {
+            match self.find(VirtAddr::new(addr)) {
+                Some(vma) => addr = vma.end().as_usize(),
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// 在 `addr` 处分裂包含它的 VMA (mm/mmap.c: split_vma)
+    ///
+    /// # 返回
+    /// 发生分裂时返回 (前半部分, 后半部分)；
+    /// `addr` 不在任何 VMA 中或恰好是 VMA 的起始地址时返回 None
+    pub fn split_at(&mut self, addr: VirtAddr) -> Option<(Vma, Vma)> {
+        let (first, second) = self.find(addr)?.split(addr)?;
+        self.vmas.insert(first.start(), first);
+        self.vmas.insert(second.start(), second);
+        self.count.fetch_add(1, Ordering::Release);
+        Some((first, second))
+    }
+
+    /// 起始地址位于 [start, end) 内的 VMA（可变引用）
+    pub fn range_mut(&mut self, start: VirtAddr, end: VirtAddr) -> impl Iterator<Item = &mut Vma> {
+        self.vmas.range_mut(start..end).map(|(_, vma)| vma)
+    }
+
     /// 清空所有 VMA
     pub fn clear(&mut self) {
         self.vmas.clear();
@@ -498,6 +554,21 @@ mod tests {
         assert!(!vma.contains(VirtAddr::new(0x3000)));
         assert!(!vma.contains(VirtAddr::new(0xfff)));
     }
+
+    #[test]
+    fn test_vma_split_at() {
+        let mut mgr = VmaManager::new();
+        let flags = VmaFlags::from_bits(VmaFlags::READ | VmaFlags::WRITE);
+        mgr.add(Vma::new(VirtAddr::new(0x1000), VirtAddr::new(0x4000), flags)).unwrap();
+
+        assert!(mgr.split_at(VirtAddr::new(0x1000)).is_none());
+        let (first, second) = mgr.split_at(VirtAddr::new(0x2000)).unwrap();
+        assert_eq!(first.end(), VirtAddr::new(0x2000));
+        assert_eq!(second.start(), VirtAddr::new(0x2000));
+        assert_eq!(mgr.count(), 2);
+        assert!(mgr.covers(VirtAddr::new(0x1000), VirtAddr::new(0x4000)));
+        assert!(!mgr.covers(VirtAddr::new(0x1000), VirtAddr::new(0x5000)));
+    }
 }
 
 // ============================================================================
@@ -8,7 +8,9 @@
 //! 本模块实现 fork 系统调用的核心逻辑，参考 Linux kernel/fork.c
 //!
 //! 主要函数:
-//! - `do_fork`: 创建子进程的核心实现
+//! - `do_clone`: 创建子进程的核心实现，按 clone 标志决定共享还是复制父进程的资源
+//! - `do_fork`: 不带标志的 `do_clone`
+//! - `copy_files`: 共享或复制文件描述符表
 //!
 //! 流程 (参考 Linux):
 //! 1. 分配新的 task_struct
@@ -21,15 +23,63 @@
 use crate::process::task::{Task, SchedPolicy, Pid};
 use crate::fs::FdTable;
 use crate::sched::pid::alloc_pid;
+use alloc::sync::Arc;
 
-/// 创建子进程
+/// 子进程退出时发给父进程的信号，clone 标志的低 8 位 (include/uapi/linux/sched.h)
+pub const CSIGNAL: u64 = 0x0000_00ff;
+/// 子进程与父进程共享文件描述符表 (include/uapi/linux/sched.h)
+pub const CLONE_FILES: u64 = 0x0000_0400;
+
+/// 检查 clone 的标志和新栈地址是否受支持
+///
+/// 地址空间总是按 COW 复制，子进程从父进程的 sp 继续运行，所以只支持 `CLONE_FILES`；
+/// 其他标志（CLONE_VM、CLONE_THREAD、CLONE_SETTLS、CLONE_PARENT_SETTID 等）和非零的
+/// `newsp` 返回 EINVAL，而不是静默忽略。退出时总是发送 SIGCHLD，退出信号只能是 0 或 SIGCHLD
+pub fn check_clone_args(clone_flags: u64, newsp: u64) -> Result<(), i32> {
+    let exit_signal = clone_flags & CSIGNAL;
+    if clone_flags & !(CSIGNAL | CLONE_FILES) != 0
+        || newsp != 0
+        || (exit_signal != 0 && exit_signal != crate::signal::Signal::SIGCHLD as u64)
+    {
+        return Err(crate::errno::Errno::InvalidArgument.as_neg_i32());
+    }
+    Ok(())
+}
+
+/// 为子进程准备文件描述符表
+///
+/// 参考 Linux: kernel/fork.c -> copy_files()
+/// - `CLONE_FILES`: 共享父进程的表（只增加引用计数），一方打开或关闭的 fd 对另一方可见
+/// - 否则复制一份独立的表，表项与父进程共享同一个打开的文件
+///
+/// 父进程没有 fd 表时创建带标准输入输出的新表
+pub fn copy_files(parent: Option<&Arc<FdTable>>, clone_flags: u64) -> Arc<FdTable> {
+    match parent {
+        Some(parent) if clone_flags & CLONE_FILES != 0 => Arc::clone(parent),
+        Some(parent) => Arc::new(parent.dup_table()),
+        None => {
+            let fdtable = Arc::new(FdTable::new());
+            crate::init::init_std_fds_for_task(&fdtable);
+            fdtable
+        }
+    }
+}
+
+/// 创建子进程（fork）
+pub fn do_fork() -> Option<Pid> {
+    do_clone(0)
+}
+
+/// 按 `clone_flags` 创建子进程
 ///
 /// 参考 Linux: kernel/fork.c -> kernel_clone() -> copy_process()
 ///
+/// 调用者先用 `check_clone_args` 拒绝不支持的标志
+///
 /// # 返回
 /// - Some(pid): 子进程的 PID（在父进程中返回）
 /// - None: 创建失败
-pub fn do_fork() -> Option<Pid> {
+pub fn do_clone(clone_flags: u64) -> Option<Pid> {
     use crate::arch::riscv64::trap::{current_trap_frame, TrapFrame};
 
     unsafe {
@@ -152,15 +202,14 @@ pub fn do_fork() -> Option<Pid> {
         // 复制信号掩码
         (*task_ptr).sigmask = (*current_ptr).sigmask;
 
-        // === copy_files: 复制文件描述符表 ===
-        {
-            let child_fdtable: alloc::boxed::Box<FdTable> = alloc::boxed::Box::new(FdTable::new());
-            (*task_ptr).set_fdtable(Some(child_fdtable));
+        // 继承资源限制（CPU 时间从 0 重新累计）
+        (*task_ptr).set_rlimits((*current_ptr).rlimits());
 
-            if let Some(fdtable) = (*task_ptr).try_fdtable_mut() {
-                crate::init::init_std_fds_for_task(fdtable);
-            }
-        }
+        // === copy_files: 共享或复制文件描述符表 ===
+        // 子进程继承父进程的所有文件描述符（包括 shell 通过 dup2 设置的重定向），
+        // CLONE_FILES 时与父进程共享同一个表
+        let child_fdtable = copy_files((*current_ptr).shared_fdtable(), clone_flags);
+        (*task_ptr).set_fdtable(Some(child_fdtable));
 
         // === copy_mm: 复制地址空间 (COW) ===
         let parent_addr_space = (*current_ptr).address_space();
@@ -11,15 +11,17 @@
 //! - `wait`: 等待队列 (kernel/wait.c)
 //! - `test`: 进程测试
 //! - `usermod`: 用户模式管理
+//! - `rlimit`: 资源限制 (kernel/sys.c)
 
 pub mod task;
 pub mod fork;
 pub mod test;
 pub mod usermod;
+pub mod rlimit;
 pub mod wait;
 
 pub use task::Task;
-pub use fork::do_fork;
+pub use fork::{do_clone, do_fork};
 
 pub fn current_pid() -> u32 {
     crate::sched::get_current_pid()
@@ -0,0 +1,144 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! 进程资源限制 (include/uapi/asm-generic/resource.h, kernel/sys.c)
+//!
+//! 每个任务保存 `RLIM_NLIMITS` 个软/硬限制，fork 时由子进程继承。
+//! 目前内核实际检查的限制：
+//! - `RLIMIT_NOFILE`: 分配文件描述符时不超过软限制
+//! - `RLIMIT_STACK`: 用户栈向下扩展后的大小不超过软限制
+//! - `RLIMIT_CPU`: CPU 时间达到软限制后每秒发送 SIGXCPU，达到硬限制时发送 SIGKILL
+
+use crate::errno::Errno;
+
+pub const RLIMIT_CPU: usize = 0;
+pub const RLIMIT_FSIZE: usize = 1;
+pub const RLIMIT_DATA: usize = 2;
+pub const RLIMIT_STACK: usize = 3;
+pub const RLIMIT_CORE: usize = 4;
+pub const RLIMIT_RSS: usize = 5;
+pub const RLIMIT_NPROC: usize = 6;
+pub const RLIMIT_NOFILE: usize = 7;
+pub const RLIMIT_MEMLOCK: usize = 8;
+pub const RLIMIT_AS: usize = 9;
+pub const RLIMIT_LOCKS: usize = 10;
+pub const RLIMIT_SIGPENDING: usize = 11;
+pub const RLIMIT_MSGQUEUE: usize = 12;
+pub const RLIMIT_NICE: usize = 13;
+pub const RLIMIT_RTPRIO: usize = 14;
+pub const RLIMIT_RTTIME: usize = 15;
+
+/// 资源种类数量
+pub const RLIM_NLIMITS: usize = 16;
+
+/// 无限制
+pub const RLIM_INFINITY: u64 = u64::MAX;
+
+/// 文件描述符数量的系统上限（`FdTable` 的大小），RLIMIT_NOFILE 不能超过它
+pub const NR_OPEN: u64 = 1024;
+
+/// struct rlimit64
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RLimit {
+    /// 软限制
+    pub rlim_cur: u64,
+    /// 硬限制（软限制的上限）
+    pub rlim_max: u64,
+}
+
+impl RLimit {
+    /// 软硬限制都为无限制
+    pub const INFINITY: Self = Self::new(RLIM_INFINITY, RLIM_INFINITY);
+
+    pub const fn new(rlim_cur: u64, rlim_max: u64) -> Self {
+        Self { rlim_cur, rlim_max }
+    }
+}
+
+/// init 进程的默认资源限制
+pub const fn default_rlimits() -> [RLimit; RLIM_NLIMITS] {
+    let mut limits = [RLimit::INFINITY; RLIM_NLIMITS];
+    limits[RLIMIT_STACK] = RLimit::new(crate::config::USER_STACK_SIZE as u64, RLIM_INFINITY);
+    limits[RLIMIT_NOFILE] = RLimit::new(NR_OPEN, NR_OPEN);
+    limits
+}
+
+/// 检查 `new` 能否替换资源 `resource` 的当前限制
+///
+/// # 返回
+/// - `Err(-EINVAL)`: 资源编号无效或软限制大于硬限制
+/// - `Err(-EPERM)`: RLIMIT_NOFILE 的硬限制超过 `NR_OPEN`
+pub fn check_rlimit(resource: usize, new: &RLimit) -> Result<(), i32> {
+    if resource >= RLIM_NLIMITS || new.rlim_cur > new.rlim_max {
+        return Err(Errno::InvalidArgument.as_neg_i32());
+    }
+    if resource == RLIMIT_NOFILE && new.rlim_max > NR_OPEN {
+        return Err(Errno::OperationNotPermitted.as_neg_i32());
+    }
+    Ok(())
+}
+
+/// 读取并（可选）替换任务的资源限制（prlimit64 的核心逻辑）
+///
+/// # 返回
+/// 替换前的限制；资源编号或新限制无效时返回负错误码，限制保持不变
+pub fn do_prlimit(task: &crate::process::Task, resource: usize, new: Option<RLimit>) -> Result<RLimit, i32> {
+    if resource >= RLIM_NLIMITS {
+        return Err(Errno::InvalidArgument.as_neg_i32());
+    }
+    let old = task.rlimit(resource);
+    if let Some(new) = new {
+        check_rlimit(resource, &new)?;
+        task.set_rlimit(resource, new);
+    }
+    Ok(old)
+}
+
+/// 累计 CPU 时间刚到达 `ticks` 个时钟中断时需要发送的信号
+///
+/// 只在整秒边界检查：达到硬限制发送 SIGKILL，
+/// 达到软限制之后每秒发送一次 SIGXCPU (kernel/time/posix-cpu-timers.c)
+pub fn cpu_limit_signal(ticks: u64, hz: u64, limit: RLimit) -> Option<i32> {
+    use crate::signal::Signal;
+
+    if hz == 0 || ticks == 0 || ticks % hz != 0 {
+        return None;
+    }
+    let secs = ticks / hz;
+    if limit.rlim_max != RLIM_INFINITY && secs >= limit.rlim_max {
+        Some(Signal::SIGKILL as i32)
+    } else if limit.rlim_cur != RLIM_INFINITY && secs >= limit.rlim_cur {
+        Some(Signal::SIGXCPU as i32)
+    } else {
+        None
+    }
+}
+
+/// 为 `task` 记录一个时钟中断的 CPU 时间，超出 RLIMIT_CPU 时把信号加入其待处理信号
+///
+/// 由时钟中断在持有运行队列锁时调用，所以不经过 `send_signal`
+///
+/// # 返回
+/// 发送的信号
+pub fn account_cpu_time(task: &crate::process::Task, hz: u64) -> Option<i32> {
+    let ticks = task.account_cpu_tick();
+    let sig = cpu_limit_signal(ticks, hz, task.rlimit(RLIMIT_CPU))?;
+    task.pending.add(sig);
+    Some(sig)
+}
+
+/// 当前进程的资源限制，没有当前进程时（早期启动）返回默认值
+pub fn current_rlimit(resource: usize) -> RLimit {
+    match crate::sched::current() {
+        Some(task) => task.rlimit(resource),
+        None => default_rlimits()[resource],
+    }
+}
+
+/// 当前进程可以使用的文件描述符数量（RLIMIT_NOFILE 的软限制，不超过 `NR_OPEN`）
+pub fn nofile_limit() -> usize {
+    current_rlimit(RLIMIT_NOFILE).rlim_cur.min(NR_OPEN) as usize
+}
@@ -12,8 +12,10 @@ use core::ptr;
 use crate::mm::pagemap::AddressSpace;
 use crate::fs::FdTable;
 use crate::signal::{SignalStruct, SigPending};
+use crate::process::rlimit::{default_rlimits, RLimit, RLIM_NLIMITS};
 use crate::config::TIME_SLICE_TICKS as DEFAULT_TIME_SLICE;
 use alloc::boxed::Box;
+use alloc::sync::Arc;
 use alloc::alloc::{alloc, dealloc};
 use core::alloc::Layout;
 use core::mem::offset_of;
@@ -219,8 +221,8 @@ pub struct Task {
     address_space: Option<AddressSpace>,
 
     /// 文件描述符表 (files_struct)
-    /// 使用 Box 以减少 Task 的大小
-    fdtable: Option<Box<FdTable>>,
+    /// 引用计数，CLONE_FILES 创建的线程共享同一个表
+    fdtable: Option<Arc<FdTable>>,
 
     /// 信号处理结构 (signal_struct)
     /// 使用 Box 以减少 Task 的大小
@@ -282,6 +284,21 @@ pub struct Task {
     /// 指向进程堆的末尾地址，由 sys_brk 管理
     /// 初始值为 0，在第一次 brk 调用时设置为默认值
     brk: core::sync::atomic::AtomicU64,
+
+    /// 资源限制 (signal_struct::rlim)，fork 时继承
+    rlimits: spin::Mutex<[RLimit; RLIM_NLIMITS]>,
+
+    /// 已使用的 CPU 时间（时钟中断次数），用于检查 RLIMIT_CPU
+    cpu_ticks: core::sync::atomic::AtomicU64,
+
+    /// 不需要 I/O 的缺页次数 (min_flt)
+    min_flt: core::sync::atomic::AtomicU64,
+
+    /// 需要从文件读取页面的缺页次数 (maj_flt)
+    maj_flt: core::sync::atomic::AtomicU64,
+
+    /// 所在运行队列的 CPU (task_struct::cpu)，唤醒时据此发送 Reschedule IPI
+    cpu: core::sync::atomic::AtomicU32,
 }
 
 impl Task {
@@ -332,6 +349,11 @@ impl Task {
             robust_list_head: ptr::null(),
             robust_list_len: 0,
             brk: core::sync::atomic::AtomicU64::new(0),
+            rlimits: spin::Mutex::new(default_rlimits()),
+            cpu_ticks: core::sync::atomic::AtomicU64::new(0),
+            min_flt: core::sync::atomic::AtomicU64::new(0),
+            maj_flt: core::sync::atomic::AtomicU64::new(0),
+            cpu: core::sync::atomic::AtomicU32::new(0),
         };
 
         // 初始化 children 和 sibling 链表（必须在结构体构造后）
@@ -406,7 +428,7 @@ impl Task {
             None,
         );
         ptr::write(
-            (ptr as usize + offset_of!(Task, fdtable)) as *mut Option<Box<FdTable>>,
+            (ptr as usize + offset_of!(Task, fdtable)) as *mut Option<Arc<FdTable>>,
             None,
         );
         ptr::write(
@@ -457,6 +479,26 @@ impl Task {
             (ptr as usize + offset_of!(Task, robust_list_len)) as *mut usize,
             0,
         );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, rlimits)) as *mut spin::Mutex<[RLimit; RLIM_NLIMITS]>,
+            spin::Mutex::new(default_rlimits()),
+        );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, cpu_ticks)) as *mut core::sync::atomic::AtomicU64,
+            core::sync::atomic::AtomicU64::new(0),
+        );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, min_flt)) as *mut core::sync::atomic::AtomicU64,
+            core::sync::atomic::AtomicU64::new(0),
+        );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, maj_flt)) as *mut core::sync::atomic::AtomicU64,
+            core::sync::atomic::AtomicU64::new(0),
+        );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, cpu)) as *mut core::sync::atomic::AtomicU32,
+            core::sync::atomic::AtomicU32::new(0),
+        );
 
         // 初始化 children 和 sibling 链表
         let children_ptr = (ptr as usize + offset_of!(Task, children)) as *mut ListHead;
@@ -535,7 +577,7 @@ impl Task {
             None,
         );
         ptr::write(
-            (ptr as usize + offset_of!(Task, fdtable)) as *mut Option<Box<FdTable>>,
+            (ptr as usize + offset_of!(Task, fdtable)) as *mut Option<Arc<FdTable>>,
             None,
         );
         ptr::write(
@@ -590,6 +632,26 @@ impl Task {
             (ptr as usize + offset_of!(Task, brk)) as *mut core::sync::atomic::AtomicU64,
             core::sync::atomic::AtomicU64::new(0),
         );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, rlimits)) as *mut spin::Mutex<[RLimit; RLIM_NLIMITS]>,
+            spin::Mutex::new(default_rlimits()),
+        );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, cpu_ticks)) as *mut core::sync::atomic::AtomicU64,
+            core::sync::atomic::AtomicU64::new(0),
+        );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, min_flt)) as *mut core::sync::atomic::AtomicU64,
+            core::sync::atomic::AtomicU64::new(0),
+        );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, maj_flt)) as *mut core::sync::atomic::AtomicU64,
+            core::sync::atomic::AtomicU64::new(0),
+        );
+        ptr::write(
+            (ptr as usize + offset_of!(Task, cpu)) as *mut core::sync::atomic::AtomicU32,
+            core::sync::atomic::AtomicU32::new(0),
+        );
 
         // 初始化 children 和 sibling 链表
         let children_ptr = (ptr as usize + offset_of!(Task, children)) as *mut ListHead;
@@ -723,6 +785,8 @@ impl Task {
 
                     // 设置 need_resched 标志，触发重新调度
                     crate::sched::set_need_resched();
+                    // 任务在其他 CPU 的运行队列上时，由该 CPU 重新调度（对应 Linux `ttwu_queue()`）
+                    crate::sched::resched_cpu((*task).cpu());
 
                     true
                 }
@@ -928,21 +992,17 @@ impl Task {
         self.fdtable.as_ref().expect("FdTable not initialized")
     }
 
-    /// 获取文件描述符表的可变引用 (Option 版本)
-    #[inline]
-    pub fn try_fdtable_mut(&mut self) -> Option<&mut FdTable> {
-        self.fdtable.as_mut().map(|b| b.as_mut())
-    }
-
-    /// 获取文件描述符表的可变引用
+    /// 获取文件描述符表的引用计数指针，用于 CLONE_FILES 共享
     #[inline]
-    pub fn fdtable_mut(&mut self) -> &mut FdTable {
-        self.fdtable.as_mut().expect("FdTable not initialized")
+    pub fn shared_fdtable(&self) -> Option<&Arc<FdTable>> {
+        self.fdtable.as_ref()
     }
 
     /// 设置文件描述符表
+    ///
+    /// 替换掉的表只释放本任务的引用，最后一个引用释放时才关闭其中的文件
     #[inline]
-    pub fn set_fdtable(&mut self, fdtable: Option<alloc::boxed::Box<FdTable>>) {
+    pub fn set_fdtable(&mut self, fdtable: Option<Arc<FdTable>>) {
         self.fdtable = fdtable;
     }
 
@@ -1208,8 +1268,75 @@ impl Task {
     pub fn set_brk(&self, value: u64) {
         self.brk.store(value, core::sync::atomic::Ordering::Release);
     }
+
+    /// 获取资源 `resource`（`rlimit::RLIMIT_*`）的限制
+    #[inline]
+    pub fn rlimit(&self, resource: usize) -> RLimit {
+        self.rlimits.lock()[resource]
+    }
+
+    /// 设置资源 `resource` 的限制，调用者负责用 `rlimit::check_rlimit` 检查
+    #[inline]
+    pub fn set_rlimit(&self, resource: usize, limit: RLimit) {
+        self.rlimits.lock()[resource] = limit;
+    }
+
+    /// 获取全部资源限制（fork 时复制给子进程）
+    #[inline]
+    pub fn rlimits(&self) -> [RLimit; RLIM_NLIMITS] {
+        *self.rlimits.lock()
+    }
+
+    /// 设置全部资源限制
+    #[inline]
+    pub fn set_rlimits(&self, limits: [RLimit; RLIM_NLIMITS]) {
+        *self.rlimits.lock() = limits;
+    }
+
+    /// 记录一个时钟中断的 CPU 时间，返回累计的中断次数
+    #[inline]
+    pub fn account_cpu_tick(&self) -> u64 {
+        self.cpu_ticks.fetch_add(1, core::sync::atomic::Ordering::Relaxed) + 1
+    }
+
+    /// 已使用的 CPU 时间（时钟中断次数）
+    #[inline]
+    pub fn cpu_ticks(&self) -> u64 {
+        self.cpu_ticks.load(core::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 记录一次已修复的缺页，`major` 表示需要从文件读取页面
+    #[inline]
+    pub fn account_fault(&self, major: bool) {
+        let counter = if major { &self.maj_flt } else { &self.min_flt };
+        counter.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// 不需要 I/O 的缺页次数
+    #[inline]
+    pub fn min_flt(&self) -> u64 {
+        self.min_flt.load(core::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 需要从文件读取页面的缺页次数
+    #[inline]
+    pub fn maj_flt(&self) -> u64 {
+        self.maj_flt.load(core::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 所在运行队列的 CPU（对应 Linux `task_cpu()`）
+    #[inline]
+    pub fn cpu(&self) -> usize {
+        self.cpu.load(core::sync::atomic::Ordering::Acquire) as usize
+    }
+
+    /// 记录任务被放入哪个 CPU 的运行队列（对应 Linux `set_task_cpu()`）
+    #[inline]
+    pub fn set_cpu(&self, cpu: usize) {
+        self.cpu.store(cpu as u32, core::sync::atomic::Ordering::Release);
+    }
 }
 
 ///
-/// 可选: 100, 250, 300, 1000
-const HZ: u32 = 100;
+/// 可选: 100, 250, 300, 1000（由 Kernel.toml 的 `[timer] hz` 配置）
+const HZ: u32 = crate::config::TIMER_HZ as u32;
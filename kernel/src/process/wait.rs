@@ -80,6 +80,12 @@ pub struct WaitQueueHead {
     list: Mutex<Vec<WaitQueueEntry>>,
 }
 
+// SAFETY: 等待项中的 `*mut Task` 只在持有 `list` 锁时读取，任务在从队列移除前保持有效
+// （与 Linux `wait_queue_head` 由 `lock` 保护相同），因此可以在 CPU 之间共享，
+// 例如作为全局的 `static` 等待队列
+unsafe impl Send for WaitQueueHead {}
+unsafe impl Sync for WaitQueueHead {}
+
 impl WaitQueueHead {
     /// 创建新的等待队列头
     ///
@@ -149,9 +155,8 @@ impl WaitQueueHead {
 
             if !entry.is_woken() {
                 entry.set_woken();
-                // TODO: 实际唤醒进程
-                // 当前简化实现：标记为已唤醒
-                // 完整实现需要将进程添加到运行队列
+                // 将睡眠中的进程设置为可运行（未睡眠的进程保持不变）
+                Task::wake_up(entry.task());
                 awakened += 1;
 
                 // 独占模式：只唤醒一个
@@ -0,0 +1,132 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+//! 定长字节环形缓冲区
+//!
+//! 参考 Linux: include/linux/kfifo.h
+//!
+//! 用途：
+//! - 终端输入: tty 行规程中等待 read 的字节
+//!
+//! 设计特点：
+//! - 容量由 const 泛型参数决定，可以放在静态变量中（`const fn new`）
+//! - 本身不加锁，由使用者用 Mutex 保护
+//! - 写满后丢弃新数据（与 kfifo_in 相同），丢弃的字节数记录在 `overflows`
+
+pub struct RingBuffer<const N: usize> {
+    buf: [u8; N],
+    /// 下一个读取位置
+    head: usize,
+    /// 已存储的字节数
+    len: usize,
+    /// 因缓冲区已满而丢弃的字节数
+    overflows: usize,
+}
+
+impl<const N: usize> RingBuffer<N> {
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            head: 0,
+            len: 0,
+            overflows: 0,
+        }
+    }
+
+    /// 容量（字节）
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// 已存储的字节数
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// 剩余空间（字节）
+    pub fn space(&self) -> usize {
+        N - self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// 累计丢弃的字节数
+    pub fn overflows(&self) -> usize {
+        self.overflows
+    }
+
+    /// 丢弃所有数据（不清零溢出计数）
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// 写入一个字节
+    ///
+    /// # 返回
+    /// 缓冲区已满时丢弃该字节并返回 false
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.is_full() {
+            self.overflows += 1;
+            return false;
+        }
+        self.buf[(self.head + self.len) % N] = byte;
+        self.len += 1;
+        true
+    }
+
+    /// 读取一个字节
+    pub fn pop(&mut self) -> Option<u8> {
+        if self.is_empty() {
+            return None;
+        }
+        let byte = self.buf[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(byte)
+    }
+
+    /// 写入尽可能多的字节，放不下的部分被丢弃
+    ///
+    /// # 返回
+    /// 实际写入的字节数
+    pub fn push_slice(&mut self, data: &[u8]) -> usize {
+        let count = data.len().min(self.space());
+        let tail = (self.head + self.len) % N;
+        // 先写到数组末尾，再从头部继续
+        let first = count.min(N - tail);
+        self.buf[tail..tail + first].copy_from_slice(&data[..first]);
+        self.buf[..count - first].copy_from_slice(&data[first..count]);
+        self.len += count;
+        self.overflows += data.len() - count;
+        count
+    }
+
+    /// 读取最多 `out.len()` 个字节
+    ///
+    /// # 返回
+    /// 实际读取的字节数
+    pub fn pop_slice(&mut self, out: &mut [u8]) -> usize {
+        let count = out.len().min(self.len);
+        let first = count.min(N - self.head);
+        out[..first].copy_from_slice(&self.buf[self.head..self.head + first]);
+        out[first..count].copy_from_slice(&self.buf[..count - first]);
+        self.head = (self.head + count) % N;
+        self.len -= count;
+        count
+    }
+}
+
+impl<const N: usize> Default for RingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
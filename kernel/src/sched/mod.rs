@@ -23,6 +23,7 @@ pub use sched::{
     find_task_by_pid,
     get_current_fdtable,
     do_exit,
+    notify_parent,
     do_wait,
     do_wait_nonblock,
     alloc_task_slot,
@@ -31,12 +32,14 @@ pub use sched::{
     init,
     schedule,
     send_signal,
+    send_signal_self,
     cpu_rq,
     this_cpu_rq,
     load_balance,
     resched_curr,
     resched_cpu,
     wake_up_process,
+    cpus_running_mm,
     // 抢占式调度支持
     need_resched,
     set_need_resched,
@@ -110,6 +110,11 @@ pub fn scheduler_tick() {
     let task = unsafe { &mut *current };
     let still_has_slice = task.tick_time_slice();
 
+    // CPU 时间记账，超出 RLIMIT_CPU 时发送 SIGXCPU / SIGKILL（idle 任务不记账）
+    if task.pid() != 0 {
+        crate::process::rlimit::account_cpu_time(task, crate::config::TIMER_HZ);
+    }
+
     // 检查时间片是否用完
     if !still_has_slice {
         // 时间片用完，重新分配时间片
@@ -139,6 +144,30 @@ pub fn resched_cpu(cpu: usize) {
     crate::arch::ipi::send_reschedule_ipi(cpu);
 }
 
+/// 正在运行使用页表 `root_ppn` 的任务的 CPU（位掩码，bit N 对应 CPU N）
+///
+/// 用于 TLB shootdown 确定需要刷新的 CPU
+pub fn cpus_running_mm(root_ppn: u64) -> usize {
+    let mut mask = 0;
+    for cpu in 0..MAX_CPUS {
+        let rq = match unsafe { PER_CPU_RQ[cpu].as_ref() } {
+            Some(rq) => rq,
+            None => continue,
+        };
+        let current = rq.lock().current;
+        if current.is_null() {
+            continue;
+        }
+        let runs_mm = unsafe {
+            (*current).address_space().map_or(false, |mm| mm.root_ppn() == root_ppn)
+        };
+        if runs_mm {
+            mask |= 1 << cpu;
+        }
+    }
+    mask
+}
+
 
 pub fn wake_up_process(task: *mut Task) -> bool {
     use crate::process::Task;
@@ -198,7 +227,7 @@ static mut IDLE_TASK_STORAGES: [core::mem::MaybeUninit<Task>; MAX_CPUS] = [
 const TASK_POOL_SIZE: usize = 16;
 
 // 计算 Task 结构体的实际大小，确保每个槽位足够大
-// Task 包含：CpuContext、AddressSpace、Option<Box<FdTable>>、
+// Task 包含：CpuContext、AddressSpace、Option<Arc<FdTable>>、
 //            Option<Box<SignalStruct>>、ListHead 等
 const TASK_SIZE: usize = core::mem::size_of::<Task>();
 
@@ -487,6 +516,7 @@ pub fn enqueue_task(task: &'static mut Task) {
                 if rq_inner.tasks[i].is_null() {
                     rq_inner.tasks[i] = task;
                     rq_inner.nr_running += 1;
+                    task.set_cpu(crate::arch::cpu_id() as usize);
                     task.set_state(TaskState::Running);
                     return;
                 }
@@ -623,7 +653,8 @@ pub unsafe fn find_task_by_pid(pid: Pid) -> *mut Task {
     for cpu_id in 0..MAX_CPUS {
         if let Some(rq) = cpu_rq(cpu_id) {
             let rq_inner = rq.lock();
-            for i in 0..rq_inner.nr_running {
+            // 任务槽可能因出队而出现空洞，必须扫描全部槽位
+            for i in 0..MAX_TASKS {
                 let task = rq_inner.tasks[i];
                 if !task.is_null() && (*task).pid() == pid {
                     return task;
@@ -665,7 +696,7 @@ pub fn init_std_fds() {
             }
 
             // Idle 任务没有 fdtable
-            let fdtable = match (*current).try_fdtable_mut() {
+            let fdtable = match (*current).try_fdtable() {
                 Some(ft) => ft,
                 None => return,
             };
@@ -679,27 +710,30 @@ pub fn init_std_fds() {
                 write: Some(uart_file_write),
                 lseek: None,
                 close: None,
+                poll: Some(crate::fs::char_dev::uart_file_poll),
+                ioctl: Some(crate::fs::char_dev::uart_file_ioctl),
+                mmap: None,
             };
 
             // 创建 stdin (fd=0)
-            let stdin = Arc::new(File::new(FileFlags::new(FileFlags::O_RDONLY)));
+            let mut stdin = File::new(FileFlags::new(FileFlags::O_RDONLY));
             stdin.set_ops(&UART_OPS);
             stdin.set_private_data(&uart_dev as *const CharDev as *mut u8);
 
             // 创建 stdout (fd=1)
-            let stdout = Arc::new(File::new(FileFlags::new(FileFlags::O_WRONLY)));
+            let mut stdout = File::new(FileFlags::new(FileFlags::O_WRONLY));
             stdout.set_ops(&UART_OPS);
             stdout.set_private_data(&uart_dev as *const CharDev as *mut u8);
 
             // 创建 stderr (fd=2)
-            let stderr = Arc::new(File::new(FileFlags::new(FileFlags::O_WRONLY)));
+            let mut stderr = File::new(FileFlags::new(FileFlags::O_WRONLY));
             stderr.set_ops(&UART_OPS);
             stderr.set_private_data(&uart_dev as *const CharDev as *mut u8);
 
             // 安装标准文件描述符
-            let _ = fdtable.install_fd(0, stdin);
-            let _ = fdtable.install_fd(1, stdout);
-            let _ = fdtable.install_fd(2, stderr);
+            let _ = fdtable.install_fd(0, Arc::new(stdin));
+            let _ = fdtable.install_fd(1, Arc::new(stdout));
+            let _ = fdtable.install_fd(2, Arc::new(stderr));
         }
     }
 }
@@ -903,8 +937,6 @@ pub fn check_and_handle_signals() {
 // ============================================================================
 
 pub fn do_exit(exit_code: i32) -> ! {
-    use crate::signal::Signal;
-
     if let Some(rq) = this_cpu_rq() {
         unsafe {
             let rq_inner = rq.lock();
@@ -924,21 +956,24 @@ pub fn do_exit(exit_code: i32) -> ! {
             (*current).set_exit_code(exit_code);
 
             // 设置进程状态为 Zombie
+            // 僵尸进程留在运行队列中（不会被调度），等待父进程 wait4 回收
             (*current).set_state(TaskState::Zombie);
+            drop(rq_inner);  // 释放锁后再通知父进程
 
-            // 从运行队列移除
-            drop(rq_inner);  // 释放锁后再调用 dequeue_task
-            dequeue_task(&*current);
+            // exit_files: 释放对 fd 表的引用，CLONE_FILES 共享的表在最后一个线程退出时才关闭
+            (*current).set_fdtable(None);
 
             // 向父进程发送 SIGCHLD 信号并唤醒父进程
-            if parent_pid != 0 {
-                let _ = send_signal(parent_pid, Signal::SIGCHLD as i32);
+            let parent = if parent_pid != 0 {
+                find_task_by_pid(parent_pid)
+            } else {
+                core::ptr::null_mut()
+            };
+            let autoreap = parent.is_null() || notify_parent(parent);
 
-                // 唤醒父进程（如果父进程在 wait4 中阻塞等待）
-                let parent = find_task_by_pid(parent_pid);
-                if !parent.is_null() {
-                    wake_up_process(parent);
-                }
+            // 没有父进程等待回收，或父进程忽略 SIGCHLD：直接从运行队列移除
+            if autoreap {
+                dequeue_task(&*current);
             }
 
             // 调度器选择下一个进程运行
@@ -959,6 +994,48 @@ pub fn do_exit(exit_code: i32) -> ! {
     }
 }
 
+/// 子进程退出时通知父进程（对应 Linux `do_notify_parent()`）
+///
+/// - 父进程 SIGCHLD 为 SIG_IGN 或设置了 SA_NOCLDWAIT：子进程自动回收
+/// - 只有安装了处理函数或 SIGCHLD 被屏蔽时才投递；SIG_DFL 和 SIG_IGN
+///   的 SIGCHLD 直接丢弃（对应 Linux `sig_ignored()`），
+///   否则 wait4 中的父进程会被这个信号以 EINTR 打断
+/// - 唤醒在 wait4 中阻塞的父进程
+///
+/// # 返回
+/// `true` 表示子进程应自动回收，不留下僵尸进程
+pub fn notify_parent(parent: *mut Task) -> bool {
+    use crate::signal::{self, SigActionKind, Signal};
+
+    if parent.is_null() {
+        return true;
+    }
+
+    unsafe {
+        let sig = Signal::SIGCHLD as i32;
+        let blocked = (*parent).sigmask & (1u64 << (sig - 1)) != 0;
+        let (autoreap, deliver) = match (*parent).signal.as_ref() {
+            Some(signal_ref) => {
+                let handled = signal_ref
+                    .get_action(sig)
+                    .map(|action| action.action() == SigActionKind::Handler)
+                    .unwrap_or(false);
+                (signal_ref.child_autoreap(), handled || blocked)
+            }
+            None => (false, blocked),
+        };
+
+        if deliver {
+            (*parent).pending.add(sig);
+        }
+
+        // 唤醒父进程（如果父进程在 wait4 中阻塞等待）
+        signal::signal_wake_up(parent);
+
+        autoreap
+    }
+}
+
 pub fn do_wait(pid: i32, status_ptr: *mut i32) -> Result<Pid, i32> {
     // Debug: entering do_wait
     unsafe {
@@ -1095,6 +1172,13 @@ pub fn do_wait(pid: i32, status_ptr: *mut i32) -> Result<Pid, i32> {
 
             // 有子进程但还没有退出的
             if found_child {
+                // 先扫描僵尸子进程再检查信号：与子进程退出同时到达的信号
+                // 不会让已经可以回收的子进程丢失
+                use crate::signal;
+                if signal::signal_pending() {
+                    return Err(errno::Errno::InterruptedSystemCall.as_neg_i32());  // EINTR
+                }
+
                 // Debug: going to sleep
                 unsafe {
                     crate::console::putchar(b'D');
@@ -1117,13 +1201,7 @@ pub fn do_wait(pid: i32, status_ptr: *mut i32) -> Result<Pid, i32> {
                     crate::console::putchar(b'\n');
                 }
 
-                // 被唤醒后，检查是否有信号到达
-                use crate::signal;
-                if signal::signal_pending() {
-                    return Err(errno::Errno::InterruptedSystemCall.as_neg_i32());  // EINTR
-                }
-
-                // 继续循环检查是否有子进程退出
+                // 被唤醒后重新扫描：子进程退出则回收，否则检查信号后继续睡眠
             } else {
                 // Debug: no child
                 unsafe {
@@ -1339,8 +1417,8 @@ pub fn load_balance() {
                     // 添加任务到当前 CPU 的运行队列
                     enqueue_task_locked(&mut *this_rq_inner, task);
 
-                    // 更新任务的 CPU 亲和性（可选）
-                    // (*task).set_cpu(this_cpu);
+                    // 之后的唤醒通知新的 CPU
+                    (*task).set_cpu(this_cpu);
                 }
             }
         }
@@ -1400,8 +1478,13 @@ pub fn cpu_idle_loop() -> ! {
 
         // 3. 进入 WFI 休眠，等待中断唤醒
         // 中断会设置 need_resched 标志，从而跳出 WFI
+        // tickless idle：定时器设置到下一个延迟工作的截止时间
+        #[cfg(feature = "riscv64")]
+        crate::drivers::timer::tick_nohz_idle_enter();
         unsafe {
             asm!("wfi", options(nomem, nostack));
         }
+        #[cfg(feature = "riscv64")]
+        crate::drivers::timer::tick_nohz_idle_exit();
     }
 }
@@ -67,6 +67,12 @@ pub enum Signal {
     SIGTTIN = 21,
     /// SIGTTOU - 后台写
     SIGTTOU = 22,
+    /// SIGURG - 套接字紧急数据
+    SIGURG = 23,
+    /// SIGXCPU - 超出 CPU 时间限制 (RLIMIT_CPU)
+    SIGXCPU = 24,
+    /// SIGXFSZ - 超出文件大小限制 (RLIMIT_FSIZE)
+    SIGXFSZ = 25,
 }
 
 /// 实时信号范围 (32-64)
@@ -409,13 +415,12 @@ impl SigPending {
 
         // 设置位图
         let mask = 1u64 << (sig - 1);
-        self.signal.fetch_or(mask, Ordering::AcqRel);
+        let was_pending = self.signal.fetch_or(mask, Ordering::AcqRel) & mask != 0;
 
-        // 实时信号需要排队
-        if sig >= SIGRTMIN {
+        // 实时信号需要排队；标准信号已在等待时不再保存新的 info
+        if sig >= SIGRTMIN || !was_pending {
             self.queue.enqueue(info);
         }
-        // 标准信号只保留最新的 info，不排队
     }
 
     /// 删除信号（从位图和队列中删除）
@@ -426,8 +431,8 @@ impl SigPending {
 
         let mask = 1u64 << (sig - 1);
 
-        // 如果是实时信号且队列非空，从队列中移除
-        if sig >= SIGRTMIN && !self.queue.is_empty() {
+        // 队列非空时移除该信号的 info
+        if !self.queue.is_empty() {
             // 尝试从队列头部移除该信号
             while let Some(info) = self.queue.peek() {
                 if info.si_signo == sig {
@@ -499,8 +504,9 @@ impl SignalStruct {
         actions[Signal::SIGKILL as usize - 1] = SigAction::new();  // SIGKILL: 默认杀死
         actions[Signal::SIGSTOP as usize - 1] = SigAction::new();  // SIGSTOP: 默认停止
 
-        // SIGCHLD 默认忽略
-        actions[Signal::SIGCHLD as usize - 1] = SigAction::ignore();
+        // SIGCHLD 默认动作是忽略，但必须保持 SIG_DFL：
+        // 只有显式 SIG_IGN 才会让子进程退出时被自动回收
+        actions[Signal::SIGCHLD as usize - 1] = SigAction::new();
 
         Self {
             action: actions,
@@ -549,6 +555,16 @@ impl SignalStruct {
         self.mask.fetch_and(!mask, Ordering::AcqRel);
     }
 
+    /// 子进程退出时是否自动回收（不产生僵尸进程）
+    ///
+    /// 对应 Linux `do_notify_parent()` 中的判断：
+    /// SIGCHLD 被显式设置为 SIG_IGN，或设置了 SA_NOCLDWAIT
+    pub fn child_autoreap(&self) -> bool {
+        let action = &self.action[Signal::SIGCHLD as usize - 1];
+        action.action() == SigActionKind::Ignore
+            || (action.sa_flags.bits() & SigFlags::SA_NOCLDWAIT) != 0
+    }
+
     /// 检查信号是否被屏蔽
     pub fn is_masked(&self, sig: i32) -> bool {
         if sig < 1 || sig > 64 {
@@ -574,6 +590,8 @@ pub struct SigInfo {
     pub si_uid: u32,
     /// 退出状态或错误值
     pub si_status: i32,
+    /// 出错的地址（SIGSEGV / SIGBUS）
+    pub si_addr: u64,
 }
 
 impl SigInfo {
@@ -585,6 +603,19 @@ impl SigInfo {
             si_pid: pid,
             si_uid: uid,
             si_status: 0,
+            si_addr: 0,
+        }
+    }
+
+    /// 创建同步错误信号信息（SIGSEGV / SIGBUS），带出错地址
+    pub fn fault(signo: i32, code: i32, addr: u64) -> Self {
+        Self {
+            si_signo: signo,
+            si_code: code,
+            si_pid: 0,
+            si_uid: 0,
+            si_status: 0,
+            si_addr: addr,
         }
     }
 
@@ -596,6 +627,7 @@ impl SigInfo {
             si_pid: pid,
             si_uid: uid,
             si_status: status,
+            si_addr: 0,
         }
     }
 }
@@ -612,6 +644,10 @@ pub mod si_code {
     pub const CLD_KILLED: i32 = 2;
     /// 子进程异常终止
     pub const CLD_DUMPED: i32 = 3;
+    /// 地址没有映射 (SIGSEGV)
+    pub const SEGV_MAPERR: i32 = 1;
+    /// 地址已映射但访问权限不符 (SIGSEGV)
+    pub const SEGV_ACCERR: i32 = 2;
 }
 
 // ============================================================================
@@ -670,12 +706,12 @@ pub struct SignalStack {
 }
 
 impl SignalStack {
-    /// 创建新的信号栈
+    /// 创建新的信号栈（未设置时为禁用状态）
     pub fn new() -> Self {
         Self {
             ss_sp: 0,
             ss_size: 0,
-            ss_flags: 0,
+            ss_flags: ss_flags::SS_DISABLE,
         }
     }
 
@@ -688,6 +724,50 @@ impl SignalStack {
     pub fn is_on_stack(&self) -> bool {
         (self.ss_flags & crate::signal::ss_flags::SS_ONSTACK) != 0
     }
+
+    /// 地址是否落在信号栈内
+    pub fn contains(&self, addr: u64) -> bool {
+        addr >= self.ss_sp && addr < self.ss_sp + self.ss_size
+    }
+}
+
+/// sigaltstack 核心：返回任务原来的备用信号栈，`new` 不为空时设置新的栈
+///
+/// # 返回
+/// * `Err(-EPERM)` - 正在备用栈上执行信号处理函数时不能修改
+/// * `Err(-EINVAL)` - 未知的标志
+/// * `Err(-ENOMEM)` - 栈小于 `MINSIGSTKSZ`
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务
+pub unsafe fn do_sigaltstack(
+    task: *mut crate::process::task::Task,
+    new: Option<SignalStack>,
+) -> Result<SignalStack, i32> {
+    use crate::errno::Errno;
+
+    let old = (*task).sigstack;
+    let Some(new) = new else {
+        return Ok(old);
+    };
+
+    if old.is_on_stack() {
+        return Err(Errno::OperationNotPermitted.as_neg_i32());
+    }
+
+    (*task).sigstack = match new.ss_flags {
+        ss_flags::SS_DISABLE => SignalStack { ss_sp: 0, ss_size: 0, ss_flags: ss_flags::SS_DISABLE },
+        0 => {
+            if new.ss_size < MINSIGSTKSZ as u64 {
+                return Err(Errno::OutOfMemory.as_neg_i32());
+            }
+            SignalStack { ss_sp: new.ss_sp, ss_size: new.ss_size, ss_flags: 0 }
+        }
+        _ => return Err(Errno::InvalidArgument.as_neg_i32()),
+    };
+
+    Ok(old)
 }
 
 /// 信号栈标志
@@ -762,41 +842,72 @@ pub fn do_signal() -> bool {
 
     unsafe {
         let current = match sched::current() {
-            Some(c) => c,
+            Some(c) => c as *mut crate::process::task::Task,
             None => return false,
         };
 
-        // 检查是否有待处理信号
-        let sig = match (*current).pending.first() {
-            Some(s) => s,
-            None => return false,
-        };
+        if (*current).pending.get_all() & !(*current).sigmask == 0 {
+            return false;
+        }
 
-        // 获取信号处理动作（克隆需要的数据）
-        let action = (*current).signal.as_ref()
-            .and_then(|s| s.get_action(sig))
-            .cloned();
-
-        // 处理信号
-        if let Some(action) = action {
-            // 检查是否有自定义处理函数
-            if action.has_handler() {
-                // 调用信号处理函数
-                if !setup_frame(current, sig, &action) {
-                    // 设置失败，执行默认动作
-                    handle_default_signal(sig);
-                }
-            } else {
-                // 执行默认动作
-                handle_default_signal(sig);
-            }
+        do_signal_task(current);
+        true
+    }
+}
+
+/// 处理任务编号最小的未屏蔽待处理信号
+///
+/// 有处理函数时调用 `setup_frame` 建立信号帧：信号信息取自待处理队列
+/// （例如缺页的 si_addr），处理函数运行期间屏蔽 `sa_mask` 和该信号本身
+/// （`SA_NODEFER` 时不屏蔽自身）。否则执行默认动作。
+///
+/// # Returns
+///
+/// * `true` - 已建立信号帧，返回用户态时应进入处理函数
+/// * `false` - 没有可处理的信号，或执行了默认动作
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务
+pub unsafe fn do_signal_task(task: *mut crate::process::task::Task) -> bool {
+    let unblocked = (*task).pending.get_all() & !(*task).sigmask;
+    if unblocked == 0 {
+        return false;
+    }
+    let sig = unblocked.trailing_zeros() as i32 + 1;
+
+    let info = (*task).pending.queue.peek().filter(|info| info.si_signo == sig);
+    let action = (*task).signal.as_ref()
+        .and_then(|s| s.get_action(sig))
+        .cloned();
+
+    // 从待处理队列中删除信号
+    (*task).pending.remove(sig);
+
+    let action = match action {
+        Some(action) if action.has_handler() => action,
+        _ => {
+            handle_default_signal(sig);
+            return false;
         }
+    };
 
-        // 从待处理队列中删除信号
-        (*current).pending.remove(sig);
+    if !setup_frame(task, sig, &action) {
+        // 设置失败，执行默认动作
+        handle_default_signal(sig);
+        return false;
+    }
 
-        true
+    if let (Some(info), Some(frame)) = (info, (*task).sigframe.as_mut()) {
+        frame.info = info;
+    }
+    let mut blocked = action.sa_mask;
+    if (action.sa_flags.bits() & SigFlags::SA_NODEFER) == 0 {
+        blocked |= 1u64 << (sig - 1);
     }
+    (*task).sigmask |= blocked & !UNBLOCKABLE_MASK;
+
+    true
 }
 
 /// 设置信号帧并准备调用信号处理函数
@@ -812,7 +923,11 @@ pub fn do_signal() -> bool {
 ///
 /// * `true` - 设置成功
 /// * `false` - 设置失败
-unsafe fn setup_frame(
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务
+pub unsafe fn setup_frame(
     task: *mut crate::process::task::Task,
     sig: i32,
     action: &SigAction,
@@ -833,14 +948,23 @@ unsafe fn setup_frame(
         let sigstack = &(*task).sigstack;
 
         // 检查信号栈是否有效
-        if sigstack.is_disabled() || sigstack.ss_sp == 0 {
+        if sigstack.is_disabled() || sigstack.ss_sp == 0 || sigstack.ss_size < SIGNAL_FRAME_SIZE {
+            return false;
+        }
+
+        // 计算信号帧位置（在信号栈顶部，16 字节对齐）
+        let frame_addr = (sigstack.ss_sp + sigstack.ss_size - SIGNAL_FRAME_SIZE) & !0xF;
+        if frame_addr < sigstack.ss_sp {
             return false;
         }
 
-        // 计算信号帧位置（在信号栈顶部）
-        sigstack.ss_sp + sigstack.ss_size - SIGNAL_FRAME_SIZE
+        // 处理函数返回前不允许修改备用栈
+        (*task).sigstack.ss_flags |= ss_flags::SS_ONSTACK;
+        frame_addr
+    } else if ctx.user_sp > SIGNAL_FRAME_SIZE {
+        // 使用正常用户栈，帧放在被打断代码的栈顶之下
+        (ctx.user_sp - SIGNAL_FRAME_SIZE) & !0xF
     } else {
-        // 使用正常用户栈
         USER_STACK_TOP - SIGNAL_FRAME_SIZE
     };
 
@@ -906,7 +1030,7 @@ unsafe fn setup_frame(
     ctx.pc = action.sa_handler as u64;
 
     // 设置用户栈指针到信号帧位置
-    ctx.sp = frame_addr;
+    ctx.user_sp = frame_addr;
 
     true  // 成功
 }
@@ -974,6 +1098,11 @@ pub unsafe fn restore_sigcontext(
     // 恢复信号掩码
     (*task).sigmask = frame.uc.uc_sigmask;
 
+    // 离开备用信号栈
+    if (*task).sigstack.contains(frame_addr) {
+        (*task).sigstack.ss_flags &= !ss_flags::SS_ONSTACK;
+    }
+
     // 清除信号帧
     (*task).sigframe = None;
     (*task).sigframe_addr = 0;
@@ -1004,7 +1133,8 @@ fn handle_default_signal(sig: i32) {
         // 终止进程
         1 | 2 | 3 | 4 | 5 | 6   // SIGHUP | SIGINT | SIGQUIT | SIGILL | SIGTRAP | SIGABRT
         | 7 | 8 | 11 | 13 | 14 | 15  // SIGBUS | SIGFPE | SIGSEGV | SIGPIPE | SIGALRM | SIGTERM
-        | 16 | 10 | 12 => {          // SIGSTKFLT | SIGUSR1 | SIGUSR2
+        | 16 | 10 | 12               // SIGSTKFLT | SIGUSR1 | SIGUSR2
+        | 24 | 25 => {               // SIGXCPU | SIGXFSZ
             // TODO: 调用 exit 系统调用或直接终止进程
             // crate::process::sched::do_exit(sig);
         }
@@ -1049,6 +1179,46 @@ pub fn send_signal(pid: u32, sig: i32) -> bool {
     }
 }
 
+/// 向任务强制发送同步错误信号（对应 Linux `force_sig_fault()`）
+///
+/// 由出错的指令触发（SIGSEGV、SIGBUS 等），不能被屏蔽或忽略：
+/// 被屏蔽（例如处理函数中再次出错）或被忽略时恢复默认动作，并解除屏蔽。
+///
+/// # Returns
+///
+/// * `true` - 信号是致命的（没有处理函数，或上一次同一信号还未处理就再次出错），
+///   调用者应以该信号终止任务
+/// * `false` - 信号已加入待处理队列，等待处理函数运行
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务
+pub unsafe fn force_sig_fault(
+    task: *mut crate::process::task::Task,
+    sig: i32,
+    code: i32,
+    addr: u64,
+) -> bool {
+    // 处理函数还没运行就再次出错，继续重试只会反复出错
+    let repeated = (*task).pending.has(sig);
+    let blocked = (*task).sigmask & (1u64 << (sig - 1)) != 0;
+
+    let has_handler = match (*task).signal.as_mut() {
+        Some(signal) => {
+            signal.remove_mask(sig);
+            if blocked || signal.get_action(sig).is_some_and(|a| a.action() == SigActionKind::Ignore) {
+                let _ = signal.set_action(sig, SigAction::new());
+            }
+            signal.get_action(sig).is_some_and(|a| a.has_handler())
+        }
+        None => false,
+    };
+    (*task).sigmask &= !(1u64 << (sig - 1));
+
+    (*task).pending.add_info(SigInfo::fault(sig, code, addr));
+    repeated || !has_handler
+}
+
 /// 检查并处理信号（在内核返回用户空间前调用）
 ///
 pub fn check_and_deliver_signals() {
@@ -1286,6 +1456,154 @@ pub fn signal_pending() -> bool {
     }
 }
 
+/// 被信号中断的系统调用的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyscallRestart {
+    /// 系统调用没有被中断，保持原返回值
+    None,
+    /// 返回 `-EINTR` 给用户程序
+    Interrupt,
+    /// 回退 PC，信号处理函数返回后重新执行系统调用
+    Restart,
+}
+
+/// 检查系统调用是否允许在 `SA_RESTART` 下自动重启
+///
+/// 只包括慢速设备上的读写和等待（RISC-V 调用号）：
+/// read / write / readv / writev / wait4
+pub fn is_restartable_syscall(syscall_no: u64) -> bool {
+    matches!(syscall_no, 63 | 64 | 65 | 66 | 260)
+}
+
+/// 根据返回值和信号处理动作决定系统调用是否重启
+///
+/// # 参数
+/// * `syscall_no` - 系统调用号
+/// * `ret` - 系统调用返回值
+/// * `action` - 打断系统调用的信号的处理动作
+///
+/// 可重启的系统调用返回 `-EINTR` 时（对应 Linux `-ERESTARTSYS`）：
+/// - 没有处理函数运行（默认动作、忽略或没有信号）：透明重启
+/// - 处理函数设置了 `SA_RESTART`：处理函数返回后重启
+/// - 处理函数没有设置 `SA_RESTART`：`-EINTR` 返回给用户程序
+pub fn syscall_restart_action(
+    syscall_no: u64,
+    ret: i64,
+    action: Option<&SigAction>,
+) -> SyscallRestart {
+    if ret != crate::errno::Errno::InterruptedSystemCall.as_neg_i32() as i64 {
+        return SyscallRestart::None;
+    }
+
+    let restart = match action {
+        Some(a) if a.has_handler() => (a.sa_flags.bits() & SigFlags::SA_RESTART) != 0,
+        _ => true,
+    };
+
+    if restart && is_restartable_syscall(syscall_no) {
+        SyscallRestart::Restart
+    } else {
+        SyscallRestart::Interrupt
+    }
+}
+
+/// 针对当前进程第一个未屏蔽的待处理信号决定系统调用是否重启
+///
+/// 在系统调用返回、信号投递之前调用
+pub fn current_syscall_restart(syscall_no: u64, ret: i64) -> SyscallRestart {
+    use crate::sched;
+
+    unsafe {
+        let current = match sched::current() {
+            Some(c) => c,
+            None => return syscall_restart_action(syscall_no, ret, None),
+        };
+
+        let unblocked = (*current).pending.get_all() & !(*current).sigmask;
+        let action = if unblocked != 0 {
+            let sig = unblocked.trailing_zeros() as i32 + 1;
+            (*current).signal.as_ref()
+                .and_then(|s| s.get_action(sig))
+                .cloned()
+        } else {
+            None
+        };
+
+        syscall_restart_action(syscall_no, ret, action.as_ref())
+    }
+}
+
+/// 不能被屏蔽的信号（SIGKILL、SIGSTOP）
+const UNBLOCKABLE_MASK: u64 =
+    (1 << (Signal::SIGKILL as u64 - 1)) | (1 << (Signal::SIGSTOP as u64 - 1));
+
+/// sigsuspend 核心：临时替换信号掩码，睡眠直到有未屏蔽的待处理信号
+///
+/// `sleep` 负责让出 CPU（系统调用中为可中断睡眠），醒来后重新检查。
+/// 返回前恢复原来的信号掩码，总是返回 `-EINTR`
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务
+pub unsafe fn sigsuspend_task(
+    task: *mut crate::process::task::Task,
+    mask: u64,
+    mut sleep: impl FnMut(),
+) -> i32 {
+    let old_mask = (*task).sigmask;
+    (*task).sigmask = mask & !UNBLOCKABLE_MASK;
+
+    while (*task).pending.get_all() & !(*task).sigmask == 0 {
+        sleep();
+    }
+
+    (*task).sigmask = old_mask;
+    crate::errno::Errno::InterruptedSystemCall.as_neg_i32()
+}
+
+/// sigtimedwait 核心：等待 `set` 中的某个信号变为待处理并取走它
+///
+/// # 参数
+/// * `set` - 等待的信号集合
+/// * `deadline` - 超时时刻（jiffies），`None` 表示一直等待
+/// * `now` - 读取当前时刻
+/// * `sleep` - 让出 CPU
+///
+/// # 返回
+/// * `Ok(info)` - 取走的信号（编号最小的优先）
+/// * `Err(-EAGAIN)` - 超时
+/// * `Err(-EINTR)` - 被 `set` 之外的未屏蔽信号打断
+///
+/// # Safety
+///
+/// `task` 必须指向有效的任务
+pub unsafe fn sigtimedwait_task(
+    task: *mut crate::process::task::Task,
+    set: u64,
+    deadline: Option<u64>,
+    mut now: impl FnMut() -> u64,
+    mut sleep: impl FnMut(),
+) -> Result<SigInfo, i32> {
+    use crate::errno::Errno;
+
+    loop {
+        let pending = (*task).pending.get_all();
+        let ready = pending & set;
+        if ready != 0 {
+            let sig = ready.trailing_zeros() as i32 + 1;
+            (*task).pending.remove(sig);
+            return Ok(SigInfo::new(sig, si_code::SI_USER, 0, 0));
+        }
+        if deadline.is_some_and(|d| now() >= d) {
+            return Err(Errno::TryAgain.as_neg_i32());
+        }
+        if pending & !set & !(*task).sigmask != 0 {
+            return Err(Errno::InterruptedSystemCall.as_neg_i32());
+        }
+        sleep();
+    }
+}
+
 /// 唤醒进程并设置状态（用于信号唤醒）
 ///
 ///
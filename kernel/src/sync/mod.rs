@@ -14,5 +14,9 @@
 
 pub mod semaphore;
 pub mod condvar;
+pub mod seqlock;
+pub mod mpsc;
 
 pub use semaphore::Mutex;
+pub use seqlock::SeqLock;
+pub use mpsc::MpscQueue;
@@ -0,0 +1,152 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+//! 有界多生产者单消费者队列
+//!
+//! 参考：
+//! - Dmitry Vyukov, "Bounded MPMC queue"
+//! - `include/linux/llist.h` - 中断上下文中无锁提交、由目标 CPU 取出的用法
+//!
+//! 核心概念：
+//! - 每个槽位带一个序列号：等于写位置时槽位空闲，等于写位置 + 1 时数据可读
+//! - 生产者用 CAS 抢占写位置，不加锁、不睡眠，可以在中断上下文中调用
+//! - 队列满时 `try_push` 把数据放在 `Full` 中退回，由调用者决定重试或丢弃
+//! - 同一生产者的数据按写入顺序出队
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// 队列已满，`try_push` 退回的数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Full<T>(pub T);
+
+struct Slot<T> {
+    /// 槽位序列号
+    seq: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// 容量为 `N` 的多生产者单消费者队列
+pub struct MpscQueue<T, const N: usize> {
+    slots: [Slot<T>; N],
+    /// 下一个出队位置
+    head: AtomicUsize,
+    /// 下一个入队位置
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for MpscQueue<T, N> {}
+unsafe impl<T: Send, const N: usize> Send for MpscQueue<T, N> {}
+
+impl<T, const N: usize> MpscQueue<T, N> {
+    /// 创建空队列（可用于静态变量）
+    pub const fn new() -> Self {
+        assert!(N > 0, "MpscQueue capacity must be non-zero");
+        let mut slots = [const {
+            Slot { seq: AtomicUsize::new(0), value: UnsafeCell::new(MaybeUninit::uninit()) }
+        }; N];
+        let mut i = 0;
+        while i < N {
+            slots[i].seq = AtomicUsize::new(i);
+            i += 1;
+        }
+        Self {
+            slots,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// 容量
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// 当前元素个数（并发入队时只是近似值）
+    pub fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        tail.wrapping_sub(head).min(N)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 入队，可以在中断上下文中由多个 CPU 同时调用
+    ///
+    /// # 返回
+    /// 队列已满时返回 `Err(Full(value))`
+    pub fn try_push(&self, value: T) -> Result<(), Full<T>> {
+        let mut pos = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos % N];
+            let seq = slot.seq.load(Ordering::Acquire);
+            let diff = seq.wrapping_sub(pos) as isize;
+            if diff == 0 {
+                // 槽位空闲，抢占写位置
+                match self.tail.compare_exchange_weak(
+                    pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.seq.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                // 槽位中还是上一轮未取走的数据
+                return Err(Full(value));
+            } else {
+                // 其他生产者已经抢占了这个位置
+                pos = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// 出队
+    ///
+    /// 设计上只有一个消费者（如 IPI 的目标 CPU）；
+    /// 出队位置同样用 CAS 更新，误用为多个消费者时也不会重复取出同一元素
+    pub fn pop(&self) -> Option<T> {
+        let mut pos = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[pos % N];
+            let seq = slot.seq.load(Ordering::Acquire);
+            let diff = seq.wrapping_sub(pos.wrapping_add(1)) as isize;
+            if diff == 0 {
+                match self.head.compare_exchange_weak(
+                    pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let value = unsafe { (*slot.value.get()).assume_init_read() };
+                        // 槽位留给下一轮的生产者
+                        slot.seq.store(pos.wrapping_add(N), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                // 槽位尚未写入：队列为空
+                return None;
+            } else {
+                pos = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Default for MpscQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for MpscQueue<T, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
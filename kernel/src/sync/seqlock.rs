@@ -0,0 +1,132 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+//! 顺序锁 (Sequence Lock) 机制
+//!
+//! 完全...
+//! - `include/linux/seqlock.h` - seqlock_t / seqcount_t
+//! - `kernel/time/timekeeping.c` - 时间读取路径
+//!
+//! 核心概念：
+//! - 写者在更新前后各递增一次序列号（更新期间序列号为奇数）
+//! - 读者记录开始时的序列号，读完后检查序列号是否变化，变化则重试
+//! - 读者不加锁、不阻塞写者，适合读多写少的小数据（jiffies、墙上时间）
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{fence, AtomicUsize, Ordering};
+use spin::Mutex;
+
+/// 顺序锁
+///
+/// 对应 Linux 的 `seqlock_t`：一个序列计数器加一把写者自旋锁。
+///
+/// 受保护的数据必须是 `Copy` 的，因为读者可能读到正在被修改的中间状态，
+/// 只有在 `read_seqretry` 返回 false 之后读到的副本才是一致的。
+pub struct SeqLock<T: Copy> {
+    /// 序列号（奇数表示写者正在更新）
+    sequence: AtomicUsize,
+    /// 写者互斥锁（多个写者之间串行化）
+    lock: Mutex<()>,
+    /// 受保护的数据
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Copy + Send> Sync for SeqLock<T> {}
+unsafe impl<T: Copy + Send> Send for SeqLock<T> {}
+
+impl<T: Copy> SeqLock<T> {
+    /// 创建新的顺序锁
+    pub const fn new(data: T) -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// 开始一次读操作
+    ///
+    /// 对应 Linux `read_seqbegin()`：等待正在进行的写操作完成，返回当前序列号
+    #[inline]
+    pub fn read_seqbegin(&self) -> usize {
+        loop {
+            let seq = self.sequence.load(Ordering::Acquire);
+            if seq & 1 == 0 {
+                return seq;
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// 检查读操作是否需要重试
+    ///
+    /// 对应 Linux `read_seqretry()`：序列号在读期间发生变化则返回 true
+    #[inline]
+    pub fn read_seqretry(&self, start: usize) -> bool {
+        fence(Ordering::Acquire);
+        self.sequence.load(Ordering::Relaxed) != start
+    }
+
+    /// 读取一致的数据副本
+    ///
+    /// 无锁读取，写者在读期间更新时自动重试
+    pub fn read(&self) -> T {
+        loop {
+            let seq = self.read_seqbegin();
+            let value = unsafe { core::ptr::read_volatile(self.data.get()) };
+            if !self.read_seqretry(seq) {
+                return value;
+            }
+        }
+    }
+
+    /// 获取写锁
+    ///
+    /// 对应 Linux `write_seqlock()`：持有写者锁并将序列号变为奇数，
+    /// guard 释放时（`write_sequnlock()`）序列号再次递增为偶数
+    pub fn write_seqlock(&self) -> SeqLockWriteGuard<'_, T> {
+        let guard = self.lock.lock();
+        self.sequence.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        SeqLockWriteGuard {
+            seqlock: self,
+            _guard: guard,
+        }
+    }
+
+    /// 在写锁保护下更新数据
+    pub fn write<F: FnOnce(&mut T)>(&self, f: F) {
+        let mut guard = self.write_seqlock();
+        f(&mut guard);
+    }
+}
+
+/// 顺序锁写者 guard
+///
+/// 析构时结束写操作，使读者可以看到一致的新数据
+pub struct SeqLockWriteGuard<'a, T: Copy> {
+    seqlock: &'a SeqLock<T>,
+    _guard: spin::MutexGuard<'a, ()>,
+}
+
+impl<T: Copy> Deref for SeqLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.seqlock.data.get() }
+    }
+}
+
+impl<T: Copy> DerefMut for SeqLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.seqlock.data.get() }
+    }
+}
+
+impl<T: Copy> Drop for SeqLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.seqlock.sequence.fetch_add(1, Ordering::Release);
+    }
+}
@@ -0,0 +1,121 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：LRU 块缓存
+//
+// 测试内容：
+// 1. 同一个块第二次读取命中缓存，不再访问设备
+// 2. bwrite 只标记为脏，sync_device 写回并刷新设备
+// 3. 超出容量时淘汰最久未使用的块，被引用的块不会被淘汰
+// 4. 不同设备上相同块号的缓冲区互不影响
+
+use crate::println;
+use crate::drivers::blkdev::{GenDisk, ReqCmd, Request};
+use crate::fs::bio;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// 模拟设备收到的请求数
+static READS: AtomicUsize = AtomicUsize::new(0);
+static WRITES: AtomicUsize = AtomicUsize::new(0);
+static FLUSHES: AtomicUsize = AtomicUsize::new(0);
+
+/// 模拟设备：读出的数据第一个字节为块号，第二个字节为次设备号
+unsafe extern "C" fn mock_request(req: &mut Request) {
+    match req.cmd_type {
+        ReqCmd::Read => {
+            READS.fetch_add(1, Ordering::Relaxed);
+            req.buffer[0] = (req.sector / 8) as u8;
+            req.buffer[1] = (*req.device).first_minor as u8;
+        }
+        ReqCmd::Write => {
+            WRITES.fetch_add(1, Ordering::Relaxed);
+        }
+        ReqCmd::Flush => {
+            FLUSHES.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn mock_disk(minor: u32) -> GenDisk {
+    let mut disk = GenDisk::new("mockcache", 251, 1, 4096, None);
+    disk.first_minor = minor;
+    disk.set_capacity(1 << 20);
+    disk.set_request_fn(mock_request);
+    disk
+}
+
+pub fn test_block_cache() {
+    println!("test: ===== Testing block cache =====");
+
+    let disk = mock_disk(0);
+    let dev = &disk as *const GenDisk;
+    let reads = || READS.load(Ordering::Relaxed);
+
+    // 测试 1: 命中
+    println!("test: 1. Testing repeated reads hit the cache...");
+    let before = reads();
+    let bh = bio::bread(dev, 5).expect("first read");
+    assert_eq!(reads(), before + 1, "First read goes to the device");
+    unsafe { assert_eq!((&(*bh).b_data)[0], 5); }
+    bio::brelse(bh);
+    let again = bio::bread(dev, 5).expect("second read");
+    assert_eq!(reads(), before + 1, "Second read is served from the cache");
+    assert_eq!(again, bh, "Same buffer returned");
+    bio::brelse(again);
+    println!("test:    SUCCESS - second read hit the cache");
+
+    // 测试 2: 延迟写回
+    println!("test: 2. Testing delayed writeback...");
+    let writes = WRITES.load(Ordering::Relaxed);
+    let flushes = FLUSHES.load(Ordering::Relaxed);
+    let bh = bio::bread(dev, 5).expect("cached block");
+    unsafe { (&mut (*bh).b_data)[100] = 0xAB; }
+    bio::bwrite(bh);
+    bio::brelse(bh);
+    unsafe { assert!((*bh).is_dirty(), "bwrite marks the buffer dirty"); }
+    assert_eq!(WRITES.load(Ordering::Relaxed), writes, "bwrite does no I/O");
+    assert_eq!(bio::sync_device(dev), Ok(()));
+    assert_eq!(WRITES.load(Ordering::Relaxed), writes + 1, "sync_device writes the dirty block");
+    assert_eq!(FLUSHES.load(Ordering::Relaxed), flushes + 1, "sync_device flushes the device");
+    unsafe { assert!(!(*bh).is_dirty(), "Buffer clean after writeback"); }
+    println!("test:    SUCCESS - dirty block written by sync_device");
+
+    // 测试 3: LRU 淘汰
+    println!("test: 3. Testing LRU eviction...");
+    let pinned = bio::bread(dev, 1000).expect("pinned block");
+    for block in 0..80u64 {
+        let bh = bio::bread(dev, 2000 + block).expect("filler block");
+        bio::brelse(bh);
+    }
+    let before = reads();
+    let bh = bio::bread(dev, 5).expect("evicted block");
+    assert_eq!(reads(), before + 1, "Least recently used block was evicted");
+    bio::brelse(bh);
+    let bh = bio::bread(dev, 1000).expect("pinned block");
+    assert_eq!(reads(), before + 1, "Referenced block stays cached");
+    assert_eq!(bh, pinned);
+    bio::brelse(bh);
+    bio::brelse(pinned);
+    println!("test:    SUCCESS - LRU block evicted, pinned block kept");
+
+    // 测试 4: 按设备区分
+    println!("test: 4. Testing blocks on different devices...");
+    let other = mock_disk(1);
+    let other_dev = &other as *const GenDisk;
+    let before = reads();
+    let a = bio::bread(dev, 7).expect("block on disk 0");
+    let b = bio::bread(other_dev, 7).expect("block on disk 1");
+    assert_eq!(reads(), before + 2, "Same block number on another device is a miss");
+    unsafe { assert_eq!(((&(*a).b_data)[1], (&(*b).b_data)[1]), (0, 1)); }
+    bio::brelse(a);
+    bio::brelse(b);
+    println!("test:    SUCCESS - cache keyed by device and block");
+
+    // 模拟设备即将释放，丢弃它们的缓冲区
+    bio::invalidate_device(dev);
+    bio::invalidate_device(other_dev);
+
+    println!("test: ===== Block Cache Testing Completed =====");
+}
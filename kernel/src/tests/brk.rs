@@ -0,0 +1,74 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：brk 堆增长
+//
+// 测试内容：
+// 1. brk(0) 返回页对齐的堆起始地址
+// 2. 增长后返回请求的地址，新页已映射、清零且可写
+// 3. 缩小后解除映射，再增长得到清零的页，低于堆起始地址的请求被忽略
+
+use crate::println;
+use crate::arch::riscv64::mm::{create_user_address_space, user_virt_to_phys, AddressSpace};
+use crate::arch::riscv64::syscall::do_brk;
+use crate::mm::page::PAGE_SIZE;
+use crate::process::task::{SchedPolicy, Task};
+use alloc::boxed::Box;
+
+pub fn test_brk() {
+    println!("test: ===== Testing brk heap growth =====");
+
+    let Some(root_ppn) = create_user_address_space() else {
+        println!("test: No user memory - skipping");
+        return;
+    };
+    let mut task = Box::new(Task::new(3201, SchedPolicy::Normal));
+    task.set_address_space(Some(unsafe { AddressSpace::new(root_ppn) }));
+
+    // 测试 1: 初始堆顶
+    println!("test: 1. Testing initial break...");
+    let start = do_brk(&task, 0);
+    assert_ne!(start, 0, "Initial break must be set");
+    assert_eq!(start % PAGE_SIZE as u64, 0, "Initial break is page aligned");
+    assert_eq!(do_brk(&task, 0), start, "brk(0) does not move the break");
+    println!("test:    SUCCESS - initial break {:#x}", start);
+
+    // 测试 2: 增长得到可写内存
+    println!("test: 2. Testing growth yields writable memory...");
+    let top = start + 2 * PAGE_SIZE as u64 + 100;
+    assert_eq!(do_brk(&task, top), top, "Break moves to the exact requested address");
+    for page in 0..3u64 {
+        let virt = start + page * PAGE_SIZE as u64;
+        let phys = unsafe { user_virt_to_phys(root_ppn, virt) }.expect("heap page mapped");
+        let ptr = phys as *mut u64;
+        unsafe {
+            assert_eq!(ptr.read_volatile(), 0, "New heap pages are zero-filled");
+            ptr.write_volatile(0xC0FFEE00 + page);
+            assert_eq!(ptr.read_volatile(), 0xC0FFEE00 + page, "Heap page is writable");
+        }
+    }
+    assert!(unsafe { user_virt_to_phys(root_ppn, start + 3 * PAGE_SIZE as u64) }.is_none(),
+            "Pages past the break stay unmapped");
+    println!("test:    SUCCESS - grown heap is mapped and writable");
+
+    // 测试 3: 缩小与重新增长
+    println!("test: 3. Testing shrink and regrow...");
+    assert_eq!(do_brk(&task, start + 8), start + 8, "Shrinking returns the new break");
+    assert!(unsafe { user_virt_to_phys(root_ppn, start) }.is_some(), "Page holding the break stays mapped");
+    for page in 1..3u64 {
+        assert!(unsafe { user_virt_to_phys(root_ppn, start + page * PAGE_SIZE as u64) }.is_none(),
+                "Pages above the new break are unmapped");
+    }
+    assert_eq!(do_brk(&task, start - PAGE_SIZE as u64), start + 8, "Break cannot go below the heap start");
+    assert_eq!(do_brk(&task, top), top);
+    for page in 1..3u64 {
+        let virt = start + page * PAGE_SIZE as u64;
+        let phys = unsafe { user_virt_to_phys(root_ppn, virt) }.expect("regrown heap page mapped");
+        assert_eq!(unsafe { (phys as *const u64).read_volatile() }, 0, "Regrown heap pages read zero");
+    }
+    println!("test:    SUCCESS - shrink frees pages, regrowth is zero-filled");
+
+    println!("test: ===== brk heap growth Testing Completed =====");
+}
@@ -0,0 +1,91 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：CLONE_FILES 共享文件描述符表
+//
+// 测试内容：
+// 1. CLONE_FILES 共享同一个表，线程打开的 fd 在兄弟线程中可见，关闭也同步
+// 2. 不带 CLONE_FILES（fork）时子进程得到独立的表
+// 3. 任务释放 fd 表只减少引用计数，最后一个引用释放时才关闭文件
+// 4. 不支持的 clone 标志、新栈地址和退出信号返回 EINVAL
+
+use crate::println;
+use crate::fs::create_pipe;
+use crate::fs::file::FdTable;
+use crate::errno::Errno;
+use crate::process::fork::{check_clone_args, copy_files, CLONE_FILES};
+use crate::signal::Signal;
+use crate::process::task::{SchedPolicy, Task};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+
+pub fn test_clone_files() {
+    println!("test: ===== Testing CLONE_FILES fd table sharing =====");
+
+    let parent = Arc::new(FdTable::new());
+    crate::init::init_std_fds_for_task(&parent);
+
+    // 测试 1: CLONE_FILES 共享
+    println!("test: 1. Testing CLONE_FILES shares the fd table...");
+    let thread = copy_files(Some(&parent), CLONE_FILES);
+    assert!(Arc::ptr_eq(&parent, &thread), "Thread uses the same table");
+    assert_eq!(Arc::strong_count(&parent), 2, "Sharing only adds a reference");
+
+    let (read_end, _write_end) = create_pipe();
+    let fd = thread.alloc_fd_below(64).expect("fd in thread");
+    assert!(thread.install_fd(fd, read_end.clone()).is_ok());
+    let seen = parent.get_file(fd).expect("fd opened by the thread is visible in the sibling");
+    assert!(Arc::ptr_eq(&seen, &read_end));
+    drop(seen);
+    assert!(parent.close_fd(fd).is_ok());
+    assert!(thread.get_file(fd).is_none(), "Close is visible in the sibling");
+    println!("test:    SUCCESS - open and close shared between threads");
+
+    // 测试 2: fork 复制
+    println!("test: 2. Testing fork copies the fd table...");
+    let child = copy_files(Some(&parent), 0);
+    assert!(!Arc::ptr_eq(&parent, &child), "Forked child has its own table");
+    assert!(child.get_file(0).is_some(), "Child inherits existing fds");
+    let fd = child.alloc_fd_below(64).expect("fd in child");
+    assert!(child.install_fd(fd, read_end.clone()).is_ok());
+    assert!(parent.get_file(fd).is_none(), "Child's open is not visible in the parent");
+    assert!(child.close_fd(0).is_ok());
+    assert!(parent.get_file(0).is_some(), "Child's close does not affect the parent");
+    drop(child);
+    println!("test:    SUCCESS - forked fd table is independent");
+
+    // 测试 3: 引用计数释放
+    println!("test: 3. Testing the table is freed with the last reference...");
+    let mut task = Box::new(Task::new(3401, SchedPolicy::Normal));
+    task.set_fdtable(Some(Arc::clone(&parent)));
+    assert_eq!(Arc::strong_count(&parent), 3, "Task holds a reference");
+    let fd = parent.alloc_fd_below(64).expect("fd in shared table");
+    assert!(parent.install_fd(fd, read_end.clone()).is_ok());
+
+    task.set_fdtable(None);
+    drop(thread);
+    assert_eq!(Arc::strong_count(&parent), 1, "Exiting sharers drop their references");
+    assert!(parent.get_file(fd).is_some(), "Files stay open while a sharer remains");
+    assert_eq!(Arc::strong_count(&read_end), 2, "Table still holds the pipe");
+    drop(parent);
+    assert_eq!(Arc::strong_count(&read_end), 1, "Last reference closes the table's files");
+    println!("test:    SUCCESS - fd table freed when the last sharer drops it");
+
+    // 测试 4: 拒绝不支持的参数
+    println!("test: 4. Testing unsupported clone arguments...");
+    let sigchld = Signal::SIGCHLD as u64;
+    assert_eq!(check_clone_args(sigchld, 0), Ok(()), "fork");
+    assert_eq!(check_clone_args(CLONE_FILES | sigchld, 0), Ok(()));
+    let einval = Err(Errno::InvalidArgument.as_neg_i32());
+    // CLONE_VM、CLONE_THREAD、CLONE_SETTLS、CLONE_PARENT_SETTID、CLONE_CHILD_CLEARTID
+    for flag in [0x100, 0x10000, 0x80000, 0x100000, 0x200000] {
+        assert_eq!(check_clone_args(flag | sigchld, 0), einval, "Unsupported flag {:#x}", flag);
+    }
+    assert_eq!(check_clone_args(sigchld, 0x7fff_0000), einval, "New stack");
+    assert_eq!(check_clone_args(Signal::SIGUSR1 as u64, 0), einval, "Exit signal other than SIGCHLD");
+    println!("test:    SUCCESS - unsupported arguments rejected with EINVAL");
+
+    println!("test: ===== CLONE_FILES Testing Completed =====");
+}
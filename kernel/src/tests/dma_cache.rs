@@ -0,0 +1,100 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：DMA 缓存维护
+//
+// 测试内容（使用记录操作的模拟缓存，模拟 aarch64 64 字节缓存行）：
+// 1. 设备读取方向 (ToDevice) 对每个缓存行执行清理，最后执行屏障
+// 2. 设备写入方向 (FromDevice) 对每个缓存行执行无效化
+// 3. 未对齐的范围覆盖首尾缓存行
+// 4. 一致性架构（行大小为 0）只执行屏障；空范围不执行任何操作
+
+use crate::println;
+use crate::mm::dma::{flush_for_device, invalidate_after_device, sync_range, CacheMaintenance, DmaDirection};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Clean(usize),
+    Invalidate(usize),
+    Barrier,
+}
+
+/// 记录发出的缓存维护操作
+struct MockCache {
+    line: usize,
+    ops: RefCell<Vec<Op>>,
+}
+
+impl MockCache {
+    fn new(line: usize) -> Self {
+        Self { line, ops: RefCell::new(Vec::new()) }
+    }
+
+    fn take(&self) -> Vec<Op> {
+        core::mem::take(&mut *self.ops.borrow_mut())
+    }
+}
+
+impl CacheMaintenance for MockCache {
+    fn line_size(&self) -> usize {
+        self.line
+    }
+
+    fn clean_line(&self, addr: usize) {
+        self.ops.borrow_mut().push(Op::Clean(addr));
+    }
+
+    fn invalidate_line(&self, addr: usize) {
+        self.ops.borrow_mut().push(Op::Invalidate(addr));
+    }
+
+    fn barrier(&self) {
+        self.ops.borrow_mut().push(Op::Barrier);
+    }
+}
+
+pub fn test_dma_cache() {
+    println!("test: ===== Testing DMA cache maintenance =====");
+    let cache = MockCache::new(64);
+
+    // 测试 1: ToDevice -> 清理
+    println!("test: 1. Testing flush for device cleans each line...");
+    sync_range(&cache, 0x1000, 128, DmaDirection::ToDevice);
+    assert_eq!(cache.take(), vec![Op::Clean(0x1000), Op::Clean(0x1040), Op::Barrier]);
+    println!("test:    SUCCESS - dc cvac per line, then dsb");
+
+    // 测试 2: FromDevice -> 无效化
+    println!("test: 2. Testing invalidate after device...");
+    sync_range(&cache, 0x2000, 64, DmaDirection::FromDevice);
+    assert_eq!(cache.take(), vec![Op::Invalidate(0x2000), Op::Barrier]);
+    println!("test:    SUCCESS - dc ivac per line, then dsb");
+
+    // 测试 3: 未对齐范围
+    println!("test: 3. Testing unaligned range covers partial lines...");
+    sync_range(&cache, 0x1030, 0x20, DmaDirection::ToDevice);
+    assert_eq!(cache.take(), vec![Op::Clean(0x1000), Op::Clean(0x1040), Op::Barrier],
+               "Range straddling a line boundary must touch both lines");
+    sync_range(&cache, 0x103F, 1, DmaDirection::FromDevice);
+    assert_eq!(cache.take(), vec![Op::Invalidate(0x1000), Op::Barrier]);
+    println!("test:    SUCCESS - first and last partial lines maintained");
+
+    // 测试 4: 一致性架构与空范围
+    println!("test: 4. Testing coherent cache and empty range...");
+    let coherent = MockCache::new(0);
+    sync_range(&coherent, 0x1000, 4096, DmaDirection::ToDevice);
+    assert_eq!(coherent.take(), vec![Op::Barrier], "Coherent DMA only needs a fence");
+    sync_range(&cache, 0x1000, 0, DmaDirection::ToDevice);
+    assert!(cache.take().is_empty(), "Empty range emits nothing");
+    // 当前架构的实现可以直接调用
+    let buf = [0u8; 256];
+    flush_for_device(buf.as_ptr(), buf.len());
+    invalidate_after_device(buf.as_ptr(), buf.len());
+    println!("test:    SUCCESS - coherent path is a barrier only");
+
+    println!("test: ===== DMA cache Testing Completed =====");
+}
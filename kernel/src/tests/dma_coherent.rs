@@ -0,0 +1,77 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：DMA 一致性内存分配
+//
+// 测试内容：
+// 1. 分配的缓冲区页对齐并已清零
+// 2. 缓冲区物理连续，物理地址与 virt_to_phys 一致
+// 3. 更大的对齐要求被满足，非法参数被拒绝
+// 4. VirtIO 块请求缓冲区的物理地址布局
+// 5. 缓冲区在物理地址登记表中登记为 DMA 区域，释放后注销
+
+use crate::println;
+use crate::drivers::virtio::queue::{req_type, BlkReqBuffer, VirtIOBlkReqHeader};
+use crate::mm::dma::{alloc_coherent, free_coherent, virt_to_phys};
+use crate::mm::page::PAGE_SIZE;
+use crate::mm::{lookup_region, RegionKind};
+
+pub fn test_dma_coherent() {
+    println!("test: ===== Testing DMA coherent allocation =====");
+
+    // 测试 1: 页对齐并清零
+    println!("test: 1. Testing allocation is page-aligned and zeroed...");
+    let (virt, phys) = alloc_coherent(100, 16).expect("alloc_coherent failed");
+    assert_eq!(virt as usize % PAGE_SIZE, 0, "Virtual address must be page-aligned");
+    assert_eq!(phys as usize % PAGE_SIZE, 0, "Physical address must be page-aligned");
+    let bytes = unsafe { core::slice::from_raw_parts(virt, PAGE_SIZE) };
+    assert!(bytes.iter().all(|&b| b == 0), "Coherent buffer must be zeroed");
+    unsafe { free_coherent(virt, 100, 16) };
+    println!("test:    SUCCESS - page-aligned, zeroed buffer");
+
+    // 测试 2: 物理连续
+    println!("test: 2. Testing multi-page buffer is physically contiguous...");
+    let size = 3 * PAGE_SIZE + 1;
+    let (virt, phys) = alloc_coherent(size, PAGE_SIZE).expect("alloc_coherent failed");
+    assert_eq!(phys, virt_to_phys(virt as usize), "phys must match virt_to_phys");
+    for page in 0..4 {
+        let off = page * PAGE_SIZE;
+        assert_eq!(virt_to_phys(virt as usize + off), phys + off as u64,
+                   "Each page must follow the previous one physically");
+    }
+    unsafe { free_coherent(virt, size, PAGE_SIZE) };
+    println!("test:    SUCCESS - 4 pages physically contiguous");
+
+    // 测试 3: 对齐与非法参数
+    println!("test: 3. Testing alignment and invalid arguments...");
+    let (virt, phys) = alloc_coherent(PAGE_SIZE, 4 * PAGE_SIZE).expect("alloc_coherent failed");
+    assert_eq!(virt as usize % (4 * PAGE_SIZE), 0, "Larger alignment must be honoured");
+    assert_eq!(phys, virt_to_phys(virt as usize));
+    unsafe { free_coherent(virt, PAGE_SIZE, 4 * PAGE_SIZE) };
+    assert!(alloc_coherent(0, PAGE_SIZE).is_none(), "Zero size must fail");
+    assert!(alloc_coherent(PAGE_SIZE, 3).is_none(), "Non power-of-two alignment must fail");
+    println!("test:    SUCCESS - alignment honoured, invalid requests rejected");
+
+    // 测试 4: 块请求缓冲区
+    println!("test: 4. Testing VirtIO block request buffer...");
+    let req = BlkReqBuffer::new(req_type::VIRTIO_BLK_T_IN, 42).expect("BlkReqBuffer failed");
+    assert_eq!(req.header_phys() as usize % PAGE_SIZE, 0, "Request header must be page-aligned");
+    assert_eq!(req.resp_phys(), req.header_phys() + core::mem::size_of::<VirtIOBlkReqHeader>() as u64);
+    assert_eq!(req.status(), 0xFF, "Response status starts invalid");
+    drop(req);
+    println!("test:    SUCCESS - header and status share one coherent buffer");
+
+    // 测试 5: 区域登记
+    println!("test: 5. Testing DMA region registration...");
+    let size = 2 * PAGE_SIZE;
+    let (virt, phys) = alloc_coherent(size, PAGE_SIZE).expect("alloc_coherent failed");
+    let region = lookup_region(phys as usize + PAGE_SIZE).expect("buffer registered");
+    assert_eq!((region.base, region.size, region.kind), (phys as usize, size, RegionKind::Dma));
+    unsafe { free_coherent(virt, size, PAGE_SIZE) };
+    assert_ne!(lookup_region(phys as usize).map(|r| r.kind), Some(RegionKind::Dma), "Region released with the buffer");
+    println!("test:    SUCCESS - DMA buffer registered at {:#x}", phys);
+
+    println!("test: ===== DMA coherent Testing Completed =====");
+}
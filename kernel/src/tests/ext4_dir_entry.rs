@@ -0,0 +1,203 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：ext4 目录项创建
+//
+// 测试内容：
+// 1. 新目录块中只有一个空闲目录项时直接复用
+// 2. 之后的目录项从上一项 rec_len 的空余部分拆分，rec_len 按 4 字节对齐
+// 3. 新建的目录项可以重新查找到；重名返回 EEXIST
+// 4. 目录块放满后从分配器申请新块，目录大小写回 inode
+// 5. create 分配 inode 创建文件和目录（含 "." 和 ".."），link 增加链接数
+// 6. 块末尾的校验和项不被复用；extent 目录的新块追加到 extent 叶子，物理相邻时延长上一个 extent；
+//    带元数据校验和的文件系统拒绝添加目录项
+
+use crate::println;
+use crate::drivers::blkdev::GenDisk;
+use crate::fs::bio;
+use crate::fs::ext4::dir::{self, file_type};
+use crate::fs::ext4::extent::{get_extent_header, Ext4Extent, Ext4ExtentHeader};
+use crate::fs::ext4::EXT4_FEATURE_RO_COMPAT_METADATA_CSUM;
+use crate::errno::Errno;
+use super::ext4_write_inode::{new_inode, ram_bytes, ram_ext4, ALLOC_START, FIRST_DATA_BLOCK, FIRST_FREE_INODE};
+use alloc::format;
+use alloc::vec;
+
+/// 读取模拟磁盘中 `block` 块内 `offset` 处目录项的 (inode, rec_len, name_len)
+fn raw_entry(block: u64, offset: usize) -> (u32, u16, u8) {
+    ram_bytes(|ram| {
+        let e = &ram[block as usize * 4096 + offset..];
+        (
+            u32::from_le_bytes([e[0], e[1], e[2], e[3]]),
+            u16::from_le_bytes([e[4], e[5]]),
+            e[6],
+        )
+    })
+}
+
+pub fn test_ext4_dir_entry() {
+    println!("test: ===== Testing ext4 directory entry creation =====");
+
+    let (disk, mut fs) = ram_ext4();
+    let dev = &*disk as *const GenDisk;
+
+    // 新目录：一个块，整块是一个空闲目录项
+    let dir_block = FIRST_DATA_BLOCK;
+    let mut fresh = vec![0u8; 4096];
+    dir::write_dir_entry(&mut fresh, 0, 4096, b"", 0);
+    assert_eq!(fs.write_block(dir_block, 0, &fresh), Ok(()));
+    let mut dir_inode = new_inode(13, 0o040755, 4096, &[dir_block as u32]);
+    assert_eq!(fs.write_inode(13, &dir_inode), Ok(()));
+
+    // 测试 1: 复用空闲目录项
+    println!("test: 1. Testing reuse of an empty record...");
+    assert_eq!(dir::ext4_dir_rec_len(1), 12);
+    assert_eq!(dir::ext4_dir_rec_len(4), 12);
+    assert_eq!(dir::ext4_dir_rec_len(5), 16);
+    assert_eq!(fs.add_dir_entry(&mut dir_inode, "a", 20, file_type::EXT4_FT_REG_FILE), Ok(()));
+    assert_eq!(raw_entry(dir_block, 0), (20, 4096, 1), "Empty record reused whole");
+    println!("test:    SUCCESS - first entry fills the empty record");
+
+    // 测试 2: 拆分 rec_len
+    println!("test: 2. Testing rec_len split...");
+    assert_eq!(fs.add_dir_entry(&mut dir_inode, "hello.txt", 21, file_type::EXT4_FT_REG_FILE), Ok(()));
+    assert_eq!(fs.add_dir_entry(&mut dir_inode, "sub", 22, file_type::EXT4_FT_DIR), Ok(()));
+    assert_eq!(raw_entry(dir_block, 0), (20, 12, 1), "\"a\" shrunk to 8+1 rounded up to 12");
+    assert_eq!(raw_entry(dir_block, 12), (21, 20, 9), "\"hello.txt\" shrunk to 8+9 rounded up to 20");
+    assert_eq!(raw_entry(dir_block, 32), (22, 4096 - 32, 3), "Last entry owns the rest of the block");
+    println!("test:    SUCCESS - records split at 4-byte aligned offsets");
+
+    // 测试 3: 重新查找
+    println!("test: 3. Testing lookup of new entries...");
+    bio::invalidate_device(dev);
+    let dir_inode_disk = fs.read_inode(13).expect("re-read directory");
+    for (name, ino) in [("a", 20), ("hello.txt", 21), ("sub", 22)] {
+        let entry = fs.lookup(&dir_inode_disk, name).expect("entry found");
+        assert_eq!(entry.inode, ino);
+    }
+    assert!(fs.lookup(&dir_inode_disk, "sub").unwrap().is_dir());
+    assert_eq!(fs.list_dir(&dir_inode_disk).map(|v| v.len()), Ok(3));
+    assert_eq!(
+        fs.add_dir_entry(&mut dir_inode, "hello.txt", 30, file_type::EXT4_FT_REG_FILE),
+        Err(Errno::FileExists.as_neg_i32())
+    );
+    println!("test:    SUCCESS - all entries found, duplicate rejected");
+
+    // 测试 4: 申请新目录块
+    println!("test: 4. Testing directory growth...");
+    let mut count = 0;
+    while dir_inode.size == 4096 {
+        let name = format!("file{:04}", count);
+        assert_eq!(fs.add_dir_entry(&mut dir_inode, &name, 100 + count, file_type::EXT4_FT_REG_FILE), Ok(()));
+        count += 1;
+        assert!(count < 300, "Block must fill up");
+    }
+    assert_eq!(dir_inode.block[1], ALLOC_START as u32, "New block from the allocator");
+    assert_eq!(raw_entry(ALLOC_START, 0), (100 + count - 1, 4096, 8), "New block holds one entry");
+
+    bio::invalidate_device(dev);
+    let dir_inode_disk = fs.read_inode(13).expect("re-read directory");
+    assert_eq!(dir_inode_disk.size, 8192, "Directory size written back");
+    for i in [0, count / 2, count - 1] {
+        let name = format!("file{:04}", i);
+        assert_eq!(fs.lookup(&dir_inode_disk, &name).map(|e| e.inode), Ok(100 + i));
+    }
+    println!("test:    SUCCESS - {} entries, second block {}", count + 3, ALLOC_START);
+
+    // 测试 5: create 与 link
+    println!("test: 5. Testing create, mkdir and link...");
+    let root_block = FIRST_DATA_BLOCK + 1;
+    assert_eq!(fs.write_block(root_block, 0, &fresh), Ok(()));
+    let mut root = new_inode(2, 0o040755, 4096, &[root_block as u32]);
+    root.links_count = 2;
+    assert_eq!(fs.write_inode(2, &root), Ok(()));
+
+    let file_ino = fs.create("/notes.txt", 0o100644).expect("create file");
+    assert_eq!(file_ino, FIRST_FREE_INODE, "First free inode from the bitmap");
+    let dir_ino = fs.create("/docs", 0o040755).expect("create directory");
+    assert!(fs.create("/docs/a", 0o100600).is_ok());
+    assert_eq!(fs.create("/notes.txt", 0o100644), Err(Errno::FileExists.as_neg_i32()));
+    assert_eq!(fs.create("/missing/x", 0o100644), Err(Errno::NoSuchFileOrDirectory.as_neg_i32()));
+    assert_eq!(fs.link("/notes.txt", "/docs/b"), Ok(()));
+    assert_eq!(fs.link("/docs", "/docs2"), Err(Errno::OperationNotPermitted.as_neg_i32()));
+
+    bio::invalidate_device(dev);
+    let (ino, linked) = fs.lookup_path("/docs/b", true).expect("hard link resolves");
+    assert_eq!((ino, linked.links_count, linked.mode), (file_ino, 2, 0o100644));
+    let docs = fs.read_inode(dir_ino).expect("read new directory");
+    assert_eq!((docs.links_count, docs.size), (2, 4096));
+    assert_eq!(fs.lookup(&docs, ".").map(|e| e.inode), Ok(dir_ino));
+    assert_eq!(fs.lookup(&docs, "..").map(|e| e.inode), Ok(2));
+    assert_eq!(fs.read_inode(2).map(|r| r.links_count), Ok(3), "Parent gains a link for \"..\"");
+    println!("test:    SUCCESS - file {} and directory {} created", file_ino, dir_ino);
+
+    // 测试 6: 校验和项与 extent 目录
+    println!("test: 6. Testing checksum tail and extent directory growth...");
+    // 块中的目录项正好用到校验和项之前：15 个 255 字节名字的目录项加一个 116 字节名字的目录项
+    let ext_block = FIRST_DATA_BLOCK + 2;
+    let mut full = vec![0u8; 4096];
+    let mut offset = 0;
+    for i in 0..16u32 {
+        let name = format!("{:0>width$}", i, width = if i < 15 { 255 } else { 116 });
+        let rec_len = dir::ext4_dir_rec_len(name.len());
+        dir::write_dir_entry(&mut full[offset..], 200 + i, rec_len as u16, name.as_bytes(), file_type::EXT4_FT_REG_FILE);
+        offset += rec_len;
+    }
+    assert_eq!(offset, 4096 - dir::EXT4_DIR_TAIL_LEN);
+    dir::write_dir_entry(&mut full[offset..], 0, dir::EXT4_DIR_TAIL_LEN as u16, b"", file_type::EXT4_FT_DIR_CSUM);
+    assert!(dir::is_dir_csum_tail(&full[offset..]));
+    assert_eq!(fs.write_block(ext_block, 0, &full), Ok(()));
+
+    // i_block: depth 为 0 的 extent 头，一个 extent 把逻辑块 0 映射到 ext_block
+    let mut ext_dir = new_inode(14, 0o040755, 4096, &[]);
+    ext_dir.flags = 0x80000;
+    ext_dir.block[0] = 0xF30A | (1 << 16);
+    ext_dir.block[1] = 4;
+    ext_dir.block[3] = 0;
+    ext_dir.block[4] = 1;
+    ext_dir.block[5] = ext_block as u32;
+    assert_eq!(fs.write_inode(14, &ext_dir), Ok(()));
+
+    fs.feature_ro_compat = EXT4_FEATURE_RO_COMPAT_METADATA_CSUM;
+    assert_eq!(
+        fs.add_dir_entry(&mut ext_dir, "t", 300, file_type::EXT4_FT_REG_FILE),
+        Err(Errno::ReadOnlyFileSystem.as_neg_i32()),
+        "Directory block checksums are not maintained"
+    );
+    fs.feature_ro_compat = 0;
+
+    assert_eq!(fs.add_dir_entry(&mut ext_dir, "t", 300, file_type::EXT4_FT_REG_FILE), Ok(()));
+    let second = ext_dir.get_data_block(&fs, 1).expect("second block mapped");
+    assert_ne!(second, 0, "Full block grows the directory");
+    assert_eq!(raw_entry(ext_block, 4096 - dir::EXT4_DIR_TAIL_LEN), (0, 12, 0), "Checksum tail untouched");
+    assert_eq!(raw_entry(second, 0), (300, 4096, 1), "New entry in the new block");
+
+    let mut count = 0;
+    while ext_dir.size == 8192 {
+        let name = format!("ext{:04}", count);
+        assert_eq!(fs.add_dir_entry(&mut ext_dir, &name, 400 + count, file_type::EXT4_FT_REG_FILE), Ok(()));
+        count += 1;
+        assert!(count < 300, "Block must fill up");
+    }
+
+    bio::invalidate_device(dev);
+    let ext_dir_disk = fs.read_inode(14).expect("re-read extent directory");
+    assert_eq!(ext_dir_disk.size, 3 * 4096, "Directory size written back");
+    assert_eq!(ext_dir_disk.get_data_blocks(&fs), Ok(vec![ext_block, second, second + 1]));
+    let header: &Ext4ExtentHeader = get_extent_header(&ext_dir_disk.block);
+    assert_eq!((header.eh_entries, header.eh_depth), (2, 0), "Adjacent block extends the last extent");
+    let extents = unsafe {
+        core::slice::from_raw_parts(ext_dir_disk.block.as_ptr().add(3) as *const Ext4Extent, 2)
+    };
+    assert_eq!((extents[1].ee_block, extents[1].ee_len, extents[1].start_block()), (1, 2, second));
+    assert_eq!(fs.lookup(&ext_dir_disk, "t").map(|e| e.inode), Ok(300));
+    let name = format!("ext{:04}", count - 1);
+    assert_eq!(fs.lookup(&ext_dir_disk, &name).map(|e| e.inode), Ok(400 + count - 1));
+    println!("test:    SUCCESS - extent directory grew to blocks {} and {}", second, second + 1);
+
+    bio::invalidate_device(dev);
+
+    println!("test: ===== ext4 Directory Entry Testing Completed =====");
+}
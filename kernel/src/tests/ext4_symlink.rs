@@ -0,0 +1,137 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：ext4 符号链接
+//
+// 测试内容：
+// 1. read_symlink 读取存放在 i_block 中的快速符号链接和存放在数据块中的慢速符号链接
+// 2. lookup_path 解析相对目标（相对链接所在目录）和绝对目标，包括路径中间的链接
+// 3. 链接成环时返回 ELOOP，链接指向不存在的文件返回 ENOENT
+// 4. follow_last 为 false 时返回最后一个分量的链接本身，中间分量的链接仍然跟随
+
+use crate::println;
+use crate::drivers::blkdev::GenDisk;
+use crate::errno::Errno;
+use crate::fs::bio;
+use crate::fs::ext4::dir::{self, file_type};
+use crate::fs::ext4::inode::Ext4Inode;
+use crate::fs::ext4::{file, Ext4FileSystem};
+use super::ext4_write_inode::{new_inode, ram_ext4, FIRST_DATA_BLOCK};
+use alloc::vec;
+
+/// 文件内容
+const HOSTS: &[u8] = b"127.0.0.1 localhost\n";
+
+/// 构造一个快速符号链接，目标放在 i_block 中
+fn fast_symlink(ino: u32, target: &str) -> Ext4Inode {
+    let mut inode = new_inode(ino, 0o120777, target.len() as u64, &[]);
+    let mut inline = [0u8; 60];
+    inline[..target.len()].copy_from_slice(target.as_bytes());
+    for (i, word) in inode.block.iter_mut().enumerate() {
+        *word = u32::from_le_bytes([inline[i * 4], inline[i * 4 + 1], inline[i * 4 + 2], inline[i * 4 + 3]]);
+    }
+    inode
+}
+
+/// 在 `block` 创建一个空目录并写入 inode
+fn make_dir(fs: &Ext4FileSystem, ino: u32, block: u64) -> Ext4Inode {
+    let mut fresh = vec![0u8; 4096];
+    dir::write_dir_entry(&mut fresh, 0, 4096, b"", 0);
+    assert_eq!(fs.write_block(block, 0, &fresh), Ok(()));
+    let inode = new_inode(ino, 0o040755, 4096, &[block as u32]);
+    assert_eq!(fs.write_inode(ino, &inode), Ok(()));
+    inode
+}
+
+pub fn test_ext4_symlink() {
+    println!("test: ===== Testing ext4 symlinks =====");
+
+    let (disk, fs) = ram_ext4();
+    let dev = &*disk as *const GenDisk;
+
+    // 目录树：
+    // /etc/hosts              常规文件
+    // /etc/self -> hosts      快速符号链接，相对目标
+    // /abs -> /etc/hosts      快速符号链接，绝对目标
+    // /conf -> etc            指向目录的链接
+    // /slow -> etc/hosts      目标存放在数据块中
+    // /loop -> loop           自己指向自己
+    // /dangling -> nowhere
+    let mut root = make_dir(&fs, 2, FIRST_DATA_BLOCK);
+    let mut etc = make_dir(&fs, 12, FIRST_DATA_BLOCK + 1);
+    assert_eq!(fs.add_dir_entry(&mut root, "etc", 12, file_type::EXT4_FT_DIR), Ok(()));
+
+    assert_eq!(fs.write_block(FIRST_DATA_BLOCK + 2, 0, HOSTS), Ok(()));
+    let hosts = new_inode(13, 0o100644, HOSTS.len() as u64, &[FIRST_DATA_BLOCK as u32 + 2]);
+    assert_eq!(fs.write_inode(13, &hosts), Ok(()));
+    assert_eq!(fs.add_dir_entry(&mut etc, "hosts", 13, file_type::EXT4_FT_REG_FILE), Ok(()));
+
+    let links = [(14, "self", "hosts"), (15, "abs", "/etc/hosts"), (16, "conf", "etc"), (18, "loop", "loop"), (19, "dangling", "nowhere")];
+    for (ino, name, target) in links {
+        assert_eq!(fs.write_inode(ino, &fast_symlink(ino, target)), Ok(()));
+        let parent = if name == "self" { &mut etc } else { &mut root };
+        assert_eq!(fs.add_dir_entry(parent, name, ino, file_type::EXT4_FT_SYMLINK), Ok(()));
+    }
+
+    assert_eq!(fs.write_block(FIRST_DATA_BLOCK + 3, 0, b"etc/hosts"), Ok(()));
+    let slow = new_inode(17, 0o120777, 9, &[FIRST_DATA_BLOCK as u32 + 3]);
+    assert_eq!(fs.write_inode(17, &slow), Ok(()));
+    assert_eq!(fs.add_dir_entry(&mut root, "slow", 17, file_type::EXT4_FT_SYMLINK), Ok(()));
+
+    bio::invalidate_device(dev);
+
+    // 测试 1: 读取链接目标
+    println!("test: 1. Testing read_symlink...");
+    let link = fs.read_inode(15).expect("read fast symlink");
+    assert!(link.is_symlink() && link.is_fast_symlink());
+    assert_eq!(fs.read_symlink(&link).as_deref(), Ok("/etc/hosts"), "Inline target");
+    let link = fs.read_inode(17).expect("read slow symlink");
+    assert!(link.is_symlink() && !link.is_fast_symlink());
+    assert_eq!(fs.read_symlink(&link).as_deref(), Ok("etc/hosts"), "Target from data block");
+    let not_link = fs.read_inode(13).expect("read regular file");
+    assert!(fs.read_symlink(&not_link).is_err(), "Regular file is not a symlink");
+    println!("test:    SUCCESS - fast and slow targets read");
+
+    // 测试 2: 路径解析
+    println!("test: 2. Testing symlink resolution in lookup_path...");
+    for path in ["/etc/hosts", "/etc/self", "/abs", "/conf/hosts", "/conf/self", "/slow"] {
+        let (ino, inode) = fs.lookup_path(path, true).expect("path resolves");
+        assert_eq!(ino, 13, "{} resolves to /etc/hosts", path);
+        let mut buf = [0u8; 64];
+        let n = file::ext4_file_read(&fs, &inode, 0, &mut buf).expect("read target");
+        assert_eq!(&buf[..n], HOSTS);
+    }
+    assert_eq!(fs.lookup_path("/conf", true).map(|(ino, _)| ino), Ok(12), "Link to a directory");
+    println!("test:    SUCCESS - relative, absolute and intermediate links followed");
+
+    // 测试 3: 环与悬空链接
+    println!("test: 3. Testing loops and dangling links...");
+    assert_eq!(
+        fs.lookup_path("/loop", true).map(|(ino, _)| ino),
+        Err(Errno::TooManySymbolicLinks.as_neg_i32())
+    );
+    assert_eq!(
+        fs.lookup_path("/dangling", true).map(|(ino, _)| ino),
+        Err(Errno::NoSuchFileOrDirectory.as_neg_i32())
+    );
+    assert_eq!(
+        fs.lookup_path("/etc/hosts/x", true).map(|(ino, _)| ino),
+        Err(Errno::NotADirectory.as_neg_i32())
+    );
+    println!("test:    SUCCESS - ELOOP after {} links", crate::fs::ext4::EXT4_MAX_SYMLINKS);
+
+    // 测试 4: 不跟随最后一个分量
+    println!("test: 4. Testing lookup without following the last link...");
+    let (ino, inode) = fs.lookup_path("/abs", false).expect("link itself");
+    assert!(ino == 15 && inode.is_symlink(), "Last component returned as a symlink");
+    assert_eq!(fs.lookup_path("/conf/self", false).map(|(ino, _)| ino), Ok(14), "Intermediate link followed");
+    assert_eq!(fs.lookup_path("/conf/hosts", false).map(|(ino, _)| ino), Ok(13));
+    assert_eq!(fs.lookup_path("/loop", false).map(|(ino, _)| ino), Ok(18), "No ELOOP for the link itself");
+    println!("test:    SUCCESS - O_NOFOLLOW lookups see the link");
+
+    bio::invalidate_device(dev);
+
+    println!("test: ===== ext4 Symlink Testing Completed =====");
+}
@@ -0,0 +1,222 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：ext4 inode 与数据块写回
+//
+// 测试内容：
+// 1. 默认只读挂载，"rw" 选项才允许写；只读时 write_inode / write_block 返回 EROFS；
+//    带元数据校验和的文件系统不能读写挂载
+// 2. write_inode 修改的字段写回磁盘后可以重新读出，同一块中的其他 inode 和未建模字段不变
+// 3. write_block 覆盖已分配的数据块；ext4_file_write 在已有块内写入并把新大小写回 inode
+// 4. ext4_truncate 缩短时释放块并清零尾部，扩展时分配清零的块；ext4_chmod 只改权限位
+
+use crate::println;
+use crate::drivers::blkdev::{GenDisk, ReqCmd, Request};
+use crate::fs::bio;
+use crate::fs::ext4::{self, file, inode::Ext4Inode, superblock::Ext4GroupDesc, Ext4FileSystem};
+use crate::fs::superblock::FsContext;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use spin::Mutex;
+
+/// 模拟磁盘的块数（4KB 块）
+pub const RAM_BLOCKS: u64 = 32;
+/// inode 表起始块
+pub const INODE_TABLE: u32 = 4;
+/// 第一个可用的数据块
+pub const FIRST_DATA_BLOCK: u64 = 10;
+/// 块分配器分配的第一个块，之前的块在位图中标记为已用
+pub const ALLOC_START: u64 = 16;
+/// inode 分配器分配的第一个 inode，之前的 inode 在位图中标记为已用
+pub const FIRST_FREE_INODE: u32 = 33;
+
+/// 模拟磁盘的内容
+static RAM: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+/// 模拟磁盘：读写 RAM 中的数据
+unsafe extern "C" fn ram_request(req: &mut Request) {
+    let start = req.sector as usize * 512;
+    let len = req.buffer.len();
+    let mut ram = RAM.lock();
+    match req.cmd_type {
+        ReqCmd::Read => req.buffer.copy_from_slice(&ram[start..start + len]),
+        ReqCmd::Write => ram[start..start + len].copy_from_slice(&req.buffer),
+        ReqCmd::Flush => {}
+    }
+}
+
+/// 直接读写模拟磁盘中的字节，绕过块缓存
+pub fn ram_bytes<R>(f: impl FnOnce(&mut [u8]) -> R) -> R {
+    f(&mut RAM.lock())
+}
+
+/// 创建一个清零的内存磁盘和挂在它上面的单块组 ext4 实例（可写）
+///
+/// 块 0-3 保留，inode 表从 `INODE_TABLE` 开始，数据块从 `FIRST_DATA_BLOCK` 开始，
+/// 块分配器从 `ALLOC_START` 开始分配。
+/// 测试结束后应调用 `bio::invalidate_device` 丢弃缓冲区。
+pub fn ram_ext4() -> (Box<GenDisk>, Ext4FileSystem) {
+    let mut ram = vec![0u8; RAM_BLOCKS as usize * 4096];
+    // 块位图在块 2
+    ram[2 * 4096..2 * 4096 + ALLOC_START as usize / 8].fill(0xFF);
+    // inode 位图在块 3，第 n 位对应 inode n + 1
+    ram[3 * 4096..3 * 4096 + (FIRST_FREE_INODE as usize - 1) / 8].fill(0xFF);
+    *RAM.lock() = ram;
+
+    let mut disk = Box::new(GenDisk::new("mockext4", 253, 1, 4096, None));
+    disk.set_capacity(RAM_BLOCKS as u32 * 8);
+    disk.set_request_fn(ram_request);
+
+    let mut fs = Ext4FileSystem::new(&*disk as *const GenDisk);
+    fs.block_size = 4096;
+    fs.block_size_bits = 12;
+    fs.inode_size = 256;
+    fs.blocks_per_group = RAM_BLOCKS as u32;
+    fs.inodes_per_group = 64;
+    fs.group_count = 1;
+    fs.total_blocks = RAM_BLOCKS;
+    fs.total_inodes = 64;
+    fs.group_descs = vec![Box::new(Ext4GroupDesc {
+        bg_block_bitmap: 2,
+        bg_inode_bitmap: 3,
+        bg_inode_table: INODE_TABLE,
+        bg_free_blocks_count: (RAM_BLOCKS - ALLOC_START) as u16,
+        bg_free_inodes_count: (64 - (FIRST_FREE_INODE - 1)) as u16,
+        ..Ext4GroupDesc::default()
+    })];
+    fs.read_only = false;
+
+    (disk, fs)
+}
+
+/// 构造一个使用直接块指针的 inode
+pub fn new_inode(ino: u32, mode: u16, size: u64, blocks: &[u32]) -> Ext4Inode {
+    let mut block = [0u32; 15];
+    block[..blocks.len()].copy_from_slice(blocks);
+    Ext4Inode {
+        ino,
+        mode,
+        uid: 0,
+        gid: 0,
+        size,
+        blocks: blocks.len() as u64 * 8,
+        links_count: 1,
+        flags: 0,
+        block,
+        atime: 0,
+        mtime: 0,
+        ctime: 0,
+    }
+}
+
+pub fn test_ext4_write_inode() {
+    println!("test: ===== Testing ext4 inode writeback =====");
+
+    let (disk, mut fs) = ram_ext4();
+    let dev = &*disk as *const GenDisk;
+
+    // 测试 1: 只读与 rw 选项
+    println!("test: 1. Testing read-only default and rw option...");
+    let mut fc = FsContext::new(Some("/dev/vda"), Some("/mnt"), 0);
+    assert!(!ext4::mount_rw(&fc), "Read-only without options");
+    fc.data = Some("noatime,rw");
+    assert!(ext4::mount_rw(&fc), "rw option allows writes");
+    fc.data = Some("rw,ro");
+    assert!(!ext4::mount_rw(&fc), "Last option wins");
+    fc.data = Some("rw");
+    fc.ms_flags = 1;
+    assert!(!ext4::mount_rw(&fc), "MS_RDONLY overrides rw");
+    assert!(!fs.has_checksums(), "Test image has no checksums");
+    for feature in [ext4::EXT4_FEATURE_RO_COMPAT_GDT_CSUM, ext4::EXT4_FEATURE_RO_COMPAT_METADATA_CSUM] {
+        fs.feature_ro_compat = feature | 0x1;
+        assert!(fs.has_checksums(), "Checksummed metadata cannot be written");
+    }
+    fs.feature_ro_compat = 0;
+
+    let erofs = crate::errno::Errno::ReadOnlyFileSystem.as_neg_i32();
+    fs.read_only = true;
+    let reg = new_inode(12, 0o100644, 100, &[FIRST_DATA_BLOCK as u32]);
+    assert_eq!(fs.write_inode(12, &reg), Err(erofs));
+    assert_eq!(fs.write_block(FIRST_DATA_BLOCK, 0, b"x"), Err(erofs));
+    fs.read_only = false;
+    println!("test:    SUCCESS - writes need an rw mount");
+
+    // 测试 2: inode 写回
+    println!("test: 2. Testing write_inode round trip...");
+    // 在 inode 12 的 i_generation 处放一个标记，它不在 Ext4Inode 中
+    let gen_offset = INODE_TABLE as usize * 4096 + 11 * 256 + 100;
+    ram_bytes(|ram| ram[gen_offset] = 0x5A);
+
+    let neighbour = new_inode(11, 0o040755, 4096, &[FIRST_DATA_BLOCK as u32 + 1]);
+    assert_eq!(fs.write_inode(11, &neighbour), Ok(()));
+    assert_eq!(fs.write_inode(12, &reg), Ok(()));
+
+    let mut changed = fs.read_inode(12).expect("read inode 12");
+    changed.mtime = 1_700_000_000;
+    changed.links_count = 2;
+    assert_eq!(fs.write_inode(12, &changed), Ok(()));
+
+    // 丢弃缓存，强制从磁盘重新读取
+    bio::invalidate_device(dev);
+    let back = fs.read_inode(12).expect("re-read inode 12");
+    assert_eq!(back.mtime, 1_700_000_000, "mtime persisted");
+    assert_eq!((back.mode, back.size, back.links_count), (0o100644, 100, 2));
+    assert_eq!(back.block[0], FIRST_DATA_BLOCK as u32);
+    let other = fs.read_inode(11).expect("re-read inode 11");
+    assert!(other.is_dir(), "Neighbouring inode untouched");
+    assert_eq!(other.block[0], FIRST_DATA_BLOCK as u32 + 1);
+    assert_eq!(ram_bytes(|ram| ram[gen_offset]), 0x5A, "Unmodelled fields preserved");
+    println!("test:    SUCCESS - mtime={} read back from disk", back.mtime);
+
+    // 测试 3: 数据块覆盖
+    println!("test: 3. Testing data block overwrite...");
+    assert_eq!(fs.write_block(FIRST_DATA_BLOCK, 0, &[b'a'; 100]), Ok(()));
+    let mut inode = fs.read_inode(12).expect("read inode 12");
+    assert_eq!(file::ext4_file_write(&fs, &mut inode, 50, &[b'b'; 100]), Ok(100));
+
+    bio::invalidate_device(dev);
+    let inode = fs.read_inode(12).expect("re-read inode 12");
+    assert_eq!(inode.size, 150, "New size written back to the inode");
+    assert_eq!(inode.block[0], FIRST_DATA_BLOCK as u32, "No block allocated");
+    let mut buf = [0u8; 150];
+    assert_eq!(file::ext4_file_read(&fs, &inode, 0, &mut buf), Ok(150));
+    assert!(buf[..50].iter().all(|&b| b == b'a'));
+    assert!(buf[50..].iter().all(|&b| b == b'b'));
+    assert!(fs.write_block(FIRST_DATA_BLOCK, 4000, &[0u8; 200]).is_err(), "Write past block end rejected");
+    println!("test:    SUCCESS - existing block overwritten in place");
+
+    // 测试 4: 截断与 chmod
+    println!("test: 4. Testing truncate and chmod...");
+    let mut grow = new_inode(14, 0o100644, 0, &[]);
+    assert_eq!(fs.write_inode(14, &grow), Ok(()));
+    assert_eq!(file::ext4_file_write(&fs, &mut grow, 0, &[b'c'; 9000]), Ok(9000));
+    assert_eq!(grow.blocks, 3 * 8, "i_blocks counts allocated blocks");
+    let third = grow.block[2];
+    assert_ne!(third, 0);
+
+    assert_eq!(file::ext4_truncate(&fs, &mut grow, 5000), Ok(()));
+    assert_eq!((grow.size, grow.blocks, grow.block[2]), (5000, 2 * 8, 0), "Third block released");
+    assert_eq!(file::ext4_truncate(&fs, &mut grow, 9000), Ok(()));
+    assert_eq!(grow.block[2], third, "Released block reused by the allocator");
+
+    bio::invalidate_device(dev);
+    let grow = fs.read_inode(14).expect("re-read inode 14");
+    assert_eq!(grow.size, 9000, "Truncated size written back");
+    let mut buf = [0u8; 9000];
+    assert_eq!(file::ext4_file_read(&fs, &grow, 0, &mut buf), Ok(9000));
+    assert!(buf[..5000].iter().all(|&b| b == b'c'), "Data before the cut kept");
+    assert!(buf[5000..].iter().all(|&b| b == 0), "Data past the cut reads as zeros");
+
+    let mut grow = grow;
+    assert_eq!(file::ext4_chmod(&fs, &mut grow, 0o600), Ok(()));
+    bio::invalidate_device(dev);
+    assert_eq!(fs.read_inode(14).map(|i| i.mode), Ok(0o100600), "File type bits kept");
+    println!("test:    SUCCESS - truncate 9000 -> 5000 -> 9000");
+
+    bio::invalidate_device(dev);
+
+    println!("test: ===== ext4 Inode Writeback Testing Completed =====");
+}
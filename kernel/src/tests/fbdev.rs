@@ -0,0 +1,120 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：framebuffer 字符设备 /dev/fb0
+//
+// 测试内容：
+// 1. /dev/fb0 已登记，重复登记和不存在的设备被拒绝
+// 2. 在某个偏移写入会更新对应的 framebuffer 像素，读取返回同样的内容
+// 3. 超出末尾的读、写和 lseek 被拒绝
+// 4. mmap 返回映射到 framebuffer 物理内存、大小符合 GPU 信息的设备映射
+// 5. 超出 framebuffer 的 mmap 被拒绝
+
+use crate::println;
+use crate::arch::riscv64::mm::{create_user_address_space, user_virt_to_phys, AddressSpace, map, prot};
+use crate::drivers::gpu::{fbdev_mmap, get_framebuffer_info, FBDEV_FILE_OPS};
+use crate::errno::Errno;
+use crate::fs::devfs;
+use crate::fs::file::{File, FileFlags, REG_FILE_OPS};
+use crate::mm::page::{VirtAddr, PAGE_SIZE};
+use crate::mm::vma::VmaType;
+
+pub fn test_fbdev() {
+    println!("test: ===== Testing framebuffer device =====");
+
+    let info = match get_framebuffer_info() {
+        Some(info) => info,
+        None => {
+            println!("test:    No framebuffer - skipping");
+            return;
+        }
+    };
+    let fb_size = info.size as usize;
+
+    // 测试 1: 设备登记
+    println!("test: 1. Testing /dev/fb0 registration...");
+    assert!(devfs::lookup("fb0").is_some(), "fb0 is registered during GPU init");
+    assert_eq!(devfs::register_char_device("fb0", &FBDEV_FILE_OPS),
+               Err(Errno::FileExists.as_neg_i32()), "Duplicate device names are rejected");
+    assert_eq!(devfs::open("nonexistent", FileFlags::O_RDWR).err(),
+               Some(Errno::NoSuchFileOrDirectory.as_neg_i32()), "Unknown devices cannot be opened");
+    let file = devfs::open("fb0", FileFlags::O_RDWR).expect("open /dev/fb0");
+    println!("test:    SUCCESS - /dev/fb0 registered");
+
+    // 测试 2: 按偏移读写像素
+    println!("test: 2. Testing read/write at an offset...");
+    let (x, y) = (info.width as usize / 2, info.height as usize / 2);
+    let offset = (y * info.stride as usize + x) * 4;
+    let pixel = (info.addr as usize + offset) as *mut u32;
+    let saved = unsafe { pixel.read_volatile() };
+    let color: u32 = 0x00AB_CDEF;
+    unsafe {
+        assert_eq!(file.lseek(offset as isize, 0), offset as isize, "Seek to the pixel");
+        assert_eq!(file.write(&color as *const u32 as *const u8, 4), 4, "Whole pixel written");
+        assert_eq!(pixel.read_volatile(), color, "Framebuffer pixel updated");
+        assert_eq!(file.get_pos(), (offset + 4) as u64, "Position advances past the pixel");
+
+        let mut read_back: u32 = 0;
+        assert_eq!(file.lseek(offset as isize, 0), offset as isize);
+        assert_eq!(file.read(&mut read_back as *mut u32 as *mut u8, 4), 4);
+        assert_eq!(read_back, color, "Read returns the written pixel");
+        pixel.write_volatile(saved);
+    }
+    println!("test:    SUCCESS - pixel updated through write");
+
+    // 测试 3: 越界读写
+    println!("test: 3. Testing out-of-range access...");
+    let mut buf = [0u8; 8];
+    unsafe {
+        assert_eq!(file.lseek(0, 2), fb_size as isize, "SEEK_END is the framebuffer size");
+        assert_eq!(file.read(buf.as_mut_ptr(), buf.len()), 0, "Read at the end returns EOF");
+        assert_eq!(file.write(buf.as_ptr(), buf.len()), Errno::NoSpaceLeftOnDevice.as_neg_i32() as isize,
+                   "Write at the end is rejected");
+        assert_eq!(file.lseek(1, 2), Errno::InvalidArgument.as_neg_i32() as isize, "Seek beyond the end");
+        // 用原内容写回最后一个像素
+        assert_eq!(file.lseek(-4, 2), (fb_size - 4) as isize);
+        assert_eq!(file.read(buf.as_mut_ptr(), buf.len()), 4, "Read is truncated at the end");
+        assert_eq!(file.lseek(-4, 2), (fb_size - 4) as isize);
+        assert_eq!(file.write(buf.as_ptr(), buf.len()), 4, "Write is truncated at the end");
+    }
+    println!("test:    SUCCESS - out-of-range access rejected");
+
+    // 测试 4/5: mmap
+    println!("test: 4. Testing mmap...");
+    let mut regular = File::new(FileFlags::new(FileFlags::O_RDWR));
+    regular.set_ops(&REG_FILE_OPS);
+    assert!(regular.mmap(0, PAGE_SIZE, prot::PROT_READ, map::MAP_SHARED, 0).is_none(),
+            "Regular files have no driver mmap");
+
+    match create_user_address_space() {
+        Some(root_ppn) => {
+            let addr_space = unsafe { AddressSpace::new(root_ppn) };
+            let rw = prot::PROT_READ | prot::PROT_WRITE;
+            let start = fbdev_mmap(&addr_space, 0, fb_size, rw, map::MAP_SHARED, 0).expect("mmap framebuffer");
+            let vma = addr_space.find_vma(VirtAddr::new(start)).expect("mapping has a VMA");
+            let expected = (fb_size + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+            assert_eq!(vma.start().as_usize(), start);
+            assert_eq!(vma.size(), expected, "Mapping covers the whole framebuffer");
+            assert_eq!(vma.vma_type(), VmaType::Device, "Framebuffer is a device mapping");
+            let phys = unsafe { user_virt_to_phys(root_ppn, (start + offset) as u64) };
+            assert_eq!(phys, Some(info.addr + offset as u64), "Mapped to the framebuffer memory");
+            println!("test:    SUCCESS - framebuffer mapped");
+
+            println!("test: 5. Testing out-of-range mmap...");
+            let einval = Err(Errno::InvalidArgument.as_neg_i32());
+            assert_eq!(fbdev_mmap(&addr_space, 0, expected + PAGE_SIZE, rw, map::MAP_SHARED, 0), einval,
+                       "Length beyond the framebuffer");
+            assert_eq!(fbdev_mmap(&addr_space, 0, PAGE_SIZE, rw, map::MAP_SHARED, expected), einval,
+                       "Offset at the end of the framebuffer");
+            assert_eq!(fbdev_mmap(&addr_space, 0, PAGE_SIZE, rw, map::MAP_SHARED, 8), einval,
+                       "Unaligned offset");
+            assert_eq!(fbdev_mmap(&addr_space, 0, 0, rw, map::MAP_SHARED, 0), einval, "Zero length");
+            println!("test:    SUCCESS - out-of-range mmap rejected");
+        }
+        None => println!("test:    No user memory - skipping"),
+    }
+
+    println!("test: ===== Framebuffer Device Testing Completed =====");
+}
@@ -5,7 +5,8 @@
 //! sys_fcntl 测试
 
 use crate::println;
-use crate::fs::{file_open, file_close, file_fcntl, fcntl, FileFlags};
+use crate::fs::{file_open, file_close, file_fcntl, fcntl, FileFlags, create_pipe};
+use crate::fs::file::get_file_fd_install;
 
 pub fn test_fcntl() {
     println!("test: ===== Starting fcntl() Tests =====");
@@ -26,6 +27,10 @@ pub fn test_fcntl() {
     println!("test: 4. Testing F_SETFL...");
     test_setfl();
 
+    // 测试 5: F_SETFL(O_NONBLOCK) 影响管道读
+    println!("test: 5. Testing F_SETFL O_NONBLOCK on pipe...");
+    test_setfl_nonblock_pipe();
+
     println!("test: ===== fcntl() Tests Completed =====");
 }
 
@@ -203,3 +208,49 @@ fn test_setfl() {
         }
     }
 }
+
+fn test_setfl_nonblock_pipe() {
+    let (read_end, write_end) = create_pipe();
+    let fd = match unsafe { get_file_fd_install(read_end.clone()) } {
+        Some(fd) => fd,
+        None => {
+            println!("test:    SKIPPED - no fd table for current task");
+            return;
+        }
+    };
+
+    // 设置前 F_GETFL 不应包含 O_NONBLOCK
+    let flags = file_fcntl(fd, fcntl::F_GETFL, 0).expect("F_GETFL failed");
+    assert_eq!(flags as u32 & FileFlags::O_NONBLOCK, 0, "Pipe should start blocking");
+    assert_eq!(flags as u32 & FileFlags::O_ACCMODE, FileFlags::O_RDONLY);
+
+    // 设置 O_NONBLOCK，并尝试修改访问模式（应被忽略）
+    let arg = (FileFlags::O_NONBLOCK | FileFlags::O_RDWR) as usize;
+    file_fcntl(fd, fcntl::F_SETFL, arg).expect("F_SETFL failed");
+    let flags = file_fcntl(fd, fcntl::F_GETFL, 0).expect("F_GETFL failed");
+    assert_ne!(flags as u32 & FileFlags::O_NONBLOCK, 0, "F_GETFL should report O_NONBLOCK");
+    assert_eq!(flags as u32 & FileFlags::O_ACCMODE, FileFlags::O_RDONLY, "Access mode must not change");
+    println!("test:    SUCCESS - F_GETFL reflects O_NONBLOCK");
+
+    // 空管道非阻塞读返回 -EAGAIN
+    let mut buf = [0u8; 8];
+    let ret = unsafe { read_end.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(ret, -11, "Empty non-blocking pipe read should return -EAGAIN");
+    println!("test:    SUCCESS - empty pipe read returns -EAGAIN");
+
+    // 清除 O_NONBLOCK 后写入数据，正常读取
+    file_fcntl(fd, fcntl::F_SETFL, 0).expect("F_SETFL failed");
+    assert!(!read_end.flags.is_nonblock(), "O_NONBLOCK should be cleared");
+    unsafe { write_end.write(b"ok".as_ptr(), 2) };
+    let ret = unsafe { read_end.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(ret, 2);
+    assert_eq!(&buf[..2], b"ok");
+    println!("test:    SUCCESS - cleared O_NONBLOCK, read returned data");
+
+    // F_SETFD/F_GETFD 管理 FD_CLOEXEC
+    file_fcntl(fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC).expect("F_SETFD failed");
+    assert_eq!(file_fcntl(fd, fcntl::F_GETFD, 0), Ok(fcntl::FD_CLOEXEC));
+    println!("test:    SUCCESS - FD_CLOEXEC managed via F_SETFD/F_GETFD");
+
+    let _ = file_close(fd);
+}
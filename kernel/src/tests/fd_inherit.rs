@@ -0,0 +1,89 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：fork/execve 文件描述符继承
+//
+// 测试内容：
+// 1. fork 复制 fd 表，子进程与父进程共享同一个打开的文件
+// 2. shell 通过 dup2 重定向的 stdout 被子进程继承，写入到达重定向目标
+// 3. execve 关闭 CLOEXEC fd，保留其他 fd
+// 4. 子进程关闭共享的管道端不会关闭父进程的管道
+// 5. 所有 fd 都关闭后，fd 表之外仍持有引用时不释放；最后一个引用释放时才关闭
+
+use crate::println;
+use crate::fs::create_pipe;
+use crate::fs::file::{FdTable, FileFlags};
+use alloc::sync::Arc;
+
+pub fn test_fd_inherit() {
+    println!("test: ===== Testing fd inheritance across fork/exec =====");
+
+    // 父进程：标准输入输出 + 一个 CLOEXEC 管道 + 一个普通管道
+    let parent = FdTable::new();
+    crate::init::init_std_fds_for_task(&parent);
+    let (redir_read, redir_write) = create_pipe();
+    let (cloexec_read, _cloexec_write) = create_pipe();
+    let (keep_read, _keep_write) = create_pipe();
+    cloexec_read.set_cloexec(true);
+    assert!(parent.install_fd(3, cloexec_read.clone()).is_ok());
+    assert!(parent.install_fd(4, keep_read.clone()).is_ok());
+
+    // 模拟 shell 的 dup2(pipe_write, 1)：stdout 重定向到管道
+    let _ = parent.close_fd(1);
+    assert!(parent.install_fd(1, redir_write.clone()).is_ok());
+
+    // 测试 1: fork 复制 fd 表
+    println!("test: 1. Testing fork copies the fd table...");
+    let child = parent.dup_table();
+    for fd in [0usize, 1, 2, 3, 4] {
+        let p = parent.get_file(fd).expect("parent fd missing");
+        let c = child.get_file(fd).expect("child fd missing");
+        assert!(Arc::ptr_eq(&p, &c), "Child must share the open file description");
+    }
+    println!("test:    SUCCESS - child inherited fds 0-4");
+
+    // 测试 2: 子进程写入继承的（已重定向的）stdout
+    println!("test: 2. Testing child writes to inherited stdout...");
+    let stdout = child.get_file(1).expect("child stdout missing");
+    let written = unsafe { stdout.write(b"from child".as_ptr(), 10) };
+    assert_eq!(written, 10);
+    let mut buf = [0u8; 16];
+    let n = unsafe { redir_read.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, 10);
+    assert_eq!(&buf[..10], b"from child", "Redirected stdout should reach the pipe");
+    println!("test:    SUCCESS - child output reached redirection target");
+
+    // 测试 3: execve 关闭 CLOEXEC fd
+    println!("test: 3. Testing close-on-exec after execve...");
+    child.close_on_exec();
+    assert!(child.get_file(3).is_none(), "CLOEXEC fd must be closed after exec");
+    assert!(child.get_file(4).is_some(), "Non-CLOEXEC fd must survive exec");
+    for fd in [0usize, 1, 2] {
+        assert!(child.get_file(fd).is_some(), "Standard fds must survive exec");
+    }
+    assert!(parent.get_file(3).is_some(), "Parent's CLOEXEC fd must be untouched");
+    println!("test:    SUCCESS - CLOEXEC fd absent, others preserved");
+
+    // 测试 4: 子进程关闭共享管道端，父进程仍可使用
+    println!("test: 4. Testing child close does not close parent's pipe...");
+    assert!(child.close_fd(1).is_ok());
+    let written = unsafe { redir_write.write(b"x".as_ptr(), 1) };
+    assert_eq!(written, 1, "Parent's pipe write end must stay open");
+    println!("test:    SUCCESS - shared pipe still open in parent");
+
+    // 测试 5: 最后一个引用释放时才关闭
+    println!("test: 5. Testing release on the last reference...");
+    assert!(parent.close_fd(1).is_ok());
+    redir_read.flags.set_status_flags(FileFlags::O_NONBLOCK);
+    assert_eq!(unsafe { redir_read.read(buf.as_mut_ptr(), buf.len()) }, 1, "Byte from test 4");
+    let n = unsafe { redir_read.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, -11, "Write end still referenced outside the fd tables");
+    drop(redir_write);
+    let n = unsafe { redir_read.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, 0, "Dropping the last reference closes the write end");
+    println!("test:    SUCCESS - EOF after the last reference is gone");
+
+    println!("test: ===== fd inheritance Testing Completed =====");
+}
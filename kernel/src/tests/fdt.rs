@@ -0,0 +1,216 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：扁平设备树解析
+//
+// 测试内容：
+// 1. 魔数错误或块越界的 DTB 被拒绝
+// 2. 提取 virtio-mmio 节点的基地址和中断号
+// 3. 提取 UART 和 PLIC 节点
+// 4. 按父节点的 #address-cells / #size-cells 解码 reg
+// 5. simple-framebuffer 节点的 format 和 stride 决定 framebuffer 布局
+
+use crate::println;
+use crate::drivers::gpu::{simple_framebuffer_from_node, PixelFormat};
+use crate::fdt::{Fdt, PlatformInfo, FDT_MAGIC};
+use alloc::vec::Vec;
+
+/// 构造测试用 DTB
+struct DtbBuilder {
+    structs: Vec<u8>,
+    strings: Vec<u8>,
+}
+
+impl DtbBuilder {
+    fn new() -> Self {
+        Self { structs: Vec::new(), strings: Vec::new() }
+    }
+
+    fn token(&mut self, token: u32) {
+        self.structs.extend_from_slice(&token.to_be_bytes());
+    }
+
+    fn pad(&mut self) {
+        while self.structs.len() % 4 != 0 {
+            self.structs.push(0);
+        }
+    }
+
+    fn begin(&mut self, name: &str) -> &mut Self {
+        self.token(0x1);
+        self.structs.extend_from_slice(name.as_bytes());
+        self.structs.push(0);
+        self.pad();
+        self
+    }
+
+    fn end(&mut self) -> &mut Self {
+        self.token(0x2);
+        self
+    }
+
+    fn prop(&mut self, name: &str, value: &[u8]) -> &mut Self {
+        let nameoff = self.strings.len() as u32;
+        self.strings.extend_from_slice(name.as_bytes());
+        self.strings.push(0);
+        self.token(0x3);
+        self.token(value.len() as u32);
+        self.token(nameoff);
+        self.structs.extend_from_slice(value);
+        self.pad();
+        self
+    }
+
+    fn prop_cells(&mut self, name: &str, cells: &[u32]) -> &mut Self {
+        let value: Vec<u8> = cells.iter().flat_map(|c| c.to_be_bytes()).collect();
+        self.prop(name, &value)
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        self.token(0x9);
+        let header_size = 0x28 + 16; // 头部 + 空的内存保留表
+        let off_struct = header_size;
+        let off_strings = off_struct + self.structs.len();
+        let total = off_strings + self.strings.len();
+
+        let header = [
+            FDT_MAGIC,
+            total as u32,
+            off_struct as u32,
+            off_strings as u32,
+            0x28,
+            17,
+            16,
+            0,
+            self.strings.len() as u32,
+            self.structs.len() as u32,
+        ];
+        let mut dtb: Vec<u8> = header.iter().flat_map(|v| v.to_be_bytes()).collect();
+        dtb.resize(header_size, 0);
+        dtb.extend_from_slice(&self.structs);
+        dtb.extend_from_slice(&self.strings);
+        dtb
+    }
+}
+
+/// 类似 QEMU virt 的示例设备树
+fn sample_dtb() -> Vec<u8> {
+    let mut b = DtbBuilder::new();
+    b.begin("")
+        .prop_cells("#address-cells", &[2])
+        .prop_cells("#size-cells", &[2])
+        .prop("compatible", b"riscv-virtio\0");
+    b.begin("soc")
+        .prop_cells("#address-cells", &[2])
+        .prop_cells("#size-cells", &[2])
+        .prop("compatible", b"simple-bus\0");
+    b.begin("serial@10000000")
+        .prop("compatible", b"ns16550a\0")
+        .prop_cells("reg", &[0, 0x1000_0000, 0, 0x100])
+        .prop_cells("interrupts", &[10])
+        .end();
+    b.begin("plic@c000000")
+        .prop("compatible", b"sifive,plic-1.0.0\0riscv,plic0\0")
+        .prop_cells("reg", &[0, 0x0c00_0000, 0, 0x60_0000])
+        .end();
+    b.begin("virtio_mmio@10002000")
+        .prop("compatible", b"virtio,mmio\0")
+        .prop_cells("reg", &[0, 0x1000_2000, 0, 0x1000])
+        .prop_cells("interrupts", &[2])
+        .end();
+    b.begin("virtio_mmio@10001000")
+        .prop("compatible", b"virtio,mmio\0")
+        .prop_cells("reg", &[0, 0x1000_1000, 0, 0x1000])
+        .prop_cells("interrupts", &[1])
+        .end();
+    b.begin("bus32")
+        .prop_cells("#address-cells", &[1])
+        .prop_cells("#size-cells", &[1]);
+    b.begin("dev@2000")
+        .prop("compatible", b"test,dev32\0")
+        .prop_cells("reg", &[0x2000, 0x80])
+        .end();
+    b.end(); // bus32
+    b.begin("framebuffer@50000000")
+        .prop("compatible", b"simple-framebuffer\0")
+        .prop_cells("reg", &[0, 0x5000_0000, 0, 0x12_c000])
+        .prop_cells("width", &[640])
+        .prop_cells("height", &[480])
+        .prop_cells("stride", &[1536])
+        .prop("format", b"r5g6b5\0")
+        .end();
+    b.end(); // soc
+    b.end(); // /
+    b.finish()
+}
+
+pub fn test_fdt() {
+    println!("test: ===== Testing FDT parser =====");
+
+    let dtb = sample_dtb();
+
+    // 测试 1: 拒绝无效的 DTB
+    println!("test: 1. Testing invalid blob rejection...");
+    assert!(Fdt::new(&dtb).is_some(), "Valid DTB should parse");
+    let mut bad_magic = dtb.clone();
+    bad_magic[0] = 0;
+    assert!(Fdt::new(&bad_magic).is_none(), "Bad magic must be rejected");
+    assert!(Fdt::new(&dtb[..dtb.len() - 8]).is_none(), "Truncated DTB must be rejected");
+    assert!(Fdt::new(&[]).is_none(), "Empty blob must be rejected");
+    println!("test:    SUCCESS - invalid blobs rejected");
+
+    // 测试 2: virtio-mmio 基地址和中断号
+    println!("test: 2. Testing virtio-mmio base and IRQ extraction...");
+    let fdt = Fdt::new(&dtb).unwrap();
+    let virtio = fdt.find_compatible("virtio,mmio");
+    assert_eq!(virtio.len(), 2, "Should find both virtio-mmio nodes");
+    let first = virtio.iter().find(|n| n.name == "virtio_mmio@10001000").unwrap();
+    assert_eq!(first.reg, Some((0x1000_1000, 0x1000)));
+    assert_eq!(first.irq, Some(1));
+
+    let info = PlatformInfo::from_fdt(&fdt);
+    assert_eq!(info.virtio_mmio.len(), 2);
+    assert_eq!(info.virtio_mmio[0].base(), Some(0x1000_1000), "Transports sorted by base");
+    assert_eq!(info.virtio_mmio[1].base(), Some(0x1000_2000));
+    assert_eq!(info.virtio_mmio[1].irq, Some(2));
+    println!("test:    SUCCESS - virtio-mmio @ {:#x} irq {}", 0x1000_1000, 1);
+
+    // 测试 3: UART 和 PLIC
+    println!("test: 3. Testing UART and PLIC extraction...");
+    let uart = info.uart.as_ref().expect("UART node");
+    assert_eq!(uart.base(), Some(0x1000_0000));
+    assert_eq!(uart.irq, Some(10));
+    let plic = info.intc.as_ref().expect("PLIC node");
+    assert!(plic.is_compatible("riscv,plic0"), "Second compatible entry should match");
+    assert_eq!(plic.reg, Some((0x0c00_0000, 0x60_0000)));
+    assert_eq!(plic.irq, None);
+    println!("test:    SUCCESS - UART and PLIC found");
+
+    // 测试 4: 单元数解码
+    println!("test: 4. Testing #address-cells/#size-cells decoding...");
+    let dev = fdt.find_compatible("test,dev32");
+    assert_eq!(dev.len(), 1);
+    assert_eq!(dev[0].reg, Some((0x2000, 0x80)), "reg must use the parent's cell counts");
+    println!("test:    SUCCESS - 1/1 cells decoded");
+
+    // 测试 5: simple-framebuffer
+    println!("test: 5. Testing simple-framebuffer layout...");
+    let mut node = info.framebuffer.clone().expect("framebuffer node");
+    assert_eq!((node.prop_u32("width"), node.prop_str("format")), (Some(640), Some("r5g6b5")));
+    let fb = simple_framebuffer_from_node(&node).expect("RGB565 framebuffer");
+    assert_eq!((fb.addr, fb.format), (0x5000_0000, PixelFormat::Rgb565), "Format from the node, not 32bpp");
+    assert_eq!((fb.stride, fb.size), (1536, 1536 * 480), "Padded stride from the node");
+
+    node.props.retain(|(name, _)| name != "stride");
+    assert_eq!(simple_framebuffer_from_node(&node).map(|fb| fb.stride), Some(640 * 2), "Stride defaults to a packed line");
+    node.props.push(("stride".into(), 1000u32.to_be_bytes().to_vec()));
+    assert!(simple_framebuffer_from_node(&node).is_none(), "Stride shorter than a line rejected");
+    node.props.retain(|(name, _)| name != "stride" && name != "format");
+    node.props.push(("format".into(), b"r8g8b8\0".to_vec()));
+    assert!(simple_framebuffer_from_node(&node).is_none(), "Unsupported format rejected");
+    println!("test:    SUCCESS - 640x480 RGB565, stride {}", fb.stride);
+
+    println!("test: ===== FDT Parser Testing Completed =====");
+}
@@ -0,0 +1,67 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：FileOps::poll 就绪状态查询
+//
+// 测试内容：
+// 1. 空管道：读端无 POLLIN，写端报告 POLLOUT
+// 2. 写入数据后读端报告 POLLIN，读空后消失
+// 3. 管道写满后写端不再报告 POLLOUT，读出后恢复
+// 4. 写端关闭后读端报告 POLLHUP
+// 5. 未实现 poll 的普通文件始终可读可写
+
+use crate::println;
+use crate::fs::create_pipe;
+use crate::fs::file::{File, FileFlags};
+use crate::fs::poll::events::*;
+use alloc::vec;
+
+pub fn test_file_poll() {
+    println!("test: ===== Testing FileOps poll =====");
+
+    // 测试 1: 空管道
+    println!("test: 1. Testing empty pipe readiness...");
+    let (read_end, write_end) = create_pipe();
+    assert_eq!(read_end.poll(POLLIN), 0, "Empty pipe must not report POLLIN");
+    assert_eq!(write_end.poll(POLLOUT), POLLOUT, "Empty pipe must report POLLOUT");
+    println!("test:    SUCCESS - empty pipe is writable only");
+
+    // 测试 2: 有数据时可读
+    println!("test: 2. Testing POLLIN with buffered data...");
+    unsafe { write_end.write(b"ping".as_ptr(), 4) };
+    assert_eq!(read_end.poll(POLLIN), POLLIN, "Pipe with data must report POLLIN");
+    assert_eq!(read_end.poll(POLLOUT), 0, "Read end must never report POLLOUT");
+    let mut buf = [0u8; 8];
+    assert_eq!(unsafe { read_end.read(buf.as_mut_ptr(), buf.len()) }, 4);
+    assert_eq!(read_end.poll(POLLIN), 0, "Drained pipe must not report POLLIN");
+    println!("test:    SUCCESS - POLLIN tracks buffered data");
+
+    // 测试 3: 写满后不可写
+    println!("test: 3. Testing POLLOUT with full buffer...");
+    write_end.flags.set_status_flags(FileFlags::O_NONBLOCK);
+    let chunk = vec![0xA5u8; 20000];
+    let written = unsafe { write_end.write(chunk.as_ptr(), chunk.len()) };
+    assert_eq!(written, 16384, "Write should fill the pipe to capacity");
+    assert_eq!(write_end.poll(POLLOUT), 0, "Full pipe must not report POLLOUT");
+    assert_eq!(unsafe { read_end.read(buf.as_mut_ptr(), buf.len()) }, 8);
+    assert_eq!(write_end.poll(POLLOUT), POLLOUT, "Pipe with free space must report POLLOUT");
+    println!("test:    SUCCESS - POLLOUT tracks free space");
+
+    // 测试 4: 写端关闭后挂断（POLLHUP 不需要请求也会报告）
+    println!("test: 4. Testing POLLHUP after writer close...");
+    let (read_end, write_end) = create_pipe();
+    drop(write_end);
+    assert_eq!(read_end.poll(POLLIN), POLLHUP, "Closed writer must report POLLHUP");
+    println!("test:    SUCCESS - POLLHUP reported after writer close");
+
+    // 测试 5: 普通文件
+    println!("test: 5. Testing regular file readiness...");
+    let file = File::new(FileFlags::new(FileFlags::O_RDWR));
+    assert_eq!(file.poll(POLLIN | POLLOUT), POLLIN | POLLOUT, "Regular file must always be ready");
+    assert_eq!(file.poll(POLLIN), POLLIN, "Only requested events are reported");
+    println!("test:    SUCCESS - regular file always ready");
+
+    println!("test: ===== FileOps poll Testing Completed =====");
+}
@@ -0,0 +1,69 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：framebuffer 像素格式
+//
+// 测试内容：
+// 1. 按 bits_per_pixel 识别像素格式
+// 2. ARGB 与 RGB565 之间的颜色转换
+// 3. RGB565 framebuffer 的 put_pixel/fill_rect 写入 16 位像素
+// 4. simple framebuffer 按格式计算 stride
+
+use crate::println;
+use crate::drivers::gpu::{simple_framebuffer_info, FrameBuffer, FrameBufferInfo, PixelFormat};
+
+const WIDTH: u32 = 4;
+const HEIGHT: u32 = 2;
+
+pub fn test_framebuffer_format() {
+    println!("test: ===== Testing Framebuffer Pixel Format =====");
+
+    println!("test: 1. Testing format detection from bpp...");
+    assert_eq!(PixelFormat::from_bpp(32), Some(PixelFormat::Xrgb8888), "32bpp should be xRGB");
+    assert_eq!(PixelFormat::from_bpp(16), Some(PixelFormat::Rgb565), "16bpp should be RGB565");
+    assert_eq!(PixelFormat::from_bpp(24), None, "24bpp is not supported");
+    assert_eq!(PixelFormat::Rgb565.bytes_per_pixel(), 2, "RGB565 uses 2 bytes per pixel");
+    println!("test:    SUCCESS - formats detected");
+
+    println!("test: 2. Testing ARGB <-> RGB565 conversion...");
+    let f = PixelFormat::Rgb565;
+    assert_eq!(f.encode(0xFFFF0000), 0xF800, "red should map to 0xF800");
+    assert_eq!(f.encode(0xFF00FF00), 0x07E0, "green should map to 0x07E0");
+    assert_eq!(f.encode(0xFF0000FF), 0x001F, "blue should map to 0x001F");
+    assert_eq!(f.decode(0xFFFF), 0xFFFFFFFF, "white should round-trip");
+    assert_eq!(f.decode(0x0000), 0xFF000000, "black should decode opaque");
+    assert_eq!(PixelFormat::Xrgb8888.encode(0x12345678), 0x12345678, "xRGB is passthrough");
+    println!("test:    SUCCESS - colors converted");
+
+    println!("test: 3. Testing RGB565 framebuffer writes...");
+    let mut buf = [0u16; (WIDTH * HEIGHT) as usize];
+    let info = FrameBufferInfo {
+        addr: buf.as_ptr() as u64,
+        size: WIDTH * HEIGHT * 2,
+        width: WIDTH,
+        height: HEIGHT,
+        stride: WIDTH * 2,
+        format: PixelFormat::Rgb565,
+    };
+    // SAFETY: buf 在 fb 使用期间一直有效，且大小与 info 一致
+    let fb = unsafe { FrameBuffer::new(buf.as_mut_ptr() as u64, info) };
+    fb.put_pixel(1, 0, 0xFFFF0000);
+    fb.fill_rect(0, 1, 2, 1, 0xFF0000FF);
+    assert_eq!(fb.get_pixel(1, 0), 0xFFFF0000, "red should read back");
+    assert_eq!(fb.get_pixel(2, 1), 0xFF000000, "untouched pixel stays black");
+    drop(fb);
+    assert_eq!(buf, [0, 0xF800, 0, 0, 0x001F, 0x001F, 0, 0], "pixels should be packed as u16");
+    println!("test:    SUCCESS - RGB565 pixels written");
+
+    println!("test: 4. Testing simple framebuffer layout...");
+    let info = simple_framebuffer_info(0, 640, 480, 16).expect("16bpp should be supported");
+    assert_eq!(info.format, PixelFormat::Rgb565, "format should follow bpp");
+    assert_eq!(info.stride, 1280, "stride should be width * 2");
+    assert_eq!(info.size, 1280 * 480, "size should be stride * height");
+    assert!(simple_framebuffer_info(0, 640, 480, 8).is_none(), "8bpp should be rejected");
+    println!("test:    SUCCESS - layout computed");
+
+    println!("test: ===== Framebuffer Pixel Format Testing Completed =====");
+}
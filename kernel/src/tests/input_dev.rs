@@ -0,0 +1,123 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：输入事件设备 /dev/input0
+//
+// 测试内容：
+// 1. /dev/input0 已登记并可以打开
+// 2. 队列中的事件以完整的 RawInputEvent 结构读出，poll 报告可读
+// 3. 缓冲区放不下一个事件时返回 -EINVAL，只返回能完整放下的事件
+// 4. 队列为空时 poll 不可读，非阻塞读取返回 -EAGAIN
+// 5. 事件队列容量有上限，超出时丢弃最旧的事件
+// 6. EVIOCGREP / EVIOCSREP 读取和设置自动重复的延迟与间隔
+
+use crate::println;
+use crate::drivers::input::evdev::{DEVICE_NAME, EVENT_SIZE, EVIOCGREP, EVIOCSREP};
+use crate::drivers::keyboard::ps2::KeyEvent;
+use crate::errno::Errno;
+use crate::fs::devfs;
+use crate::fs::file::{File, FileFlags};
+use crate::fs::poll::READABLE;
+use crate::input::{self, InputEvent, RawInputEvent, EV_KEY, EV_REL, REL_X, REL_Y};
+
+/// 读取并解析为事件数组
+fn read_events(file: &File, buf: &mut [u8]) -> (isize, alloc::vec::Vec<RawInputEvent>) {
+    let n = unsafe { file.read(buf.as_mut_ptr(), buf.len()) };
+    let events = if n > 0 {
+        buf[..n as usize]
+            .chunks_exact(EVENT_SIZE)
+            .map(|chunk| unsafe { core::ptr::read_unaligned(chunk.as_ptr() as *const RawInputEvent) })
+            .collect()
+    } else {
+        alloc::vec::Vec::new()
+    };
+    (n, events)
+}
+
+pub fn test_input_dev() {
+    println!("test: ===== Testing input event device =====");
+
+    // 测试 1: 设备登记
+    println!("test: 1. Testing /dev/input0 registration...");
+    assert!(devfs::lookup(DEVICE_NAME).is_some(), "input0 is registered by input::init");
+    let file = devfs::open(DEVICE_NAME, FileFlags::O_RDONLY | FileFlags::O_NONBLOCK).expect("open /dev/input0");
+    // 丢弃之前残留的事件
+    while input::get_raw_input_event().is_some() {}
+    println!("test:    SUCCESS - /dev/input0 registered");
+
+    // 测试 2: 读取完整事件
+    println!("test: 2. Testing event reads...");
+    input::push_event(InputEvent::MouseMove { dx: 5, dy: -3 });
+    assert_eq!(file.poll(READABLE), READABLE, "Queued event makes the device readable");
+    let mut buf = [0u8; 4 * EVENT_SIZE];
+    let (n, events) = read_events(&file, &mut buf);
+    assert_eq!(n, (2 * EVENT_SIZE) as isize, "Both motion components are read");
+    assert_eq!((events[0].type_, events[0].code, events[0].value), (EV_REL, REL_X, 5));
+    assert_eq!((events[1].type_, events[1].code, events[1].value), (EV_REL, REL_Y, -3));
+    println!("test:    SUCCESS - events read as RawInputEvent");
+
+    // 测试 3: 缓冲区大小
+    println!("test: 3. Testing short buffers...");
+    input::push_event(InputEvent::Keyboard(KeyEvent::Press(30)));
+    input::push_event(InputEvent::Keyboard(KeyEvent::Release(30)));
+    let mut small = [0u8; EVENT_SIZE - 1];
+    assert_eq!(read_events(&file, &mut small).0, Errno::InvalidArgument.as_neg_i32() as isize,
+               "Buffer smaller than one event");
+    let mut one_and_half = [0u8; EVENT_SIZE + EVENT_SIZE / 2];
+    let (n, events) = read_events(&file, &mut one_and_half);
+    assert_eq!(n, EVENT_SIZE as isize, "Only whole events are returned");
+    assert_eq!((events[0].type_, events[0].code, events[0].value), (EV_KEY, 30, 1));
+    let (n, events) = read_events(&file, &mut one_and_half);
+    assert_eq!(n, EVENT_SIZE as isize, "Remaining event is kept for the next read");
+    assert_eq!((events[0].type_, events[0].code, events[0].value), (EV_KEY, 30, 0));
+    println!("test:    SUCCESS - partial events never returned");
+
+    // 测试 4: 空队列
+    println!("test: 4. Testing empty queue...");
+    assert_eq!(file.poll(READABLE), 0, "Empty queue is not readable");
+    assert_eq!(read_events(&file, &mut buf).0, Errno::TryAgain.as_neg_i32() as isize,
+               "Nonblocking read returns EAGAIN");
+    println!("test:    SUCCESS - empty queue would block");
+
+    // 测试 5: 队列容量
+    println!("test: 5. Testing bounded event queue...");
+    let mut queue = alloc::collections::VecDeque::new();
+    for dx in 0..3 {
+        assert!(input::push_bounded(&mut queue, InputEvent::MouseMove { dx, dy: 0 }, 3).is_none());
+    }
+    let dropped = input::push_bounded(&mut queue, InputEvent::MouseMove { dx: 3, dy: 0 }, 3);
+    assert!(matches!(dropped, Some(InputEvent::MouseMove { dx: 0, .. })), "Oldest event is dropped");
+    assert_eq!(queue.len(), 3, "Queue stays at its capacity");
+
+    let extra = 10;
+    for dx in 0..(input::EVENT_QUEUE_CAP + extra) as i16 {
+        input::push_event(InputEvent::MouseMove { dx, dy: 0 });
+    }
+    assert!(matches!(input::poll_event(), Some(InputEvent::MouseMove { dx, .. }) if dx == extra as i16),
+            "First remaining event is the oldest one kept");
+    let mut kept = 1;
+    while input::poll_event().is_some() {
+        kept += 1;
+    }
+    assert_eq!(kept, input::EVENT_QUEUE_CAP, "Global queue holds at most the cap");
+    println!("test:    SUCCESS - queue capped, oldest events dropped");
+
+    // 测试 6: 自动重复设置
+    println!("test: 6. Testing EVIOCGREP/EVIOCSREP...");
+    let mut rep = [0u32; 2];
+    assert_eq!(file.ioctl(EVIOCGREP, rep.as_mut_ptr() as usize), 0);
+    assert_eq!(rep, [input::DEFAULT_REPEAT_DELAY_MS as u32, input::DEFAULT_REPEAT_INTERVAL_MS as u32]);
+    let new_rate = [240u32, 40];
+    assert_eq!(file.ioctl(EVIOCSREP, new_rate.as_ptr() as usize), 0);
+    assert_eq!(input::key_repeat(), (240, 40), "Rate applied to the key repeat state");
+    assert_eq!(file.ioctl(EVIOCGREP, rep.as_mut_ptr() as usize), 0);
+    assert_eq!(rep, new_rate);
+    assert_eq!(file.ioctl(EVIOCGREP, 0), Errno::BadAddress.as_neg_i32() as isize);
+    assert_eq!(file.ioctl(0x5401, 0), Errno::NotATypewriter.as_neg_i32() as isize, "TCGETS is not an evdev command");
+    input::set_key_repeat(input::DEFAULT_REPEAT_DELAY_MS, input::DEFAULT_REPEAT_INTERVAL_MS);
+    println!("test:    SUCCESS - repeat {}ms/{}ms", new_rate[0], new_rate[1]);
+
+    println!("test: ===== Input Event Device Testing Completed =====");
+}
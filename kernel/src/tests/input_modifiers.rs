@@ -0,0 +1,52 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：修饰键与键盘 LED 状态
+//
+// 测试内容：
+// 1. 按下/释放 Shift 更新 shift 状态
+// 2. CapsLock 按下翻转 caps 并请求更新 LED，按住时的重复不再翻转
+// 3. 多个锁定键组合成 LED 位掩码
+
+use crate::println;
+use crate::drivers::keyboard::ps2::{led, scancode, KeyEvent};
+use crate::input::{ModifierState, Modifiers};
+
+pub fn test_input_modifiers() {
+    println!("test: ===== Testing Input Modifiers =====");
+
+    let mut state = ModifierState::new();
+    assert_eq!(state.modifiers(), Modifiers::default(), "All modifiers start released");
+
+    // 测试 1: Shift
+    println!("test: 1. Testing Shift press/release...");
+    assert!(!state.apply(KeyEvent::Press(scancode::KEY_LSHIFT)), "Shift does not touch LEDs");
+    assert!(state.modifiers().shift, "Shift should be down");
+    state.apply(KeyEvent::Release(scancode::KEY_LSHIFT));
+    assert!(!state.modifiers().shift, "Shift should be up after release");
+    println!("test:    SUCCESS - shift tracked");
+
+    // 测试 2: CapsLock
+    println!("test: 2. Testing CapsLock toggle...");
+    assert!(state.apply(KeyEvent::Press(scancode::KEY_CAPSLOCK)), "CapsLock should request an LED update");
+    assert!(state.modifiers().caps, "Caps should be on");
+    assert!(!state.apply(KeyEvent::Press(scancode::KEY_CAPSLOCK)), "Auto-repeat must not toggle again");
+    assert!(state.modifiers().caps);
+    assert!(!state.apply(KeyEvent::Release(scancode::KEY_CAPSLOCK)), "Release does not toggle");
+    assert!(state.apply(KeyEvent::Press(scancode::KEY_CAPSLOCK)));
+    assert!(!state.modifiers().caps, "Second press turns caps off");
+    state.apply(KeyEvent::Release(scancode::KEY_CAPSLOCK));
+    println!("test:    SUCCESS - caps toggled");
+
+    // 测试 3: LED 位掩码
+    println!("test: 3. Testing LED mask...");
+    state.apply(KeyEvent::Press(scancode::KEY_CAPSLOCK));
+    state.apply(KeyEvent::Press(scancode::KEY_NUMLOCK));
+    assert_eq!(state.modifiers().leds(), led::CAPS_LOCK | led::NUM_LOCK, "Caps and Num LEDs should be lit");
+    assert!(!state.modifiers().scroll);
+    println!("test:    SUCCESS - LED mask built");
+
+    println!("test: ===== Input Modifiers Testing Completed =====");
+}
@@ -0,0 +1,84 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：FileOps::ioctl 分发
+//
+// 测试内容：
+// 1. 设备自定义的 ioctl 收到正确的 cmd 和 arg，返回值原样传回
+// 2. 未实现 ioctl 的文件对任何命令返回 -ENOTTY
+// 3. 设备不认识的命令返回 -ENOTTY
+// 4. UART 终端的 TIOCGWINSZ 通过文件分发
+
+use crate::println;
+use crate::errno::Errno;
+use crate::fs::file::{File, FileFlags, FileOps, REG_FILE_OPS};
+use crate::fs::char_dev::UART_OPS;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+const TEST_IOCTL_CMD: u32 = 0xAB01;
+
+static LAST_CMD: AtomicU32 = AtomicU32::new(0);
+static LAST_ARG: AtomicUsize = AtomicUsize::new(0);
+
+/// 只认识 TEST_IOCTL_CMD 的测试设备
+fn test_dev_ioctl(_file: &File, cmd: u32, arg: usize) -> isize {
+    LAST_CMD.store(cmd, Ordering::SeqCst);
+    LAST_ARG.store(arg, Ordering::SeqCst);
+    match cmd {
+        TEST_IOCTL_CMD => 42,
+        _ => Errno::NotATypewriter.as_neg_i32() as isize,
+    }
+}
+
+static TEST_DEV_OPS: FileOps = FileOps {
+    read: None,
+    write: None,
+    lseek: None,
+    close: None,
+    poll: None,
+    ioctl: Some(test_dev_ioctl),
+    mmap: None,
+};
+
+pub fn test_ioctl() {
+    println!("test: ===== Testing FileOps ioctl =====");
+    let enotty = Errno::NotATypewriter.as_neg_i32() as isize;
+
+    // 测试 1: 自定义 ioctl
+    println!("test: 1. Testing custom device ioctl...");
+    let mut dev = File::new(FileFlags::new(FileFlags::O_RDWR));
+    dev.set_ops(&TEST_DEV_OPS);
+    assert_eq!(dev.ioctl(TEST_IOCTL_CMD, 0x1234_5678), 42, "Device result should be returned");
+    assert_eq!(LAST_CMD.load(Ordering::SeqCst), TEST_IOCTL_CMD, "Device should see the cmd");
+    assert_eq!(LAST_ARG.load(Ordering::SeqCst), 0x1234_5678, "Device should see the arg");
+    println!("test:    SUCCESS - custom ioctl invoked with cmd/arg");
+
+    // 测试 2: 没有 ioctl 的文件
+    println!("test: 2. Testing file without ioctl...");
+    let mut reg = File::new(FileFlags::new(FileFlags::O_RDWR));
+    reg.set_ops(&REG_FILE_OPS);
+    assert_eq!(reg.ioctl(TEST_IOCTL_CMD, 0), enotty, "Regular file should return -ENOTTY");
+    let bare = File::new(FileFlags::new(FileFlags::O_RDWR));
+    assert_eq!(bare.ioctl(0x5401, 0), enotty, "File without ops should return -ENOTTY");
+    println!("test:    SUCCESS - missing ioctl returns -ENOTTY");
+
+    // 测试 3: 设备不认识的命令
+    println!("test: 3. Testing unhandled device command...");
+    assert_eq!(dev.ioctl(0xAB02, 7), enotty, "Unknown cmd should return -ENOTTY");
+    assert_eq!(LAST_CMD.load(Ordering::SeqCst), 0xAB02, "Unknown cmd still reaches the device");
+    println!("test:    SUCCESS - unhandled cmd returns -ENOTTY");
+
+    // 测试 4: UART 终端
+    println!("test: 4. Testing UART TIOCGWINSZ...");
+    let mut tty = File::new(FileFlags::new(FileFlags::O_RDWR));
+    tty.set_ops(&UART_OPS);
+    let mut winsize = [0u16; 4];
+    assert_eq!(tty.ioctl(0x5413, winsize.as_mut_ptr() as usize), 0, "TIOCGWINSZ should succeed");
+    assert_eq!((winsize[0], winsize[1]), (25, 80), "Window size should be 25x80");
+    assert_eq!(tty.ioctl(TEST_IOCTL_CMD, 0), enotty, "Non-TTY cmd on UART should return -ENOTTY");
+    println!("test:    SUCCESS - UART ioctls dispatched through FileOps");
+
+    println!("test: ===== FileOps ioctl Testing Completed =====");
+}
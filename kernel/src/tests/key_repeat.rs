@@ -0,0 +1,94 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：按键自动重复
+//
+// 测试内容：
+// 1. 按住超过延迟后按间隔产生重复的 Press
+// 2. 释放后停止重复
+// 3. 修饰键不重复，按下其他键时重复切换到新键
+// 4. 下一次重复的时间随按下、重复和释放更新，按住键时设置唤醒读者的延迟工作
+
+use crate::println;
+use crate::drivers::keyboard::ps2::{scancode, KeyEvent};
+use crate::input::{self, InputEvent, KeyRepeat};
+
+const DELAY: u64 = 5;
+const INTERVAL: u64 = 2;
+
+/// 从 `from` 到 `to`（不含）逐 tick 推进，返回产生重复的时刻
+fn run_ticks(repeat: &mut KeyRepeat, from: u64, to: u64) -> ([u64; 8], usize) {
+    let mut at = [0u64; 8];
+    let mut n = 0;
+    for now in from..to {
+        if let Some(KeyEvent::Press(code)) = repeat.tick(now) {
+            assert_eq!(Some(code), repeat.held_key(), "Repeats are for the held key");
+            at[n] = now;
+            n += 1;
+        }
+    }
+    (at, n)
+}
+
+pub fn test_key_repeat() {
+    println!("test: ===== Testing Key Auto-Repeat =====");
+
+    let mut repeat = KeyRepeat::new(DELAY, INTERVAL);
+
+    // 测试 1: 延迟与间隔
+    println!("test: 1. Testing delay and interval...");
+    repeat.on_key(KeyEvent::Press(scancode::KEY_A), 100);
+    let (at, n) = run_ticks(&mut repeat, 100, 112);
+    assert_eq!(n, 4, "Repeats at 105, 107, 109, 111");
+    assert_eq!(&at[..n], &[105, 107, 109, 111], "First repeat after the delay, then every interval");
+    println!("test:    SUCCESS - repeats follow the configured rate");
+
+    // 测试 2: 释放
+    println!("test: 2. Testing release stops repeat...");
+    repeat.on_key(KeyEvent::Release(scancode::KEY_A), 112);
+    let (_, n) = run_ticks(&mut repeat, 112, 130);
+    assert_eq!(n, 0, "No repeats after release");
+    assert_eq!(repeat.held_key(), None);
+    println!("test:    SUCCESS - release stops repeat");
+
+    // 测试 3: 修饰键与换键
+    println!("test: 3. Testing modifiers and key switching...");
+    repeat.on_key(KeyEvent::Press(scancode::KEY_LSHIFT), 200);
+    assert_eq!(run_ticks(&mut repeat, 200, 220).1, 0, "Shift must not repeat");
+    repeat.on_key(KeyEvent::Press(scancode::KEY_A), 220);
+    repeat.on_key(KeyEvent::Press(scancode::KEY_B), 222);
+    assert_eq!(repeat.held_key(), Some(scancode::KEY_B), "The newest key repeats");
+    repeat.on_key(KeyEvent::Release(scancode::KEY_A), 223);
+    let (at, n) = run_ticks(&mut repeat, 222, 228);
+    assert_eq!(&at[..n], &[227], "Releasing the old key keeps the new one repeating");
+    println!("test:    SUCCESS - modifiers excluded");
+
+    // 测试 4: 重复定时
+    println!("test: 4. Testing repeat deadline and timer...");
+    let mut repeat = KeyRepeat::new(DELAY, INTERVAL);
+    assert_eq!(repeat.next_due(), None, "Nothing held");
+    repeat.on_key(KeyEvent::Press(scancode::KEY_A), 300);
+    assert_eq!(repeat.next_due(), Some(300 + DELAY), "First repeat after the delay");
+    assert!(repeat.tick(300 + DELAY).is_some());
+    assert_eq!(repeat.next_due(), Some(300 + DELAY + INTERVAL), "Then every interval");
+    repeat.on_key(KeyEvent::Release(scancode::KEY_A), 306);
+    assert_eq!(repeat.next_due(), None, "Release clears the deadline");
+
+    #[cfg(feature = "riscv64")]
+    {
+        use crate::drivers::timer::delayed_work::cancel_delayed_work;
+
+        while input::poll_event().is_some() {}
+        input::push_event(InputEvent::Keyboard(KeyEvent::Press(scancode::KEY_A)));
+        while input::poll_event().is_some() {}
+        assert!(cancel_delayed_work(input::key_repeat_work), "Holding a key arms the repeat timer");
+        input::push_event(InputEvent::Keyboard(KeyEvent::Release(scancode::KEY_A)));
+        while input::poll_event().is_some() {}
+        assert!(!cancel_delayed_work(input::key_repeat_work), "Release leaves no repeat timer");
+    }
+    println!("test:    SUCCESS - repeat timer follows the held key");
+
+    println!("test: ===== Key Auto-Repeat Testing Completed =====");
+}
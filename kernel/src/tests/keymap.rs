@@ -0,0 +1,87 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：US 键盘布局
+//
+// 测试内容：
+// 1. 无修饰键时字母、数字、标点和特殊键翻译为对应的 ASCII
+// 2. Shift 选择上档字符，释放后恢复
+// 3. CapsLock 只影响字母，与 Shift 同时生效时相互抵消
+// 4. Ctrl+字母 产生控制字符，释放、修饰键和未知扫描码不产生字符
+// 5. poll_event 在按键事件之后返回解析出的 Char 事件
+
+use crate::println;
+use crate::drivers::keyboard::ps2::{scancode, KeyEvent};
+use crate::input::keymap::{translate, Keymap};
+use crate::input::{self, InputEvent, Modifiers};
+
+/// 依次按下并释放扫描码，返回产生的字符
+fn type_keys(keymap: &mut Keymap, codes: &[u16]) -> alloc::vec::Vec<u8> {
+    let mut out = alloc::vec::Vec::new();
+    for &code in codes {
+        out.extend(keymap.feed(KeyEvent::Press(code)));
+        assert_eq!(keymap.feed(KeyEvent::Release(code)), None, "Release produces no character");
+    }
+    out
+}
+
+pub fn test_keymap() {
+    println!("test: ===== Testing US keymap =====");
+
+    let mut keymap = Keymap::new();
+
+    // 测试 1: 无修饰键
+    println!("test: 1. Testing unmodified keys...");
+    // h, i, 1, '-', '/', space, Enter, Backspace, Tab, Esc
+    let typed = type_keys(&mut keymap, &[0x23, 0x17, 0x02, 0x0C, 0x35, 0x39, 0x1C, 0x0E, 0x0F, 0x01]);
+    assert_eq!(&typed[..], b"hi1-/ \r\x08\t\x1b");
+    println!("test:    SUCCESS - base layer translated");
+
+    // 测试 2: Shift
+    println!("test: 2. Testing Shift...");
+    keymap.feed(KeyEvent::Press(scancode::KEY_LSHIFT));
+    let typed = type_keys(&mut keymap, &[0x23, 0x02, 0x0C, 0x35, 0x28]);
+    assert_eq!(&typed[..], b"H!_?\"", "Shift selects the upper symbols");
+    keymap.feed(KeyEvent::Release(scancode::KEY_LSHIFT));
+    assert_eq!(type_keys(&mut keymap, &[0x23]), b"h", "Releasing Shift restores lowercase");
+    println!("test:    SUCCESS - shift layer translated");
+
+    // 测试 3: CapsLock
+    println!("test: 3. Testing CapsLock...");
+    type_keys(&mut keymap, &[scancode::KEY_CAPSLOCK]);
+    assert!(keymap.modifiers().caps, "CapsLock toggled on");
+    assert_eq!(type_keys(&mut keymap, &[0x23, 0x02]), b"H1", "Caps affects letters only");
+    keymap.feed(KeyEvent::Press(scancode::KEY_RSHIFT));
+    assert_eq!(type_keys(&mut keymap, &[0x23, 0x02]), b"h!", "Shift cancels Caps for letters");
+    keymap.feed(KeyEvent::Release(scancode::KEY_RSHIFT));
+    type_keys(&mut keymap, &[scancode::KEY_CAPSLOCK]);
+    assert_eq!(type_keys(&mut keymap, &[0x23]), b"h", "CapsLock toggled off");
+    println!("test:    SUCCESS - caps lock applied to letters");
+
+    // 测试 4: Ctrl 和无字符的键
+    println!("test: 4. Testing Ctrl and keys without characters...");
+    keymap.feed(KeyEvent::Press(scancode::KEY_LCTRL));
+    assert_eq!(type_keys(&mut keymap, &[0x2E, 0x20]), [0x03, 0x04], "Ctrl+C and Ctrl+D");
+    keymap.feed(KeyEvent::Release(scancode::KEY_LCTRL));
+    assert_eq!(keymap.feed(KeyEvent::Press(scancode::KEY_LALT)), None, "Modifiers have no character");
+    keymap.feed(KeyEvent::Release(scancode::KEY_LALT));
+    let none = Modifiers::default();
+    assert_eq!(translate(0x3B, &none), None, "F1 has no character");
+    assert_eq!(translate(0x1FF, &none), None, "Out-of-range scancode");
+    println!("test:    SUCCESS - control characters and non-character keys handled");
+
+    // 测试 5: Char 事件
+    println!("test: 5. Testing Char events from poll_event...");
+    while input::poll_event().is_some() {}
+    input::push_event(InputEvent::Keyboard(KeyEvent::Press(0x1E)));
+    input::push_event(InputEvent::Keyboard(KeyEvent::Release(0x1E)));
+    assert!(matches!(input::poll_event(), Some(InputEvent::Keyboard(KeyEvent::Press(0x1E)))));
+    assert!(matches!(input::poll_event(), Some(InputEvent::Char(b'a'))), "Char follows the key press");
+    assert!(matches!(input::poll_event(), Some(InputEvent::Keyboard(KeyEvent::Release(0x1E)))));
+    assert!(input::poll_event().is_none(), "Release produces no Char");
+    println!("test:    SUCCESS - Char event emitted after the key press");
+
+    println!("test: ===== US Keymap Testing Completed =====");
+}
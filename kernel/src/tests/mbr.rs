@@ -0,0 +1,201 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：MBR 分区表解析
+//
+// 测试内容：
+// 1. 解析合成的 MBR，得到正确的分区号、类型和起始扇区
+// 2. 空表项和扩展分区被跳过
+// 3. 签名错误时拒绝解析
+// 4. 超出磁盘的分区被截断或跳过
+// 5. 分区上的读写加上起始扇区后交给整个磁盘，越界请求被拒绝
+// 6. add_disk 把磁盘和分区登记为 /dev/vdX、/dev/vdXN，ext4 通过分区设备名挂载；
+//    卸载时写回分区上的脏缓冲区；sync 写回后刷新整个磁盘的写缓存
+
+use crate::println;
+use crate::drivers::blkdev::mbr::{clamp_to_disk, parse_mbr, part_type, SECTOR_SIZE};
+use crate::drivers::blkdev::{self, blkdev_read, blkdev_write, GenDisk, ReqCmd, Request};
+use crate::errno::Errno;
+use crate::fs::ext4::superblock::{Ext4GroupDesc, Ext4SuperBlockOnDisk};
+use crate::fs::ext4::{Ext4FileSystem, EXT4_SUPER_MAGIC};
+use crate::fs::{bio, devfs, superblock};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+use spin::Mutex;
+
+/// 模拟磁盘收到的最后一个扇区号
+static LAST_SECTOR: AtomicU64 = AtomicU64::new(u64::MAX);
+
+unsafe extern "C" fn record_request(req: &mut Request) {
+    LAST_SECTOR.store(req.sector, Ordering::Relaxed);
+}
+
+/// 带 ext4 分区的模拟磁盘内容
+static RAM: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+/// 模拟磁盘收到的 Flush 请求数
+static FLUSHES: AtomicU64 = AtomicU64::new(0);
+
+unsafe extern "C" fn ram_request(req: &mut Request) {
+    let start = req.sector as usize * SECTOR_SIZE;
+    let len = req.buffer.len();
+    let mut ram = RAM.lock();
+    match req.cmd_type {
+        ReqCmd::Read => req.buffer.copy_from_slice(&ram[start..start + len]),
+        ReqCmd::Write => ram[start..start + len].copy_from_slice(&req.buffer),
+        ReqCmd::Flush => {
+            FLUSHES.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 把结构体按字节复制到 `dst`
+fn put<T>(dst: &mut [u8], value: &T) {
+    // SAFETY: T 是 repr(C) 的磁盘结构
+    let bytes = unsafe { core::slice::from_raw_parts(value as *const T as *const u8, core::mem::size_of::<T>()) };
+    dst[..bytes.len()].copy_from_slice(bytes);
+}
+
+/// 在合成的扇区 0 中写入一个分区表项
+fn set_entry(sector: &mut [u8], index: usize, boot: u8, kind: u8, start: u32, len: u32) {
+    let entry = &mut sector[0x1BE + index * 16..][..16];
+    entry[0] = boot;
+    entry[4] = kind;
+    entry[8..12].copy_from_slice(&start.to_le_bytes());
+    entry[12..16].copy_from_slice(&len.to_le_bytes());
+}
+
+pub fn test_mbr() {
+    println!("test: ===== Testing MBR partition parsing =====");
+
+    let mut sector = [0u8; SECTOR_SIZE];
+    sector[0x1FE] = 0x55;
+    sector[0x1FF] = 0xAA;
+    set_entry(&mut sector, 0, 0x80, part_type::LINUX, 2048, 204800);
+    set_entry(&mut sector, 2, 0x00, part_type::DOS_EXTENDED, 206848, 4096);
+    set_entry(&mut sector, 3, 0x00, 0x0C, 210944, 8192);
+
+    // 测试 1: 分区偏移
+    println!("test: 1. Testing partition offsets...");
+    let parts = parse_mbr(&sector).expect("valid MBR");
+    assert_eq!(parts.len(), 2, "Two primary data partitions");
+    assert_eq!((parts[0].partno, parts[0].part_type), (1, part_type::LINUX));
+    assert!(parts[0].bootable, "Active flag parsed");
+    assert_eq!((parts[0].start_lba, parts[0].num_sectors), (2048, 204800));
+    assert_eq!((parts[1].partno, parts[1].start_lba, parts[1].num_sectors), (4, 210944, 8192));
+    assert!(!parts[1].bootable);
+    println!("test:    SUCCESS - partitions at LBA {} and {}", parts[0].start_lba, parts[1].start_lba);
+
+    // 测试 2: 空表项与扩展分区
+    println!("test: 2. Testing empty and extended entries...");
+    assert!(parts.iter().all(|p| p.partno != 2 && p.partno != 3), "Empty and extended entries skipped");
+    println!("test:    SUCCESS - only primary data partitions registered");
+
+    // 测试 3: 签名
+    println!("test: 3. Testing signature check...");
+    sector[0x1FF] = 0x00;
+    assert!(parse_mbr(&sector).is_err(), "Missing 0x55AA signature rejected");
+    assert!(parse_mbr(&sector[..100]).is_err(), "Short buffer rejected");
+    println!("test:    SUCCESS - invalid MBR rejected");
+
+    // 测试 4: 磁盘容量
+    println!("test: 4. Testing partitions past the end of the disk...");
+    assert_eq!(clamp_to_disk(&parts[0], 1 << 20), Some((2048, 204800)), "Partition inside the disk kept");
+    assert_eq!(clamp_to_disk(&parts[0], 4096), Some((2048, 2048)), "Partition truncated at the disk end");
+    assert_eq!(clamp_to_disk(&parts[1], 210944), None, "Partition starting past the end skipped");
+    println!("test:    SUCCESS - partitions clamped to the disk");
+
+    // 测试 5: 扇区重映射
+    println!("test: 5. Testing sector remapping on a partition...");
+    let mut whole = GenDisk::new("mockblk", 250, 5, 512, None);
+    whole.set_capacity(1 << 20);
+    whole.set_request_fn(record_request);
+    let part = unsafe { GenDisk::new_partition(&whole, 1, 2048, 4096) };
+    assert!(part.is_partition() && !whole.is_partition());
+    assert_eq!((part.major, part.first_minor, part.get_capacity()), (250, 1, 4096));
+    let mut buf = [0u8; 1024];
+    assert_eq!(blkdev_read(&part, 10, &mut buf), Ok(1024));
+    assert_eq!(LAST_SECTOR.load(Ordering::Relaxed), 2058, "Read offset by the partition start");
+    assert_eq!(blkdev_write(&part, 0, &buf), Ok(1024));
+    assert_eq!(LAST_SECTOR.load(Ordering::Relaxed), 2048, "Write offset by the partition start");
+    assert_eq!(blkdev_read(&part, 4095, &mut buf), Err(-5), "Request past the partition end rejected");
+    assert_eq!(LAST_SECTOR.load(Ordering::Relaxed), 2048, "Rejected request never reaches the disk");
+    assert_eq!(blkdev_read(&whole, 10, &mut buf), Ok(1024));
+    assert_eq!(LAST_SECTOR.load(Ordering::Relaxed), 10, "Whole disk is not remapped");
+    println!("test:    SUCCESS - partition I/O remapped to the whole disk");
+
+    // 测试 6: 通过分区挂载
+    println!("test: 6. Testing ext4 mount through a partition node...");
+    const PART_START: u32 = 8;
+    const PART_BLOCKS: u32 = 16;
+    let mut ram = vec![0u8; (PART_START as usize + PART_BLOCKS as usize * 8) * SECTOR_SIZE];
+    ram[0x1FE] = 0x55;
+    ram[0x1FF] = 0xAA;
+    set_entry(&mut ram, 0, 0x00, part_type::LINUX, PART_START, PART_BLOCKS * 8);
+    let part_base = PART_START as usize * SECTOR_SIZE;
+    let sb = Ext4SuperBlockOnDisk {
+        s_inodes_count: 64,
+        s_blocks_count: PART_BLOCKS,
+        s_log_block_size: 2,
+        s_blocks_per_group: PART_BLOCKS,
+        s_inodes_per_group: 64,
+        s_magic: EXT4_SUPER_MAGIC,
+        s_inode_size: 256,
+        ..Ext4SuperBlockOnDisk::default()
+    };
+    put(&mut ram[part_base + 1024..], &sb);
+    let gd = Ext4GroupDesc { bg_block_bitmap: 2, bg_inode_bitmap: 3, bg_inode_table: 4, ..Ext4GroupDesc::default() };
+    put(&mut ram[part_base + 4096..], &gd);
+    *RAM.lock() = ram;
+
+    let disk: &'static mut GenDisk = Box::leak(Box::new(GenDisk::new("ramblk", 251, 5, 512, None)));
+    disk.set_capacity(PART_START + PART_BLOCKS * 8);
+    disk.set_request_fn(ram_request);
+    let name = blkdev::add_disk(disk);
+    let part_name = format!("{}1", name);
+    assert_eq!(devfs::lookup_block_device(&name), Some(&*disk as *const GenDisk), "Whole disk node");
+    let part = devfs::lookup_block_device(&part_name).expect("partition node registered");
+    assert_eq!(blkdev::get_partition(251, 1), Some(part));
+    assert_eq!(unsafe { ((*part).start_sect, (*part).get_capacity()) }, (PART_START as u64, PART_BLOCKS * 8));
+
+    let ext4 = superblock::get_fs_type("ext4").expect("ext4 registered at boot");
+    let source = format!("/dev/{}", part_name);
+    let vfs_sb = unsafe { ext4.mount_fs(Some(&source), Some("/mnt"), 0) }.expect("mount the partition");
+    let fs = unsafe { &*((*vfs_sb).s_fs_info.expect("ext4 instance") as *const Ext4FileSystem) };
+    assert_eq!(fs.device, part, "Filesystem reads through the partition");
+    assert_eq!((fs.block_size, fs.total_blocks, fs.group_descs[0].bg_inode_table), (4096, PART_BLOCKS as u64, 4));
+    assert!(fs.read_only, "Mounted read-only by default");
+
+    let bh = bio::bread(part, 6).expect("partition block 6");
+    unsafe { (&mut (*bh).b_data)[0] = 0x5A };
+    bio::bwrite(bh);
+    bio::brelse(bh);
+    unsafe { ext4.kill_super(vfs_sb) };
+    let offset = (PART_START as usize + 6 * 8) * SECTOR_SIZE;
+    assert_eq!(RAM.lock()[offset], 0x5A, "umount writes dirty buffers back through the partition");
+
+    let bh = bio::bread(part, 6).expect("partition block 6");
+    unsafe { (&mut (*bh).b_data)[0] = 0xA5 };
+    bio::bwrite(bh);
+    bio::brelse(bh);
+    let flushes = FLUSHES.load(Ordering::Relaxed);
+    assert_eq!(bio::sync_buffers(), Ok(()));
+    assert_eq!(RAM.lock()[offset], 0xA5, "sync writes dirty buffers back");
+    assert_eq!(FLUSHES.load(Ordering::Relaxed), flushes + 1, "sync flushes the whole disk once");
+
+    let whole = format!("/dev/{}", name);
+    assert!(unsafe { ext4.mount_fs(Some(&whole), Some("/mnt"), 0) }.is_err(), "Whole disk has no superblock at 1024");
+    assert_eq!(
+        unsafe { ext4.mount_fs(Some("/dev/nonexistent"), Some("/mnt"), 0) }.err(),
+        Some(Errno::NoSuchFileOrDirectory.as_neg_i32())
+    );
+    bio::invalidate_device(part);
+    bio::invalidate_device(disk);
+    println!("test:    SUCCESS - ext4 mounted from {}", source);
+
+    println!("test: ===== MBR Testing Completed =====");
+}
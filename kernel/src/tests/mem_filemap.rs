@@ -0,0 +1,74 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：文件映射 (MAP_SHARED / MAP_PRIVATE)
+//
+// 测试内容：
+// 1. 缺页填充反映文件内容，超出文件末尾的部分清零
+// 2. 共享映射写入在 msync 写回后可通过 read() 看到
+// 3. 私有映射写入不会写回文件
+// 4. 写回不会扩展文件大小
+
+use crate::println;
+use crate::fs::file::{File, FileFlags, REG_FILE_OPS};
+use crate::fs::inode;
+use crate::mm::filemap::FileMapping;
+use crate::mm::page::PAGE_SIZE;
+use alloc::sync::Arc;
+use alloc::vec;
+
+/// 通过文件对象从头读取（模拟 msync 之后的 read()）
+fn read_through_file(inode: &Arc<inode::Inode>, buf: &mut [u8]) -> isize {
+    let mut file = File::new(FileFlags::new(FileFlags::O_RDONLY));
+    file.set_inode(inode.clone());
+    file.set_ops(&REG_FILE_OPS);
+    unsafe { file.read(buf.as_mut_ptr(), buf.len()) }
+}
+
+pub fn test_filemap() {
+    println!("test: ===== Testing file-backed mmap =====");
+
+    // 测试 1: 缺页填充
+    println!("test: 1. Testing page fill from page cache...");
+    let inode = Arc::new(inode::make_reg_inode_with_data(900, b"hello mmap"));
+    let shared = FileMapping::new(inode.clone(), true);
+    let mut page = vec![0xAAu8; PAGE_SIZE];
+    let filled = shared.fill_page(0, &mut page);
+    assert_eq!(filled, 10, "Should fill file-sized prefix");
+    assert_eq!(&page[..10], b"hello mmap", "Mapping should reflect file contents");
+    assert!(page[10..].iter().all(|&b| b == 0), "Tail past EOF must be zeroed");
+    println!("test:    SUCCESS - mapped page reflects file contents");
+
+    // 测试 2: 共享映射写回
+    println!("test: 2. Testing MAP_SHARED write-back on msync...");
+    page[..5].copy_from_slice(b"HELLO");
+    let written = shared.write_back_page(0, &page);
+    assert_eq!(written, 10, "Write-back should cover the file-sized prefix");
+    let mut buf = [0u8; 16];
+    let n = read_through_file(&inode, &mut buf);
+    assert_eq!(n, 10);
+    assert_eq!(&buf[..10], b"HELLO mmap", "read() after msync should see shared write");
+    println!("test:    SUCCESS - shared write visible through read()");
+
+    // 测试 3: 私有映射不写回
+    println!("test: 3. Testing MAP_PRIVATE writes stay local...");
+    let private = FileMapping::new(inode.clone(), false);
+    let mut private_page = vec![0u8; PAGE_SIZE];
+    private.fill_page(0, &mut private_page);
+    assert_eq!(&private_page[..10], b"HELLO mmap", "Private mapping starts from file contents");
+    private_page[..5].copy_from_slice(b"xxxxx");
+    assert_eq!(private.write_back_page(0, &private_page), 0, "Private mapping must not write back");
+    let n = read_through_file(&inode, &mut buf);
+    assert_eq!(&buf[..n as usize], b"HELLO mmap", "Private write must not reach the file");
+    println!("test:    SUCCESS - private write not visible through read()");
+
+    // 测试 4: 写回不扩展文件
+    println!("test: 4. Testing write-back does not extend the file...");
+    assert_eq!(shared.write_back_page(PAGE_SIZE, &page), 0, "Page past EOF must not be written");
+    assert_eq!(inode.get_size(), 10, "File size must be unchanged");
+    println!("test:    SUCCESS - file size unchanged by write-back");
+
+    println!("test: ===== File-backed mmap Testing Completed =====");
+}
@@ -0,0 +1,70 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：物理地址空间区域登记表
+//
+// 测试内容：
+// 1. 嵌套在容器中的区域可以登记
+// 2. 重叠区域的登记被拒绝 (EBUSY)
+// 3. find_free_region 跳过已登记的区域
+// 4. MMIO 动态分配只在 MMIO 窗口中进行
+
+use crate::println;
+use crate::errno::Errno;
+use crate::mm::region::{RegionKind, RegionMap};
+
+pub fn test_mem_region() {
+    println!("test: ===== Testing memory region map =====");
+
+    let mut map = RegionMap::new();
+
+    // 测试 1: 登记容器及嵌套区域
+    println!("test: 1. Testing nested reservations...");
+    assert!(map.reserve(0x8000_0000, 0x100_0000, RegionKind::Ram, "RAM").is_ok());
+    assert!(map.reserve(0x8000_0000, 0x20_0000, RegionKind::Kernel, "kernel").is_ok());
+    assert!(map.reserve(0x8020_0000, 0x40_0000, RegionKind::Heap, "heap").is_ok());
+    assert!(map.reserve(0x8030_0000, 0x1000, RegionKind::Framebuffer, "fb-in-heap").is_ok());
+    assert!(map.reserve(0x4000_0000, 0x10_0000, RegionKind::MmioWindow, "window").is_ok());
+    assert!(map.reserve(0x1000_0000, 0x100, RegionKind::Mmio, "uart").is_ok());
+    println!("test:    SUCCESS - containers and nested regions reserved");
+
+    // 测试 2: 重叠区域被拒绝
+    println!("test: 2. Testing overlapping reservations are rejected...");
+    let busy = Errno::DeviceOrResourceBusy.as_neg_i32();
+    assert_eq!(map.reserve(0x8010_0000, 0x20_0000, RegionKind::Dma, "dma"), Err(busy),
+               "DMA buffer straddling kernel and heap must be rejected");
+    assert_eq!(map.reserve(0x1000_0080, 0x100, RegionKind::Mmio, "uart2"), Err(busy),
+               "Overlapping MMIO must be rejected");
+    assert_eq!(map.reserve(0x80F0_0000, 0x20_0000, RegionKind::Reserved, "past-ram"), Err(busy),
+               "Region sticking out of RAM must be rejected");
+    assert_eq!(map.reserve(0x8000_0000, 0x1000, RegionKind::Ram, "ram2"), Err(busy),
+               "RAM cannot nest inside RAM");
+    assert_eq!(map.reserve(0x9000_0000, 0, RegionKind::Dma, "empty"),
+               Err(Errno::InvalidArgument.as_neg_i32()), "Zero-sized region is invalid");
+    println!("test:    SUCCESS - overlaps rejected with EBUSY");
+
+    // 测试 3: find_free_region 跳过已登记区域
+    println!("test: 3. Testing find_free_region skips reserved ranges...");
+    let addr = map.find_free(0x1_0000, 0x1000, RegionKind::Dma).expect("RAM has free space");
+    assert_eq!(addr, 0x8060_0000, "First free RAM after kernel and heap");
+    assert!(map.reserve(addr, 0x1_0000, RegionKind::Dma, "dma0").is_ok());
+    let next = map.find_free(0x1_0000, 0x10_0000, RegionKind::Dma).expect("RAM has free space");
+    assert_eq!(next, 0x8070_0000, "Next allocation must skip dma0 and honour alignment");
+    assert!(map.iter().all(|r| r.kind == RegionKind::Ram || !r.overlaps(next, 0x1_0000)));
+    assert_eq!(map.find_free(0x200_0000, 0x1000, RegionKind::Dma), None, "Too large for RAM");
+    println!("test:    SUCCESS - free ranges avoid reservations");
+
+    // 测试 4: MMIO 分配位于 MMIO 窗口
+    println!("test: 4. Testing MMIO allocation from the MMIO window...");
+    let bar = map.find_free(0x4000, 0x4000, RegionKind::Mmio).expect("window has space");
+    assert_eq!(bar, 0x4000_0000);
+    assert!(map.reserve(bar, 0x4000, RegionKind::Mmio, "bar0").is_ok());
+    let bar1 = map.find_free(0x4000, 0x4000, RegionKind::Mmio).expect("window has space");
+    assert_eq!(bar1, 0x4000_4000, "Second BAR follows the first");
+    assert_eq!(map.lookup(0x4000_0010).map(|r| r.name), Some("bar0"), "Lookup finds innermost region");
+    println!("test:    SUCCESS - BARs allocated inside the MMIO window");
+
+    println!("test: ===== Memory region map Testing Completed =====");
+}
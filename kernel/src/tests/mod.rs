@@ -100,6 +100,98 @@ pub mod ipc_eventfd;
 pub mod mem_mmap;
 #[cfg(feature = "unit-test")]
 pub mod mem_cow;
+#[cfg(feature = "unit-test")]
+pub mod seqlock;
+#[cfg(feature = "unit-test")]
+pub mod mem_filemap;
+#[cfg(feature = "unit-test")]
+pub mod sendfile;
+#[cfg(feature = "unit-test")]
+pub mod readv_writev;
+#[cfg(feature = "unit-test")]
+pub mod fd_inherit;
+#[cfg(feature = "unit-test")]
+pub mod pipe_buffer;
+#[cfg(feature = "unit-test")]
+pub mod sigchld;
+#[cfg(feature = "unit-test")]
+pub mod timer_tickless;
+#[cfg(feature = "unit-test")]
+pub mod mem_region;
+#[cfg(feature = "unit-test")]
+pub mod dma_coherent;
+#[cfg(feature = "unit-test")]
+pub mod dma_cache;
+#[cfg(feature = "unit-test")]
+pub mod fdt;
+#[cfg(feature = "unit-test")]
+pub mod virtio_mmio_scan;
+#[cfg(feature = "unit-test")]
+pub mod framebuffer_format;
+#[cfg(feature = "unit-test")]
+pub mod virtio_input;
+#[cfg(feature = "unit-test")]
+pub mod input_modifiers;
+#[cfg(feature = "unit-test")]
+pub mod key_repeat;
+#[cfg(feature = "unit-test")]
+pub mod mouse_buttons;
+#[cfg(feature = "unit-test")]
+pub mod sa_restart;
+#[cfg(feature = "unit-test")]
+pub mod sigwait;
+#[cfg(feature = "unit-test")]
+pub mod sigaltstack;
+#[cfg(feature = "unit-test")]
+pub mod file_poll;
+#[cfg(feature = "unit-test")]
+pub mod open_flags;
+#[cfg(feature = "unit-test")]
+pub mod brk;
+#[cfg(feature = "unit-test")]
+pub mod ioctl;
+#[cfg(feature = "unit-test")]
+pub mod rlimit;
+#[cfg(feature = "unit-test")]
+pub mod fbdev;
+#[cfg(feature = "unit-test")]
+pub mod input_dev;
+#[cfg(feature = "unit-test")]
+pub mod tty;
+#[cfg(feature = "unit-test")]
+pub mod clone_files;
+#[cfg(feature = "unit-test")]
+pub mod keymap;
+#[cfg(feature = "unit-test")]
+pub mod ring_buffer;
+#[cfg(feature = "unit-test")]
+pub mod mpsc_queue;
+#[cfg(feature = "unit-test")]
+pub mod tlb_shootdown;
+#[cfg(feature = "unit-test")]
+pub mod virtio_inflight;
+#[cfg(feature = "unit-test")]
+pub mod mprotect;
+#[cfg(feature = "unit-test")]
+pub mod virtio_desc_free;
+#[cfg(feature = "unit-test")]
+pub mod virtio_flush;
+#[cfg(feature = "unit-test")]
+pub mod page_fault_signal;
+#[cfg(feature = "unit-test")]
+pub mod mbr;
+#[cfg(feature = "unit-test")]
+pub mod block_cache;
+#[cfg(feature = "unit-test")]
+pub mod readahead;
+#[cfg(feature = "unit-test")]
+pub mod ext4_write_inode;
+#[cfg(feature = "unit-test")]
+pub mod ext4_dir_entry;
+#[cfg(feature = "unit-test")]
+pub mod ext4_symlink;
+#[cfg(feature = "unit-test")]
+pub mod signal_delivery;
 
 #[cfg(feature = "unit-test")]
 pub fn run_all_tests() {
@@ -228,5 +320,143 @@ pub fn run_all_tests() {
     // 41. 标准 alloc crate 类型测试
     // standard_alloc::test_standard_alloc();
 
+    // 42. SeqLock 顺序锁测试
+    seqlock::test_seqlock();
+
+    // 43. 文件映射 (MAP_SHARED/MAP_PRIVATE) 测试
+    mem_filemap::test_filemap();
+
+    // 44. sendfile 测试
+    sendfile::test_sendfile();
+
+    // 45. readv/writev 测试
+    readv_writev::test_readv_writev();
+
+    // 46. fork/execve 文件描述符继承测试
+    fd_inherit::test_fd_inherit();
+
+    // 47. 管道环形缓冲区与阻塞语义测试
+    pipe_buffer::test_pipe_buffer();
+
+    // 48. SIGCHLD 退出通知测试
+    sigchld::test_sigchld();
+
+    // 49. 可配置时钟频率与 tickless idle 测试
+    timer_tickless::test_timer_tickless();
+
+    // 50. 物理地址空间区域登记表测试
+    mem_region::test_mem_region();
+
+    // 51. DMA 一致性内存分配测试
+    dma_coherent::test_dma_coherent();
+
+    // 52. DMA 缓存维护测试
+    dma_cache::test_dma_cache();
+
+    // 53. 设备树解析测试
+    fdt::test_fdt();
+
+    // 54. virtio-mmio 窗口扫描测试
+    virtio_mmio_scan::test_virtio_mmio_scan();
+
+    // 55. framebuffer 像素格式测试
+    framebuffer_format::test_framebuffer_format();
+
+    // 56. virtio-input 事件翻译测试
+    virtio_input::test_virtio_input();
+
+    // 57. 修饰键与 LED 状态测试
+    input_modifiers::test_input_modifiers();
+
+    // 58. 按键自动重复测试
+    key_repeat::test_key_repeat();
+
+    // 59. 鼠标按键原始事件测试
+    mouse_buttons::test_mouse_buttons();
+
+    // 60. SA_RESTART 系统调用重启测试
+    sa_restart::test_sa_restart();
+
+    // 61. sigsuspend/sigtimedwait 测试
+    sigwait::test_sigwait();
+
+    // 62. sigaltstack 备用信号栈测试
+    sigaltstack::test_sigaltstack();
+
+    // 63. FileOps poll 就绪状态测试
+    file_poll::test_file_poll();
+
+    // 64. open 标志 O_DIRECTORY/O_NOFOLLOW/O_EXCL 测试
+    open_flags::test_open_flags();
+
+    // 65. brk 堆增长测试
+    brk::test_brk();
+
+    // 66. FileOps ioctl 分发测试
+    ioctl::test_ioctl();
+
+    // 67. 资源限制测试
+    rlimit::test_rlimit();
+
+    // 68. framebuffer 字符设备测试
+    fbdev::test_fbdev();
+
+    // 69. 输入事件设备测试
+    input_dev::test_input_dev();
+
+    // 70. 终端行规程测试
+    tty::test_tty();
+
+    // 71. CLONE_FILES 共享 fd 表测试
+    clone_files::test_clone_files();
+
+    // 72. US 键盘布局测试
+    keymap::test_keymap();
+
+    // 73. 环形缓冲区测试
+    ring_buffer::test_ring_buffer();
+
+    // 74. MPSC 队列测试
+    mpsc_queue::test_mpsc_queue();
+
+    // 75. TLB shootdown 测试
+    tlb_shootdown::test_tlb_shootdown();
+
+    // 76. VirtIO 进行中请求表测试
+    virtio_inflight::test_virtio_inflight();
+
+    // 77. mprotect 测试
+    mprotect::test_mprotect();
+
+    // 78. VirtQueue 描述符回收测试
+    virtio_desc_free::test_virtio_desc_free();
+
+    // 79. VirtIO 块设备刷新测试
+    virtio_flush::test_virtio_flush();
+
+    // 80. 缺页统计与 SIGSEGV 测试
+    page_fault_signal::test_page_fault_signal();
+
+    // 81. MBR 分区表测试
+    mbr::test_mbr();
+
+    // 82. 块缓存测试
+    block_cache::test_block_cache();
+
+    // 83. 块预读测试
+    readahead::test_readahead();
+
+    // 84. ext4 inode 写回测试
+    ext4_write_inode::test_ext4_write_inode();
+
+    // 85. ext4 目录项创建测试
+    ext4_dir_entry::test_ext4_dir_entry();
+
+    // 86. ext4 符号链接测试
+    ext4_symlink::test_ext4_symlink();
+
+    // 87. 信号投递测试
+    signal_delivery::test_signal_delivery();
+
     println!("test: ===== All Unit Tests Completed =====");
 }
@@ -0,0 +1,58 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：鼠标按键原始事件
+//
+// 测试内容：
+// 1. 右键按下/释放产生 BTN_RIGHT 1 / BTN_RIGHT 0
+// 2. 按键状态未变化时不产生事件
+// 3. 多个按键同时变化时逐个产生事件
+
+use crate::println;
+use crate::input::{InputEvent, RawEventTranslator, BTN_LEFT, BTN_MIDDLE, BTN_RIGHT, EV_KEY};
+
+fn buttons(left: bool, right: bool, middle: bool) -> InputEvent {
+    InputEvent::MouseButton { left, right, middle }
+}
+
+/// 取出下一个原始事件的 (type, code, value)
+fn next(translator: &mut RawEventTranslator) -> Option<(u16, u16, i32)> {
+    translator.pop().map(|raw| (raw.type_, raw.code, raw.value))
+}
+
+pub fn test_mouse_buttons() {
+    println!("test: ===== Testing Mouse Button Raw Events =====");
+
+    let mut translator = RawEventTranslator::new();
+
+    // 测试 1: 右键按下与释放
+    println!("test: 1. Testing right button press/release...");
+    translator.translate(buttons(false, true, false));
+    assert_eq!(next(&mut translator), Some((EV_KEY, BTN_RIGHT, 1)), "Right press");
+    assert_eq!(next(&mut translator), None);
+    translator.translate(buttons(false, false, false));
+    assert_eq!(next(&mut translator), Some((EV_KEY, BTN_RIGHT, 0)), "Release must report BTN_RIGHT, not BTN_LEFT");
+    assert_eq!(next(&mut translator), None);
+    println!("test:    SUCCESS - right release reported as BTN_RIGHT");
+
+    // 测试 2: 状态未变化
+    println!("test: 2. Testing unchanged state...");
+    translator.translate(buttons(false, false, false));
+    assert_eq!(next(&mut translator), None, "No change, no event");
+    println!("test:    SUCCESS - unchanged state ignored");
+
+    // 测试 3: 多个按键同时变化
+    println!("test: 3. Testing simultaneous changes...");
+    translator.translate(buttons(true, false, true));
+    assert_eq!(next(&mut translator), Some((EV_KEY, BTN_LEFT, 1)));
+    assert_eq!(next(&mut translator), Some((EV_KEY, BTN_MIDDLE, 1)));
+    translator.translate(buttons(false, true, true));
+    assert_eq!(next(&mut translator), Some((EV_KEY, BTN_LEFT, 0)), "Left released");
+    assert_eq!(next(&mut translator), Some((EV_KEY, BTN_RIGHT, 1)), "Right pressed");
+    assert_eq!(next(&mut translator), None, "Middle still held");
+    println!("test:    SUCCESS - each changed button reported");
+
+    println!("test: ===== Mouse Button Raw Events Testing Completed =====");
+}
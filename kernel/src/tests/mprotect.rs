@@ -0,0 +1,134 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：mprotect 修改已有映射的保护属性
+//
+// 测试内容：
+// 1. 可写页改为只读后写访问产生缺页错误，VMA 在范围边界分裂
+// 2. 恢复写权限后写访问不再出错，页面仍是原来的物理页
+// 3. PROT_NONE 页不可访问，恢复后原来的数据还在
+// 4. 范围没有被映射完整覆盖时返回 ENOMEM 且不修改任何映射
+// 5. fork 后父子进程的页（包括只读页和 PROT_NONE 页）都是 COW；
+//    子进程 mprotect 恢复写权限后写入会先复制，不影响父进程
+
+use crate::println;
+use crate::arch::riscv64::fault::{handle_user_fault, FaultOutcome};
+use crate::arch::riscv64::mm::{
+    cow_flags, create_user_address_space, handle_mm_fault, prot, sw_flags, user_pte, user_virt_to_phys,
+    AddressSpace, FaultFlags, MmFaultResult, VirtAddr as PteVirtAddr,
+};
+use crate::mm::page::{VirtAddr, PAGE_SIZE};
+use crate::mm::pagemap::{MapError, Perm};
+use crate::mm::vma::{VmaFlags, VmaType};
+
+pub fn test_mprotect() {
+    println!("test: ===== Testing mprotect =====");
+
+    let Some(root_ppn) = create_user_address_space() else {
+        println!("test:    No user memory - skipping");
+        return;
+    };
+    let addr_space = unsafe { AddressSpace::new(root_ppn) };
+    let mut flags = VmaFlags::new();
+    flags.insert(VmaFlags::READ | VmaFlags::WRITE | VmaFlags::PRIVATE);
+    let start = addr_space
+        .mmap(VirtAddr::new(0), 4 * PAGE_SIZE, flags, VmaType::Anonymous, Perm::ReadWrite, 0)
+        .expect("mmap anonymous pages");
+    let page = |i: usize| start.as_usize() + i * PAGE_SIZE;
+    let pte = |i: usize| unsafe { user_pte(root_ppn, page(i) as u64) }.expect("page table exists");
+    let phys = |i: usize| unsafe { user_virt_to_phys(root_ppn, page(i) as u64) };
+    let fault = |i: usize, access: u32| {
+        handle_mm_fault(&addr_space, PteVirtAddr::new(page(i) as u64), access | FaultFlags::USER)
+    };
+    let rw = prot::PROT_READ | prot::PROT_WRITE;
+
+    // 测试 1: 改为只读
+    println!("test: 1. Testing write fault after removing PROT_WRITE...");
+    let frame = phys(1).expect("page 1 is mapped");
+    assert_eq!(fault(1, FaultFlags::WRITE), MmFaultResult::AlreadyMapped, "Writable page needs no fixup");
+    assert_eq!(addr_space.mprotect(VirtAddr::new(page(1)), PAGE_SIZE, prot::PROT_READ), Ok(()));
+    assert!(pte(1).is_valid() && pte(1).is_readable(), "Page stays mapped and readable");
+    assert!(!pte(1).is_writable(), "Write bit cleared in the page table");
+    assert!(pte(0).is_writable() && pte(2).is_writable(), "Neighbouring pages keep write access");
+    assert_eq!(fault(1, FaultFlags::WRITE), MmFaultResult::PermissionDenied, "Write to a read-only page faults");
+    let below = addr_space.find_vma(VirtAddr::new(page(0))).expect("VMA below the range");
+    let middle = addr_space.find_vma(VirtAddr::new(page(1))).expect("VMA for the range");
+    let above = addr_space.find_vma(VirtAddr::new(page(2))).expect("VMA above the range");
+    assert_eq!(below.end().as_usize(), page(1), "VMA split at the start of the range");
+    assert_eq!((middle.start().as_usize(), middle.end().as_usize()), (page(1), page(2)));
+    assert!(!middle.flags().is_writable() && middle.flags().is_readable());
+    assert_eq!(above.start().as_usize(), page(2), "VMA split at the end of the range");
+    assert!(below.flags().is_writable() && above.flags().is_writable());
+    println!("test:    SUCCESS - read-only page rejects writes");
+
+    // 测试 2: 恢复写权限
+    println!("test: 2. Testing restored write access...");
+    assert_eq!(addr_space.mprotect(VirtAddr::new(page(1)), PAGE_SIZE, rw), Ok(()));
+    assert!(pte(1).is_writable(), "Write bit restored");
+    assert_eq!(fault(1, FaultFlags::WRITE), MmFaultResult::AlreadyMapped, "Write is allowed again");
+    assert_eq!(phys(1), Some(frame), "Protection change keeps the same physical page");
+    println!("test:    SUCCESS - write access restored");
+
+    // 测试 3: PROT_NONE
+    println!("test: 3. Testing PROT_NONE...");
+    let frame = phys(3).expect("page 3 is mapped");
+    unsafe { *(frame as *mut u8) = 0x5a; }
+    assert_eq!(addr_space.mprotect(VirtAddr::new(page(3)), PAGE_SIZE, prot::PROT_NONE), Ok(()));
+    assert!(!pte(3).is_valid(), "PROT_NONE page is not accessible");
+    assert!(pte(3).bits() & sw_flags::PROT_NONE != 0, "Frame kept behind the software bit");
+    assert_eq!(fault(3, FaultFlags::READ), MmFaultResult::PermissionDenied, "Read of a PROT_NONE page faults");
+    assert_eq!(addr_space.mprotect(VirtAddr::new(page(3)), PAGE_SIZE, rw), Ok(()));
+    assert_eq!(phys(3), Some(frame), "Original page mapped again");
+    assert_eq!(unsafe { *(frame as *const u8) }, 0x5a, "Data survives PROT_NONE");
+    println!("test:    SUCCESS - PROT_NONE round trip keeps the data");
+
+    // 测试 4: 未完整覆盖的范围
+    println!("test: 4. Testing ranges not covered by mappings...");
+    assert_eq!(addr_space.mprotect(VirtAddr::new(page(2)), 4 * PAGE_SIZE, prot::PROT_READ),
+               Err(MapError::NotMapped), "Range past the end of the mapping");
+    assert!(pte(2).is_writable(), "Failed mprotect changes nothing");
+    assert!(addr_space.find_vma(VirtAddr::new(page(2))).is_some_and(|vma| vma.flags().is_writable()));
+    assert_eq!(addr_space.mprotect(VirtAddr::new(page(0) + 8), PAGE_SIZE, prot::PROT_READ),
+               Err(MapError::Invalid), "Unaligned address");
+    println!("test:    SUCCESS - uncovered range rejected");
+
+    // 测试 5: fork 之后 mprotect
+    println!("test: 5. Testing mprotect after fork...");
+    assert_eq!(addr_space.mprotect(VirtAddr::new(page(1)), PAGE_SIZE, prot::PROT_READ), Ok(()));
+    assert_eq!(addr_space.mprotect(VirtAddr::new(page(3)), PAGE_SIZE, prot::PROT_NONE), Ok(()));
+    let ro_frame = phys(1).expect("page 1 is mapped");
+    unsafe { *(ro_frame as *mut u8) = 0x11; }
+    let child = addr_space.fork().expect("fork address space");
+    let child_root = child.root_ppn();
+    let child_pte = |i: usize| unsafe { user_pte(child_root, page(i) as u64) }.expect("child page table exists");
+
+    for i in [0, 1, 3] {
+        assert!(child_pte(i).bits() & cow_flags::COW != 0, "Child page {} is COW", i);
+        assert!(pte(i).bits() & cow_flags::COW != 0, "Parent page {} is COW", i);
+        assert!(!pte(i).is_writable() && !child_pte(i).is_writable());
+    }
+    assert!(!child_pte(3).is_valid() && child_pte(3).bits() & sw_flags::PROT_NONE != 0,
+            "PROT_NONE page carried into the child");
+    assert_eq!(child_pte(3).ppn(), pte(3).ppn(), "Child keeps the PROT_NONE frame");
+
+    assert_eq!(child.mprotect(VirtAddr::new(page(1)), PAGE_SIZE, rw), Ok(()));
+    assert!(!child_pte(1).is_writable(), "COW keeps the write bit off after mprotect");
+    assert_eq!(handle_user_fault(&child, page(1) as u64, FaultFlags::WRITE), FaultOutcome::Minor);
+    let child_frame = unsafe { user_virt_to_phys(child_root, page(1) as u64) }.expect("child page 1");
+    assert_ne!(child_frame, ro_frame, "Child write gets its own copy");
+    unsafe { *(child_frame as *mut u8) = 0x22; }
+    assert_eq!(unsafe { *(ro_frame as *const u8) }, 0x11, "Parent page unchanged");
+    assert!(!pte(1).is_writable(), "Parent page stays read-only");
+
+    assert_eq!(child.mprotect(VirtAddr::new(page(3)), PAGE_SIZE, rw), Ok(()));
+    let shared = unsafe { user_virt_to_phys(child_root, page(3) as u64) }.expect("child page 3");
+    assert_eq!(unsafe { *(shared as *const u8) }, 0x5a, "Child sees the parent's PROT_NONE data");
+    assert_eq!(handle_user_fault(&child, page(3) as u64, FaultFlags::WRITE), FaultOutcome::Minor);
+    assert_ne!(unsafe { user_virt_to_phys(child_root, page(3) as u64) }, Some(shared),
+               "Write to a restored PROT_NONE page copies it");
+    println!("test:    SUCCESS - child writes never reach parent pages");
+
+    println!("test: ===== mprotect Testing Completed =====");
+}
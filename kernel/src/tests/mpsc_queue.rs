@@ -0,0 +1,96 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：有界多生产者单消费者队列
+//
+// 测试内容：
+// 1. 多个模拟生产者交错入队，全部出队且每个生产者的数据保持顺序
+// 2. 队列满时 try_push 退回数据（back-pressure），出队后可以继续入队
+// 3. 多轮入队出队后槽位序列号正确回绕
+// 4. 发给自己或无效 CPU 的 IPI 不进入消息队列
+
+use crate::println;
+use crate::arch::ipi::{pending_ipis, smp_cross_call, IpiType};
+use crate::config::MAX_CPUS;
+use crate::sync::mpsc::{Full, MpscQueue};
+
+pub fn test_mpsc_queue() {
+    println!("test: ===== Testing MPSC queue =====");
+
+    // 测试 1: 交错的生产者
+    println!("test: 1. Testing interleaved producers...");
+    const PRODUCERS: usize = 3;
+    const PER_PRODUCER: usize = 20;
+    let queue: MpscQueue<(usize, usize), 8> = MpscQueue::new();
+    let mut sent = [0usize; PRODUCERS];
+    let mut received = [0usize; PRODUCERS];
+    let mut total = 0;
+    let mut rng: u32 = 12345;
+    while total < PRODUCERS * PER_PRODUCER {
+        // 伪随机选择本轮入队的生产者，消费者每隔几次取走一批
+        rng = rng.wrapping_mul(1103515245).wrapping_add(12345);
+        let producer = (rng >> 16) as usize % PRODUCERS;
+        if sent[producer] < PER_PRODUCER && queue.try_push((producer, sent[producer])).is_ok() {
+            sent[producer] += 1;
+        }
+        if rng & 0x3 == 0 || queue.len() == queue.capacity() {
+            while let Some((producer, seq)) = queue.pop() {
+                assert_eq!(seq, received[producer], "Per-producer order must be preserved");
+                received[producer] += 1;
+                total += 1;
+            }
+        }
+        if sent.iter().all(|&n| n == PER_PRODUCER) {
+            while let Some((producer, seq)) = queue.pop() {
+                assert_eq!(seq, received[producer]);
+                received[producer] += 1;
+                total += 1;
+            }
+        }
+    }
+    assert_eq!(received, [PER_PRODUCER; PRODUCERS], "Every pushed item is dequeued");
+    assert!(queue.is_empty());
+    println!("test:    SUCCESS - all items dequeued in per-producer order");
+
+    // 测试 2: 队列满
+    println!("test: 2. Testing back-pressure when full...");
+    let queue: MpscQueue<u32, 4> = MpscQueue::new();
+    for i in 0..4 {
+        assert_eq!(queue.try_push(i), Ok(()));
+    }
+    assert_eq!(queue.len(), 4);
+    assert_eq!(queue.try_push(99), Err(Full(99)), "Full queue hands the value back");
+    assert_eq!(queue.pop(), Some(0));
+    assert_eq!(queue.try_push(4), Ok(()), "Space freed by pop can be reused");
+    for expected in 1..=4 {
+        assert_eq!(queue.pop(), Some(expected));
+    }
+    assert_eq!(queue.pop(), None, "Empty queue");
+    println!("test:    SUCCESS - full queue reports back-pressure");
+
+    // 测试 3: 回绕
+    println!("test: 3. Testing slot reuse over many rounds...");
+    let queue: MpscQueue<usize, 2> = MpscQueue::new();
+    for round in 0..50 {
+        assert!(queue.try_push(round * 2).is_ok());
+        assert!(queue.try_push(round * 2 + 1).is_ok());
+        assert!(queue.try_push(0).is_err());
+        assert_eq!(queue.pop(), Some(round * 2));
+        assert_eq!(queue.pop(), Some(round * 2 + 1));
+    }
+    println!("test:    SUCCESS - sequence numbers wrap correctly");
+
+    // 测试 4: IPI 消息队列
+    println!("test: 4. Testing IPI queue targets...");
+    let this_cpu = crate::arch::cpu_id() as usize;
+    let before = pending_ipis(this_cpu);
+    assert_eq!(smp_cross_call(this_cpu, IpiType::Reschedule), Ok(()));
+    assert_eq!(pending_ipis(this_cpu), before, "IPIs to self are not queued");
+    assert_eq!(smp_cross_call(MAX_CPUS, IpiType::Reschedule), Ok(()), "Invalid CPU is ignored");
+    assert_eq!(pending_ipis(MAX_CPUS), 0);
+    println!("test:    SUCCESS - IPI queue targets validated");
+
+    println!("test: ===== MPSC Queue Testing Completed =====");
+}
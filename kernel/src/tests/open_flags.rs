@@ -0,0 +1,73 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：open 标志 O_DIRECTORY / O_NOFOLLOW / O_EXCL
+//
+// 测试内容：
+// 1. O_DIRECTORY 打开普通文件返回 ENOTDIR
+// 2. O_NOFOLLOW 打开符号链接返回 ELOOP，不带时跟随链接（包括相对目标）
+// 3. O_CREAT|O_EXCL 打开已存在的文件或符号链接返回 EEXIST
+
+use crate::println;
+use crate::errno::Errno;
+use crate::fs::file::FileFlags;
+use crate::fs::rootfs;
+use crate::fs::vfs;
+
+pub fn test_open_flags() {
+    println!("test: ===== Testing open flags =====");
+
+    let sb_ptr = rootfs::get_rootfs();
+    if sb_ptr.is_null() {
+        println!("test: RootFS not initialized - skipping");
+        return;
+    }
+    let sb = unsafe { &*sb_ptr };
+
+    // 以下错误都在分配文件描述符之前返回，不依赖当前任务的 fdtable
+    let _ = sb.create_file("/open_flags_file", b"data".to_vec());
+    let _ = sb.symlink("/open_flags_file", "/open_flags_link");
+    let _ = sb.symlink("open_flags_file", "/open_flags_rel");
+
+    // 测试 1: O_DIRECTORY
+    println!("test: 1. Testing O_DIRECTORY on a regular file...");
+    let flags = FileFlags::O_RDONLY | FileFlags::O_DIRECTORY;
+    assert_eq!(vfs::file_open("/open_flags_file", flags, 0), Err(Errno::NotADirectory.as_neg_i32()),
+               "O_DIRECTORY on a file must fail with ENOTDIR");
+    assert_eq!(vfs::file_opendir("/open_flags_file", flags), Err(Errno::NotADirectory.as_neg_i32()),
+               "opendir on a file must fail with ENOTDIR");
+    assert_eq!(vfs::file_open("/open_flags_link", flags, 0), Err(Errno::NotADirectory.as_neg_i32()),
+               "O_DIRECTORY checks the symlink target");
+    println!("test:    SUCCESS - O_DIRECTORY rejects non-directories");
+
+    // 测试 2: O_NOFOLLOW
+    println!("test: 2. Testing O_NOFOLLOW on a symlink...");
+    let flags = FileFlags::O_RDONLY | FileFlags::O_NOFOLLOW;
+    assert_eq!(vfs::file_open("/open_flags_link", flags, 0), Err(Errno::TooManySymbolicLinks.as_neg_i32()),
+               "O_NOFOLLOW on a symlink must fail with ELOOP");
+    assert_eq!(sb.resolve("/open_flags_link", false).map(|_| ()), Err(Errno::TooManySymbolicLinks.as_neg_i32()));
+    let (node, path) = sb.resolve("/open_flags_link", true).expect("link resolves");
+    assert!(!node.is_symlink(), "Following must end on the target");
+    assert_eq!(path, "/open_flags_file");
+    let (_, path) = sb.resolve("/open_flags_rel", true).expect("relative link resolves");
+    assert_eq!(path, "/open_flags_file", "Relative target is resolved from the link's directory");
+    assert!(sb.resolve("/open_flags_file", false).is_ok(), "O_NOFOLLOW allows regular files");
+    println!("test:    SUCCESS - O_NOFOLLOW refuses symlinks, default follows them");
+
+    // 测试 3: O_CREAT|O_EXCL
+    println!("test: 3. Testing O_CREAT|O_EXCL on existing paths...");
+    let flags = FileFlags::O_WRONLY | FileFlags::O_CREAT | FileFlags::O_EXCL;
+    assert_eq!(vfs::file_open("/open_flags_file", flags, 0o644), Err(Errno::FileExists.as_neg_i32()),
+               "O_EXCL on an existing file must fail with EEXIST");
+    assert_eq!(vfs::file_open("/open_flags_link", flags, 0o644), Err(Errno::FileExists.as_neg_i32()),
+               "O_EXCL must not follow a symlink");
+    let mut buf = [0u8; 4];
+    let node = sb.lookup("/open_flags_file").expect("file still exists");
+    assert_eq!(node.read_data(0, &mut buf), 4);
+    assert_eq!(&buf, b"data", "Existing file is left untouched");
+    println!("test:    SUCCESS - O_CREAT|O_EXCL rejects existing paths");
+
+    println!("test: ===== open flags Testing Completed =====");
+}
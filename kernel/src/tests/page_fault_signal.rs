@@ -0,0 +1,139 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：用户缺页统计与 SIGSEGV
+//
+// 测试内容：
+// 1. 按需分页和陈旧 TLB 项计入 min_flt，任务继续执行
+// 2. 越界写入向任务发送 SIGSEGV（SEGV_MAPERR，信号信息带出错地址），没有处理函数时终止任务
+// 3. 写只读映射产生 SEGV_ACCERR
+// 4. 有处理函数时信号等待处理；处理函数运行前再次出错则终止任务；被忽略的 SIGSEGV 仍然致命
+// 5. 返回用户态时进入处理函数，信号帧带出错地址；处理函数中再次出错则终止任务；
+//    rt_sigreturn 回到出错的指令
+
+use crate::println;
+use crate::arch::riscv64::fault::do_user_page_fault;
+use crate::arch::riscv64::mm::{create_user_address_space, AddressSpace, FaultFlags};
+use crate::arch::riscv64::trap::{deliver_signals, restore_signal_frame, TrapFrame};
+use crate::mm::page::{VirtAddr, PAGE_SIZE};
+use crate::mm::pagemap::Perm;
+use crate::mm::vma::{VmaFlags, VmaType};
+use crate::process::task::{SchedPolicy, Task};
+use crate::signal::{si_code, SigAction, SigFlags, Signal, SignalStruct};
+use alloc::boxed::Box;
+
+pub fn test_page_fault_signal() {
+    println!("test: ===== Testing page fault accounting and SIGSEGV =====");
+
+    let Some(root_ppn) = create_user_address_space() else {
+        println!("test:    No user memory - skipping");
+        return;
+    };
+    let mut task = Box::new(Task::new(3501, SchedPolicy::Normal));
+    task.signal = Some(Box::new(SignalStruct::new()));
+    task.set_address_space(Some(unsafe { AddressSpace::new(root_ppn) }));
+    let task_ptr = &mut *task as *mut Task;
+    let addr_space = task.address_space().expect("address space installed");
+
+    let mut rw = VmaFlags::new();
+    rw.insert(VmaFlags::READ | VmaFlags::WRITE | VmaFlags::PRIVATE);
+    let data = addr_space
+        .mmap(VirtAddr::new(0), 2 * PAGE_SIZE, rw, VmaType::Anonymous, Perm::ReadWrite, 0)
+        .expect("mmap writable pages")
+        .as_usize() as u64;
+    let mut ro = VmaFlags::new();
+    ro.insert(VmaFlags::READ | VmaFlags::PRIVATE);
+    let rodata = addr_space
+        .mmap(VirtAddr::new(0), PAGE_SIZE, ro, VmaType::Anonymous, Perm::Read, 0)
+        .expect("mmap read-only page")
+        .as_usize() as u64;
+    let sigsegv = Signal::SIGSEGV as i32;
+    let fault = |addr: u64, access: u32| unsafe { do_user_page_fault(task_ptr, addr, access) };
+
+    // 测试 1: 缺页统计
+    println!("test: 1. Testing fault counters...");
+    assert_eq!((task.min_flt(), task.maj_flt()), (0, 0), "New task has no faults");
+    assert_eq!(fault(data, FaultFlags::WRITE), None, "Demand fault is fixed up");
+    assert_eq!(fault(data + PAGE_SIZE as u64, FaultFlags::READ), None);
+    assert_eq!(task.min_flt(), 2, "Anonymous faults are minor");
+    assert_eq!(fault(data + 8, FaultFlags::WRITE), None, "Stale TLB entry is retried");
+    assert_eq!(task.min_flt(), 3);
+    assert_eq!(task.maj_flt(), 0, "No page was read from a file");
+    assert!(!task.pending.has(sigsegv), "Valid faults send no signal");
+    println!("test:    SUCCESS - min_flt={}, maj_flt={}", task.min_flt(), task.maj_flt());
+
+    // 测试 2: 越界写入
+    println!("test: 2. Testing out-of-bounds write...");
+    let bad = data + 16 * PAGE_SIZE as u64 + 0x24;
+    assert_eq!(fault(bad, FaultFlags::WRITE), Some(sigsegv), "Unhandled SIGSEGV terminates the task");
+    assert!(task.pending.has(sigsegv), "SIGSEGV is pending");
+    let info = task.pending.first_info().expect("siginfo saved");
+    assert_eq!((info.si_signo, info.si_code), (sigsegv, si_code::SEGV_MAPERR));
+    assert_eq!(info.si_addr, bad, "Fault address reported in siginfo");
+    assert_eq!(sigsegv & 0x7f, 11, "wait status reports termination by SIGSEGV");
+    assert_eq!(task.min_flt(), 3, "Invalid access is not counted");
+    task.pending.clear();
+    println!("test:    SUCCESS - SIGSEGV at {:#x}", bad);
+
+    // 测试 3: 写只读页
+    println!("test: 3. Testing write to a read-only mapping...");
+    assert_eq!(fault(rodata, FaultFlags::READ), None, "Read-only page can be read");
+    assert_eq!(fault(rodata, FaultFlags::WRITE), Some(sigsegv), "Write to read-only page is fatal");
+    let info = task.pending.first_info().expect("siginfo saved");
+    assert_eq!((info.si_code, info.si_addr), (si_code::SEGV_ACCERR, rodata));
+    task.pending.clear();
+    println!("test:    SUCCESS - SEGV_ACCERR reported");
+
+    // 测试 4: 处理函数与忽略
+    println!("test: 4. Testing handler and ignored SIGSEGV...");
+    let handler = SigAction { sa_handler: 0x1000, sa_flags: SigFlags::new(0), sa_mask: 0 };
+    assert!(task.signal.as_mut().unwrap().set_action(sigsegv, handler).is_ok());
+    assert_eq!(fault(bad, FaultFlags::READ), None, "Signal waits for the handler");
+    assert!(task.pending.has(sigsegv));
+    assert_eq!(fault(bad, FaultFlags::READ), Some(sigsegv), "Fault before the handler ran is fatal");
+    task.pending.clear();
+    assert!(task.signal.as_mut().unwrap().set_action(sigsegv, SigAction::ignore()).is_ok());
+    assert_eq!(fault(bad, FaultFlags::READ), Some(sigsegv), "Ignored SIGSEGV is still fatal");
+    assert!(!task.signal.as_ref().unwrap().get_action(sigsegv).unwrap().has_handler());
+    task.pending.clear();
+    println!("test:    SUCCESS - handler gets the signal, ignore falls back to default");
+
+    // 测试 5: 进入处理函数
+    println!("test: 5. Testing handler invocation on trap return...");
+    const FAULT_PC: u64 = 0x1_0200;
+    const USER_SP: u64 = 0x7fff_e000;
+    // trap.S 的栈布局：用户 sp 保存在 TrapFrame 之前 8 字节处
+    #[repr(C)]
+    struct UserTrap {
+        sp: u64,
+        frame: TrapFrame,
+    }
+    // SAFETY: TrapFrame 只包含 u64 字段
+    let mut trap: Box<UserTrap> = Box::new(unsafe { core::mem::zeroed() });
+    trap.sp = USER_SP;
+    trap.frame.sepc = FAULT_PC;
+
+    assert!(task.signal.as_mut().unwrap().set_action(sigsegv, handler).is_ok());
+    assert_eq!(fault(bad, FaultFlags::WRITE), None, "Handler installed, task keeps running");
+    unsafe { deliver_signals(task_ptr, &mut trap.frame) };
+    assert_eq!(trap.frame.sepc, handler.sa_handler as u64, "Returns to user mode in the handler");
+    assert_eq!(trap.frame.a0, sigsegv as u64);
+    let frame = task.sigframe.expect("signal frame built");
+    assert_eq!((frame.info.si_code, frame.info.si_addr), (si_code::SEGV_MAPERR, bad), "Handler sees the fault address");
+    assert_eq!(trap.frame.a1, task.sigframe_addr + 32, "Second argument points at the siginfo");
+    assert_eq!(frame.uc.uc_pc, FAULT_PC);
+    assert!(!task.pending.has(sigsegv), "Signal consumed by the handler");
+
+    assert_eq!(fault(bad, FaultFlags::READ), Some(sigsegv), "Fault inside the handler is fatal");
+    task.pending.clear();
+    assert!(task.signal.as_mut().unwrap().set_action(sigsegv, handler).is_ok());
+
+    assert!(unsafe { restore_signal_frame(task_ptr, &mut trap.frame) });
+    assert_eq!((trap.frame.sepc, trap.sp), (FAULT_PC, USER_SP), "Faulting instruction runs again");
+    assert_eq!(task.sigmask, 0);
+    println!("test:    SUCCESS - handler entered at {:#x}", handler.sa_handler);
+
+    println!("test: ===== Page Fault Signal Testing Completed =====");
+}
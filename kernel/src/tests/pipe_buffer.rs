@@ -0,0 +1,105 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：管道环形缓冲区与阻塞语义
+//
+// 测试内容：
+// 1. 环形缓冲区回绕读写、容量可完全使用
+// 2. 缓冲区扩容/缩容保持数据顺序
+// 3. 满/空交接：写满后写入 EAGAIN，读出后写入恢复
+// 4. 写端关闭后读返回 EOF
+// 5. 读端关闭后写返回 EPIPE
+// 6. F_SETPIPE_SZ 按页和 2 的幂取整，F_GETPIPE_SZ 读回容量；非管道文件返回 EBADF
+
+use crate::println;
+use crate::fs::create_pipe;
+use crate::fs::file::{File, FileFlags};
+use crate::fs::pipe::{pipe_fcntl, PipeBuffer, PIPE_MAX_SIZE};
+use crate::fs::vfs::fcntl;
+use alloc::vec;
+
+pub fn test_pipe_buffer() {
+    println!("test: ===== Testing pipe ring buffer =====");
+
+    // 测试 1: 回绕
+    println!("test: 1. Testing ring buffer wrap-around...");
+    let mut ring = PipeBuffer::new(8);
+    assert_eq!(ring.write(b"abcdef"), 6);
+    let mut out = [0u8; 4];
+    assert_eq!(ring.read(&mut out), 4);
+    assert_eq!(&out, b"abcd");
+    // 写入跨越缓冲区末尾
+    assert_eq!(ring.write(b"ghijklmn"), 6, "Only free space should be written");
+    assert_eq!(ring.available_write(), 0, "Full capacity must be usable");
+    let mut all = [0u8; 8];
+    assert_eq!(ring.read(&mut all), 8);
+    assert_eq!(&all, b"efghijkl", "Data must come back in order across the wrap");
+    assert_eq!(ring.available_read(), 0);
+    println!("test:    SUCCESS - wrap-around preserves order");
+
+    // 测试 2: 调整容量
+    println!("test: 2. Testing buffer resize...");
+    let mut ring = PipeBuffer::new(4);
+    ring.write(b"xy");
+    ring.read(&mut out[..1]);
+    ring.write(b"zw");
+    assert!(ring.resize(16).is_ok(), "Growing should succeed");
+    assert_eq!(ring.capacity(), 16);
+    assert_eq!(ring.available_read(), 3);
+    let mut got = [0u8; 3];
+    ring.read(&mut got);
+    assert_eq!(&got, b"yzw", "Resize must keep buffered data in order");
+    ring.write(b"12345");
+    assert_eq!(ring.resize(2), Err(-16), "Shrinking below buffered data should be EBUSY");
+    println!("test:    SUCCESS - resize keeps data, rejects too-small size");
+
+    // 测试 3: 满/空交接（非阻塞模式下验证边界）
+    println!("test: 3. Testing full/empty handoff...");
+    let (read_end, write_end) = create_pipe();
+    read_end.flags.set_status_flags(FileFlags::O_NONBLOCK);
+    write_end.flags.set_status_flags(FileFlags::O_NONBLOCK);
+    let mut buf = [0u8; 8];
+    assert_eq!(unsafe { read_end.read(buf.as_mut_ptr(), buf.len()) }, -11, "Empty pipe read should be EAGAIN");
+    let chunk = vec![0x5Au8; 20000];
+    let written = unsafe { write_end.write(chunk.as_ptr(), chunk.len()) };
+    assert_eq!(written, 16384, "Write should fill the pipe to capacity");
+    assert_eq!(unsafe { write_end.write(chunk.as_ptr(), 1) }, -11, "Full pipe write should be EAGAIN");
+    assert_eq!(unsafe { read_end.read(buf.as_mut_ptr(), buf.len()) }, 8);
+    assert_eq!(unsafe { write_end.write(chunk.as_ptr(), 100) }, 8, "Write after read should use freed space");
+    println!("test:    SUCCESS - full/empty boundaries hand off correctly");
+
+    // 测试 4: 写端关闭后读到 EOF
+    println!("test: 4. Testing EOF after writer close...");
+    let (read_end, write_end) = create_pipe();
+    unsafe { write_end.write(b"tail".as_ptr(), 4) };
+    drop(write_end);
+    let n = unsafe { read_end.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, 4, "Buffered data is still readable after writer close");
+    let n = unsafe { read_end.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, 0, "Read with no writers should return EOF");
+    println!("test:    SUCCESS - EOF after writer close");
+
+    // 测试 5: 读端关闭后写返回 EPIPE
+    println!("test: 5. Testing EPIPE after reader close...");
+    let (read_end, write_end) = create_pipe();
+    drop(read_end);
+    let ret = unsafe { write_end.write(b"x".as_ptr(), 1) };
+    assert_eq!(ret, -32, "Write with no readers should return EPIPE");
+    println!("test:    SUCCESS - EPIPE after reader close");
+
+    // 测试 6: F_SETPIPE_SZ / F_GETPIPE_SZ
+    println!("test: 6. Testing F_SETPIPE_SZ/F_GETPIPE_SZ...");
+    let (read_end, write_end) = create_pipe();
+    assert_eq!(pipe_fcntl(&read_end, fcntl::F_GETPIPE_SZ, 0), Ok(16384));
+    assert_eq!(pipe_fcntl(&write_end, fcntl::F_SETPIPE_SZ, 5000), Ok(8192), "Size rounded up to a power of two");
+    assert_eq!(pipe_fcntl(&read_end, fcntl::F_GETPIPE_SZ, 0), Ok(8192), "Both ends share the buffer");
+    assert_eq!(pipe_fcntl(&write_end, fcntl::F_SETPIPE_SZ, 1), Ok(4096), "At least one page");
+    assert_eq!(pipe_fcntl(&write_end, fcntl::F_SETPIPE_SZ, PIPE_MAX_SIZE + 1), Err(-22));
+    let not_pipe = File::new(FileFlags::new(FileFlags::O_RDONLY));
+    assert_eq!(pipe_fcntl(&not_pipe, fcntl::F_GETPIPE_SZ, 0), Err(-9), "Not a pipe is EBADF");
+    println!("test:    SUCCESS - pipe size set and read back");
+
+    println!("test: ===== pipe ring buffer Testing Completed =====");
+}
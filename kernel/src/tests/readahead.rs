@@ -0,0 +1,98 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：顺序读取预读
+//
+// 测试内容：
+// 1. 读取块 0、1 后用一个请求预读块 2 开始的窗口，之后读取窗口内的块不再访问设备
+// 2. 读到窗口的最后一个块时预读下一个窗口
+// 3. 随机读取不触发预读
+// 4. 预读块数为 0 时关闭预读，预读不超过磁盘末尾
+
+use crate::println;
+use crate::drivers::blkdev::{self, GenDisk, ReqCmd, Request};
+use crate::fs::bio;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// 模拟设备收到的读请求数、最后一个请求的起始扇区和长度
+static READS: AtomicUsize = AtomicUsize::new(0);
+static LAST_SECTOR: AtomicU64 = AtomicU64::new(0);
+static LAST_LEN: AtomicUsize = AtomicUsize::new(0);
+
+unsafe extern "C" fn mock_request(req: &mut Request) {
+    if req.cmd_type == ReqCmd::Read {
+        READS.fetch_add(1, Ordering::Relaxed);
+        LAST_SECTOR.store(req.sector, Ordering::Relaxed);
+        LAST_LEN.store(req.buffer.len(), Ordering::Relaxed);
+        // 每个 4KB 块的第一个字节为块号
+        for (i, block) in req.buffer.chunks_mut(4096).enumerate() {
+            block[0] = (req.sector / 8) as u8 + i as u8;
+        }
+    }
+}
+
+/// 读取一个块并检查内容，返回设备读请求数的增量
+fn read(dev: *const GenDisk, block: u64) -> usize {
+    let before = READS.load(Ordering::Relaxed);
+    let bh = bio::bread(dev, block).expect("block read");
+    unsafe { assert_eq!((&(*bh).b_data)[0], block as u8, "Block {} has its own data", block); }
+    bio::brelse(bh);
+    READS.load(Ordering::Relaxed) - before
+}
+
+pub fn test_readahead() {
+    println!("test: ===== Testing block read-ahead =====");
+
+    let saved = blkdev::readahead();
+    blkdev::set_readahead(4);
+    let mut disk = GenDisk::new("mockra", 252, 1, 4096, None);
+    disk.set_capacity(64 * 8);  // 64 个块
+    disk.set_request_fn(mock_request);
+    let dev = &disk as *const GenDisk;
+
+    // 测试 1: 顺序读取触发预读
+    println!("test: 1. Testing blocks 0,1 prefetch block 2...");
+    assert_eq!(read(dev, 0), 1);
+    assert_eq!(read(dev, 1), 2, "Block 1 read plus one read-ahead request");
+    assert_eq!((LAST_SECTOR.load(Ordering::Relaxed), LAST_LEN.load(Ordering::Relaxed)), (16, 4 * 4096),
+        "Read-ahead covers blocks 2-5 in one request");
+    for block in 2..=4 {
+        assert_eq!(read(dev, block), 0, "Block {} was prefetched", block);
+    }
+    println!("test:    SUCCESS - blocks 2-5 prefetched");
+
+    // 测试 2: 下一个窗口
+    println!("test: 2. Testing the next window...");
+    assert_eq!(read(dev, 5), 1, "Last block of the window prefetches the next window");
+    assert_eq!(LAST_SECTOR.load(Ordering::Relaxed), 6 * 8, "Next window starts at block 6");
+    for block in 6..=8 {
+        assert_eq!(read(dev, block), 0, "Block {} was prefetched", block);
+    }
+    println!("test:    SUCCESS - read-ahead follows the stream");
+
+    // 测试 3: 随机读取
+    println!("test: 3. Testing random reads...");
+    assert_eq!(read(dev, 40), 1);
+    assert_eq!(read(dev, 30), 1, "Backward jump does not prefetch");
+    assert_eq!(read(dev, 50), 1, "Forward jump does not prefetch");
+    println!("test:    SUCCESS - no read-ahead for random access");
+
+    // 测试 4: 关闭预读与磁盘末尾
+    println!("test: 4. Testing disabled read-ahead and the disk end...");
+    assert_eq!(read(dev, 61), 1);
+    assert_eq!(read(dev, 62), 2, "Sequential read of 62 prefetches only the last block");
+    assert_eq!(LAST_LEN.load(Ordering::Relaxed), 4096, "Read-ahead stops at the disk end");
+    assert_eq!(read(dev, 63), 0);
+    blkdev::set_readahead(0);
+    assert_eq!(read(dev, 20), 1);
+    assert_eq!(read(dev, 21), 1, "No prefetch with read-ahead disabled");
+    assert_eq!(read(dev, 22), 1);
+    println!("test:    SUCCESS - read-ahead disabled and clamped");
+
+    blkdev::set_readahead(saved);
+    bio::invalidate_device(dev);
+
+    println!("test: ===== Read-ahead Testing Completed =====");
+}
@@ -0,0 +1,71 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：readv/writev 分散/聚集 I/O
+//
+// 测试内容：
+// 1. 两个 iovec 写入管道，返回总字节数
+// 2. 读回到三个大小不同的 iovec，数据按顺序分布
+// 3. 短读时停止在第一个未填满的段
+
+use crate::println;
+use crate::fs::{create_pipe, vfs_readv, vfs_writev, Iovec};
+
+pub fn test_readv_writev() {
+    println!("test: ===== Testing readv/writev =====");
+
+    let (pipe_read, pipe_write) = create_pipe();
+
+    // 测试 1: 聚集写
+    println!("test: 1. Testing writev with two iovecs...");
+    let mut part1 = *b"hello, ";
+    let mut part2 = *b"vectored world";
+    let write_iovs = [
+        Iovec { iov_base: part1.as_mut_ptr(), iov_len: part1.len() },
+        Iovec { iov_base: part2.as_mut_ptr(), iov_len: part2.len() },
+    ];
+    let written = vfs_writev(&pipe_write, &write_iovs).expect("writev failed");
+    assert_eq!(written, 21, "writev should write both segments");
+    println!("test:    SUCCESS - wrote {} bytes", written);
+
+    // 测试 2: 分散读（3 + 10 + 8 = 21 字节）
+    println!("test: 2. Testing readv into three iovecs...");
+    let mut a = [0u8; 3];
+    let mut b = [0u8; 10];
+    let mut c = [0u8; 8];
+    let read_iovs = [
+        Iovec { iov_base: a.as_mut_ptr(), iov_len: a.len() },
+        Iovec { iov_base: b.as_mut_ptr(), iov_len: b.len() },
+        Iovec { iov_base: c.as_mut_ptr(), iov_len: c.len() },
+    ];
+    let read = vfs_readv(&pipe_read, &read_iovs).expect("readv failed");
+    assert_eq!(read, 21, "readv should read all bytes");
+    assert_eq!(&a, b"hel");
+    assert_eq!(&b, b"lo, vector");
+    assert_eq!(&c, b"ed world");
+    println!("test:    SUCCESS - data scattered across iovecs in order");
+
+    // 测试 3: 短读停止
+    println!("test: 3. Testing readv stops at short segment...");
+    let mut small = *b"abcde";
+    let one_iov = [Iovec { iov_base: small.as_mut_ptr(), iov_len: small.len() }];
+    vfs_writev(&pipe_write, &one_iov).expect("writev failed");
+    let mut x = [0u8; 4];
+    let mut y = [0u8; 4];
+    let mut z = [0u8; 4];
+    let short_iovs = [
+        Iovec { iov_base: x.as_mut_ptr(), iov_len: x.len() },
+        Iovec { iov_base: y.as_mut_ptr(), iov_len: y.len() },
+        Iovec { iov_base: z.as_mut_ptr(), iov_len: z.len() },
+    ];
+    let read = vfs_readv(&pipe_read, &short_iovs).expect("readv failed");
+    assert_eq!(read, 5, "readv should stop after the short segment");
+    assert_eq!(&x, b"abcd");
+    assert_eq!(y[0], b'e');
+    assert_eq!(z, [0u8; 4], "Segments after a short read must be untouched");
+    println!("test:    SUCCESS - readv stopped at short segment");
+
+    println!("test: ===== readv/writev Testing Completed =====");
+}
@@ -0,0 +1,88 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：定长字节环形缓冲区
+//
+// 测试内容：
+// 1. 单字节读写保持先进先出，读写位置跨过数组末尾时数据正确
+// 2. push_slice/pop_slice 只处理能放下/已存储的部分，并正确跨过数组末尾
+// 3. 写满后新数据被丢弃，丢弃的字节数计入溢出计数，clear 不重置计数
+// 4. 终端行规程的输入缓冲区写满后丢弃新输入
+
+use crate::println;
+use crate::fs::tty::{lflag, LineDiscipline, N_TTY_BUF_SIZE};
+use crate::ring_buffer::RingBuffer;
+
+pub fn test_ring_buffer() {
+    println!("test: ===== Testing ring buffer =====");
+
+    // 测试 1: 单字节读写和回绕
+    println!("test: 1. Testing push/pop with wrap-around...");
+    let mut ring = RingBuffer::<4>::new();
+    assert!(ring.is_empty() && ring.capacity() == 4 && ring.space() == 4);
+    assert_eq!(ring.pop(), None, "Empty buffer has nothing to pop");
+    for byte in 1..=3 {
+        assert!(ring.push(byte));
+    }
+    assert_eq!(ring.pop(), Some(1));
+    assert_eq!(ring.pop(), Some(2));
+    // 写位置从下标 3 回绕到 0、1
+    assert!(ring.push(4) && ring.push(5) && ring.push(6));
+    assert!(ring.is_full(), "Four bytes fill the buffer");
+    for expected in 3..=6 {
+        assert_eq!(ring.pop(), Some(expected), "Bytes come out in FIFO order across the wrap");
+    }
+    assert!(ring.is_empty());
+    println!("test:    SUCCESS - FIFO order kept across the wrap");
+
+    // 测试 2: 切片读写
+    println!("test: 2. Testing partial slice operations...");
+    let mut ring = RingBuffer::<8>::new();
+    assert_eq!(ring.push_slice(b"abcdef"), 6);
+    let mut out = [0u8; 4];
+    assert_eq!(ring.pop_slice(&mut out), 4);
+    assert_eq!(&out, b"abcd");
+    // 写入跨过数组末尾：下标 6、7 后回到 0..4
+    assert_eq!(ring.push_slice(b"ghijkl"), 6);
+    assert_eq!(ring.len(), 8);
+    let mut out = [0u8; 16];
+    assert_eq!(ring.pop_slice(&mut out), 8, "Read is limited to the stored bytes");
+    assert_eq!(&out[..8], b"efghijkl", "Slice read follows the wrap");
+    assert_eq!(ring.pop_slice(&mut out), 0, "Nothing left to read");
+    println!("test:    SUCCESS - slices split across the end of the buffer");
+
+    // 测试 3: 溢出
+    println!("test: 3. Testing overflow accounting...");
+    let mut ring = RingBuffer::<4>::new();
+    assert_eq!(ring.push_slice(b"123456"), 4, "Only the free space is written");
+    assert_eq!(ring.overflows(), 2, "Dropped bytes are counted");
+    assert!(!ring.push(b'7'), "Push into a full buffer fails");
+    assert_eq!(ring.overflows(), 3);
+    let mut out = [0u8; 4];
+    assert_eq!(ring.pop_slice(&mut out), 4);
+    assert_eq!(&out, b"1234", "Existing data is kept, new data dropped");
+    ring.push_slice(b"xy");
+    ring.clear();
+    assert!(ring.is_empty() && ring.space() == 4, "Clear empties the buffer");
+    assert_eq!(ring.overflows(), 3, "Clear keeps the overflow count");
+    println!("test:    SUCCESS - overflow drops new data and is counted");
+
+    // 测试 4: 终端输入缓冲区
+    println!("test: 4. Testing tty input buffer limit...");
+    let mut ldisc = LineDiscipline::new();
+    let mut termios = ldisc.termios();
+    termios.c_lflag &= !(lflag::ICANON | lflag::ECHO);
+    ldisc.set_termios(termios);
+    for i in 0..N_TTY_BUF_SIZE + 10 {
+        ldisc.receive(b'a' + (i % 26) as u8, |_| {});
+    }
+    assert_eq!(ldisc.pending(), N_TTY_BUF_SIZE, "Input beyond the buffer size is dropped");
+    let mut buf = [0u8; 2];
+    assert_eq!(ldisc.read(&mut buf), Some(2));
+    assert_eq!(&buf, b"ab", "Oldest input is kept");
+    println!("test:    SUCCESS - tty input bounded by the ring buffer");
+
+    println!("test: ===== Ring Buffer Testing Completed =====");
+}
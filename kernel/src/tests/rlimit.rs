@@ -0,0 +1,127 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：资源限制 (prlimit/getrlimit/setrlimit)
+//
+// 测试内容：
+// 1. 新任务使用默认限制（NOFILE 1024，STACK 8MB，CPU 无限制）
+// 2. do_prlimit 返回原限制，无效的资源编号和限制被拒绝且不修改原值
+// 3. 降低 RLIMIT_NOFILE 后无法分配超出限制的文件描述符
+// 4. 栈只能在 RLIMIT_STACK 范围内向下扩展
+// 5. CPU 时间达到 RLIMIT_CPU 软限制时收到 SIGXCPU，达到硬限制时收到 SIGKILL
+
+use crate::println;
+use crate::arch::riscv64::mm::{create_user_address_space, AddressSpace};
+use crate::errno::Errno;
+use crate::fs::file::{File, FileFlags, FdTable};
+use crate::mm::page::{VirtAddr, PAGE_SIZE};
+use crate::mm::vma::{Vma, VmaFlags};
+use crate::process::rlimit::*;
+use crate::process::task::{SchedPolicy, Task};
+use crate::signal::Signal;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+
+pub fn test_rlimit() {
+    println!("test: ===== Testing resource limits =====");
+
+    let task = Box::new(Task::new(3301, SchedPolicy::Normal));
+
+    // 测试 1: 默认限制
+    println!("test: 1. Testing default limits...");
+    assert_eq!(task.rlimit(RLIMIT_NOFILE), RLimit::new(NR_OPEN, NR_OPEN), "Default NOFILE limit");
+    assert_eq!(task.rlimit(RLIMIT_STACK).rlim_cur, crate::config::USER_STACK_SIZE as u64, "Default stack limit");
+    assert_eq!(task.rlimit(RLIMIT_CPU), RLimit::INFINITY, "CPU time is unlimited by default");
+    println!("test:    SUCCESS - defaults set");
+
+    // 测试 2: 读取和修改
+    println!("test: 2. Testing do_prlimit...");
+    let old = do_prlimit(&task, RLIMIT_NOFILE, Some(RLimit::new(16, 64)));
+    assert_eq!(old, Ok(RLimit::new(NR_OPEN, NR_OPEN)), "Returns the previous limit");
+    assert_eq!(do_prlimit(&task, RLIMIT_NOFILE, None), Ok(RLimit::new(16, 64)), "New limit is stored");
+    assert_eq!(do_prlimit(&task, RLIMIT_NOFILE, Some(RLimit::new(65, 64))),
+               Err(Errno::InvalidArgument.as_neg_i32()), "Soft limit above hard limit is rejected");
+    assert_eq!(do_prlimit(&task, RLIMIT_NOFILE, Some(RLimit::new(16, NR_OPEN + 1))),
+               Err(Errno::OperationNotPermitted.as_neg_i32()), "NOFILE above NR_OPEN is rejected");
+    assert_eq!(do_prlimit(&task, RLIM_NLIMITS, None),
+               Err(Errno::InvalidArgument.as_neg_i32()), "Unknown resource is rejected");
+    assert_eq!(task.rlimit(RLIMIT_NOFILE), RLimit::new(16, 64), "Rejected updates leave the limit unchanged");
+    println!("test:    SUCCESS - limits read and validated");
+
+    // 测试 3: RLIMIT_NOFILE
+    println!("test: 3. Testing RLIMIT_NOFILE enforcement...");
+    let fdtable = FdTable::new();
+    let limit = 3;
+    for expected in 0..limit {
+        let fd = fdtable.alloc_fd_below(limit).expect("fd below the limit");
+        assert_eq!(fd, expected, "Descriptors are allocated from the lowest free number");
+        assert!(fdtable.install_fd(fd, Arc::new(File::new(FileFlags::new(FileFlags::O_RDONLY)))).is_ok());
+    }
+    assert_eq!(fdtable.alloc_fd_below(limit), None, "No descriptor beyond the limit");
+    if let Some(current) = crate::sched::current() {
+        let saved = current.rlimit(RLIMIT_NOFILE);
+        assert!(do_prlimit(current, RLIMIT_NOFILE, Some(RLimit::new(2, saved.rlim_max))).is_ok());
+        let fdtable = FdTable::new();
+        for fd in 0..2 {
+            assert_eq!(fdtable.alloc_fd(), Some(fd));
+            assert!(fdtable.install_fd(fd, Arc::new(File::new(FileFlags::new(FileFlags::O_RDONLY)))).is_ok());
+        }
+        assert_eq!(fdtable.alloc_fd(), None, "alloc_fd honours the current process limit");
+        current.set_rlimit(RLIMIT_NOFILE, saved);
+    }
+    println!("test:    SUCCESS - descriptors limited by RLIMIT_NOFILE");
+
+    // 测试 4: RLIMIT_STACK
+    println!("test: 4. Testing RLIMIT_STACK enforcement...");
+    match create_user_address_space() {
+        Some(root_ppn) => {
+            let addr_space = unsafe { AddressSpace::new(root_ppn) };
+            let top = 0x3000_0000usize;
+            let mut flags = VmaFlags::new();
+            flags.insert(VmaFlags::READ | VmaFlags::WRITE | VmaFlags::GROWSDOWN);
+            let stack = Vma::new(VirtAddr::new(top - 4 * PAGE_SIZE), VirtAddr::new(top), flags);
+            assert!(addr_space.vma_write().add(stack).is_ok());
+            let limit = 8 * PAGE_SIZE as u64;
+
+            let inside = VirtAddr::new(top - 6 * PAGE_SIZE + 8);
+            assert!(addr_space.expand_stack(inside, limit), "Growth within the limit succeeds");
+            let vma = addr_space.find_vma(inside).expect("stack covers the new page");
+            assert_eq!(vma.start().as_usize(), top - 6 * PAGE_SIZE, "Stack grows to the faulting page");
+            assert_eq!(vma.end().as_usize(), top, "Stack top does not move");
+
+            let beyond = VirtAddr::new(top - 9 * PAGE_SIZE);
+            assert!(!addr_space.expand_stack(beyond, limit), "Growth beyond RLIMIT_STACK fails");
+            assert!(addr_space.find_vma(beyond).is_none(), "Rejected growth leaves the stack unchanged");
+            assert!(addr_space.expand_stack(VirtAddr::new(top - 8 * PAGE_SIZE), limit),
+                    "Growth up to exactly the limit succeeds");
+            println!("test:    SUCCESS - stack growth limited by RLIMIT_STACK");
+        }
+        None => println!("test:    No user memory - skipping"),
+    }
+
+    // 测试 5: RLIMIT_CPU
+    println!("test: 5. Testing RLIMIT_CPU signals...");
+    let hz = 100;
+    let limit = RLimit::new(2, 4);
+    assert_eq!(cpu_limit_signal(150, hz, limit), None, "Below the soft limit");
+    assert_eq!(cpu_limit_signal(200, hz, limit), Some(Signal::SIGXCPU as i32), "Soft limit reached");
+    assert_eq!(cpu_limit_signal(250, hz, limit), None, "Only checked on second boundaries");
+    assert_eq!(cpu_limit_signal(300, hz, limit), Some(Signal::SIGXCPU as i32), "SIGXCPU repeats every second");
+    assert_eq!(cpu_limit_signal(400, hz, limit), Some(Signal::SIGKILL as i32), "Hard limit kills");
+    assert_eq!(cpu_limit_signal(400, hz, RLimit::INFINITY), None, "Unlimited never signals");
+
+    let cpu_task = Box::new(Task::new(3302, SchedPolicy::Normal));
+    assert!(do_prlimit(&cpu_task, RLIMIT_CPU, Some(RLimit::new(1, RLIM_INFINITY))).is_ok());
+    for _ in 0..hz - 1 {
+        assert_eq!(account_cpu_time(&cpu_task, hz), None);
+    }
+    assert!(!cpu_task.pending.has(Signal::SIGXCPU as i32), "No signal before the limit");
+    assert_eq!(account_cpu_time(&cpu_task, hz), Some(Signal::SIGXCPU as i32));
+    assert_eq!(cpu_task.cpu_ticks(), hz, "Every tick is accounted");
+    assert!(cpu_task.pending.has(Signal::SIGXCPU as i32), "SIGXCPU is pending after exceeding the limit");
+    println!("test:    SUCCESS - SIGXCPU delivered at the CPU limit");
+
+    println!("test: ===== Resource Limits Testing Completed =====");
+}
@@ -0,0 +1,95 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：SA_RESTART 系统调用重启
+//
+// 测试内容：
+// 1. 被 SA_RESTART 信号打断的 read 重新执行并完成
+// 2. 没有 SA_RESTART 的 read 返回 -EINTR
+// 3. 不可重启的系统调用和未被中断的返回值不受影响
+// 4. 没有处理函数运行（默认动作、忽略、没有信号）时透明重启
+
+use crate::println;
+use crate::arch::riscv64::syscall::{apply_syscall_restart, SyscallFrame};
+use crate::errno::Errno;
+use crate::signal::{syscall_restart_action, SigAction, SigFlags, SyscallRestart};
+
+const SYS_READ: u64 = 63;
+const SYS_GETPID: u64 = 172;
+const ECALL_PC: u64 = 0x1000;
+const FD: u64 = 3;
+const COUNT: u64 = 16;
+
+unsafe extern "C" fn dummy_handler(_sig: i32) {}
+
+fn eintr() -> u64 {
+    Errno::InterruptedSystemCall.as_neg_i32() as i64 as u64
+}
+
+/// 模拟一次 ecall：第一次调用被信号打断，之后读取完成
+///
+/// 返回 trap 返回时的 sepc
+fn run_read(frame: &mut SyscallFrame, interrupted: &mut bool, action: &SigAction) -> u64 {
+    let orig_a0 = frame.a0;
+    assert_eq!(frame.a0, FD, "read must see its original fd");
+    frame.a0 = if *interrupted { COUNT } else { eintr() };
+    *interrupted = true;
+
+    let restart = syscall_restart_action(frame.a7, frame.a0 as i64, Some(action));
+    apply_syscall_restart(frame, orig_a0, restart);
+    frame.pc.wrapping_add(4)
+}
+
+pub fn test_sa_restart() {
+    println!("test: ===== Testing SA_RESTART =====");
+
+    // 测试 1: SA_RESTART 时重启并完成
+    println!("test: 1. Testing restart with SA_RESTART...");
+    let action = SigAction::handler(dummy_handler, SigFlags::new(SigFlags::SA_RESTART));
+    let mut frame = SyscallFrame { a0: FD, a1: 0x2000, a2: COUNT, a7: SYS_READ, pc: ECALL_PC, ..Default::default() };
+    let mut interrupted = false;
+    let sepc = run_read(&mut frame, &mut interrupted, &action);
+    assert_eq!(sepc, ECALL_PC, "PC must point back at the ecall");
+    assert_eq!(frame.a0, FD, "First argument must be restored");
+    assert_eq!((frame.a1, frame.a2), (0x2000, COUNT), "Other arguments untouched");
+    frame.pc = sepc;
+    let sepc = run_read(&mut frame, &mut interrupted, &action);
+    assert_eq!(frame.a0, COUNT, "Restarted read completes");
+    assert_eq!(sepc, ECALL_PC + 4, "Completed read returns past the ecall");
+    println!("test:    SUCCESS - interrupted read restarted and completed");
+
+    // 测试 2: 没有 SA_RESTART 时返回 -EINTR
+    println!("test: 2. Testing -EINTR without SA_RESTART...");
+    let action = SigAction::handler(dummy_handler, SigFlags::new(0));
+    let mut frame = SyscallFrame { a0: FD, a7: SYS_READ, pc: ECALL_PC, ..Default::default() };
+    let mut interrupted = false;
+    let sepc = run_read(&mut frame, &mut interrupted, &action);
+    assert_eq!(frame.a0, eintr(), "read returns -EINTR");
+    assert_eq!(sepc, ECALL_PC + 4, "No restart, continue after the ecall");
+    println!("test:    SUCCESS - read without SA_RESTART returns -EINTR");
+
+    // 测试 3: 其他情况
+    println!("test: 3. Testing non-restartable cases...");
+    let action = SigAction::handler(dummy_handler, SigFlags::new(SigFlags::SA_RESTART));
+    assert_eq!(syscall_restart_action(SYS_GETPID, eintr() as i64, Some(&action)), SyscallRestart::Interrupt,
+               "Only slow I/O syscalls restart");
+    assert_eq!(syscall_restart_action(SYS_READ, COUNT as i64, Some(&action)), SyscallRestart::None,
+               "Successful read is left alone");
+    println!("test:    SUCCESS - non-restartable cases unchanged");
+
+    // 测试 4: 没有处理函数
+    println!("test: 4. Testing transparent restart without a handler...");
+    assert_eq!(syscall_restart_action(SYS_READ, eintr() as i64, Some(&SigAction::new())), SyscallRestart::Restart,
+               "Default action restarts transparently");
+    assert_eq!(syscall_restart_action(SYS_READ, eintr() as i64, Some(&SigAction::ignore())), SyscallRestart::Restart,
+               "Ignored signal restarts transparently");
+    assert_eq!(syscall_restart_action(SYS_READ, eintr() as i64, None), SyscallRestart::Restart,
+               "No signal restarts transparently");
+    assert_eq!(syscall_restart_action(SYS_GETPID, eintr() as i64, None), SyscallRestart::Interrupt,
+               "Non-restartable syscall keeps -EINTR");
+    println!("test:    SUCCESS - restarted when no handler runs");
+
+    println!("test: ===== SA_RESTART Testing Completed =====");
+}
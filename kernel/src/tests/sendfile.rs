@@ -0,0 +1,62 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：sendfile 文件到管道的内核内复制
+//
+// 测试内容：
+// 1. 显式偏移复制：字节正确，偏移更新，文件位置不变
+// 2. NULL 偏移复制：使用并更新输入文件位置，遇到 EOF 时短复制
+// 3. 非常规文件输入返回 EINVAL
+
+use crate::println;
+use crate::fs::{create_pipe, do_sendfile};
+use crate::fs::file::{File, FileFlags, REG_FILE_OPS};
+use crate::fs::inode;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+pub fn test_sendfile() {
+    println!("test: ===== Testing sendfile() =====");
+
+    let data: Vec<u8> = (0..100u8).collect();
+    let mut in_file = File::new(FileFlags::new(FileFlags::O_RDONLY));
+    in_file.set_inode(Arc::new(inode::make_reg_inode_with_data(910, &data)));
+    in_file.set_ops(&REG_FILE_OPS);
+    let (pipe_read, pipe_write) = create_pipe();
+
+    // 测试 1: 显式偏移
+    println!("test: 1. Testing sendfile with explicit offset...");
+    let (copied, new_offset) = do_sendfile(&pipe_write, &in_file, Some(10), 50)
+        .expect("sendfile with offset failed");
+    assert_eq!(copied, 50, "Should copy 50 bytes");
+    assert_eq!(new_offset, 60, "Offset should advance by bytes copied");
+    assert_eq!(in_file.get_pos(), 0, "File position must not change with explicit offset");
+    let mut buf = [0u8; 128];
+    let n = unsafe { pipe_read.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, 50);
+    assert_eq!(&buf[..50], &data[10..60], "Pipe should contain file bytes 10..60");
+    println!("test:    SUCCESS - copied {} bytes, offset now {}", copied, new_offset);
+
+    // 测试 2: NULL 偏移，读到 EOF
+    println!("test: 2. Testing sendfile with file position...");
+    in_file.set_pos(80);
+    let (copied, new_offset) = do_sendfile(&pipe_write, &in_file, None, 1000)
+        .expect("sendfile with file position failed");
+    assert_eq!(copied, 20, "Should stop at EOF");
+    assert_eq!(new_offset, 100);
+    assert_eq!(in_file.get_pos(), 100, "File position should be updated");
+    let n = unsafe { pipe_read.read(buf.as_mut_ptr(), buf.len()) };
+    assert_eq!(n, 20);
+    assert_eq!(&buf[..20], &data[80..], "Pipe should contain file tail");
+    println!("test:    SUCCESS - copied tail up to EOF, pos updated");
+
+    // 测试 3: 管道作为输入
+    println!("test: 3. Testing non-regular input...");
+    let result = do_sendfile(&pipe_write, &pipe_read, None, 10);
+    assert_eq!(result.err(), Some(-22), "Pipe input should return EINVAL");
+    println!("test:    SUCCESS - non-regular input rejected");
+
+    println!("test: ===== sendfile() Testing Completed =====");
+}
@@ -0,0 +1,89 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：SeqLock 顺序锁
+//
+// 测试内容：
+// 1. 无写者时读取一致
+// 2. 写者更新期间开始的读操作需要重试
+// 3. 模拟写者与读者交替执行，读者从不看到撕裂的值
+// 4. jiffies 通过 seqlock 读取
+
+use crate::println;
+use crate::sync::SeqLock;
+use crate::drivers::timer;
+
+/// 用两个 32 位半字模拟“分两次更新”的 64 位时间值
+#[derive(Clone, Copy)]
+struct SplitTime {
+    hi: u32,
+    lo: u32,
+}
+
+pub fn test_seqlock() {
+    println!("test: ===== Testing SeqLock =====");
+
+    // 测试 1: 无写者时读取一致
+    println!("test: 1. Testing read without writer...");
+    let lock = SeqLock::new(SplitTime { hi: 0, lo: 0 });
+    let start = lock.read_seqbegin();
+    assert!(!lock.read_seqretry(start), "No writer, read should not retry");
+    println!("test:    SUCCESS - read without writer is consistent");
+
+    // 测试 2: 写者在读期间更新，读者必须重试
+    println!("test: 2. Testing reader retry across a write...");
+    let start = lock.read_seqbegin();
+    {
+        let mut guard = lock.write_seqlock();
+        guard.lo = 1;
+        // 写到一半：读者此时检查必须要求重试
+        assert!(lock.read_seqretry(start), "Reader must retry while writer is active");
+        guard.hi = 1;
+    }
+    assert!(lock.read_seqretry(start), "Reader must retry after a completed write");
+    let value = lock.read();
+    assert_eq!(value.hi, value.lo, "Read after write must be consistent");
+    println!("test:    SUCCESS - reader detected concurrent write");
+
+    // 测试 3: 模拟并发的写者递增，读者从不看到撕裂值
+    println!("test: 3. Testing interleaved writer increments...");
+    let counter = SeqLock::new(SplitTime { hi: 0, lo: 0 });
+    let mut torn = 0;
+    for i in 0..1000u32 {
+        // 读者开始
+        let seq = counter.read_seqbegin();
+
+        // 写者（模拟时钟中断）分两步更新
+        counter.write(|t| {
+            t.lo = t.lo.wrapping_add(1);
+            t.hi = t.hi.wrapping_add(1);
+        });
+
+        // 读者结束：序列号已变化，必须重试
+        if !counter.read_seqretry(seq) {
+            torn += 1;
+        }
+
+        let value = counter.read();
+        if value.hi != value.lo || value.lo != i + 1 {
+            torn += 1;
+        }
+    }
+    assert_eq!(torn, 0, "Reader observed a torn value");
+    println!("test:    SUCCESS - 1000 interleaved updates, no torn reads");
+
+    // 测试 4: jiffies 读取路径
+    println!("test: 4. Testing seqlock-backed jiffies read...");
+    let j1 = timer::get_jiffies();
+    let (sec1, nsec1) = timer::ktime_get_real_ts();
+    let j2 = timer::get_jiffies();
+    let (sec2, nsec2) = timer::ktime_get_real_ts();
+    assert!(j2 >= j1, "jiffies went backwards");
+    assert!(nsec1 < 1_000_000_000 && nsec2 < 1_000_000_000, "nsec out of range");
+    assert!((sec2, nsec2) >= (sec1, nsec1), "wall clock went backwards");
+    println!("test:    SUCCESS - jiffies={} wall={}.{:09}", j2, sec2, nsec2);
+
+    println!("test: ===== SeqLock Testing Completed =====");
+}
@@ -0,0 +1,83 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：备用信号栈 (sigaltstack)
+//
+// 测试内容：
+// 1. 设置后查询返回相同的栈，禁用后恢复为禁用状态
+// 2. 小于 MINSIGSTKSZ 的栈和未知标志被拒绝
+// 3. SA_ONSTACK 处理函数的信号帧建立在备用栈上，不带该标志时不使用备用栈
+
+use crate::println;
+use crate::errno::Errno;
+use crate::process::task::{SchedPolicy, Task};
+use crate::signal::{
+    do_sigaltstack, restore_sigcontext, setup_frame, ss_flags, SigAction, SigFlags, Signal, SignalStack,
+    MINSIGSTKSZ, SIGSTKSZ,
+};
+use alloc::boxed::Box;
+
+const ALT_SP: u64 = 0x4000_0000;
+
+unsafe extern "C" fn handler(_sig: i32) {}
+
+fn alt_stack() -> SignalStack {
+    SignalStack { ss_sp: ALT_SP, ss_size: SIGSTKSZ as u64, ss_flags: 0 }
+}
+
+pub fn test_sigaltstack() {
+    println!("test: ===== Testing sigaltstack =====");
+
+    // 测试 1: 设置与查询
+    println!("test: 1. Testing set/query round-trip...");
+    let mut task = Box::new(Task::new(3101, SchedPolicy::Normal));
+    let task_ptr = &mut *task as *mut Task;
+    unsafe {
+        let old = do_sigaltstack(task_ptr, Some(alt_stack())).expect("set alt stack");
+        assert!(old.is_disabled(), "No alt stack by default");
+        let cur = do_sigaltstack(task_ptr, None).expect("query alt stack");
+        assert_eq!((cur.ss_sp, cur.ss_size, cur.ss_flags), (ALT_SP, SIGSTKSZ as u64, 0), "Query returns what was set");
+        let disable = SignalStack { ss_sp: 0, ss_size: 0, ss_flags: ss_flags::SS_DISABLE };
+        do_sigaltstack(task_ptr, Some(disable)).expect("disable alt stack");
+        assert!(do_sigaltstack(task_ptr, None).unwrap().is_disabled(), "Disabled after SS_DISABLE");
+    }
+    println!("test:    SUCCESS - alt stack round-trips");
+
+    // 测试 2: 参数校验
+    println!("test: 2. Testing validation...");
+    unsafe {
+        let small = SignalStack { ss_size: MINSIGSTKSZ as u64 - 1, ..alt_stack() };
+        assert_eq!(do_sigaltstack(task_ptr, Some(small)).map(|_| ()), Err(Errno::OutOfMemory.as_neg_i32()),
+                   "Stack below MINSIGSTKSZ is rejected");
+        let bad = SignalStack { ss_flags: 0x80, ..alt_stack() };
+        assert_eq!(do_sigaltstack(task_ptr, Some(bad)).map(|_| ()), Err(Errno::InvalidArgument.as_neg_i32()),
+                   "Unknown flags are rejected");
+    }
+    println!("test:    SUCCESS - invalid stacks rejected");
+
+    // 测试 3: 信号帧位置
+    println!("test: 3. Testing SA_ONSTACK frame placement...");
+    let sig = Signal::SIGSEGV as i32;
+    unsafe {
+        do_sigaltstack(task_ptr, Some(alt_stack())).expect("set alt stack");
+
+        let plain = SigAction::handler(handler, SigFlags::new(0));
+        assert!(setup_frame(task_ptr, sig, &plain));
+        assert!(!task.sigstack.contains(task.sigframe_addr), "Without SA_ONSTACK the user stack is used");
+        assert!(restore_sigcontext(task_ptr, task.sigframe_addr));
+
+        let onstack = SigAction::handler(handler, SigFlags::new(SigFlags::SA_ONSTACK));
+        assert!(setup_frame(task_ptr, sig, &onstack));
+        assert!(task.sigstack.contains(task.sigframe_addr), "SA_ONSTACK frame is on the alt stack");
+        assert!(task.sigstack.is_on_stack(), "Alt stack is marked in use");
+        assert_eq!(do_sigaltstack(task_ptr, Some(alt_stack())).map(|_| ()),
+                   Err(Errno::OperationNotPermitted.as_neg_i32()), "Cannot change the stack while on it");
+        assert!(restore_sigcontext(task_ptr, task.sigframe_addr));
+        assert!(!task.sigstack.is_on_stack(), "sigreturn leaves the alt stack");
+    }
+    println!("test:    SUCCESS - SA_ONSTACK handler runs on the alt stack");
+
+    println!("test: ===== sigaltstack Testing Completed =====");
+}
@@ -0,0 +1,78 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：子进程退出通知 (SIGCHLD)
+//
+// 测试内容：
+// 1. SIG_DFL：唤醒 wait4 中的父进程，但不投递 SIGCHLD（不会打断 wait4）
+// 2. 父进程显式忽略 SIGCHLD 时自动回收，不投递信号
+// 3. SA_NOCLDWAIT + 处理函数：自动回收，但仍投递 SIGCHLD
+// 4. 没有信号结构的父进程按 SIG_DFL 处理；屏蔽 SIGCHLD 时信号保持挂起
+
+use crate::println;
+use crate::process::task::{SchedPolicy, Task, TaskState};
+use crate::sched::notify_parent;
+use crate::signal::{SigAction, SigFlags, Signal, SignalStruct};
+use alloc::boxed::Box;
+
+/// 创建带信号处理结构、并在 wait4 中睡眠的父进程
+fn sleeping_parent(pid: u32) -> Box<Task> {
+    let mut parent = Box::new(Task::new(pid, SchedPolicy::Normal));
+    parent.signal = Some(Box::new(SignalStruct::new()));
+    parent.set_state(TaskState::Interruptible);
+    parent
+}
+
+pub fn test_sigchld() {
+    println!("test: ===== Testing SIGCHLD exit notification =====");
+    let sigchld = Signal::SIGCHLD as i32;
+
+    // 测试 1: 默认处理 (SIG_DFL)
+    println!("test: 1. Testing child exit wakes a SIG_DFL parent...");
+    let mut parent = sleeping_parent(2001);
+    let autoreap = notify_parent(&mut *parent as *mut Task);
+    assert!(!autoreap, "SIG_DFL parent must keep the zombie for wait4");
+    assert!(!parent.pending.has(sigchld), "SIG_DFL SIGCHLD must not be queued");
+    assert_eq!(parent.state(), TaskState::Running, "Parent blocked in wait4 should be woken");
+    println!("test:    SUCCESS - parent woken, no SIGCHLD queued");
+
+    // 测试 2: 显式忽略 SIGCHLD
+    println!("test: 2. Testing ignoring parent auto-reaps...");
+    let mut parent = sleeping_parent(2002);
+    assert!(parent.signal.as_mut().unwrap().set_action(sigchld, SigAction::ignore()).is_ok());
+    let autoreap = notify_parent(&mut *parent as *mut Task);
+    assert!(autoreap, "SIG_IGN parent must not leave a zombie");
+    assert!(!parent.pending.has(sigchld), "Ignored SIGCHLD must not be queued");
+    assert_eq!(parent.state(), TaskState::Running, "Parent should still be woken");
+    println!("test:    SUCCESS - child auto-reaped, no SIGCHLD queued");
+
+    // 测试 3: SA_NOCLDWAIT + 处理函数
+    println!("test: 3. Testing SA_NOCLDWAIT with handler...");
+    let mut parent = sleeping_parent(2003);
+    let action = SigAction {
+        sa_handler: 0x1000,
+        sa_flags: SigFlags::new(SigFlags::SA_NOCLDWAIT),
+        sa_mask: 0,
+    };
+    assert!(parent.signal.as_mut().unwrap().set_action(sigchld, action).is_ok());
+    let autoreap = notify_parent(&mut *parent as *mut Task);
+    assert!(autoreap, "SA_NOCLDWAIT parent must not leave a zombie");
+    assert!(parent.pending.has(sigchld), "Handler should still receive SIGCHLD");
+    println!("test:    SUCCESS - auto-reaped and SIGCHLD delivered to handler");
+
+    // 测试 4: 没有信号结构的父进程
+    println!("test: 4. Testing parent without signal struct and blocked SIGCHLD...");
+    let mut parent = Box::new(Task::new(2004, SchedPolicy::Normal));
+    let autoreap = notify_parent(&mut *parent as *mut Task);
+    assert!(!autoreap, "Parent without signal struct must reap via wait4");
+    assert!(!parent.pending.has(sigchld), "Default action drops SIGCHLD");
+    let mut parent = sleeping_parent(2005);
+    parent.sigmask |= 1u64 << (sigchld - 1);
+    assert!(!notify_parent(&mut *parent as *mut Task));
+    assert!(parent.pending.has(sigchld), "Blocked SIGCHLD stays pending until unblocked");
+    println!("test:    SUCCESS - SIGCHLD dropped, kept pending while blocked");
+
+    println!("test: ===== SIGCHLD Testing Completed =====");
+}
@@ -71,9 +71,10 @@ pub fn test_signal() {
     let sigstop_action = sig_struct.get_action(Signal::SIGSTOP as i32).unwrap();
     assert_eq!(sigstop_action.action(), SigActionKind::Default, "SIGSTOP should be Default");
 
-    // SIGCHLD 默认是 Ignore
+    // SIGCHLD 默认是 SIG_DFL（默认动作为忽略，但不会自动回收子进程）
     let sigchld_action = sig_struct.get_action(Signal::SIGCHLD as i32).unwrap();
-    assert_eq!(sigchld_action.action(), SigActionKind::Ignore, "SIGCHLD should be Ignore by default");
+    assert_eq!(sigchld_action.action(), SigActionKind::Default, "SIGCHLD should be SIG_DFL by default");
+    assert!(!sig_struct.child_autoreap(), "SIG_DFL SIGCHLD must not auto-reap");
 
     // 其他信号默认是 Default (终止)
     let sigterm_action = sig_struct.get_action(Signal::SIGTERM as i32).unwrap();
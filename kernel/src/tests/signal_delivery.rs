@@ -0,0 +1,145 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：trap 返回路径上的信号投递
+//
+// 测试内容：
+// 1. 被 SA_RESTART 信号打断的 wait4：先回退 PC，再进入处理函数，信号帧保存 ecall 地址；
+//    处理函数的 ra 指向 rt_sigreturn trampoline；
+//    rt_sigreturn 恢复寄存器后重新执行 ecall，信号不再处于待处理状态
+// 2. 处理函数运行期间屏蔽该信号和 sa_mask
+// 3. 没有待处理信号、信号被屏蔽或使用默认动作时 TrapFrame 不变
+
+use crate::println;
+use crate::arch::riscv64::mm::SIGRETURN_TRAMPOLINE;
+use crate::arch::riscv64::syscall::{apply_syscall_restart, SyscallFrame};
+use crate::arch::riscv64::trap::{deliver_signals, restore_signal_frame, TrapFrame};
+use crate::errno::Errno;
+use crate::process::task::{SchedPolicy, Task};
+use crate::signal::{syscall_restart_action, SigAction, SigFlags, Signal, SignalStruct, SyscallRestart};
+use alloc::boxed::Box;
+
+const SYS_WAIT4: u64 = 260;
+const ECALL_PC: u64 = 0x1_0000;
+const HANDLER: u64 = 0x2_0000;
+const USER_SP: u64 = 0x7fff_e000;
+const STATUS_PTR: u64 = 0x3_0000;
+
+/// trap.S 的栈布局：用户 sp 保存在 TrapFrame 之前 8 字节处
+#[repr(C)]
+struct UserTrap {
+    sp: u64,
+    frame: TrapFrame,
+}
+
+impl UserTrap {
+    fn new(sepc: u64) -> Box<Self> {
+        // SAFETY: TrapFrame 只包含 u64 字段
+        let mut trap: Box<Self> = Box::new(unsafe { core::mem::zeroed() });
+        trap.sp = USER_SP;
+        trap.frame.sepc = sepc;
+        trap.frame.ra = 0x1_0040;
+        trap.frame.t0 = 0x77;
+        trap.frame.t6 = 0x66;
+        trap
+    }
+}
+
+fn sig_bit(sig: Signal) -> u64 {
+    1u64 << (sig as u64 - 1)
+}
+
+pub fn test_signal_delivery() {
+    println!("test: ===== Testing signal delivery on trap return =====");
+
+    let mut task = Box::new(Task::new(3601, SchedPolicy::Normal));
+    task.signal = Some(Box::new(SignalStruct::new()));
+    let task_ptr = &mut *task as *mut Task;
+    let sigchld = Signal::SIGCHLD as i32;
+
+    // 测试 1: wait4 被打断后重启
+    println!("test: 1. Testing restarted wait4 through the handler frame...");
+    let action = SigAction {
+        sa_handler: HANDLER as usize,
+        sa_flags: SigFlags::new(SigFlags::SA_RESTART),
+        sa_mask: sig_bit(Signal::SIGUSR2),
+    };
+    assert!(task.signal.as_mut().unwrap().set_action(sigchld, action).is_ok());
+    task.pending.add(sigchld);
+
+    // 系统调用返回 -EINTR，按 SA_RESTART 回退 PC（syscall_handler）
+    let mut sys = SyscallFrame { a0: -1_i64 as u64, a1: STATUS_PTR, a7: SYS_WAIT4, pc: ECALL_PC, ..Default::default() };
+    let orig_a0 = sys.a0;
+    sys.a0 = Errno::InterruptedSystemCall.as_neg_i32() as i64 as u64;
+    let restart = syscall_restart_action(SYS_WAIT4, sys.a0 as i64, Some(&action));
+    assert_eq!(restart, SyscallRestart::Restart);
+    apply_syscall_restart(&mut sys, orig_a0, restart);
+
+    // trap_handler 写回 TrapFrame 并跳过 ecall
+    let mut trap = UserTrap::new(sys.pc.wrapping_add(4));
+    trap.frame.a0 = sys.a0;
+    trap.frame.a1 = sys.a1;
+    trap.frame.a7 = sys.a7;
+    unsafe { deliver_signals(task_ptr, &mut trap.frame) };
+
+    assert_eq!(trap.frame.sepc, HANDLER, "Returns to user mode in the handler");
+    assert_eq!(trap.frame.a0, sigchld as u64, "Handler gets the signal number");
+    assert_eq!(trap.frame.ra, SIGRETURN_TRAMPOLINE, "Handler returns through the rt_sigreturn trampoline");
+    assert!(trap.sp < USER_SP && trap.sp % 16 == 0, "Frame below the interrupted stack");
+    assert_eq!(task.sigframe_addr, trap.sp);
+    assert!(!task.pending.has(sigchld), "Signal consumed, wait4 will not see it again");
+    let saved = task.sigframe.expect("signal frame saved");
+    assert_eq!(saved.uc.uc_pc, ECALL_PC, "Frame resumes at the rewound ecall");
+    assert_eq!(task.sigmask, sig_bit(Signal::SIGCHLD) | sig_bit(Signal::SIGUSR2), "Signal and sa_mask blocked");
+
+    // 处理函数覆盖调用者保存寄存器后执行 rt_sigreturn
+    trap.frame.a0 = 0;
+    trap.frame.ra = 0;
+    trap.frame.t0 = 0;
+    trap.frame.t6 = 0;
+    trap.sp -= 64;
+    assert!(unsafe { restore_signal_frame(task_ptr, &mut trap.frame) });
+    assert_eq!(trap.frame.sepc, ECALL_PC, "ecall executes again");
+    assert_eq!((trap.frame.a0, trap.frame.a1, trap.frame.a7), (orig_a0, STATUS_PTR, SYS_WAIT4), "Arguments restored");
+    assert_eq!((trap.frame.ra, trap.frame.t0, trap.frame.t6), (0x1_0040, 0x77, 0x66), "Caller-saved registers restored");
+    assert_eq!(trap.sp, USER_SP, "User stack restored");
+    assert_eq!(task.sigmask, 0, "Mask restored");
+    assert!(!unsafe { restore_signal_frame(task_ptr, &mut trap.frame) }, "No frame left");
+    println!("test:    SUCCESS - handler at {:#x}, wait4 restarted at {:#x}", HANDLER, ECALL_PC);
+
+    // 测试 2: SA_NODEFER 不屏蔽自身
+    println!("test: 2. Testing SA_NODEFER...");
+    let nodefer = SigAction { sa_handler: HANDLER as usize, sa_flags: SigFlags::new(SigFlags::SA_NODEFER), sa_mask: 0 };
+    assert!(task.signal.as_mut().unwrap().set_action(sigchld, nodefer).is_ok());
+    task.pending.add(sigchld);
+    let mut trap = UserTrap::new(ECALL_PC + 4);
+    unsafe { deliver_signals(task_ptr, &mut trap.frame) };
+    assert_eq!(trap.frame.sepc, HANDLER);
+    assert_eq!(task.sigmask, 0, "SA_NODEFER leaves the signal unblocked");
+    assert!(unsafe { restore_signal_frame(task_ptr, &mut trap.frame) });
+    println!("test:    SUCCESS - mask unchanged with SA_NODEFER");
+
+    // 测试 3: 不投递的情况
+    println!("test: 3. Testing frames left untouched...");
+    let mut trap = UserTrap::new(ECALL_PC + 4);
+    unsafe { deliver_signals(task_ptr, &mut trap.frame) };
+    assert_eq!((trap.frame.sepc, trap.sp), (ECALL_PC + 4, USER_SP), "Nothing pending");
+
+    task.sigmask = sig_bit(Signal::SIGCHLD);
+    task.pending.add(sigchld);
+    unsafe { deliver_signals(task_ptr, &mut trap.frame) };
+    assert_eq!(trap.frame.sepc, ECALL_PC + 4, "Blocked signal stays pending");
+    assert!(task.pending.has(sigchld));
+    task.sigmask = 0;
+
+    assert!(task.signal.as_mut().unwrap().set_action(sigchld, SigAction::new()).is_ok());
+    unsafe { deliver_signals(task_ptr, &mut trap.frame) };
+    assert_eq!((trap.frame.sepc, trap.sp), (ECALL_PC + 4, USER_SP), "Default action builds no frame");
+    assert!(!task.pending.has(sigchld), "Default action consumes the signal");
+    assert!(task.sigframe.is_none());
+    println!("test:    SUCCESS - no frame without a handler");
+
+    println!("test: ===== Signal Delivery Testing Completed =====");
+}
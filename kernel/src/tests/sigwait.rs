@@ -0,0 +1,72 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：同步等待信号 (sigsuspend / sigtimedwait)
+//
+// 测试内容：
+// 1. sigsuspend 忽略被屏蔽的信号，收到未屏蔽的信号后返回 -EINTR 并恢复掩码
+// 2. sigtimedwait 取走集合中的待处理信号
+// 3. sigtimedwait 超时返回 -EAGAIN
+
+use crate::println;
+use crate::errno::Errno;
+use crate::process::task::{SchedPolicy, Task};
+use crate::signal::{sigsuspend_task, sigtimedwait_task, Signal};
+use alloc::boxed::Box;
+use core::cell::Cell;
+
+fn bit(sig: Signal) -> u64 {
+    1 << (sig as u64 - 1)
+}
+
+pub fn test_sigwait() {
+    println!("test: ===== Testing sigsuspend/sigtimedwait =====");
+
+    // 测试 1: sigsuspend
+    println!("test: 1. Testing sigsuspend wakes on unmasked signal...");
+    let mut task = Box::new(Task::new(3001, SchedPolicy::Normal));
+    let task_ptr = &mut *task as *mut Task;
+    task.sigmask = bit(Signal::SIGUSR2);
+    let mut sleeps = 0;
+    let ret = unsafe {
+        sigsuspend_task(task_ptr, bit(Signal::SIGUSR1), || {
+            // 第一次睡眠期间到达被屏蔽的 SIGUSR1，第二次到达 SIGUSR2
+            sleeps += 1;
+            let sig = if sleeps == 1 { Signal::SIGUSR1 } else { Signal::SIGUSR2 };
+            (*task_ptr).pending.add(sig as i32);
+        })
+    };
+    assert_eq!(ret, Errno::InterruptedSystemCall.as_neg_i32(), "sigsuspend returns -EINTR");
+    assert_eq!(sleeps, 2, "Masked SIGUSR1 must not wake the task");
+    assert_eq!(task.sigmask, bit(Signal::SIGUSR2), "Old mask is restored");
+    println!("test:    SUCCESS - sigsuspend woke on SIGUSR2");
+
+    // 测试 2: sigtimedwait 返回待处理信号
+    println!("test: 2. Testing sigtimedwait returns pending signal...");
+    let mut task = Box::new(Task::new(3002, SchedPolicy::Normal));
+    let task_ptr = &mut *task as *mut Task;
+    task.pending.add(Signal::SIGUSR2 as i32);
+    let set = bit(Signal::SIGUSR1) | bit(Signal::SIGUSR2);
+    let info = unsafe { sigtimedwait_task(task_ptr, set, Some(10), || 0, || {}) };
+    assert_eq!(info.map(|i| i.si_signo), Ok(Signal::SIGUSR2 as i32), "Pending SIGUSR2 is returned");
+    assert!(!task.pending.has(Signal::SIGUSR2 as i32), "Returned signal is consumed");
+    println!("test:    SUCCESS - sigtimedwait returned SIGUSR2");
+
+    // 测试 3: sigtimedwait 超时
+    println!("test: 3. Testing sigtimedwait timeout...");
+    let now = Cell::new(0u64);
+    let mut sleeps = 0;
+    let result = unsafe {
+        sigtimedwait_task(task_ptr, set, Some(5), || now.get(), || {
+            sleeps += 1;
+            now.set(now.get() + 1);
+        })
+    };
+    assert_eq!(result.map(|i| i.si_signo), Err(Errno::TryAgain.as_neg_i32()), "Times out with -EAGAIN");
+    assert_eq!(sleeps, 5, "Sleeps until the deadline");
+    println!("test:    SUCCESS - sigtimedwait timed out");
+
+    println!("test: ===== sigsuspend/sigtimedwait Testing Completed =====");
+}
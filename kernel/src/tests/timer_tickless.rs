@@ -0,0 +1,84 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：可配置时钟频率与 tickless idle
+//
+// 测试内容：
+// 1. 配置的 HZ 决定周期性 tick 的比较值
+// 2. 延迟工作队列返回最近的截止时间并执行到期工作；cancel_at 只取消指定截止时间的工作
+// 3. tickless idle 将定时器设置到下一个工作的截止时间
+// 4. 截止时间临近或队列为空时的边界情况
+
+use crate::println;
+use crate::config::TIMER_HZ;
+use crate::drivers::timer::delayed_work::DelayedWorkQueue;
+use crate::drivers::timer::{
+    cycles_per_tick, tick_deadline, tickless_deadline, CLOCK_FREQ, HZ, MAX_IDLE_TICKS,
+    TIME_SLICE_TICKS,
+};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+static WORK_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+fn count_work() {
+    WORK_RUNS.fetch_add(1, Ordering::SeqCst);
+}
+
+fn other_work() {}
+
+pub fn test_timer_tickless() {
+    println!("test: ===== Testing timer frequency and tickless idle =====");
+
+    // 测试 1: 配置的频率
+    println!("test: 1. Testing configured HZ sets the compare value...");
+    assert_eq!(HZ, TIMER_HZ, "Timer HZ must come from config");
+    assert_eq!(TIME_SLICE_TICKS, CLOCK_FREQ / TIMER_HZ);
+    assert_eq!(cycles_per_tick(100), 100_000, "HZ=100 -> 10ms at 10MHz");
+    assert_eq!(cycles_per_tick(250), 40_000, "HZ=250 -> 4ms at 10MHz");
+    assert_eq!(cycles_per_tick(1000), 10_000, "HZ=1000 -> 1ms at 10MHz");
+    assert_eq!(tick_deadline(12_345), 12_345 + CLOCK_FREQ / TIMER_HZ);
+    println!("test:    SUCCESS - compare value is now + CLOCK_FREQ/HZ");
+
+    // 测试 2: 延迟工作队列
+    println!("test: 2. Testing delayed work queue...");
+    let queue = DelayedWorkQueue::new();
+    assert_eq!(queue.next_deadline(), None, "Empty queue has no deadline");
+    queue.queue(50, count_work);
+    queue.queue(20, count_work);
+    queue.queue(80, other_work);
+    assert_eq!(queue.next_deadline(), Some(20), "Earliest deadline should win");
+    WORK_RUNS.store(0, Ordering::SeqCst);
+    assert_eq!(queue.run_expired(19), 0, "Nothing expired yet");
+    assert_eq!(queue.run_expired(50), 2, "Two works expired");
+    assert_eq!(WORK_RUNS.load(Ordering::SeqCst), 2);
+    assert_eq!(queue.next_deadline(), Some(80));
+    assert!(queue.cancel(other_work), "Cancel should remove pending work");
+    assert!(queue.is_empty());
+    queue.queue(30, count_work);
+    queue.queue(60, count_work);
+    assert!(!queue.cancel_at(40, count_work), "No work at that deadline");
+    assert!(queue.cancel_at(30, count_work), "Cancel one caller's work");
+    assert_eq!(queue.next_deadline(), Some(60), "Other work with the same callback kept");
+    assert!(queue.cancel(count_work));
+    println!("test:    SUCCESS - expired work runs, deadlines tracked");
+
+    // 测试 3: tickless idle 设置到下一个工作截止时间
+    println!("test: 3. Testing tickless idle programs the next work deadline...");
+    let now = 1_000_000;
+    let deadline = tickless_deadline(now, 10, Some(30));
+    assert_eq!(deadline, now + 20 * TIME_SLICE_TICKS, "Timer should fire at the work deadline");
+    assert!(deadline > tick_deadline(now), "Tickless idle should skip periodic ticks");
+    println!("test:    SUCCESS - timer programmed 20 ticks ahead");
+
+    // 测试 4: 边界情况
+    println!("test: 4. Testing tickless idle edge cases...");
+    assert_eq!(tickless_deadline(now, 10, Some(11)), tick_deadline(now), "Imminent work keeps the periodic tick");
+    assert_eq!(tickless_deadline(now, 10, Some(5)), tick_deadline(now), "Overdue work keeps the periodic tick");
+    assert_eq!(tickless_deadline(now, 10, None), now + MAX_IDLE_TICKS * TIME_SLICE_TICKS, "Empty queue sleeps for the maximum idle interval");
+    assert_eq!(tickless_deadline(now, 0, Some(u64::MAX)), now + MAX_IDLE_TICKS * TIME_SLICE_TICKS, "Far-off work is capped");
+    println!("test:    SUCCESS - edge cases handled");
+
+    println!("test: ===== Timer Testing Completed =====");
+}
@@ -0,0 +1,82 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：跨 CPU TLB shootdown
+//
+// 测试内容：
+// 1. shootdown 只通知目标 CPU，等所有目标确认后才返回（模拟 IPI）
+// 2. 不在请求中的 CPU 和重复的 IPI 不会确认
+// 3. 没有目标 CPU 时不发送 IPI
+// 4. 只有运行该地址空间的 CPU 成为目标，没有其他 CPU 使用时立即返回
+
+use crate::println;
+use crate::arch::riscv64::tlb::{flush_tlb_range_mm, FlushRequest};
+use crate::mm::page::PAGE_SIZE;
+use alloc::vec::Vec;
+
+pub fn test_tlb_shootdown() {
+    println!("test: ===== Testing TLB shootdown =====");
+
+    let start = 0x1000_0000;
+    let end = start + 4 * PAGE_SIZE;
+
+    // 测试 1: 等待确认
+    println!("test: 1. Testing shootdown waits for acknowledgements...");
+    let request = FlushRequest::new();
+    let targets = 0b1010;
+    let mut sent = Vec::new();
+    let mut waits = 0;
+    request.run(
+        targets,
+        start,
+        end,
+        |cpu| sent.push(cpu),
+        || {
+            // 模拟目标 CPU 依次处理 IPI：每次等待只有一个 CPU 确认
+            waits += 1;
+            let cpu = request.pending().trailing_zeros() as usize;
+            assert!(request.ack(cpu), "Target CPU acknowledges the flush");
+        },
+    );
+    assert_eq!(sent, [1, 3], "IPIs go to the CPUs sharing the mm");
+    assert_eq!(waits, 2, "Initiator waits until every target has acknowledged");
+    assert_eq!(request.pending(), 0, "No acknowledgement outstanding after return");
+    println!("test:    SUCCESS - shootdown returned after all acks");
+
+    // 测试 2: 非目标与重复确认
+    println!("test: 2. Testing stray and duplicate IPIs...");
+    assert!(!request.ack(1), "Duplicate IPI after the ack is ignored");
+    let request = FlushRequest::new();
+    let mut sent = Vec::new();
+    request.run(0b100, start, end, |cpu| sent.push(cpu), || {
+        assert!(!request.ack(0), "CPU outside the request does not ack");
+        assert!(request.ack(2));
+    });
+    assert_eq!(sent, [2]);
+    println!("test:    SUCCESS - only targeted CPUs acknowledge");
+
+    // 测试 3: 没有目标
+    println!("test: 3. Testing shootdown without targets...");
+    let request = FlushRequest::new();
+    request.run(0, start, end, |_| panic!("no IPI expected"), || panic!("nothing to wait for"));
+    println!("test:    SUCCESS - no IPI sent without targets");
+
+    // 测试 4: 地址空间的目标 CPU
+    println!("test: 4. Testing target selection by address space...");
+    let this_cpu = crate::arch::cpu_id() as usize;
+    match crate::sched::current().and_then(|task| task.address_space()) {
+        Some(mm) => {
+            let mask = crate::sched::cpus_running_mm(mm.root_ppn());
+            assert!(mask & (1 << this_cpu) != 0, "This CPU runs the current address space");
+        }
+        None => println!("test:    Current task has no address space - skipping mask check"),
+    }
+    let unused_root = u64::MAX >> 20;
+    assert_eq!(crate::sched::cpus_running_mm(unused_root), 0, "No CPU runs an unknown page table");
+    flush_tlb_range_mm(unused_root, start, end);
+    println!("test:    SUCCESS - only CPUs sharing the mm are targeted");
+
+    println!("test: ===== TLB Shootdown Testing Completed =====");
+}
@@ -0,0 +1,105 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：终端行规程 (ECHO/ICANON)
+//
+// 测试内容：
+// 1. 默认 termios 为规范模式并回显，'\r' 转换为 '\n' 回显为 "\r\n"
+// 2. 关闭 ECHO 后输入的字节不回显
+// 3. 规范模式下 read 在换行前不返回，之后只返回一行；VERASE/VKILL 编辑当前行，VEOF 在空行表示文件结束
+// 4. 非规范模式下字节立即可读，切换模式时正在编辑的行变为可读
+// 5. 通过 UART 的 TCGETS/TCSETS ioctl 切换 ECHO/ICANON
+
+use crate::println;
+use crate::fs::char_dev::uart_file_ioctl;
+use crate::fs::file::{File, FileFlags};
+use crate::fs::tty::*;
+use alloc::vec::Vec;
+
+/// 输入一串字节，返回回显的内容
+fn type_bytes(ldisc: &mut LineDiscipline, bytes: &[u8]) -> Vec<u8> {
+    let mut echoed = Vec::new();
+    for &c in bytes {
+        ldisc.receive(c, |e| echoed.push(e));
+    }
+    echoed
+}
+
+pub fn test_tty() {
+    println!("test: ===== Testing tty line discipline =====");
+
+    // 测试 1: 默认设置
+    println!("test: 1. Testing default termios...");
+    let mut ldisc = LineDiscipline::new();
+    let termios = ldisc.termios();
+    assert!(termios.is_canonical() && termios.echo(), "Console starts in cooked mode with echo");
+    assert_eq!(core::mem::size_of::<Termios>(), 36, "struct termios layout");
+    assert_eq!(type_bytes(&mut ldisc, b"hi\r"), b"hi\r\n", "Typed bytes are echoed, CR becomes CRLF");
+    let mut buf = [0u8; 16];
+    assert_eq!(ldisc.read(&mut buf), Some(3));
+    assert_eq!(&buf[..3], b"hi\n", "ICRNL turns CR into NL");
+    println!("test:    SUCCESS - cooked mode with echo by default");
+
+    // 测试 2: ECHO 关闭
+    println!("test: 2. Testing ECHO off...");
+    let mut quiet = termios;
+    quiet.c_lflag &= !lflag::ECHO;
+    ldisc.set_termios(quiet);
+    assert!(type_bytes(&mut ldisc, b"secret\n").is_empty(), "Nothing is echoed with ECHO off");
+    assert_eq!(ldisc.read(&mut buf), Some(7), "Input is still delivered");
+    assert_eq!(&buf[..7], b"secret\n");
+    ldisc.set_termios(termios);
+    println!("test:    SUCCESS - ECHO off suppresses echo");
+
+    // 测试 3: 规范模式
+    println!("test: 3. Testing ICANON line buffering...");
+    let echoed = type_bytes(&mut ldisc, b"lx\x7fs");
+    assert_eq!(echoed, b"lx\x08 \x08s", "Erase removes the character on screen");
+    assert_eq!(ldisc.read(&mut buf), None, "No data before newline");
+    assert!(!ldisc.is_readable());
+    type_bytes(&mut ldisc, b"\nsecond\n");
+    assert_eq!(ldisc.read(&mut buf), Some(3), "Only the first line is returned");
+    assert_eq!(&buf[..3], b"ls\n");
+    assert_eq!(ldisc.read(&mut buf), Some(7));
+    assert_eq!(&buf[..7], b"second\n");
+    type_bytes(&mut ldisc, b"junk\x15ok\n");
+    assert_eq!(ldisc.read(&mut buf), Some(3), "VKILL discards the line");
+    assert_eq!(&buf[..3], b"ok\n");
+    type_bytes(&mut ldisc, b"\x04");
+    assert_eq!(ldisc.read(&mut buf), Some(0), "VEOF on an empty line is end of file");
+    assert_eq!(ldisc.read(&mut buf), None);
+    println!("test:    SUCCESS - reads return whole lines");
+
+    // 测试 4: 非规范模式
+    println!("test: 4. Testing non-canonical mode...");
+    type_bytes(&mut ldisc, b"ab");
+    let mut raw = termios;
+    raw.c_lflag &= !(lflag::ICANON | lflag::ECHO);
+    ldisc.set_termios(raw);
+    assert_eq!(ldisc.pending(), 2, "Partial line becomes readable when leaving ICANON");
+    type_bytes(&mut ldisc, b"c\x7f");
+    assert_eq!(ldisc.read(&mut buf), Some(4), "Bytes are delivered without waiting for newline");
+    assert_eq!(&buf[..4], b"abc\x7f", "Erase is not interpreted");
+    println!("test:    SUCCESS - non-canonical input is immediate");
+
+    // 测试 5: ioctl
+    println!("test: 5. Testing TCGETS/TCSETS...");
+    let file = File::new(FileFlags::new(FileFlags::O_RDWR));
+    let mut saved = Termios::new();
+    assert_eq!(uart_file_ioctl(&file, TCGETS, &mut saved as *mut Termios as usize), 0);
+    let mut changed = saved;
+    changed.c_lflag &= !(lflag::ECHO | lflag::ICANON);
+    assert_eq!(uart_file_ioctl(&file, TCSETS, &changed as *const Termios as usize), 0);
+    let mut current = Termios::new();
+    assert_eq!(uart_file_ioctl(&file, TCGETS, &mut current as *mut Termios as usize), 0);
+    assert!(!current.echo() && !current.is_canonical(), "ECHO and ICANON are cleared");
+    assert_eq!(console_tty().termios(), changed, "Console line discipline uses the new settings");
+    assert_eq!(uart_file_ioctl(&file, TCSETS, 0), -14, "NULL termios is EFAULT");
+    assert_eq!(uart_file_ioctl(&file, TCSETS, &saved as *const Termios as usize), 0);
+    assert_eq!(console_tty().termios(), saved, "Settings restored");
+    println!("test:    SUCCESS - termios set through ioctl");
+
+    println!("test: ===== TTY Line Discipline Testing Completed =====");
+}
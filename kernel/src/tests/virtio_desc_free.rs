@@ -0,0 +1,117 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：VirtQueue 描述符的分配与回收
+//
+// 测试内容：
+// 1. 释放描述符链时沿 NEXT 标志归还链上的每个描述符
+// 2. 对模拟设备连续发出 1000 次读请求，完成后空闲链表回到满状态
+// 3. 多个请求乱序完成时各自的描述符链都能正确回收
+// 4. 描述符耗尽时分配失败，重置后全部可用
+
+use crate::println;
+use crate::drivers::virtio::queue::{desc_flags, UsedElem, VirtQueue};
+
+/// 模拟设备寄存器（通知、中断状态、中断应答）
+static mut MOCK_REGS: [u32; 3] = [0; 3];
+
+const QUEUE_SIZE: u16 = 8;
+
+fn mock_queue() -> Option<VirtQueue> {
+    let regs = &raw mut MOCK_REGS as u64;
+    VirtQueue::new(QUEUE_SIZE, 0, regs, regs + 4, regs + 8)
+}
+
+/// 按读请求的格式分配并填写三个描述符，返回链头
+fn build_read_chain(queue: &mut VirtQueue) -> Option<u16> {
+    use desc_flags::{VIRTQ_DESC_F_NEXT, VIRTQ_DESC_F_WRITE};
+
+    if queue.num_free() < 3 {
+        return None;
+    }
+    let header = queue.alloc_desc()?;
+    let data = queue.alloc_desc()?;
+    let resp = queue.alloc_desc()?;
+    queue.set_desc(header, 0x8000_0000, 16, VIRTQ_DESC_F_NEXT, data);
+    queue.set_desc(data, 0x8000_1000, 512, VIRTQ_DESC_F_WRITE | VIRTQ_DESC_F_NEXT, resp);
+    queue.set_desc(resp, 0x8000_2000, 1, VIRTQ_DESC_F_WRITE, 0);
+    Some(header)
+}
+
+/// 模拟设备完成以 `head` 为链头的请求：写入 used ring 元素并递增 used.idx
+fn device_complete(queue: &mut VirtQueue, head: u16) {
+    unsafe {
+        let idx_ptr = (queue.used as usize + 2) as *mut u16;
+        let idx = core::ptr::read_volatile(idx_ptr);
+        let ring = (queue.used as usize + 4) as *mut UsedElem;
+        let elem = UsedElem { id: head as u32, len: 513 };
+        core::ptr::write_volatile(ring.add(idx as usize % QUEUE_SIZE as usize), elem);
+        core::ptr::write_volatile(idx_ptr, idx.wrapping_add(1));
+    }
+}
+
+pub fn test_virtio_desc_free() {
+    println!("test: ===== Testing VirtQueue descriptor recycling =====");
+
+    let Some(mut queue) = mock_queue() else {
+        println!("test:    No DMA memory - skipping");
+        return;
+    };
+
+    // 测试 1: 释放描述符链
+    println!("test: 1. Testing free_desc_chain...");
+    assert_eq!(queue.num_free(), QUEUE_SIZE, "New queue has every descriptor free");
+    let head = build_read_chain(&mut queue).expect("allocate a read chain");
+    assert_eq!(queue.num_free(), QUEUE_SIZE - 3);
+    assert_eq!(queue.free_desc_chain(head), 3, "Whole chain is freed from its head");
+    assert_eq!(queue.num_free(), QUEUE_SIZE);
+    println!("test:    SUCCESS - chain returned to the free list");
+
+    // 测试 2: 1000 次读请求
+    println!("test: 2. Testing 1000 reads against a mock device...");
+    for i in 0..1000 {
+        let head = build_read_chain(&mut queue)
+            .unwrap_or_else(|| panic!("descriptor table exhausted at request {}", i));
+        queue.submit(head);
+        device_complete(&mut queue, head);
+        let used = queue.pop_used().expect("device completed the request");
+        assert_eq!(used.id, head as u32, "Used element names the chain head");
+        assert_eq!(queue.free_desc_chain(used.id as u16), 3);
+    }
+    assert!(queue.pop_used().is_none(), "No completion left over");
+    assert_eq!(queue.num_free(), QUEUE_SIZE, "Free list is full after 1000 requests");
+    println!("test:    SUCCESS - descriptors reused across 1000 requests");
+
+    // 测试 3: 乱序完成
+    println!("test: 3. Testing out-of-order completion...");
+    let first = build_read_chain(&mut queue).expect("first request");
+    let second = build_read_chain(&mut queue).expect("second request");
+    assert_ne!(first, second);
+    assert!(build_read_chain(&mut queue).is_none(), "Not enough descriptors for a third request");
+    assert_eq!(queue.num_free(), QUEUE_SIZE - 6, "Failed request allocates nothing");
+    queue.submit(first);
+    queue.submit(second);
+    device_complete(&mut queue, second);
+    device_complete(&mut queue, first);
+    for expected in [second, first] {
+        let used = queue.pop_used().expect("completion");
+        assert_eq!(used.id, expected as u32, "Completions in device order");
+        queue.free_desc_chain(used.id as u16);
+    }
+    assert_eq!(queue.num_free(), QUEUE_SIZE);
+    println!("test:    SUCCESS - both chains recycled");
+
+    // 测试 4: 耗尽与重置
+    println!("test: 4. Testing exhaustion and reset...");
+    for _ in 0..QUEUE_SIZE {
+        assert!(queue.alloc_desc().is_some());
+    }
+    assert_eq!(queue.alloc_desc(), None, "Empty free list");
+    queue.reset_desc_allocator();
+    assert_eq!(queue.num_free(), QUEUE_SIZE, "Reset frees every descriptor");
+    println!("test:    SUCCESS - exhaustion reported and reset recovers");
+
+    println!("test: ===== VirtQueue Descriptor Testing Completed =====");
+}
@@ -0,0 +1,45 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：VirtIO 块设备刷新 (VIRTIO_BLK_T_FLUSH)
+//
+// 测试内容：
+// 1. 特性协商只保留驱动支持的特性位
+// 2. 设备不提供 VIRTIO_BLK_F_FLUSH 时不协商该特性
+// 3. 对已初始化的设备执行 flush 成功，且不泄漏描述符
+
+use crate::println;
+use crate::drivers::virtio::{negotiate_features, VIRTIO_BLK_F_FLUSH};
+
+pub fn test_virtio_flush() {
+    println!("test: ===== Testing VirtIO flush =====");
+
+    // 测试 1: 协商结果
+    println!("test: 1. Testing feature negotiation mask...");
+    let offered = VIRTIO_BLK_F_FLUSH | (1 << 5) | (1 << 6);  // FLUSH + RO + BLK_SIZE
+    assert_eq!(negotiate_features(offered), VIRTIO_BLK_F_FLUSH, "Only supported features are accepted");
+    println!("test:    SUCCESS - unsupported features dropped");
+
+    // 测试 2: 设备不提供 FLUSH
+    println!("test: 2. Testing device without flush support...");
+    assert_eq!(negotiate_features(1 << 5), 0, "FLUSH is not negotiated unless offered");
+    assert_eq!(negotiate_features(0), 0);
+    println!("test:    SUCCESS - flush not negotiated");
+
+    // 测试 3: 真实设备
+    println!("test: 3. Testing flush on the block device...");
+    match crate::drivers::virtio::get_device() {
+        Some(dev) => {
+            println!("test:    flush negotiated: {}", dev.has_flush());
+            for _ in 0..16 {
+                assert_eq!(dev.flush(), Ok(()), "Flush completes successfully");
+            }
+            println!("test:    SUCCESS - repeated flushes completed");
+        }
+        None => println!("test:    No VirtIO block device - skipping"),
+    }
+
+    println!("test: ===== VirtIO Flush Testing Completed =====");
+}
@@ -0,0 +1,76 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：VirtIO 进行中请求表
+//
+// 测试内容：
+// 1. 登记、完成、取走结果的生命周期，取走后表项可以复用
+// 2. 未登记、越界和重复的完成被忽略
+// 3. 轮询等待时完成可以乱序到达，每个请求只等待自己的链头
+// 4. 放弃的请求忽略之后到达的完成
+
+use crate::println;
+use crate::drivers::virtio::inflight::InflightTable;
+
+pub fn test_virtio_inflight() {
+    println!("test: ===== Testing VirtIO in-flight table =====");
+
+    // 测试 1: 生命周期
+    println!("test: 1. Testing register/complete/finish...");
+    let table: InflightTable<8> = InflightTable::new();
+    assert!(table.register(0));
+    assert!(!table.register(0), "Head already in flight");
+    assert!(!table.is_complete(0));
+    assert_eq!(table.finish(0), None, "Unfinished request has no result");
+    assert!(table.complete(0, 513));
+    assert!(table.is_complete(0));
+    assert_eq!(table.finish(0), Some(513), "Result carries the used length");
+    assert_eq!(table.in_flight(), 0);
+    assert!(table.register(0), "Slot is reusable after finish");
+    assert!(table.complete(0, 1) && table.finish(0) == Some(1));
+    println!("test:    SUCCESS - request lifecycle works");
+
+    // 测试 2: 无效的完成
+    println!("test: 2. Testing spurious completions...");
+    assert!(!table.complete(3, 0), "Completion for an unregistered head is ignored");
+    assert!(!table.complete(8, 0), "Out-of-range id is ignored");
+    assert!(!table.register(8), "Out-of-range head cannot be registered");
+    assert!(table.register(3));
+    assert!(table.complete(3, 0));
+    assert!(!table.complete(3, 0), "Duplicate used element is ignored");
+    assert_eq!(table.finish(3), Some(0));
+    println!("test:    SUCCESS - spurious completions ignored");
+
+    // 测试 3: 乱序完成
+    println!("test: 3. Testing out-of-order completion while polling...");
+    assert!(table.register(0) && table.register(3));
+    let mut reaps = 0;
+    let done = table.wait(0, false, || {
+        // 模拟 used ring：先完成链头 3，两次后才完成链头 0
+        reaps += 1;
+        match reaps {
+            1 => assert!(table.complete(3, 1)),
+            3 => assert!(table.complete(0, 512)),
+            _ => {}
+        }
+    });
+    assert!(done);
+    assert_eq!(reaps, 3, "Waiter returns as soon as its own head completes");
+    assert_eq!(table.in_flight(), 2, "Both results wait to be taken");
+    assert!(table.wait(3, false, || {}), "Already completed head returns at once");
+    assert_eq!(table.finish(3), Some(1));
+    assert_eq!(table.finish(0), Some(512));
+    println!("test:    SUCCESS - completions matched by descriptor head");
+
+    // 测试 4: 放弃的请求
+    println!("test: 4. Testing cancelled request...");
+    assert!(table.register(5));
+    table.cancel(5);
+    assert!(!table.complete(5, 0), "Late completion after cancel is ignored");
+    assert_eq!(table.in_flight(), 0);
+    println!("test:    SUCCESS - cancelled request released");
+
+    println!("test: ===== VirtIO In-flight Table Testing Completed =====");
+}
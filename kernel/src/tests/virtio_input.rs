@@ -0,0 +1,112 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：virtio-input 事件翻译
+//
+// 测试内容：
+// 1. 按键按下/释放翻译为键盘事件
+// 2. 相对移动在 SYN_REPORT 时合并为一次鼠标移动
+// 3. 绝对坐标翻译为 MouseAbsolute
+// 4. 鼠标按键只在状态变化时上报
+
+use crate::println;
+use crate::drivers::input::virtio_input::{drain_ring, EventRing, VirtioInputDecoder, VirtioInputEvent};
+use crate::drivers::keyboard::ps2::KeyEvent;
+use crate::input::{
+    InputEvent, ABS_X, ABS_Y, BTN_LEFT, EV_ABS, EV_KEY, EV_REL, EV_SYN, REL_X, REL_Y, SYN_REPORT,
+};
+use alloc::collections::vec_deque::VecDeque;
+use alloc::vec::Vec;
+
+/// evdev KEY_A
+const KEY_A: u16 = 30;
+
+/// 模拟的事件环：按顺序返回预先放入的事件
+struct MockRing {
+    events: VecDeque<VirtioInputEvent>,
+}
+
+impl MockRing {
+    fn new(events: &[VirtioInputEvent]) -> Self {
+        Self { events: events.iter().copied().collect() }
+    }
+}
+
+impl EventRing for MockRing {
+    fn pop_event(&mut self) -> Option<VirtioInputEvent> {
+        self.events.pop_front()
+    }
+}
+
+/// 同步事件
+const SYN: VirtioInputEvent = VirtioInputEvent::new(EV_SYN, SYN_REPORT, 0);
+
+/// 取出事件环中的所有事件，返回翻译结果
+fn translate(decoder: &mut VirtioInputDecoder, events: &[VirtioInputEvent]) -> Vec<InputEvent> {
+    let mut ring = MockRing::new(events);
+    let mut out = Vec::new();
+    let count = drain_ring(&mut ring, decoder, |e| out.push(e));
+    assert_eq!(count, events.len(), "Every device event should be consumed");
+    out
+}
+
+pub fn test_virtio_input() {
+    println!("test: ===== Testing virtio-input Translation =====");
+
+    let mut decoder = VirtioInputDecoder::new();
+
+    // 测试 1: 按键
+    println!("test: 1. Testing key press/release...");
+    let out = translate(&mut decoder, &[
+        VirtioInputEvent::new(EV_KEY, KEY_A, 1),
+        SYN,
+        VirtioInputEvent::new(EV_KEY, KEY_A, 0),
+        SYN,
+    ]);
+    assert_eq!(out.len(), 2, "Press and release should each produce one event");
+    assert!(matches!(out[0], InputEvent::Keyboard(KeyEvent::Press(KEY_A))), "First event should be KEY_A press");
+    assert!(matches!(out[1], InputEvent::Keyboard(KeyEvent::Release(KEY_A))), "Second event should be KEY_A release");
+    println!("test:    SUCCESS - key events translated");
+
+    // 测试 2: 相对移动
+    println!("test: 2. Testing relative move...");
+    let out = translate(&mut decoder, &[
+        VirtioInputEvent::new(EV_REL, REL_X, 5),
+        VirtioInputEvent::new(EV_REL, REL_Y, (-3i32) as u32),
+        SYN,
+    ]);
+    assert_eq!(out.len(), 1, "X and Y should be merged at SYN_REPORT");
+    assert!(matches!(out[0], InputEvent::MouseMove { dx: 5, dy: -3 }), "Move should be (5, -3)");
+    let out = translate(&mut decoder, &[SYN]);
+    assert!(out.is_empty(), "An empty report should not produce a move");
+    println!("test:    SUCCESS - relative move translated");
+
+    // 测试 3: 绝对坐标
+    println!("test: 3. Testing absolute position...");
+    let out = translate(&mut decoder, &[
+        VirtioInputEvent::new(EV_ABS, ABS_X, 0x4000),
+        VirtioInputEvent::new(EV_ABS, ABS_Y, 0x2000),
+        SYN,
+    ]);
+    assert_eq!(out.len(), 1);
+    assert!(matches!(out[0], InputEvent::MouseAbsolute { x: 0x4000, y: 0x2000 }), "Absolute position should be reported");
+    println!("test:    SUCCESS - absolute position translated");
+
+    // 测试 4: 鼠标按键
+    println!("test: 4. Testing mouse buttons...");
+    let out = translate(&mut decoder, &[
+        VirtioInputEvent::new(EV_KEY, BTN_LEFT, 1),
+        VirtioInputEvent::new(EV_KEY, BTN_LEFT, 1),
+        SYN,
+        VirtioInputEvent::new(EV_KEY, BTN_LEFT, 0),
+        SYN,
+    ]);
+    assert_eq!(out.len(), 2, "Repeated press should not be reported twice");
+    assert!(matches!(out[0], InputEvent::MouseButton { left: true, right: false, middle: false }));
+    assert!(matches!(out[1], InputEvent::MouseButton { left: false, right: false, middle: false }));
+    println!("test:    SUCCESS - button state tracked");
+
+    println!("test: ===== virtio-input Translation Testing Completed =====");
+}
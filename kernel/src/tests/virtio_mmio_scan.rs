@@ -0,0 +1,115 @@
+//! MIT License
+//!
+//! Copyright (c) 2026 Fei Wang
+//!
+
+// 测试：virtio-mmio 窗口扫描
+//
+// 测试内容：
+// 1. 模拟窗口中只有槽位 2 的块设备被发现
+// 2. 只对块设备调用驱动初始化
+// 3. 魔数错误、版本不支持、无驱动的设备被跳过
+// 4. 初始化失败的设备不出现在结果中
+
+use crate::println;
+use crate::drivers::virtio::probe::{
+    probe_virtio_mmio, scan_virtio_mmio, MmioBus, VirtIODeviceId, VIRTIO_MMIO_MAGIC,
+};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+const WINDOW_BASE: u64 = 0x1000_1000;
+const WINDOW_STRIDE: u64 = 0x1000;
+const SLOTS: usize = 8;
+
+/// 模拟的 virtio-mmio 窗口：每个槽位 (magic, version, device_id)
+struct MockWindow {
+    slots: [(u32, u32, u32); SLOTS],
+    reads: RefCell<usize>,
+}
+
+impl MockWindow {
+    /// 所有槽位都是空设备（魔数正确，设备 ID 为 0，与 QEMU 一致）
+    fn empty() -> Self {
+        Self {
+            slots: [(VIRTIO_MMIO_MAGIC, 2, 0); SLOTS],
+            reads: RefCell::new(0),
+        }
+    }
+}
+
+impl MmioBus for MockWindow {
+    fn read_u32(&self, addr: u64) -> u32 {
+        *self.reads.borrow_mut() += 1;
+        let off = addr - WINDOW_BASE;
+        let slot = (off / WINDOW_STRIDE) as usize;
+        let (magic, version, device_id) = self.slots[slot];
+        match off % WINDOW_STRIDE {
+            0x000 => magic,
+            0x004 => version,
+            0x008 => device_id,
+            _ => 0,
+        }
+    }
+}
+
+pub fn test_virtio_mmio_scan() {
+    println!("test: ===== Testing virtio-mmio window scan =====");
+
+    let slots: Vec<(u64, u32)> = (0..SLOTS).map(|i| (WINDOW_BASE + i as u64 * WINDOW_STRIDE, 0)).collect();
+    let mut window = MockWindow::empty();
+    window.slots[2] = (VIRTIO_MMIO_MAGIC, 2, VirtIODeviceId::VirtioBlk as u32);
+
+    // 测试 1: 只发现槽位 2 的块设备
+    println!("test: 1. Testing scan finds only the blk device...");
+    let found = scan_virtio_mmio(&window, &slots);
+    assert_eq!(found.len(), 1, "Empty slots must be skipped");
+    assert_eq!(found[0].slot, 2);
+    assert_eq!(found[0].base, WINDOW_BASE + 2 * WINDOW_STRIDE);
+    assert_eq!(found[0].device_id, VirtIODeviceId::VirtioBlk);
+    assert_eq!(found[0].version, 2);
+    assert!(*window.reads.borrow() >= SLOTS * 3, "Every slot should be read");
+    println!("test:    SUCCESS - blk device found in slot 2");
+
+    // 测试 2: 只初始化块设备
+    println!("test: 2. Testing only the blk driver is initialized...");
+    let mut probed = Vec::new();
+    let devices = probe_virtio_mmio(&window, &slots, |dev| {
+        probed.push((dev.slot, dev.device_id));
+        Ok(())
+    });
+    assert_eq!(probed, [(2, VirtIODeviceId::VirtioBlk)], "Only blk should be probed");
+    assert_eq!(devices.len(), 1);
+    println!("test:    SUCCESS - exactly one driver init");
+
+    // 测试 3: 跳过无效槽位
+    println!("test: 3. Testing invalid slots are skipped...");
+    window.slots[0] = (0xdead_beef, 2, VirtIODeviceId::VirtioNet as u32);
+    window.slots[1] = (VIRTIO_MMIO_MAGIC, 3, VirtIODeviceId::VirtioNet as u32);
+    window.slots[3] = (VIRTIO_MMIO_MAGIC, 1, VirtIODeviceId::VirtioRng as u32);
+    window.slots[5] = (VIRTIO_MMIO_MAGIC, 1, VirtIODeviceId::VirtioNet as u32);
+    let found = scan_virtio_mmio(&window, &slots);
+    let kinds: Vec<(usize, VirtIODeviceId)> = found.iter().map(|d| (d.slot, d.device_id)).collect();
+    assert_eq!(
+        kinds,
+        [(2, VirtIODeviceId::VirtioBlk), (5, VirtIODeviceId::VirtioNet)],
+        "Bad magic, bad version and driverless devices must be skipped"
+    );
+    assert_eq!(found[1].version, 1, "Legacy transport is accepted");
+    println!("test:    SUCCESS - invalid slots skipped");
+
+    // 测试 4: 初始化失败
+    println!("test: 4. Testing failed init is not reported...");
+    let devices = probe_virtio_mmio(&window, &slots, |dev| {
+        if dev.device_id == VirtIODeviceId::VirtioNet {
+            Err("no net")
+        } else {
+            Ok(())
+        }
+    });
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].device_id, VirtIODeviceId::VirtioBlk);
+    println!("test:    SUCCESS - failed device dropped");
+
+    println!("test: ===== virtio-mmio Scan Testing Completed =====");
+}
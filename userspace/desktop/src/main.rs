@@ -2,20 +2,55 @@
 //!
 //! 用户态桌面环境应用
 
+use std::fs::{File, OpenOptions};
+use std::io::Read;
+use std::os::unix::fs::OpenOptionsExt;
+
 use rux_gui::{
     FramebufferDevice, FontRenderer, DoubleBuffer, MouseCursor,
-    WindowManager, SimplePanel, color,
+    WindowEvent, WindowManager, WindowState, SimplePanel, color,
 };
 
+/// 局部重绘时在窗口矩形外额外重绘的边距（覆盖阴影）
+const DAMAGE_MARGIN: u32 = 8;
+
+/// 屏幕底部任务栏高度
+const TASKBAR_HEIGHT: u32 = 30;
+
+/// 需要重绘的屏幕区域 (x, y, 宽, 高)
+type Damage = (u32, u32, u32, u32);
+
+/// 输入事件设备，每次读取返回若干个完整的 `struct RawInputEvent`
+const INPUT_DEVICE: &str = "/dev/input0";
+/// `struct RawInputEvent` 的大小：tv_sec、tv_usec、type、code、value
+const INPUT_EVENT_SIZE: usize = 24;
+/// O_NONBLOCK：没有事件时读取立即返回，不阻塞绘制循环
+const O_NONBLOCK: i32 = 0o4000;
+
+// 事件类型和代码（与内核 `input` 模块一致）
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const BTN_LEFT: u16 = 0x110;
+
 /// 桌面环境
 struct Desktop {
     fb: FramebufferDevice,
     double_buffer: DoubleBuffer,
     font: FontRenderer,
     cursor: MouseCursor,
+    /// 输入事件设备，打开失败时桌面不响应输入
+    input: Option<File>,
     wm: WindowManager,
     launcher_panel: SimplePanel,
     clock_panel: SimplePanel,
+    screen_width: u32,
+    screen_height: u32,
+    /// 需要整屏重绘（如分辨率改变后）
+    needs_full_redraw: bool,
+    /// 上一帧绘制光标时的位置
+    last_cursor: (i32, i32),
     running: bool,
 }
 
@@ -31,16 +66,30 @@ impl Desktop {
 
         // 初始化双缓冲
         let mut double_buffer = DoubleBuffer::new();
-        double_buffer.init(screen_width, screen_height, screen_width);
+        double_buffer
+            .init(screen_width, screen_height, screen_width)
+            .expect("Failed to initialize double buffer");
+        double_buffer.set_clear_color(color::BLUE);
 
         // 初始化字体
         let font = FontRenderer::new_8x8();
 
         // 初始化光标
         let cursor = MouseCursor::new(screen_width, screen_height);
+        let last_cursor = (cursor.x, cursor.y);
+
+        // 打开输入设备
+        let input = OpenOptions::new()
+            .read(true)
+            .custom_flags(O_NONBLOCK)
+            .open(INPUT_DEVICE)
+            .map_err(|e| eprintln!("desktop: cannot open {}: {}", INPUT_DEVICE, e))
+            .ok();
 
         // 初始化窗口管理器
         let mut wm = WindowManager::new();
+        wm.set_screen_size(screen_width, screen_height);
+        wm.set_taskbar_height(TASKBAR_HEIGHT);
         wm.create_window("Launcher", 10, 10, 200, 300);
         wm.create_window("Clock", 220, 10, 200, 100);
 
@@ -61,44 +110,184 @@ impl Desktop {
             double_buffer,
             font,
             cursor,
+            input,
             wm,
             launcher_panel,
             clock_panel,
+            screen_width,
+            screen_height,
+            needs_full_redraw: true,
+            last_cursor,
             running: true,
         }
     }
 
+    /// 显示模式改变后调整桌面
+    ///
+    /// 重新分配双缓冲，更新窗口管理器和光标的屏幕边界
+    /// （窗口被限制回屏幕内），下一帧按新尺寸重绘任务栏
+    fn on_resize(&mut self, width: u32, height: u32) {
+        if (width, height) == (self.screen_width, self.screen_height) {
+            return;
+        }
+        if let Err(e) = self.double_buffer.resize(width, height, width) {
+            eprintln!("desktop: resize to {}x{} failed: {}", width, height, e);
+            return;
+        }
+        self.screen_width = width;
+        self.screen_height = height;
+        self.wm.set_screen_size(width, height);
+        self.cursor.set_screen_size(width, height);
+        self.needs_full_redraw = true;
+    }
+
+    /// 读取输入设备中的所有事件并分发
+    ///
+    /// 相对移动移动光标，左键按下/释放交给窗口管理器（选中、拖动、标题栏按钮）
+    fn handle_events(&mut self) {
+        let Some(input) = self.input.as_mut() else {
+            return;
+        };
+
+        let mut events = Vec::new();
+        let mut buf = [0u8; INPUT_EVENT_SIZE * 16];
+        while let Ok(n) = input.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            for raw in buf[..n].chunks_exact(INPUT_EVENT_SIZE) {
+                let type_ = u16::from_ne_bytes([raw[16], raw[17]]);
+                let code = u16::from_ne_bytes([raw[18], raw[19]]);
+                let value = i32::from_ne_bytes([raw[20], raw[21], raw[22], raw[23]]);
+                events.push((type_, code, value));
+            }
+        }
+
+        for (type_, code, value) in events {
+            let (x, y) = (self.cursor.x.max(0) as u32, self.cursor.y.max(0) as u32);
+            match (type_, code) {
+                (EV_REL, REL_X) | (EV_REL, REL_Y) => {
+                    let delta = value.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+                    if code == REL_X {
+                        self.cursor.move_by(delta, 0);
+                    } else {
+                        self.cursor.move_by(0, delta);
+                    }
+                    self.wm.handle_mouse_move(self.cursor.x.max(0) as u32, self.cursor.y.max(0) as u32);
+                }
+                (EV_KEY, BTN_LEFT) if value != 0 => {
+                    self.wm.handle_mouse_down(x, y);
+                }
+                (EV_KEY, BTN_LEFT) => self.wm.handle_mouse_up(),
+                _ => {}
+            }
+        }
+    }
+
+    /// 取出窗口管理器排队的事件并响应
+    ///
+    /// 目前桌面只处理关闭请求：移除窗口并重绘整个场景
+    fn handle_window_events(&mut self) {
+        while let Some(event) = self.wm.poll_window_event() {
+            if let WindowEvent::CloseRequested(id) = event {
+                if self.wm.remove_window(id).is_ok() {
+                    self.needs_full_redraw = true;
+                }
+            }
+        }
+    }
+
     fn run(&mut self) {
         while self.running {
-            // 处理输入事件（需要系统调用支持）
-            // self.handle_events();
+            // 处理输入事件（按键翻译成字符后交给 `wm.handle_key`，
+            // Alt+Tab / Alt+Shift+Tab 在其中切换焦点窗口）
+            self.handle_events();
+
+            // 响应窗口管理器产生的事件（关闭请求等）
+            self.handle_window_events();
+
+            // 检测显示模式变化
+            let (width, height) = (self.fb.width(), self.fb.height());
+            self.on_resize(width, height);
+
+            // 最大化的窗口和任务栏覆盖整个屏幕时不需要清空背景
+            let covered = self.wm.windows().iter()
+                .any(|w| w.visible && w.state == WindowState::Maximized);
+            self.double_buffer.set_opaque_coverage(covered);
+
+            // 只重绘变化的区域，双缓冲只复制这些脏区域；没有变化时跳过
+            let damage = self.collect_damage();
+            let cursor_moved = (self.cursor.x, self.cursor.y) != self.last_cursor;
+            if self.needs_full_redraw || cursor_moved || !damage.is_empty() {
+                // 先擦除上一帧的光标，重绘的场景不会混入旧光标像素
+                self.cursor.restore_under(&self.double_buffer);
+
+                if self.needs_full_redraw {
+                    self.needs_full_redraw = false;
+                    self.draw();
+                } else {
+                    for &(x, y, w, h) in &damage {
+                        self.double_buffer.set_clip(x, y, w, h);
+                        self.draw();
+                    }
+                    self.double_buffer.clear_clip();
+                }
 
-            // 绘制
-            self.draw();
+                // 光标最后绘制在场景之上，只触碰它覆盖的小块区域
+                self.cursor.save_under(&self.double_buffer);
+                self.cursor.draw(&self.double_buffer);
 
-            // 刷新屏幕
-            self.double_buffer.swap_buffers(&self.fb);
+                // 刷新屏幕
+                self.double_buffer.swap_buffers(&self.fb);
+            }
+            self.last_cursor = (self.cursor.x, self.cursor.y);
 
             // 延迟
             std::thread::sleep(std::time::Duration::from_millis(16));
         }
     }
 
+    /// 收集自上一帧以来变化的区域：有控件变化的面板、需要重绘的窗口
+    ///
+    /// 光标移动不产生重绘区域，由 `MouseCursor::save_under` / `restore_under` 处理
+    fn collect_damage(&self) -> Vec<Damage> {
+        let mut damage = Vec::new();
+
+        for panel in [&self.launcher_panel, &self.clock_panel] {
+            if panel.needs_redraw() {
+                damage.push((panel.x, panel.y, panel.width, panel.height));
+            }
+        }
+
+        for window in self.wm.windows() {
+            if window.is_dirty() {
+                damage.push((
+                    window.x,
+                    window.y,
+                    window.width + DAMAGE_MARGIN,
+                    window.height + DAMAGE_MARGIN,
+                ));
+            }
+        }
+
+        damage
+    }
+
     fn draw(&self) {
         // 清空背景
-        self.double_buffer.clear(color::BLUE);
+        self.double_buffer.clear_background();
 
         // 绘制任务栏
-        let taskbar_height = 30u32;
-        let screen_width = self.fb.width();
-        let screen_height = self.fb.height();
+        let taskbar_height = TASKBAR_HEIGHT;
+        let screen_width = self.screen_width;
+        let screen_height = self.screen_height;
 
         self.double_buffer.fill_rect(
             0,
             screen_height - taskbar_height,
             screen_width,
             taskbar_height,
-            0xFF303030,
+            color::rgb(0x30, 0x30, 0x30),
         );
         self.font.draw_string(
             &self.double_buffer,
@@ -114,9 +303,6 @@ impl Desktop {
         // 绘制面板
         self.launcher_panel.draw(&self.double_buffer, &self.font);
         self.clock_panel.draw(&self.double_buffer, &self.font);
-
-        // 绘制光标
-        self.cursor.draw(&self.double_buffer);
     }
 }
 
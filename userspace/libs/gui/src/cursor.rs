@@ -1,5 +1,7 @@
 //! 鼠标光标
 
+use crate::framebuffer::{ClipRect, Framebuffer};
+
 /// 默认箭头光标 (16x16)
 const ARROW_CURSOR: [u16; 16] = [
     0b0000000000000001,
@@ -39,10 +41,73 @@ const ARROW_MASK: [u16; 16] = [
     0b0000000000000011,
 ];
 
+/// 箭头光标的热点（箭头尖端，位图第 0 行最右一列）
+pub const ARROW_HOTSPOT: (u32, u32) = (15, 0);
+
+/// 内置箭头光标的边长
+pub const ARROW_SIZE: u32 = 16;
+
 /// 光标颜色
 pub mod cursor_color {
-    pub const BLACK: u32 = 0xFF000000;
-    pub const WHITE: u32 = 0xFFFFFFFF;
+    pub const BLACK: u32 = crate::framebuffer::color::BLACK;
+    pub const WHITE: u32 = crate::framebuffer::color::WHITE;
+    /// 默认阴影颜色（半透明黑色）
+    pub const SHADOW: u32 = crate::framebuffer::color::with_alpha(BLACK, 0x60);
+    /// 自定义光标位图的透明色键，等于该值的像素不绘制
+    pub const KEY: u32 = crate::framebuffer::color::TRANSPARENT;
+}
+
+/// 自定义光标位图
+struct CursorBitmap {
+    width: u32,
+    height: u32,
+    /// 行优先的 ARGB 像素，`cursor_color::KEY` 为透明
+    pixels: Vec<u32>,
+}
+
+/// 光标下方被覆盖的像素
+struct SavedUnder {
+    rect: ClipRect,
+    /// 行优先保存的 `rect` 内像素
+    pixels: Vec<u32>,
+}
+
+/// 绝对定位设备的默认坐标上限（virtio-input 平板的 ABS_X/ABS_Y 范围）
+pub const DEFAULT_ABS_MAX: u32 = 0x7FFF;
+
+/// 指针输入模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerMode {
+    /// 相对模式：输入是位移（鼠标），按灵敏度和加速曲线累加
+    Relative,
+    /// 绝对模式：输入是设备坐标（触摸屏、平板、虚拟机绝对指针），线性映射到屏幕
+    Absolute,
+}
+
+/// 相对模式的加速曲线：单次位移 |dx| + |dy| 超过 `threshold` 时乘以 `factor`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerAccel {
+    /// 基础灵敏度
+    pub sensitivity: f32,
+    /// 加速阈值（设备单位）
+    pub threshold: u32,
+    /// 超过阈值后的额外倍数
+    pub factor: f32,
+}
+
+impl Default for PointerAccel {
+    fn default() -> Self {
+        Self { sensitivity: 1.0, threshold: 0, factor: 1.0 }
+    }
+}
+
+/// 光标阴影
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorShadow {
+    /// 向右下方的偏移（像素）
+    pub offset: u32,
+    /// ARGB 颜色，高 8 位为不透明度
+    pub color: u32,
 }
 
 /// 鼠标光标
@@ -52,6 +117,22 @@ pub struct MouseCursor {
     pub screen_width: u32,
     pub screen_height: u32,
     pub visible: bool,
+    /// 光标阴影，None 表示不绘制
+    shadow: Option<CursorShadow>,
+    /// 热点在光标位图中的位置；(x, y) 是热点的屏幕坐标
+    hotspot: (u32, u32),
+    /// 自定义光标位图，None 时绘制内置箭头
+    bitmap: Option<CursorBitmap>,
+    /// `save_under` 保存的光标下方像素，`restore_under` 时写回
+    saved: Option<SavedUnder>,
+    /// 指针输入模式
+    mode: PointerMode,
+    /// 相对模式的加速曲线
+    accel: PointerAccel,
+    /// 相对模式缩放后不足一像素的余量
+    remainder: (f32, f32),
+    /// 绝对模式的设备坐标上限
+    abs_max: (u32, u32),
 }
 
 impl MouseCursor {
@@ -62,46 +143,252 @@ impl MouseCursor {
             screen_width,
             screen_height,
             visible: true,
+            shadow: None,
+            hotspot: ARROW_HOTSPOT,
+            bitmap: None,
+            saved: None,
+            mode: PointerMode::Relative,
+            accel: PointerAccel::default(),
+            remainder: (0.0, 0.0),
+            abs_max: (DEFAULT_ABS_MAX, DEFAULT_ABS_MAX),
+        }
+    }
+
+    /// 更新限制区域（如分辨率改变后），超出新边界的光标被拉回
+    pub fn set_bounds(&mut self, width: u32, height: u32) {
+        self.screen_width = width;
+        self.screen_height = height;
+        self.set_position(self.x, self.y);
+    }
+
+    /// 屏幕大小改变后更新边界，同 `set_bounds`
+    pub fn set_screen_size(&mut self, screen_width: u32, screen_height: u32) {
+        self.set_bounds(screen_width, screen_height);
+    }
+
+    /// 设置热点在光标位图中的位置（限制在当前位图内）
+    pub fn set_hotspot(&mut self, hx: u32, hy: u32) {
+        let (width, height) = self.size();
+        self.hotspot = (hx.min(width - 1), hy.min(height - 1));
+    }
+
+    /// 安装自定义光标位图（如文本框的 I 形光标、按钮上的手形光标）
+    ///
+    /// `pixels` 行优先排列，等于 `cursor_color::KEY` 的像素透明，不足的部分视为透明；
+    /// 热点是点击生效的位置，限制在位图内。宽或高为 0 时恢复内置箭头
+    pub fn set_bitmap(&mut self, width: u32, height: u32, pixels: &[u32], hotspot_x: u32, hotspot_y: u32) {
+        if width == 0 || height == 0 {
+            self.reset_bitmap();
+            return;
+        }
+        let mut pixels = pixels.to_vec();
+        pixels.resize((width * height) as usize, cursor_color::KEY);
+        self.bitmap = Some(CursorBitmap { width, height, pixels });
+        self.set_hotspot(hotspot_x, hotspot_y);
+    }
+
+    /// 恢复内置箭头光标和它的热点
+    pub fn reset_bitmap(&mut self) {
+        self.bitmap = None;
+        self.hotspot = ARROW_HOTSPOT;
+    }
+
+    /// 当前光标位图的尺寸
+    pub fn size(&self) -> (u32, u32) {
+        self.bitmap.as_ref().map_or((ARROW_SIZE, ARROW_SIZE), |b| (b.width, b.height))
+    }
+
+    /// 位图内 (px, py) 的颜色，透明时返回 None
+    fn pixel(&self, px: u32, py: u32) -> Option<u32> {
+        match &self.bitmap {
+            Some(bitmap) => {
+                let color = bitmap.pixels[(py * bitmap.width + px) as usize];
+                (color != cursor_color::KEY).then_some(color)
+            }
+            None => {
+                let shift = ARROW_SIZE - 1 - px;
+                if (ARROW_MASK[py as usize] >> shift) & 1 == 0 {
+                    return None;
+                }
+                let cursor_bit = (ARROW_CURSOR[py as usize] >> shift) & 1;
+                Some(if cursor_bit != 0 { cursor_color::BLACK } else { cursor_color::WHITE })
+            }
         }
     }
 
+    /// 热点在光标位图中的位置
+    #[inline]
+    pub fn hotspot(&self) -> (u32, u32) {
+        self.hotspot
+    }
+
+    /// 相对移动，热点限制在当前边界内
     pub fn move_by(&mut self, dx: i16, dy: i16) {
-        self.x = (self.x + dx as i32).clamp(0, (self.screen_width - 1) as i32);
-        self.y = (self.y + dy as i32).clamp(0, (self.screen_height - 1) as i32);
+        self.set_position(self.x + dx as i32, self.y + dy as i32);
     }
 
+    /// 设置热点位置，限制在当前边界内，使光标尖端始终可见
     pub fn set_position(&mut self, x: i32, y: i32) {
-        self.x = x.clamp(0, (self.screen_width - 1) as i32);
-        self.y = y.clamp(0, (self.screen_height - 1) as i32);
+        self.x = x.clamp(0, self.screen_width.saturating_sub(1) as i32);
+        self.y = y.clamp(0, self.screen_height.saturating_sub(1) as i32);
     }
 
-    pub fn draw<F: crate::framebuffer::Framebuffer>(&self, fb: &F) {
+    /// 选择指针输入模式
+    pub fn set_mode(&mut self, mode: PointerMode) {
+        self.mode = mode;
+        self.remainder = (0.0, 0.0);
+    }
+
+    /// 当前指针输入模式
+    #[inline]
+    pub fn mode(&self) -> PointerMode {
+        self.mode
+    }
+
+    /// 设置相对模式的灵敏度和加速曲线
+    pub fn set_acceleration(&mut self, accel: PointerAccel) {
+        self.accel = accel;
+        self.remainder = (0.0, 0.0);
+    }
+
+    /// 相对模式的加速曲线
+    #[inline]
+    pub fn acceleration(&self) -> PointerAccel {
+        self.accel
+    }
+
+    /// 设置绝对模式的设备坐标上限（设备坐标 0..=max 映射到整个屏幕）
+    pub fn set_absolute_range(&mut self, max_x: u32, max_y: u32) {
+        self.abs_max = (max_x.max(1), max_y.max(1));
+    }
+
+    /// 处理一次指针输入
+    ///
+    /// 相对模式下 (a, b) 是位移，经灵敏度和加速后累加（保留不足一像素的余量）；
+    /// 绝对模式下 (a, b) 是设备坐标，线性缩放到屏幕
+    pub fn handle_pointer(&mut self, a: i32, b: i32) {
+        match self.mode {
+            PointerMode::Relative => {
+                let mut scale = self.accel.sensitivity;
+                if a.unsigned_abs() + b.unsigned_abs() > self.accel.threshold {
+                    scale *= self.accel.factor;
+                }
+                let fx = a as f32 * scale + self.remainder.0;
+                let fy = b as f32 * scale + self.remainder.1;
+                let (dx, dy) = (fx.trunc(), fy.trunc());
+                self.remainder = (fx - dx, fy - dy);
+                self.set_position(self.x + dx as i32, self.y + dy as i32);
+            }
+            PointerMode::Absolute => {
+                let map = |v: i32, max: u32, size: u32| {
+                    let v = v.clamp(0, max as i32) as u64;
+                    (v * size.saturating_sub(1) as u64 / max as u64) as i32
+                };
+                let x = map(a, self.abs_max.0, self.screen_width);
+                let y = map(b, self.abs_max.1, self.screen_height);
+                self.set_position(x, y);
+            }
+        }
+    }
+
+    /// 光标位图左上角的屏幕坐标（可能为负）
+    fn origin(&self) -> (i32, i32) {
+        (self.x - self.hotspot.0 as i32, self.y - self.hotspot.1 as i32)
+    }
+
+    /// 位图内 (px, py) 对应的屏幕坐标，超出屏幕时返回 None
+    fn screen_pos(&self, (ox, oy): (i32, i32), px: u32, py: u32) -> Option<(u32, u32)> {
+        let sx = u32::try_from(ox + px as i32).ok()?;
+        let sy = u32::try_from(oy + py as i32).ok()?;
+        (sx < self.screen_width && sy < self.screen_height).then_some((sx, sy))
+    }
+
+    /// 光标（含阴影）在 `fb` 上覆盖的区域，完全在屏幕外时返回 None
+    pub fn bounds<F: Framebuffer>(&self, fb: &F) -> Option<ClipRect> {
+        let (width, height) = self.size();
+        let extra = self.shadow.map_or(0, |s| s.offset);
+        let (ox, oy) = self.origin();
+        let x0 = ox.max(0);
+        let y0 = oy.max(0);
+        let x1 = (ox + (width + extra) as i32).min(fb.width() as i32);
+        let y1 = (oy + (height + extra) as i32).min(fb.height() as i32);
+        (x0 < x1 && y0 < y1).then(|| ClipRect::new(x0 as u32, y0 as u32, (x1 - x0) as u32, (y1 - y0) as u32))
+    }
+
+    /// 在绘制光标前保存它将覆盖的像素
+    ///
+    /// 配合 `restore_under` 使用：移动光标时先恢复旧位置、再保存新位置并绘制，
+    /// 只需要触碰光标所在的小块区域而不必整屏重绘。
+    /// 已保存的内容会被丢弃，调用者应先 `restore_under`
+    pub fn save_under<F: Framebuffer>(&mut self, fb: &F) {
+        self.saved = self.bounds(fb).map(|rect| {
+            let mut pixels = Vec::with_capacity((rect.width * rect.height) as usize);
+            for y in rect.y..rect.y + rect.height {
+                for x in rect.x..rect.x + rect.width {
+                    pixels.push(fb.get_pixel(x, y));
+                }
+            }
+            SavedUnder { rect, pixels }
+        });
+    }
+
+    /// 写回 `save_under` 保存的像素，擦除上次绘制的光标
+    ///
+    /// 返回被恢复的区域（用于脏区域记录），没有保存内容时返回 None
+    pub fn restore_under<F: Framebuffer>(&mut self, fb: &F) -> Option<ClipRect> {
+        let saved = self.saved.take()?;
+        let rect = saved.rect;
+        fb.blit_bitmap(rect.x, rect.y, rect.width, rect.height, &saved.pixels);
+        Some(rect)
+    }
+
+    /// 启用或禁用光标阴影
+    ///
+    /// 阴影是光标形状向右下偏移 `offset` 像素、按 `color` 的 alpha 混合的副本
+    pub fn set_shadow(&mut self, enabled: bool, offset: u32, color: u32) {
+        self.shadow = enabled.then_some(CursorShadow { offset, color });
+    }
+
+    /// 当前阴影设置
+    pub fn shadow(&self) -> Option<CursorShadow> {
+        self.shadow
+    }
+
+    pub fn draw<F: Framebuffer>(&self, fb: &F) {
         if !self.visible {
             return;
         }
 
-        let cursor_x = self.x as u32;
-        let cursor_y = self.y as u32;
+        let origin = self.origin();
 
-        for py in 0..16u32 {
-            for px in 0..16u32 {
-                let screen_x = cursor_x + px;
-                let screen_y = cursor_y + py;
+        if let Some(shadow) = self.shadow {
+            let offset = shadow.offset as i32;
+            self.draw_shadow(fb, (origin.0 + offset, origin.1 + offset), shadow.color);
+        }
 
-                if screen_x >= self.screen_width || screen_y >= self.screen_height {
+        let (width, height) = self.size();
+        for py in 0..height {
+            for px in 0..width {
+                let Some((screen_x, screen_y)) = self.screen_pos(origin, px, py) else {
                     continue;
+                };
+                if let Some(color) = self.pixel(px, py) {
+                    fb.put_pixel(screen_x, screen_y, color);
                 }
+            }
+        }
+    }
 
-                let mask_bit = (ARROW_MASK[py as usize] >> (15 - px)) & 1;
-                let cursor_bit = (ARROW_CURSOR[py as usize] >> (15 - px)) & 1;
-
-                if mask_bit != 0 {
-                    let color = if cursor_bit != 0 {
-                        cursor_color::BLACK
-                    } else {
-                        cursor_color::WHITE
-                    };
-                    fb.put_pixel(screen_x, screen_y, color);
+    /// 以 `origin` 为左上角按光标形状混合绘制阴影，超出屏幕的部分被裁剪
+    fn draw_shadow<F: Framebuffer>(&self, fb: &F, origin: (i32, i32), color: u32) {
+        let (width, height) = self.size();
+        for py in 0..height {
+            for px in 0..width {
+                let Some((screen_x, screen_y)) = self.screen_pos(origin, px, py) else {
+                    continue;
+                };
+                if self.pixel(px, py).is_some() {
+                    fb.blend_pixel(screen_x, screen_y, color);
                 }
             }
         }
@@ -2,14 +2,54 @@
 //!
 //! 提供无闪烁的图形渲染
 
-use std::vec;
+use core::cell::{Cell, RefCell};
 use std::vec::Vec;
-use crate::framebuffer::Framebuffer;
+use crate::framebuffer::{clip_bounds, scroll_rows, ClipRect, Framebuffer, color};
+
+/// 双缓冲初始化错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoubleBufferError {
+    /// 宽度或高度为 0
+    ZeroSize,
+    /// 每行像素数小于宽度
+    StrideTooSmall,
+    /// 缓冲区过大或内存分配失败
+    AllocFailed,
+    /// 已经初始化（使用 `resize` 改变大小）
+    AlreadyInitialized,
+}
+
+impl core::fmt::Display for DoubleBufferError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DoubleBufferError::ZeroSize => write!(f, "zero width or height"),
+            DoubleBufferError::StrideTooSmall => write!(f, "stride smaller than width"),
+            DoubleBufferError::AllocFailed => write!(f, "back buffer allocation failed"),
+            DoubleBufferError::AlreadyInitialized => write!(f, "already initialized"),
+        }
+    }
+}
+
+/// 脏矩形数量上限，超过后合并为一个包围矩形
+const MAX_DIRTY_RECTS: usize = 16;
 
 /// 双缓冲管理器
+///
+/// 绘制操作记录脏矩形，`swap_buffers` 只把脏区域复制到前端 framebuffer。
+///
+/// 默认只有一个后端缓冲区；`new_triple` 创建三个后端缓冲区组成的环，
+/// 每次刷新后切换到下一个，CPU 可以在上一帧仍在刷新时开始绘制下一帧。
+/// 每个后端缓冲区占 `stride * height * 4` 字节（1024x768 约 3 MiB），
+/// 三缓冲比默认多用两份
 pub struct DoubleBuffer {
-    /// 后端缓冲区
-    back_buffer: Vec<u32>,
+    /// 后端缓冲区环（默认 1 个，三缓冲为 3 个）
+    buffers: Vec<Vec<u32>>,
+    /// 后端缓冲区数量
+    buffer_count: usize,
+    /// 当前绘制的后端缓冲区
+    active: Cell<usize>,
+    /// 每个后端缓冲区落后于当前帧的区域，切换到它时从上一个缓冲区补齐
+    stale: RefCell<Vec<Vec<ClipRect>>>,
     /// 屏幕宽度
     width: u32,
     /// 屏幕高度
@@ -18,34 +58,143 @@ pub struct DoubleBuffer {
     stride: u32,
     /// 是否已初始化
     initialized: bool,
+    /// 上次刷新到屏幕时后端缓冲区的哈希，None 表示必须刷新
+    last_flush_hash: Cell<Option<u64>>,
+    /// `clear_background` 使用的背景色
+    clear_color: u32,
+    /// 缓冲区每帧都会被不透明内容完全覆盖（壁纸、最大化窗口），此时跳过清空
+    opaque_coverage: bool,
+    /// 裁剪矩形
+    clip: Cell<Option<ClipRect>>,
+    /// 自上次刷新以来改变的区域（互不相邻）
+    dirty: RefCell<Vec<ClipRect>>,
+    /// 单个像素写入的包围盒 (x0, y0, x1, y1)，刷新时并入脏矩形
+    dirty_pixels: Cell<Option<(u32, u32, u32, u32)>>,
+    /// 显式标记过脏区域，即使内容未变化也要刷新
+    force_flush: Cell<bool>,
+}
+
+/// 计算像素数据的 FNV-1a 哈希
+fn frame_hash(pixels: &[u32]) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    pixels.iter().fold(FNV_OFFSET, |hash, &p| (hash ^ p as u64).wrapping_mul(FNV_PRIME))
 }
 
 impl DoubleBuffer {
     /// 创建新的双缓冲系统
     pub fn new() -> Self {
         Self {
-            back_buffer: Vec::new(),
+            buffers: Vec::new(),
+            buffer_count: 1,
+            active: Cell::new(0),
+            stale: RefCell::new(Vec::new()),
             width: 0,
             height: 0,
             stride: 0,
             initialized: false,
+            last_flush_hash: Cell::new(None),
+            clear_color: color::BLACK,
+            opaque_coverage: false,
+            clip: Cell::new(None),
+            dirty: RefCell::new(Vec::new()),
+            dirty_pixels: Cell::new(None),
+            force_flush: Cell::new(false),
         }
     }
 
+    /// 创建三缓冲系统（三个后端缓冲区轮流使用，内存占用为默认的三倍）
+    pub fn new_triple() -> Self {
+        Self {
+            buffer_count: 3,
+            ..Self::new()
+        }
+    }
+
+    /// 后端缓冲区数量
+    #[inline]
+    pub fn buffer_count(&self) -> usize {
+        self.buffer_count
+    }
+
+    /// 当前绘制的后端缓冲区（未初始化时为空）
+    #[inline]
+    pub fn back_buffer(&self) -> &[u32] {
+        self.buffers.get(self.active.get()).map_or(&[], Vec::as_slice)
+    }
+
+    /// 设置默认背景色
+    pub fn set_clear_color(&mut self, color: u32) {
+        self.clear_color = color;
+    }
+
+    /// 默认背景色
+    #[inline]
+    pub fn clear_color(&self) -> u32 {
+        self.clear_color
+    }
+
+    /// 标记缓冲区是否被不透明内容完全覆盖
+    ///
+    /// 设置后 `clear` 和 `clear_background` 成为空操作
+    pub fn set_opaque_coverage(&mut self, covered: bool) {
+        self.opaque_coverage = covered;
+    }
+
+    /// 是否被不透明内容完全覆盖
+    #[inline]
+    pub fn has_opaque_coverage(&self) -> bool {
+        self.opaque_coverage
+    }
+
     /// 初始化双缓冲
-    pub fn init(&mut self, width: u32, height: u32, stride: u32) {
+    ///
+    /// 宽高必须非零且 `stride >= width`；分配失败时返回错误，
+    /// 缓冲区保持未初始化状态（所有绘制操作为空操作）
+    pub fn init(&mut self, width: u32, height: u32, stride: u32) -> Result<(), DoubleBufferError> {
         if self.initialized {
-            return;
+            return Err(DoubleBufferError::AlreadyInitialized);
+        }
+        if width == 0 || height == 0 {
+            return Err(DoubleBufferError::ZeroSize);
+        }
+        if stride < width {
+            return Err(DoubleBufferError::StrideTooSmall);
+        }
+
+        let buffer_size = (stride as usize)
+            .checked_mul(height as usize)
+            .ok_or(DoubleBufferError::AllocFailed)?;
+        let mut buffers = Vec::with_capacity(self.buffer_count);
+        for _ in 0..self.buffer_count {
+            let mut back_buffer = Vec::new();
+            back_buffer
+                .try_reserve_exact(buffer_size)
+                .map_err(|_| DoubleBufferError::AllocFailed)?;
+            back_buffer.resize(buffer_size, 0u32);
+            buffers.push(back_buffer);
         }
 
+        self.buffers = buffers;
+        self.active.set(0);
+        *self.stale.borrow_mut() = vec![Vec::new(); self.buffer_count];
         self.width = width;
         self.height = height;
         self.stride = stride;
-
-        let buffer_size = (stride * height) as usize;
-        self.back_buffer = vec![0u32; buffer_size];
-
         self.initialized = true;
+        self.last_flush_hash.set(None);
+        self.take_dirty();
+        Ok(())
+    }
+
+    /// 按新的屏幕大小重新分配后端缓冲区（内容清零）
+    ///
+    /// 用于显示模式改变后；未初始化时等同于 `init`。
+    /// 失败时缓冲区变为未初始化
+    pub fn resize(&mut self, width: u32, height: u32, stride: u32) -> Result<(), DoubleBufferError> {
+        self.initialized = false;
+        self.buffers = Vec::new();
+        self.init(width, height, stride)
     }
 
     /// 检查是否已初始化
@@ -71,11 +220,26 @@ impl DoubleBuffer {
         if !self.initialized || x >= self.width || y >= self.height {
             return;
         }
+        if self.clip.get().is_some_and(|c| !c.contains(x, y)) {
+            return;
+        }
+
+        self.write_pixel(x, y, color);
+        let bounds = match self.dirty_pixels.get() {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+            None => (x, y, x + 1, y + 1),
+        };
+        self.dirty_pixels.set(Some(bounds));
+    }
 
+    /// 写入后端缓冲区（调用者负责边界检查和脏区域记录）
+    #[inline]
+    fn write_pixel(&self, x: u32, y: u32, color: u32) {
         let offset = (y * self.stride + x) as usize;
-        if offset < self.back_buffer.len() {
+        let back = self.back_buffer();
+        if offset < back.len() {
             unsafe {
-                let ptr = self.back_buffer.as_ptr() as *mut u32;
+                let ptr = back.as_ptr() as *mut u32;
                 core::ptr::write_volatile(ptr.add(offset), color);
             }
         }
@@ -89,45 +253,164 @@ impl DoubleBuffer {
         }
 
         let offset = (y * self.stride + x) as usize;
-        if offset < self.back_buffer.len() {
-            self.back_buffer[offset]
+        let back = self.back_buffer();
+        if offset < back.len() {
+            back[offset]
         } else {
             0
         }
     }
 
-    /// 填充矩形
+    /// 设置裁剪矩形
+    pub fn set_clip(&self, x: u32, y: u32, width: u32, height: u32) {
+        self.clip.set(Some(ClipRect::new(x, y, width, height)));
+    }
+
+    /// 取消裁剪
+    pub fn clear_clip(&self) {
+        self.clip.set(None);
+    }
+
+    /// 填充矩形（与裁剪矩形求交）
     pub fn fill_rect(&self, x: u32, y: u32, width: u32, height: u32, color: u32) {
         if !self.initialized {
             return;
         }
 
-        let x_end = (x + width).min(self.width);
-        let y_end = (y + height).min(self.height);
+        let (x0, y0, x1, y1) = clip_bounds(self.clip.get(), (x, y, width, height), (self.width, self.height));
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        for py in y0..y1 {
+            for px in x0..x1 {
+                self.write_pixel(px, py, color);
+            }
+        }
+        self.add_dirty(ClipRect::new(x0, y0, x1 - x0, y1 - y0));
+    }
+
+    /// 标记区域需要在下一次 `swap_buffers` 时复制到前端（即使内容未变化）
+    pub fn mark_dirty(&self, x: u32, y: u32, width: u32, height: u32) {
+        if !self.initialized {
+            return;
+        }
+        let (x0, y0, x1, y1) = clip_bounds(None, (x, y, width, height), (self.width, self.height));
+        if x0 < x1 && y0 < y1 {
+            self.add_dirty(ClipRect::new(x0, y0, x1 - x0, y1 - y0));
+            self.force_flush.set(true);
+        }
+    }
+
+    /// 标记整个屏幕需要复制到前端
+    pub fn mark_all_dirty(&self) {
+        self.mark_dirty(0, 0, self.width, self.height);
+    }
+
+    /// 当前记录的脏矩形（包含单个像素写入的包围盒）
+    pub fn dirty_rects(&self) -> Vec<ClipRect> {
+        self.flush_pixel_bounds();
+        self.dirty.borrow().clone()
+    }
+
+    /// 记录脏矩形：与已有矩形重叠或相邻时合并，数量超过上限时合并为包围矩形
+    fn add_dirty(&self, rect: ClipRect) {
+        let mut dirty = self.dirty.borrow_mut();
+        let mut rect = rect;
+        while let Some(i) = dirty.iter().position(|d| d.touches(&rect)) {
+            rect = rect.union(&dirty.swap_remove(i));
+        }
+        dirty.push(rect);
+        if dirty.len() > MAX_DIRTY_RECTS {
+            let bounds = dirty.iter().skip(1).fold(dirty[0], |acc, d| acc.union(d));
+            dirty.clear();
+            dirty.push(bounds);
+        }
+    }
+
+    /// 把单个像素写入的包围盒并入脏矩形
+    fn flush_pixel_bounds(&self) {
+        if let Some((x0, y0, x1, y1)) = self.dirty_pixels.take() {
+            self.add_dirty(ClipRect::new(x0, y0, x1 - x0, y1 - y0));
+        }
+    }
+
+    /// 取出并清空脏矩形
+    fn take_dirty(&self) -> Vec<ClipRect> {
+        self.flush_pixel_bounds();
+        self.force_flush.set(false);
+        core::mem::take(&mut *self.dirty.borrow_mut())
+    }
+
+    /// 区域内像素上移 `lines` 行，底部露出的行填充 `fill_color`
+    pub fn scroll_rect_up(&self, x: u32, y: u32, width: u32, height: u32, lines: u32, fill_color: u32) {
+        self.scroll_rect((x, y, width, height), lines, fill_color, true);
+    }
+
+    /// 区域内像素下移 `lines` 行，顶部露出的行填充 `fill_color`
+    pub fn scroll_rect_down(&self, x: u32, y: u32, width: u32, height: u32, lines: u32, fill_color: u32) {
+        self.scroll_rect((x, y, width, height), lines, fill_color, false);
+    }
+
+    /// 区域滚动：无裁剪时直接按行 memmove 后端缓冲区，否则逐像素复制
+    fn scroll_rect(&self, rect: (u32, u32, u32, u32), lines: u32, fill_color: u32, up: bool) {
+        if !self.initialized {
+            return;
+        }
+        if self.clip.get().is_some() {
+            scroll_rows(self, rect, lines, fill_color, up);
+            return;
+        }
 
-        for py in y..y_end {
-            for px in x..x_end {
-                self.put_pixel(px, py, color);
+        let (x0, y0, x1, y1) = clip_bounds(None, rect, (self.width, self.height));
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+        let lines = lines.min(y1 - y0);
+        let len = (x1 - x0) as usize;
+        let base = self.back_buffer().as_ptr() as *mut u32;
+        let row_ptr = |row: u32| unsafe { base.add((row * self.stride + x0) as usize) };
+        // SAFETY: 行号 < height、列 < width <= stride，均在后端缓冲区内；ptr::copy 允许重叠
+        unsafe {
+            if up {
+                for row in y0..y1 - lines {
+                    core::ptr::copy(row_ptr(row + lines), row_ptr(row), len);
+                }
+            } else {
+                for row in (y0 + lines..y1).rev() {
+                    core::ptr::copy(row_ptr(row - lines), row_ptr(row), len);
+                }
             }
         }
+        self.add_dirty(ClipRect::new(x0, y0, x1 - x0, y1 - y0));
+        let fill_y = if up { y1 - lines } else { y0 };
+        self.fill_rect(x0, fill_y, x1 - x0, lines, fill_color);
     }
 
     /// 绘制矩形边框
     pub fn blit_rect(&self, x: u32, y: u32, width: u32, height: u32, color: u32, thickness: u32) {
+        if !self.initialized {
+            return;
+        }
         self.fill_rect(x, y, width, thickness, color);
-        self.fill_rect(x, y + height - thickness, width, thickness, color);
+        self.fill_rect(x, (y + height).saturating_sub(thickness), width, thickness, color);
         self.fill_rect(x, y, thickness, height, color);
-        self.fill_rect(x + width - thickness, y, thickness, height, color);
+        self.fill_rect((x + width).saturating_sub(thickness), y, thickness, height, color);
     }
 
-    /// 清空
+    /// 清空（完全覆盖模式下跳过）
     pub fn clear(&self, color: u32) {
-        if !self.initialized {
+        if !self.initialized || self.opaque_coverage {
             return;
         }
         self.fill_rect(0, 0, self.width, self.height, color);
     }
 
+    /// 用默认背景色清空
+    pub fn clear_background(&self) {
+        self.clear(self.clear_color);
+    }
+
     /// 绘制水平线
     pub fn draw_line_h(&self, x: u32, y: u32, width: u32, color: u32) {
         self.fill_rect(x, y, width, 1, color);
@@ -172,17 +455,82 @@ impl DoubleBuffer {
     }
 
     /// 复制到前端 framebuffer
-    pub fn swap_buffers<F: Framebuffer>(&self, fb: &F) {
+    ///
+    /// 只复制自上次刷新以来的脏矩形，然后清空脏矩形列表；
+    /// 后端缓冲区与上次刷新时完全相同且没有显式标记脏区域时跳过复制。
+    /// 第一次刷新或 `invalidate` 之后复制整个屏幕
+    ///
+    /// # 返回
+    /// 是否实际写入了前端 framebuffer
+    pub fn swap_buffers<F: Framebuffer>(&self, fb: &F) -> bool {
         if !self.initialized {
+            return false;
+        }
+
+        let forced = self.force_flush.get();
+        let dirty = self.take_dirty();
+        let hash = frame_hash(self.back_buffer());
+        let last = self.last_flush_hash.replace(Some(hash));
+        if last == Some(hash) && !forced {
+            return false;
+        }
+
+        let flushed = if last.is_none() || dirty.is_empty() {
+            vec![ClipRect::new(0, 0, self.width, self.height)]
+        } else {
+            dirty
+        };
+        for &rect in &flushed {
+            self.copy_rect(fb, rect);
+        }
+        self.rotate(&flushed);
+        true
+    }
+
+    /// 三缓冲：切换到环中的下一个后端缓冲区
+    ///
+    /// 其他缓冲区记下本帧刷新的区域；新的当前缓冲区从上一个缓冲区
+    /// 补齐它落后的区域，保证增量绘制看到的是最新的一帧
+    fn rotate(&self, flushed: &[ClipRect]) {
+        if self.buffer_count < 2 {
             return;
         }
+        let prev = self.active.get();
+        let next = (prev + 1) % self.buffer_count;
 
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let color = self.get_pixel(x, y);
-                fb.put_pixel(x, y, color);
+        let mut stale = self.stale.borrow_mut();
+        for (i, rects) in stale.iter_mut().enumerate() {
+            if i != prev {
+                rects.extend_from_slice(flushed);
             }
         }
+
+        let src = self.buffers[prev].as_ptr();
+        let dst = self.buffers[next].as_ptr() as *mut u32;
+        for rect in stale[next].drain(..) {
+            for row in rect.y..rect.y + rect.height {
+                let offset = (row * self.stride + rect.x) as usize;
+                // SAFETY: 脏矩形已裁剪到屏幕内，两个缓冲区大小相同且互不重叠
+                unsafe {
+                    core::ptr::copy_nonoverlapping(src.add(offset), dst.add(offset), rect.width as usize);
+                }
+            }
+        }
+        self.active.set(next);
+    }
+
+    /// 把后端缓冲区的一个矩形复制到前端
+    fn copy_rect<F: Framebuffer>(&self, fb: &F, rect: ClipRect) {
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                fb.put_pixel(x, y, self.get_pixel(x, y));
+            }
+        }
+    }
+
+    /// 使下一次 `swap_buffers` 无条件刷新整个屏幕（如前端内容被其他程序覆盖后）
+    pub fn invalidate(&self) {
+        self.last_flush_hash.set(None);
     }
 }
 
@@ -197,6 +545,22 @@ impl Framebuffer for DoubleBuffer {
         self.put_pixel(x, y, color);
     }
 
+    fn get_pixel(&self, x: u32, y: u32) -> u32 {
+        self.get_pixel(x, y)
+    }
+
+    fn clip(&self) -> Option<ClipRect> {
+        self.clip.get()
+    }
+
+    fn set_clip(&self, x: u32, y: u32, width: u32, height: u32) {
+        self.set_clip(x, y, width, height);
+    }
+
+    fn clear_clip(&self) {
+        self.clear_clip();
+    }
+
     fn width(&self) -> u32 {
         self.width
     }
@@ -204,4 +568,16 @@ impl Framebuffer for DoubleBuffer {
     fn height(&self) -> u32 {
         self.height
     }
+
+    fn clear(&self, color: u32) {
+        self.clear(color);
+    }
+
+    fn scroll_rect_up(&self, x: u32, y: u32, width: u32, height: u32, lines: u32, fill_color: u32) {
+        self.scroll_rect_up(x, y, width, height, lines, fill_color);
+    }
+
+    fn scroll_rect_down(&self, x: u32, y: u32, width: u32, height: u32, lines: u32, fill_color: u32) {
+        self.scroll_rect_down(x, y, width, height, lines, fill_color);
+    }
 }
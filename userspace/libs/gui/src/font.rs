@@ -1,7 +1,9 @@
 //! 8x8 位图字体渲染
 //!
-//! 提供基础的 ASCII 字符渲染功能 (0x20-0x7F)
+//! 提供基础的 ASCII 字符渲染功能 (0x20-0x7F)，支持位图字体、PSF 字体文件、后备字体链和合成粗体/斜体/下划线
 
+use core::cell::{Cell, RefCell};
+use std::vec::Vec;
 use crate::framebuffer::Framebuffer;
 
 /// 8x8 位图字体数据
@@ -109,12 +111,184 @@ pub const FONT_8x8: [u8; 720] = [
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // '~'
 ];
 
+/// 斜体倾斜度：每上移这么多行，右移 1 像素
+pub const ITALIC_SLANT: u32 = 3;
+
+/// 合成字体样式
+///
+/// 位图字体通常只有一种字重，粗体/斜体/下划线在绘制时合成
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FontStyle {
+    /// 粗体：字形向右偏移 1 像素再绘制一次
+    pub bold: bool,
+    /// 斜体：按行水平错切，基线不动，越往上越向右
+    pub italic: bool,
+    /// 下划线：在字形单元最后一行画线
+    pub underline: bool,
+}
+
+impl FontStyle {
+    pub const REGULAR: Self = Self { bold: false, italic: false, underline: false };
+    pub const BOLD: Self = Self { bold: true, italic: false, underline: false };
+    pub const ITALIC: Self = Self { bold: false, italic: true, underline: false };
+    pub const UNDERLINE: Self = Self { bold: false, italic: false, underline: true };
+}
+
+/// 文本宽度缓存默认容量
+pub const METRICS_CACHE_CAPACITY: usize = 64;
+
+/// 默认制表位间隔（字符数）
+pub const DEFAULT_TAB_WIDTH: u32 = 4;
+
+/// 文本宽度 LRU 缓存
+///
+/// 以 (文本哈希, 样式) 为键；条目按最近使用排序，最旧的在前
+struct MetricsCache {
+    entries: Vec<(u64, u32)>,
+    capacity: usize,
+}
+
+impl MetricsCache {
+    const fn new() -> Self {
+        Self { entries: Vec::new(), capacity: METRICS_CACHE_CAPACITY }
+    }
+
+    /// 查找并把命中条目移到最近使用端
+    fn get(&mut self, key: u64) -> Option<u32> {
+        let index = self.entries.iter().position(|&(k, _)| k == key)?;
+        let entry = self.entries.remove(index);
+        self.entries.push(entry);
+        Some(entry.1)
+    }
+
+    /// 插入条目，满时淘汰最久未使用的
+    fn insert(&mut self, key: u64, width: u32) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((key, width));
+    }
+}
+
+/// 按空格切分单词，返回 (字节偏移, 单词)，忽略连续空格产生的空单词
+fn word_spans(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(' ')
+        .scan(0, |offset, word| {
+            let start = *offset;
+            *offset += word.len() + 1;
+            Some((start, word))
+        })
+        .filter(|(_, word)| !word.is_empty())
+}
+
+/// 缓存键：文本的 FNV-1a 哈希混入样式位和解码方式（按字节 / 按 UTF-8）
+fn metrics_key(text: &str, style: FontStyle, utf8: bool) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    let style_bits = style.bold as u64 | (style.italic as u64) << 1 | (utf8 as u64) << 2;
+    text.bytes()
+        .fold(FNV_OFFSET ^ style_bits, |hash, b| (hash ^ b as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// PSF1 魔数
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+/// PSF1 模式位：512 个字形
+const PSF1_MODE512: u8 = 0x01;
+/// PSF2 魔数
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xB5, 0x4A, 0x86];
+
+/// PSF 字体解析错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PsfError {
+    /// 数据比文件头短
+    TooShort,
+    /// 不是 PSF1/PSF2 魔数
+    BadMagic,
+    /// 不支持的 PSF2 版本
+    UnsupportedVersion,
+    /// 字形尺寸为 0 或与每字形字节数不一致
+    BadGlyphSize,
+    /// 字形数据不完整
+    Truncated,
+}
+
+impl core::fmt::Display for PsfError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PsfError::TooShort => write!(f, "data shorter than the header"),
+            PsfError::BadMagic => write!(f, "not a PSF1/PSF2 font"),
+            PsfError::UnsupportedVersion => write!(f, "unsupported PSF2 version"),
+            PsfError::BadGlyphSize => write!(f, "invalid glyph size"),
+            PsfError::Truncated => write!(f, "glyph data truncated"),
+        }
+    }
+}
+
+/// 解析 PSF1/PSF2 字体，返回 (宽, 高, 字形数据)
+///
+/// 字形按下标对应码点（Unicode 映射表被忽略），每行 `(width + 7) / 8` 字节，高位在左
+fn parse_psf(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), PsfError> {
+    let (width, height, count, glyph_size, header_size) = if bytes.starts_with(&PSF1_MAGIC) {
+        // magic[2] mode[1] charsize[1]，宽度固定 8
+        let header = bytes.get(..4).ok_or(PsfError::TooShort)?;
+        let count = if header[2] & PSF1_MODE512 != 0 { 512 } else { 256 };
+        let height = header[3] as u32;
+        (8, height, count, height as usize, 4)
+    } else if bytes.starts_with(&PSF2_MAGIC) {
+        // magic version headersize flags length charsize height width，均为小端 u32
+        let header = bytes.get(..32).ok_or(PsfError::TooShort)?;
+        let field = |i: usize| u32::from_le_bytes([header[i * 4], header[i * 4 + 1], header[i * 4 + 2], header[i * 4 + 3]]);
+        if field(1) != 0 {
+            return Err(PsfError::UnsupportedVersion);
+        }
+        (field(7), field(6), field(4) as usize, field(5) as usize, field(2) as usize)
+    } else {
+        return Err(PsfError::BadMagic);
+    };
+
+    let row_bytes = width.div_ceil(8) as usize;
+    if width == 0 || height == 0 || glyph_size != row_bytes * height as usize {
+        return Err(PsfError::BadGlyphSize);
+    }
+    let len = count.checked_mul(glyph_size).ok_or(PsfError::Truncated)?;
+    let end = header_size.checked_add(len).ok_or(PsfError::Truncated)?;
+    let data = bytes.get(header_size..end).ok_or(PsfError::Truncated)?;
+    Ok((width, height, data.to_vec()))
+}
+
+/// 字形数据来源
+enum Glyphs {
+    /// 内置 8x8 字体 (`FONT_8x8`)
+    Builtin,
+    /// 位图字体：从 `first` 开始连续编码的字形，
+    /// 每个字形 `height` 行，每行 `(width + 7) / 8` 字节，高位在左
+    Bitmap { first: u32, data: Vec<u8> },
+}
+
 /// 字体渲染器
+///
+/// 可以挂接一组后备字体：某个码点在本字体中没有字形时，
+/// 按添加顺序使用第一个包含该字形的后备字体
 pub struct FontRenderer {
-    /// 字体宽度
+    /// 字形位图宽度（未缩放）
     width: u32,
-    /// 字体高度
+    /// 字形位图高度（未缩放）
     height: u32,
+    /// 整数缩放倍数，每个位图像素绘制为 `scale`×`scale` 的方块
+    scale: u32,
+    /// 字形数据
+    glyphs: Glyphs,
+    /// 后备字体链
+    fallbacks: Vec<FontRenderer>,
+    /// 非等宽字体的文本宽度缓存
+    metrics: RefCell<MetricsCache>,
+    /// 缓存命中次数
+    metrics_hits: Cell<u64>,
+    /// 制表位间隔（字符数）
+    tab_width: u32,
 }
 
 impl FontRenderer {
@@ -123,67 +297,488 @@ impl FontRenderer {
         Self {
             width: 8,
             height: 8,
+            scale: 1,
+            glyphs: Glyphs::Builtin,
+            fallbacks: Vec::new(),
+            metrics: RefCell::new(MetricsCache::new()),
+            metrics_hits: Cell::new(0),
+            tab_width: DEFAULT_TAB_WIDTH,
         }
     }
 
-    /// 获取字体宽度
+    /// 从位图数据创建字体，覆盖从 `first` 开始的连续码点
+    ///
+    /// 数据按字形依次排列，每个字形 `height` 行，每行 `(width + 7) / 8` 字节
+    pub fn from_bitmap(width: u32, height: u32, first: u32, data: Vec<u8>) -> Self {
+        Self {
+            width,
+            height,
+            scale: 1,
+            glyphs: Glyphs::Bitmap { first, data },
+            fallbacks: Vec::new(),
+            metrics: RefCell::new(MetricsCache::new()),
+            metrics_hits: Cell::new(0),
+            tab_width: DEFAULT_TAB_WIDTH,
+        }
+    }
+
+    /// 从 PC Screen Font (PSF1/PSF2) 数据创建字体，解析失败时返回错误
+    ///
+    /// 字形 i 对应码点 i，字形尺寸取自文件头
+    pub fn try_from_psf(bytes: &[u8]) -> Result<Self, PsfError> {
+        let (width, height, data) = parse_psf(bytes)?;
+        Ok(Self::from_bitmap(width, height, 0, data))
+    }
+
+    /// 从 PSF 数据创建字体，解析失败时回退到内置 8x8 字体
+    pub fn from_psf(bytes: &[u8]) -> Self {
+        Self::try_from_psf(bytes).unwrap_or_else(|_| Self::new_8x8())
+    }
+
+    /// 按整数倍缩放字形（至少为 1），后备字体使用相同倍数
+    ///
+    /// `width()`、`height()` 和文本测量都返回缩放后的尺寸
+    pub fn with_scale(mut self, scale: u32) -> Self {
+        self.set_scale(scale.max(1));
+        self
+    }
+
+    /// 缩放倍数
+    #[inline]
+    pub const fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    fn set_scale(&mut self, scale: u32) {
+        self.scale = scale;
+        for fallback in &mut self.fallbacks {
+            fallback.set_scale(scale);
+        }
+        self.clear_metrics_cache();
+    }
+
+    /// 添加后备字体（追加到链尾，使用本字体的缩放倍数），字形表改变后清空宽度缓存
+    pub fn add_fallback(&mut self, mut font: FontRenderer) -> &mut Self {
+        font.set_scale(self.scale);
+        self.fallbacks.push(font);
+        self.clear_metrics_cache();
+        self
+    }
+
+    /// 设置制表位间隔（字符数，至少为 1），清空宽度缓存
+    pub fn set_tab_width(&mut self, chars: u32) -> &mut Self {
+        self.tab_width = chars.max(1);
+        self.clear_metrics_cache();
+        self
+    }
+
+    /// 制表位间隔（字符数）
+    #[inline]
+    pub fn tab_width(&self) -> u32 {
+        self.tab_width
+    }
+
+    /// 行内偏移 `offset` 之后的下一个制表位（相对行首的像素偏移）
+    pub fn next_tab_stop(&self, offset: u32) -> u32 {
+        let stop = (self.tab_width * self.width()).max(1);
+        (offset / stop + 1) * stop
+    }
+
+    /// 是否等宽：后备字体都等宽且宽度相同
+    ///
+    /// 等宽字体直接按 字节数 × 步进 计算宽度，不使用缓存
+    pub fn is_monospace(&self) -> bool {
+        self.fallbacks.iter().all(|f| f.width() == self.width() && f.is_monospace())
+    }
+
+    /// 清空文本宽度缓存
+    pub fn clear_metrics_cache(&self) {
+        self.metrics.borrow_mut().entries.clear();
+    }
+
+    /// 设置文本宽度缓存容量，0 表示禁用缓存
+    pub fn set_metrics_cache_capacity(&mut self, capacity: usize) {
+        let cache = self.metrics.get_mut();
+        cache.capacity = capacity;
+        let excess = cache.entries.len().saturating_sub(capacity);
+        cache.entries.drain(..excess);
+    }
+
+    /// 缓存中的条目数
+    pub fn metrics_cache_len(&self) -> usize {
+        self.metrics.borrow().entries.len()
+    }
+
+    /// 缓存命中次数
+    pub fn metrics_cache_hits(&self) -> u64 {
+        self.metrics_hits.get()
+    }
+
+    /// 获取字体宽度（含缩放）
     #[inline]
     pub const fn width(&self) -> u32 {
-        self.width
+        self.width * self.scale
     }
 
-    /// 获取字体高度
+    /// 获取字体高度（含缩放）
     #[inline]
     pub const fn height(&self) -> u32 {
-        self.height
+        self.height * self.scale
     }
 
-    /// 绘制单个字符
-    pub fn draw_char<F: Framebuffer>(&self, fb: &F, x: u32, y: u32, ch: u8, color: u32) {
-        // 字体数据覆盖 0x20-0x7F (但实际只有 90 个字符: 0x20-0x79)
-        if ch < 0x20 || ch > 0x79 {
-            return;
+    /// 每行字节数
+    #[inline]
+    fn row_bytes(&self) -> usize {
+        self.width.div_ceil(8) as usize
+    }
+
+    /// 本字体（不含后备字体）中码点 `cp` 的字形数据
+    fn own_glyph(&self, cp: u32) -> Option<&[u8]> {
+        match &self.glyphs {
+            // 字体数据覆盖 0x20-0x7F (但实际只有 90 个字符: 0x20-0x79)
+            Glyphs::Builtin => {
+                if !(0x20..=0x79).contains(&cp) {
+                    return None;
+                }
+                let base = (cp - 0x20) as usize * 8;
+                Some(&FONT_8x8[base..base + 8])
+            }
+            Glyphs::Bitmap { first, data } => {
+                let size = self.row_bytes() * self.height as usize;
+                let base = (cp.checked_sub(*first)? as usize).checked_mul(size)?;
+                data.get(base..base + size)
+            }
+        }
+    }
+
+    /// 按后备链查找包含 `cp` 的字体
+    fn font_for(&self, cp: u32) -> Option<&FontRenderer> {
+        if self.own_glyph(cp).is_some() {
+            return Some(self);
+        }
+        self.fallbacks.iter().find_map(|f| f.font_for(cp))
+    }
+
+    /// 本字体或后备字体中是否有 `cp` 的字形
+    pub fn has_glyph(&self, cp: u32) -> bool {
+        self.font_for(cp).is_some()
+    }
+
+    /// 码点 `cp` 的步进宽度（使用提供字形的字体的宽度）
+    pub fn advance(&self, cp: u32) -> u32 {
+        self.font_for(cp).map_or(self.width(), |f| f.width())
+    }
+
+    /// 样式化后码点 `cp` 的步进宽度（粗体每个字形加宽 1 个位图像素）
+    pub fn advance_styled(&self, cp: u32, style: FontStyle) -> u32 {
+        self.advance(cp) + self.bold_extra(style)
+    }
+
+    /// 粗体的额外宽度
+    #[inline]
+    fn bold_extra(&self, style: FontStyle) -> u32 {
+        style.bold as u32 * self.scale
+    }
+
+    /// 斜体时最上一行相对基线的右移量，即文本末尾多出的宽度
+    pub fn italic_overhang(&self, style: FontStyle) -> u32 {
+        if style.italic {
+            self.height.saturating_sub(1) / ITALIC_SLANT * self.scale
+        } else {
+            0
         }
+    }
+
+    /// 绘制单个码点，返回步进宽度
+    ///
+    /// 控制字符不绘制；所有字体都没有字形的可打印码点绘制 `.notdef` 方框
+    pub fn draw_codepoint<F: Framebuffer>(&self, fb: &F, x: u32, y: u32, cp: u32, color: u32) -> u32 {
+        self.draw_codepoint_styled(fb, x, y, cp, color, FontStyle::REGULAR)
+    }
 
-        let idx = (ch - 0x20) as usize;
-        let base = idx * 8;
+    /// 以合成样式绘制单个码点，返回步进宽度（不含下划线，由字符串绘制负责）
+    pub fn draw_codepoint_styled<F: Framebuffer>(
+        &self,
+        fb: &F,
+        x: u32,
+        y: u32,
+        cp: u32,
+        color: u32,
+        style: FontStyle,
+    ) -> u32 {
+        if cp < 0x20 || cp == 0x7F {
+            return self.advance_styled(cp, style);
+        }
+        let Some(font) = self.font_for(cp) else {
+            self.draw_notdef(fb, x, y, color);
+            return self.width() + self.bold_extra(style);
+        };
+        let glyph = font.own_glyph(cp).unwrap_or(&[]);
+        let row_bytes = font.row_bytes();
+        let n = font.scale;
 
-        for py in 0..8 {
-            let row_data = FONT_8x8[base + py as usize];
-            for px in 0..8 {
-                let bit = (row_data >> (7 - px)) & 1;
+        for py in 0..font.height {
+            let row = &glyph[py as usize * row_bytes..(py as usize + 1) * row_bytes];
+            let shift = if style.italic { (font.height - 1 - py) / ITALIC_SLANT } else { 0 };
+            for px in 0..font.width {
+                let bit = (row[(px / 8) as usize] >> (7 - px % 8)) & 1;
                 if bit != 0 {
-                    fb.put_pixel(x + px, y + py, color);
+                    let (dx, dy) = (x + (px + shift) * n, y + py * n);
+                    font.put_block(fb, dx, dy, color);
+                    if style.bold {
+                        font.put_block(fb, dx + n, dy, color);
+                    }
                 }
             }
         }
+        font.width() + font.bold_extra(style)
+    }
+
+    /// 绘制一个缩放后的位图像素
+    #[inline]
+    fn put_block<F: Framebuffer>(&self, fb: &F, x: u32, y: u32, color: u32) {
+        if self.scale == 1 {
+            fb.put_pixel(x, y, color);
+        } else {
+            fb.fill_rect(x, y, self.scale, self.scale, color);
+        }
+    }
+
+    /// 绘制 `.notdef` 字形（留一个位图像素边距的空心方框）
+    fn draw_notdef<F: Framebuffer>(&self, fb: &F, x: u32, y: u32, color: u32) {
+        let (n, width, height) = (self.scale, self.width(), self.height());
+        if width > 2 * n && height > 2 * n {
+            fb.blit_rect(x + n, y + n, width - 2 * n, height - 2 * n, color, n);
+        }
+    }
+
+    /// 绘制单个字符
+    pub fn draw_char<F: Framebuffer>(&self, fb: &F, x: u32, y: u32, ch: u8, color: u32) {
+        self.draw_codepoint(fb, x, y, ch as u32, color);
     }
 
     /// 绘制字符串
-    pub fn draw_string<F: Framebuffer>(&self, fb: &F, mut x: u32, mut y: u32, text: &str, color: u32) {
-        for ch in text.bytes() {
-            match ch {
-                b'\n' => {
-                    y += self.height;
+    pub fn draw_string<F: Framebuffer>(&self, fb: &F, x: u32, y: u32, text: &str, color: u32) {
+        self.draw_string_styled(fb, x, y, text, color, FontStyle::REGULAR);
+    }
+
+    /// 以合成样式绘制字符串，下划线按行绘制
+    ///
+    /// 按字节绘制（每个字节一个码点），适合 ASCII 文本；其他文本使用 `draw_string_utf8`
+    pub fn draw_string_styled<F: Framebuffer>(
+        &self,
+        fb: &F,
+        x: u32,
+        y: u32,
+        text: &str,
+        color: u32,
+        style: FontStyle,
+    ) {
+        self.draw_codepoints(fb, x, y, text.bytes().map(u32::from), color, style);
+    }
+
+    /// 解码 UTF-8 后逐码点绘制，所有字体都缺失的码点绘制 `.notdef` 方框
+    ///
+    /// 纯 ASCII 文本走按字节的快速路径
+    pub fn draw_string_utf8<F: Framebuffer>(&self, fb: &F, x: u32, y: u32, text: &str, color: u32) {
+        if text.is_ascii() {
+            self.draw_string(fb, x, y, text, color);
+        } else {
+            self.draw_codepoints(fb, x, y, text.chars().map(u32::from), color, FontStyle::REGULAR);
+        }
+    }
+
+    /// 逐码点绘制，处理换行和制表符
+    fn draw_codepoints<F: Framebuffer, I: Iterator<Item = u32>>(
+        &self,
+        fb: &F,
+        mut x: u32,
+        mut y: u32,
+        codepoints: I,
+        color: u32,
+        style: FontStyle,
+    ) {
+        let mut line_start = x;
+        for cp in codepoints {
+            match cp {
+                0x0A => {
+                    self.draw_underline(fb, line_start, x, y, color, style);
+                    y += self.height();
                     x = 0;
+                    line_start = 0;
+                }
+                0x09 => {
+                    x = line_start + self.next_tab_stop(x - line_start);
                 }
                 _ => {
-                    self.draw_char(fb, x, y, ch, color);
-                    x += self.width;
+                    x += self.draw_codepoint_styled(fb, x, y, cp, color, style);
+                }
+            }
+        }
+        self.draw_underline(fb, line_start, x, y, color, style);
+    }
+
+    /// 绘制单行文本，下一个字形会超出 `max_x` 时停止
+    ///
+    /// 按 UTF-8 解码，遇到换行符也停止。返回绘制（含制表符）的字符数
+    pub fn draw_string_clipped<F: Framebuffer>(
+        &self,
+        fb: &F,
+        x: u32,
+        y: u32,
+        text: &str,
+        color: u32,
+        max_x: u32,
+    ) -> usize {
+        self.draw_line_until(fb, x, y, text, color, max_x).0
+    }
+
+    /// 同 `draw_string_clipped`，但文本被截断时在末尾绘制省略号 `...`
+    ///
+    /// 返回绘制的文本字符数（不含省略号）
+    pub fn draw_string_ellipsis<F: Framebuffer>(
+        &self,
+        fb: &F,
+        x: u32,
+        y: u32,
+        text: &str,
+        color: u32,
+        max_x: u32,
+    ) -> usize {
+        let line = text.split('\n').next().unwrap_or("");
+        if x.saturating_add(self.measure_text_utf8(line)) <= max_x {
+            return self.draw_string_clipped(fb, x, y, line, color, max_x);
+        }
+        let ellipsis_width = 3 * self.advance(b'.' as u32);
+        let text_max = max_x.saturating_sub(ellipsis_width).max(x);
+        let (drawn, end_x) = self.draw_line_until(fb, x, y, line, color, text_max);
+        self.draw_string_clipped(fb, end_x, y, "...", color, max_x);
+        drawn
+    }
+
+    /// 按空格自动换行绘制段落，返回占用的总高度
+    ///
+    /// 下一个单词会超出 `max_width` 时换行，`\n` 强制换行；
+    /// 比 `max_width` 还宽的单词单独占一行并被截断。按 UTF-8 解码
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_string_wrapped<F: Framebuffer>(
+        &self,
+        fb: &F,
+        x: u32,
+        y: u32,
+        max_width: u32,
+        line_height: u32,
+        text: &str,
+        color: u32,
+    ) -> u32 {
+        let lines = self.wrap_lines(text, max_width);
+        let max_x = x.saturating_add(max_width);
+        for (i, line) in lines.iter().enumerate() {
+            self.draw_string_clipped(fb, x, y + i as u32 * line_height, line, color, max_x);
+        }
+        lines.len() as u32 * line_height
+    }
+
+    /// 把文本拆成宽度不超过 `max_width` 的行，空文本占一行
+    pub(crate) fn wrap_lines<'a>(&self, text: &'a str, max_width: u32) -> Vec<&'a str> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut start = 0;
+            let mut end = 0;
+            for (offset, word) in word_spans(paragraph) {
+                let candidate = &paragraph[start..offset + word.len()];
+                if end > start && self.measure_text_utf8(candidate) > max_width {
+                    lines.push(&paragraph[start..end]);
+                    start = offset;
                 }
+                end = offset + word.len();
             }
+            lines.push(&paragraph[start..end]);
+        }
+        lines
+    }
+
+    /// 逐字绘制到 `max_x` 为止，返回 (字符数, 结束时的 x)
+    fn draw_line_until<F: Framebuffer>(
+        &self,
+        fb: &F,
+        x: u32,
+        y: u32,
+        text: &str,
+        color: u32,
+        max_x: u32,
+    ) -> (usize, u32) {
+        let mut cx = x;
+        let mut count = 0;
+        for ch in text.chars() {
+            let next = match ch {
+                '\n' => return (count, cx),
+                '\t' => x + self.next_tab_stop(cx - x),
+                _ => cx + self.advance(ch as u32),
+            };
+            if next > max_x {
+                return (count, cx);
+            }
+            if ch != '\t' {
+                self.draw_codepoint(fb, cx, y, ch as u32, color);
+            }
+            cx = next;
+            count += 1;
+        }
+        (count, cx)
+    }
+
+    /// 在字形单元最后一行画 [start, end) 的下划线
+    fn draw_underline<F: Framebuffer>(&self, fb: &F, start: u32, end: u32, y: u32, color: u32, style: FontStyle) {
+        if style.underline && end > start && self.height > 0 {
+            fb.fill_rect(start, y + self.height() - self.scale, end - start, self.scale, color);
         }
     }
 
     /// 计算文本宽度
     pub fn measure_text(&self, text: &str) -> u32 {
-        let mut width = 0u32;
-        for ch in text.bytes() {
-            match ch {
-                b'\n' => break,
-                _ => width += self.width,
-            }
+        self.measure_text_styled(text, FontStyle::REGULAR)
+    }
+
+    /// 计算样式化文本宽度，包含粗体加宽和斜体末尾的倾斜量
+    ///
+    /// 按字节计算；`\t` 前进到下一个制表位；不含制表符的等宽文本直接计算，否则先查宽度缓存
+    pub fn measure_text_styled(&self, text: &str, style: FontStyle) -> u32 {
+        self.measure_line(text, style, false)
+    }
+
+    /// 按 UTF-8 码点计算文本宽度（与 `draw_string_utf8` 一致，每个码点占一个字形步进）
+    pub fn measure_text_utf8(&self, text: &str) -> u32 {
+        self.measure_line(text, FontStyle::REGULAR, !text.is_ascii())
+    }
+
+    /// 计算第一行的宽度，`utf8` 为 false 时每个字节算一个码点
+    fn measure_line(&self, text: &str, style: FontStyle, utf8: bool) -> u32 {
+        let line = text.split('\n').next().unwrap_or("");
+        if line.is_empty() {
+            return 0;
+        }
+        if self.is_monospace() && !line.contains('\t') {
+            let advance = self.width() + self.bold_extra(style);
+            let columns = if utf8 { line.chars().count() } else { line.len() };
+            return columns as u32 * advance + self.italic_overhang(style);
+        }
+
+        let key = metrics_key(line, style, utf8);
+        if let Some(width) = self.metrics.borrow_mut().get(key) {
+            self.metrics_hits.set(self.metrics_hits.get() + 1);
+            return width;
         }
+        let advance = |w: u32, cp: u32| match cp {
+            0x09 => self.next_tab_stop(w),
+            _ => w + self.advance_styled(cp, style),
+        };
+        let width = if utf8 {
+            line.chars().map(u32::from).fold(0, advance)
+        } else {
+            line.bytes().map(u32::from).fold(0, advance)
+        } + self.italic_overhang(style);
+        self.metrics.borrow_mut().insert(key, width);
         width
     }
 }
@@ -2,6 +2,7 @@
 //!
 //! 提供基础的像素级绘图操作
 
+use core::cell::Cell;
 use core::ptr::write_volatile;
 use core::ptr::read_volatile;
 
@@ -177,7 +178,7 @@ unsafe fn syscall6(_num: usize, _arg0: usize, _arg1: usize, _arg2: usize,
     -1
 }
 
-/// 颜色常量 (xRGB 格式)
+/// 颜色常量与转换工具 (xRGB 格式)
 pub mod color {
     pub const BLACK: u32 = 0xFF000000;
     pub const WHITE: u32 = 0xFFFFFFFF;
@@ -190,7 +191,227 @@ pub mod color {
     pub const GRAY: u32 = 0xFF808080;
     pub const DARK_GRAY: u32 = 0xFF404040;
     pub const LIGHT_GRAY: u32 = 0xFFC0C0C0;
+    pub const DARK_BLUE: u32 = 0xFF000080;
+    pub const ORANGE: u32 = 0xFFFFA500;
     pub const TRANSPARENT: u32 = 0x00000000;
+
+    /// 由 R/G/B 分量构造不透明颜色
+    #[inline]
+    pub const fn rgb(r: u8, g: u8, b: u8) -> u32 {
+        rgba(r, g, b, 0xFF)
+    }
+
+    /// 由 R/G/B/A 分量构造颜色
+    #[inline]
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> u32 {
+        ((a as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+    }
+
+    /// 拆分为 (r, g, b, a)
+    #[inline]
+    pub const fn components(c: u32) -> (u8, u8, u8, u8) {
+        ((c >> 16) as u8, (c >> 8) as u8, c as u8, (c >> 24) as u8)
+    }
+
+    /// 替换颜色的 alpha 分量
+    #[inline]
+    pub const fn with_alpha(c: u32, a: u8) -> u32 {
+        (c & 0x00FF_FFFF) | ((a as u32) << 24)
+    }
+
+    /// source-over 合成：`src` 的高 8 位为不透明度，覆盖在 `dst` 之上
+    pub fn over(src: u32, dst: u32) -> u32 {
+        let (sr, sg, sb, sa) = components(src);
+        let (dr, dg, db, da) = components(dst);
+        let a = sa as u32;
+        let mix = |s: u8, d: u8| ((s as u32 * a + d as u32 * (255 - a) + 127) / 255) as u8;
+        let out_a = a + (da as u32 * (255 - a) + 127) / 255;
+        rgba(mix(sr, dr), mix(sg, dg), mix(sb, db), out_a as u8)
+    }
+
+    /// 由 HSV 构造颜色：色相 0..360 度，饱和度和明度 0..=255
+    pub fn hsv(h: u16, s: u8, v: u8) -> u32 {
+        let h = (h % 360) as f32 / 60.0;
+        let s = s as f32 / 255.0;
+        let v = v as f32;
+        let c = v * s;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let m = v - c;
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        rgb((r + m).round() as u8, (g + m).round() as u8, (b + m).round() as u8)
+    }
+
+    /// 转换为 HSV (色相 0..360, 饱和度, 明度)
+    pub fn to_hsv(c: u32) -> (u16, u8, u8) {
+        let (r, g, b, _) = components(c);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = (max - min) as f32;
+        if max == 0 {
+            return (0, 0, 0);
+        }
+        let s = (delta * 255.0 / max as f32).round() as u8;
+        if delta == 0.0 {
+            return (0, s, max);
+        }
+        let (r, g, b) = (r as f32, g as f32, b as f32);
+        let h = if max as f32 == r {
+            60.0 * ((g - b) / delta)
+        } else if max as f32 == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let h = if h < 0.0 { h + 360.0 } else { h };
+        ((h.round() as u16) % 360, s, max)
+    }
+
+    /// 每个颜色分量增加 `amount`（饱和到 255），保留 alpha
+    pub fn lighten(c: u32, amount: u8) -> u32 {
+        let (r, g, b, a) = components(c);
+        rgba(r.saturating_add(amount), g.saturating_add(amount), b.saturating_add(amount), a)
+    }
+
+    /// 每个颜色分量减少 `amount`（饱和到 0），保留 alpha
+    pub fn darken(c: u32, amount: u8) -> u32 {
+        let (r, g, b, a) = components(c);
+        rgba(r.saturating_sub(amount), g.saturating_sub(amount), b.saturating_sub(amount), a)
+    }
+}
+
+/// 裁剪矩形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ClipRect {
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// 点是否在矩形内
+    #[inline]
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x - self.x < self.width && y >= self.y && y - self.y < self.height
+    }
+
+    /// 面积是否为 0
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// 两个矩形是否重叠或相邻（合并后不会多覆盖空隙）
+    pub fn touches(&self, other: &ClipRect) -> bool {
+        self.x <= other.x.saturating_add(other.width)
+            && other.x <= self.x.saturating_add(self.width)
+            && self.y <= other.y.saturating_add(other.height)
+            && other.y <= self.y.saturating_add(self.height)
+    }
+
+    /// 两个矩形的交集，不相交时返回宽高为 0 的矩形
+    pub fn intersect(&self, other: &ClipRect) -> ClipRect {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = self.x.saturating_add(self.width).min(other.x.saturating_add(other.width));
+        let y1 = self.y.saturating_add(self.height).min(other.y.saturating_add(other.height));
+        ClipRect::new(x0, y0, x1.saturating_sub(x0), y1.saturating_sub(y0))
+    }
+
+    /// 同时包含两个矩形的最小矩形
+    pub fn union(&self, other: &ClipRect) -> ClipRect {
+        let x0 = self.x.min(other.x);
+        let y0 = self.y.min(other.y);
+        let x1 = self.x.saturating_add(self.width).max(other.x.saturating_add(other.width));
+        let y1 = self.y.saturating_add(self.height).max(other.y.saturating_add(other.height));
+        ClipRect::new(x0, y0, x1 - x0, y1 - y0)
+    }
+}
+
+/// 把矩形与缓冲区边界及可选的裁剪矩形求交
+///
+/// 返回半开区间 (x0, y0, x1, y1)；无交集时 x0 >= x1 或 y0 >= y1
+#[inline]
+pub(crate) fn clip_bounds(
+    clip: Option<ClipRect>,
+    (x, y, width, height): (u32, u32, u32, u32),
+    (fb_w, fb_h): (u32, u32),
+) -> (u32, u32, u32, u32) {
+    let (mut x0, mut y0) = (x, y);
+    let mut x1 = x.saturating_add(width).min(fb_w);
+    let mut y1 = y.saturating_add(height).min(fb_h);
+    if let Some(c) = clip {
+        x0 = x0.max(c.x);
+        y0 = y0.max(c.y);
+        x1 = x1.min(c.x.saturating_add(c.width));
+        y1 = y1.min(c.y.saturating_add(c.height));
+    }
+    (x0, y0, x1, y1)
+}
+
+/// 把行优先的位图复制到 `fb` 的 (x, y)，跳过越界、被裁剪和等于 `key` 的像素
+fn blit_rows<F: Framebuffer + ?Sized>(
+    fb: &F,
+    (x, y, width, height): (u32, u32, u32, u32),
+    pixels: &[u32],
+    key: Option<u32>,
+) {
+    let (x0, y0, x1, y1) = clip_bounds(fb.clip(), (x, y, width, height), (fb.width(), fb.height()));
+    for py in y0..y1 {
+        for px in x0..x1 {
+            let index = ((py - y) * width + (px - x)) as usize;
+            match pixels.get(index) {
+                Some(&p) if Some(p) != key => fb.put_pixel(px, py, p),
+                Some(_) => {}
+                None => return,
+            }
+        }
+    }
+}
+
+/// 区域内逐行上移（`up`）或下移 `lines` 行，露出的行填充 `fill`
+///
+/// 上移时从上往下复制、下移时从下往上复制，重叠的源行在被覆盖前已读出
+pub(crate) fn scroll_rows<F: Framebuffer + ?Sized>(
+    fb: &F,
+    (x, y, width, height): (u32, u32, u32, u32),
+    lines: u32,
+    fill: u32,
+    up: bool,
+) {
+    let (x0, y0, x1, y1) = clip_bounds(None, (x, y, width, height), (fb.width(), fb.height()));
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+    let rows = y1 - y0;
+    let lines = lines.min(rows);
+    let copy_row = |dst: u32, src: u32| {
+        for px in x0..x1 {
+            fb.put_pixel(px, dst, fb.get_pixel(px, src));
+        }
+    };
+    if up {
+        for row in y0..y1 - lines {
+            copy_row(row, row + lines);
+        }
+        fb.fill_rect(x0, y1 - lines, x1 - x0, lines, fill);
+    } else {
+        for row in (y0 + lines..y1).rev() {
+            copy_row(row, row - lines);
+        }
+        fb.fill_rect(x0, y0, x1 - x0, lines, fill);
+    }
 }
 
 /// Framebuffer 绘图 trait
@@ -199,16 +420,78 @@ pub trait Framebuffer {
     fn width(&self) -> u32;
     fn height(&self) -> u32;
 
+    /// 读取像素（用于混合），不支持回读的实现返回 0
+    fn get_pixel(&self, _x: u32, _y: u32) -> u32 {
+        0
+    }
+
+    /// 当前裁剪矩形，None 表示可写整个缓冲区
+    fn clip(&self) -> Option<ClipRect> {
+        None
+    }
+
+    /// 设置裁剪矩形，之后所有绘制只写入矩形内（不支持裁剪的实现忽略）
+    fn set_clip(&self, _x: u32, _y: u32, _width: u32, _height: u32) {}
+
+    /// 取消裁剪
+    fn clear_clip(&self) {}
+
     fn fill_rect(&self, x: u32, y: u32, width: u32, height: u32, color: u32) {
-        let x_end = (x + width).min(self.width());
-        let y_end = (y + height).min(self.height());
-        for py in y..y_end {
-            for px in x..x_end {
+        let (x0, y0, x1, y1) = clip_bounds(self.clip(), (x, y, width, height), (self.width(), self.height()));
+        for py in y0..y1 {
+            for px in x0..x1 {
                 self.put_pixel(px, py, color);
             }
         }
     }
 
+    /// 把行优先的 `width`×`height` ARGB 像素复制到 (x, y)
+    ///
+    /// 超出缓冲区或裁剪矩形的部分被跳过；`pixels` 不足时只复制已有的部分
+    fn blit_bitmap(&self, x: u32, y: u32, width: u32, height: u32, pixels: &[u32]) {
+        blit_rows(self, (x, y, width, height), pixels, None);
+    }
+
+    /// 同 `blit_bitmap`，但跳过等于颜色键 `transparent` 的像素
+    fn blit_bitmap_keyed(&self, x: u32, y: u32, width: u32, height: u32, pixels: &[u32], transparent: u32) {
+        blit_rows(self, (x, y, width, height), pixels, Some(transparent));
+    }
+
+    /// 把 (x, y, width, height) 区域内的像素上移 `lines` 行，底部露出的行填充 `fill_color`
+    fn scroll_rect_up(&self, x: u32, y: u32, width: u32, height: u32, lines: u32, fill_color: u32) {
+        scroll_rows(self, (x, y, width, height), lines, fill_color, true);
+    }
+
+    /// 把区域内的像素下移 `lines` 行，顶部露出的行填充 `fill_color`
+    fn scroll_rect_down(&self, x: u32, y: u32, width: u32, height: u32, lines: u32, fill_color: u32) {
+        scroll_rows(self, (x, y, width, height), lines, fill_color, false);
+    }
+
+    /// 按 `argb` 高 8 位的不透明度与现有像素做 source-over 混合
+    fn blend_pixel(&self, x: u32, y: u32, argb: u32) {
+        match argb >> 24 {
+            0 => {}
+            0xFF => self.put_pixel(x, y, argb),
+            _ => self.put_pixel(x, y, color::over(argb, self.get_pixel(x, y))),
+        }
+    }
+
+    /// 半透明填充矩形；完全不透明时走 `fill_rect` 快速路径
+    fn fill_rect_blend(&self, x: u32, y: u32, width: u32, height: u32, argb: u32) {
+        match argb >> 24 {
+            0 => {}
+            0xFF => self.fill_rect(x, y, width, height, argb),
+            _ => {
+                let (x0, y0, x1, y1) = clip_bounds(self.clip(), (x, y, width, height), (self.width(), self.height()));
+                for py in y0..y1 {
+                    for px in x0..x1 {
+                        self.blend_pixel(px, py, argb);
+                    }
+                }
+            }
+        }
+    }
+
     fn blit_rect(&self, x: u32, y: u32, width: u32, height: u32, color: u32, thickness: u32) {
         self.fill_rect(x, y, width, thickness, color);
         self.fill_rect(x, y + height - thickness, width, thickness, color);
@@ -257,6 +540,45 @@ pub trait Framebuffer {
             }
         }
     }
+
+    /// 绘制圆（`fill` 为 true 时填充），超出屏幕的部分被裁剪
+    fn draw_circle(&self, cx: u32, cy: u32, radius: u32, color: u32, fill: bool) {
+        let cx = cx as i32;
+        let cy = cy as i32;
+        let radius = radius as i32;
+
+        if fill {
+            for y in -radius..=radius {
+                for x in -radius..=radius {
+                    if x * x + y * y <= radius * radius {
+                        self.put_pixel((cx + x) as u32, (cy + y) as u32, color);
+                    }
+                }
+            }
+        } else {
+            let mut x = radius;
+            let mut y = 0i32;
+            let mut err = 0i32;
+
+            while x >= y {
+                self.put_pixel((cx + x) as u32, (cy + y) as u32, color);
+                self.put_pixel((cx + y) as u32, (cy + x) as u32, color);
+                self.put_pixel((cx - y) as u32, (cy + x) as u32, color);
+                self.put_pixel((cx - x) as u32, (cy + y) as u32, color);
+                self.put_pixel((cx - x) as u32, (cy - y) as u32, color);
+                self.put_pixel((cx - y) as u32, (cy - x) as u32, color);
+                self.put_pixel((cx + y) as u32, (cy - x) as u32, color);
+                self.put_pixel((cx + x) as u32, (cy - y) as u32, color);
+
+                y += 1;
+                err += 1 + 2 * y;
+                if 2 * (err - x) + 1 > 0 {
+                    x -= 1;
+                    err += 1 - 2 * x;
+                }
+            }
+        }
+    }
 }
 
 /// Framebuffer 信息
@@ -280,6 +602,8 @@ pub struct FramebufferDevice {
     info: FramebufferInfo,
     /// Framebuffer 起始指针
     ptr: *mut u8,
+    /// 裁剪矩形
+    clip: Cell<Option<ClipRect>>,
 }
 
 unsafe impl Send for FramebufferDevice {}
@@ -348,6 +672,7 @@ impl FramebufferDevice {
                     stride: fix_info.line_length / 4, // 转换为像素数
                 },
                 ptr: fb_ptr as usize as *mut u8,
+                clip: Cell::new(None),
             })
         }
     }
@@ -358,7 +683,7 @@ impl FramebufferDevice {
     /// `addr` 必须是有效的地址
     pub unsafe fn new(addr: usize, info: FramebufferInfo) -> Self {
         let ptr = addr as *mut u8;
-        Self { info, ptr }
+        Self { info, ptr, clip: Cell::new(None) }
     }
 
     /// 从原始指针创建
@@ -374,6 +699,7 @@ impl FramebufferDevice {
                 stride,
             },
             ptr: addr as *mut u8,
+            clip: Cell::new(None),
         }
     }
 
@@ -413,6 +739,9 @@ impl FramebufferDevice {
         if x >= self.width() || y >= self.height() {
             return;
         }
+        if self.clip.get().is_some_and(|c| !c.contains(x, y)) {
+            return;
+        }
 
         unsafe {
             let offset = (y * self.stride() + x * 4) as usize;
@@ -435,13 +764,22 @@ impl FramebufferDevice {
         }
     }
 
-    /// 填充矩形
+    /// 设置裁剪矩形
+    pub fn set_clip(&self, x: u32, y: u32, width: u32, height: u32) {
+        self.clip.set(Some(ClipRect::new(x, y, width, height)));
+    }
+
+    /// 取消裁剪
+    pub fn clear_clip(&self) {
+        self.clip.set(None);
+    }
+
+    /// 填充矩形（与裁剪矩形求交）
     pub fn fill_rect(&self, x: u32, y: u32, width: u32, height: u32, color: u32) {
-        let x_end = (x + width).min(self.width());
-        let y_end = (y + height).min(self.height());
+        let (x0, y0, x1, y1) = clip_bounds(self.clip.get(), (x, y, width, height), (self.width(), self.height()));
 
-        for py in y..y_end {
-            for px in x..x_end {
+        for py in y0..y1 {
+            for px in x0..x1 {
                 self.put_pixel(px, py, color);
             }
         }
@@ -570,6 +908,22 @@ impl Framebuffer for FramebufferDevice {
         self.put_pixel(x, y, color);
     }
 
+    fn get_pixel(&self, x: u32, y: u32) -> u32 {
+        self.get_pixel(x, y)
+    }
+
+    fn clip(&self) -> Option<ClipRect> {
+        self.clip.get()
+    }
+
+    fn set_clip(&self, x: u32, y: u32, width: u32, height: u32) {
+        self.set_clip(x, y, width, height);
+    }
+
+    fn clear_clip(&self) {
+        self.clear_clip();
+    }
+
     fn width(&self) -> u32 {
         self.width()
     }
@@ -578,3 +932,83 @@ impl Framebuffer for FramebufferDevice {
         self.height()
     }
 }
+
+/// 内存帧缓冲区（测试用）
+///
+/// 使用普通的 `Vec<u32>` 作为像素存储，实现与设备相同的绘图接口，
+/// 使窗口管理器、控件、字体等逻辑可以在没有 GPU 的环境下测试
+#[cfg(any(test, feature = "unit-test"))]
+pub struct MemFramebuffer {
+    pixels: core::cell::RefCell<std::vec::Vec<u32>>,
+    width: u32,
+    height: u32,
+    clip: Cell<Option<ClipRect>>,
+}
+
+#[cfg(any(test, feature = "unit-test"))]
+impl MemFramebuffer {
+    /// 创建指定大小的内存帧缓冲区，初始为黑色
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            pixels: core::cell::RefCell::new(std::vec![color::BLACK; (width * height) as usize]),
+            width,
+            height,
+            clip: Cell::new(None),
+        }
+    }
+
+    /// 获取像素颜色，越界返回 0
+    pub fn get_pixel(&self, x: u32, y: u32) -> u32 {
+        if x >= self.width || y >= self.height {
+            return 0;
+        }
+        self.pixels.borrow()[(y * self.width + x) as usize]
+    }
+
+    /// 统计指定颜色的像素数量
+    pub fn count_color(&self, color: u32) -> usize {
+        self.pixels.borrow().iter().filter(|&&p| p == color).count()
+    }
+
+    /// 像素数据的副本
+    pub fn pixels(&self) -> std::vec::Vec<u32> {
+        self.pixels.borrow().clone()
+    }
+}
+
+#[cfg(any(test, feature = "unit-test"))]
+impl Framebuffer for MemFramebuffer {
+    fn put_pixel(&self, x: u32, y: u32, color: u32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        if self.clip.get().is_some_and(|c| !c.contains(x, y)) {
+            return;
+        }
+        self.pixels.borrow_mut()[(y * self.width + x) as usize] = color;
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> u32 {
+        self.get_pixel(x, y)
+    }
+
+    fn clip(&self) -> Option<ClipRect> {
+        self.clip.get()
+    }
+
+    fn set_clip(&self, x: u32, y: u32, width: u32, height: u32) {
+        self.clip.set(Some(ClipRect::new(x, y, width, height)));
+    }
+
+    fn clear_clip(&self) {
+        self.clip.set(None);
+    }
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+}
@@ -0,0 +1,68 @@
+//! 布局管理
+//!
+//! 为面板的子控件自动计算位置：
+//! - `Layout::Absolute` - 使用添加控件时给出的坐标
+//! - `Layout::Grid` - 按添加顺序从左到右、从上到下填入网格单元
+//! - `Layout::VerticalFlow` - 每个控件占一行（等价于单列网格）
+
+/// 网格布局
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridLayout {
+    /// 列数
+    pub cols: u32,
+    /// 行高（像素）
+    pub row_height: u32,
+    /// 单元之间以及与面板边缘的间距（像素）
+    pub padding: u32,
+}
+
+impl GridLayout {
+    /// 单元宽度：面板宽度扣除间距后平均分给每列
+    pub fn cell_width(&self, panel_width: u32) -> u32 {
+        let cols = self.cols.max(1);
+        panel_width.saturating_sub(self.padding * (cols + 1)) / cols
+    }
+
+    /// 第 `index` 个单元相对面板左上角的矩形 (x, y, 宽, 高)
+    pub fn cell_rect(&self, index: usize, panel_width: u32) -> (u32, u32, u32, u32) {
+        let cols = self.cols.max(1);
+        let col = index as u32 % cols;
+        let row = index as u32 / cols;
+        let cell_w = self.cell_width(panel_width);
+        (
+            self.padding + col * (cell_w + self.padding),
+            self.padding + row * (self.row_height + self.padding),
+            cell_w,
+            self.row_height,
+        )
+    }
+}
+
+/// 面板布局方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// 绝对定位
+    Absolute,
+    /// 网格布局
+    Grid(GridLayout),
+    /// 垂直流式布局
+    VerticalFlow {
+        /// 行高（像素）
+        row_height: u32,
+        /// 间距（像素）
+        padding: u32,
+    },
+}
+
+impl Layout {
+    /// 第 `index` 个控件的单元矩形，绝对定位时返回 None
+    pub fn cell_rect(&self, index: usize, panel_width: u32) -> Option<(u32, u32, u32, u32)> {
+        match *self {
+            Layout::Absolute => None,
+            Layout::Grid(grid) => Some(grid.cell_rect(index, panel_width)),
+            Layout::VerticalFlow { row_height, padding } => {
+                Some(GridLayout { cols: 1, row_height, padding }.cell_rect(index, panel_width))
+            }
+        }
+    }
+}
@@ -4,20 +4,31 @@
 //! - 基础绘图原语
 //! - 字体渲染
 //! - 双缓冲
+//! - 离屏渲染表面
 //! - 窗口管理
 //! - UI 控件
+//! - 布局管理
 //! - 鼠标光标
 
 pub mod framebuffer;
 pub mod font;
 pub mod double_buffer;
+pub mod surface;
 pub mod cursor;
 pub mod window;
 pub mod widgets;
+pub mod layout;
 
-pub use framebuffer::{Framebuffer, FramebufferDevice, color};
-pub use font::FontRenderer;
-pub use double_buffer::DoubleBuffer;
-pub use cursor::MouseCursor;
-pub use window::{Window, WindowManager, WindowId, WindowState};
-pub use widgets::{Button, Label, TextBox, SimplePanel, WidgetState, WidgetEvent, WidgetId};
+#[cfg(any(test, feature = "unit-test"))]
+pub mod tests;
+
+pub use framebuffer::{ClipRect, Framebuffer, FramebufferDevice, color};
+#[cfg(any(test, feature = "unit-test"))]
+pub use framebuffer::MemFramebuffer;
+pub use font::{FontRenderer, FontStyle, PsfError};
+pub use double_buffer::{DoubleBuffer, DoubleBufferError};
+pub use surface::Surface;
+pub use cursor::{CursorShadow, MouseCursor, PointerAccel, PointerMode};
+pub use window::{Window, WindowManager, WindowId, WindowState, WmError, TitleButton, WindowHandler, ResizeEdge, WindowEvent};
+pub use layout::{GridLayout, Layout};
+pub use widgets::{Button, Label, TextBox, RadioButton, ScrollBar, SimplePanel, WidgetState, WidgetEvent, WidgetId, WidgetRef, CharFilter, InputRejected, parse_mnemonic};
@@ -0,0 +1,131 @@
+//! 离屏渲染目标
+//!
+//! `Surface` 拥有自己的像素缓冲区，实现完整的 `Framebuffer` 绘图接口。
+//! 控件、窗口、工具提示等可以先渲染到 Surface，再通过 `blit_to()`
+//! 合成到屏幕（或另一个 Surface），并可指定整体透明度。
+
+use core::cell::{Cell, RefCell};
+use std::vec;
+use std::vec::Vec;
+use crate::font::FontRenderer;
+use crate::framebuffer::{color, ClipRect, Framebuffer};
+
+/// 离屏表面
+pub struct Surface {
+    pixels: RefCell<Vec<u32>>,
+    width: u32,
+    height: u32,
+    /// 合成时的整体透明度（255 = 不透明，0 = 完全透明）
+    alpha: u8,
+    /// 裁剪矩形
+    clip: Cell<Option<ClipRect>>,
+}
+
+/// 按 `alpha` 混合两个 xRGB 颜色，结果不透明
+#[inline]
+pub fn blend(src: u32, dst: u32, alpha: u8) -> u32 {
+    let a = alpha as u32;
+    let mix = |shift: u32| {
+        let s = (src >> shift) & 0xFF;
+        let d = (dst >> shift) & 0xFF;
+        ((s * a + d * (255 - a)) / 255) << shift
+    };
+    0xFF00_0000 | mix(16) | mix(8) | mix(0)
+}
+
+impl Surface {
+    /// 创建指定大小的表面，初始为透明黑色
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            pixels: RefCell::new(vec![color::TRANSPARENT; (width * height) as usize]),
+            width,
+            height,
+            alpha: 255,
+            clip: Cell::new(None),
+        }
+    }
+
+    /// 获取像素颜色，越界返回 0
+    pub fn get_pixel(&self, x: u32, y: u32) -> u32 {
+        if x >= self.width || y >= self.height {
+            return 0;
+        }
+        self.pixels.borrow()[(y * self.width + x) as usize]
+    }
+
+    /// 合成透明度
+    #[inline]
+    pub fn alpha(&self) -> u8 {
+        self.alpha
+    }
+
+    /// 设置合成透明度
+    pub fn set_alpha(&mut self, alpha: u8) {
+        self.alpha = alpha;
+    }
+
+    /// 使用字体绘制文本
+    pub fn draw_text(&self, font: &FontRenderer, x: u32, y: u32, text: &str, color: u32) {
+        font.draw_string(self, x, y, text, color);
+    }
+
+    /// 将表面合成到 `fb` 的 (x, y) 处
+    ///
+    /// 不透明时直接复制像素；半透明时与目标像素混合；超出目标的部分被裁剪
+    pub fn blit_to<F: Framebuffer>(&self, fb: &F, x: u32, y: u32) {
+        if self.alpha == 0 {
+            return;
+        }
+
+        let pixels = self.pixels.borrow();
+        let w = self.width.min(fb.width().saturating_sub(x));
+        let h = self.height.min(fb.height().saturating_sub(y));
+        for py in 0..h {
+            for px in 0..w {
+                let src = pixels[(py * self.width + px) as usize];
+                let out = if self.alpha == 255 {
+                    src
+                } else {
+                    blend(src, fb.get_pixel(x + px, y + py), self.alpha)
+                };
+                fb.put_pixel(x + px, y + py, out);
+            }
+        }
+    }
+}
+
+impl Framebuffer for Surface {
+    fn put_pixel(&self, x: u32, y: u32, color: u32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        if self.clip.get().is_some_and(|c| !c.contains(x, y)) {
+            return;
+        }
+        self.pixels.borrow_mut()[(y * self.width + x) as usize] = color;
+    }
+
+    fn clip(&self) -> Option<ClipRect> {
+        self.clip.get()
+    }
+
+    fn set_clip(&self, x: u32, y: u32, width: u32, height: u32) {
+        self.clip.set(Some(ClipRect::new(x, y, width, height)));
+    }
+
+    fn clear_clip(&self) {
+        self.clip.set(None);
+    }
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> u32 {
+        self.get_pixel(x, y)
+    }
+}
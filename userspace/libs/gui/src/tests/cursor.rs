@@ -0,0 +1,149 @@
+//! 测试：鼠标光标
+//!
+//! 测试内容：
+//! 1. 启用阴影时偏移处的像素被混合，禁用后不受影响
+//! 2. 移动超出边界时被限制，更新边界后重新限制，热点（箭头尖端）始终在屏幕内
+//! 3. 相对模式按灵敏度和加速累加位移，绝对模式线性映射设备坐标
+//! 4. 自定义位图光标按色键透明绘制，热点决定位图位置
+//! 5. 绘制前保存、之后恢复光标下方像素，framebuffer 与绘制前完全相同
+
+use crate::cursor::{cursor_color, MouseCursor, PointerAccel, PointerMode, ARROW_HOTSPOT};
+use crate::framebuffer::{color, Framebuffer, MemFramebuffer};
+
+pub fn test_cursor() {
+    println!("test: ===== Testing mouse cursor =====");
+
+    // 测试 1: 光标阴影
+    println!("test: 1. Testing cursor shadow...");
+    let mut cursor = MouseCursor::new(40, 40);
+    // 热点在位图 (15, 0)，位图左上角落在 (10, 10)
+    cursor.set_position(25, 10);
+    assert_eq!(cursor.shadow(), None, "Shadow is off by default");
+    // 遮罩第 0 行点亮最右两列 (14, 15)，阴影偏移 2 后落在 (26..=27, 12)，光标本身不覆盖
+    let fb = MemFramebuffer::new(40, 40);
+    fb.clear(color::WHITE);
+    cursor.draw(&fb);
+    assert_eq!(fb.get_pixel(27, 12), color::WHITE, "No shadow when disabled");
+    let shadow = color::with_alpha(color::BLACK, 0x80);
+    cursor.set_shadow(true, 2, shadow);
+    let fb = MemFramebuffer::new(40, 40);
+    fb.clear(color::WHITE);
+    cursor.draw(&fb);
+    assert_eq!(fb.get_pixel(27, 12), color::over(shadow, color::WHITE), "Shadow pixel blended");
+    assert_eq!(fb.get_pixel(24, 14), cursor_color::BLACK, "Cursor drawn over its shadow");
+    assert_eq!(fb.get_pixel(24, 10), cursor_color::WHITE, "Cursor outline unaffected");
+    cursor.set_position(39, 39);
+    let fb = MemFramebuffer::new(40, 40);
+    cursor.draw(&fb);
+    cursor.set_shadow(false, 2, cursor_color::SHADOW);
+    assert_eq!(cursor.shadow(), None);
+    println!("test:    SUCCESS - shadow blended only when enabled");
+
+    // 测试 2: 边界与热点
+    println!("test: 2. Testing cursor bounds and hotspot...");
+    let mut cursor = MouseCursor::new(100, 80);
+    assert_eq!(cursor.hotspot(), ARROW_HOTSPOT);
+    cursor.set_position(95, 75);
+    cursor.move_by(50, 50);
+    assert_eq!((cursor.x, cursor.y), (99, 79), "Moving past the edge clamps");
+    cursor.move_by(-200, -200);
+    assert_eq!((cursor.x, cursor.y), (0, 0));
+    cursor.set_position(90, 70);
+    cursor.set_bounds(64, 48);
+    assert_eq!((cursor.x, cursor.y), (63, 47), "New bounds re-clamp the cursor");
+    cursor.move_by(10, 0);
+    assert_eq!(cursor.x, 63, "Moves clamp to the current bounds");
+    cursor.set_bounds(200, 150);
+    cursor.move_by(10, 0);
+    assert_eq!(cursor.x, 73, "Larger bounds free the cursor");
+    let fb = MemFramebuffer::new(64, 48);
+    let mut cursor = MouseCursor::new(64, 48);
+    cursor.set_position(63, 0);
+    cursor.draw(&fb);
+    assert_eq!(fb.get_pixel(63, 0), cursor_color::BLACK, "Tip drawn at the right edge");
+    let fb = MemFramebuffer::new(64, 48);
+    cursor.set_position(0, 0);
+    cursor.draw(&fb);
+    assert_eq!(fb.get_pixel(0, 0), cursor_color::BLACK, "Tip stays visible at the left edge");
+    cursor.set_hotspot(20, 20);
+    assert_eq!(cursor.hotspot(), (15, 15), "Hotspot limited to the bitmap");
+    println!("test:    SUCCESS - cursor clamped to bounds");
+
+    // 测试 3: 相对/绝对模式
+    println!("test: 3. Testing relative and absolute pointer modes...");
+    let mut cursor = MouseCursor::new(1000, 800);
+    cursor.set_position(100, 100);
+    assert_eq!(cursor.mode(), PointerMode::Relative);
+    cursor.handle_pointer(3, -2);
+    assert_eq!((cursor.x, cursor.y), (103, 98), "Default curve passes deltas through");
+    cursor.set_acceleration(PointerAccel { sensitivity: 0.5, threshold: 4, factor: 3.0 });
+    cursor.handle_pointer(1, 0);
+    assert_eq!(cursor.x, 103, "Half a pixel is kept as remainder");
+    cursor.handle_pointer(1, 0);
+    assert_eq!(cursor.x, 104, "Remainders accumulate");
+    cursor.handle_pointer(10, 0);
+    assert_eq!(cursor.x, 104 + 15, "Fast motion is accelerated: 10 * 0.5 * 3");
+    cursor.handle_pointer(4, 0);
+    assert_eq!(cursor.x, 119 + 2, "At the threshold only the sensitivity applies");
+    cursor.set_mode(PointerMode::Absolute);
+    cursor.handle_pointer(0, 0);
+    assert_eq!((cursor.x, cursor.y), (0, 0));
+    cursor.handle_pointer(0x7FFF, 0x7FFF);
+    assert_eq!((cursor.x, cursor.y), (999, 799), "Device maximum maps to the last pixel");
+    cursor.set_absolute_range(100, 100);
+    cursor.handle_pointer(50, 25);
+    assert_eq!((cursor.x, cursor.y), (499, 199), "Coordinates scale linearly");
+    cursor.handle_pointer(500, -5);
+    assert_eq!((cursor.x, cursor.y), (999, 0), "Out-of-range device values clamp");
+    println!("test:    SUCCESS - pointer modes map input correctly");
+
+    // 测试 4: 自定义位图光标
+    println!("test: 4. Testing custom bitmap cursor...");
+    let mut cursor = MouseCursor::new(40, 40);
+    // 3x3 的十字：中心和四臂不透明，四角透明
+    let k = cursor_color::KEY;
+    let r = color::RED;
+    cursor.set_bitmap(3, 3, &[k, r, k, r, r, r, k, r, k], 1, 1);
+    assert_eq!(cursor.size(), (3, 3));
+    assert_eq!(cursor.hotspot(), (1, 1));
+    cursor.set_position(10, 10);
+    let fb = MemFramebuffer::new(40, 40);
+    fb.clear(color::WHITE);
+    cursor.draw(&fb);
+    assert_eq!(fb.get_pixel(10, 10), color::RED, "Hotspot pixel lands on the pointer position");
+    assert_eq!(fb.get_pixel(10, 9), color::RED, "Arm drawn");
+    assert_eq!(fb.get_pixel(9, 9), color::WHITE, "Keyed corner is transparent");
+    assert_eq!(fb.count_color(color::RED), 5, "Only the opaque pixels are drawn");
+    cursor.set_hotspot(10, 10);
+    assert_eq!(cursor.hotspot(), (2, 2), "Hotspot limited to the custom bitmap");
+    cursor.reset_bitmap();
+    assert_eq!((cursor.size(), cursor.hotspot()), ((16, 16), ARROW_HOTSPOT), "Reset restores the arrow");
+    println!("test:    SUCCESS - bitmap cursor drawn with color key");
+
+    // 测试 5: 保存/恢复光标下方像素
+    println!("test: 5. Testing cursor save/restore under...");
+    let mut cursor = MouseCursor::new(40, 40);
+    cursor.set_shadow(true, 2, cursor_color::SHADOW);
+    let fb = MemFramebuffer::new(40, 40);
+    for y in 0..40 {
+        for x in 0..40 {
+            fb.put_pixel(x, y, color::rgb(x as u8 * 6, y as u8 * 6, 0x40));
+        }
+    }
+    let before = fb.pixels();
+    assert_eq!(cursor.restore_under(&fb), None, "Nothing to restore before a save");
+    // 靠近右下角，光标和阴影部分超出屏幕
+    for (x, y) in [(20, 20), (39, 35)] {
+        cursor.set_position(x, y);
+        cursor.save_under(&fb);
+        cursor.draw(&fb);
+        assert_ne!(fb.pixels(), before, "Cursor changes the framebuffer");
+        let rect = cursor.restore_under(&fb).expect("saved region");
+        assert_eq!(Some(rect), cursor.bounds(&fb), "Restored region is the cursor bounds");
+        assert!(fb.pixels() == before, "Restore leaves the framebuffer identical");
+    }
+    assert_eq!(cursor.restore_under(&fb), None, "Restore consumes the saved pixels");
+    println!("test:    SUCCESS - save/restore around a draw is lossless");
+
+    println!("test: ===== Mouse Cursor Testing Completed =====");
+}
@@ -0,0 +1,284 @@
+//! 测试：字体渲染
+//!
+//! 测试内容：
+//! 1. 字符位图按行从高位到低位绘制
+//! 2. 不可打印字符不绘制
+//! 3. measure_text 按字符宽度计算
+//! 4. 主字体缺失的码点从后备字体绘制
+//! 5. 测量使用后备字体的步进宽度，都缺失时绘制 .notdef
+//! 6. 合成粗体加宽字形并增加步进
+//! 7. 合成斜体错切：上方各行右移，基线行不动
+//! 8. 下划线在字形单元最后一行画线
+//! 9. 文本宽度缓存命中、字形表改变后失效，等宽字体不占用缓存
+//! 10. 制表符前进到下一个制表位，测量计入制表位宽度
+//! 11. 按右边界截断文本，截断时可绘制省略号
+//! 12. 从 PSF2/PSF1 数据加载字体并使用文件头中的字形尺寸，解析失败回退到 8x8
+//! 13. UTF-8 文本按码点绘制和测量，缺失字形绘制 .notdef，截断按字符计数
+//! 14. 自动换行：超出宽度的文本按空格换成多行，`\n` 强制换行
+//! 15. 整数缩放：度量按倍数放大，每个位图像素绘制为方块
+
+use crate::font::{FontRenderer, FontStyle, PsfError};
+use std::vec;
+use crate::framebuffer::{color, MemFramebuffer};
+
+pub fn test_font() {
+    println!("test: ===== Testing font rendering =====");
+
+    let font = FontRenderer::new_8x8();
+
+    // 测试 1: 字符位图
+    println!("test: 1. Testing glyph bitmap...");
+    let fb = MemFramebuffer::new(16, 8);
+    font.draw_char(&fb, 0, 0, b'I', color::WHITE);
+    // 'I' 第一行 0x1E = 0b0001_1110
+    assert_eq!(fb.get_pixel(2, 0), color::BLACK);
+    assert_eq!(fb.get_pixel(3, 0), color::WHITE);
+    assert_eq!(fb.get_pixel(6, 0), color::WHITE);
+    assert_eq!(fb.get_pixel(7, 0), color::BLACK);
+    assert_eq!(fb.count_color(color::WHITE), 18, "'I' glyph has 18 lit pixels");
+    println!("test:    SUCCESS - glyph pixels match bitmap");
+
+    // 测试 2: 不可打印字符
+    println!("test: 2. Testing non-printable characters...");
+    let fb = MemFramebuffer::new(8, 8);
+    font.draw_char(&fb, 0, 0, 0x07, color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 0);
+    println!("test:    SUCCESS - nothing drawn");
+
+    // 测试 3: 文本宽度
+    println!("test: 3. Testing measure_text...");
+    assert_eq!(font.measure_text("abc"), 24);
+    assert_eq!(font.measure_text("ab\ncdef"), 16, "Width stops at newline");
+    println!("test:    SUCCESS - text measured");
+
+    // 后备字体：12x2 的 '{'，两行均为最左 12 像素点亮
+    let mut chain = FontRenderer::new_8x8();
+    chain.add_fallback(FontRenderer::from_bitmap(12, 2, b'{' as u32, vec![0xFF, 0xF0, 0xFF, 0xF0]));
+
+    // 测试 4: 后备字体绘制
+    println!("test: 4. Testing fallback glyph rendering...");
+    assert!(!FontRenderer::new_8x8().has_glyph(b'{' as u32), "Builtin font lacks '{{'");
+    assert!(chain.has_glyph(b'{' as u32));
+    let fb = MemFramebuffer::new(16, 8);
+    chain.draw_char(&fb, 0, 0, b'{', color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 24, "12x2 fallback glyph drawn");
+    assert_eq!(fb.get_pixel(11, 1), color::WHITE);
+    assert_eq!(fb.get_pixel(12, 0), color::BLACK);
+    let fb = MemFramebuffer::new(16, 8);
+    chain.draw_char(&fb, 0, 0, b'I', color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 18, "Primary font still preferred");
+    println!("test:    SUCCESS - fallback glyph used");
+
+    // 测试 5: 后备字体步进与 .notdef
+    println!("test: 5. Testing fallback advance and .notdef...");
+    assert_eq!(chain.advance(b'{' as u32), 12);
+    assert_eq!(chain.measure_text("I{I"), 8 + 12 + 8, "Measurement uses the fallback advance");
+    let fb = MemFramebuffer::new(40, 8);
+    chain.draw_string(&fb, 0, 0, "{I", color::WHITE);
+    assert_eq!(fb.get_pixel(12 + 3, 0), color::WHITE, "Next glyph starts after the 12px advance");
+    let fb = MemFramebuffer::new(8, 8);
+    chain.draw_char(&fb, 0, 0, b'~', color::WHITE);
+    assert_eq!(fb.get_pixel(1, 1), color::WHITE, ".notdef box drawn when no font has the glyph");
+    assert_eq!(fb.get_pixel(3, 3), color::BLACK);
+    assert_eq!(fb.count_color(color::WHITE), 20);
+    println!("test:    SUCCESS - fallback advance used");
+
+    // 测试 6: 粗体
+    println!("test: 6. Testing synthetic bold...");
+    let fb = MemFramebuffer::new(16, 8);
+    font.draw_codepoint_styled(&fb, 0, 0, b'I' as u32, color::WHITE, FontStyle::BOLD);
+    // 'I' 第一行 0x1E：加粗后 3..=7 都点亮
+    assert_eq!(fb.get_pixel(3, 0), color::WHITE);
+    assert_eq!(fb.get_pixel(7, 0), color::WHITE, "Bold extends one pixel to the right");
+    assert_eq!(fb.get_pixel(2, 0), color::BLACK);
+    assert!(fb.count_color(color::WHITE) > 18, "Bold glyph has more lit pixels");
+    assert_eq!(font.advance_styled(b'I' as u32, FontStyle::BOLD), 9);
+    assert_eq!(font.measure_text_styled("II", FontStyle::BOLD), 18);
+    assert_eq!(font.measure_text_styled("II", FontStyle::REGULAR), font.measure_text("II"));
+    println!("test:    SUCCESS - bold widens glyphs");
+
+    // 测试 7: 斜体
+    println!("test: 7. Testing synthetic italic...");
+    let plain = MemFramebuffer::new(16, 8);
+    font.draw_char(&plain, 0, 0, b'I', color::WHITE);
+    let fb = MemFramebuffer::new(16, 8);
+    font.draw_codepoint_styled(&fb, 0, 0, b'I' as u32, color::WHITE, FontStyle::ITALIC);
+    // 第 0 行右移 (8 - 1) / 3 = 2 像素
+    assert_eq!(fb.get_pixel(3, 0), color::BLACK);
+    assert_eq!(fb.get_pixel(5, 0), color::WHITE);
+    assert_eq!(fb.get_pixel(8, 0), color::WHITE, "Top row shifted right by the slant");
+    for x in 0..16 {
+        assert_eq!(fb.get_pixel(x, 7), plain.get_pixel(x, 7), "Baseline row is not sheared");
+    }
+    assert_eq!(fb.count_color(color::WHITE), 18, "Shearing keeps the pixel count");
+    assert_eq!(font.measure_text_styled("II", FontStyle::ITALIC), 16 + 2, "Overhang added once");
+    assert_eq!(font.measure_text_styled("", FontStyle::ITALIC), 0);
+    println!("test:    SUCCESS - italic shears rows");
+
+    // 测试 8: 下划线
+    println!("test: 8. Testing underline...");
+    let fb = MemFramebuffer::new(24, 8);
+    font.draw_string_styled(&fb, 0, 0, "II", color::WHITE, FontStyle::UNDERLINE);
+    for x in 0..16 {
+        assert_eq!(fb.get_pixel(x, 7), color::WHITE, "Underline spans the text");
+    }
+    assert_eq!(fb.get_pixel(16, 7), color::BLACK, "Underline stops at the text end");
+    assert_eq!(font.measure_text_styled("II", FontStyle::UNDERLINE), 16, "Underline adds no width");
+    println!("test:    SUCCESS - underline drawn under text");
+
+    // 测试 9: 文本宽度缓存
+    println!("test: 9. Testing text metrics cache...");
+    assert!(font.is_monospace());
+    assert_eq!(font.measure_text("Hello"), 40);
+    assert_eq!(font.metrics_cache_len(), 0, "Monospace fonts bypass the cache");
+    assert!(!chain.is_monospace(), "12px fallback makes the chain proportional");
+    chain.clear_metrics_cache();
+    let hits = chain.metrics_cache_hits();
+    let first = chain.measure_text("I{I");
+    assert_eq!(chain.metrics_cache_len(), 1);
+    assert_eq!(chain.metrics_cache_hits(), hits, "First measurement is a miss");
+    assert_eq!(chain.measure_text("I{I"), first);
+    assert_eq!(chain.metrics_cache_hits(), hits + 1, "Second measurement comes from the cache");
+    assert_eq!(chain.measure_text("I{I\nignored"), first, "Only the first line is measured");
+    assert_ne!(
+        chain.measure_text_styled("I{I", FontStyle::BOLD),
+        first,
+        "Styles are cached separately"
+    );
+    chain.add_fallback(FontRenderer::from_bitmap(16, 1, b'|' as u32, vec![0xFF, 0xFF]));
+    assert_eq!(chain.metrics_cache_len(), 0, "Changing the glyph tables invalidates the cache");
+    assert_eq!(chain.measure_text("I|I"), 8 + 16 + 8);
+    chain.set_metrics_cache_capacity(1);
+    assert_eq!(chain.metrics_cache_len(), 1);
+    chain.measure_text("{{");
+    assert_eq!(chain.metrics_cache_len(), 1, "Least recently used entry evicted");
+    let hits = chain.metrics_cache_hits();
+    assert_eq!(chain.measure_text("I|I"), 32);
+    assert_eq!(chain.metrics_cache_hits(), hits, "Evicted entry is recomputed");
+    println!("test:    SUCCESS - metrics cached and invalidated");
+
+    // 测试 10: 制表位
+    println!("test: 10. Testing tab stops...");
+    let mut tabbed = FontRenderer::new_8x8();
+    assert_eq!(tabbed.tab_width(), 4);
+    assert_eq!(tabbed.measure_text("I\tI"), 32 + 8, "Tab advances to the 4-character stop");
+    assert_eq!(tabbed.measure_text("IIII\t"), 64, "Tab at a stop moves to the next one");
+    let fb = MemFramebuffer::new(64, 8);
+    tabbed.draw_string(&fb, 10, 0, "I\tI", color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 2 * 18, "Tab draws no glyph");
+    assert_eq!(fb.get_pixel(10 + 32 + 3, 0), color::WHITE, "Stops are relative to the line start");
+    tabbed.set_tab_width(2);
+    assert_eq!(tabbed.measure_text("\t"), 16);
+    assert_eq!(tabbed.measure_text("IIIII\tI"), 48 + 8);
+    assert_eq!(tabbed.next_tab_stop(17), 32);
+    chain.clear_metrics_cache();
+    let before = chain.measure_text("{\t");
+    chain.set_tab_width(8);
+    assert_eq!(chain.metrics_cache_len(), 0, "Changing the tab width invalidates cached widths");
+    assert_ne!(chain.measure_text("{\t"), before);
+    println!("test:    SUCCESS - tabs expand to stops");
+
+    // 测试 11: 右边界截断
+    println!("test: 11. Testing clipped text drawing...");
+    let fb = MemFramebuffer::new(64, 8);
+    assert_eq!(font.draw_string_clipped(&fb, 0, 0, "IIIII", color::WHITE, 20), 2, "Third glyph would pass max_x");
+    assert_eq!(fb.count_color(color::WHITE), 2 * 18);
+    let fb = MemFramebuffer::new(64, 8);
+    assert_eq!(font.draw_string_clipped(&fb, 4, 0, "IIIII", color::WHITE, 28), 3, "Glyph ending on max_x fits");
+    assert_eq!(font.draw_string_clipped(&fb, 0, 0, "II\nII", color::WHITE, 64), 2, "Stops at a newline");
+    let fb = MemFramebuffer::new(100, 8);
+    let drawn = font.draw_string_ellipsis(&fb, 0, 0, "IIIIIIIIIIII", color::WHITE, 64);
+    assert_eq!(drawn, 5, "Room is left for the ellipsis");
+    let dots = MemFramebuffer::new(100, 8);
+    font.draw_string(&dots, 40, 0, "...", color::WHITE);
+    for x in 40..100 {
+        for y in 0..8 {
+            assert_eq!(fb.get_pixel(x, y), dots.get_pixel(x, y), "Ellipsis follows the truncated text");
+        }
+    }
+    let fb = MemFramebuffer::new(100, 8);
+    assert_eq!(font.draw_string_ellipsis(&fb, 0, 0, "III", color::WHITE, 64), 3, "Fitting text has no ellipsis");
+    assert_eq!(fb.count_color(color::WHITE), 3 * 18);
+    println!("test:    SUCCESS - text truncated at the boundary");
+
+    // 测试 12: PSF 字体
+    println!("test: 12. Testing PSF font loading...");
+    let psf = psf2_blob(10, 12, 0x42);
+    let big = FontRenderer::try_from_psf(&psf).expect("synthetic PSF2 should parse");
+    assert_eq!((big.width(), big.height()), (10, 12), "Glyph size comes from the header");
+    assert_eq!(big.measure_text("AB"), 20);
+    let fb = MemFramebuffer::new(32, 16);
+    big.draw_char(&fb, 0, 0, b'A', color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 12, "Glyph 'A' is a 12-row left edge");
+    assert_eq!(fb.get_pixel(0, 11), color::WHITE, "Rows below 8 are drawn");
+    assert!(!big.has_glyph(0x42), "Glyphs past the count are missing");
+    let mut psf1 = vec![0x36, 0x04, 0x00, 16];
+    psf1.resize(4 + 256 * 16, 0);
+    assert_eq!(FontRenderer::try_from_psf(&psf1).map(|f| f.height()), Ok(16), "PSF1 is 8 pixels wide");
+    assert_eq!(FontRenderer::try_from_psf(&psf[..40]).err(), Some(PsfError::Truncated));
+    assert_eq!(FontRenderer::try_from_psf(b"not a font").err(), Some(PsfError::BadMagic));
+    let fallback = FontRenderer::from_psf(b"");
+    assert_eq!((fallback.width(), fallback.height()), (8, 8), "Bad data falls back to 8x8");
+    println!("test:    SUCCESS - PSF glyph size used");
+
+    // 测试 13: UTF-8 文本
+    println!("test: 13. Testing UTF-8 text...");
+    let mut font = FontRenderer::new_8x8();
+    font.add_fallback(FontRenderer::from_bitmap(8, 8, 0xE9, vec![0xFF; 8]));
+    assert_eq!(font.measure_text_utf8("\u{4E2D}a"), 16, "One column per codepoint");
+    assert_eq!(font.measure_text("\u{4E2D}a"), 32, "The byte path counts UTF-8 bytes");
+    assert_eq!(font.measure_text_utf8("abc"), font.measure_text("abc"), "ASCII is unchanged");
+    let fb = MemFramebuffer::new(32, 8);
+    font.draw_string_utf8(&fb, 0, 0, "\u{E9}", color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 64, "Latin-1 glyph comes from the fallback font");
+    let fb = MemFramebuffer::new(32, 8);
+    font.draw_string_utf8(&fb, 0, 0, "\u{4E2D}", color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 20, "A missing CJK glyph draws one .notdef box");
+    let fb = MemFramebuffer::new(64, 8);
+    assert_eq!(font.draw_string_clipped(&fb, 0, 0, "\u{4E2D}\u{4E2D}\u{4E2D}", color::WHITE, 16), 2,
+        "Clipping counts characters, not bytes");
+    println!("test:    SUCCESS - UTF-8 decoded");
+
+    // 测试 14: 自动换行
+    println!("test: 14. Testing word wrapping...");
+    let fb = MemFramebuffer::new(80, 40);
+    let height = font.draw_string_wrapped(&fb, 0, 0, 80, 10, "the quick brown fox", color::WHITE);
+    assert_eq!(height, 20, "19 characters at 8px do not fit in 80px");
+    assert!((0..80).any(|x| fb.get_pixel(x, 12) == color::WHITE), "Second line is drawn at line_height");
+    assert!((0..80).all(|x| fb.get_pixel(x, 30) == color::BLACK), "Nothing drawn below the last line");
+    assert_eq!(font.draw_string_wrapped(&fb, 0, 0, 80, 10, "a\nb", color::WHITE), 20, "Newline forces a break");
+    assert_eq!(font.draw_string_wrapped(&fb, 0, 0, 80, 10, "short", color::WHITE), 10, "Short text stays on one line");
+    println!("test:    SUCCESS - long text wrapped");
+
+    // 测试 15: 整数缩放
+    println!("test: 15. Testing integer scaling...");
+    let scaled = FontRenderer::new_8x8().with_scale(2);
+    assert_eq!(scaled.height(), 2 * font.height(), "Scale 2 doubles height()");
+    assert_eq!(scaled.measure_text("Hello"), 2 * font.measure_text("Hello"), "Scale 2 doubles measure_text()");
+    let fb = MemFramebuffer::new(8, 8);
+    font.draw_char(&fb, 0, 0, b'I', color::WHITE);
+    let lit = fb.count_color(color::WHITE);
+    let fb = MemFramebuffer::new(16, 16);
+    scaled.draw_char(&fb, 0, 0, b'I', color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 4 * lit, "Each source pixel becomes a 2x2 block");
+    assert_eq!(FontRenderer::new_8x8().with_scale(0).scale(), 1, "Scale is at least 1");
+    println!("test:    SUCCESS - glyphs scaled");
+
+    println!("test: ===== Font Rendering Testing Completed =====");
+}
+
+/// 构造 PSF2 数据：`count` 个字形，'A' 的每行只有最左一个像素
+fn psf2_blob(width: u32, height: u32, count: u32) -> Vec<u8> {
+    let row_bytes = width.div_ceil(8);
+    let glyph_size = row_bytes * height;
+    let mut blob = vec![0x72, 0xB5, 0x4A, 0x86];
+    for field in [0, 32, 0, count, glyph_size, height, width] {
+        blob.extend_from_slice(&u32::to_le_bytes(field));
+    }
+    blob.resize(32 + (count * glyph_size) as usize, 0);
+    let a = 32 + (b'A' as u32 * glyph_size) as usize;
+    for row in 0..height as usize {
+        blob[a + row * row_bytes as usize] = 0x80;
+    }
+    blob
+}
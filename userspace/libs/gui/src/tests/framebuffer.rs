@@ -0,0 +1,331 @@
+//! 测试：内存帧缓冲区绘图
+//!
+//! 测试内容：
+//! 1. fill_rect 裁剪到屏幕范围
+//! 2. blit_rect 只绘制边框
+//! 3. draw_line 覆盖两个端点
+//! 4. clear 填充整个缓冲区
+//! 5. rgb/rgba 打包颜色分量
+//! 6. lighten/darken 调整分量并饱和
+//! 7. hsv 与 RGB 互相转换
+//! 8. 双缓冲初始化参数校验，未初始化时绘制为空操作
+//! 9. 相同的帧只刷新一次，内容变化后再次刷新
+//! 10. 默认背景色和完全覆盖时跳过清空
+//! 11. 设置裁剪矩形后所有绘制只写入矩形内
+//! 12. 按 alpha 做 source-over 混合，不透明时等同普通填充
+//! 13. 位图复制裁剪到屏幕，颜色键像素被跳过
+//! 14. 区域上移/下移处理重叠行并填充露出的行（通用实现与双缓冲快速路径一致）
+//! 15. 双缓冲只把脏矩形复制到前端，相邻的脏矩形合并
+//! 16. 三缓冲每次刷新切换到不同的后端缓冲区，切换后内容保持最新
+
+use crate::double_buffer::{DoubleBuffer, DoubleBufferError};
+use crate::font::FontRenderer;
+use crate::framebuffer::{color, ClipRect, Framebuffer, MemFramebuffer};
+
+pub fn test_mem_framebuffer() {
+    println!("test: ===== Testing in-memory framebuffer =====");
+
+    // 测试 1: fill_rect 裁剪
+    println!("test: 1. Testing fill_rect clipping...");
+    let fb = MemFramebuffer::new(10, 10);
+    fb.fill_rect(8, 8, 5, 5, color::RED);
+    assert_eq!(fb.count_color(color::RED), 4, "Rect must be clipped to the screen");
+    assert_eq!(fb.get_pixel(9, 9), color::RED);
+    assert_eq!(fb.get_pixel(7, 7), color::BLACK);
+    assert_eq!(fb.get_pixel(10, 0), 0, "Out-of-bounds read returns 0");
+    println!("test:    SUCCESS - fill_rect clipped");
+
+    // 测试 2: blit_rect 边框
+    println!("test: 2. Testing blit_rect draws only the border...");
+    let fb = MemFramebuffer::new(10, 10);
+    fb.blit_rect(0, 0, 10, 10, color::WHITE, 1);
+    assert_eq!(fb.count_color(color::WHITE), 36, "10x10 border is 36 pixels");
+    assert_eq!(fb.get_pixel(5, 5), color::BLACK, "Interior untouched");
+    println!("test:    SUCCESS - border only");
+
+    // 测试 3: draw_line
+    println!("test: 3. Testing draw_line endpoints...");
+    let fb = MemFramebuffer::new(10, 10);
+    fb.draw_line(0, 0, 9, 9, color::GREEN);
+    assert_eq!(fb.count_color(color::GREEN), 10, "Diagonal covers one pixel per row");
+    assert_eq!(fb.get_pixel(0, 0), color::GREEN);
+    assert_eq!(fb.get_pixel(9, 9), color::GREEN);
+    println!("test:    SUCCESS - diagonal drawn");
+
+    // 测试 4: clear
+    println!("test: 4. Testing clear...");
+    fb.clear(color::BLUE);
+    assert_eq!(fb.count_color(color::BLUE), 100);
+    println!("test:    SUCCESS - buffer cleared");
+
+    // 测试 5: rgb/rgba 打包
+    println!("test: 5. Testing rgb/rgba packing...");
+    assert_eq!(color::rgb(0x12, 0x34, 0x56), 0xFF123456);
+    assert_eq!(color::rgba(0x12, 0x34, 0x56, 0x78), 0x78123456);
+    assert_eq!(color::rgb(0xFF, 0xA5, 0x00), color::ORANGE);
+    assert_eq!(color::components(0x78123456), (0x12, 0x34, 0x56, 0x78));
+    println!("test:    SUCCESS - components packed as ARGB");
+
+    // 测试 6: lighten/darken
+    println!("test: 6. Testing lighten/darken with clamping...");
+    assert_eq!(color::lighten(color::GRAY, 0x20), 0xFFA0A0A0);
+    assert_eq!(color::darken(color::GRAY, 0x20), 0xFF606060);
+    assert_eq!(color::lighten(color::ORANGE, 0x80), 0xFFFFFF80, "Channels clamp at 255");
+    assert_eq!(color::darken(color::ORANGE, 0xB0), 0xFF4F0000, "Channels clamp at 0");
+    assert_eq!(color::darken(0x80FFFFFF, 0x10) >> 24, 0x80, "Alpha is preserved");
+    println!("test:    SUCCESS - shades computed");
+
+    // 测试 7: HSV 转换
+    println!("test: 7. Testing hsv conversion...");
+    assert_eq!(color::hsv(0, 255, 255), color::RED);
+    assert_eq!(color::hsv(120, 255, 255), color::GREEN);
+    assert_eq!(color::hsv(240, 255, 128), color::DARK_BLUE);
+    assert_eq!(color::hsv(0, 0, 128), color::GRAY, "Zero saturation is gray");
+    assert_eq!(color::to_hsv(color::ORANGE), (39, 255, 255));
+    let amber = color::rgb(0xFF, 0x80, 0x00);
+    let (h, s, v) = color::to_hsv(amber);
+    assert_eq!((h, s, v), (30, 255, 255));
+    assert_eq!(color::hsv(h, s, v), amber, "Known color round-trips through HSV");
+    println!("test:    SUCCESS - hsv round-trip");
+
+    // 测试 8: 双缓冲初始化校验
+    println!("test: 8. Testing double buffer init validation...");
+    let mut buffer = DoubleBuffer::new();
+    buffer.put_pixel(0, 0, color::RED);
+    buffer.fill_rect(0, 0, 10, 10, color::RED);
+    buffer.blit_rect(0, 0, 1, 1, color::RED, 3);
+    buffer.draw_line(0, 0, 5, 5, color::RED);
+    buffer.clear(color::RED);
+    buffer.swap_buffers(&MemFramebuffer::new(4, 4));
+    assert_eq!(buffer.get_pixel(0, 0), 0, "Drawing before init is a no-op");
+    assert_eq!(buffer.init(0, 480, 640), Err(DoubleBufferError::ZeroSize));
+    assert_eq!(buffer.init(640, 0, 640), Err(DoubleBufferError::ZeroSize));
+    assert_eq!(buffer.init(640, 480, 320), Err(DoubleBufferError::StrideTooSmall));
+    assert_eq!(buffer.init(u32::MAX, u32::MAX, u32::MAX), Err(DoubleBufferError::AllocFailed));
+    assert!(!buffer.is_initialized(), "Failed init leaves the buffer unusable");
+    assert_eq!(buffer.init(16, 8, 20), Ok(()));
+    assert!(buffer.is_initialized());
+    assert_eq!(buffer.init(16, 8, 20), Err(DoubleBufferError::AlreadyInitialized));
+    buffer.put_pixel(15, 7, color::GREEN);
+    assert_eq!(buffer.get_pixel(15, 7), color::GREEN);
+    assert_eq!(buffer.resize(0, 8, 8), Err(DoubleBufferError::ZeroSize));
+    assert!(!buffer.is_initialized(), "Failed resize leaves the buffer uninitialized");
+    buffer.put_pixel(1, 1, color::GREEN);
+    assert_eq!(buffer.get_pixel(1, 1), 0);
+    println!("test:    SUCCESS - invalid sizes rejected");
+
+    // 测试 9: 跳过未变化的帧
+    println!("test: 9. Testing swap_buffers change detection...");
+    let mut buffer = DoubleBuffer::new();
+    assert!(!buffer.swap_buffers(&MemFramebuffer::new(4, 4)), "Uninitialized buffer never flushes");
+    assert_eq!(buffer.init(8, 8, 8), Ok(()));
+    let screen = MemFramebuffer::new(8, 8);
+    let draw_frame = |b: &DoubleBuffer, c: u32| {
+        b.clear(color::BLUE);
+        b.fill_rect(2, 2, 3, 3, c);
+    };
+    draw_frame(&buffer, color::RED);
+    assert!(buffer.swap_buffers(&screen), "First frame flushes");
+    assert_eq!(screen.get_pixel(3, 3), color::RED);
+    draw_frame(&buffer, color::RED);
+    assert!(!buffer.swap_buffers(&screen), "Identical redraw is skipped");
+    draw_frame(&buffer, color::GREEN);
+    assert!(buffer.swap_buffers(&screen), "Changed frame flushes again");
+    assert_eq!(screen.get_pixel(3, 3), color::GREEN);
+    screen.clear(color::BLACK);
+    assert!(!buffer.swap_buffers(&screen));
+    buffer.invalidate();
+    assert!(buffer.swap_buffers(&screen), "Invalidate forces a flush");
+    assert_eq!(screen.get_pixel(0, 0), color::BLUE);
+    println!("test:    SUCCESS - only changed frames flushed");
+
+    // 测试 10: 背景色与覆盖模式
+    println!("test: 10. Testing clear color and opaque coverage...");
+    let mut buffer = DoubleBuffer::new();
+    assert_eq!(buffer.init(4, 4, 4), Ok(()));
+    assert_eq!(buffer.clear_color(), color::BLACK);
+    buffer.set_clear_color(color::ORANGE);
+    buffer.clear_background();
+    assert_eq!(buffer.get_pixel(3, 3), color::ORANGE, "Default color fills the buffer");
+    buffer.fill_rect(0, 0, 2, 2, color::RED);
+    buffer.set_opaque_coverage(true);
+    assert!(buffer.has_opaque_coverage());
+    buffer.clear_background();
+    buffer.clear(color::WHITE);
+    Framebuffer::clear(&buffer, color::WHITE);
+    assert_eq!(buffer.get_pixel(0, 0), color::RED, "Clear is a no-op when covered");
+    assert_eq!(buffer.get_pixel(3, 3), color::ORANGE);
+    buffer.set_opaque_coverage(false);
+    buffer.clear(color::WHITE);
+    assert_eq!(buffer.get_pixel(0, 0), color::WHITE, "Clear works again without coverage");
+    println!("test:    SUCCESS - coverage skips the clear");
+
+    // 测试 11: 裁剪矩形
+    println!("test: 11. Testing clip rectangle...");
+    let fb = MemFramebuffer::new(10, 10);
+    assert_eq!(fb.clip(), None);
+    fb.set_clip(2, 3, 4, 5);
+    assert_eq!(fb.clip(), Some(ClipRect::new(2, 3, 4, 5)));
+    fb.fill_rect(0, 0, 10, 10, color::RED);
+    assert_eq!(fb.count_color(color::RED), 20, "Full-screen fill touches only the clip");
+    assert_eq!(fb.get_pixel(2, 3), color::RED);
+    assert_eq!(fb.get_pixel(5, 7), color::RED);
+    assert_eq!(fb.get_pixel(1, 3), color::BLACK);
+    assert_eq!(fb.get_pixel(6, 7), color::BLACK);
+    assert_eq!(fb.get_pixel(5, 8), color::BLACK);
+    fb.draw_line(0, 0, 9, 9, color::GREEN);
+    assert_eq!(fb.count_color(color::GREEN), 3, "Line clipped to (3,3)..=(5,5)");
+    fb.blit_rect(0, 0, 10, 10, color::BLUE, 1);
+    assert_eq!(fb.count_color(color::BLUE), 0, "Border outside the clip not drawn");
+    fb.clear(color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 20);
+    let fb = MemFramebuffer::new(16, 8);
+    fb.set_clip(0, 0, 4, 8);
+    FontRenderer::new_8x8().draw_char(&fb, 0, 0, b'I', color::WHITE);
+    assert_eq!(fb.get_pixel(3, 0), color::WHITE);
+    assert_eq!(fb.get_pixel(4, 0), color::BLACK, "Glyph pixels right of the clip dropped");
+    fb.clear_clip();
+    fb.fill_rect(0, 0, 16, 8, color::RED);
+    assert_eq!(fb.count_color(color::RED), 16 * 8, "clear_clip restores full-buffer drawing");
+    let mut buffer = DoubleBuffer::new();
+    assert_eq!(buffer.init(4, 4, 4), Ok(()));
+    buffer.set_clip(1, 1, 2, 2);
+    buffer.clear(color::WHITE);
+    buffer.put_pixel(0, 0, color::RED);
+    assert_eq!(buffer.get_pixel(0, 0), 0, "Double buffer honours the clip");
+    assert_eq!(buffer.get_pixel(1, 1), color::WHITE);
+    println!("test:    SUCCESS - drawing clipped to the rectangle");
+
+    // 测试 12: alpha 混合
+    println!("test: 12. Testing alpha blending...");
+    let half_white = color::with_alpha(color::WHITE, 0x80);
+    assert_eq!(color::over(half_white, color::BLACK), color::rgb(0x80, 0x80, 0x80));
+    assert_eq!(color::over(color::RED, color::BLUE), color::RED, "Opaque source replaces");
+    assert_eq!(color::over(color::TRANSPARENT, color::BLUE), color::BLUE, "Transparent source keeps dst");
+    let fb = MemFramebuffer::new(4, 4);
+    fb.blend_pixel(0, 0, half_white);
+    assert_eq!(fb.get_pixel(0, 0), color::rgb(0x80, 0x80, 0x80));
+    fb.blend_pixel(1, 0, color::with_alpha(color::WHITE, 0));
+    assert_eq!(fb.get_pixel(1, 0), color::BLACK, "Alpha 0 leaves the pixel alone");
+    fb.clear(color::WHITE);
+    fb.fill_rect_blend(0, 0, 2, 4, color::with_alpha(color::RED, 0x80));
+    assert_eq!(fb.get_pixel(1, 3), color::rgb(0xFF, 0x7F, 0x7F), "Half red over white");
+    assert_eq!(fb.get_pixel(2, 0), color::WHITE);
+    fb.fill_rect_blend(0, 0, 4, 4, color::GREEN);
+    assert_eq!(fb.count_color(color::GREEN), 16, "Opaque blend fill matches fill_rect");
+    fb.set_clip(0, 0, 1, 1);
+    fb.fill_rect_blend(0, 0, 4, 4, half_white);
+    assert_ne!(fb.get_pixel(0, 0), color::GREEN);
+    assert_eq!(fb.count_color(color::GREEN), 15, "Blended fill honours the clip");
+    println!("test:    SUCCESS - source-over compositing");
+
+    // 测试 13: 位图复制
+    println!("test: 13. Testing bitmap blit...");
+    let bitmap = [color::RED, color::GREEN, color::BLUE, color::WHITE];
+    let fb = MemFramebuffer::new(4, 4);
+    fb.blit_bitmap(2, 2, 2, 2, &bitmap);
+    assert_eq!(fb.count_color(color::BLACK), 12, "Exactly four pixels written");
+    assert_eq!(fb.get_pixel(2, 2), color::RED);
+    assert_eq!(fb.get_pixel(3, 2), color::GREEN);
+    assert_eq!(fb.get_pixel(2, 3), color::BLUE);
+    assert_eq!(fb.get_pixel(3, 3), color::WHITE);
+    let fb = MemFramebuffer::new(4, 4);
+    fb.blit_bitmap(3, 3, 2, 2, &bitmap);
+    assert_eq!(fb.count_color(color::BLACK), 15, "Bitmap clipped at the corner");
+    assert_eq!(fb.get_pixel(3, 3), color::RED);
+    fb.blit_bitmap(0, 0, 2, 2, &bitmap[..3]);
+    assert_eq!(fb.get_pixel(1, 1), color::BLACK, "Short pixel buffer copies what exists");
+    let fb = MemFramebuffer::new(4, 4);
+    fb.blit_bitmap_keyed(0, 0, 2, 2, &bitmap, color::GREEN);
+    assert_eq!(fb.get_pixel(1, 0), color::BLACK, "Color-keyed pixel skipped");
+    assert_eq!(fb.get_pixel(0, 0), color::RED);
+    assert_eq!(fb.count_color(color::BLACK), 13);
+    println!("test:    SUCCESS - bitmap blitted with clipping");
+
+    // 测试 14: 区域滚动
+    println!("test: 14. Testing region scroll...");
+    let row_color = |row: u32| color::rgb(row as u8 * 10, 0, 0);
+    let fb = MemFramebuffer::new(4, 6);
+    let mut buffer = DoubleBuffer::new();
+    assert_eq!(buffer.init(4, 6, 4), Ok(()));
+    for row in 0..6 {
+        fb.fill_rect(0, row, 4, 1, row_color(row));
+        buffer.fill_rect(0, row, 4, 1, row_color(row));
+    }
+    // 只滚动第 1..5 行、第 1..3 列
+    fb.scroll_rect_up(1, 1, 2, 4, 1, color::BLUE);
+    buffer.scroll_rect_up(1, 1, 2, 4, 1, color::BLUE);
+    assert_eq!(fb.get_pixel(1, 1), row_color(2), "Rows move up by one");
+    assert_eq!(fb.get_pixel(2, 3), row_color(4));
+    assert_eq!(fb.get_pixel(1, 4), color::BLUE, "Exposed bottom row filled");
+    assert_eq!(fb.get_pixel(1, 5), row_color(5), "Rows below the region untouched");
+    assert_eq!(fb.get_pixel(0, 1), row_color(1), "Columns outside the region untouched");
+    assert_eq!(fb.get_pixel(1, 0), row_color(0));
+    fb.scroll_rect_down(0, 0, 4, 6, 2, color::GREEN);
+    buffer.scroll_rect_down(0, 0, 4, 6, 2, color::GREEN);
+    assert_eq!(fb.get_pixel(3, 1), color::GREEN, "Exposed top rows filled");
+    assert_eq!(fb.get_pixel(0, 2), row_color(0), "Rows move down without smearing");
+    assert_eq!(fb.get_pixel(1, 3), row_color(2));
+    assert_eq!(fb.get_pixel(1, 5), row_color(4));
+    for py in 0..6 {
+        for px in 0..4 {
+            assert_eq!(buffer.get_pixel(px, py), fb.get_pixel(px, py), "Fast path matches generic");
+        }
+    }
+    fb.scroll_rect_up(0, 0, 4, 6, 100, color::WHITE);
+    assert_eq!(fb.count_color(color::WHITE), 24, "Scrolling past the height clears the region");
+    println!("test:    SUCCESS - region scrolled in place");
+
+    // 测试 15: 脏矩形
+    println!("test: 15. Testing dirty-rectangle flush...");
+    let mut buffer = DoubleBuffer::new();
+    assert_eq!(buffer.init(8, 8, 8), Ok(()));
+    let screen = MemFramebuffer::new(8, 8);
+    buffer.clear(color::BLUE);
+    assert!(buffer.swap_buffers(&screen), "First frame copies everything");
+    assert!(buffer.dirty_rects().is_empty(), "Flush clears the dirty list");
+    screen.clear(color::WHITE);
+    buffer.fill_rect(1, 1, 2, 2, color::RED);
+    buffer.fill_rect(2, 2, 2, 2, color::RED);
+    assert_eq!(buffer.dirty_rects(), [ClipRect::new(1, 1, 3, 3)], "Overlapping rects merge");
+    assert!(buffer.swap_buffers(&screen));
+    assert_eq!(screen.get_pixel(3, 3), color::RED);
+    assert_eq!(screen.get_pixel(0, 0), color::WHITE, "Clean area is not copied");
+    buffer.put_pixel(6, 6, color::GREEN);
+    assert!(buffer.swap_buffers(&screen));
+    assert_eq!(screen.get_pixel(6, 6), color::GREEN, "Single pixels are tracked");
+    assert_eq!(screen.get_pixel(5, 5), color::WHITE);
+    buffer.mark_dirty(4, 4, 2, 2);
+    assert!(buffer.swap_buffers(&screen), "Explicit marks flush unchanged content");
+    assert_eq!(screen.get_pixel(5, 5), color::BLUE);
+    assert_eq!(screen.get_pixel(0, 0), color::WHITE);
+    buffer.mark_all_dirty();
+    assert!(buffer.swap_buffers(&screen));
+    assert_eq!(screen.get_pixel(0, 0), color::BLUE);
+    assert!(!buffer.swap_buffers(&screen), "Nothing dirty, nothing copied");
+    println!("test:    SUCCESS - only dirty regions flushed");
+
+    // 测试 16: 三缓冲
+    println!("test: 16. Testing triple buffering...");
+    assert_eq!(DoubleBuffer::new().buffer_count(), 1, "Double buffering stays the default");
+    let mut buffer = DoubleBuffer::new_triple();
+    assert_eq!(buffer.buffer_count(), 3);
+    assert_eq!(buffer.init(8, 8, 8), Ok(()));
+    let screen = MemFramebuffer::new(8, 8);
+    let mut backing = Vec::new();
+    for (i, c) in [color::RED, color::GREEN, color::BLUE].into_iter().enumerate() {
+        backing.push(buffer.back_buffer().as_ptr());
+        buffer.fill_rect(i as u32, 0, 1, 1, c);
+        assert!(buffer.swap_buffers(&screen));
+    }
+    assert!(backing[0] != backing[1] && backing[1] != backing[2] && backing[0] != backing[2],
+        "Each swap should draw into a distinct allocation");
+    assert_eq!(buffer.back_buffer().as_ptr(), backing[0], "The ring wraps after three swaps");
+    for (x, c) in [(0, color::RED), (1, color::GREEN), (2, color::BLUE)] {
+        assert_eq!(buffer.get_pixel(x, 0), c, "Rotated buffer catches up on earlier frames");
+        assert_eq!(screen.get_pixel(x, 0), c);
+    }
+    println!("test:    SUCCESS - buffers rotated");
+
+    println!("test: ===== In-memory Framebuffer Testing Completed =====");
+}
@@ -0,0 +1,94 @@
+//! 测试：面板布局
+//!
+//! 测试内容：
+//! 1. 网格布局按添加顺序分配单元坐标
+//! 2. relayout 按新尺寸重新计算单元
+//! 3. 垂直流式布局每个控件占一行
+//! 4. 绝对定位保持给定坐标
+//! 5. 移动面板时子控件的绘制位置平移相同距离
+
+use crate::font::FontRenderer;
+use crate::framebuffer::{color, MemFramebuffer};
+use crate::layout::{GridLayout, Layout};
+use crate::widgets::SimplePanel;
+
+pub fn test_layout() {
+    println!("test: ===== Testing panel layout =====");
+
+    // 测试 1: 网格单元
+    println!("test: 1. Testing grid cell assignment...");
+    let mut panel = SimplePanel::new(0, 0, 210, 200);
+    panel.set_layout(Layout::Grid(GridLayout { cols: 2, row_height: 20, padding: 5 }));
+    panel.add_button(0, 0, 0, 0, "A");
+    panel.add_button(0, 0, 0, 0, "B");
+    panel.add_label(0, 0, "C");
+    panel.add_textbox(0, 0, 0, 0);
+    // 单元宽度 = (210 - 3 * 5) / 2 = 97
+    let b = &panel.buttons;
+    assert_eq!((b[0].x, b[0].y, b[0].width, b[0].height), (5, 5, 97, 20));
+    assert_eq!((b[1].x, b[1].y), (107, 5));
+    assert_eq!((panel.labels[0].x, panel.labels[0].y), (5, 30), "Third widget wraps to row 2");
+    let t = &panel.textboxes[0];
+    assert_eq!((t.x, t.y, t.width, t.height), (107, 30, 97, 20));
+    println!("test:    SUCCESS - cells assigned in add order");
+
+    // 测试 2: relayout
+    println!("test: 2. Testing relayout on resize...");
+    panel.relayout(310, 240);
+    assert_eq!((panel.width, panel.height), (310, 240));
+    // 单元宽度 = (310 - 15) / 2 = 147
+    assert_eq!((panel.buttons[1].x, panel.buttons[1].width), (157, 147));
+    assert_eq!(panel.textboxes[0].x, 157);
+    assert_eq!(panel.labels[0].y, 30, "Rows unchanged by width-only resize");
+    println!("test:    SUCCESS - widgets repositioned");
+
+    // 测试 3: 垂直流式布局
+    println!("test: 3. Testing vertical flow...");
+    let mut panel = SimplePanel::new(100, 50, 120, 200);
+    panel.set_layout(Layout::VerticalFlow { row_height: 16, padding: 4 });
+    for _ in 0..3 {
+        panel.add_button(0, 0, 0, 0, "X");
+    }
+    let ys: std::vec::Vec<u32> = panel.buttons.iter().map(|b| b.y).collect();
+    assert_eq!(ys, [54, 74, 94], "One row per widget, offset by panel origin");
+    assert!(panel.buttons.iter().all(|b| b.x == 104 && b.width == 112));
+    println!("test:    SUCCESS - flow rows stacked");
+
+    // 测试 4: 绝对定位
+    println!("test: 4. Testing absolute positioning is unchanged...");
+    let mut panel = SimplePanel::new(10, 10, 100, 100);
+    panel.add_button(5, 6, 30, 12, "Abs");
+    panel.relayout(200, 200);
+    let b = &panel.buttons[0];
+    assert_eq!((b.x, b.y, b.width, b.height), (15, 16, 30, 12));
+    println!("test:    SUCCESS - absolute widgets keep coordinates");
+
+    // 测试 5: 移动面板
+    println!("test: 5. Testing panel move shifts children...");
+    let font = FontRenderer::new_8x8();
+    let mut panel = SimplePanel::new(10, 10, 100, 80);
+    panel.add_button(5, 5, 40, 20, "B");
+    panel.add_textbox(5, 40, 60, 20);
+    panel.set_position(60, 45);
+    let b = &panel.buttons[0];
+    assert_eq!((b.x, b.y, b.width, b.height), (65, 50, 40, 20), "Button shifted by (50, 35)");
+    let t = &panel.textboxes[0];
+    assert_eq!((t.x, t.y), (65, 85), "Textbox shifted by (50, 35)");
+
+    let fb = MemFramebuffer::new(200, 150);
+    panel.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(15, 15), color::BLACK, "Nothing left at the old position");
+    assert_eq!(fb.get_pixel(65, 50), color::BLACK, "Button border at the new position");
+    assert_eq!(fb.get_pixel(67, 52), color::GRAY, "Button body at the new position");
+    assert_eq!(fb.get_pixel(67, 87), color::WHITE, "Textbox body at the new position");
+
+    let mut grid = SimplePanel::new(0, 0, 210, 100);
+    grid.set_layout(Layout::Grid(GridLayout { cols: 2, row_height: 20, padding: 5 }));
+    grid.add_button(0, 0, 0, 0, "A");
+    grid.add_button(0, 0, 0, 0, "B");
+    grid.set_position(100, 100);
+    assert_eq!((grid.buttons[1].x, grid.buttons[1].y), (207, 105), "Grid cells follow the panel");
+    println!("test:    SUCCESS - children follow the panel");
+
+    println!("test: ===== Panel Layout Testing Completed =====");
+}
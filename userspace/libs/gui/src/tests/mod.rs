@@ -0,0 +1,81 @@
+//! GUI 单元测试
+//!
+//! 使用内存帧缓冲区 (`MemFramebuffer`) 测试绘图、字体、控件、窗口管理器和光标，
+//! 不需要真实的 framebuffer 设备。
+//!
+//! - 主机上：`cargo test -p rux_gui`
+//! - QEMU 中：以 `unit-test` 特性构建，调用 `run_all_tests()`
+
+pub mod framebuffer;
+pub mod font;
+pub mod widgets;
+pub mod window;
+pub mod layout;
+pub mod surface;
+pub mod cursor;
+
+/// 运行所有 GUI 单元测试
+pub fn run_all_tests() {
+    println!("test: ===== Starting GUI Unit Tests =====");
+
+    // 1. 内存帧缓冲区绘图测试
+    framebuffer::test_mem_framebuffer();
+
+    // 2. 字体渲染测试
+    font::test_font();
+
+    // 3. 控件测试
+    widgets::test_widgets();
+
+    // 4. 窗口管理器测试
+    window::test_window_manager();
+
+    // 5. 面板布局测试
+    layout::test_layout();
+
+    // 6. 离屏渲染表面测试
+    surface::test_surface();
+
+    // 7. 鼠标光标测试
+    cursor::test_cursor();
+
+    println!("test: ===== All GUI Unit Tests Completed =====");
+}
+
+#[cfg(test)]
+mod harness {
+    #[test]
+    fn mem_framebuffer() {
+        super::framebuffer::test_mem_framebuffer();
+    }
+
+    #[test]
+    fn font() {
+        super::font::test_font();
+    }
+
+    #[test]
+    fn widgets() {
+        super::widgets::test_widgets();
+    }
+
+    #[test]
+    fn window_manager() {
+        super::window::test_window_manager();
+    }
+
+    #[test]
+    fn layout() {
+        super::layout::test_layout();
+    }
+
+    #[test]
+    fn surface() {
+        super::surface::test_surface();
+    }
+
+    #[test]
+    fn cursor() {
+        super::cursor::test_cursor();
+    }
+}
@@ -0,0 +1,65 @@
+//! 测试：离屏渲染表面
+//!
+//! 测试内容：
+//! 1. 在表面中绘制图形和文本
+//! 2. 不透明合成到屏幕的指定位置
+//! 3. 合成超出屏幕时被裁剪
+//! 4. 半透明合成与目标像素混合
+
+use crate::font::FontRenderer;
+use crate::framebuffer::{color, Framebuffer, MemFramebuffer};
+use crate::surface::{blend, Surface};
+
+pub fn test_surface() {
+    println!("test: ===== Testing off-screen surface =====");
+
+    // 测试 1: 绘制到表面
+    println!("test: 1. Testing drawing into a surface...");
+    let font = FontRenderer::new_8x8();
+    let surface = Surface::new(20, 10);
+    surface.fill_rect(0, 0, 20, 10, color::BLUE);
+    surface.blit_rect(0, 0, 20, 10, color::WHITE, 1);
+    surface.draw_text(&font, 2, 1, "I", color::YELLOW);
+    assert_eq!(surface.get_pixel(0, 0), color::WHITE);
+    assert_eq!(surface.get_pixel(1, 1), color::BLUE);
+    assert_eq!(surface.get_pixel(5, 1), color::YELLOW, "'I' top row lit at column 3");
+    println!("test:    SUCCESS - surface holds drawn pixels");
+
+    // 测试 2: 不透明合成
+    println!("test: 2. Testing opaque blit...");
+    let screen = MemFramebuffer::new(50, 40);
+    surface.blit_to(&screen, 10, 5);
+    assert_eq!(screen.get_pixel(10, 5), color::WHITE, "Surface origin lands at (10, 5)");
+    assert_eq!(screen.get_pixel(11, 6), color::BLUE);
+    assert_eq!(screen.get_pixel(15, 6), color::YELLOW);
+    assert_eq!(screen.get_pixel(9, 5), color::BLACK, "Outside the surface untouched");
+    assert_eq!(screen.get_pixel(30, 15), color::BLACK);
+    println!("test:    SUCCESS - surface composited at offset");
+
+    // 测试 3: 裁剪
+    println!("test: 3. Testing blit clipping...");
+    let screen = MemFramebuffer::new(25, 12);
+    surface.blit_to(&screen, 15, 8);
+    assert_eq!(screen.get_pixel(24, 11), color::BLUE, "Visible part drawn");
+    assert_eq!(screen.count_color(color::BLACK), 25 * 12 - 10 * 4, "Only the overlap is written");
+    println!("test:    SUCCESS - blit clipped to the screen");
+
+    // 测试 4: 半透明合成
+    println!("test: 4. Testing alpha blit...");
+    let screen = MemFramebuffer::new(4, 4);
+    screen.clear(color::WHITE);
+    let mut overlay = Surface::new(2, 2);
+    overlay.clear(color::BLACK);
+    overlay.set_alpha(128);
+    overlay.blit_to(&screen, 1, 1);
+    let mid = blend(color::BLACK, color::WHITE, 128);
+    assert_eq!(mid, 0xFF7F7F7F, "50% black over white is mid gray");
+    assert_eq!(screen.get_pixel(1, 1), mid);
+    assert_eq!(screen.get_pixel(0, 0), color::WHITE);
+    overlay.set_alpha(0);
+    overlay.blit_to(&screen, 0, 0);
+    assert_eq!(screen.get_pixel(0, 0), color::WHITE, "Fully transparent surface draws nothing");
+    println!("test:    SUCCESS - alpha blended");
+
+    println!("test: ===== Off-screen Surface Testing Completed =====");
+}
@@ -0,0 +1,493 @@
+//! 测试：UI 控件
+//!
+//! 测试内容：
+//! 1. 按钮命中测试与按下/释放产生点击
+//! 2. 禁用的按钮不处理事件
+//! 3. 按钮绘制背景和边框
+//! 4. 文本框获得焦点后接收输入和退格
+//! 5. 面板按自身位置偏移子控件
+//! 6. 助记符解析并从显示文本中去掉 `&`
+//! 7. 助记符下划线位置
+//! 8. Alt+助记符触发按钮点击
+//! 9. 禁用面板后子控件忽略事件并以禁用颜色绘制
+//! 10. 重新启用面板恢复交互
+//! 11. 文本框 on_change 在每次修改后以最终文本调用一次
+//! 12. 字符过滤器和最大长度
+//! 13. 重绘标记：新建为脏、绘制后清除、状态变化后重新置位
+//! 14. 命中测试返回最上层的可见控件
+//! 15. 单选按钮分组互斥、查询选中项并绘制实心/空心圆
+//! 16. 方向键/Home/End 移动光标，在中间插入和删除，光标不越界
+//! 17. 长文本水平滚动，光标始终在文本框内
+//! 18. 密码模式显示掩码字符但保存真实文本
+//! 19. Tab/Shift+Tab 在文本框和按钮间循环切换焦点，按键发给焦点控件
+//! 20. 内容超出面板时出现滚动条，拖动滑块滚动子控件并裁剪到面板内
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::string::String;
+use std::vec::Vec;
+use crate::font::FontRenderer;
+use crate::framebuffer::{color, MemFramebuffer};
+use crate::widgets::{
+    keys, parse_mnemonic, Button, CharFilter, InputRejected, SimplePanel, TextBox, WidgetEvent,
+    WidgetRef, WidgetState, DISABLED_BG, DISABLED_TEXT,
+};
+
+pub fn test_widgets() {
+    println!("test: ===== Testing widgets =====");
+
+    // 测试 1: 按钮点击
+    println!("test: 1. Testing button hit-test and click...");
+    let mut button = Button::new(1, 10, 10, 60, 20, "OK");
+    assert!(button.contains(10, 10));
+    assert!(button.contains(69, 29));
+    assert!(!button.contains(70, 10), "Right edge is exclusive");
+    assert!(button.handle_event(WidgetEvent::MouseDown { x: 20, y: 20 }));
+    assert_eq!(button.state, WidgetState::Pressed);
+    button.handle_event(WidgetEvent::MouseUp { x: 20, y: 20 });
+    assert!(button.was_clicked(), "Press + release is a click");
+    assert!(!button.was_clicked(), "Click is consumed");
+    println!("test:    SUCCESS - button clicked");
+
+    // 测试 2: 禁用按钮
+    println!("test: 2. Testing disabled button...");
+    button.enabled = false;
+    assert!(!button.handle_event(WidgetEvent::MouseDown { x: 20, y: 20 }));
+    assert_eq!(button.state, WidgetState::Hover, "State unchanged while disabled");
+    println!("test:    SUCCESS - disabled button ignores events");
+
+    // 测试 3: 按钮绘制
+    println!("test: 3. Testing button drawing...");
+    let fb = MemFramebuffer::new(100, 50);
+    let font = FontRenderer::new_8x8();
+    let button = Button::new(2, 10, 10, 60, 20, "OK");
+    button.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(10, 10), color::BLACK, "Border");
+    assert_eq!(fb.get_pixel(12, 12), color::GRAY, "Normal background");
+    assert_eq!(fb.get_pixel(80, 40), color::BLACK, "Outside untouched");
+    assert!(fb.count_color(color::WHITE) > 0, "Label text drawn");
+    println!("test:    SUCCESS - button pixels correct");
+
+    // 测试 4: 文本框输入
+    println!("test: 4. Testing textbox input...");
+    let mut textbox = TextBox::new(3, 0, 0, 100, 20);
+    assert!(!textbox.handle_event(WidgetEvent::KeyPress { key: b'x' }), "Unfocused ignores keys");
+    textbox.handle_event(WidgetEvent::MouseDown { x: 5, y: 5 });
+    assert_eq!(textbox.state, WidgetState::Focused);
+    for &key in b"ab\x08c" {
+        textbox.handle_event(WidgetEvent::KeyPress { key });
+    }
+    assert_eq!(textbox.text, "ac");
+    assert_eq!(textbox.cursor_pos, 2);
+    println!("test:    SUCCESS - textbox edited");
+
+    // 测试 5: 面板偏移
+    println!("test: 5. Testing panel child offsets...");
+    let mut panel = SimplePanel::new(100, 50, 200, 100);
+    let id = panel.add_button(10, 20, 40, 16, "Go");
+    assert_eq!(panel.buttons[0].id, id);
+    assert_eq!((panel.buttons[0].x, panel.buttons[0].y), (110, 70));
+    println!("test:    SUCCESS - children offset by panel origin");
+
+    // 测试 6: 助记符解析
+    println!("test: 6. Testing mnemonic parsing...");
+    assert_eq!(parse_mnemonic("&File"), ("File".into(), Some((0, b'f'))));
+    assert_eq!(parse_mnemonic("Save &As"), ("Save As".into(), Some((5, b'a'))));
+    assert_eq!(parse_mnemonic("R&&D"), ("R&D".into(), None), "&& is a literal &");
+    assert_eq!(parse_mnemonic("A&&B &Go"), ("A&B Go".into(), Some((4, b'g'))));
+    assert_eq!(parse_mnemonic("Plain"), ("Plain".into(), None));
+    let button = Button::new(4, 0, 0, 80, 20, "E&xit");
+    assert_eq!(button.text, "Exit", "Displayed text has no &");
+    assert_eq!(button.mnemonic, Some(b'x'));
+    println!("test:    SUCCESS - mnemonic parsed and stripped");
+
+    // 测试 7: 下划线位置
+    println!("test: 7. Testing underline position...");
+    // 文本 "Exit" 宽 32，居中于 80 宽的按钮：x = 24；y = (20 - 8) / 2 = 6
+    assert_eq!(button.underline_rect(&font), Some((24 + 8, 6 + 8, 8)));
+    let fb = MemFramebuffer::new(80, 20);
+    button.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(32, 14), color::WHITE, "Underline under 'x'");
+    assert_eq!(fb.get_pixel(39, 14), color::WHITE);
+    assert_eq!(fb.get_pixel(24, 14), color::GRAY, "No underline under 'E'");
+    assert_eq!(Button::new(5, 0, 0, 80, 20, "OK").underline_rect(&font), None);
+    println!("test:    SUCCESS - underline drawn under mnemonic");
+
+    // 测试 8: Alt+助记符
+    println!("test: 8. Testing Alt+mnemonic fires click...");
+    let mut panel = SimplePanel::new(0, 0, 200, 100);
+    let open = panel.add_button(0, 0, 60, 20, "&Open");
+    let quit = panel.add_button(0, 30, 60, 20, "&Quit");
+    assert_eq!(panel.handle_alt_key(b'Q'), Some(quit), "Mnemonic is case-insensitive");
+    assert!(panel.buttons[1].was_clicked());
+    assert!(!panel.buttons[0].was_clicked());
+    assert_eq!(panel.handle_alt_key(b'z'), None);
+    panel.buttons[0].enabled = false;
+    assert_eq!(panel.handle_alt_key(b'o'), None, "Disabled button ignores mnemonic");
+    assert!(!panel.buttons.iter().any(|b| b.id == open && b.clicked));
+    println!("test:    SUCCESS - Alt+mnemonic clicked the button");
+
+    // 测试 9: 禁用面板
+    println!("test: 9. Testing disabled panel...");
+    let mut panel = SimplePanel::new(0, 0, 200, 100);
+    panel.add_button(10, 10, 60, 20, "&Go");
+    panel.add_label(10, 40, "Name");
+    panel.add_textbox(10, 60, 100, 20);
+    panel.set_enabled(false);
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 20, y: 20 });
+    panel.handle_mouse(WidgetEvent::MouseUp { x: 20, y: 20 });
+    assert!(!panel.buttons[0].was_clicked(), "Disabled button ignores clicks");
+    assert_eq!(panel.handle_alt_key(b'g'), None);
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 20, y: 70 });
+    assert_ne!(panel.textboxes[0].state, WidgetState::Focused, "Disabled textbox cannot focus");
+    assert!(!panel.textboxes[0].handle_event(WidgetEvent::KeyPress { key: b'a' }));
+    assert!(panel.textboxes[0].text.is_empty());
+
+    let fb = MemFramebuffer::new(200, 100);
+    panel.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(12, 12), DISABLED_BG, "Disabled button background");
+    assert!(fb.count_color(DISABLED_TEXT) > 0, "Dimmed text drawn");
+    assert_eq!(fb.get_pixel(12, 62), color::LIGHT_GRAY, "Disabled textbox background");
+    println!("test:    SUCCESS - disabled widgets inert and dimmed");
+
+    // 测试 10: 重新启用
+    println!("test: 10. Testing re-enabling restores interaction...");
+    panel.set_enabled(true);
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 20, y: 20 });
+    panel.handle_mouse(WidgetEvent::MouseUp { x: 20, y: 20 });
+    assert!(panel.buttons[0].was_clicked(), "Enabled button clicks again");
+    panel.textboxes[0].handle_event(WidgetEvent::MouseDown { x: 20, y: 70 });
+    assert!(panel.textboxes[0].handle_event(WidgetEvent::KeyPress { key: b'a' }));
+    assert_eq!(panel.textboxes[0].text, "a");
+    let fb = MemFramebuffer::new(200, 100);
+    panel.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(12, 12), 0xFFA0A0A0, "Enabled (hovered) button background restored");
+    println!("test:    SUCCESS - interaction restored");
+
+    // 测试 11: on_change 回调
+    println!("test: 11. Testing TextBox on_change...");
+    let seen: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let mut textbox = TextBox::new(6, 0, 0, 100, 20);
+    let sink = seen.clone();
+    textbox.set_on_change(move |text| sink.borrow_mut().push(String::from(text)));
+    textbox.handle_event(WidgetEvent::MouseDown { x: 5, y: 5 });
+    assert!(seen.borrow().is_empty(), "Focus is not an edit");
+
+    textbox.handle_event(WidgetEvent::KeyPress { key: b'h' });
+    textbox.insert_char('i').unwrap();
+    textbox.paste("!\x07?");
+    textbox.backspace();
+    textbox.cursor_pos = 0;
+    textbox.delete();
+    assert!(!textbox.backspace(), "Backspace at start changes nothing");
+    textbox.clear();
+    assert!(!textbox.clear(), "Clearing empty text changes nothing");
+    assert_eq!(
+        *seen.borrow(),
+        ["h", "hi", "hi!?", "hi!", "i!", ""],
+        "One callback per edit, observing the final text"
+    );
+    assert_eq!(textbox.cursor_pos, 0);
+    println!("test:    SUCCESS - on_change fired once per edit");
+
+    // 测试 12: 过滤器与最大长度
+    println!("test: 12. Testing CharFilter and max_length...");
+    let mut digits = TextBox::new(7, 0, 0, 100, 20);
+    digits.set_filter(CharFilter::Digits).set_max_length(Some(3));
+    assert_eq!(digits.insert_char('a'), Err(InputRejected::Filtered), "Digits rejects letters");
+    assert!(digits.text.is_empty());
+    assert_eq!(digits.insert_char('4'), Ok(()));
+    digits.handle_event(WidgetEvent::MouseDown { x: 1, y: 1 });
+    for &key in b"x2" {
+        digits.handle_event(WidgetEvent::KeyPress { key });
+    }
+    assert_eq!(digits.text, "42");
+    assert!(digits.paste("9a87"), "Paste keeps accepted chars up to max_length");
+    assert_eq!(digits.text, "429");
+    assert_eq!(digits.insert_char('1'), Err(InputRejected::MaxLength), "Insert past max_length ignored");
+    assert_eq!(digits.text, "429");
+    assert_eq!(digits.rejected, Some(InputRejected::MaxLength));
+    let fb = MemFramebuffer::new(100, 20);
+    digits.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(0, 0), color::RED, "Rejected input flags the border");
+    digits.backspace();
+    assert_eq!(digits.rejected, None, "Successful edit clears the feedback");
+
+    let mut custom = TextBox::new(8, 0, 0, 100, 20);
+    custom.set_filter(CharFilter::Custom(|c| c == 'a' || c == 'b'));
+    assert_eq!(custom.insert_char('a'), Ok(()));
+    assert_eq!(custom.insert_char('c'), Err(InputRejected::Filtered));
+    assert_eq!(custom.insert_char('b'), Ok(()));
+    assert_eq!(custom.text, "ab");
+    assert!(CharFilter::Hex.accepts('F') && !CharFilter::Hex.accepts('g'));
+    assert!(CharFilter::Alpha.accepts('z') && !CharFilter::Alpha.accepts('1'));
+    println!("test:    SUCCESS - filters and max_length enforced");
+
+    // 测试 13: 重绘标记
+    println!("test: 13. Testing dirty flag...");
+    let fb = MemFramebuffer::new(200, 100);
+    let mut panel = SimplePanel::new(0, 0, 200, 100);
+    panel.add_button(10, 10, 60, 20, "OK");
+    panel.add_label(10, 40, "Name");
+    panel.add_textbox(10, 60, 100, 20);
+    assert!(panel.buttons[0].is_dirty(), "New widgets start dirty");
+    assert!(panel.labels[0].is_dirty() && panel.textboxes[0].is_dirty());
+    assert!(panel.needs_redraw());
+
+    panel.draw(&fb, &font);
+    assert!(!panel.buttons[0].is_dirty(), "Draw clears the flag");
+    assert!(!panel.needs_redraw(), "Nothing changed since the last draw");
+
+    panel.handle_mouse(WidgetEvent::MouseMove { x: 150, y: 90 });
+    assert!(!panel.needs_redraw(), "Moving over empty space changes nothing");
+    panel.handle_mouse(WidgetEvent::MouseMove { x: 20, y: 15 });
+    assert!(panel.buttons[0].is_dirty(), "Hover marks the button dirty");
+    assert!(!panel.textboxes[0].is_dirty());
+    assert!(panel.needs_redraw());
+    panel.draw(&fb, &font);
+
+    panel.labels[0].set_text("Name");
+    assert!(!panel.needs_redraw(), "Setting identical text is not a change");
+    panel.labels[0].set_text("User");
+    assert!(panel.labels[0].is_dirty());
+    panel.draw(&fb, &font);
+
+    panel.textboxes[0].handle_event(WidgetEvent::MouseDown { x: 20, y: 65 });
+    panel.draw(&fb, &font);
+    panel.textboxes[0].handle_event(WidgetEvent::KeyPress { key: b'a' });
+    assert!(panel.textboxes[0].is_dirty(), "Text edit marks the textbox dirty");
+    panel.draw(&fb, &font);
+
+    panel.set_position(5, 5);
+    assert!(panel.needs_redraw(), "Moving the panel repositions every child");
+    println!("test:    SUCCESS - dirty flag tracks changes");
+
+    // 测试 14: 命中测试
+    println!("test: 14. Testing widget_at hit-testing...");
+    let mut panel = SimplePanel::new(10, 10, 200, 100);
+    let label = panel.add_label(0, 0, "Background");
+    let textbox = panel.add_textbox(20, 0, 100, 30);
+    let lower = panel.add_button(40, 10, 60, 20, "A");
+    let upper = panel.add_button(70, 10, 60, 20, "B");
+    assert_eq!(panel.widget_at(12, 12), Some(label), "Only the label covers (12, 12)");
+    assert_eq!(panel.widget_ref_at(35, 12), Some(WidgetRef::TextBox(textbox)), "TextBox above label");
+    assert_eq!(panel.widget_at(55, 25), Some(lower), "Button above textbox");
+    assert_eq!(panel.widget_at(85, 25), Some(upper), "Later button is on top");
+    assert_eq!(panel.widget_at(120, 25), Some(upper));
+    assert_eq!(panel.widget_at(150, 90), None, "Empty space hits nothing");
+    assert_eq!(panel.widget_at(5, 5), None, "Outside the panel hits nothing");
+    panel.buttons[1].visible = false;
+    assert_eq!(panel.widget_at(85, 25), Some(lower), "Hidden widgets are skipped");
+    panel.visible = false;
+    assert_eq!(panel.widget_at(55, 25), None, "Hidden panel hits nothing");
+    println!("test:    SUCCESS - topmost widget returned");
+
+    // 测试 15: 单选按钮
+    println!("test: 15. Testing radio button groups...");
+    let mut panel = SimplePanel::new(0, 0, 200, 100);
+    let small = panel.add_radio(10, 10, 1, "Small");
+    let large = panel.add_radio(10, 30, 1, "Large");
+    let red = panel.add_radio(100, 10, 2, "Red");
+    assert_eq!(panel.get_selected_radio(1), None, "Nothing selected initially");
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 15, y: 15 });
+    assert_eq!(panel.get_selected_radio(1), Some(small));
+    assert_eq!(panel.get_selected_radio(2), None, "Other groups unaffected");
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 40, y: 35 });
+    assert_eq!(panel.get_selected_radio(1), Some(large), "Clicking the label selects too");
+    assert!(!panel.radios[0].checked, "Sibling in the group is cleared");
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 105, y: 15 });
+    assert_eq!(panel.get_selected_radio(2), Some(red));
+    assert_eq!(panel.get_selected_radio(1), Some(large), "Selecting another group keeps this one");
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 150, y: 80 });
+    assert_eq!(panel.get_selected_radio(1), Some(large), "Click in empty space changes nothing");
+    assert_eq!(panel.widget_ref_at(15, 15), Some(WidgetRef::Radio(small)));
+    assert!(panel.select_radio(small));
+    assert!(!panel.select_radio(9999), "Unknown id is rejected");
+    assert_eq!(panel.get_selected_radio(1), Some(small));
+
+    let fb = MemFramebuffer::new(200, 100);
+    panel.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(15, 15), color::WHITE, "Selected radio has a filled center");
+    assert_eq!(fb.get_pixel(15, 35), color::BLACK, "Unselected radio center is empty");
+    assert_eq!(fb.get_pixel(20, 35), color::WHITE, "Unselected radio ring drawn");
+    panel.set_enabled(false);
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 15, y: 35 });
+    assert_eq!(panel.get_selected_radio(1), Some(small), "Disabled panel ignores radio clicks");
+    println!("test:    SUCCESS - radio groups are mutually exclusive");
+
+    // 测试 16: 光标移动
+    println!("test: 16. Testing caret movement keys...");
+    let mut textbox = TextBox::new(1, 0, 0, 60, 20);
+    textbox.handle_event(WidgetEvent::MouseDown { x: 5, y: 5 });
+    for key in *b"held" {
+        textbox.handle_event(WidgetEvent::KeyPress { key });
+    }
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::LEFT });
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::LEFT });
+    assert_eq!(textbox.cursor_pos, 2);
+    assert_eq!(textbox.text, "held", "Arrows do not edit");
+    textbox.handle_event(WidgetEvent::KeyPress { key: b'l' });
+    assert_eq!(textbox.text, "helld", "Insert happens at the caret");
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::DELETE });
+    assert_eq!(textbox.text, "held");
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::HOME });
+    assert_eq!(textbox.cursor_pos, 0);
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::LEFT });
+    assert_eq!(textbox.cursor_pos, 0, "Left stops at the start");
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::END });
+    assert_eq!(textbox.cursor_pos, 4);
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::RIGHT });
+    assert_eq!(textbox.cursor_pos, 4, "Right stops at the end");
+    textbox.cursor_pos = 99;
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::BACKSPACE });
+    assert_eq!((textbox.text.as_str(), textbox.cursor_pos), ("hel", 3), "Out-of-range caret is clamped");
+
+    assert_eq!(textbox.caret_x(&font), 4 + 3 * 8, "Caret follows the logical position");
+    textbox.paste("lo world");
+    assert_eq!(textbox.visible_chars(), 6);
+    assert_eq!(textbox.scroll_offset, 5, "Text scrolls to keep the caret visible");
+    assert_eq!(textbox.caret_x(&font), 4 + 6 * 8, "Caret at the right edge of the visible text");
+    let fb = MemFramebuffer::new(80, 20);
+    textbox.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(52, 10), color::BLACK, "Caret drawn after the last visible char");
+    println!("test:    SUCCESS - caret moves without editing");
+
+    // 测试 17: 水平滚动
+    println!("test: 17. Testing horizontal scrolling...");
+    let mut textbox = TextBox::new(1, 20, 0, 60, 20);
+    textbox.handle_event(WidgetEvent::MouseDown { x: 25, y: 5 });
+    for i in 0..100u8 {
+        textbox.handle_event(WidgetEvent::KeyPress { key: b'a' + i % 26 });
+        let caret = textbox.caret_x(&font);
+        assert!(caret >= textbox.x && caret <= textbox.x + textbox.width,
+                "Caret must stay inside the box");
+    }
+    assert_eq!(textbox.text.len(), 100);
+    assert_eq!(textbox.scroll_offset, 94);
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::HOME });
+    assert_eq!((textbox.scroll_offset, textbox.caret_x(&font)), (0, 24), "Home scrolls back");
+    for _ in 0..7 {
+        textbox.handle_event(WidgetEvent::KeyPress { key: keys::RIGHT });
+    }
+    assert_eq!(textbox.scroll_offset, 1, "Moving past the right edge scrolls by one");
+    for _ in 0..6 {
+        textbox.handle_event(WidgetEvent::KeyPress { key: keys::LEFT });
+    }
+    assert_eq!(textbox.scroll_offset, 1, "Moving inside the view does not scroll");
+    textbox.handle_event(WidgetEvent::KeyPress { key: keys::LEFT });
+    assert_eq!(textbox.scroll_offset, 0, "Moving left of the view scrolls back");
+    let fb = MemFramebuffer::new(100, 20);
+    textbox.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(90, 10), color::BLACK, "Nothing drawn past the box");
+    assert!(fb.count_color(color::BLACK) > 2 * 60, "Visible text drawn in black");
+    println!("test:    SUCCESS - caret stays in view");
+
+    // 测试 18: 密码模式
+    println!("test: 18. Testing masked TextBox...");
+    let mut panel = SimplePanel::new(0, 0, 200, 60);
+    let id = panel.add_textbox(0, 0, 80, 20);
+    panel.textboxes[0].set_masked(true);
+    panel.textboxes[0].paste("abc");
+    assert_eq!(panel.get_textbox_text(id), Some("abc"), "Real text is kept");
+    assert_eq!(panel.get_textbox_text(id + 1), None);
+    let masked_fb = MemFramebuffer::new(80, 20);
+    panel.textboxes[0].draw(&masked_fb, &font);
+    let mut plain = TextBox::new(2, 0, 0, 80, 20);
+    plain.paste("***");
+    let plain_fb = MemFramebuffer::new(80, 20);
+    plain.draw(&plain_fb, &font);
+    assert_eq!(masked_fb.pixels(), plain_fb.pixels(), "Each character renders as '*'");
+    panel.textboxes[0].handle_event(WidgetEvent::MouseDown { x: 5, y: 5 });
+    panel.textboxes[0].handle_event(WidgetEvent::KeyPress { key: keys::LEFT });
+    panel.textboxes[0].handle_event(WidgetEvent::KeyPress { key: keys::BACKSPACE });
+    assert_eq!(panel.get_textbox_text(id), Some("ac"), "Editing is unchanged");
+    assert_eq!(panel.textboxes[0].cursor_pos, 1);
+    panel.textboxes[0].set_masked(false);
+    let fb = MemFramebuffer::new(80, 20);
+    panel.textboxes[0].draw(&fb, &font);
+    assert_ne!(fb.pixels(), plain_fb.pixels(), "Unmasked text is shown as-is");
+    println!("test:    SUCCESS - masked text hidden");
+
+    // 测试 19: Tab 焦点切换
+    println!("test: 19. Testing Tab focus traversal...");
+    let mut panel = SimplePanel::new(0, 0, 200, 120);
+    let name = panel.add_textbox(10, 10, 100, 20);
+    panel.add_label(10, 40, "skip me");
+    let ok = panel.add_button(10, 60, 60, 20, "OK");
+    let note = panel.add_textbox(10, 90, 100, 20);
+    assert_eq!(panel.focused_widget(), None);
+    assert!(panel.handle_keyboard(keys::TAB));
+    assert_eq!(panel.focused_widget(), Some(name), "First Tab focuses the first textbox");
+    assert_eq!(panel.textboxes[0].state, WidgetState::Focused);
+    panel.handle_keyboard(b'x');
+    assert_eq!(panel.textboxes[0].text, "x", "Keys go to the focused textbox");
+    panel.handle_keyboard(keys::TAB);
+    assert_eq!(panel.focused_widget(), Some(ok), "Labels are skipped");
+    assert_eq!(panel.textboxes[0].state, WidgetState::Normal, "Old widget receives Blur");
+    assert!(panel.buttons[0].has_focus);
+    panel.handle_keyboard(keys::ENTER);
+    assert!(panel.buttons[0].was_clicked(), "Enter activates the focused button");
+    panel.handle_keyboard(keys::TAB);
+    assert_eq!(panel.focused_widget(), Some(note));
+    assert!(!panel.buttons[0].has_focus);
+    panel.handle_keyboard(keys::TAB);
+    assert_eq!(panel.focused_widget(), Some(name), "Tab wraps around");
+    panel.handle_keyboard(keys::BACKTAB);
+    assert_eq!(panel.focused_widget(), Some(note), "Shift+Tab goes backwards and wraps");
+    assert_eq!(panel.focus_prev(), Some(ok));
+    panel.buttons[0].set_enabled(false);
+    assert_eq!(panel.focus_next(), Some(note));
+    assert_eq!(panel.focus_next(), Some(name), "Disabled widgets are skipped");
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 20, y: 95 });
+    assert_eq!(panel.focused_widget(), Some(note), "Clicking a textbox moves focus");
+    assert_eq!(panel.textboxes[0].state, WidgetState::Normal, "Previously focused textbox blurred");
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 150, y: 5 });
+    assert_eq!(panel.focused_widget(), Some(note), "Clicking empty space keeps focus");
+    panel.handle_keyboard(b'y');
+    assert_eq!(panel.textboxes[1].text, "y");
+    assert_eq!(panel.textboxes[0].text, "x", "Only the focused textbox receives keys");
+    println!("test:    SUCCESS - focus cycles across widgets");
+
+    // 测试 20: 滚动面板
+    println!("test: 20. Testing scrollable panel...");
+    let mut panel = SimplePanel::new(0, 0, 100, 50);
+    let top = panel.add_button(10, 10, 60, 20, "Top");
+    let bottom = panel.add_button(10, 80, 60, 20, "Bottom");
+    assert!(panel.scrollbar().is_none(), "No scrollbar without content height");
+    panel.set_content_height(40);
+    assert!(panel.scrollbar().is_none(), "Content fits, no scrollbar");
+    panel.set_content_height(100);
+    let bar = panel.scrollbar().expect("Content taller than panel shows a scrollbar");
+    assert_eq!(bar.thumb_rect(), (88, 0, 12, 25), "Thumb is visible/total of the track");
+    assert_eq!(panel.max_scroll(), 50);
+    assert_eq!(panel.widget_at(20, 15), Some(top));
+    assert_eq!(panel.widget_at(20, 85), None, "Content below the panel is clipped");
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 94, y: 5 });
+    assert!(panel.scrollbar().unwrap().is_dragging());
+    panel.handle_mouse(WidgetEvent::MouseMove { x: 94, y: 30 });
+    assert_eq!(panel.scrollbar().unwrap().position(), 1.0);
+    assert_eq!(panel.content_offset_y(), 50, "Dragging to the end scrolls to the bottom");
+    panel.handle_mouse(WidgetEvent::MouseUp { x: 94, y: 30 });
+    assert!(!panel.scrollbar().unwrap().is_dragging());
+    assert_eq!(panel.widget_at(20, 35), Some(bottom), "Hit-testing follows the scroll offset");
+    assert_eq!(panel.widget_at(20, 15), None);
+    assert!(panel.needs_redraw(), "Scrolling marks children dirty");
+    let fb = MemFramebuffer::new(100, 60);
+    let blank = fb.get_pixel(0, 0);
+    panel.draw(&fb, &font);
+    assert_ne!(fb.get_pixel(30, 31), blank, "Bottom button drawn shifted up");
+    assert_eq!(fb.get_pixel(30, 5), blank, "Top button scrolled out of view");
+    assert_eq!(fb.get_pixel(30, 55), blank, "Nothing drawn below the panel");
+    panel.scroll_to(1000);
+    assert_eq!(panel.content_offset_y(), 50, "scroll_to clamps to the range");
+    panel.scroll_to(25);
+    assert_eq!(panel.scrollbar().unwrap().position(), 0.5);
+    panel.handle_mouse(WidgetEvent::MouseDown { x: 94, y: 48 });
+    assert_eq!(panel.content_offset_y(), 50, "Clicking the track pages down");
+    panel.set_content_height(40);
+    assert_eq!(panel.content_offset_y(), 0, "Shrinking content resets the offset");
+    assert!(panel.scrollbar().is_none());
+    println!("test:    SUCCESS - panel scrolls and clips children");
+
+    println!("test: ===== Widgets Testing Completed =====");
+}
@@ -0,0 +1,501 @@
+//! 测试：窗口管理器
+//!
+//! 测试内容：
+//! 1. 窗口命中测试（内容区、标题栏、关闭按钮、隐藏窗口）
+//! 2. 点击重叠区域时选中最上层窗口并置顶
+//! 3. 拖动标题栏移动窗口（偏移保持、坐标不小于 0）
+//! 4. 绘制窗口到内存帧缓冲区并检查像素（阴影半透明）
+//! 5. 操作不存在的窗口返回 NoSuchWindow
+//! 6. 状态不允许的操作返回 InvalidState，合法操作返回 Ok
+//! 7. 标题栏最小化/最大化按钮命中测试和绘制
+//! 8. 点击标题栏按钮触发最小化、最大化和还原
+//! 9. 修改标题后重新绘制并标记窗口为脏
+//! 10. 屏幕缩小后窗口被限制回屏幕内，缓冲区和光标使用新尺寸
+//! 11. 事件路由：鼠标事件以客户区坐标发给目标窗口，键盘事件发给焦点窗口
+//! 12. 拖动边缘/角调整大小，对边保持不动，尺寸不小于最小值
+//! 13. 最大化占满任务栏以外的区域并可切换还原，最小化的窗口不绘制但仍被列出
+//! 14. 聚焦、移动、调整大小和关闭请求按顺序进入窗口事件队列
+//! 15. Alt+Tab 按 Z 顺序轮换焦点窗口，标题栏区分焦点和非焦点颜色
+//! 16. 内容区位于标题栏以下、边框以内，draw_content 的绘制被裁剪到内容区
+//! 17. 被更高层窗口完全遮挡的窗口不绘制，部分遮挡或上层隐藏时照常绘制
+//! 18. 按偏移量移动窗口，向左/向上越过 0 时坐标被限制为 0
+
+use crate::cursor::MouseCursor;
+use crate::double_buffer::DoubleBuffer;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::vec::Vec;
+use crate::font::FontRenderer;
+use crate::framebuffer::{color, ClipRect, Framebuffer, MemFramebuffer};
+use crate::widgets::{keys, WidgetEvent};
+use crate::window::{ResizeEdge, TitleButton, Window, WindowEvent, WindowManager, WindowState, WmError, ACTIVE_TITLE_COLOR, INACTIVE_TITLE_COLOR, SHADOW_COLOR, TITLE_BAR_HEIGHT};
+
+pub fn test_window_manager() {
+    println!("test: ===== Testing window manager =====");
+
+    // 测试 1: 命中测试
+    println!("test: 1. Testing window hit-testing...");
+    let mut window = Window::new(1, "Test", 10, 10, 100, 80);
+    assert!(window.contains(10, 10));
+    assert!(!window.contains(110, 50), "Right edge is exclusive");
+    assert!(window.is_in_title_bar(30, 15));
+    assert!(!window.is_in_title_bar(30, 10 + TITLE_BAR_HEIGHT));
+    assert!(!window.is_in_title_bar(95, 15), "Close button area is not draggable");
+    assert!(window.is_in_close_button(95, 18));
+    window.visible = false;
+    assert!(!window.contains(50, 50), "Hidden window is never hit");
+    println!("test:    SUCCESS - hit-testing correct");
+
+    // 测试 2: 重叠窗口
+    println!("test: 2. Testing top-most window selection...");
+    let mut wm = WindowManager::new();
+    let back = wm.create_window("Back", 10, 10, 100, 80);
+    let front = wm.create_window("Front", 50, 50, 100, 80);
+    assert!(wm.get_window(front).unwrap().z_order > wm.get_window(back).unwrap().z_order);
+    assert_eq!(wm.handle_mouse_down(60, 80), None);
+    assert!(!wm.is_dragging(), "Click in content area does not drag");
+    assert!(wm.get_window(front).unwrap().z_order > wm.get_window(back).unwrap().z_order,
+            "Overlap click selects the front window");
+    wm.handle_mouse_down(20, 50);
+    assert!(wm.get_window(back).unwrap().z_order > wm.get_window(front).unwrap().z_order,
+            "Clicked window is raised");
+    assert_eq!(wm.handle_mouse_down(95, 18), Some(back), "Close button reports the window");
+    wm.handle_mouse_up();
+    println!("test:    SUCCESS - top-most window selected");
+
+    // 测试 3: 拖动
+    println!("test: 3. Testing drag math...");
+    let mut wm = WindowManager::new();
+    let id = wm.create_window("Drag", 10, 10, 100, 80);
+    wm.handle_mouse_down(30, 15);
+    assert!(wm.is_dragging());
+    wm.handle_mouse_move(130, 105);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y), (110, 100), "Grab offset (20, 5) is preserved");
+    wm.handle_mouse_move(5, 2);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y), (0, 0), "Position is clamped at 0");
+    wm.handle_mouse_up();
+    assert!(!wm.is_dragging());
+    wm.handle_mouse_move(200, 200);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y), (0, 0), "Moves after release are ignored");
+    println!("test:    SUCCESS - drag math correct");
+
+    // 测试 4: 绘制
+    println!("test: 4. Testing window drawing...");
+    let fb = MemFramebuffer::new(200, 150);
+    let font = FontRenderer::new_8x8();
+    let window = Window::new(1, "Test", 10, 10, 100, 80);
+    window.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(50, 50), color::WHITE, "Client area");
+    assert_eq!(fb.get_pixel(11, 50), color::BLACK, "Left border");
+    assert_eq!(fb.get_pixel(80, 27), color::BLUE, "Title bar");
+    assert_eq!(fb.get_pixel(92, 14), color::RED, "Close button");
+    assert_eq!(fb.get_pixel(111, 50), color::over(SHADOW_COLOR, color::BLACK), "Translucent drop shadow");
+    assert_ne!(fb.get_pixel(111, 50), color::DARK_GRAY, "Shadow is blended, not solid");
+    assert_eq!(fb.get_pixel(150, 120), color::BLACK, "Outside untouched");
+    println!("test:    SUCCESS - window pixels correct");
+
+    // 测试 5: 不存在的窗口
+    println!("test: 5. Testing operations on a missing window...");
+    let mut wm = WindowManager::new();
+    let id = wm.create_window("Ops", 10, 10, 100, 80);
+    let missing = id + 100;
+    assert_eq!(wm.move_window(missing, 0, 0), Err(WmError::NoSuchWindow));
+    assert_eq!(wm.set_window_visible(missing, false), Err(WmError::NoSuchWindow));
+    assert_eq!(wm.remove_window(missing), Err(WmError::NoSuchWindow));
+    assert!(!wm.move_window_ok(missing, 0, 0), "Boolean wrapper reports failure");
+    println!("test:    SUCCESS - NoSuchWindow reported");
+
+    // 测试 6: 合法操作与状态错误
+    println!("test: 6. Testing valid operations and invalid states...");
+    assert_eq!(wm.move_window(id, 40, 30), Ok(()));
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y), (40, 30));
+    assert_eq!(wm.set_window_visible(id, false), Ok(()));
+    assert!(!wm.get_window(id).unwrap().visible);
+    wm.get_window_mut(id).unwrap().state = WindowState::Minimized;
+    assert_eq!(wm.move_window(id, 0, 0), Err(WmError::InvalidState));
+    assert_eq!(wm.set_window_visible(id, true), Err(WmError::InvalidState));
+    assert_eq!(wm.remove_window(id), Ok(()));
+    assert!(wm.get_window(id).is_none());
+    assert!(!wm.remove_window_ok(id), "Second removal fails");
+    println!("test:    SUCCESS - Ok and InvalidState reported");
+
+    // 测试 7: 标题栏按钮
+    println!("test: 7. Testing title-bar button hit-testing and glyphs...");
+    let window = Window::new(1, "Buttons", 10, 10, 100, 80);
+    assert_eq!(window.title_button_origin(TitleButton::Close), (92, 14));
+    assert_eq!(window.title_button_origin(TitleButton::Maximize), (76, 14));
+    assert_eq!(window.title_button_origin(TitleButton::Minimize), (60, 14));
+    assert!(window.is_in_maximize_button(80, 18));
+    assert!(!window.is_in_maximize_button(95, 18), "Close is not maximize");
+    assert!(window.is_in_minimize_button(60, 14));
+    assert!(!window.is_in_minimize_button(72, 14), "Gap between buttons");
+    assert!(!window.is_in_title_bar(65, 15), "Button area is not draggable");
+    let fb = MemFramebuffer::new(200, 150);
+    window.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(77, 15), color::GRAY, "Maximize button background");
+    assert_eq!(fb.get_pixel(78, 16), color::WHITE, "Maximize glyph frame");
+    assert_eq!(fb.get_pixel(64, 23), color::WHITE, "Minimize glyph bar");
+    let mut maximized = Window::new(1, "Buttons", 10, 10, 100, 80);
+    maximized.state = WindowState::Maximized;
+    let fb = MemFramebuffer::new(200, 150);
+    maximized.draw(&fb, &font);
+    assert_eq!(fb.get_pixel(78, 16), color::GRAY, "Restore glyph differs from maximize");
+    assert_eq!(fb.get_pixel(78, 18), color::WHITE);
+    println!("test:    SUCCESS - buttons hit-tested and drawn");
+
+    // 测试 8: 点击标题栏按钮
+    println!("test: 8. Testing title-bar button actions...");
+    let mut wm = WindowManager::new();
+    let id = wm.create_window("Act", 10, 10, 100, 80);
+    assert_eq!(wm.handle_mouse_down(80, 18), None);
+    assert_eq!(wm.get_window(id).unwrap().state, WindowState::Normal,
+               "Maximize needs a known screen size");
+    wm.set_screen_size(320, 240);
+    assert_eq!(wm.handle_mouse_down(80, 18), None, "Maximize click is handled internally");
+    let w = wm.get_window(id).unwrap();
+    assert_eq!(w.state, WindowState::Maximized);
+    assert_eq!((w.x, w.y, w.width, w.height), (0, 0, 320, 240));
+    assert!(!wm.is_dragging(), "Maximize click does not start a drag");
+    wm.handle_mouse_down(100, 10);
+    assert!(!wm.is_dragging(), "Maximized window cannot be dragged");
+    // 最大化后按钮移到屏幕右上角
+    let (rx, ry) = wm.get_window(id).unwrap().title_button_origin(TitleButton::Maximize);
+    assert_eq!((rx, ry), (286, 4));
+    wm.handle_mouse_down(rx + 1, ry + 1);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!(w.state, WindowState::Normal, "Second click restores");
+    assert_eq!((w.x, w.y, w.width, w.height), (10, 10, 100, 80), "Original geometry restored");
+    assert_eq!(wm.handle_mouse_down(62, 16), None);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!(w.state, WindowState::Minimized, "Minimize click minimizes");
+    assert!(!w.visible);
+    assert_eq!(wm.handle_mouse_down(62, 16), None, "Minimized window is not hit");
+    assert_eq!(wm.restore(id), Ok(()));
+    assert_eq!(wm.get_window(id).unwrap().state, WindowState::Normal);
+    assert!(wm.get_window(id).unwrap().visible);
+    assert_eq!(wm.restore(id), Err(WmError::InvalidState), "Normal window cannot be restored");
+    assert_eq!(wm.handle_mouse_down(95, 18), Some(id), "Close still reports the window");
+    println!("test:    SUCCESS - minimize/maximize/restore triggered");
+
+    // 测试 9: 修改标题
+    println!("test: 9. Testing set_window_title...");
+    let mut wm = WindowManager::new();
+    let id = wm.create_window("I", 10, 10, 100, 80);
+    let fb = MemFramebuffer::new(200, 150);
+    wm.draw_all(&fb, &font);
+    assert!(!wm.get_window(id).unwrap().is_dirty(), "Draw clears the flag");
+    let title_pixels = fb.count_color(color::WHITE);
+    assert_eq!(wm.set_window_title(id, "II"), Ok(()));
+    assert_eq!(wm.get_window(id).unwrap().title, "II");
+    assert!(wm.get_window(id).unwrap().is_dirty(), "New title marks the window dirty");
+    wm.draw_all(&fb, &font);
+    assert_eq!(fb.count_color(color::WHITE), title_pixels + 18, "Second 'I' glyph rendered");
+    assert_eq!(wm.set_window_title(id, ""), Ok(()), "Empty title is accepted");
+    wm.draw_all(&fb, &font);
+    assert_eq!(fb.count_color(color::WHITE), title_pixels - 18, "No title glyphs drawn");
+    assert_eq!(wm.set_window_title(id, "A very long title that will not fit"), Ok(()));
+    wm.draw_all(&fb, &font);
+    assert_eq!(fb.get_pixel(55, 16), INACTIVE_TITLE_COLOR, "Long title is truncated before the buttons");
+    assert_eq!(wm.set_window_title(id + 1, "x"), Err(WmError::NoSuchWindow));
+    println!("test:    SUCCESS - title updated and redrawn");
+
+    // 测试 10: 屏幕大小改变
+    println!("test: 10. Testing screen resize...");
+    let mut wm = WindowManager::new();
+    wm.set_screen_size(640, 480);
+    let far = wm.create_window("Far", 500, 400, 100, 60);
+    let big = wm.create_window("Big", 50, 50, 400, 300);
+    let max = wm.create_window("Max", 10, 10, 100, 80);
+    assert_eq!(wm.maximize(max), Ok(()));
+    wm.draw_all(&MemFramebuffer::new(640, 480), &font);
+    wm.set_screen_size(320, 240);
+    assert_eq!(wm.screen_size(), Some((320, 240)));
+    let w = wm.get_window(far).unwrap();
+    assert_eq!((w.x, w.y), (220, 180), "Off-screen window pulled back on-screen");
+    assert!(w.is_dirty(), "Clamped window needs a redraw");
+    let w = wm.get_window(big).unwrap();
+    assert_eq!((w.x, w.y, w.width, w.height), (0, 0, 400, 300), "Oversized window pinned to the corner");
+    let w = wm.get_window(max).unwrap();
+    assert_eq!((w.x, w.y, w.width, w.height), (0, 0, 320, 240), "Maximized window fills the new screen");
+    assert_eq!(wm.restore(max), Ok(()));
+    let w = wm.get_window(max).unwrap();
+    assert_eq!((w.x, w.y, w.width, w.height), (10, 10, 100, 80));
+
+    let mut buffer = DoubleBuffer::new();
+    assert_eq!(buffer.init(640, 480, 640), Ok(()));
+    buffer.put_pixel(600, 400, color::RED);
+    assert_eq!(buffer.resize(320, 240, 320), Ok(()));
+    assert_eq!((buffer.width(), buffer.height()), (320, 240), "Buffer reports the new size");
+    buffer.put_pixel(319, 239, color::RED);
+    assert_eq!(buffer.get_pixel(319, 239), color::RED);
+    assert_eq!(buffer.get_pixel(0, 0), 0, "Resized buffer starts cleared");
+
+    let mut cursor = MouseCursor::new(640, 480);
+    cursor.set_position(600, 400);
+    cursor.set_screen_size(320, 240);
+    assert_eq!((cursor.x, cursor.y), (319, 239), "Cursor clamped to the new screen");
+    println!("test:    SUCCESS - windows and buffers follow the new mode");
+
+    // 测试 11: 事件路由
+    println!("test: 11. Testing per-window event routing...");
+    let mut wm = WindowManager::new();
+    let a = wm.create_window("A", 10, 10, 100, 80);
+    let b = wm.create_window("B", 200, 10, 100, 80);
+    let got_a: Rc<RefCell<Vec<WidgetEvent>>> = Rc::new(RefCell::new(Vec::new()));
+    let got_b: Rc<RefCell<Vec<WidgetEvent>>> = Rc::new(RefCell::new(Vec::new()));
+    let sink = got_a.clone();
+    assert_eq!(wm.set_event_handler(a, move |e| sink.borrow_mut().push(e)), Ok(()));
+    let sink = got_b.clone();
+    assert_eq!(wm.set_event_handler(b, move |e| sink.borrow_mut().push(e)), Ok(()));
+    assert_eq!(wm.set_event_handler(999, |_| {}), Err(WmError::NoSuchWindow));
+
+    assert_eq!(wm.dispatch_event(WidgetEvent::MouseDown { x: 25, y: 45 }), Some(a));
+    assert!(matches!(got_a.borrow()[0], WidgetEvent::MouseDown { x: 15, y: 15 }),
+            "Coordinates are relative to A's client area");
+    assert!(got_b.borrow().is_empty(), "B receives nothing");
+    assert_eq!(wm.focused_window(), Some(a), "Click focuses A");
+
+    assert_eq!(wm.dispatch_event(WidgetEvent::KeyPress { key: b'k' }), Some(a));
+    assert!(matches!(got_a.borrow()[1], WidgetEvent::KeyPress { key: b'k' }));
+    assert_eq!(wm.dispatch_event(WidgetEvent::MouseMove { x: 210, y: 40 }), Some(b));
+    assert!(matches!(got_b.borrow()[0], WidgetEvent::MouseMove { x: 10, y: 10 }));
+    assert_eq!(wm.focused_window(), Some(a), "Hover does not steal focus");
+    assert_eq!(wm.dispatch_event(WidgetEvent::MouseDown { x: 220, y: 15 }), None,
+               "Title bar clicks are not client input");
+    assert_eq!(wm.dispatch_event(WidgetEvent::MouseDown { x: 150, y: 150 }), None,
+               "Desktop clicks reach no window");
+    assert_eq!(got_a.borrow().len(), 2);
+    assert_eq!(got_b.borrow().len(), 1);
+
+    wm.bring_to_front(b);
+    wm.dispatch_event(WidgetEvent::KeyPress { key: b'z' });
+    assert!(matches!(got_b.borrow()[1], WidgetEvent::KeyPress { key: b'z' }), "Keys follow focus");
+    assert_eq!(wm.remove_window(b), Ok(()));
+    assert_eq!(wm.dispatch_event(WidgetEvent::KeyPress { key: b'q' }), None,
+               "Removing the focused window clears focus");
+    println!("test:    SUCCESS - events routed to the right window");
+
+    // 测试 12: 拖动边缘调整大小
+    println!("test: 12. Testing edge resizing...");
+    let mut wm = WindowManager::new();
+    let id = wm.create_window("Size", 100, 100, 200, 150);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!(w.is_in_resize_border(299, 200), Some(ResizeEdge::Right));
+    assert_eq!(w.is_in_resize_border(100, 100), Some(ResizeEdge::TopLeft));
+    assert_eq!(w.is_in_resize_border(200, 249), Some(ResizeEdge::Bottom));
+    assert_eq!(w.is_in_resize_border(200, 200), None, "Interior is not a border");
+    assert_eq!(w.is_in_resize_border(90, 200), None, "Outside the window is not a border");
+
+    // 右下角：左上角不动
+    wm.handle_mouse_down(298, 248);
+    assert!(wm.is_resizing() && !wm.is_dragging());
+    wm.handle_mouse_move(318, 258);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y, w.width, w.height), (100, 100, 220, 160));
+    wm.handle_mouse_up();
+    assert!(!wm.is_resizing());
+
+    // 左上角：右边和下边保持在 (320, 260)
+    wm.handle_mouse_down(101, 101);
+    wm.handle_mouse_move(71, 91);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y, w.width, w.height), (70, 90, 250, 170));
+    assert_eq!((w.x + w.width, w.y + w.height), (320, 260), "Opposite edges stay fixed");
+    // 缩到最小尺寸后左边停住，右边仍不动
+    wm.set_min_window_size(80, 40);
+    wm.handle_mouse_move(500, 500);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y, w.width, w.height), (240, 220, 80, 40), "Clamped to the minimum size");
+    // 不能越过屏幕原点
+    wm.handle_mouse_move(0, 0);
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y, w.x + w.width, w.y + w.height), (0, 0, 320, 260));
+    wm.handle_mouse_up();
+
+    // 最大化的窗口不能调整大小
+    wm.set_screen_size(640, 480);
+    assert_eq!(wm.minimize(id), Ok(()));
+    assert_eq!(wm.get_window(id).unwrap().is_in_resize_border(639, 240), None);
+    wm.handle_mouse_down(639, 240);
+    assert!(!wm.is_resizing(), "Maximized windows are not resizable");
+    println!("test:    SUCCESS - edges resize with the opposite edge fixed");
+
+    // 测试 13: 最大化避开任务栏，最小化只隐藏
+    println!("test: 13. Testing window state layout...");
+    let mut wm = WindowManager::new();
+    wm.set_screen_size(640, 480);
+    wm.set_taskbar_height(30);
+    assert_eq!(wm.work_area(), Some((640, 450)));
+    let id = wm.create_window("State", 40, 50, 200, 100);
+    wm.create_window("Other", 300, 50, 100, 100);
+    assert_eq!(wm.toggle_maximize(id), Ok(()));
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.x, w.y, w.width, w.height), (0, 0, 640, 450), "Maximized window leaves the taskbar visible");
+    assert_eq!(w.restore_rect, Some((40, 50, 200, 100)), "Pre-maximize geometry saved");
+    wm.set_taskbar_height(40);
+    assert_eq!(wm.get_window(id).unwrap().height, 440, "Maximized window follows the taskbar height");
+    assert_eq!(wm.toggle_maximize(id), Ok(()));
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.state, w.x, w.y, w.width, w.height), (WindowState::Normal, 40, 50, 200, 100),
+               "Toggling again restores the saved rect");
+
+    assert_eq!(wm.set_window_state(id, WindowState::Maximized), Ok(()));
+    assert_eq!(wm.set_window_state(id, WindowState::Minimized), Ok(()));
+    assert_eq!(wm.toggle_maximize(id), Err(WmError::InvalidState), "Minimized windows cannot toggle");
+    assert_eq!(wm.windows().len(), 2, "Minimized window is still listed for the taskbar");
+    let fb = MemFramebuffer::new(640, 480);
+    fb.clear(color::BLUE);
+    wm.draw_all(&fb, &FontRenderer::new_8x8());
+    assert_eq!(fb.get_pixel(20, 300), color::BLUE, "Minimized window is not drawn");
+    assert_ne!(fb.get_pixel(350, 100), color::BLUE, "Other windows are still drawn");
+    assert_eq!(wm.set_window_state(id, WindowState::Normal), Ok(()));
+    let w = wm.get_window(id).unwrap();
+    assert_eq!((w.state, w.visible, w.x, w.y, w.width, w.height), (WindowState::Normal, true, 40, 50, 200, 100),
+               "Normal from a minimized maximized window restores the original rect");
+    assert_eq!(wm.set_window_state(id, WindowState::Normal), Ok(()), "Already normal is a no-op");
+    assert_eq!(wm.set_window_state(999, WindowState::Normal), Err(WmError::NoSuchWindow));
+    println!("test:    SUCCESS - maximize/minimize change layout and drawing");
+
+    // 测试 14: 窗口事件队列
+    println!("test: 14. Testing window event queue...");
+    let mut wm = WindowManager::new();
+    let a = wm.create_window("A", 10, 10, 100, 80);
+    let b = wm.create_window("B", 200, 10, 100, 80);
+    assert_eq!(wm.poll_window_event(), None, "No events before input");
+    wm.handle_mouse_down(30, 15);
+    assert_eq!(wm.poll_window_event(), Some(WindowEvent::Focused(a)));
+    wm.handle_mouse_move(50, 35);
+    wm.handle_mouse_up();
+    assert_eq!(wm.poll_window_event(), Some(WindowEvent::Moved { id: a, x: 30, y: 30 }));
+    wm.handle_mouse_down(35, 35);
+    assert_eq!(wm.poll_window_event(), None, "Clicking the focused window does not refocus");
+    wm.handle_mouse_up();
+    wm.handle_mouse_down(298, 88);
+    wm.handle_mouse_move(318, 98);
+    wm.handle_mouse_up();
+    assert_eq!(wm.poll_window_event(), Some(WindowEvent::Focused(b)));
+    assert_eq!(wm.poll_window_event(), Some(WindowEvent::Resized { id: b, w: 120, h: 90 }));
+    assert_eq!(wm.handle_mouse_down(305, 18), Some(b));
+    assert_eq!(wm.poll_window_event(), Some(WindowEvent::CloseRequested(b)));
+    assert_eq!(wm.poll_window_event(), None);
+    // 应用响应关闭请求
+    assert_eq!(wm.remove_window(b), Ok(()));
+    assert!(wm.get_window(b).is_none());
+    wm.set_screen_size(640, 480);
+    assert_eq!(wm.maximize(a), Ok(()));
+    assert_eq!(wm.poll_window_event(), Some(WindowEvent::Moved { id: a, x: 0, y: 0 }));
+    assert_eq!(wm.poll_window_event(), Some(WindowEvent::Resized { id: a, w: 640, h: 480 }));
+    println!("test:    SUCCESS - focus, move, resize and close reported in order");
+
+    // 测试 15: Alt+Tab 切换焦点窗口
+    println!("test: 15. Testing Alt+Tab focus cycling...");
+    let mut wm = WindowManager::new();
+    let a = wm.create_window("A", 10, 10, 100, 80);
+    let b = wm.create_window("B", 150, 10, 100, 80);
+    let c = wm.create_window("C", 300, 10, 100, 80);
+    wm.bring_to_front(c);
+    assert_eq!(wm.cycle_focus_forward(), Some(a), "Bottom window comes to front");
+    assert_eq!(wm.cycle_focus_forward(), Some(b));
+    assert_eq!(wm.cycle_focus_forward(), Some(c), "Three cycles return to the first window");
+    assert_eq!(wm.focused_window(), Some(c));
+    assert_eq!(wm.cycle_focus_backward(), Some(b), "Backward sends the top window down");
+    assert_eq!(wm.cycle_focus_backward(), Some(a));
+    assert_eq!(wm.handle_key(keys::ALT_TAB), Some(b), "Alt+Tab undoes the last backward step");
+    assert_eq!(wm.handle_key(keys::ALT_BACKTAB), Some(a), "Alt+Shift+Tab key cycles backward");
+    assert_eq!(wm.minimize(b), Ok(()));
+    assert_eq!(wm.cycle_focus_forward(), Some(c), "Minimized windows are skipped");
+    assert_eq!(wm.cycle_focus_forward(), Some(a));
+    let fb = MemFramebuffer::new(480, 120);
+    wm.draw_all(&fb, &font);
+    assert_eq!(fb.get_pixel(60, 13), ACTIVE_TITLE_COLOR, "Focused title bar is active");
+    assert_eq!(fb.get_pixel(350, 13), INACTIVE_TITLE_COLOR, "Other title bars are inactive");
+    let mut empty = WindowManager::new();
+    assert_eq!(empty.cycle_focus_forward(), None, "Nothing to cycle without windows");
+    println!("test:    SUCCESS - focus cycles through visible windows in z-order");
+
+    // 测试 16: 内容区绘制
+    println!("test: 16. Testing window content drawing...");
+    let window = Window::new(1, "Content", 10, 10, 100, 80);
+    assert_eq!(window.content_rect(), (12, 10 + TITLE_BAR_HEIGHT, 96, 80 - TITLE_BAR_HEIGHT - 2));
+    let fb = MemFramebuffer::new(200, 120);
+    window.draw(&fb, &font);
+    let calls = RefCell::new(Vec::new());
+    window.draw_content(&fb, |fb, x, y, w, h| {
+        calls.borrow_mut().push((x, y, w, h));
+        // 故意画满整个屏幕，只有内容区应被写入
+        fb.fill_rect(0, 0, fb.width(), fb.height(), color::GREEN);
+    });
+    assert_eq!(calls.borrow().as_slice(), &[window.content_rect()], "Closure receives the content rect");
+    assert_eq!(fb.get_pixel(12, 10 + TITLE_BAR_HEIGHT), color::GREEN, "Content area is drawn");
+    assert_eq!(fb.get_pixel(107, 87), color::GREEN, "Bottom-right content pixel is drawn");
+    assert_eq!(fb.get_pixel(30, 15), ACTIVE_TITLE_COLOR, "Title bar is not overdrawn");
+    assert_eq!(fb.get_pixel(10, 50), color::BLACK, "Left border is not overdrawn");
+    assert_eq!(fb.get_pixel(60, 89), color::BLACK, "Bottom border is not overdrawn");
+    assert_eq!(fb.get_pixel(150, 50), color::BLACK, "Outside the window is not drawn");
+    assert_eq!(fb.clip(), None, "Clip is cleared afterwards");
+
+    // 已有裁剪时与内容区求交，结束后恢复
+    fb.set_clip(0, 0, 50, 120);
+    window.draw_content(&fb, |fb, _, _, _, _| fb.fill_rect(0, 0, fb.width(), fb.height(), color::RED));
+    assert_eq!(fb.get_pixel(40, 50), color::RED, "Inside both clips");
+    assert_eq!(fb.get_pixel(60, 50), color::GREEN, "Outside the outer clip");
+    assert_eq!(fb.clip(), Some(ClipRect::new(0, 0, 50, 120)), "Outer clip is restored");
+    fb.clear_clip();
+
+    let mut hidden = Window::new(2, "Hidden", 0, 0, 100, 80);
+    hidden.visible = false;
+    hidden.draw_content(&fb, |_, _, _, _, _| panic!("hidden windows have no content to draw"));
+    println!("test:    SUCCESS - content drawing clipped to the client area");
+
+    // 测试 17: 遮挡剔除
+    println!("test: 17. Testing occlusion culling...");
+    let mut wm = WindowManager::new();
+    let small = wm.create_window("Small", 50, 50, 60, 40);
+    let big = wm.create_window("Big", 20, 20, 200, 150);
+    let side = wm.create_window("Side", 300, 20, 60, 40);
+    assert!(wm.is_occluded(small), "Small window lies entirely under the big one");
+    assert!(!wm.is_occluded(big) && !wm.is_occluded(side));
+    assert_eq!(wm.windows_to_draw(), vec![big, side], "Occluded window is skipped");
+    let fb = MemFramebuffer::new(400, 200);
+    wm.draw_all(&fb, &font);
+    assert!(!wm.get_window(small).unwrap().is_dirty(), "Skipped window does not stay dirty");
+    assert_eq!(fb.get_pixel(60, 55), color::WHITE, "Small title bar is not painted over the big window");
+
+    // 阴影露出来时仍需绘制
+    assert_eq!(wm.move_window(small, 50, 130), Ok(()));
+    assert!(!wm.is_occluded(small), "Shadow below the big window is exposed");
+    assert_eq!(wm.move_window(small, 50, 50), Ok(()));
+    wm.bring_to_front(small);
+    assert!(!wm.is_occluded(small), "Raised window is drawn");
+    assert!(!wm.is_occluded(big), "Partially covered window is drawn");
+    assert_eq!(wm.set_window_visible(small, false), Ok(()));
+    wm.bring_to_front(big);
+    assert_eq!(wm.set_window_visible(small, true), Ok(()));
+    assert_eq!(wm.set_window_visible(big, false), Ok(()));
+    assert!(!wm.is_occluded(small), "Hidden windows do not occlude");
+    assert_eq!(wm.windows_to_draw(), vec![side, small], "Bottom to top, hidden window omitted");
+    println!("test:    SUCCESS - fully covered windows are not drawn");
+
+    // 测试 18: 按偏移量移动
+    println!("test: 18. Testing relative window moves...");
+    let mut wm = WindowManager::new();
+    let id = wm.create_window("Nudge", 30, 40, 100, 80);
+    assert_eq!(wm.move_window_by(id, 15, -10), Ok(()));
+    let window = wm.get_window(id).unwrap();
+    assert_eq!((window.x, window.y), (45, 30), "Deltas apply in both directions");
+    assert_eq!(wm.move_window_by(id, -100, -5), Ok(()));
+    let window = wm.get_window(id).unwrap();
+    assert_eq!((window.x, window.y), (0, 25), "Moving left past 0 clamps to x=0");
+    assert_eq!(wm.move_window_by(id, 0, i32::MIN), Ok(()));
+    assert_eq!(wm.get_window(id).unwrap().y, 0, "Large negative deltas clamp to y=0");
+    assert_eq!(wm.move_window_by(id + 100, -1, 0), Err(WmError::NoSuchWindow));
+    assert_eq!(wm.minimize(id), Ok(()));
+    assert_eq!(wm.move_window_by(id, 5, 5), Err(WmError::InvalidState), "Minimized windows do not move");
+    println!("test:    SUCCESS - relative moves clamped at the screen origin");
+
+    println!("test: ===== Window Manager Testing Completed =====");
+}
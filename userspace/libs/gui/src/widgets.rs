@@ -1,9 +1,12 @@
 //! UI 控件
 
+use core::cell::Cell;
+use std::boxed::Box;
 use std::string::String;
 use std::vec::Vec;
 use crate::framebuffer::{Framebuffer, color};
 use crate::font::FontRenderer;
+use crate::layout::Layout;
 
 /// 控件 ID
 pub type WidgetId = u32;
@@ -20,6 +23,48 @@ pub enum WidgetEvent {
     Blur,
 }
 
+/// 带类型的控件引用（命中测试结果）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WidgetRef {
+    Button(WidgetId),
+    Label(WidgetId),
+    TextBox(WidgetId),
+    Radio(WidgetId),
+}
+
+impl WidgetRef {
+    /// 控件 ID
+    pub fn id(&self) -> WidgetId {
+        match *self {
+            WidgetRef::Button(id)
+            | WidgetRef::Label(id)
+            | WidgetRef::TextBox(id)
+            | WidgetRef::Radio(id) => id,
+        }
+    }
+}
+
+/// `WidgetEvent::KeyPress` 使用的非打印按键码
+///
+/// 可打印字符使用 ASCII 码；编辑键使用 ASCII 控制码，
+/// 方向键等没有 ASCII 对应的按键使用 0x80 以上的值
+pub mod keys {
+    pub const BACKSPACE: u8 = 0x08;
+    pub const TAB: u8 = 0x09;
+    pub const ENTER: u8 = b'\r';
+    pub const DELETE: u8 = 0x7F;
+    pub const LEFT: u8 = 0x80;
+    pub const RIGHT: u8 = 0x81;
+    pub const HOME: u8 = 0x82;
+    pub const END: u8 = 0x83;
+    /// Shift+Tab
+    pub const BACKTAB: u8 = 0x84;
+    /// Alt+Tab，由窗口管理器用来切换焦点窗口
+    pub const ALT_TAB: u8 = 0x85;
+    /// Alt+Shift+Tab
+    pub const ALT_BACKTAB: u8 = 0x86;
+}
+
 /// 控件状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WidgetState {
@@ -30,6 +75,51 @@ pub enum WidgetState {
     Focused,
 }
 
+/// 文本变化回调
+pub type ChangeCallback = Box<dyn FnMut(&str)>;
+
+/// 禁用控件的背景色
+pub const DISABLED_BG: u32 = color::DARK_GRAY;
+/// 禁用控件的文本颜色
+pub const DISABLED_TEXT: u32 = color::GRAY;
+/// 悬停/按下时相对基础色的明暗变化量
+pub const SHADE_STEP: u8 = 0x20;
+
+/// 解析带助记符的标签
+///
+/// `&` 标记其后的字符为助记符（如 "&File"），`&&` 表示一个字面 `&`。
+/// 只有第一个标记生效，末尾单独的 `&` 被丢弃。
+///
+/// # 返回
+/// (显示文本, 助记符在显示文本中的字符位置和小写字符)
+pub fn parse_mnemonic(label: &str) -> (String, Option<(usize, u8)>) {
+    let mut text = String::new();
+    let mut mnemonic = None;
+    let mut len = 0;
+    let mut chars = label.chars();
+
+    while let Some(ch) = chars.next() {
+        let ch = if ch != '&' {
+            ch
+        } else {
+            match chars.next() {
+                Some('&') => '&',
+                Some(next) => {
+                    if mnemonic.is_none() && next.is_ascii() {
+                        mnemonic = Some((len, (next as u8).to_ascii_lowercase()));
+                    }
+                    next
+                }
+                None => break,
+            }
+        };
+        text.push(ch);
+        len += 1;
+    }
+
+    (text, mnemonic)
+}
+
 /// 按钮
 pub struct Button {
     pub id: WidgetId,
@@ -42,20 +132,73 @@ pub struct Button {
     pub visible: bool,
     pub enabled: bool,
     pub clicked: bool,
+    /// 拥有键盘焦点（Enter/空格触发点击）
+    pub has_focus: bool,
+    /// 助记符（小写），Alt+该字符触发点击
+    pub mnemonic: Option<u8>,
+    /// 助记符在显示文本中的字符位置
+    pub mnemonic_index: Option<usize>,
+    /// 自上次绘制以来外观是否改变
+    dirty: Cell<bool>,
 }
 
 impl Button {
+    /// 创建按钮，`text` 中的 `&` 标记助记符（见 `parse_mnemonic`）
     pub fn new(id: WidgetId, x: u32, y: u32, width: u32, height: u32, text: &str) -> Self {
+        let (text, mnemonic) = parse_mnemonic(text);
         Self {
             id, x, y, width, height,
-            text: String::from(text),
+            text,
             state: WidgetState::Normal,
             visible: true,
             enabled: true,
             clicked: false,
+            has_focus: false,
+            mnemonic: mnemonic.map(|(_, ch)| ch),
+            mnemonic_index: mnemonic.map(|(idx, _)| idx),
+            dirty: Cell::new(true),
         }
     }
 
+    /// 自上次绘制以来是否需要重绘
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// 标记需要重绘
+    #[inline]
+    pub fn mark_dirty(&self) {
+        self.dirty.set(true);
+    }
+
+    /// 文本左上角位置（文本在按钮内居中）
+    fn text_origin(&self, font: &FontRenderer) -> (u32, u32) {
+        let text_width = font.measure_text(&self.text);
+        let text_x = self.x + (self.width.saturating_sub(text_width)) / 2;
+        let text_y = self.y + (self.height.saturating_sub(font.height())) / 2;
+        (text_x, text_y)
+    }
+
+    /// 助记符下划线位置 (x, y, 宽度)
+    pub fn underline_rect(&self, font: &FontRenderer) -> Option<(u32, u32, u32)> {
+        let idx = self.mnemonic_index?;
+        let (text_x, text_y) = self.text_origin(font);
+        Some((text_x + idx as u32 * font.width(), text_y + font.height(), font.width()))
+    }
+
+    /// 启用或禁用按钮
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.state = if enabled { WidgetState::Normal } else { WidgetState::Disabled };
+        self.mark_dirty();
+    }
+
+    /// 是否响应 Alt+`key`
+    pub fn matches_mnemonic(&self, key: u8) -> bool {
+        self.enabled && self.visible && self.mnemonic == Some(key.to_ascii_lowercase())
+    }
+
     pub fn contains(&self, px: u32, py: u32) -> bool {
         px >= self.x && px < self.x + self.width && py >= self.y && py < self.y + self.height
     }
@@ -65,7 +208,8 @@ impl Button {
             return false;
         }
 
-        match event {
+        let before = self.state;
+        let handled = match event {
             WidgetEvent::MouseDown { .. } => {
                 self.state = WidgetState::Pressed;
                 true
@@ -87,31 +231,52 @@ impl Button {
                 }
                 true
             }
+            WidgetEvent::Focus | WidgetEvent::Blur => {
+                self.has_focus = matches!(event, WidgetEvent::Focus);
+                self.mark_dirty();
+                true
+            }
+            WidgetEvent::KeyPress { key: keys::ENTER | b' ' } if self.has_focus => {
+                self.clicked = true;
+                true
+            }
             _ => false,
+        };
+        if self.state != before {
+            self.mark_dirty();
         }
+        handled
     }
 
+    /// 绘制按钮并清除重绘标记
     pub fn draw<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
+        self.dirty.set(false);
         if !self.visible {
             return;
         }
 
         let bg = match self.state {
+            _ if !self.enabled => DISABLED_BG,
             WidgetState::Normal => color::GRAY,
-            WidgetState::Hover => 0xFFA0A0A0,
-            WidgetState::Pressed => 0xFF606060,
-            WidgetState::Disabled => 0xFF404040,
-            WidgetState::Focused => 0xFFA0A0A0,
+            WidgetState::Hover => color::lighten(color::GRAY, SHADE_STEP),
+            WidgetState::Pressed => color::darken(color::GRAY, SHADE_STEP),
+            WidgetState::Disabled => DISABLED_BG,
+            WidgetState::Focused => color::lighten(color::GRAY, SHADE_STEP),
         };
+        let fg = if self.enabled { color::WHITE } else { DISABLED_TEXT };
 
         fb.fill_rect(self.x, self.y, self.width, self.height, bg);
         fb.blit_rect(self.x, self.y, self.width, self.height, color::BLACK, 1);
+        if self.has_focus && self.width > 2 && self.height > 2 {
+            fb.blit_rect(self.x + 1, self.y + 1, self.width - 2, self.height - 2, color::BLUE, 1);
+        }
 
-        let text_width = font.measure_text(&self.text);
-        let text_x = self.x + (self.width.saturating_sub(text_width)) / 2;
-        let text_y = self.y + (self.height.saturating_sub(font.height())) / 2;
+        let (text_x, text_y) = self.text_origin(font);
+        font.draw_string(fb, text_x, text_y, &self.text, fg);
 
-        font.draw_string(fb, text_x, text_y, &self.text, color::WHITE);
+        if let Some((ux, uy, uw)) = self.underline_rect(font) {
+            fb.draw_line_h(ux, uy, uw, fg);
+        }
     }
 
     pub fn was_clicked(&mut self) -> bool {
@@ -128,7 +293,14 @@ pub struct Label {
     pub y: u32,
     pub text: String,
     pub visible: bool,
+    pub enabled: bool,
     pub text_color: u32,
+    /// 多行模式：按 `wrap_width` 自动换行并处理 `\n`
+    pub multiline: bool,
+    /// 多行模式下的换行宽度（像素）
+    pub wrap_width: u32,
+    /// 自上次绘制以来外观是否改变
+    dirty: Cell<bool>,
 }
 
 impl Label {
@@ -137,18 +309,429 @@ impl Label {
             id, x, y,
             text: String::from(text),
             visible: true,
+            enabled: true,
             text_color: color::WHITE,
+            multiline: false,
+            wrap_width: 0,
+            dirty: Cell::new(true),
         }
     }
 
+    /// 自上次绘制以来是否需要重绘
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// 标记需要重绘
+    #[inline]
+    pub fn mark_dirty(&self) {
+        self.dirty.set(true);
+    }
+
+    /// 切换多行模式，`wrap_width` 为换行宽度
+    pub fn set_multiline(&mut self, multiline: bool, wrap_width: u32) {
+        self.multiline = multiline;
+        self.wrap_width = wrap_width;
+        self.mark_dirty();
+    }
+
+    /// 修改文本，文本不变时不标记重绘
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text = String::from(text);
+            self.mark_dirty();
+        }
+    }
+
+    /// 点是否落在标签文本范围内（按默认 8x8 字体计算，多行模式为换行后的区域）
+    pub fn contains(&self, px: u32, py: u32) -> bool {
+        let font = FontRenderer::new_8x8();
+        let (width, height) = if self.multiline {
+            let lines = font.wrap_lines(&self.text, self.wrap_width).len() as u32;
+            (self.wrap_width, lines * font.height())
+        } else {
+            (self.text.len() as u32 * font.width(), font.height())
+        };
+        px >= self.x && px < self.x + width && py >= self.y && py < self.y + height
+    }
+
+    /// 启用或禁用标签（禁用时文本变暗）
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.mark_dirty();
+    }
+
+    /// 绘制标签并清除重绘标记
     pub fn draw<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
+        self.dirty.set(false);
         if !self.visible {
             return;
         }
-        font.draw_string(fb, self.x, self.y, &self.text, self.text_color);
+        let fg = if self.enabled { self.text_color } else { DISABLED_TEXT };
+        if self.multiline {
+            font.draw_string_wrapped(fb, self.x, self.y, self.wrap_width, font.height(), &self.text, fg);
+        } else {
+            font.draw_string(fb, self.x, self.y, &self.text, fg);
+        }
     }
 }
 
+/// 密码模式文本框显示的字符
+pub const MASK_CHAR: u8 = b'*';
+
+/// 单选按钮圆圈半径
+const RADIO_RADIUS: u32 = 5;
+/// 单选按钮文本相对左边缘的偏移
+const RADIO_TEXT_OFFSET: u32 = 2 * RADIO_RADIUS + 6;
+
+/// 单选按钮
+///
+/// 同一面板中 `group` 相同的单选按钮互斥，最多一个被选中
+pub struct RadioButton {
+    pub id: WidgetId,
+    pub x: u32,
+    pub y: u32,
+    /// 所属分组
+    pub group: u32,
+    pub text: String,
+    pub checked: bool,
+    pub visible: bool,
+    pub enabled: bool,
+    /// 自上次绘制以来外观是否改变
+    dirty: Cell<bool>,
+}
+
+impl RadioButton {
+    pub fn new(id: WidgetId, x: u32, y: u32, group: u32, text: &str) -> Self {
+        Self {
+            id, x, y, group,
+            text: String::from(text),
+            checked: false,
+            visible: true,
+            enabled: true,
+            dirty: Cell::new(true),
+        }
+    }
+
+    /// 自上次绘制以来是否需要重绘
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// 标记需要重绘
+    #[inline]
+    pub fn mark_dirty(&self) {
+        self.dirty.set(true);
+    }
+
+    /// 选中或取消选中（不影响同组其他按钮，见 `SimplePanel::select_radio`）
+    pub fn set_checked(&mut self, checked: bool) {
+        if self.checked != checked {
+            self.checked = checked;
+            self.mark_dirty();
+        }
+    }
+
+    /// 启用或禁用单选按钮
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.mark_dirty();
+    }
+
+    /// 点是否落在圆圈或文本上（文本宽度按默认 8x8 字体计算）
+    pub fn contains(&self, px: u32, py: u32) -> bool {
+        let font = FontRenderer::new_8x8();
+        let width = RADIO_TEXT_OFFSET + self.text.len() as u32 * font.width();
+        let height = (2 * RADIO_RADIUS + 1).max(font.height());
+        px >= self.x && px < self.x + width && py >= self.y && py < self.y + height
+    }
+
+    /// 处理事件，在按钮内按下鼠标时返回 true（由面板完成选中）
+    pub fn handle_event(&self, event: WidgetEvent) -> bool {
+        if !self.enabled || !self.visible {
+            return false;
+        }
+        match event {
+            WidgetEvent::MouseDown { x, y } | WidgetEvent::Click { x, y } => self.contains(x, y),
+            _ => false,
+        }
+    }
+
+    /// 绘制单选按钮并清除重绘标记：选中时为实心圆，否则为空心圆
+    pub fn draw<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
+        self.dirty.set(false);
+        if !self.visible {
+            return;
+        }
+
+        let fg = if self.enabled { color::WHITE } else { DISABLED_TEXT };
+        let (cx, cy) = (self.x + RADIO_RADIUS, self.y + RADIO_RADIUS);
+        fb.draw_circle(cx, cy, RADIO_RADIUS, fg, false);
+        if self.checked {
+            fb.draw_circle(cx, cy, RADIO_RADIUS - 2, fg, true);
+        }
+        let text_y = (self.y + RADIO_RADIUS).saturating_sub(font.height() / 2);
+        font.draw_string(fb, self.x + RADIO_TEXT_OFFSET, text_y, &self.text, fg);
+    }
+}
+
+/// 滚动条宽度
+pub const SCROLLBAR_WIDTH: u32 = 12;
+/// 滚动条滑块最小高度，避免内容很长时滑块无法点中
+const SCROLLBAR_MIN_THUMB: u32 = 8;
+
+/// 竖直滚动条（轨道 + 可拖动滑块）
+///
+/// 滚动位置为 0.0..=1.0，滑块高度与 可见高度/内容总高度 成正比
+pub struct ScrollBar {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub visible: bool,
+    pub enabled: bool,
+    /// 滚动位置（0.0 = 顶部，1.0 = 底部）
+    position: f32,
+    /// 可见内容高度
+    view_len: u32,
+    /// 内容总高度
+    total_len: u32,
+    /// 拖动中时记录按下点相对滑块顶部的偏移
+    grab: Option<u32>,
+    /// 自上次绘制以来外观是否改变
+    dirty: Cell<bool>,
+}
+
+impl ScrollBar {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x, y, width, height,
+            visible: true,
+            enabled: true,
+            position: 0.0,
+            view_len: height,
+            total_len: height,
+            grab: None,
+            dirty: Cell::new(true),
+        }
+    }
+
+    /// 自上次绘制以来是否需要重绘
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// 标记需要重绘
+    #[inline]
+    pub fn mark_dirty(&self) {
+        self.dirty.set(true);
+    }
+
+    /// 当前滚动位置（0.0..=1.0）
+    #[inline]
+    pub fn position(&self) -> f32 {
+        self.position
+    }
+
+    /// 设置滚动位置，超出 0.0..=1.0 的值被截断
+    pub fn set_position(&mut self, position: f32) {
+        let position = if position.is_nan() { 0.0 } else { position.clamp(0.0, 1.0) };
+        if self.position != position {
+            self.position = position;
+            self.mark_dirty();
+        }
+    }
+
+    /// 设置可见高度与内容总高度，决定滑块大小
+    pub fn set_range(&mut self, view_len: u32, total_len: u32) {
+        self.view_len = view_len;
+        self.total_len = total_len.max(view_len);
+        self.mark_dirty();
+    }
+
+    /// 是否正在拖动滑块
+    #[inline]
+    pub fn is_dragging(&self) -> bool {
+        self.grab.is_some()
+    }
+
+    /// 滑块高度：与 可见/总高度 成正比，不小于 `SCROLLBAR_MIN_THUMB`
+    fn thumb_height(&self) -> u32 {
+        if self.total_len == 0 {
+            return self.height;
+        }
+        let h = (self.height as u64 * self.view_len as u64 / self.total_len as u64) as u32;
+        h.max(SCROLLBAR_MIN_THUMB).min(self.height)
+    }
+
+    /// 滑块可移动的距离
+    fn travel(&self) -> u32 {
+        self.height - self.thumb_height()
+    }
+
+    /// 滑块矩形 (x, y, width, height)
+    pub fn thumb_rect(&self) -> (u32, u32, u32, u32) {
+        let offset = (self.travel() as f32 * self.position).round() as u32;
+        (self.x, self.y + offset, self.width, self.thumb_height())
+    }
+
+    /// 点是否落在轨道内
+    pub fn contains(&self, px: u32, py: u32) -> bool {
+        px >= self.x && px < self.x + self.width && py >= self.y && py < self.y + self.height
+    }
+
+    /// 把滑块顶部移到 `top`（绝对坐标）并换算成滚动位置
+    fn move_thumb_to(&mut self, top: u32) {
+        let travel = self.travel();
+        if travel == 0 {
+            self.set_position(0.0);
+            return;
+        }
+        let rel = top.saturating_sub(self.y).min(travel);
+        self.set_position(rel as f32 / travel as f32);
+    }
+
+    /// 处理事件，事件属于滚动条时返回 true
+    ///
+    /// 在滑块上按下开始拖动，拖动中的 `MouseMove` 更新位置，`MouseUp` 结束拖动；
+    /// 在滑块之外的轨道上按下时向该方向翻一页
+    pub fn handle_event(&mut self, event: WidgetEvent) -> bool {
+        if !self.enabled || !self.visible {
+            self.grab = None;
+            return false;
+        }
+        match event {
+            WidgetEvent::MouseDown { x, y } if self.contains(x, y) => {
+                let (_, ty, _, th) = self.thumb_rect();
+                if y >= ty && y < ty + th {
+                    self.grab = Some(y - ty);
+                    self.mark_dirty();
+                } else {
+                    let scrollable = self.total_len - self.view_len;
+                    let page = if scrollable == 0 { 1.0 } else { self.view_len as f32 / scrollable as f32 };
+                    let step = if y < ty { -page } else { page };
+                    self.set_position(self.position + step);
+                }
+                true
+            }
+            WidgetEvent::MouseMove { y, .. } => match self.grab {
+                Some(grab) => {
+                    self.move_thumb_to(y.saturating_sub(grab));
+                    true
+                }
+                None => false,
+            },
+            WidgetEvent::MouseUp { .. } if self.grab.is_some() => {
+                self.grab = None;
+                self.mark_dirty();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 绘制轨道和滑块并清除重绘标记
+    pub fn draw<F: Framebuffer>(&self, fb: &F) {
+        self.dirty.set(false);
+        if !self.visible {
+            return;
+        }
+
+        fb.fill_rect(self.x, self.y, self.width, self.height, color::DARK_GRAY);
+        let (tx, ty, tw, th) = self.thumb_rect();
+        let thumb = if !self.enabled {
+            DISABLED_TEXT
+        } else if self.is_dragging() {
+            color::lighten(color::GRAY, SHADE_STEP)
+        } else {
+            color::GRAY
+        };
+        fb.fill_rect(tx + 1, ty, tw.saturating_sub(2), th, thumb);
+    }
+}
+
+/// 带纵向偏移和裁剪的帧缓冲视图
+///
+/// 面板滚动时子控件仍按内容坐标绘制，经此视图上移 `dy` 并裁剪到可视区域
+struct ScrolledView<'a, F: Framebuffer> {
+    fb: &'a F,
+    dy: u32,
+    /// 可视区域 (x, y, width, height)，屏幕坐标
+    clip: (u32, u32, u32, u32),
+}
+
+impl<F: Framebuffer> ScrolledView<'_, F> {
+    /// 内容坐标转换为屏幕坐标，落在可视区域之外时返回 None
+    fn map(&self, x: u32, y: u32) -> Option<(u32, u32)> {
+        let y = y.checked_sub(self.dy)?;
+        let (cx, cy, cw, ch) = self.clip;
+        (x >= cx && x < cx + cw && y >= cy && y < cy + ch).then_some((x, y))
+    }
+}
+
+impl<F: Framebuffer> Framebuffer for ScrolledView<'_, F> {
+    fn put_pixel(&self, x: u32, y: u32, color: u32) {
+        if let Some((x, y)) = self.map(x, y) {
+            self.fb.put_pixel(x, y, color);
+        }
+    }
+
+    fn get_pixel(&self, x: u32, y: u32) -> u32 {
+        self.map(x, y).map_or(0, |(x, y)| self.fb.get_pixel(x, y))
+    }
+
+    fn width(&self) -> u32 {
+        self.fb.width()
+    }
+
+    fn height(&self) -> u32 {
+        // 内容坐标比屏幕坐标大 dy
+        self.fb.height().saturating_add(self.dy)
+    }
+}
+
+/// 文本框字符过滤器
+#[derive(Clone, Copy)]
+pub enum CharFilter {
+    /// 任意可打印字符
+    Any,
+    /// 十进制数字
+    Digits,
+    /// 十六进制数字
+    Hex,
+    /// 字母
+    Alpha,
+    /// 自定义过滤函数
+    Custom(fn(char) -> bool),
+}
+
+impl CharFilter {
+    /// 是否接受字符 `ch`
+    pub fn accepts(&self, ch: char) -> bool {
+        match self {
+            CharFilter::Any => true,
+            CharFilter::Digits => ch.is_ascii_digit(),
+            CharFilter::Hex => ch.is_ascii_hexdigit(),
+            CharFilter::Alpha => ch.is_ascii_alphabetic(),
+            CharFilter::Custom(f) => f(ch),
+        }
+    }
+}
+
+/// 文本框拒绝输入的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputRejected {
+    /// 不是可打印 ASCII 字符
+    NotPrintable,
+    /// 被字符过滤器拒绝
+    Filtered,
+    /// 已达到最大长度
+    MaxLength,
+}
+
 /// 文本框
 pub struct TextBox {
     pub id: WidgetId,
@@ -159,7 +742,22 @@ pub struct TextBox {
     pub text: String,
     pub state: WidgetState,
     pub visible: bool,
+    pub enabled: bool,
     pub cursor_pos: usize,
+    /// 第一个可见字符的位置（文本超出宽度时水平滚动）
+    pub scroll_offset: usize,
+    /// 密码模式：绘制时每个字符显示为 `MASK_CHAR`
+    pub masked: bool,
+    /// 文本变化回调，在每次修改完成后以新文本调用
+    pub on_change: Option<ChangeCallback>,
+    /// 字符过滤器
+    pub filter: CharFilter,
+    /// 最大长度（字符数），None 表示不限制
+    pub max_length: Option<usize>,
+    /// 最近一次输入被拒绝（绘制红色边框作为提示，下次成功修改时清除）
+    pub rejected: Option<InputRejected>,
+    /// 自上次绘制以来外观是否改变
+    dirty: Cell<bool>,
 }
 
 impl TextBox {
@@ -169,55 +767,328 @@ impl TextBox {
             text: String::new(),
             state: WidgetState::Normal,
             visible: true,
+            enabled: true,
             cursor_pos: 0,
+            scroll_offset: 0,
+            masked: false,
+            on_change: None,
+            filter: CharFilter::Any,
+            max_length: None,
+            rejected: None,
+            dirty: Cell::new(true),
         }
     }
 
+    /// 自上次绘制以来是否需要重绘
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// 标记需要重绘
+    #[inline]
+    pub fn mark_dirty(&self) {
+        self.dirty.set(true);
+    }
+
+    /// 设置字符过滤器
+    pub fn set_filter(&mut self, filter: CharFilter) -> &mut Self {
+        self.filter = filter;
+        self
+    }
+
+    /// 设置最大长度
+    pub fn set_max_length(&mut self, max_length: Option<usize>) -> &mut Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// 检查字符是否可以插入
+    fn check_char(&self, ch: char) -> Result<(), InputRejected> {
+        if !(' '..='~').contains(&ch) {
+            return Err(InputRejected::NotPrintable);
+        }
+        if !self.filter.accepts(ch) {
+            return Err(InputRejected::Filtered);
+        }
+        if self.max_length.is_some_and(|max| self.text.len() >= max) {
+            return Err(InputRejected::MaxLength);
+        }
+        Ok(())
+    }
+
+    /// 设置文本变化回调
+    pub fn set_on_change<F: FnMut(&str) + 'static>(&mut self, f: F) -> &mut Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    /// 通知文本已变化（状态更新完成之后调用）
+    fn notify_change(&mut self) {
+        self.rejected = None;
+        self.scroll_to_caret();
+        self.mark_dirty();
+        if let Some(callback) = self.on_change.as_mut() {
+            callback(&self.text);
+        }
+    }
+
+    /// 设置密码模式（只影响显示，`text` 仍保存真实内容）
+    pub fn set_masked(&mut self, masked: bool) -> &mut Self {
+        if self.masked != masked {
+            self.masked = masked;
+            self.mark_dirty();
+        }
+        self
+    }
+
+    /// 移动光标到 `pos`（限制在文本长度内），不修改文本
+    pub fn set_cursor_pos(&mut self, pos: usize) {
+        let pos = pos.min(self.text.len());
+        if pos != self.cursor_pos {
+            self.cursor_pos = pos;
+            self.scroll_to_caret();
+            self.mark_dirty();
+        }
+    }
+
+    /// 一次可显示的字符数（按默认 8x8 字体，两侧各留 4 像素）
+    pub fn visible_chars(&self) -> usize {
+        let font = FontRenderer::new_8x8();
+        (self.width.saturating_sub(8) / font.width()).max(1) as usize
+    }
+
+    /// 调整 `scroll_offset` 使光标保持在可见范围内
+    fn scroll_to_caret(&mut self) {
+        let visible = self.visible_chars();
+        if self.cursor_pos < self.scroll_offset {
+            self.scroll_offset = self.cursor_pos;
+        } else if self.cursor_pos > self.scroll_offset + visible {
+            self.scroll_offset = self.cursor_pos - visible;
+        }
+        self.scroll_offset = self.scroll_offset.min(self.text.len());
+    }
+
+    /// 光标左移一个字符
+    pub fn move_cursor_left(&mut self) {
+        self.set_cursor_pos(self.cursor_pos.min(self.text.len()).saturating_sub(1));
+    }
+
+    /// 光标右移一个字符
+    pub fn move_cursor_right(&mut self) {
+        self.set_cursor_pos(self.cursor_pos + 1);
+    }
+
+    /// 光标移到行首
+    pub fn move_cursor_home(&mut self) {
+        self.set_cursor_pos(0);
+    }
+
+    /// 光标移到行尾
+    pub fn move_cursor_end(&mut self) {
+        self.set_cursor_pos(self.text.len());
+    }
+
+    /// 防止直接修改 `text`/`cursor_pos` 后光标越界
+    fn clamp_cursor(&mut self) {
+        self.cursor_pos = self.cursor_pos.min(self.text.len());
+    }
+
+    /// 在光标处插入字符
+    ///
+    /// 字符必须是可打印 ASCII、通过过滤器且未超过最大长度，
+    /// 否则丢弃并返回拒绝原因
+    pub fn insert_char(&mut self, ch: char) -> Result<(), InputRejected> {
+        self.clamp_cursor();
+        if let Err(reason) = self.check_char(ch) {
+            self.rejected = Some(reason);
+            self.mark_dirty();
+            return Err(reason);
+        }
+        self.text.insert(self.cursor_pos, ch);
+        self.cursor_pos += 1;
+        self.notify_change();
+        Ok(())
+    }
+
+    /// 删除光标前的字符
+    pub fn backspace(&mut self) -> bool {
+        self.clamp_cursor();
+        if self.cursor_pos == 0 {
+            return false;
+        }
+        self.cursor_pos -= 1;
+        self.text.remove(self.cursor_pos);
+        self.notify_change();
+        true
+    }
+
+    /// 删除光标处的字符
+    pub fn delete(&mut self) -> bool {
+        self.clamp_cursor();
+        if self.cursor_pos >= self.text.len() {
+            return false;
+        }
+        self.text.remove(self.cursor_pos);
+        self.notify_change();
+        true
+    }
+
+    /// 清空文本
+    pub fn clear(&mut self) -> bool {
+        if self.text.is_empty() {
+            return false;
+        }
+        self.text.clear();
+        self.cursor_pos = 0;
+        self.notify_change();
+        true
+    }
+
+    /// 在光标处粘贴文本，整体算一次修改
+    ///
+    /// 只保留可打印且通过过滤器的字符，超出最大长度的部分被截断
+    pub fn paste(&mut self, text: &str) -> bool {
+        self.clamp_cursor();
+        let room = self.max_length.map_or(usize::MAX, |max| max.saturating_sub(self.text.len()));
+        let filtered: String = text
+            .chars()
+            .filter(|&c| (' '..='~').contains(&c) && self.filter.accepts(c))
+            .take(room)
+            .collect();
+        if filtered.is_empty() {
+            return false;
+        }
+        self.text.insert_str(self.cursor_pos, &filtered);
+        self.cursor_pos += filtered.len();
+        self.notify_change();
+        true
+    }
+
+    /// 启用或禁用文本框（禁用时失去焦点）
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.state = if enabled { WidgetState::Normal } else { WidgetState::Disabled };
+        self.mark_dirty();
+    }
+
     pub fn contains(&self, px: u32, py: u32) -> bool {
         px >= self.x && px < self.x + self.width && py >= self.y && py < self.y + self.height
     }
 
     pub fn handle_event(&mut self, event: WidgetEvent) -> bool {
-        match event {
-            WidgetEvent::MouseDown { .. } => {
+        if !self.enabled || !self.visible {
+            return false;
+        }
+
+        let before = self.state;
+        let handled = match event {
+            WidgetEvent::MouseDown { x, y } => {
+                if !self.contains(x, y) {
+                    return false;
+                }
                 self.state = WidgetState::Focused;
                 true
             }
+            WidgetEvent::Focus => {
+                self.state = WidgetState::Focused;
+                true
+            }
+            WidgetEvent::Blur => {
+                self.state = WidgetState::Normal;
+                true
+            }
             WidgetEvent::KeyPress { key } if self.state == WidgetState::Focused => {
-                if key == b'\x08' {
-                    if self.cursor_pos > 0 {
-                        self.cursor_pos -= 1;
-                        self.text.remove(self.cursor_pos);
+                match key {
+                    keys::BACKSPACE => {
+                        self.backspace();
+                    }
+                    keys::DELETE => {
+                        self.delete();
+                    }
+                    keys::LEFT => self.move_cursor_left(),
+                    keys::RIGHT => self.move_cursor_right(),
+                    keys::HOME => self.move_cursor_home(),
+                    keys::END => self.move_cursor_end(),
+                    _ => {
+                        let _ = self.insert_char(key as char);
                     }
-                } else if key >= 0x20 && key <= 0x7E {
-                    self.text.insert(self.cursor_pos, key as char);
-                    self.cursor_pos += 1;
                 }
                 true
             }
             _ => false,
+        };
+        if self.state != before {
+            self.mark_dirty();
         }
+        handled
     }
 
+    /// 绘制文本框并清除重绘标记
     pub fn draw<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
+        self.dirty.set(false);
         if !self.visible {
             return;
         }
 
-        fb.fill_rect(self.x, self.y, self.width, self.height, color::WHITE);
-        let border = if self.state == WidgetState::Focused { color::BLUE } else { color::BLACK };
+        let (bg, fg) = if self.enabled {
+            (color::WHITE, color::BLACK)
+        } else {
+            (color::LIGHT_GRAY, DISABLED_TEXT)
+        };
+        fb.fill_rect(self.x, self.y, self.width, self.height, bg);
+        let border = if self.rejected.is_some() {
+            color::RED
+        } else if self.state == WidgetState::Focused {
+            color::BLUE
+        } else {
+            color::BLACK
+        };
         fb.blit_rect(self.x, self.y, self.width, self.height, border, 1);
 
-        let text_x = self.x + 4;
-        let text_y = self.y + (self.height.saturating_sub(font.height())) / 2;
-
-        font.draw_string(fb, text_x, text_y, &self.text, color::BLACK);
+        let (text_x, text_y) = self.text_origin(font);
+        let start = self.scroll_offset.min(self.text.len());
+        let max_x = (self.x + self.width).saturating_sub(4);
+        if self.masked {
+            let mask: String = core::iter::repeat_n(MASK_CHAR as char, self.text[start..].chars().count()).collect();
+            font.draw_string_clipped(fb, text_x, text_y, &mask, fg, max_x);
+        } else {
+            font.draw_string_clipped(fb, text_x, text_y, &self.text[start..], fg, max_x);
+        }
 
         if self.state == WidgetState::Focused {
-            let cursor_x = text_x + (self.cursor_pos as u32 * 8);
-            fb.draw_line_v(cursor_x, text_y, font.height(), color::BLACK);
+            fb.draw_line_v(self.caret_x(font), text_y, font.height(), color::BLACK);
         }
     }
+
+    /// 文本左上角位置（左侧留 4 像素，垂直居中）
+    fn text_origin(&self, font: &FontRenderer) -> (u32, u32) {
+        (self.x + 4, self.y + (self.height.saturating_sub(font.height())) / 2)
+    }
+
+    /// 光标竖线的 x 坐标：跟随滚动后的逻辑位置，但不超出文本框右边框
+    pub fn caret_x(&self, font: &FontRenderer) -> u32 {
+        let (text_x, _) = self.text_origin(font);
+        let pos = self.cursor_pos.min(self.text.len()).saturating_sub(self.scroll_offset) as u32;
+        (text_x + pos * font.width()).min((self.x + self.width).saturating_sub(2))
+    }
+}
+
+/// 面板子控件（按添加顺序记录，用于布局）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Button(usize),
+    Label(usize),
+    TextBox(usize),
+    Radio(usize),
+}
+
+/// 子控件记录
+#[derive(Debug, Clone, Copy)]
+struct Child {
+    slot: Slot,
+    /// 绝对定位时相对面板左上角的偏移
+    offset: (u32, u32),
 }
 
 /// 简单面板
@@ -227,9 +1098,22 @@ pub struct SimplePanel {
     pub width: u32,
     pub height: u32,
     pub visible: bool,
+    pub enabled: bool,
     pub buttons: Vec<Button>,
     pub labels: Vec<Label>,
     pub textboxes: Vec<TextBox>,
+    pub radios: Vec<RadioButton>,
+    /// 子控件布局方式
+    pub layout: Layout,
+    /// 内容向上滚动的像素数
+    content_offset_y: u32,
+    /// 内容总高度（None 表示不滚动）
+    content_height: Option<u32>,
+    /// 内容超出面板高度时显示的滚动条
+    scrollbar: Option<ScrollBar>,
+    children: Vec<Child>,
+    /// 拥有键盘焦点的子控件（`children` 中的下标）
+    focused_index: Option<usize>,
     next_id: WidgetId,
 }
 
@@ -238,17 +1122,36 @@ impl SimplePanel {
         Self {
             x, y, width, height,
             visible: true,
+            enabled: true,
             buttons: Vec::new(),
             labels: Vec::new(),
             textboxes: Vec::new(),
+            radios: Vec::new(),
+            layout: Layout::Absolute,
+            content_offset_y: 0,
+            content_height: None,
+            scrollbar: None,
+            children: Vec::new(),
+            focused_index: None,
             next_id: 1,
         }
     }
 
+    /// 设置布局方式并重新排列已有控件
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.relayout(self.width, self.height);
+    }
+
+    /// 添加按钮
+    ///
+    /// 绝对定位时使用相对面板的 (bx, by) 和给定大小；
+    /// 使用网格/流式布局时忽略这些参数，按下一个单元定位
     pub fn add_button(&mut self, bx: u32, by: u32, bw: u32, bh: u32, text: &str) -> WidgetId {
         let id = self.next_id;
         self.next_id += 1;
         self.buttons.push(Button::new(id, self.x + bx, self.y + by, bw, bh, text));
+        self.push_child(Slot::Button(self.buttons.len() - 1), (bx, by));
         id
     }
 
@@ -256,6 +1159,7 @@ impl SimplePanel {
         let id = self.next_id;
         self.next_id += 1;
         self.labels.push(Label::new(id, self.x + lx, self.y + ly, text));
+        self.push_child(Slot::Label(self.labels.len() - 1), (lx, ly));
         id
     }
 
@@ -263,31 +1167,427 @@ impl SimplePanel {
         let id = self.next_id;
         self.next_id += 1;
         self.textboxes.push(TextBox::new(id, self.x + tx, self.y + ty, tw, th));
+        self.push_child(Slot::TextBox(self.textboxes.len() - 1), (tx, ty));
+        id
+    }
+
+    /// 添加单选按钮，`group` 相同的按钮互斥
+    pub fn add_radio(&mut self, rx: u32, ry: u32, group: u32, text: &str) -> WidgetId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.radios.push(RadioButton::new(id, self.x + rx, self.y + ry, group, text));
+        self.push_child(Slot::Radio(self.radios.len() - 1), (rx, ry));
         id
     }
 
+    /// 选中单选按钮 `id`，并取消同组其他按钮
+    ///
+    /// # 返回
+    /// `id` 不是本面板的单选按钮时返回 false
+    pub fn select_radio(&mut self, id: WidgetId) -> bool {
+        let Some(group) = self.radios.iter().find(|r| r.id == id).map(|r| r.group) else {
+            return false;
+        };
+        for radio in self.radios.iter_mut().filter(|r| r.group == group) {
+            radio.set_checked(radio.id == id);
+        }
+        true
+    }
+
+    /// 文本框 `id` 的文本（密码模式下同样返回真实内容）
+    pub fn get_textbox_text(&self, id: WidgetId) -> Option<&str> {
+        self.textboxes.iter().find(|t| t.id == id).map(|t| t.text.as_str())
+    }
+
+    /// 分组中被选中的单选按钮
+    pub fn get_selected_radio(&self, group: u32) -> Option<WidgetId> {
+        self.radios.iter().find(|r| r.group == group && r.checked).map(|r| r.id)
+    }
+
+    /// 记录新控件并按当前布局定位
+    fn push_child(&mut self, slot: Slot, offset: (u32, u32)) {
+        self.children.push(Child { slot, offset });
+        self.place(self.children.len() - 1);
+    }
+
+    /// 根据面板位置和布局计算第 `index` 个控件的绝对位置
+    ///
+    /// 绝对定位的控件使用记录的相对偏移并保持原大小
+    fn place(&mut self, index: usize) {
+        let child = self.children[index];
+        let cell = self.layout.cell_rect(index, self.width);
+        let (rx, ry) = cell.map_or(child.offset, |(cx, cy, _, _)| (cx, cy));
+        let (x, y) = (self.x + rx, self.y + ry);
+        match child.slot {
+            Slot::Button(i) => {
+                let b = &mut self.buttons[i];
+                (b.x, b.y) = (x, y);
+                if let Some((_, _, cw, ch)) = cell {
+                    (b.width, b.height) = (cw, ch);
+                }
+                b.mark_dirty();
+            }
+            Slot::Label(i) => {
+                let l = &mut self.labels[i];
+                (l.x, l.y) = (x, y);
+                l.mark_dirty();
+            }
+            Slot::TextBox(i) => {
+                let t = &mut self.textboxes[i];
+                (t.x, t.y) = (x, y);
+                if let Some((_, _, cw, ch)) = cell {
+                    (t.width, t.height) = (cw, ch);
+                }
+                t.mark_dirty();
+            }
+            Slot::Radio(i) => {
+                let r = &mut self.radios[i];
+                (r.x, r.y) = (x, y);
+                r.mark_dirty();
+            }
+        }
+    }
+
+    /// 移动面板，子控件随之移动
+    pub fn set_position(&mut self, x: u32, y: u32) {
+        self.x = x;
+        self.y = y;
+        for index in 0..self.children.len() {
+            self.place(index);
+        }
+        self.sync_scrollbar();
+    }
+
+    /// 设置内容总高度
+    ///
+    /// 超过面板高度时在右侧显示滚动条，内容可滚动；否则取消滚动
+    pub fn set_content_height(&mut self, h: u32) {
+        self.content_height = Some(h);
+        self.sync_scrollbar();
+    }
+
+    /// 内容向上滚动的像素数
+    #[inline]
+    pub fn content_offset_y(&self) -> u32 {
+        self.content_offset_y
+    }
+
+    /// 最大滚动距离
+    pub fn max_scroll(&self) -> u32 {
+        self.content_height.map_or(0, |h| h.saturating_sub(self.height))
+    }
+
+    /// 滚动条（内容未超出面板时为 None）
+    pub fn scrollbar(&self) -> Option<&ScrollBar> {
+        self.scrollbar.as_ref()
+    }
+
+    /// 滚动到 `offset`（截断到最大滚动距离），同步滚动条位置
+    pub fn scroll_to(&mut self, offset: u32) {
+        self.set_scroll(offset.min(self.max_scroll()));
+        let max = self.max_scroll();
+        if let Some(bar) = self.scrollbar.as_mut() {
+            bar.set_position(if max == 0 { 0.0 } else { self.content_offset_y as f32 / max as f32 });
+        }
+    }
+
+    /// 更新滚动偏移，改变时所有子控件需要重绘
+    fn set_scroll(&mut self, offset: u32) {
+        if self.content_offset_y != offset {
+            self.content_offset_y = offset;
+            self.mark_children_dirty();
+        }
+    }
+
+    fn mark_children_dirty(&self) {
+        self.buttons.iter().for_each(Button::mark_dirty);
+        self.labels.iter().for_each(Label::mark_dirty);
+        self.textboxes.iter().for_each(TextBox::mark_dirty);
+        self.radios.iter().for_each(RadioButton::mark_dirty);
+    }
+
+    /// 按面板位置、大小和内容高度创建/移动/移除滚动条
+    fn sync_scrollbar(&mut self) {
+        if self.max_scroll() == 0 {
+            self.scrollbar = None;
+            self.set_scroll(0);
+            return;
+        }
+        let total = self.content_height.unwrap_or(self.height);
+        let (bar_x, bar_y, bar_h) = (self.x + self.width.saturating_sub(SCROLLBAR_WIDTH), self.y, self.height);
+        let bar = self
+            .scrollbar
+            .get_or_insert_with(|| ScrollBar::new(bar_x, bar_y, SCROLLBAR_WIDTH, bar_h));
+        (bar.x, bar.y, bar.height) = (bar_x, bar_y, bar_h);
+        bar.set_range(bar_h, total);
+        self.scroll_to(self.content_offset_y);
+    }
+
+    /// 子控件的可视区域 (x, y, width, height)，不含滚动条
+    fn viewport(&self) -> (u32, u32, u32, u32) {
+        let bar = if self.scrollbar.is_some() { SCROLLBAR_WIDTH } else { 0 };
+        (self.x, self.y, self.width.saturating_sub(bar), self.height)
+    }
+
+    /// 屏幕坐标转换为内容坐标；面板可滚动且点在可视区域外时返回 None
+    fn to_content(&self, x: u32, y: u32) -> Option<(u32, u32)> {
+        if self.scrollbar.is_none() {
+            return Some((x, y));
+        }
+        let (vx, vy, vw, vh) = self.viewport();
+        (x >= vx && x < vx + vw && y >= vy && y < vy + vh).then_some((x, y + self.content_offset_y))
+    }
+
+    /// 面板大小改变后重新排列子控件
+    ///
+    /// 绝对定位的控件保持相对面板的位置
+    pub fn relayout(&mut self, new_w: u32, new_h: u32) {
+        self.width = new_w;
+        self.height = new_h;
+        for index in 0..self.children.len() {
+            self.place(index);
+        }
+        self.sync_scrollbar();
+    }
+
+    /// 是否有子控件自上次绘制以来发生变化
+    ///
+    /// 返回 false 时桌面循环可以跳过本帧合成
+    pub fn needs_redraw(&self) -> bool {
+        self.buttons.iter().any(Button::is_dirty)
+            || self.labels.iter().any(Label::is_dirty)
+            || self.textboxes.iter().any(TextBox::is_dirty)
+            || self.radios.iter().any(RadioButton::is_dirty)
+            || self.scrollbar.as_ref().is_some_and(ScrollBar::is_dirty)
+    }
+
+    /// 命中测试：返回 (x, y) 处最上层的可见控件
+    ///
+    /// 按绘制顺序的逆序查找（按钮在文本框之上，文本框在标签之上），
+    /// 同类控件中后添加的在上层。禁用的控件同样可以命中
+    pub fn widget_ref_at(&self, x: u32, y: u32) -> Option<WidgetRef> {
+        if !self.visible {
+            return None;
+        }
+        let (x, y) = self.to_content(x, y)?;
+        if let Some(b) = self.buttons.iter().rev().find(|b| b.visible && b.contains(x, y)) {
+            return Some(WidgetRef::Button(b.id));
+        }
+        if let Some(r) = self.radios.iter().rev().find(|r| r.visible && r.contains(x, y)) {
+            return Some(WidgetRef::Radio(r.id));
+        }
+        if let Some(t) = self.textboxes.iter().rev().find(|t| t.visible && t.contains(x, y)) {
+            return Some(WidgetRef::TextBox(t.id));
+        }
+        self.labels
+            .iter()
+            .rev()
+            .find(|l| l.visible && l.contains(x, y))
+            .map(|l| WidgetRef::Label(l.id))
+    }
+
+    /// 返回 (x, y) 处最上层可见控件的 ID
+    pub fn widget_at(&self, x: u32, y: u32) -> Option<WidgetId> {
+        self.widget_ref_at(x, y).map(|w| w.id())
+    }
+
     pub fn draw<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
         if !self.visible {
             return;
         }
 
+        match &self.scrollbar {
+            Some(bar) => {
+                let view = ScrolledView { fb, dy: self.content_offset_y, clip: self.viewport() };
+                self.draw_children(&view, font);
+                bar.draw(fb);
+            }
+            None => self.draw_children(fb, font),
+        }
+    }
+
+    fn draw_children<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
         for label in &self.labels {
             label.draw(fb, font);
         }
         for textbox in &self.textboxes {
             textbox.draw(fb, font);
         }
+        for radio in &self.radios {
+            radio.draw(fb, font);
+        }
         for button in &self.buttons {
             button.draw(fb, font);
         }
     }
 
-    pub fn handle_mouse(&mut self, event: WidgetEvent) {
+    /// 启用或禁用面板及其所有子控件
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
         for button in &mut self.buttons {
-            button.handle_event(event);
+            button.set_enabled(enabled);
+        }
+        for label in &mut self.labels {
+            label.set_enabled(enabled);
         }
         for textbox in &mut self.textboxes {
+            textbox.set_enabled(enabled);
+        }
+        for radio in &mut self.radios {
+            radio.set_enabled(enabled);
+        }
+    }
+
+    /// 处理 Alt+`key`：点击助记符匹配的第一个按钮
+    ///
+    /// # 返回
+    /// 被点击的按钮 ID
+    pub fn handle_alt_key(&mut self, key: u8) -> Option<WidgetId> {
+        if !self.enabled {
+            return None;
+        }
+        let button = self.buttons.iter_mut().find(|b| b.matches_mnemonic(key))?;
+        button.clicked = true;
+        Some(button.id)
+    }
+
+    /// 子控件是否可以获得焦点（可见且启用的文本框和按钮）
+    fn is_focusable(&self, index: usize) -> bool {
+        match self.children[index].slot {
+            Slot::Button(i) => self.buttons[i].visible && self.buttons[i].enabled,
+            Slot::TextBox(i) => self.textboxes[i].visible && self.textboxes[i].enabled,
+            Slot::Label(_) | Slot::Radio(_) => false,
+        }
+    }
+
+    /// 向子控件发送事件
+    fn send_to_child(&mut self, index: usize, event: WidgetEvent) -> bool {
+        match self.children[index].slot {
+            Slot::Button(i) => self.buttons[i].handle_event(event),
+            Slot::TextBox(i) => self.textboxes[i].handle_event(event),
+            Slot::Label(_) | Slot::Radio(_) => false,
+        }
+    }
+
+    fn child_id(&self, index: usize) -> WidgetId {
+        match self.children[index].slot {
+            Slot::Button(i) => self.buttons[i].id,
+            Slot::Label(i) => self.labels[i].id,
+            Slot::TextBox(i) => self.textboxes[i].id,
+            Slot::Radio(i) => self.radios[i].id,
+        }
+    }
+
+    /// 把焦点移到第 `index` 个子控件：旧控件收到 Blur，新控件收到 Focus
+    fn set_focus_index(&mut self, index: Option<usize>) {
+        if index == self.focused_index {
+            return;
+        }
+        if let Some(old) = self.focused_index.take() {
+            self.send_to_child(old, WidgetEvent::Blur);
+        }
+        if let Some(new) = index {
+            self.send_to_child(new, WidgetEvent::Focus);
+        }
+        self.focused_index = index;
+    }
+
+    /// 按添加顺序向前或向后查找下一个可获得焦点的控件并聚焦（循环）
+    fn move_focus(&mut self, forward: bool) -> Option<WidgetId> {
+        let n = self.children.len();
+        if !self.enabled || n == 0 {
+            return None;
+        }
+        let start = self.focused_index.unwrap_or(if forward { n - 1 } else { 0 });
+        let next = (1..=n)
+            .map(|step| if forward { (start + step) % n } else { (start + n - step) % n })
+            .find(|&i| self.is_focusable(i))?;
+        self.set_focus_index(Some(next));
+        Some(self.child_id(next))
+    }
+
+    /// 焦点移到下一个可获得焦点的控件（Tab）
+    pub fn focus_next(&mut self) -> Option<WidgetId> {
+        self.move_focus(true)
+    }
+
+    /// 焦点移到上一个可获得焦点的控件（Shift+Tab）
+    pub fn focus_prev(&mut self) -> Option<WidgetId> {
+        self.move_focus(false)
+    }
+
+    /// 当前拥有焦点的控件
+    pub fn focused_widget(&self) -> Option<WidgetId> {
+        self.focused_index.map(|i| self.child_id(i))
+    }
+
+    /// 处理按键：Tab/Shift+Tab 切换焦点，其他按键发给焦点控件
+    ///
+    /// # 返回
+    /// 按键是否被处理
+    pub fn handle_keyboard(&mut self, key: u8) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match key {
+            keys::TAB => self.focus_next().is_some(),
+            keys::BACKTAB => self.focus_prev().is_some(),
+            _ => match self.focused_index {
+                Some(index) if self.is_focusable(index) => {
+                    self.send_to_child(index, WidgetEvent::KeyPress { key })
+                }
+                _ => false,
+            },
+        }
+    }
+
+    pub fn handle_mouse(&mut self, event: WidgetEvent) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(bar) = self.scrollbar.as_mut() {
+            if bar.handle_event(event) {
+                let offset = (bar.position() * self.max_scroll() as f32).round() as u32;
+                self.set_scroll(offset);
+                return;
+            }
+        }
+        // 子控件使用内容坐标；可视区域外的按下/点击不属于任何子控件
+        let dy = self.content_offset_y;
+        let event = match event {
+            WidgetEvent::MouseDown { x, y } => match self.to_content(x, y) {
+                Some((x, y)) => WidgetEvent::MouseDown { x, y },
+                None => return,
+            },
+            WidgetEvent::Click { x, y } => match self.to_content(x, y) {
+                Some((x, y)) => WidgetEvent::Click { x, y },
+                None => return,
+            },
+            WidgetEvent::MouseMove { x, y } => WidgetEvent::MouseMove { x, y: y + dy },
+            WidgetEvent::MouseUp { x, y } => WidgetEvent::MouseUp { x, y: y + dy },
+            other => other,
+        };
+        if let WidgetEvent::MouseDown { x, y } = event {
+            let hit = (0..self.children.len()).rev().find(|&i| {
+                self.is_focusable(i)
+                    && match self.children[i].slot {
+                        Slot::Button(b) => self.buttons[b].contains(x, y),
+                        Slot::TextBox(t) => self.textboxes[t].contains(x, y),
+                        Slot::Label(_) | Slot::Radio(_) => false,
+                    }
+            });
+            if hit.is_some() {
+                self.set_focus_index(hit);
+            }
+        }
+        for button in self.buttons.iter_mut().filter(|b| b.enabled) {
+            button.handle_event(event);
+        }
+        for textbox in self.textboxes.iter_mut().filter(|t| t.enabled) {
             textbox.handle_event(event);
         }
+        if let Some(id) = self.radios.iter().rev().find(|r| r.handle_event(event)).map(|r| r.id) {
+            self.select_radio(id);
+        }
     }
 }
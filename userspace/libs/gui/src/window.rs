@@ -1,14 +1,20 @@
 //! 窗口管理器
 
-use std::collections::BTreeMap;
+use core::cell::Cell;
+use std::boxed::Box;
+use std::collections::{BTreeMap, VecDeque};
 use std::vec::Vec;
 use std::string::String;
-use crate::framebuffer::{Framebuffer, color};
+use crate::framebuffer::{ClipRect, Framebuffer, color};
 use crate::font::FontRenderer;
+use crate::widgets::{keys, WidgetEvent};
 
 /// 窗口 ID
 pub type WindowId = u32;
 
+/// 窗口事件处理函数，收到的鼠标坐标已转换为窗口客户区坐标
+pub type WindowHandler = Box<dyn FnMut(WidgetEvent)>;
+
 /// 窗口状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WindowState {
@@ -17,8 +23,112 @@ pub enum WindowState {
     Maximized,
 }
 
+/// 窗口管理器发给应用的事件，通过 `WindowManager::poll_window_event` 取出
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowEvent {
+    /// 点击了关闭按钮，由应用决定是否调用 `remove_window`
+    CloseRequested(WindowId),
+    /// 窗口获得焦点
+    Focused(WindowId),
+    /// 窗口左上角移动到 (x, y)
+    Moved { id: WindowId, x: u32, y: u32 },
+    /// 窗口大小变为 w x h
+    Resized { id: WindowId, w: u32, h: u32 },
+}
+
+/// 窗口管理器操作错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WmError {
+    /// 窗口 ID 不存在
+    NoSuchWindow,
+    /// 窗口当前状态不允许该操作（如移动最大化/最小化的窗口）
+    InvalidState,
+}
+
+impl core::fmt::Display for WmError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WmError::NoSuchWindow => write!(f, "no such window"),
+            WmError::InvalidState => write!(f, "invalid window state"),
+        }
+    }
+}
+
 /// 标题栏高度
 pub const TITLE_BAR_HEIGHT: u32 = 20;
+/// 标题栏按钮边长
+pub const TITLE_BUTTON_SIZE: u32 = 12;
+/// 相邻标题栏按钮的间隔（左边缘之间的距离）
+const TITLE_BUTTON_STRIDE: u32 = 16;
+/// 标题栏右侧按钮区域宽度（关闭、最大化、最小化）
+const TITLE_BUTTONS_WIDTH: u32 = 6 + 3 * TITLE_BUTTON_STRIDE;
+/// 窗口边框宽度
+pub const WINDOW_BORDER: u32 = 2;
+/// 阴影偏移
+const SHADOW_OFFSET: u32 = 4;
+/// 半透明阴影颜色
+pub const SHADOW_COLOR: u32 = color::with_alpha(color::DARK_GRAY, 0x80);
+/// 焦点窗口的标题栏颜色
+pub const ACTIVE_TITLE_COLOR: u32 = color::BLUE;
+/// 非焦点窗口的标题栏颜色
+pub const INACTIVE_TITLE_COLOR: u32 = color::DARK_GRAY;
+
+/// 窗口边缘可拖动调整大小的宽度
+pub const RESIZE_BORDER: u32 = 4;
+/// 默认的最小窗口大小（保留标题栏按钮和一行客户区）
+pub const DEFAULT_MIN_WINDOW_SIZE: (u32, u32) = (TITLE_BUTTONS_WIDTH + 2 * RESIZE_BORDER, TITLE_BAR_HEIGHT + 2 * RESIZE_BORDER);
+
+/// 调整大小时拖动的边或角
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ResizeEdge {
+    /// 是否移动左边（右边保持不动）
+    fn left(self) -> bool {
+        matches!(self, ResizeEdge::Left | ResizeEdge::TopLeft | ResizeEdge::BottomLeft)
+    }
+
+    fn right(self) -> bool {
+        matches!(self, ResizeEdge::Right | ResizeEdge::TopRight | ResizeEdge::BottomRight)
+    }
+
+    /// 是否移动上边（下边保持不动）
+    fn top(self) -> bool {
+        matches!(self, ResizeEdge::Top | ResizeEdge::TopLeft | ResizeEdge::TopRight)
+    }
+
+    fn bottom(self) -> bool {
+        matches!(self, ResizeEdge::Bottom | ResizeEdge::BottomLeft | ResizeEdge::BottomRight)
+    }
+}
+
+/// 标题栏按钮
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleButton {
+    Close,
+    Maximize,
+    Minimize,
+}
+
+impl TitleButton {
+    /// 从右往左的位置
+    fn slot(self) -> u32 {
+        match self {
+            TitleButton::Close => 0,
+            TitleButton::Maximize => 1,
+            TitleButton::Minimize => 2,
+        }
+    }
+}
 
 /// 窗口
 pub struct Window {
@@ -31,6 +141,10 @@ pub struct Window {
     pub z_order: u32,
     pub state: WindowState,
     pub visible: bool,
+    /// 最大化之前的位置和大小 (x, y, 宽, 高)，用于恢复
+    pub restore_rect: Option<(u32, u32, u32, u32)>,
+    /// 自上次绘制以来外观是否改变
+    dirty: Cell<bool>,
 }
 
 impl Window {
@@ -45,7 +159,51 @@ impl Window {
             z_order: 0,
             state: WindowState::Normal,
             visible: true,
+            restore_rect: None,
+            dirty: Cell::new(true),
+        }
+    }
+
+    /// 自上次绘制以来是否需要重绘
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// 标记需要重绘
+    #[inline]
+    pub fn mark_dirty(&self) {
+        self.dirty.set(true);
+    }
+
+    /// 窗口矩形 (x, y, 宽, 高)
+    #[inline]
+    pub fn rect(&self) -> (u32, u32, u32, u32) {
+        (self.x, self.y, self.width, self.height)
+    }
+
+    /// 将窗口限制在 `screen_w` x `screen_h` 的屏幕内
+    ///
+    /// 最大化的窗口重新占满屏幕；其他窗口只移动不缩放，
+    /// 比屏幕大的窗口对齐到左上角
+    pub fn clamp_to_screen(&mut self, screen_w: u32, screen_h: u32) {
+        if self.state == WindowState::Maximized {
+            (self.x, self.y, self.width, self.height) = (0, 0, screen_w, screen_h);
+        } else {
+            self.x = self.x.min(screen_w.saturating_sub(self.width));
+            self.y = self.y.min(screen_h.saturating_sub(self.height));
+        }
+        if let Some((x, y, w, h)) = self.restore_rect {
+            self.restore_rect = Some((x.min(screen_w.saturating_sub(w)), y.min(screen_h.saturating_sub(h)), w, h));
         }
+        self.mark_dirty();
+    }
+
+    /// 修改标题并标记重绘，过长的标题在绘制时截断
+    pub fn set_title(&mut self, title: &str) {
+        self.title.clear();
+        self.title.push_str(title);
+        self.mark_dirty();
     }
 
     pub fn contains(&self, px: u32, py: u32) -> bool {
@@ -55,55 +213,166 @@ impl Window {
         px >= self.x && px < self.x + self.width && py >= self.y && py < self.y + self.height
     }
 
+    /// 客户区（标题栏以下部分）左上角的屏幕坐标
+    pub fn client_origin(&self) -> (u32, u32) {
+        (self.x, self.y + TITLE_BAR_HEIGHT)
+    }
+
+    /// 屏幕坐标转换为客户区坐标，不在客户区内时返回 None
+    pub fn to_client(&self, px: u32, py: u32) -> Option<(u32, u32)> {
+        let (cx, cy) = self.client_origin();
+        if !self.contains(px, py) || py < cy {
+            return None;
+        }
+        Some((px - cx, py - cy))
+    }
+
+    /// 内容区矩形 (x, y, 宽, 高)：标题栏以下、边框以内，应用在这里绘制
+    pub fn content_rect(&self) -> (u32, u32, u32, u32) {
+        (
+            self.x + WINDOW_BORDER,
+            self.y + TITLE_BAR_HEIGHT,
+            self.width.saturating_sub(2 * WINDOW_BORDER),
+            self.height.saturating_sub(TITLE_BAR_HEIGHT + WINDOW_BORDER),
+        )
+    }
+
+    /// 把绘制裁剪到内容区后调用 `f(fb, x, y, 宽, 高)`，参数是内容区矩形
+    ///
+    /// 已有的裁剪矩形（如局部重绘区域）与内容区求交，调用结束后恢复；
+    /// 隐藏的窗口和没有内容区的窗口不调用 `f`
+    pub fn draw_content<F: Framebuffer, D: Fn(&F, u32, u32, u32, u32)>(&self, fb: &F, f: D) {
+        let (x, y, width, height) = self.content_rect();
+        if !self.visible || width == 0 || height == 0 {
+            return;
+        }
+
+        let saved = fb.clip();
+        let content = ClipRect::new(x, y, width, height);
+        let clip = saved.map_or(content, |c| c.intersect(&content));
+        if clip.is_empty() {
+            return;
+        }
+        fb.set_clip(clip.x, clip.y, clip.width, clip.height);
+        f(fb, x, y, width, height);
+        match saved {
+            Some(c) => fb.set_clip(c.x, c.y, c.width, c.height),
+            None => fb.clear_clip(),
+        }
+    }
+
     pub fn is_in_title_bar(&self, px: u32, py: u32) -> bool {
         if !self.visible {
             return false;
         }
-        px >= self.x && px < self.x + self.width - 20 && py >= self.y && py < self.y + TITLE_BAR_HEIGHT
+        px >= self.x
+            && px < self.x + self.width.saturating_sub(TITLE_BUTTONS_WIDTH)
+            && py >= self.y
+            && py < self.y + TITLE_BAR_HEIGHT
     }
 
-    pub fn is_in_close_button(&self, px: u32, py: u32) -> bool {
+    /// 点落在哪条可调整大小的边或角上（边缘 `RESIZE_BORDER` 像素内）
+    ///
+    /// 只有正常状态的可见窗口可以调整大小，其他情况返回 None
+    pub fn is_in_resize_border(&self, px: u32, py: u32) -> Option<ResizeEdge> {
+        if self.state != WindowState::Normal || !self.contains(px, py) {
+            return None;
+        }
+        let left = px - self.x < RESIZE_BORDER;
+        let right = self.x + self.width - px <= RESIZE_BORDER;
+        let top = py - self.y < RESIZE_BORDER;
+        let bottom = self.y + self.height - py <= RESIZE_BORDER;
+        match (left, right, top, bottom) {
+            (true, _, true, _) => Some(ResizeEdge::TopLeft),
+            (_, true, true, _) => Some(ResizeEdge::TopRight),
+            (true, _, _, true) => Some(ResizeEdge::BottomLeft),
+            (_, true, _, true) => Some(ResizeEdge::BottomRight),
+            (true, ..) => Some(ResizeEdge::Left),
+            (_, true, ..) => Some(ResizeEdge::Right),
+            (_, _, true, _) => Some(ResizeEdge::Top),
+            (.., true) => Some(ResizeEdge::Bottom),
+            _ => None,
+        }
+    }
+
+    /// 标题栏按钮左上角位置
+    pub fn title_button_origin(&self, button: TitleButton) -> (u32, u32) {
+        let x = (self.x + self.width).saturating_sub(18 + button.slot() * TITLE_BUTTON_STRIDE);
+        (x, self.y + 4)
+    }
+
+    /// 点是否落在指定的标题栏按钮内
+    pub fn is_in_title_button(&self, button: TitleButton, px: u32, py: u32) -> bool {
         if !self.visible {
             return false;
         }
-        let close_x = self.x + self.width - 18;
-        let close_y = self.y + 4;
-        px >= close_x && px < close_x + 12 && py >= close_y && py < close_y + 12
+        let (bx, by) = self.title_button_origin(button);
+        px >= bx && px < bx + TITLE_BUTTON_SIZE && py >= by && py < by + TITLE_BUTTON_SIZE
+    }
+
+    pub fn is_in_close_button(&self, px: u32, py: u32) -> bool {
+        self.is_in_title_button(TitleButton::Close, px, py)
+    }
+
+    pub fn is_in_maximize_button(&self, px: u32, py: u32) -> bool {
+        self.is_in_title_button(TitleButton::Maximize, px, py)
     }
 
+    pub fn is_in_minimize_button(&self, px: u32, py: u32) -> bool {
+        self.is_in_title_button(TitleButton::Minimize, px, py)
+    }
+
+    /// 以焦点窗口的样式绘制窗口并清除重绘标记
     pub fn draw<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
+        self.draw_active(fb, font, true);
+    }
+
+    /// 绘制窗口并清除重绘标记，`active` 决定标题栏使用
+    /// `ACTIVE_TITLE_COLOR` 还是 `INACTIVE_TITLE_COLOR`
+    pub fn draw_active<F: Framebuffer>(&self, fb: &F, font: &FontRenderer, active: bool) {
+        self.dirty.set(false);
         if !self.visible {
             return;
         }
 
         // 阴影
-        fb.fill_rect(self.x + 4, self.y + 4, self.width, self.height, color::DARK_GRAY);
+        fb.fill_rect_blend(self.x + SHADOW_OFFSET, self.y + SHADOW_OFFSET, self.width, self.height, SHADOW_COLOR);
         // 背景
         fb.fill_rect(self.x, self.y, self.width, self.height, color::WHITE);
         // 边框
-        fb.blit_rect(self.x, self.y, self.width, self.height, color::BLACK, 2);
+        fb.blit_rect(self.x, self.y, self.width, self.height, color::BLACK, WINDOW_BORDER);
         // 标题栏
-        fb.fill_rect(self.x, self.y, self.width, TITLE_BAR_HEIGHT, color::BLUE);
-
-        // 标题文本
-        if self.width > 40 {
-            let title_x = self.x + 6;
-            let title_y = self.y + 6;
-            let max_chars = ((self.width - 30) / 8) as usize;
-            for (i, ch) in self.title.bytes().enumerate() {
-                if i >= max_chars {
-                    break;
-                }
-                font.draw_char(fb, title_x + i as u32 * 8, title_y, ch, color::WHITE);
-            }
-        }
+        let title_color = if active { ACTIVE_TITLE_COLOR } else { INACTIVE_TITLE_COLOR };
+        fb.fill_rect(self.x, self.y, self.width, TITLE_BAR_HEIGHT, title_color);
+
+        // 标题文本（不覆盖右侧按钮区域）
+        let title_max_x = (self.x + self.width).saturating_sub(TITLE_BUTTONS_WIDTH);
+        font.draw_string_clipped(fb, self.x + 6, self.y + 6, &self.title, color::WHITE, title_max_x);
 
         // 关闭按钮
-        let close_x = self.x + self.width - 18;
-        let close_y = self.y + 4;
-        fb.fill_rect(close_x, close_y, 12, 12, color::RED);
+        let (close_x, close_y) = self.title_button_origin(TitleButton::Close);
+        fb.fill_rect(close_x, close_y, TITLE_BUTTON_SIZE, TITLE_BUTTON_SIZE, color::RED);
         fb.draw_line(close_x + 2, close_y + 2, close_x + 10, close_y + 10, color::WHITE);
         fb.draw_line(close_x + 10, close_y + 2, close_x + 2, close_y + 10, color::WHITE);
+
+        // 最大化/还原按钮
+        let (max_x, max_y) = self.title_button_origin(TitleButton::Maximize);
+        fb.fill_rect(max_x, max_y, TITLE_BUTTON_SIZE, TITLE_BUTTON_SIZE, color::GRAY);
+        if self.state == WindowState::Maximized {
+            // 还原：两个重叠的小窗口
+            fb.blit_rect(max_x + 4, max_y + 2, 6, 6, color::WHITE, 1);
+            fb.fill_rect(max_x + 2, max_y + 4, 6, 6, color::GRAY);
+            fb.blit_rect(max_x + 2, max_y + 4, 6, 6, color::WHITE, 1);
+        } else {
+            // 最大化：一个大窗口
+            fb.blit_rect(max_x + 2, max_y + 2, 8, 8, color::WHITE, 1);
+            fb.draw_line_h(max_x + 2, max_y + 3, 8, color::WHITE);
+        }
+
+        // 最小化按钮
+        let (min_x, min_y) = self.title_button_origin(TitleButton::Minimize);
+        fb.fill_rect(min_x, min_y, TITLE_BUTTON_SIZE, TITLE_BUTTON_SIZE, color::GRAY);
+        fb.draw_line_h(min_x + 2, min_y + 9, 8, color::WHITE);
     }
 }
 
@@ -115,6 +384,24 @@ pub struct WindowManager {
     dragging_window: Option<WindowId>,
     drag_offset_x: i32,
     drag_offset_y: i32,
+    /// 正在调整大小的窗口和拖动的边
+    resizing: Option<(WindowId, ResizeEdge)>,
+    /// 开始调整大小时的指针位置
+    resize_origin: (i32, i32),
+    /// 开始调整大小时的窗口矩形 (x, y, 宽, 高)
+    resize_start: (u32, u32, u32, u32),
+    /// 调整大小时的最小窗口尺寸
+    min_window_size: (u32, u32),
+    /// 屏幕大小（最大化时使用）
+    screen_size: Option<(u32, u32)>,
+    /// 屏幕底部任务栏高度，最大化的窗口不覆盖任务栏
+    taskbar_height: u32,
+    /// 焦点窗口（接收键盘事件）
+    focused: Option<WindowId>,
+    /// 各窗口的事件处理函数
+    handlers: BTreeMap<WindowId, WindowHandler>,
+    /// 等待应用取出的窗口事件
+    events: VecDeque<WindowEvent>,
 }
 
 impl WindowManager {
@@ -126,9 +413,68 @@ impl WindowManager {
             dragging_window: None,
             drag_offset_x: 0,
             drag_offset_y: 0,
+            resizing: None,
+            resize_origin: (0, 0),
+            resize_start: (0, 0, 0, 0),
+            min_window_size: DEFAULT_MIN_WINDOW_SIZE,
+            screen_size: None,
+            taskbar_height: 0,
+            focused: None,
+            handlers: BTreeMap::new(),
+            events: VecDeque::new(),
         }
     }
 
+    /// 设置屏幕大小，最大化的窗口占满任务栏以外的区域
+    ///
+    /// 已有窗口被重新限制在新屏幕范围内（见 `Window::clamp_to_screen`）
+    pub fn set_screen_size(&mut self, width: u32, height: u32) {
+        self.screen_size = Some((width, height));
+        self.fit_to_screen();
+    }
+
+    /// 当前屏幕大小
+    pub fn screen_size(&self) -> Option<(u32, u32)> {
+        self.screen_size
+    }
+
+    /// 设置屏幕底部任务栏的高度，已最大化的窗口随之调整
+    pub fn set_taskbar_height(&mut self, height: u32) {
+        self.taskbar_height = height;
+        self.fit_to_screen();
+    }
+
+    /// 最大化窗口可用的区域（屏幕扣除任务栏），未设置屏幕大小时返回 None
+    pub fn work_area(&self) -> Option<(u32, u32)> {
+        self.screen_size.map(|(w, h)| (w, h.saturating_sub(self.taskbar_height)))
+    }
+
+    /// 把所有窗口限制在屏幕内，最大化的窗口占满工作区
+    fn fit_to_screen(&mut self) {
+        let Some((width, height)) = self.screen_size else {
+            return;
+        };
+        let work_height = height.saturating_sub(self.taskbar_height);
+        for window in self.windows.values_mut() {
+            let before = window.rect();
+            window.clamp_to_screen(width, height);
+            if window.state == WindowState::Maximized {
+                window.height = work_height;
+            }
+            push_geometry_events(&mut self.events, window, before);
+        }
+    }
+
+    /// 设置拖动边缘调整大小时的最小窗口尺寸
+    pub fn set_min_window_size(&mut self, width: u32, height: u32) {
+        self.min_window_size = (width, height);
+    }
+
+    /// 调整大小时的最小窗口尺寸
+    pub fn min_window_size(&self) -> (u32, u32) {
+        self.min_window_size
+    }
+
     pub fn create_window(&mut self, title: &str, x: u32, y: u32, width: u32, height: u32) -> WindowId {
         let id = self.next_id;
         self.next_id += 1;
@@ -141,8 +487,188 @@ impl WindowManager {
         id
     }
 
-    pub fn remove_window(&mut self, id: WindowId) -> bool {
-        self.windows.remove(&id).is_some()
+    /// 移除窗口
+    pub fn remove_window(&mut self, id: WindowId) -> Result<(), WmError> {
+        self.windows.remove(&id).ok_or(WmError::NoSuchWindow)?;
+        self.handlers.remove(&id);
+        self.cancel_drag(id);
+        if self.focused == Some(id) {
+            self.focused = None;
+        }
+        Ok(())
+    }
+
+    /// 移动窗口到 (x, y)
+    ///
+    /// 最大化或最小化的窗口不能移动，返回 `InvalidState`
+    pub fn move_window(&mut self, id: WindowId, x: u32, y: u32) -> Result<(), WmError> {
+        let window = self.windows.get_mut(&id).ok_or(WmError::NoSuchWindow)?;
+        if window.state != WindowState::Normal {
+            return Err(WmError::InvalidState);
+        }
+        let before = window.rect();
+        window.x = x;
+        window.y = y;
+        window.mark_dirty();
+        push_geometry_events(&mut self.events, window, before);
+        Ok(())
+    }
+
+    /// 按偏移量移动窗口，结果坐标不小于 0
+    ///
+    /// 与 `move_window` 相同，只有正常状态的窗口可以移动
+    pub fn move_window_by(&mut self, id: WindowId, dx: i32, dy: i32) -> Result<(), WmError> {
+        let window = self.windows.get(&id).ok_or(WmError::NoSuchWindow)?;
+        let x = (window.x as i64 + dx as i64).clamp(0, u32::MAX as i64) as u32;
+        let y = (window.y as i64 + dy as i64).clamp(0, u32::MAX as i64) as u32;
+        self.move_window(id, x, y)
+    }
+
+    /// 显示或隐藏窗口
+    ///
+    /// 最小化的窗口需要先恢复才能显示，返回 `InvalidState`
+    pub fn set_window_visible(&mut self, id: WindowId, visible: bool) -> Result<(), WmError> {
+        let window = self.windows.get_mut(&id).ok_or(WmError::NoSuchWindow)?;
+        if visible && window.state == WindowState::Minimized {
+            return Err(WmError::InvalidState);
+        }
+        window.visible = visible;
+        window.mark_dirty();
+        if !visible {
+            self.cancel_drag(id);
+        }
+        Ok(())
+    }
+
+    /// 最小化窗口（隐藏，可通过 `restore` 恢复）
+    pub fn minimize(&mut self, id: WindowId) -> Result<(), WmError> {
+        let window = self.windows.get_mut(&id).ok_or(WmError::NoSuchWindow)?;
+        if window.state == WindowState::Minimized {
+            return Err(WmError::InvalidState);
+        }
+        window.state = WindowState::Minimized;
+        window.visible = false;
+        window.mark_dirty();
+        self.cancel_drag(id);
+        if self.focused == Some(id) {
+            self.focused = None;
+        }
+        Ok(())
+    }
+
+    /// 最大化窗口，占满工作区（屏幕扣除任务栏）并记录原位置
+    ///
+    /// 未设置屏幕大小或窗口不处于正常状态时返回 `InvalidState`
+    pub fn maximize(&mut self, id: WindowId) -> Result<(), WmError> {
+        let (screen_w, screen_h) = self.work_area().ok_or(WmError::InvalidState)?;
+        let window = self.windows.get_mut(&id).ok_or(WmError::NoSuchWindow)?;
+        if window.state != WindowState::Normal {
+            return Err(WmError::InvalidState);
+        }
+        let before = window.rect();
+        window.restore_rect = Some(before);
+        (window.x, window.y, window.width, window.height) = (0, 0, screen_w, screen_h);
+        window.state = WindowState::Maximized;
+        window.mark_dirty();
+        push_geometry_events(&mut self.events, window, before);
+        self.cancel_drag(id);
+        Ok(())
+    }
+
+    /// 恢复窗口
+    ///
+    /// 最小化的窗口重新显示并回到最小化之前的状态；
+    /// 最大化的窗口回到最大化之前的位置和大小
+    pub fn restore(&mut self, id: WindowId) -> Result<(), WmError> {
+        let window = self.windows.get_mut(&id).ok_or(WmError::NoSuchWindow)?;
+        let before = window.rect();
+        match window.state {
+            WindowState::Normal => return Err(WmError::InvalidState),
+            WindowState::Minimized => {
+                window.visible = true;
+                window.state = if window.restore_rect.is_some() {
+                    WindowState::Maximized
+                } else {
+                    WindowState::Normal
+                };
+            }
+            WindowState::Maximized => {
+                if let Some((x, y, w, h)) = window.restore_rect.take() {
+                    (window.x, window.y, window.width, window.height) = (x, y, w, h);
+                }
+                window.state = WindowState::Normal;
+            }
+        }
+        window.mark_dirty();
+        push_geometry_events(&mut self.events, window, before);
+        Ok(())
+    }
+
+    /// 在最大化和正常状态之间切换（标题栏最大化按钮的行为）
+    ///
+    /// 最小化的窗口返回 `InvalidState`
+    pub fn toggle_maximize(&mut self, id: WindowId) -> Result<(), WmError> {
+        match self.windows.get(&id).ok_or(WmError::NoSuchWindow)?.state {
+            WindowState::Normal => self.maximize(id),
+            WindowState::Maximized => self.restore(id),
+            WindowState::Minimized => Err(WmError::InvalidState),
+        }
+    }
+
+    /// 把窗口切换到指定状态
+    ///
+    /// - `Normal`：还原最小化或最大化的窗口（回到最大化之前的位置和大小）
+    /// - `Minimized`：隐藏窗口，仍保留在 `windows()` 中供任务栏列出
+    /// - `Maximized`：占满工作区；最小化的窗口先还原再最大化
+    ///
+    /// 已处于目标状态时直接返回 Ok
+    pub fn set_window_state(&mut self, id: WindowId, state: WindowState) -> Result<(), WmError> {
+        let current = self.windows.get(&id).ok_or(WmError::NoSuchWindow)?.state;
+        if current == state {
+            return Ok(());
+        }
+        match state {
+            WindowState::Normal => {
+                self.restore(id)?;
+                // 最小化前是最大化的窗口还原后仍是最大化，再还原一次
+                if self.windows.get(&id).is_some_and(|w| w.state == WindowState::Maximized) {
+                    self.restore(id)?;
+                }
+                Ok(())
+            }
+            WindowState::Minimized => self.minimize(id),
+            WindowState::Maximized => {
+                if current == WindowState::Minimized {
+                    self.restore(id)?;
+                    if self.windows.get(&id).is_some_and(|w| w.state == WindowState::Maximized) {
+                        return Ok(());
+                    }
+                }
+                self.maximize(id)
+            }
+        }
+    }
+
+    /// 修改窗口标题并标记重绘
+    pub fn set_window_title(&mut self, id: WindowId, title: &str) -> Result<(), WmError> {
+        let window = self.windows.get_mut(&id).ok_or(WmError::NoSuchWindow)?;
+        window.set_title(title);
+        Ok(())
+    }
+
+    /// `remove_window` 的布尔包装（兼容旧接口）
+    pub fn remove_window_ok(&mut self, id: WindowId) -> bool {
+        self.remove_window(id).is_ok()
+    }
+
+    /// `move_window` 的布尔包装
+    pub fn move_window_ok(&mut self, id: WindowId, x: u32, y: u32) -> bool {
+        self.move_window(id, x, y).is_ok()
+    }
+
+    /// `set_window_visible` 的布尔包装
+    pub fn set_window_visible_ok(&mut self, id: WindowId, visible: bool) -> bool {
+        self.set_window_visible(id, visible).is_ok()
     }
 
     pub fn get_window(&self, id: WindowId) -> Option<&Window> {
@@ -153,14 +679,169 @@ impl WindowManager {
         self.windows.get_mut(&id)
     }
 
+    /// 所有窗口，包括最小化的窗口（供任务栏列出）
     pub fn windows(&self) -> Vec<&Window> {
         self.windows.values().collect()
     }
 
+    /// 将窗口置顶并设为焦点窗口，焦点改变时推送 `Focused` 事件
     pub fn bring_to_front(&mut self, id: WindowId) {
         if let Some(window) = self.windows.get_mut(&id) {
             window.z_order = self.next_z_order;
             self.next_z_order += 1;
+            self.set_focus(id);
+        }
+    }
+
+    /// 切换焦点窗口；新旧焦点窗口的标题栏颜色改变，都需要重绘
+    fn set_focus(&mut self, id: WindowId) {
+        if self.focused == Some(id) {
+            return;
+        }
+        if let Some(old) = self.focused.and_then(|old| self.windows.get(&old)) {
+            old.mark_dirty();
+        }
+        if let Some(window) = self.windows.get(&id) {
+            window.mark_dirty();
+        }
+        self.focused = Some(id);
+        self.events.push_back(WindowEvent::Focused(id));
+    }
+
+    /// 可以通过 Alt+Tab 切换到的窗口（可见且未最小化），按 Z 顺序从底到顶排列
+    fn focus_cycle_order(&self) -> Vec<WindowId> {
+        let mut windows: Vec<&Window> = self.windows.values()
+            .filter(|w| w.visible && w.state != WindowState::Minimized)
+            .collect();
+        windows.sort_by_key(|w| w.z_order);
+        windows.iter().map(|w| w.id).collect()
+    }
+
+    /// 把最底层的窗口置顶并设为焦点（Alt+Tab）
+    ///
+    /// 连续调用依次轮换所有窗口，N 个窗口调用 N 次后回到最初的焦点窗口
+    ///
+    /// # 返回
+    /// 新的焦点窗口，没有可切换的窗口时返回 None
+    pub fn cycle_focus_forward(&mut self) -> Option<WindowId> {
+        let id = *self.focus_cycle_order().first()?;
+        self.bring_to_front(id);
+        Some(id)
+    }
+
+    /// 把最上层的窗口放到最底层，焦点交给新的最上层窗口（Alt+Shift+Tab）
+    ///
+    /// 是 `cycle_focus_forward` 的逆操作
+    ///
+    /// # 返回
+    /// 新的焦点窗口，没有可切换的窗口时返回 None
+    pub fn cycle_focus_backward(&mut self) -> Option<WindowId> {
+        let order = self.focus_cycle_order();
+        let (&top, rest) = order.split_last()?;
+        let Some(&next) = rest.last() else {
+            self.set_focus(top);
+            return Some(top);
+        };
+
+        // 重新编号 Z 顺序：原最上层窗口排到最前，其余窗口保持相对顺序
+        let mut all: Vec<&Window> = self.windows.values().collect();
+        all.sort_by_key(|w| w.z_order);
+        let mut ids: Vec<WindowId> = all.iter().map(|w| w.id).filter(|&id| id != top).collect();
+        ids.insert(0, top);
+        for (z, id) in ids.iter().enumerate() {
+            if let Some(window) = self.windows.get_mut(id) {
+                window.z_order = z as u32 + 1;
+                window.mark_dirty();
+            }
+        }
+        self.next_z_order = ids.len() as u32 + 1;
+
+        self.set_focus(next);
+        Some(next)
+    }
+
+    /// 处理键盘输入：Alt+Tab / Alt+Shift+Tab 切换焦点窗口，其他按键发给焦点窗口
+    ///
+    /// # 返回
+    /// 切换后的焦点窗口或收到按键的窗口
+    pub fn handle_key(&mut self, key: u8) -> Option<WindowId> {
+        match key {
+            keys::ALT_TAB => self.cycle_focus_forward(),
+            keys::ALT_BACKTAB => self.cycle_focus_backward(),
+            _ => self.dispatch_event(WidgetEvent::KeyPress { key }),
+        }
+    }
+
+    /// 取出最早的一个窗口事件，没有事件时返回 None
+    pub fn poll_window_event(&mut self) -> Option<WindowEvent> {
+        self.events.pop_front()
+    }
+
+    /// 当前焦点窗口
+    pub fn focused_window(&self) -> Option<WindowId> {
+        self.focused
+    }
+
+    /// 注册窗口的事件处理函数（替换已有的）
+    pub fn set_event_handler<H: FnMut(WidgetEvent) + 'static>(
+        &mut self,
+        id: WindowId,
+        handler: H,
+    ) -> Result<(), WmError> {
+        if !self.windows.contains_key(&id) {
+            return Err(WmError::NoSuchWindow);
+        }
+        self.handlers.insert(id, Box::new(handler));
+        Ok(())
+    }
+
+    /// 将输入事件路由到目标窗口的处理函数
+    ///
+    /// 鼠标事件发给该点最上层的窗口，坐标转换为客户区坐标，
+    /// 落在标题栏或窗口外时不分发；按下鼠标同时使窗口获得焦点。
+    /// 键盘和焦点事件发给焦点窗口。
+    ///
+    /// # 返回
+    /// 收到事件的窗口 ID（窗口没有处理函数时也返回）
+    pub fn dispatch_event(&mut self, event: WidgetEvent) -> Option<WindowId> {
+        let (target, local) = match event {
+            WidgetEvent::Click { x, y }
+            | WidgetEvent::MouseDown { x, y }
+            | WidgetEvent::MouseUp { x, y }
+            | WidgetEvent::MouseMove { x, y } => {
+                let id = self.get_top_window_at(x, y)?;
+                let (lx, ly) = self.windows.get(&id)?.to_client(x, y)?;
+                let local = match event {
+                    WidgetEvent::Click { .. } => WidgetEvent::Click { x: lx, y: ly },
+                    WidgetEvent::MouseDown { .. } => {
+                        self.bring_to_front(id);
+                        WidgetEvent::MouseDown { x: lx, y: ly }
+                    }
+                    WidgetEvent::MouseUp { .. } => WidgetEvent::MouseUp { x: lx, y: ly },
+                    _ => WidgetEvent::MouseMove { x: lx, y: ly },
+                };
+                (id, local)
+            }
+            WidgetEvent::KeyPress { .. } | WidgetEvent::Focus | WidgetEvent::Blur => {
+                let id = self.focused.filter(|id| {
+                    self.windows.get(id).is_some_and(|w| w.visible)
+                })?;
+                (id, event)
+            }
+        };
+        if let Some(handler) = self.handlers.get_mut(&target) {
+            handler(local);
+        }
+        Some(target)
+    }
+
+    /// 窗口不再可拖动（移除、隐藏、最小化、最大化）时结束对它的移动或调整大小
+    fn cancel_drag(&mut self, id: WindowId) {
+        if self.dragging_window == Some(id) {
+            self.dragging_window = None;
+        }
+        if self.resizing.is_some_and(|(window_id, _)| window_id == id) {
+            self.resizing = None;
         }
     }
 
@@ -172,16 +853,39 @@ impl WindowManager {
         windows.last().map(|w| w.id)
     }
 
+    /// 处理鼠标按下
+    ///
+    /// 点击最小化/最大化按钮时直接执行相应操作；
+    /// 点击关闭按钮时推送 `CloseRequested` 事件并返回窗口 ID，由调用者决定是否关闭；
+    /// 按在窗口边缘时开始调整大小，按在标题栏时开始拖动
     pub fn handle_mouse_down(&mut self, x: u32, y: u32) -> Option<WindowId> {
         if let Some(window_id) = self.get_top_window_at(x, y) {
             self.bring_to_front(window_id);
 
             if let Some(window) = self.windows.get(&window_id) {
                 if window.is_in_close_button(x, y) {
+                    self.events.push_back(WindowEvent::CloseRequested(window_id));
                     return Some(window_id);
                 }
 
-                if window.is_in_title_bar(x, y) {
+                if window.is_in_minimize_button(x, y) {
+                    let _ = self.minimize(window_id);
+                    return None;
+                }
+
+                if window.is_in_maximize_button(x, y) {
+                    let _ = self.toggle_maximize(window_id);
+                    return None;
+                }
+
+                if let Some(edge) = window.is_in_resize_border(x, y) {
+                    self.resizing = Some((window_id, edge));
+                    self.resize_origin = (x as i32, y as i32);
+                    self.resize_start = (window.x, window.y, window.width, window.height);
+                    return None;
+                }
+
+                if window.state == WindowState::Normal && window.is_in_title_bar(x, y) {
                     self.dragging_window = Some(window_id);
                     self.drag_offset_x = x as i32 - window.x as i32;
                     self.drag_offset_y = y as i32 - window.y as i32;
@@ -191,36 +895,124 @@ impl WindowManager {
         None
     }
 
+    /// 处理鼠标移动：拖动中的窗口跟随指针，调整大小中的窗口改变被拖动的边
+    ///
+    /// 拖动左边或上边时对边保持不动，尺寸不小于 `min_window_size`
     pub fn handle_mouse_move(&mut self, x: u32, y: u32) {
+        if let Some((window_id, edge)) = self.resizing {
+            let dx = x as i32 - self.resize_origin.0;
+            let dy = y as i32 - self.resize_origin.1;
+            let (x0, y0, w0, h0) = self.resize_start;
+            let (min_w, min_h) = self.min_window_size;
+
+            let resize = |start: u32, size: u32, delta: i32, min: u32, near: bool, far: bool| {
+                if near {
+                    // 远端固定，近端不越过屏幕原点
+                    let end = start + size;
+                    let new_size = (size as i32 - delta).max(min as i32) as u32;
+                    let new_size = new_size.min(end);
+                    (end - new_size, new_size)
+                } else if far {
+                    (start, (size as i32 + delta).max(min as i32) as u32)
+                } else {
+                    (start, size)
+                }
+            };
+            let (new_x, new_w) = resize(x0, w0, dx, min_w, edge.left(), edge.right());
+            let (new_y, new_h) = resize(y0, h0, dy, min_h, edge.top(), edge.bottom());
+
+            if let Some(window) = self.windows.get_mut(&window_id) {
+                let before = window.rect();
+                (window.x, window.y, window.width, window.height) = (new_x, new_y, new_w, new_h);
+                window.mark_dirty();
+                push_geometry_events(&mut self.events, window, before);
+            }
+            return;
+        }
+
         if let Some(window_id) = self.dragging_window {
             let new_x = (x as i32 - self.drag_offset_x).max(0) as u32;
             let new_y = (y as i32 - self.drag_offset_y).max(0) as u32;
 
             if let Some(window) = self.windows.get_mut(&window_id) {
+                let before = window.rect();
                 window.x = new_x;
                 window.y = new_y;
+                window.mark_dirty();
+                push_geometry_events(&mut self.events, window, before);
             }
         }
     }
 
     pub fn handle_mouse_up(&mut self) {
         self.dragging_window = None;
+        self.resizing = None;
     }
 
     pub fn is_dragging(&self) -> bool {
         self.dragging_window.is_some()
     }
 
-    pub fn draw_all<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
-        let mut windows: Vec<&Window> = self.windows.values().collect();
+    /// 是否正在拖动边缘调整窗口大小
+    pub fn is_resizing(&self) -> bool {
+        self.resizing.is_some()
+    }
+
+    /// 窗口（包括阴影）是否被某一个更高层的可见窗口完全遮挡
+    ///
+    /// 保守检查：只与单个窗口比较，被多个窗口共同遮挡时仍返回 false
+    pub fn is_occluded(&self, id: WindowId) -> bool {
+        let Some(window) = self.windows.get(&id) else {
+            return false;
+        };
+        let (x, y, w, h) = window.rect();
+        let area = ClipRect::new(x, y, w + SHADOW_OFFSET, h + SHADOW_OFFSET);
+        self.windows.values().any(|other| {
+            let (ox, oy, ow, oh) = other.rect();
+            other.visible
+                && other.z_order > window.z_order
+                && ClipRect::new(ox, oy, ow, oh).intersect(&area) == area
+        })
+    }
+
+    /// 需要绘制的窗口，按 Z 顺序从下到上；隐藏和被完全遮挡的窗口不在其中
+    pub fn windows_to_draw(&self) -> Vec<WindowId> {
+        let mut windows: Vec<&Window> = self.windows.values()
+            .filter(|w| w.visible && !self.is_occluded(w.id))
+            .collect();
         windows.sort_by_key(|w| w.z_order);
+        windows.into_iter().map(|w| w.id).collect()
+    }
+
+    /// 按 Z 顺序绘制 `windows_to_draw` 中的窗口
+    ///
+    /// 焦点窗口的标题栏使用 `ACTIVE_TITLE_COLOR`，其他窗口使用 `INACTIVE_TITLE_COLOR`；
+    /// 跳过的窗口同样清除重绘标记，被遮挡时不会每帧产生重绘区域
+    pub fn draw_all<F: Framebuffer>(&self, fb: &F, font: &FontRenderer) {
+        let visible = self.windows_to_draw();
+        for window in self.windows.values() {
+            if !visible.contains(&window.id) {
+                window.dirty.set(false);
+            }
+        }
 
-        for window in windows {
-            window.draw(fb, font);
+        for id in visible {
+            self.windows[&id].draw_active(fb, font, self.focused == Some(id));
         }
     }
 }
 
+/// 比较窗口操作前的矩形 `before`，位置或大小改变时推送 `Moved` / `Resized` 事件
+fn push_geometry_events(events: &mut VecDeque<WindowEvent>, window: &Window, before: (u32, u32, u32, u32)) {
+    let (x, y, w, h) = window.rect();
+    if (x, y) != (before.0, before.1) {
+        events.push_back(WindowEvent::Moved { id: window.id, x, y });
+    }
+    if (w, h) != (before.2, before.3) {
+        events.push_back(WindowEvent::Resized { id: window.id, w, h });
+    }
+}
+
 impl Default for WindowManager {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,102 @@
+//! Rux 用户态堆增长原语
+//!
+//! 为用户态分配器提供 `sbrk`：先用 `brk(0)` 取得当前堆顶，再用 `brk(new)` 增长，
+//! 返回增长前的堆顶。需要大块或可归还的内存时直接使用匿名 `mmap`/`munmap`。
+//!
+//! # 对齐保证
+//! - 内核给出的初始堆顶按页对齐
+//! - `sbrk` 把增量向上取整到 `HEAP_ALIGN`，因此只通过 `sbrk` 调整堆时
+//!   每次返回的地址都按 `HEAP_ALIGN` 对齐
+//! - 新增长的页由内核清零；缩小后再增长复用的页保留原内容
+
+/// 系统调用号
+#[cfg_attr(not(target_arch = "riscv64"), allow(dead_code))]
+mod syscall {
+    pub const SYS_BRK: usize = 214;
+}
+
+/// `sbrk` 返回地址和增量的对齐（满足任意基本类型）
+pub const HEAP_ALIGN: usize = 16;
+
+/// `sbrk` 失败原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbrkError {
+    /// 增量取整后超出地址空间（或缩小到 0 以下）
+    Overflow,
+    /// 内核拒绝移动堆顶（内存不足或低于堆起始地址）
+    OutOfMemory,
+}
+
+impl core::fmt::Display for SbrkError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SbrkError::Overflow => write!(f, "heap increment overflows"),
+            SbrkError::OutOfMemory => write!(f, "out of memory"),
+        }
+    }
+}
+
+/// 把 `n` 向上取整到 `HEAP_ALIGN`
+#[inline]
+pub const fn align_up(n: usize) -> Option<usize> {
+    match n.checked_add(HEAP_ALIGN - 1) {
+        Some(v) => Some(v & !(HEAP_ALIGN - 1)),
+        None => None,
+    }
+}
+
+/// 使用给定的 `brk` 实现调整堆顶
+///
+/// `brk(addr)` 遵循内核语义：成功返回新堆顶，失败返回当前堆顶，`brk(0)` 查询当前堆顶。
+/// 增量按 `HEAP_ALIGN` 向上取整（负增量按绝对值取整后缩小）。
+///
+/// # 返回
+/// 成功返回调整前的堆顶；增量为 0 时只返回当前堆顶
+pub fn sbrk_with<B: FnMut(usize) -> usize>(mut brk: B, increment: isize) -> Result<usize, SbrkError> {
+    let old = brk(0);
+    if increment == 0 {
+        return Ok(old);
+    }
+
+    let delta = align_up(increment.unsigned_abs()).ok_or(SbrkError::Overflow)?;
+    let new = if increment > 0 {
+        old.checked_add(delta)
+    } else {
+        old.checked_sub(delta)
+    };
+    let new = new.filter(|&addr| addr != 0).ok_or(SbrkError::Overflow)?;
+
+    if brk(new) != new {
+        return Err(SbrkError::OutOfMemory);
+    }
+    Ok(old)
+}
+
+/// 调整当前进程的堆顶，返回调整前的堆顶（新内存的起始地址）
+pub fn sbrk(increment: isize) -> Result<*mut u8, SbrkError> {
+    sbrk_with(|addr| unsafe { sys_brk(addr) }, increment).map(|addr| addr as *mut u8)
+}
+
+/// brk 系统调用
+#[cfg(target_arch = "riscv64")]
+#[inline(always)]
+unsafe fn sys_brk(addr: usize) -> usize {
+    let ret: usize;
+    core::arch::asm!(
+        "ecall",
+        inlateout("a0") addr => ret,
+        in("a7") syscall::SYS_BRK,
+        options(nostack)
+    );
+    ret
+}
+
+/// brk 系统调用 - 非 RISC-V 平台（开发/测试用），堆顶永远不移动
+#[cfg(not(target_arch = "riscv64"))]
+#[inline(always)]
+unsafe fn sys_brk(_addr: usize) -> usize {
+    0
+}
+
+#[cfg(any(test, feature = "unit-test"))]
+pub mod tests;
@@ -0,0 +1,26 @@
+//! 堆增长原语单元测试
+//!
+//! 使用模拟的 brk 测试 `sbrk` 的地址计算，不需要内核。
+//!
+//! - 主机上：`cargo test -p rux_heap`
+//! - QEMU 中：以 `unit-test` 特性构建，调用 `run_all_tests()`
+
+pub mod sbrk;
+
+/// 运行所有堆增长单元测试
+pub fn run_all_tests() {
+    println!("test: ===== Starting Heap Unit Tests =====");
+
+    // 1. sbrk 地址计算测试
+    sbrk::test_sbrk();
+
+    println!("test: ===== All Heap Unit Tests Completed =====");
+}
+
+#[cfg(test)]
+mod harness {
+    #[test]
+    fn sbrk() {
+        super::sbrk::test_sbrk();
+    }
+}
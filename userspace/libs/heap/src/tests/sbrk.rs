@@ -0,0 +1,68 @@
+//! 测试：sbrk 地址计算
+//!
+//! 测试内容：
+//! 1. 增长返回旧堆顶，增量按 HEAP_ALIGN 向上取整，增量 0 只查询
+//! 2. 负增量缩小堆，缩小到 0 以下或溢出时返回 Overflow
+//! 3. 内核拒绝移动堆顶时返回 OutOfMemory，堆顶不变
+
+use crate::{align_up, sbrk_with, SbrkError, HEAP_ALIGN};
+
+/// 模拟内核 brk：堆顶不低于 `start`、不高于 `limit`，失败时返回当前堆顶
+struct FakeBrk {
+    start: usize,
+    limit: usize,
+    current: usize,
+}
+
+impl FakeBrk {
+    fn new(start: usize, limit: usize) -> Self {
+        Self { start, limit, current: start }
+    }
+
+    fn brk(&mut self, addr: usize) -> usize {
+        if addr != 0 && addr >= self.start && addr <= self.limit {
+            self.current = addr;
+        }
+        self.current
+    }
+}
+
+pub fn test_sbrk() {
+    println!("test: ===== Testing sbrk =====");
+
+    // 测试 1: 增长
+    println!("test: 1. Testing growth and alignment...");
+    let mut kernel = FakeBrk::new(0x2000_0000, 0x2010_0000);
+    assert_eq!(sbrk_with(|a| kernel.brk(a), 0), Ok(0x2000_0000), "sbrk(0) reports the break");
+    assert_eq!(sbrk_with(|a| kernel.brk(a), 100), Ok(0x2000_0000), "Growth returns the old break");
+    assert_eq!(kernel.current, 0x2000_0070, "Increment 100 is rounded up to 112");
+    let p = sbrk_with(|a| kernel.brk(a), 1).unwrap();
+    assert_eq!(p % HEAP_ALIGN, 0, "Returned pointers stay aligned");
+    assert_eq!(kernel.current, p + HEAP_ALIGN);
+    assert_eq!(align_up(32), Some(32));
+    assert_eq!(align_up(usize::MAX), None);
+    println!("test:    SUCCESS - growth returns the aligned old break");
+
+    // 测试 2: 缩小与溢出
+    println!("test: 2. Testing shrink and overflow...");
+    let top = kernel.current;
+    assert_eq!(sbrk_with(|a| kernel.brk(a), -16), Ok(top));
+    assert_eq!(kernel.current, top - 16, "Negative increments shrink the heap");
+    assert_eq!(sbrk_with(|a| kernel.brk(a), isize::MIN), Err(SbrkError::Overflow),
+               "Shrinking below address 0 fails");
+    assert_eq!(sbrk_with(|_| usize::MAX - 8, 64), Err(SbrkError::Overflow),
+               "Growing past the address space fails");
+    println!("test:    SUCCESS - shrink works, overflow detected");
+
+    // 测试 3: 内核拒绝
+    println!("test: 3. Testing kernel refusal...");
+    let before = kernel.current;
+    assert_eq!(sbrk_with(|a| kernel.brk(a), 0x20_0000), Err(SbrkError::OutOfMemory),
+               "Growth past the kernel limit fails");
+    assert_eq!(kernel.current, before, "Failed growth leaves the break unchanged");
+    assert_eq!(sbrk_with(|a| kernel.brk(a), -0x10_0000), Err(SbrkError::OutOfMemory),
+               "Shrinking below the heap start fails");
+    println!("test:    SUCCESS - refusals reported as OutOfMemory");
+
+    println!("test: ===== sbrk Testing Completed =====");
+}